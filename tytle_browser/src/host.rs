@@ -1,5 +1,5 @@
 use tytle::ast::statement::{Command, Direction};
-use tytle::vm::Host;
+use tytle::vm::{Color, Host, Label, Movement, Pen, PenState, Turtle, TurtlePose, TurtleShape};
 
 use wasm_bindgen::prelude::*;
 
@@ -22,6 +22,9 @@ extern "C" {
     #[wasm_bindgen(method)]
     fn right(this: &TytleHost, count: i32);
 
+    #[wasm_bindgen(method)]
+    fn turn(this: &TytleHost, degrees: f64);
+
     #[wasm_bindgen(method)]
     fn setx(this: &TytleHost, count: i32);
 
@@ -49,9 +52,51 @@ extern "C" {
     #[wasm_bindgen(method)]
     fn clear_screen(this: &TytleHost);
 
+    #[wasm_bindgen(method)]
+    fn screen_width(this: &TytleHost) -> u32;
+
+    #[wasm_bindgen(method)]
+    fn screen_height(this: &TytleHost) -> u32;
+
     #[wasm_bindgen(method)]
     fn print(this: &TytleHost, msg: &str);
 
+    #[wasm_bindgen(method)]
+    fn set_pose(this: &TytleHost, x: f64, y: f64, heading: f64);
+
+    #[wasm_bindgen(method)]
+    fn move_to(this: &TytleHost, x: f64, y: f64, heading: f64);
+
+    #[wasm_bindgen(method)]
+    fn set_shape(this: &TytleHost, name: &str);
+
+    #[wasm_bindgen(method)]
+    fn set_pen_color(this: &TytleHost, r: u8, g: u8, b: u8);
+
+    #[wasm_bindgen(method)]
+    fn stamp(this: &TytleHost, x: f64, y: f64, heading: f64);
+
+    #[wasm_bindgen(method)]
+    fn label(this: &TytleHost, text: &str, x: f64, y: f64, heading: f64, font: &str, size: i32);
+
+    #[wasm_bindgen(method)]
+    fn play_tone(this: &TytleHost, freq: u32, ms: u32);
+
+    #[wasm_bindgen(method)]
+    fn kv_set(this: &TytleHost, key: &str, value: &str);
+
+    #[wasm_bindgen(method)]
+    fn kv_get(this: &TytleHost, key: &str) -> Option<String>;
+
+    #[wasm_bindgen(method)]
+    fn open_stream(this: &TytleHost, name: &str);
+
+    #[wasm_bindgen(method)]
+    fn write_stream(this: &TytleHost, name: &str, text: &str);
+
+    #[wasm_bindgen(method)]
+    fn close_stream(this: &TytleHost, name: &str);
+
     #[wasm_bindgen(method)]
     fn compilation_error(this: &TytleHost, error: &str);
 }
@@ -86,6 +131,10 @@ impl Host for BrowserHost {
         }
     }
 
+    fn exec_turn(&mut self, degrees: f64) {
+        self.browser.turn(degrees);
+    }
+
     fn exec_cmd(&mut self, cmd: &Command) {
         match cmd {
             Command::ShowTurtle => self.browser.show_turtle(),
@@ -109,4 +158,102 @@ impl Host for BrowserHost {
         let msg = format!("[PRINT] {}", value);
         self.browser.print(&msg);
     }
+
+    fn exec_print_text(&mut self, text: &str) {
+        let msg = format!("[PRINT] {}", text);
+        self.browser.print(&msg);
+    }
+
+    fn exec_trace(&mut self, line: &str) {
+        let msg = format!("[TRACE] {}", line);
+        self.browser.print(&msg);
+    }
+
+    // the positions and headings are passed in turtle coordinates (and degrees),
+    // the canvas ones are derived by `tytle_host.js`
+    fn exec_stamp(&mut self, position: (isize, isize), heading: f64) {
+        let (x, y) = position;
+        self.browser.stamp(x as f64, y as f64, heading);
+    }
+
+    fn exec_label(&mut self, label: &Label) {
+        let (x, y) = label.position;
+
+        self.browser.label(
+            &label.text,
+            x as f64,
+            y as f64,
+            label.heading,
+            &label.style.font,
+            label.style.size as i32,
+        );
+    }
+
+    fn turtle_shape_changed(&mut self, shape: &TurtleShape) {
+        self.browser.set_shape(&shape.name());
+    }
+
+    fn play_tone(&mut self, freq: usize, ms: usize) {
+        self.browser.play_tone(freq as u32, ms as u32);
+    }
+
+    fn pen_color_changed(&mut self, color: Color) {
+        self.browser.set_pen_color(color.r, color.g, color.b);
+    }
+
+    // the movement is drawn frame by frame, starting from the VM's pose
+    fn movement_started(&mut self, movement: &Movement) {
+        let (x, y) = movement.from.position;
+        self.browser.set_pose(x, y, movement.from.heading);
+    }
+
+    fn movement_progress(&mut self, _movement: &Movement, _frame: usize, pose: &TurtlePose) {
+        let (x, y) = pose.position;
+        self.browser.move_to(x, y, pose.heading);
+    }
+
+    fn movement_completed(&mut self, movement: &Movement) {
+        let (x, y) = movement.to.position;
+        self.browser.move_to(x, y, movement.to.heading);
+    }
+
+    fn state_restored(&mut self, turtle: &Turtle, pen: &Pen) {
+        let (x, y) = (turtle.xcor() as f64, turtle.ycor() as f64);
+        self.browser.set_pose(x, y, turtle.heading());
+
+        match pen.get_state() {
+            PenState::Up => self.browser.pen_up(),
+            PenState::Down => self.browser.pen_down(),
+            PenState::Erase => self.browser.pen_erase(),
+        }
+
+        self.pen_color_changed(pen.get_color());
+    }
+
+    fn kv_set(&mut self, key: &str, value: &str) {
+        self.browser.kv_set(key, value);
+    }
+
+    fn kv_get(&self, key: &str) -> Option<String> {
+        self.browser.kv_get(key)
+    }
+
+    fn open_stream(&mut self, name: &str) {
+        self.browser.open_stream(name);
+    }
+
+    fn write_stream(&mut self, name: &str, text: &str) {
+        self.browser.write_stream(name, text);
+    }
+
+    fn close_stream(&mut self, name: &str) {
+        self.browser.close_stream(name);
+    }
+
+    fn screen_size(&self) -> (usize, usize) {
+        let width = self.browser.screen_width() as usize;
+        let height = self.browser.screen_height() as usize;
+
+        (width, height)
+    }
 }
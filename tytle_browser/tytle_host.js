@@ -1,10 +1,16 @@
 export class TytleHost {
   constructor() {
+    // the canvas point of the turtle home (`0, 0`)
+    this.origin = { x: 400, y: 800 };
+
     this.degree = 0;
-    this.setx(400);
-    this.sety(800);
+    this.setx(this.origin.x);
+    this.sety(this.origin.y);
     this.pen_down();
     this.show_turtle();
+
+    this.shape = "arrow";
+    this.color = "#000000";
   }
 
   forward(count) {
@@ -35,6 +41,19 @@ export class TytleHost {
     this.left((-1) * degree)
   }
 
+  // a clockwise turn, whatever the steering (e.g within the stdlib procedures)
+  turn(degrees) {
+    this.right(degrees);
+  }
+
+  screen_width() {
+    return this._getCanvas().width;
+  }
+
+  screen_height() {
+    return this._getCanvas().height;
+  }
+
   setx(x) {
     this.x = x;
   }
@@ -72,6 +91,113 @@ export class TytleHost {
     ctx.clearRect(0, 0, canvas.width, canvas.height);
   }
 
+  // the turtle jumps to a pose given in turtle coordinates (e.g a `POPSTATE`), without drawing.
+  // a turtle heading grows clockwise, while `degree` grows counter-clockwise
+  set_pose(x, y, heading) {
+    [this.x, this.y] = this._toCanvas(x, y);
+    this.degree = -heading;
+  }
+
+  // the turtle moves to a pose given in turtle coordinates (e.g a frame of a movement)
+  move_to(x, y, heading) {
+    const [new_x, new_y] = this._toCanvas(x, y);
+
+    this._drawLine(this.x, this.y, new_x, new_y);
+
+    this.x = new_x;
+    this.y = new_y;
+    this.degree = -heading;
+  }
+
+  set_shape(name) {
+    this.shape = name;
+  }
+
+  set_pen_color(r, g, b) {
+    this.color = `rgb(${r}, ${g}, ${b})`;
+  }
+
+  // the images shapes aren't available to the playground, so they're stamped as a circle
+  stamp(x, y, heading) {
+    const [cx, cy] = this._toCanvas(x, y);
+    const ctx = this._getCanvas().getContext("2d");
+
+    ctx.save();
+    ctx.translate(cx, cy);
+    ctx.rotate((heading * Math.PI) / 180);
+    ctx.fillStyle = this.color;
+    ctx.beginPath();
+
+    switch (this.shape) {
+      case "arrow":
+        ctx.moveTo(0, -10);
+        ctx.lineTo(6, 6);
+        ctx.lineTo(0, 2);
+        ctx.lineTo(-6, 6);
+        break;
+      case "triangle":
+        ctx.moveTo(0, -10);
+        ctx.lineTo(8, 6);
+        ctx.lineTo(-8, 6);
+        break;
+      default:
+        ctx.arc(0, 0, 6, 0, 2 * Math.PI);
+    }
+
+    ctx.closePath();
+    ctx.fill();
+    ctx.restore();
+  }
+
+  // the text runs along the turtle heading (a heading of `90` is a horizontal text)
+  label(text, x, y, heading, font, size) {
+    const [cx, cy] = this._toCanvas(x, y);
+    const ctx = this._getCanvas().getContext("2d");
+
+    ctx.save();
+    ctx.translate(cx, cy);
+    ctx.rotate(((heading - 90) * Math.PI) / 180);
+    ctx.font = `${size}px ${font}`;
+    ctx.fillStyle = this.color;
+    ctx.fillText(text, 0, 0);
+    ctx.restore();
+  }
+
+  play_tone(freq, ms) {
+    this.audio = this.audio || new AudioContext();
+
+    const oscillator = this.audio.createOscillator();
+    oscillator.frequency.value = freq;
+    oscillator.connect(this.audio.destination);
+
+    oscillator.start();
+    oscillator.stop(this.audio.currentTime + ms / 1000);
+  }
+
+  // the values kept by `STORE` persist in the browser local storage
+  kv_set(key, value) {
+    window.localStorage.setItem(`tytle:${key}`, value);
+  }
+
+  kv_get(key) {
+    const value = window.localStorage.getItem(`tytle:${key}`);
+    return value === null ? undefined : value;
+  }
+
+  // the streams written by `PRINT` after a `SETWRITE`, logged to the console once closed
+  open_stream(name) {
+    this.streams = this.streams || new Map();
+    this.streams.set(name, []);
+  }
+
+  write_stream(name, text) {
+    this.streams.get(name).push(text);
+  }
+
+  close_stream(name) {
+    console.log(`[STREAM ${name}]\n` + this.streams.get(name).join("\n"));
+  }
+
   compilation_error(msg) {
     console.log("[COMPILE ERROR]", msg);
   }
@@ -83,6 +209,8 @@ export class TytleHost {
 
     switch (this.pen_state) {
       case 'DOWN':
+        ctx.strokeStyle = this.color;
+        ctx.beginPath();
         ctx.moveTo(x0, y0);
         ctx.lineTo(x1, y1);
         ctx.stroke();
@@ -98,6 +226,10 @@ export class TytleHost {
     // console.log(`moved (${x0}, ${y0}) -> (${x1}, ${y1})`);
   }
 
+  _toCanvas(x, y) {
+    return [this.origin.x + x, this.origin.y - y];
+  }
+
   _getCanvas() {
     return document.getElementById("tytle-canvas")
   }
@@ -0,0 +1,29 @@
+#[macro_use]
+extern crate criterion;
+
+mod workloads;
+
+use criterion::{BatchSize, BenchmarkId, Criterion};
+use tytle::ir::CfgBuilder;
+
+fn cfg_build_time(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cfg_builder");
+
+    for size in [10, 100, 1000].iter() {
+        let code = workloads::generate_program(*size);
+
+        // the semantic analysis is done in the setup phase, so only the CFG construction is measured
+        group.bench_with_input(BenchmarkId::new("generated", size), &code, |b, code| {
+            b.iter_batched(
+                || workloads::analyze(code),
+                |(ast, mut env)| CfgBuilder::new(&mut env).build(&ast),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, cfg_build_time);
+criterion_main!(benches);
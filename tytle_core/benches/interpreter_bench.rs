@@ -0,0 +1,44 @@
+#[macro_use]
+extern crate criterion;
+
+mod workloads;
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+
+fn interpreter_instructions_per_sec(c: &mut Criterion) {
+    let mut group = c.benchmark_group("interpreter");
+
+    for (name, code) in workloads::WORKLOADS.iter() {
+        let (env, cfg) = workloads::compile(code);
+        let instructions = workloads::run(&env, &cfg);
+
+        group.throughput(Throughput::Elements(instructions));
+        group.bench_function(BenchmarkId::new("workload", name), |b| {
+            b.iter(|| workloads::run(&env, &cfg))
+        });
+    }
+
+    group.finish();
+}
+
+fn interpreter_cse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("interpreter_cse");
+
+    for (name, code) in workloads::WORKLOADS.iter() {
+        let (env, cfg) = workloads::compile(code);
+        let (opt_env, opt_cfg) = workloads::compile_with_cse(code);
+
+        group.bench_function(BenchmarkId::new("baseline", name), |b| {
+            b.iter(|| workloads::run(&env, &cfg))
+        });
+
+        group.bench_function(BenchmarkId::new("cse", name), |b| {
+            b.iter(|| workloads::run(&opt_env, &opt_cfg))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, interpreter_instructions_per_sec, interpreter_cse);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+#[macro_use]
+extern crate criterion;
+
+mod workloads;
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+use tytle::lexer::{Lexer, Token, TytleLexer};
+
+fn lex_all(code: &str) {
+    let mut lexer = TytleLexer::new(code);
+
+    while let Some((token, _)) = lexer.pop_current_token() {
+        if token == Token::EOF {
+            break;
+        }
+    }
+}
+
+fn lexer_tokens_per_sec(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lexer");
+
+    for size in [10, 100, 1000].iter() {
+        let code = workloads::generate_program(*size);
+        let tokens = workloads::count_tokens(&code);
+
+        group.throughput(Throughput::Elements(tokens));
+        group.bench_with_input(BenchmarkId::new("generated", size), &code, |b, code| {
+            b.iter(|| lex_all(code))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, lexer_tokens_per_sec);
+criterion_main!(benches);
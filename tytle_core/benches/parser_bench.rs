@@ -0,0 +1,24 @@
+#[macro_use]
+extern crate criterion;
+
+mod workloads;
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+
+fn parser_throughput(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parser");
+
+    for size in [10, 100, 1000].iter() {
+        let code = workloads::generate_program(*size);
+
+        group.throughput(Throughput::Bytes(code.len() as u64));
+        group.bench_with_input(BenchmarkId::new("generated", size), &code, |b, code| {
+            b.iter(|| workloads::parse(code))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, parser_throughput);
+criterion_main!(benches);
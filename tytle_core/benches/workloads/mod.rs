@@ -0,0 +1,181 @@
+// Standard tytle workloads shared by the benchmark targets.
+//
+// Each workload is a complete tytle program exercising a different
+// part of the pipeline:
+// * `SPIRAL`       - a tight `REPEAT` loop with global variable traffic
+// * `FRACTAL_TREE` - deep recursion (procedure calls & returns)
+// * `SIEVE`        - nested loops, conditionals and integer arithmetic
+// * `NORMS`        - repeated arithmetic subexpressions (a target for CSE)
+
+#![allow(dead_code)]
+
+use tytle::ast::semantic::{AstTypeCheck, Environment, SymbolTableGenerator};
+use tytle::ir::{CfgObject, CommonSubexprElim};
+use tytle::lexer::{Lexer, Token, TytleLexer};
+use tytle::prelude::*;
+
+pub const SPIRAL: &str = r#"
+    MAKEGLOBAL STEP = 1
+
+    REPEAT 500 [
+        FORWARD STEP
+        RIGHT STEP
+        MAKE STEP = STEP + 2
+    ]
+"#;
+
+pub const FRACTAL_TREE: &str = r#"
+    TO TREE(LEVEL: INT, MAX: INT)
+        IF LEVEL < MAX [
+            FORWARD 10
+            LEFT 5
+            TREE(LEVEL + 1, MAX)
+            RIGHT 10
+            TREE(LEVEL + 1, MAX)
+            LEFT 5
+            BACKWARD 10
+        ]
+    END
+
+    TREE(0, 10)
+"#;
+
+pub const SIEVE: &str = r#"
+    TO IS_PRIME(N: INT): BOOL
+        MAKELOCAL D = 1
+        MAKELOCAL PRIME = TRUE
+
+        REPEAT N [
+            MAKE D = D + 1
+            IF D < N [
+                IF NOT ((N / D) * D < N) [
+                    MAKE PRIME = FALSE
+                ]
+            ]
+        ]
+
+        RETURN PRIME
+    END
+
+    MAKEGLOBAL PRIMES = 0
+    MAKEGLOBAL I = 1
+
+    REPEAT 150 [
+        MAKE I = I + 1
+        IF IS_PRIME(I) [
+            MAKE PRIMES = PRIMES + 1
+        ]
+    ]
+
+    PRINT PRIMES
+"#;
+
+pub const NORMS: &str = r#"
+    MAKEGLOBAL X = 3
+    MAKEGLOBAL Y = 4
+    MAKEGLOBAL NORM = 0
+
+    REPEAT 500 [
+        MAKE NORM = (X * X + Y * Y) / (X * X + Y * Y + 1) + (X * X + Y * Y) * 2
+        MAKE X = X + 1
+    ]
+
+    PRINT NORM
+"#;
+
+pub const WORKLOADS: &[(&str, &str)] = &[
+    ("spiral", SPIRAL),
+    ("fractal_tree", FRACTAL_TREE),
+    ("sieve", SIEVE),
+    ("norms", NORMS),
+];
+
+// generates a large program by repeating a representative block of statements `n` times
+pub fn generate_program(n: usize) -> String {
+    let mut code = String::new();
+
+    for i in 0..n {
+        code.push_str(&format!(
+            r#"
+    TO PROC_{i}(A: INT, B: INT): INT
+        MAKELOCAL C = (A + B) * 2
+        IF C > 10 [
+            RETURN C / 2
+        ] [
+            RETURN C
+        ]
+    END
+
+    MAKEGLOBAL VAR_{i} = PROC_{i}({i}, 2)
+    REPEAT 2 [
+        FORWARD VAR_{i}
+        RIGHT 90
+    ]
+"#,
+            i = i
+        ));
+    }
+
+    code
+}
+
+pub fn count_tokens(code: &str) -> u64 {
+    let mut lexer = TytleLexer::new(code);
+    let mut count = 0;
+
+    while let Some((token, _)) = lexer.pop_current_token() {
+        count += 1;
+
+        if token == Token::EOF {
+            break;
+        }
+    }
+
+    count
+}
+
+pub fn parse(code: &str) -> Ast {
+    TytleParser.parse(code).unwrap()
+}
+
+pub fn analyze(code: &str) -> (Ast, Environment) {
+    let mut ast = parse(code);
+
+    let generator = SymbolTableGenerator::new();
+    let mut env = generator.generate(&mut ast).unwrap();
+
+    let mut checker = AstTypeCheck::new(&mut env);
+    checker.check(&mut ast).unwrap();
+
+    (ast, env)
+}
+
+pub fn compile(code: &str) -> (Environment, CfgObject) {
+    let program = Compiler::compile(code, CompileOptions::default()).unwrap();
+
+    (program.env, program.cfg)
+}
+
+pub fn compile_with_cse(code: &str) -> (Environment, CfgObject) {
+    let (mut env, mut cfg) = compile(code);
+
+    CommonSubexprElim::new(&mut env).optimize(&mut cfg);
+
+    (env, cfg)
+}
+
+// runs a compiled program to completion and returns the number of executed instructions
+pub fn run(env: &Environment, cfg: &CfgObject) -> u64 {
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(cfg, env, &mut host);
+    let mut count = 0;
+
+    loop {
+        let completed = intr.exec_next().unwrap();
+        count += 1;
+
+        if completed {
+            return count;
+        }
+    }
+}
@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tytle::lexer::{Lexer, Token, TytleLexer};
+
+// the lexer must either reject the code (with a `LexError`) or tokenize it up to its `EOF`
+fuzz_target!(|data: &[u8]| {
+    if let Ok(code) = std::str::from_utf8(data) {
+        if TytleLexer::check(code).is_err() {
+            return;
+        }
+
+        let mut lexer = TytleLexer::new(code);
+
+        while let Some((token, _location)) = lexer.pop_current_token() {
+            if token == Token::EOF {
+                break;
+            }
+        }
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tytle::parser::{Parser, TytleParser};
+
+// any code is either parsed or rejected with a `ParseError`
+fuzz_target!(|data: &[u8]| {
+    if let Ok(code) = std::str::from_utf8(data) {
+        let _ = TytleParser.parse(code);
+    }
+});
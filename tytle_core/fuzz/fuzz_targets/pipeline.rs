@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tytle::RunOptions;
+use tytle::vm::{DummyHost, ResourceLimits};
+
+// the code is compiled and run, any failure must surface as a `TytleError`.
+// the steps & memory are capped, since a fuzzed program may well loop forever
+fuzz_target!(|data: &[u8]| {
+    if let Ok(code) = std::str::from_utf8(data) {
+        let options = RunOptions {
+            max_steps: Some(10_000),
+            limits: ResourceLimits {
+                max_heap: Some(1 << 20),
+                max_list_len: Some(1_000),
+                max_str_len: Some(10_000),
+            },
+            ..Default::default()
+        };
+
+        let mut host = DummyHost::new();
+
+        let _ = tytle::run(code, &mut host, options);
+    }
+});
@@ -0,0 +1,224 @@
+use crate::animation::{encode_apng, encode_gif, EncodeError, Framebuffer, BACKGROUND, INK};
+use crate::ast::statement::{Command, Direction};
+use crate::compat::prelude::*;
+#[cfg(feature = "bigint")]
+use crate::vm::BigInt;
+use crate::vm::{Color, Host, Label, Movement, Pen, PenState, Turtle, TurtlePose, TurtleShape};
+
+// wraps a `Host`, rasterizing the drawing of the program into a `Framebuffer`
+// and capturing a frame every N drawing events.
+//
+// a drawing event is a move made while the pen is down (or erasing), or a `CLEAN` / `CLEARSCREEN`.
+// call `finish` once the program is done, so the last drawing events make it into a frame
+pub struct AnimationHost<H: Host> {
+    inner: H,
+    turtle: Turtle,
+    pen: Pen,
+    framebuffer: Framebuffer,
+    every: usize,
+    pending: usize,
+    frames: Vec<Framebuffer>,
+}
+
+impl<H: Host> AnimationHost<H> {
+    pub fn new(inner: H, width: usize, height: usize) -> Self {
+        Self {
+            inner,
+            turtle: Turtle::new(),
+            pen: Pen::new(),
+            framebuffer: Framebuffer::new(width, height),
+            every: 1,
+            pending: 0,
+            frames: Vec::new(),
+        }
+    }
+
+    // captures a frame every `n` drawing events (at least one)
+    pub fn every(mut self, n: usize) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    pub fn inner(&self) -> &H {
+        &self.inner
+    }
+
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.framebuffer
+    }
+
+    pub fn frames(&self) -> &[Framebuffer] {
+        &self.frames
+    }
+
+    // captures the drawing events made since the last frame.
+    // a program which didn't draw anything still gets a single (blank) frame
+    pub fn finish(&mut self) {
+        if self.pending > 0 || self.frames.is_empty() {
+            self.capture();
+        }
+    }
+
+    // an animated GIF of the captured frames, showing each frame for `delay_ms` milliseconds
+    // (GIF delays are in hundredths of a second).
+    // there are no frames to encode till the first capture (see `finish`)
+    pub fn to_gif(&self, delay_ms: u16) -> Result<Vec<u8>, EncodeError> {
+        encode_gif(&self.frames, delay_ms)
+    }
+
+    // an animated PNG of the captured frames, showing each frame for `delay_ms` milliseconds
+    pub fn to_apng(&self, delay_ms: u16) -> Result<Vec<u8>, EncodeError> {
+        encode_apng(&self.frames, delay_ms)
+    }
+
+    pub fn into_parts(self) -> (H, Vec<Framebuffer>) {
+        (self.inner, self.frames)
+    }
+
+    fn drawing_event(&mut self) {
+        self.pending += 1;
+
+        if self.pending == self.every {
+            self.capture();
+        }
+    }
+
+    fn draw_direct(&mut self, direct: &Direction, count: isize) {
+        let from = (self.turtle.xcor(), self.turtle.ycor());
+        self.turtle.exec_direct(direct, count);
+        let to = (self.turtle.xcor(), self.turtle.ycor());
+
+        let color = match self.pen.get_state() {
+            PenState::Up => return,
+            PenState::Down => INK,
+            PenState::Erase => BACKGROUND,
+        };
+
+        self.framebuffer.draw_line(from, to, color);
+        self.drawing_event();
+    }
+
+    fn capture(&mut self) {
+        self.frames.push(self.framebuffer.clone());
+        self.pending = 0;
+    }
+}
+
+impl<H: Host> Host for AnimationHost<H> {
+    fn exec_cmd(&mut self, cmd: &Command) {
+        self.inner.exec_cmd(cmd);
+
+        match cmd {
+            Command::PenUp => self.pen.up(),
+            Command::PenDown => self.pen.down(),
+            Command::PenErase => self.pen.erase(),
+            Command::Clean | Command::ClearScreen => {
+                self.framebuffer.clear();
+                self.drawing_event();
+            }
+            _ => {}
+        }
+    }
+
+    fn exec_direct(&mut self, direct: &Direction, count: isize) {
+        self.inner.exec_direct(direct, count);
+        self.draw_direct(direct, count);
+    }
+
+    fn exec_turn(&mut self, degrees: f64) {
+        self.inner.exec_turn(degrees);
+        self.turtle.turn_degrees(degrees);
+    }
+
+    fn exec_trap(&mut self, node: usize, ip: usize) {
+        self.inner.exec_trap(node, ip);
+    }
+
+    fn exec_print(&mut self, value: isize) {
+        self.inner.exec_print(value);
+    }
+
+    #[cfg(feature = "bigint")]
+    fn exec_print_big(&mut self, value: &BigInt) {
+        self.inner.exec_print_big(value);
+    }
+
+    fn exec_print_text(&mut self, text: &str) {
+        self.inner.exec_print_text(text);
+    }
+
+    fn exec_trace(&mut self, line: &str) {
+        self.inner.exec_trace(line);
+    }
+
+    fn exec_stamp(&mut self, position: (isize, isize), heading: f64) {
+        self.inner.exec_stamp(position, heading);
+    }
+
+    fn exec_label(&mut self, label: &Label) {
+        self.inner.exec_label(label);
+    }
+
+    fn turtle_shape_changed(&mut self, shape: &TurtleShape) {
+        self.inner.turtle_shape_changed(shape);
+    }
+
+    fn play_tone(&mut self, freq: usize, ms: usize) {
+        self.inner.play_tone(freq, ms);
+    }
+
+    // the frames are monochrome, so only the inner host draws in color
+    fn pen_color_changed(&mut self, color: Color) {
+        self.pen.set_color(color);
+        self.inner.pen_color_changed(color);
+    }
+
+    // the frames are captured per drawing event, so a movement is drawn once it's completed
+    fn movement_started(&mut self, movement: &Movement) {
+        self.inner.movement_started(movement);
+    }
+
+    fn movement_progress(&mut self, movement: &Movement, frame: usize, pose: &TurtlePose) {
+        self.inner.movement_progress(movement, frame, pose);
+    }
+
+    fn movement_completed(&mut self, movement: &Movement) {
+        self.inner.movement_completed(movement);
+        self.draw_direct(&movement.direct, movement.count);
+    }
+
+    fn state_restored(&mut self, turtle: &Turtle, pen: &Pen) {
+        self.turtle = turtle.clone();
+        self.pen = pen.clone();
+        self.inner.state_restored(turtle, pen);
+    }
+
+    fn kv_set(&mut self, key: &str, value: &str) {
+        self.inner.kv_set(key, value);
+    }
+
+    fn kv_get(&self, key: &str) -> Option<String> {
+        self.inner.kv_get(key)
+    }
+
+    fn open_stream(&mut self, name: &str) {
+        self.inner.open_stream(name);
+    }
+
+    fn write_stream(&mut self, name: &str, text: &str) {
+        self.inner.write_stream(name, text);
+    }
+
+    fn close_stream(&mut self, name: &str) {
+        self.inner.close_stream(name);
+    }
+
+    // the programs draw on the framebuffer
+    fn screen_size(&self) -> (usize, usize) {
+        (self.framebuffer.width(), self.framebuffer.height())
+    }
+
+    fn compilation_error(&mut self, error: &str) {
+        self.inner.compilation_error(error);
+    }
+}
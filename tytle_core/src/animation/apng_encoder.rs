@@ -0,0 +1,143 @@
+use crate::animation::framebuffer::frames_size;
+use crate::animation::{EncodeError, Framebuffer, PALETTE};
+use crate::compat::prelude::*;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+// the largest length of a stored (uncompressed) deflate block
+const MAX_STORED_BLOCK: usize = 65535;
+
+// the largest width (and height) of a PNG image
+const MAX_SIZE: usize = (1 << 31) - 1;
+
+// encodes the frames into a looping APNG, showing each frame for `delay_ms` milliseconds.
+// the first frame is the default image (shown by decoders not supporting animation).
+// all the frames must be of the same size (and there must be at least one)
+pub fn encode_apng(frames: &[Framebuffer], delay_ms: u16) -> Result<Vec<u8>, EncodeError> {
+    let (width, height) = frames_size(frames, MAX_SIZE)?;
+    let (width, height) = (width as u32, height as u32);
+
+    let mut bytes = SIGNATURE.to_vec();
+
+    // 8 bits per pixel, indexed colors
+    let mut header = Vec::new();
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, 3, 0, 0, 0]);
+    push_chunk(&mut bytes, b"IHDR", &header);
+
+    let palette = PALETTE
+        .iter()
+        .flat_map(|&(r, g, b)| vec![r, g, b])
+        .collect::<Vec<_>>();
+    push_chunk(&mut bytes, b"PLTE", &palette);
+
+    // the animation control: the number of frames, looping forever
+    let mut control = Vec::new();
+    control.extend_from_slice(&(frames.len() as u32).to_be_bytes());
+    control.extend_from_slice(&0u32.to_be_bytes());
+    push_chunk(&mut bytes, b"acTL", &control);
+
+    // the `fcTL` and `fdAT` chunks share a single sequence
+    let mut sequence: u32 = 0;
+
+    for (i, frame) in frames.iter().enumerate() {
+        let mut frame_control = Vec::new();
+        frame_control.extend_from_slice(&sequence.to_be_bytes());
+        frame_control.extend_from_slice(&width.to_be_bytes());
+        frame_control.extend_from_slice(&height.to_be_bytes());
+        frame_control.extend_from_slice(&0u32.to_be_bytes());
+        frame_control.extend_from_slice(&0u32.to_be_bytes());
+        frame_control.extend_from_slice(&delay_ms.to_be_bytes());
+        frame_control.extend_from_slice(&1000u16.to_be_bytes());
+        frame_control.extend_from_slice(&[0, 0]);
+        push_chunk(&mut bytes, b"fcTL", &frame_control);
+        sequence += 1;
+
+        let data = zlib_stored(&scanlines(frame));
+
+        if i == 0 {
+            push_chunk(&mut bytes, b"IDAT", &data);
+        } else {
+            let mut frame_data = sequence.to_be_bytes().to_vec();
+            frame_data.extend_from_slice(&data);
+            push_chunk(&mut bytes, b"fdAT", &frame_data);
+            sequence += 1;
+        }
+    }
+
+    push_chunk(&mut bytes, b"IEND", &[]);
+    Ok(bytes)
+}
+
+fn push_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = bytes.len();
+    bytes.extend_from_slice(kind);
+    bytes.extend_from_slice(data);
+
+    let crc = crc32(&bytes[start..]);
+    bytes.extend_from_slice(&crc.to_be_bytes());
+}
+
+// the rows of the frame, each preceded by its filter type (none)
+fn scanlines(frame: &Framebuffer) -> Vec<u8> {
+    let mut data = Vec::with_capacity((frame.width() + 1) * frame.height());
+
+    for row in frame.pixels().chunks(frame.width().max(1)) {
+        data.push(0);
+        data.extend_from_slice(row);
+    }
+
+    data
+}
+
+// a zlib stream of stored deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+
+    if blocks.peek().is_none() {
+        bytes.extend_from_slice(&[0x01, 0x00, 0x00, 0xFF, 0xFF]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+
+        bytes.push(if last { 0x01 } else { 0x00 });
+        bytes.extend_from_slice(&len.to_le_bytes());
+        bytes.extend_from_slice(&(!len).to_le_bytes());
+        bytes.extend_from_slice(block);
+    }
+
+    bytes.extend_from_slice(&adler32(data).to_be_bytes());
+    bytes
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+
+    for &byte in data {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+
+    for &byte in data {
+        crc ^= u32::from(byte);
+
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
@@ -0,0 +1,49 @@
+use core::fmt;
+
+// returned by `encode_gif` / `encode_apng` for frames which can't be encoded
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodeError {
+    // an animation has at least one frame
+    NoFrames,
+    // the frames size exceeds the format limit (`65535` pixels for GIF, `2^31 - 1` for PNG)
+    FrameTooLarge { width: usize, height: usize },
+    // the frame of index `index` isn't of the size of the first one
+    FrameSizeMismatch { index: usize },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeError::NoFrames => write!(f, "No frames to encode"),
+            EncodeError::FrameTooLarge { width, height } => {
+                write!(f, "Frames of {}x{} pixels are too large to encode", width, height)
+            }
+            EncodeError::FrameSizeMismatch { index } => {
+                write!(f, "Frame #{} isn't of the size of the first frame", index)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compat::prelude::*;
+
+    #[test]
+    pub fn encode_error_frame_too_large() {
+        let err = EncodeError::FrameTooLarge {
+            width: 70000,
+            height: 10,
+        };
+
+        assert_eq!("Frames of 70000x10 pixels are too large to encode", err.to_string());
+    }
+
+    #[test]
+    pub fn encode_error_frame_size_mismatch() {
+        let err = EncodeError::FrameSizeMismatch { index: 2 };
+
+        assert_eq!("Frame #2 isn't of the size of the first frame", err.to_string());
+    }
+}
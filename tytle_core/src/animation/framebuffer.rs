@@ -0,0 +1,233 @@
+// the palette index of the background
+use crate::animation::EncodeError;
+use crate::compat::prelude::*;
+
+pub const BACKGROUND: u8 = 0;
+
+// the palette index of the drawn lines
+pub const INK: u8 = 1;
+
+// the colors of the palette indexes
+pub const PALETTE: [(u8, u8, u8); 2] = [(255, 255, 255), (0, 0, 0)];
+
+// an indexed-color raster of the drawing (rows are stored top to bottom).
+// the tytle y axis goes up, so the point `(0, 0)` is the bottom-left pixel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![BACKGROUND; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    // the palette index at the tytle point `(x, y)`, or `None` when it's outside the framebuffer
+    pub fn pixel(&self, x: isize, y: isize) -> Option<u8> {
+        self.offset(x, y).map(|offset| self.pixels[offset])
+    }
+
+    pub fn clear(&mut self) {
+        for pixel in self.pixels.iter_mut() {
+            *pixel = BACKGROUND;
+        }
+    }
+
+    // draws the line between the tytle points `from` and `to` (Bresenham),
+    // skipping the pixels outside the framebuffer.
+    // a line reaching far beyond the framebuffer is clipped to it first, so it's walked
+    // in a bounded number of steps (and its deltas don't overflow)
+    pub fn draw_line(&mut self, from: (isize, isize), to: (isize, isize), color: u8) {
+        let (from, to) = if self.is_near(from) && self.is_near(to) {
+            (from, to)
+        } else {
+            match self.clip(from, to) {
+                Some(line) => line,
+                None => return,
+            }
+        };
+
+        let (mut x, mut y) = from;
+        let dx = (to.0 - x).abs();
+        let dy = -(to.1 - y).abs();
+        let step_x = if x < to.0 { 1 } else { -1 };
+        let step_y = if y < to.1 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        loop {
+            self.set_pixel(x, y, color);
+
+            if (x, y) == to {
+                break;
+            }
+
+            let err2 = 2 * err;
+
+            if err2 >= dy {
+                err += dy;
+                x += step_x;
+            }
+
+            if err2 <= dx {
+                err += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    // whether the point is at most the framebuffer (width + height) away from it
+    fn is_near(&self, point: (isize, isize)) -> bool {
+        let margin = (self.width + self.height) as isize;
+
+        let near_x = point.0 >= -margin && point.0 <= self.width as isize + margin;
+        let near_y = point.1 >= -margin && point.1 <= self.height as isize + margin;
+
+        near_x && near_y
+    }
+
+    // the part of the line within the framebuffer (bordered by a pixel), or `None` when
+    // the line misses it (Cohen-Sutherland). the points are moved along the line onto the
+    // borders they're beyond, computed exactly (see `intersect`)
+    fn clip(
+        &self,
+        mut from: (isize, isize),
+        mut to: (isize, isize),
+    ) -> Option<((isize, isize), (isize, isize))> {
+        let (max_x, max_y) = (self.width as isize, self.height as isize);
+
+        let outcode = |(x, y): (isize, isize)| {
+            let mut code = 0;
+
+            if x < -1 {
+                code |= LEFT;
+            } else if x > max_x {
+                code |= RIGHT;
+            }
+
+            if y < -1 {
+                code |= BOTTOM;
+            } else if y > max_y {
+                code |= TOP;
+            }
+
+            code
+        };
+
+        // each point is moved at most once onto each of the borders
+        for _ in 0..4 {
+            let (from_code, to_code) = (outcode(from), outcode(to));
+
+            if from_code | to_code == 0 {
+                return Some((from, to));
+            }
+
+            if from_code & to_code != 0 {
+                return None;
+            }
+
+            let (point, code, other) = if from_code != 0 {
+                (&mut from, from_code, to)
+            } else {
+                (&mut to, to_code, from)
+            };
+
+            *point = if code & LEFT != 0 {
+                (-1, intersect(*point, other, -1))
+            } else if code & RIGHT != 0 {
+                (max_x, intersect(*point, other, max_x))
+            } else if code & BOTTOM != 0 {
+                (intersect(flip(*point), flip(other), -1), -1)
+            } else {
+                (intersect(flip(*point), flip(other), max_y), max_y)
+            };
+        }
+
+        // the rounding has left a point just outside a corner, the line grazes the framebuffer
+        None
+    }
+
+    fn set_pixel(&mut self, x: isize, y: isize, color: u8) {
+        if let Some(offset) = self.offset(x, y) {
+            self.pixels[offset] = color;
+        }
+    }
+
+    fn offset(&self, x: isize, y: isize) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+
+        let row = self.height - 1 - y as usize;
+
+        Some(row * self.width + x as usize)
+    }
+}
+
+// the `(width, height)` shared by all the `frames`, each at most `max_size` pixels
+pub(crate) fn frames_size(
+    frames: &[Framebuffer],
+    max_size: usize,
+) -> Result<(usize, usize), EncodeError> {
+    let first = frames.first().ok_or(EncodeError::NoFrames)?;
+    let (width, height) = (first.width, first.height);
+
+    if width > max_size || height > max_size {
+        return Err(EncodeError::FrameTooLarge { width, height });
+    }
+
+    let mismatch = frames
+        .iter()
+        .position(|frame| (frame.width, frame.height) != (width, height));
+
+    match mismatch {
+        Some(index) => Err(EncodeError::FrameSizeMismatch { index }),
+        None => Ok((width, height)),
+    }
+}
+
+// the outcodes of `Framebuffer::clip`
+const LEFT: u8 = 1;
+const RIGHT: u8 = 2;
+const BOTTOM: u8 = 4;
+const TOP: u8 = 8;
+
+// the `y` of the line `a -> b` at `x` (lying between `a.0` and `b.0`), rounded to the nearest.
+// the deltas may take the whole `isize` range, so they're multiplied as `u128` magnitudes
+fn intersect(a: (isize, isize), b: (isize, isize), x: isize) -> isize {
+    let dx = b.0.abs_diff(a.0) as u128;
+    let dy = b.1.abs_diff(a.1) as u128;
+    let run = x.abs_diff(a.0) as u128;
+
+    // `run <= dx`, so `rise <= dy` and it fits the line span
+    let rise = (run * dy + dx / 2) / dx;
+
+    let y = match b.1 >= a.1 {
+        true => a.1 as i128 + rise as i128,
+        false => a.1 as i128 - rise as i128,
+    };
+
+    y as isize
+}
+
+// swaps the axes, so `intersect` gives the `x` of a line at a `y`
+fn flip(point: (isize, isize)) -> (isize, isize) {
+    (point.1, point.0)
+}
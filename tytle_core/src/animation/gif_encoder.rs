@@ -0,0 +1,156 @@
+use crate::animation::framebuffer::frames_size;
+use crate::animation::{EncodeError, Framebuffer, PALETTE};
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
+
+// the GIF format requires LZW codes of at least 2 bits, even for a two colors palette
+const MIN_CODE_SIZE: u32 = 2;
+
+const MAX_CODE: u16 = 4095;
+
+// encodes the frames into a looping GIF89a, showing each frame for `delay_ms` milliseconds.
+// all the frames must be of the same size (and there must be at least one)
+pub fn encode_gif(frames: &[Framebuffer], delay_ms: u16) -> Result<Vec<u8>, EncodeError> {
+    let (width, height) = frames_size(frames, u16::MAX as usize)?;
+    let (width, height) = (width as u16, height as u16);
+    let delay = delay_ms / 10;
+
+    let mut bytes = b"GIF89a".to_vec();
+
+    // the logical screen descriptor: a global color table of 2 colors
+    push_u16(&mut bytes, width);
+    push_u16(&mut bytes, height);
+    bytes.extend_from_slice(&[0x80, 0, 0]);
+
+    for &(r, g, b) in PALETTE.iter() {
+        bytes.extend_from_slice(&[r, g, b]);
+    }
+
+    // the NETSCAPE application extension, looping forever
+    bytes.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+    bytes.extend_from_slice(b"NETSCAPE2.0");
+    bytes.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    for frame in frames {
+        // the graphic control extension (holding the delay)
+        bytes.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00]);
+        push_u16(&mut bytes, delay);
+        bytes.extend_from_slice(&[0x00, 0x00]);
+
+        // the image descriptor, covering the whole screen
+        bytes.push(0x2C);
+        push_u16(&mut bytes, 0);
+        push_u16(&mut bytes, 0);
+        push_u16(&mut bytes, width);
+        push_u16(&mut bytes, height);
+        bytes.push(0x00);
+
+        bytes.push(MIN_CODE_SIZE as u8);
+
+        for block in lzw_encode(frame.pixels()).chunks(255) {
+            bytes.push(block.len() as u8);
+            bytes.extend_from_slice(block);
+        }
+
+        bytes.push(0x00);
+    }
+
+    bytes.push(0x3B);
+    Ok(bytes)
+}
+
+fn push_u16(bytes: &mut Vec<u8>, value: u16) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+// the variable-length LZW coding of GIF. the codes are packed least significant bit first
+fn lzw_encode(pixels: &[u8]) -> Vec<u8> {
+    let clear: u16 = 1 << MIN_CODE_SIZE;
+    let end: u16 = clear + 1;
+
+    let mut writer = BitWriter::new();
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut code_size = MIN_CODE_SIZE + 1;
+    let mut prefix: Option<u16> = None;
+
+    writer.write(clear, code_size);
+
+    for &pixel in pixels {
+        let current = match prefix {
+            Some(current) => current,
+            None => {
+                prefix = Some(u16::from(pixel));
+                continue;
+            }
+        };
+
+        if let Some(&code) = table.get(&(current, pixel)) {
+            prefix = Some(code);
+            continue;
+        }
+
+        writer.write(current, code_size);
+
+        if next_code > MAX_CODE {
+            // the table is full, starting over
+            writer.write(clear, code_size);
+            table.clear();
+            next_code = end + 1;
+            code_size = MIN_CODE_SIZE + 1;
+        } else {
+            table.insert((current, pixel), next_code);
+            next_code += 1;
+
+            // the decoder adds its entries a code behind, so it widens its codes only
+            // once the code after `next_code - 1` doesn't fit
+            if u32::from(next_code) > (1 << code_size) && code_size < 12 {
+                code_size += 1;
+            }
+        }
+
+        prefix = Some(u16::from(pixel));
+    }
+
+    if let Some(current) = prefix {
+        writer.write(current, code_size);
+    }
+
+    writer.write(end, code_size);
+    writer.finish()
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            acc: 0,
+            bits: 0,
+        }
+    }
+
+    fn write(&mut self, code: u16, size: u32) {
+        self.acc |= u32::from(code) << self.bits;
+        self.bits += size;
+
+        while self.bits >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+
+        self.bytes
+    }
+}
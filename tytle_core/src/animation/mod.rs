@@ -0,0 +1,22 @@
+// exporting animated renderings of a program's execution.
+//
+// an `AnimationHost` wraps a `Host`, rasterizes the drawing of the program into a `Framebuffer`
+// and captures a frame every N drawing events. the captured frames are assembled into:
+// * an animated GIF - see `encode_gif`
+// * an animated PNG (APNG) - see `encode_apng`
+//
+// both encoders are hand-rolled and use the two colors palette of the framebuffer
+// (the PNG image data is stored uncompressed).
+// they reject an empty list of frames, and frames too large for the format (see `EncodeError`)
+
+mod animation_host;
+mod apng_encoder;
+mod encode_error;
+mod framebuffer;
+mod gif_encoder;
+
+pub use animation_host::AnimationHost;
+pub use apng_encoder::encode_apng;
+pub use encode_error::EncodeError;
+pub use framebuffer::{Framebuffer, BACKGROUND, INK, PALETTE};
+pub use gif_encoder::encode_gif;
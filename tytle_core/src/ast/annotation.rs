@@ -0,0 +1,104 @@
+use crate::compat::collections::HashMap;
+use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_NODE_ID: AtomicUsize = AtomicUsize::new(0);
+
+// identifies an AST expression node. each node gets its own id once it's created (by the parser),
+// which is kept by its clones and isn't affected by the passes walking the AST.
+// it's not part of the node structure, so ASTs are compared ignoring the ids of their nodes
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NodeId(pub usize);
+
+impl NodeId {
+    pub fn next() -> Self {
+        NodeId(NEXT_NODE_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "@{}", self.0)
+    }
+}
+
+// a side-table of what a pass has learned about the AST nodes (e.g their types),
+// so passes annotate the nodes without mutating the AST (see `Environment::expr_types`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotationMap<T> {
+    annotations: HashMap<NodeId, T>,
+}
+
+impl<T> AnnotationMap<T> {
+    pub fn new() -> Self {
+        Self {
+            annotations: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, node_id: NodeId, annotation: T) -> Option<T> {
+        self.annotations.insert(node_id, annotation)
+    }
+
+    pub fn get(&self, node_id: NodeId) -> Option<&T> {
+        self.annotations.get(&node_id)
+    }
+
+    pub fn contains(&self, node_id: NodeId) -> bool {
+        self.annotations.contains_key(&node_id)
+    }
+
+    pub fn remove(&mut self, node_id: NodeId) -> Option<T> {
+        self.annotations.remove(&node_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.annotations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.annotations.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &T)> {
+        self.annotations.iter().map(|(node_id, annotation)| (*node_id, annotation))
+    }
+}
+
+impl<T> Default for AnnotationMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_ids_are_unique() {
+        let a = NodeId::next();
+        let b = NodeId::next();
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn annotation_map_insert_and_get() {
+        let (a, b) = (NodeId::next(), NodeId::next());
+        let mut map = AnnotationMap::new();
+
+        assert!(map.is_empty());
+
+        map.insert(a, "INT");
+        assert_eq!(Some("INT"), map.insert(a, "STR"));
+
+        assert_eq!(Some(&"STR"), map.get(a));
+        assert_eq!(None, map.get(b));
+        assert!(map.contains(a));
+        assert_eq!(1, map.len());
+
+        assert_eq!(Some("STR"), map.remove(a));
+        assert!(!map.contains(a));
+    }
+}
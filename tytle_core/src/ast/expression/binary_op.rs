@@ -1,3 +1,5 @@
+use crate::ast::expression::ExpressionType;
+use crate::compat::prelude::*;
 use crate::lexer::Token;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -9,6 +11,8 @@ pub enum BinaryOp {
     Div,
     GreaterThan,
     LessThan,
+    Equal,
+    NotEqual,
 }
 
 impl From<&str> for BinaryOp {
@@ -21,6 +25,8 @@ impl From<&str> for BinaryOp {
             "/" => BinaryOp::Div,
             ">" => BinaryOp::GreaterThan,
             "<" => BinaryOp::LessThan,
+            "=" => BinaryOp::Equal,
+            "<>" => BinaryOp::NotEqual,
             _ => panic!("Invalid binary operator: `{:?}`", tok),
         }
     }
@@ -36,11 +42,25 @@ impl From<&Token> for BinaryOp {
             Token::DIV => BinaryOp::Div,
             Token::GT => BinaryOp::GreaterThan,
             Token::LT => BinaryOp::LessThan,
+            Token::ASSIGN => BinaryOp::Equal,
             _ => panic!("Invalid binary operator: `{:?}`", tok),
         }
     }
 }
 
+impl BinaryOp {
+    // whether the operator applies to operands of type `operand_type` (both operands must be of the same type).
+    // these rules are shared by the type checker and the VM (see `MemoryValue::equals` and `MemoryValue::compare`)
+    pub fn accepts(&self, operand_type: &ExpressionType) -> bool {
+        match self {
+            BinaryOp::And | BinaryOp::Or => *operand_type == ExpressionType::Bool,
+            BinaryOp::Add | BinaryOp::Mul | BinaryOp::Div => *operand_type == ExpressionType::Int,
+            BinaryOp::GreaterThan | BinaryOp::LessThan => operand_type.is_ordered(),
+            BinaryOp::Equal | BinaryOp::NotEqual => operand_type.is_equatable(),
+        }
+    }
+}
+
 impl ToString for BinaryOp {
     fn to_string(&self) -> String {
         let s = match *self {
@@ -51,6 +71,8 @@ impl ToString for BinaryOp {
             BinaryOp::Div => "/",
             BinaryOp::GreaterThan => ">",
             BinaryOp::LessThan => "<",
+            BinaryOp::Equal => "=",
+            BinaryOp::NotEqual => "<>",
         };
 
         s.to_string()
@@ -96,6 +118,29 @@ mod tests {
         assert_eq!("<", BinaryOp::LessThan.to_string());
     }
 
+    #[test]
+    fn binary_op_eq() {
+        assert_eq!(BinaryOp::from("="), BinaryOp::Equal);
+        assert_eq!(BinaryOp::from(&Token::ASSIGN), BinaryOp::Equal);
+        assert_eq!("=", BinaryOp::Equal.to_string());
+    }
+
+    #[test]
+    fn binary_op_ne() {
+        assert_eq!(BinaryOp::from("<>"), BinaryOp::NotEqual);
+        assert_eq!("<>", BinaryOp::NotEqual.to_string());
+    }
+
+    #[test]
+    fn binary_op_accepts() {
+        assert!(BinaryOp::Equal.accepts(&ExpressionType::Bool));
+        assert!(BinaryOp::NotEqual.accepts(&ExpressionType::Str));
+        assert!(BinaryOp::LessThan.accepts(&ExpressionType::Str));
+        assert!(!BinaryOp::GreaterThan.accepts(&ExpressionType::Bool));
+        assert!(!BinaryOp::Add.accepts(&ExpressionType::Bool));
+        assert!(!BinaryOp::Equal.accepts(&ExpressionType::Unit));
+    }
+
     #[test]
     fn binary_op_and() {
         assert_eq!(BinaryOp::from("AND"), BinaryOp::And);
@@ -1,17 +1,21 @@
 use crate::ast::expression::*;
-use crate::ast::semantic::SymbolId;
+use crate::ast::statement::BlockStatement;
+use crate::ast::NodeId;
+use crate::compat::prelude::*;
 
-#[derive(Debug, Clone, PartialEq)]
+// the type (and the symbols) of an expression are annotated by its `node_id`
+// (see `Environment::expr_types` and `Environment::expr_symbols`)
+#[derive(Debug, Clone)]
 pub struct Expression {
-    pub expr_type: Option<ExpressionType>,
+    pub node_id: NodeId,
     pub expr_ast: ExpressionAst,
 }
 
 impl Expression {
     pub fn new(expr_ast: ExpressionAst) -> Self {
         Self {
+            node_id: NodeId::next(),
             expr_ast,
-            expr_type: None,
         }
     }
 
@@ -30,6 +34,24 @@ impl Expression {
     }
 }
 
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        self.expr_ast == other.expr_ast
+    }
+}
+
+impl From<usize> for Expression {
+    fn from(v: usize) -> Self {
+        Self::new(ExpressionAst::Literal(LiteralExpr::Int(v)))
+    }
+}
+
+impl From<bool> for Expression {
+    fn from(v: bool) -> Self {
+        Self::new(ExpressionAst::Literal(LiteralExpr::Bool(v)))
+    }
+}
+
 impl Expression {
     pub fn as_lit_expr_mut(&mut self) -> &mut LiteralExpr {
         match self.expr_ast {
@@ -52,6 +74,67 @@ impl Expression {
         }
     }
 
+    pub fn as_type_query_expr(&self) -> (TypeQuery, &Expression) {
+        match &self.expr_ast {
+            ExpressionAst::TypeQuery(query, expr) => (*query, expr),
+            _ => panic!(
+                "expected a type query expression. got: `{:?}`",
+                self.expr_ast
+            ),
+        }
+    }
+
+    pub fn as_list_expr(&self) -> &Vec<Expression> {
+        match &self.expr_ast {
+            ExpressionAst::List(items) => items,
+            _ => panic!("expected a list expression. got: `{:?}`", self.expr_ast),
+        }
+    }
+
+    pub fn as_map_expr(&self) -> (&String, &Expression) {
+        match &self.expr_ast {
+            ExpressionAst::Map(proc_name, list_expr) => (proc_name, list_expr),
+            _ => panic!("expected a `MAP` expression. got: `{:?}`", self.expr_ast),
+        }
+    }
+
+    pub fn as_proc_ref_expr(&self) -> &String {
+        match &self.expr_ast {
+            ExpressionAst::ProcRef(proc_name) => proc_name,
+            _ => panic!("expected a `PROC` expression. got: `{:?}`", self.expr_ast),
+        }
+    }
+
+    pub fn as_apply_expr(&self) -> (&Expression, &Vec<Expression>) {
+        match &self.expr_ast {
+            ExpressionAst::Apply(proc_expr, args_exprs) => (proc_expr, args_exprs),
+            _ => panic!("expected an `APPLY` expression. got: `{:?}`", self.expr_ast),
+        }
+    }
+
+    pub fn as_named_arg_expr(&self) -> (&String, &Expression) {
+        match &self.expr_ast {
+            ExpressionAst::NamedArg(param_name, expr) => (param_name, expr),
+            _ => panic!("expected a named arg expression. got: `{:?}`", self.expr_ast),
+        }
+    }
+
+    pub fn as_block_expr(&self) -> &BlockStatement {
+        match &self.expr_ast {
+            ExpressionAst::Block(block_stmt) => block_stmt,
+            _ => panic!("expected a block expression. got: `{:?}`", self.expr_ast),
+        }
+    }
+
+    pub fn as_if_else_expr(&self) -> (&Expression, &Expression, &Expression) {
+        match &self.expr_ast {
+            ExpressionAst::IfElse(cond_expr, true_expr, false_expr) => {
+                (cond_expr, true_expr, false_expr)
+            }
+            _ => panic!("expected an `IFELSE` expression. got: `{:?}`", self.expr_ast),
+        }
+    }
+
     pub fn as_parentheses_expr(&self) -> &Expression {
         match &self.expr_ast {
             ExpressionAst::Parentheses(expr) => expr,
@@ -69,22 +152,16 @@ impl Expression {
         }
     }
 
-    pub fn as_proc_call_expr_mut(
-        &mut self,
-    ) -> (&mut String, &mut Vec<Expression>, &mut Option<SymbolId>) {
+    pub fn as_proc_call_expr_mut(&mut self) -> (&mut String, &mut Vec<Expression>) {
         match &mut self.expr_ast {
-            ExpressionAst::ProcCall(proc_name, proc_args_exprs, proc_id) => {
-                return (proc_name, proc_args_exprs, proc_id);
-            }
+            ExpressionAst::ProcCall(proc_name, proc_args_exprs) => (proc_name, proc_args_exprs),
             _ => panic!("expected a procedure call"),
         }
     }
 
-    pub fn as_proc_call_expr(&self) -> (&String, &Vec<Expression>, Option<&SymbolId>) {
+    pub fn as_proc_call_expr(&self) -> (&String, &Vec<Expression>) {
         match &self.expr_ast {
-            ExpressionAst::ProcCall(proc_name, proc_args_exprs, proc_id) => {
-                (proc_name, proc_args_exprs, proc_id.as_ref())
-            }
+            ExpressionAst::ProcCall(proc_name, proc_args_exprs) => (proc_name, proc_args_exprs),
             _ => panic!(
                 "expected a procedure call expression. got: `{:?}`",
                 self.expr_ast
@@ -1,13 +1,44 @@
-use crate::ast::expression::{BinaryOp, Expression, LiteralExpr};
-use crate::ast::semantic::SymbolId;
+use crate::ast::expression::{BinaryOp, Expression, LiteralExpr, TypeQuery};
+use crate::ast::statement::BlockStatement;
+use crate::compat::prelude::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExpressionAst {
     Literal(LiteralExpr),
-    ProcCall(String, Vec<Expression>, Option<SymbolId>),
+
+    // a call of a procedure, resolved (like `MAP`'s and `PROC`'s one) by the symbol table generator
+    ProcCall(String, Vec<Expression>),
     Binary(BinaryOp, Box<Expression>, Box<Expression>),
     Parentheses(Box<Expression>),
     Not(Box<Expression>),
+    TypeQuery(TypeQuery, Box<Expression>),
+
+    // a list literal (e.g `[1, 2, 3]`)
+    List(Vec<Expression>),
+
+    // `MAP("PROC", LIST)` - the list of the results of calling `PROC` with each element of `LIST`.
+    // the procedure is referenced by a word naming it, and is resolved by the symbol table generator
+    Map(String, Box<Expression>),
+
+    // `PROC("NAME")` - a reference to the procedure named by the word (resolved like `MAP`'s one)
+    ProcRef(String),
+
+    // `APPLY(REF, ARGS...)` - calls the referenced procedure with the args.
+    // the args are checked against the procedure params at runtime, and its return value is discarded
+    Apply(Box<Expression>, Vec<Expression>),
+
+    // `NAME=EXPR` - an arg of a procedure call naming the param it's passed to
+    // (e.g `POLYGON(SIZE=100, N=6)`). the args are reordered by `Procedure::order_args`
+    NamedArg(String, Box<Expression>),
+
+    // `[FORWARD 10 RIGHT 90]` - a block of statements as a value, run using `RUN`.
+    // a bracketed list starting with a statement word (or with a new line) is a block, not a list.
+    // the block statements see the variables of the scope defining it (see `CfgBuilder`)
+    Block(BlockStatement),
+
+    // `IFELSE COND [EXPR1] [EXPR2]` - `EXPR1` when `COND` is true, `EXPR2` otherwise.
+    // both branches are of the same type, and only the chosen one is evaluated
+    IfElse(Box<Expression>, Box<Expression>, Box<Expression>),
 }
 
 #[cfg(test)]
@@ -1,11 +1,26 @@
 use crate::ast::expression::BinaryOp;
+use crate::compat::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum ExpressionType {
     Int,
     Str,
     Bool,
     Unit,
+
+    // a list of values of the element type (written `[INT]`, `[STR]` etc.)
+    List(Box<ExpressionType>),
+
+    // a reference to a procedure (written `PROC`), invoked using `APPLY`
+    Proc,
+
+    // a color (written `COLOR`), e.g `RGB 255 0 0` or `PALETTE(4)`
+    Color,
+
+    // a block of statements (written `BLOCK`), e.g `[FORWARD 10 RIGHT 90]`, run using `RUN`
+    Block,
 }
 
 impl From<&str> for ExpressionType {
@@ -14,11 +29,15 @@ impl From<&str> for ExpressionType {
             "INT" => ExpressionType::Int,
             "STR" => ExpressionType::Str,
             "BOOL" => ExpressionType::Bool,
+            "PROC" => ExpressionType::Proc,
+            "COLOR" => ExpressionType::Color,
+            "BLOCK" => ExpressionType::Block,
             "" | "UNIT" => ExpressionType::Unit,
-            _ => panic!(format!(
-                "Can't convert string `{}` to an expression type",
-                type_str
-            )),
+            _ if type_str.starts_with('[') && type_str.ends_with(']') => {
+                let elem_type = ExpressionType::from(&type_str[1..type_str.len() - 1]);
+                ExpressionType::list_of(elem_type)
+            }
+            _ => panic!("Can't convert string `{}` to an expression type", type_str),
         }
     }
 }
@@ -27,11 +46,76 @@ impl From<&BinaryOp> for ExpressionType {
     fn from(bin_op: &BinaryOp) -> ExpressionType {
         match bin_op {
             BinaryOp::Add | BinaryOp::Mul | BinaryOp::Div => ExpressionType::Int,
-            BinaryOp::GreaterThan | BinaryOp::LessThan => ExpressionType::Bool,
-            _ => panic!(format!(
-                "Can't convert binary operator `{:?}` to an expression type",
-                bin_op
-            )),
+            BinaryOp::And
+            | BinaryOp::Or
+            | BinaryOp::GreaterThan
+            | BinaryOp::LessThan
+            | BinaryOp::Equal
+            | BinaryOp::NotEqual => ExpressionType::Bool,
+        }
+    }
+}
+
+impl ExpressionType {
+    pub fn list_of(elem_type: ExpressionType) -> Self {
+        ExpressionType::List(Box::new(elem_type))
+    }
+
+    // the type of the list elements (`None` for non-list types)
+    pub fn elem_type(&self) -> Option<&ExpressionType> {
+        match self {
+            ExpressionType::List(elem_type) => Some(elem_type),
+            _ => None,
+        }
+    }
+
+    // whether a value of the `actual` type may be passed for a param of this type.
+    // a `UNIT` param (i.e the `FORMAT` rest param items) accepts a value of any type
+    pub fn accepts(&self, actual: &ExpressionType) -> bool {
+        match self {
+            ExpressionType::Unit => *actual != ExpressionType::Unit,
+            _ => self == actual,
+        }
+    }
+
+    // the type keyword (e.g `INT`), as answered by `TYPEOF`
+    pub fn as_keyword(&self) -> &'static str {
+        match self {
+            ExpressionType::Int => "INT",
+            ExpressionType::Str => "STR",
+            ExpressionType::Bool => "BOOL",
+            ExpressionType::Unit => "UNIT",
+            ExpressionType::List(_) => "LIST",
+            ExpressionType::Proc => "PROC",
+            ExpressionType::Color => "COLOR",
+            ExpressionType::Block => "BLOCK",
+        }
+    }
+
+    // whether values of this type can be tested for equality (`=` / `<>`).
+    // values of different types are never comparable, and lists are compared item by item
+    pub fn is_equatable(&self) -> bool {
+        match self {
+            ExpressionType::Int
+            | ExpressionType::Bool
+            | ExpressionType::Str
+            | ExpressionType::Proc
+            | ExpressionType::Color => true,
+            ExpressionType::List(item_type) => item_type.is_equatable(),
+            ExpressionType::Unit | ExpressionType::Block => false,
+        }
+    }
+
+    // whether values of this type can be ordered (`<` / `>`)
+    pub fn is_ordered(&self) -> bool {
+        match self {
+            ExpressionType::Int | ExpressionType::Str => true,
+            ExpressionType::Bool
+            | ExpressionType::Unit
+            | ExpressionType::List(_)
+            | ExpressionType::Proc
+            | ExpressionType::Color
+            | ExpressionType::Block => false,
         }
     }
 }
@@ -43,6 +127,12 @@ impl ToString for ExpressionType {
             ExpressionType::Str => "String",
             ExpressionType::Bool => "Boolean",
             ExpressionType::Unit => "()",
+            ExpressionType::Proc => "Procedure",
+            ExpressionType::Color => "Color",
+            ExpressionType::Block => "Block",
+            ExpressionType::List(ref elem_type) => {
+                return format!("List of {}", elem_type.to_string());
+            }
         };
 
         s.to_string()
@@ -68,12 +158,31 @@ mod tests {
         assert_eq!(ExpressionType::from("BOOL"), ExpressionType::Bool);
     }
 
+    #[test]
+    fn color_to_expr_type() {
+        assert_eq!(ExpressionType::from("COLOR"), ExpressionType::Color);
+    }
+
+    #[test]
+    fn block_to_expr_type() {
+        assert_eq!(ExpressionType::from("BLOCK"), ExpressionType::Block);
+    }
+
     #[test]
     fn unit_to_expr_type() {
         assert_eq!(ExpressionType::from(""), ExpressionType::Unit);
         assert_eq!(ExpressionType::from("UNIT"), ExpressionType::Unit);
     }
 
+    #[test]
+    fn list_to_expr_type() {
+        let expected = ExpressionType::list_of(ExpressionType::Int);
+        assert_eq!(ExpressionType::from("[INT]"), expected);
+
+        let expected = ExpressionType::list_of(ExpressionType::list_of(ExpressionType::Str));
+        assert_eq!(ExpressionType::from("[[STR]]"), expected);
+    }
+
     #[test]
     #[should_panic(expected = "Can't convert string `str` to an expression type")]
     fn invalid_str_to_expr_type_should_panic() {
@@ -130,4 +239,20 @@ mod tests {
     fn expr_type_unit_to_str() {
         assert_eq!("()", ExpressionType::Unit.to_string());
     }
+
+    #[test]
+    fn expr_type_list_to_str() {
+        let list_type = ExpressionType::list_of(ExpressionType::Int);
+        assert_eq!("List of Integer", list_type.to_string());
+    }
+
+    #[test]
+    fn expr_type_accepts() {
+        assert!(ExpressionType::Int.accepts(&ExpressionType::Int));
+        assert!(!ExpressionType::Int.accepts(&ExpressionType::Str));
+
+        assert!(ExpressionType::Unit.accepts(&ExpressionType::Str));
+        assert!(ExpressionType::Unit.accepts(&ExpressionType::list_of(ExpressionType::Int)));
+        assert!(!ExpressionType::Unit.accepts(&ExpressionType::Unit));
+    }
 }
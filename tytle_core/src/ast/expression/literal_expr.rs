@@ -1,9 +1,11 @@
-use crate::ast::semantic::SymbolId;
+use crate::compat::prelude::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum LiteralExpr {
     Bool(bool),
     Int(usize),
     Str(String),
-    Var(String, Option<SymbolId>),
+
+    // a variable, resolved by the symbol table generator (see `Environment::expr_symbols`)
+    Var(String),
 }
@@ -4,6 +4,7 @@ mod expression_ast;
 mod expression_type;
 mod literal_expr;
 mod pprint_ast;
+mod type_query;
 
 pub use binary_op::BinaryOp;
 pub use expression::Expression;
@@ -11,3 +12,4 @@ pub use expression_ast::ExpressionAst;
 pub use expression_type::ExpressionType;
 pub use literal_expr::LiteralExpr;
 pub use pprint_ast::PrettyPrintAst;
+pub use type_query::TypeQuery;
@@ -1,6 +1,7 @@
 use crate::ast::expression::*;
 use crate::ast::statement::*;
 use crate::ast::Ast;
+use crate::compat::prelude::*;
 
 pub struct PrettyPrintAst;
 
@@ -34,15 +35,48 @@ impl PrettyPrintAst {
     fn do_pprint_stmt(buffer: &mut Vec<String>, stmt: &Statement) {
         match stmt {
             Statement::NOP | Statement::EOF => return,
+            Statement::Print(expr) => {
+                buffer.push("PRINT ".to_string());
+                Self::do_pprint_expr(buffer, expr);
+            }
             Statement::Command(cmd) => Self::pp_command(buffer, cmd),
             Statement::Direction(direct_stmt) => Self::pp_direction_stmt(buffer, direct_stmt),
+            Statement::Label(label_stmt) => Self::pp_label_stmt(buffer, label_stmt),
+            Statement::Stream(stream_stmt) => {
+                buffer.push(format!("{} ", stream_stmt.kind.keyword()));
+                Self::do_pprint_expr(buffer, &stream_stmt.expr);
+            }
+            Statement::SetShape(expr) => {
+                buffer.push("SETSHAPE ".to_string());
+                Self::do_pprint_expr(buffer, expr);
+            }
+            Statement::SetPenColor(expr) => {
+                buffer.push("SETPENCOLOR ".to_string());
+                Self::do_pprint_expr(buffer, expr);
+            }
+            Statement::Tone(tone_stmt) => {
+                buffer.push("TONE ".to_string());
+                Self::do_pprint_expr(buffer, &tone_stmt.freq_expr);
+                buffer.push(" ".to_string());
+                Self::do_pprint_expr(buffer, &tone_stmt.duration_expr);
+            }
+            Statement::Run(expr) => {
+                buffer.push("RUN ".to_string());
+                Self::do_pprint_expr(buffer, expr);
+            }
             Statement::Make(make_stmt) => Self::pp_make_stmt(buffer, make_stmt),
             Statement::If(if_stmt) => Self::pp_if_stmt(buffer, if_stmt),
             Statement::Repeat(repeat_stmt) => Self::pp_repeat_stmt(buffer, repeat_stmt),
+            Statement::Foreach(foreach_stmt) => Self::pp_foreach_stmt(buffer, foreach_stmt),
             Statement::Procedure(proc_stmt) => Self::pp_proc_stmt(buffer, proc_stmt),
+            Statement::OnEvent(on_event_stmt) => Self::pp_on_event_stmt(buffer, on_event_stmt),
+            Statement::Catch(catch_stmt) => Self::pp_catch_stmt(buffer, catch_stmt),
+            Statement::Throw(tag) => buffer.push(format!("THROW \"{}", tag)),
+            Statement::Assert(assert_stmt) => Self::pp_assert_stmt(buffer, assert_stmt),
+            Statement::Trace(trace_stmt) => Self::pp_trace_stmt(buffer, trace_stmt),
             Statement::Return(ret_stmt) => Self::pp_ret_stmt(buffer, ret_stmt),
             Statement::Expression(expr) => Self::do_pprint_expr(buffer, expr),
-            _ => unimplemented!(),
+            Statement::Load(path) => buffer.push(format!("LOAD \"{}", path)),
         };
     }
 
@@ -50,9 +84,17 @@ impl PrettyPrintAst {
         match expr.expr_ast {
             ExpressionAst::Literal(ref lit_expr) => Self::pp_lit_expr(buffer, &lit_expr),
             ExpressionAst::Binary(_, _, _) => Self::pp_binary_expr(buffer, expr),
-            ExpressionAst::ProcCall(_, _, _) => Self::pp_proc_call_expr(buffer, expr),
+            ExpressionAst::ProcCall(_, _) => Self::pp_proc_call_expr(buffer, expr),
             ExpressionAst::Not(_) => Self::pp_not_expr(buffer, expr),
+            ExpressionAst::TypeQuery(..) => Self::pp_type_query_expr(buffer, expr),
             ExpressionAst::Parentheses(_) => Self::pp_parentheses_expr(buffer, expr),
+            ExpressionAst::List(_) => Self::pp_list_expr(buffer, expr),
+            ExpressionAst::Map(..) => Self::pp_map_expr(buffer, expr),
+            ExpressionAst::ProcRef(..) => Self::pp_proc_ref_expr(buffer, expr),
+            ExpressionAst::Apply(..) => Self::pp_apply_expr(buffer, expr),
+            ExpressionAst::NamedArg(..) => Self::pp_named_arg_expr(buffer, expr),
+            ExpressionAst::Block(ref block_stmt) => Self::pp_block_expr(buffer, block_stmt),
+            ExpressionAst::IfElse(..) => Self::pp_if_else_expr(buffer, expr),
         };
     }
 
@@ -62,7 +104,7 @@ impl PrettyPrintAst {
             LiteralExpr::Bool(false) => buffer.push("FALSE".to_string()),
             LiteralExpr::Int(num) => buffer.push(num.to_string()),
             LiteralExpr::Str(s) => buffer.push(format!("\"{}\"", s)),
-            LiteralExpr::Var(v, _id) => buffer.push(v.clone()),
+            LiteralExpr::Var(v) => buffer.push(v.clone()),
         }
     }
 
@@ -74,6 +116,14 @@ impl PrettyPrintAst {
         Self::do_pprint_expr(buffer, expr);
     }
 
+    fn pp_type_query_expr(buffer: &mut Vec<String>, query_expr: &Expression) {
+        let (query, expr) = query_expr.as_type_query_expr();
+
+        buffer.push(format!("{} ", query));
+
+        Self::do_pprint_expr(buffer, expr);
+    }
+
     fn pp_binary_expr(buffer: &mut Vec<String>, bin_expr: &Expression) {
         let (binary_op, lexpr, rexpr) = bin_expr.as_binary_expr();
 
@@ -83,23 +133,99 @@ impl PrettyPrintAst {
     }
 
     fn pp_proc_call_expr(buffer: &mut Vec<String>, proc_call_expr: &Expression) {
-        let (proc_name, proc_args, _proc_id) = proc_call_expr.as_proc_call_expr();
+        let (proc_name, proc_args) = proc_call_expr.as_proc_call_expr();
+
+        // `RGB r g b` (see `Primitive::Rgb`), `STORE "name <value>` and `RETRIEVE "name`
+        if ["RGB", "STORE", "RETRIEVE"].contains(&proc_name.as_str()) {
+            buffer.push(proc_name.to_string());
+
+            for arg in proc_args.iter() {
+                buffer.push(" ".to_string());
+                Self::do_pprint_expr(buffer, arg);
+            }
+
+            return;
+        }
 
         buffer.push(format!("{}(", proc_name));
 
-        let mut i = 0;
-        while i < proc_args.len() - 1 {
-            Self::do_pprint_expr(buffer, &proc_args[i]);
-            buffer.push(", ".to_string());
+        for (i, arg) in proc_args.iter().enumerate() {
+            if i > 0 {
+                buffer.push(", ".to_string());
+            }
 
-            i += 1
+            Self::do_pprint_expr(buffer, arg);
         }
 
-        Self::do_pprint_expr(buffer, &proc_args[i]);
+        buffer.push(")".to_string());
+    }
+
+    fn pp_list_expr(buffer: &mut Vec<String>, list_expr: &Expression) {
+        let items = list_expr.as_list_expr();
+
+        buffer.push("[".to_string());
+
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                buffer.push(", ".to_string());
+            }
+
+            Self::do_pprint_expr(buffer, item);
+        }
+
+        buffer.push("]".to_string());
+    }
+
+    fn pp_map_expr(buffer: &mut Vec<String>, map_expr: &Expression) {
+        let (proc_name, list_expr) = map_expr.as_map_expr();
 
+        buffer.push(format!("MAP(\"{}\", ", proc_name));
+        Self::do_pprint_expr(buffer, list_expr);
         buffer.push(")".to_string());
     }
 
+    fn pp_proc_ref_expr(buffer: &mut Vec<String>, proc_ref_expr: &Expression) {
+        let proc_name = proc_ref_expr.as_proc_ref_expr();
+
+        buffer.push(format!("PROC(\"{}\")", proc_name));
+    }
+
+    fn pp_apply_expr(buffer: &mut Vec<String>, apply_expr: &Expression) {
+        let (proc_expr, args_exprs) = apply_expr.as_apply_expr();
+
+        buffer.push("APPLY(".to_string());
+        Self::do_pprint_expr(buffer, proc_expr);
+
+        for arg_expr in args_exprs {
+            buffer.push(", ".to_string());
+            Self::do_pprint_expr(buffer, arg_expr);
+        }
+
+        buffer.push(")".to_string());
+    }
+
+    fn pp_if_else_expr(buffer: &mut Vec<String>, expr: &Expression) {
+        let (cond_expr, true_expr, false_expr) = expr.as_if_else_expr();
+
+        buffer.push("IFELSE ".to_string());
+        Self::do_pprint_expr(buffer, cond_expr);
+
+        buffer.push(" [".to_string());
+        Self::do_pprint_expr(buffer, true_expr);
+
+        buffer.push("] [".to_string());
+        Self::do_pprint_expr(buffer, false_expr);
+
+        buffer.push("]".to_string());
+    }
+
+    fn pp_named_arg_expr(buffer: &mut Vec<String>, expr: &Expression) {
+        let (param_name, inner_expr) = expr.as_named_arg_expr();
+
+        buffer.push(format!("{}=", param_name));
+        Self::do_pprint_expr(buffer, inner_expr);
+    }
+
     fn pp_parentheses_expr(buffer: &mut Vec<String>, expr: &Expression) {
         let inner_expr = expr.as_parentheses_expr();
 
@@ -114,22 +240,57 @@ impl PrettyPrintAst {
             BinaryOp::Or => " OR ",
             BinaryOp::Add => " + ",
             BinaryOp::Mul => " * ",
+            BinaryOp::Div => " / ",
             BinaryOp::GreaterThan => " > ",
             BinaryOp::LessThan => " < ",
-            _ => unimplemented!(),
+            BinaryOp::Equal => " = ",
+            BinaryOp::NotEqual => " <> ",
+        };
+
+        buffer.push(s.to_string());
+    }
+
+    fn pp_command(buffer: &mut Vec<String>, cmd: &Command) {
+        let s = match cmd {
+            Command::XCor => "XCOR",
+            Command::YCor => "YCOR",
+            Command::PenUp => "PENUP",
+            Command::PenDown => "PENDOWN",
+            Command::PenErase => "PENERASE",
+            Command::ShowTurtle => "SHOWTURTLE",
+            Command::HideTurtle => "HIDETURTLE",
+            Command::Clean => "CLEAN",
+            Command::ClearScreen => "CLEARSCREEN",
+            Command::SetBackgroundColor => "SETBACKGROUND",
+            Command::Wait => "WAIT",
+            Command::Stop => "STOP",
+            Command::Trap => "TRAP",
+            Command::Stamp => "STAMP",
+            Command::Beep => "BEEP",
+            Command::PushState => "PUSHSTATE",
+            Command::PopState => "POPSTATE",
         };
 
         buffer.push(s.to_string());
     }
 
-    fn pp_command(_buffer: &mut Vec<String>, _cmd: &Command) {
-        unimplemented!()
-        // TODO: command into string
+    fn pp_direction_stmt(buffer: &mut Vec<String>, direct_stmt: &DirectionStmt) {
+        let s = match direct_stmt.direction {
+            Direction::Forward => "FORWARD ",
+            Direction::Backward => "BACKWARD ",
+            Direction::Left => "LEFT ",
+            Direction::Right => "RIGHT ",
+            Direction::SetX => "SETX ",
+            Direction::SetY => "SETY ",
+        };
+
+        buffer.push(s.to_string());
+        Self::do_pprint_expr(buffer, &direct_stmt.expr);
     }
 
-    fn pp_direction_stmt(_buffer: &mut Vec<String>, _direct_stmt: &DirectionStmt) {
-        unimplemented!()
-        // TODO: direction into string
+    fn pp_label_stmt(buffer: &mut Vec<String>, label_stmt: &LabelStmt) {
+        buffer.push(format!("{} ", label_stmt.kind.keyword()));
+        Self::do_pprint_expr(buffer, &label_stmt.expr);
     }
 
     fn pp_make_stmt(buffer: &mut Vec<String>, make_stmt: &MakeStmt) {
@@ -137,6 +298,7 @@ impl PrettyPrintAst {
             MakeStmtKind::Global => "MAKEGLOBAL ",
             MakeStmtKind::Local => "MAKELOCAL ",
             MakeStmtKind::Assign => "MAKE ",
+            MakeStmtKind::Const => "CONST ",
         };
 
         buffer.push(format!("{} {} = ", kind_str, make_stmt.var_name));
@@ -159,12 +321,86 @@ impl PrettyPrintAst {
         Self::pp_block_stmt(buffer, &repeat_stmt.block);
     }
 
-    fn pp_proc_stmt(_buffer: &mut Vec<String>, _proc_stmt: &ProcedureStmt) {
-        unimplemented!()
+    fn pp_foreach_stmt(buffer: &mut Vec<String>, foreach_stmt: &ForeachStmt) {
+        buffer.push("FOREACH ".to_string());
+        Self::do_pprint_expr(buffer, &foreach_stmt.list_expr);
+
+        buffer.push("\n".to_string());
+        Self::pp_block_stmt(buffer, &foreach_stmt.block);
+    }
+
+    fn pp_proc_stmt(buffer: &mut Vec<String>, proc_stmt: &ProcedureStmt) {
+        let params = proc_stmt
+            .params
+            .iter()
+            .map(|param| param.signature())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match proc_stmt.return_type.as_str() {
+            "UNIT" => buffer.push(format!("TO {}({})\n", proc_stmt.name, params)),
+            ret_type => buffer.push(format!("TO {}({}): {}\n", proc_stmt.name, params, ret_type)),
+        }
+
+        for stmt in &proc_stmt.block.stmts {
+            let stmt_str: String = Self::pprint_stmt(stmt);
+            buffer.push(format!("   {}\n", stmt_str));
+        }
+
+        buffer.push("END".to_string());
     }
 
-    fn pp_ret_stmt(_buffer: &mut Vec<String>, _ret_stmt: &ReturnStmt) {
-        unimplemented!()
+    fn pp_on_event_stmt(buffer: &mut Vec<String>, on_event_stmt: &OnEventStmt) {
+        buffer.push(on_event_stmt.event.to_string());
+
+        buffer.push("\n".to_string());
+        Self::pp_block_stmt(buffer, &on_event_stmt.proc_stmt.block);
+    }
+
+    fn pp_catch_stmt(buffer: &mut Vec<String>, catch_stmt: &CatchStmt) {
+        buffer.push(format!("CATCH \"{}", catch_stmt.tag));
+
+        buffer.push("\n".to_string());
+        Self::pp_block_stmt(buffer, &catch_stmt.block);
+    }
+
+    fn pp_assert_stmt(buffer: &mut Vec<String>, assert_stmt: &AssertStmt) {
+        buffer.push("ASSERT ".to_string());
+        Self::do_pprint_expr(buffer, &assert_stmt.cond_expr);
+
+        if let Some(message) = &assert_stmt.message {
+            buffer.push(format!(" \"{}\"", message));
+        }
+    }
+
+    fn pp_trace_stmt(buffer: &mut Vec<String>, trace_stmt: &TraceStmt) {
+        let keyword = match trace_stmt.enabled {
+            true => "TRACE",
+            false => "UNTRACE",
+        };
+
+        buffer.push(format!("{} \"{}", keyword, trace_stmt.proc_name));
+    }
+
+    fn pp_ret_stmt(buffer: &mut Vec<String>, ret_stmt: &ReturnStmt) {
+        match &ret_stmt.expr {
+            Some(expr) => {
+                buffer.push("RETURN ".to_string());
+                Self::do_pprint_expr(buffer, expr);
+            }
+            None => buffer.push("RETURN".to_string()),
+        }
+    }
+
+    // a block expression is printed on a single line (e.g `[FORWARD 10 RIGHT 90]`)
+    fn pp_block_expr(buffer: &mut Vec<String>, block_stmt: &BlockStatement) {
+        let stmts = block_stmt
+            .stmts
+            .iter()
+            .map(Self::pprint_stmt)
+            .collect::<Vec<_>>();
+
+        buffer.push(format!("[{}]", stmts.join(" ")));
     }
 
     fn pp_block_stmt(buffer: &mut Vec<String>, block_stmt: &BlockStatement) {
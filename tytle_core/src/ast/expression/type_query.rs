@@ -0,0 +1,109 @@
+use crate::ast::expression::{ExpressionType, LiteralExpr};
+use crate::compat::prelude::*;
+use core::fmt;
+
+// the type reflection built-ins (e.g `TYPEOF X` or `NUMBERP X`).
+// since tytle is statically typed, a query is answered from its operand type
+// and folded into a literal by the type checker (so the operand itself is never evaluated).
+// `EMPTYP` of a string is the exception, requiring the string to be known at compile time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TypeQuery {
+    TypeOf,
+    NumberP,
+    WordP,
+    ListP,
+    EmptyP,
+}
+
+impl TypeQuery {
+    pub fn from_keyword(word: &str) -> Option<TypeQuery> {
+        let query = match word {
+            "TYPEOF" => TypeQuery::TypeOf,
+            "NUMBERP" => TypeQuery::NumberP,
+            "WORDP" => TypeQuery::WordP,
+            "LISTP" => TypeQuery::ListP,
+            "EMPTYP" => TypeQuery::EmptyP,
+            _ => return None,
+        };
+
+        Some(query)
+    }
+
+    pub fn result_type(&self) -> ExpressionType {
+        match self {
+            TypeQuery::TypeOf => ExpressionType::Str,
+            _ => ExpressionType::Bool,
+        }
+    }
+
+    // answers the query for an operand of type `operand_type`.
+    // `operand_str` is the operand value when it's a string known in advance (required only by `EMPTYP`).
+    //
+    // a word is a string (unlike UCBLogo, numbers aren't words).
+    // lists are never empty (there are no empty list literals), so `EMPTYP` of a list is always `FALSE`
+    pub fn eval(
+        &self,
+        operand_type: &ExpressionType,
+        operand_str: Option<&str>,
+    ) -> Option<LiteralExpr> {
+        let v = match self {
+            TypeQuery::TypeOf => {
+                return Some(LiteralExpr::Str(operand_type.as_keyword().to_string()));
+            }
+            TypeQuery::NumberP => *operand_type == ExpressionType::Int,
+            TypeQuery::WordP => *operand_type == ExpressionType::Str,
+            TypeQuery::ListP => operand_type.elem_type().is_some(),
+            TypeQuery::EmptyP => match operand_type {
+                ExpressionType::Str => operand_str?.is_empty(),
+                _ => false,
+            },
+        };
+
+        Some(LiteralExpr::Bool(v))
+    }
+}
+
+impl fmt::Display for TypeQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            TypeQuery::TypeOf => "TYPEOF",
+            TypeQuery::NumberP => "NUMBERP",
+            TypeQuery::WordP => "WORDP",
+            TypeQuery::ListP => "LISTP",
+            TypeQuery::EmptyP => "EMPTYP",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_query_from_keyword() {
+        assert_eq!(Some(TypeQuery::TypeOf), TypeQuery::from_keyword("TYPEOF"));
+        assert_eq!(Some(TypeQuery::EmptyP), TypeQuery::from_keyword("EMPTYP"));
+        assert_eq!(None, TypeQuery::from_keyword("FORWARD"));
+        assert_eq!("WORDP", TypeQuery::WordP.to_string());
+    }
+
+    #[test]
+    fn type_query_eval() {
+        let typeof_int = TypeQuery::TypeOf.eval(&ExpressionType::Int, None);
+        assert_eq!(Some(LiteralExpr::Str("INT".to_string())), typeof_int);
+
+        let numberp_bool = TypeQuery::NumberP.eval(&ExpressionType::Bool, None);
+        assert_eq!(Some(LiteralExpr::Bool(false)), numberp_bool);
+
+        let emptyp_str = TypeQuery::EmptyP.eval(&ExpressionType::Str, Some(""));
+        assert_eq!(Some(LiteralExpr::Bool(true)), emptyp_str);
+
+        let listp_list = TypeQuery::ListP.eval(&ExpressionType::from("[INT]"), None);
+        assert_eq!(Some(LiteralExpr::Bool(true)), listp_list);
+
+        // the string isn't known in advance
+        assert_eq!(None, TypeQuery::EmptyP.eval(&ExpressionType::Str, None));
+    }
+}
@@ -56,7 +56,7 @@ macro_rules! var_lit_expr {
     ($s:expr, parens: $parens:expr) => {{
         use $crate::ast::expression::{Expression, ExpressionAst, LiteralExpr};
 
-        let ast = ExpressionAst::Literal(LiteralExpr::Var($s.to_string(), None));
+        let ast = ExpressionAst::Literal(LiteralExpr::Var($s.to_string()));
         Expression::adjust_parentheses(ast, $parens)
     }};
 }
@@ -181,6 +181,14 @@ macro_rules! make_local_stmt {
     }};
 }
 
+#[macro_export]
+macro_rules! make_const_stmt {
+    ($var_name:expr, $expr:expr) => {{
+        use $crate::ast::statement::MakeStmtKind;
+        __make_stmt!(MakeStmtKind::Const, $var_name.to_string(), $expr)
+    }};
+}
+
 #[macro_export]
 macro_rules! make_stmt {
     ($var_name:expr, $expr:expr) => {{
@@ -221,6 +229,17 @@ macro_rules! not_expr {
     }};
 }
 
+#[macro_export]
+macro_rules! type_query_expr {
+    ($keyword:expr, $expr:expr) => {{
+        use $crate::ast::expression::{Expression, ExpressionAst, TypeQuery};
+
+        let query = TypeQuery::from_keyword($keyword).unwrap();
+        let ast = ExpressionAst::TypeQuery(query, Box::new($expr));
+        Expression::new(ast)
+    }};
+}
+
 #[macro_export]
 macro_rules! binary_expr {
     ($op_str:expr, $lexpr:expr, $rexpr:expr) => {{
@@ -266,6 +285,7 @@ macro_rules! proc_stmt {
             name: $proc_name.to_string(),
             return_type,
             block: block_stmt,
+            doc: None,
         });
 
         proc_stmt
@@ -327,6 +347,19 @@ macro_rules! repeat_stmt {
     }};
 }
 
+#[macro_export]
+macro_rules! foreach_stmt {
+    ($list:expr, $block:expr) => {{
+        use $crate::ast::statement::{ForeachStmt, Statement};
+
+        Statement::Foreach(ForeachStmt {
+            list_expr: $list,
+            block: $block,
+            var_id: None,
+        })
+    }};
+}
+
 #[macro_export]
 macro_rules! ast {
     ($ ($stmt:expr) ,*) => {
@@ -371,7 +404,7 @@ macro_rules! proc_call_expr {
             let mut params = Vec::<Expression>::new();
             $( params.push($param); )*
 
-            let ast = ExpressionAst::ProcCall($proc_name.to_string(), params, None);
+            let ast = ExpressionAst::ProcCall($proc_name.to_string(), params);
             Expression::new(ast)
         }
     };
@@ -385,6 +418,7 @@ macro_rules! proc_param {
         ProcParam {
             param_name: $pname.to_string(),
             param_type: $ptype.to_string(),
+            rest: false,
         }
     }};
 }
@@ -1,19 +1,44 @@
+pub(crate) mod annotation;
 pub mod expression;
-pub mod macros;
+mod macros;
+pub mod program_builder;
 pub mod semantic;
 pub mod statement;
 
 use crate::ast::statement::*;
+use crate::compat::prelude::*;
+use crate::lexer::Location;
 
-#[derive(Debug, Clone, PartialEq)]
+pub use annotation::{AnnotationMap, NodeId};
+pub use program_builder::ProgramBuilder;
+
+#[derive(Debug, Clone)]
 pub struct Ast {
     pub statements: Vec<Statement>,
+
+    // the location of each parsed statement (by its index within `statements`),
+    // missing for the statements which weren't parsed from the source code (e.g the stdlib prelude)
+    pub locations: Vec<Option<Location>>,
 }
 
 impl Default for Ast {
     fn default() -> Self {
         Self {
             statements: Default::default(),
+            locations: Default::default(),
         }
     }
 }
+
+// the locations are a debugging aid, so programs having the same statements are equal
+impl PartialEq for Ast {
+    fn eq(&self, other: &Self) -> bool {
+        self.statements == other.statements
+    }
+}
+
+impl Ast {
+    pub fn stmt_location(&self, index: usize) -> Option<Location> {
+        self.locations.get(index).copied().flatten()
+    }
+}
@@ -0,0 +1,263 @@
+use crate::ast::expression::*;
+use crate::ast::statement::*;
+use crate::ast::Ast;
+use crate::compat::prelude::*;
+
+// builds a tytle `Ast` programmatically (without generating and parsing source code).
+//
+// ```
+// let ast = ProgramBuilder::new()
+//     .repeat(4, |b| b.forward(100).right(90))
+//     .build();
+// ```
+//
+// the built `Ast` is identical to the one `TytleParser` outputs for the equivalent source code,
+// so it should go through the same symbols-generation & type-checking process.
+// expressions are passed as anything convertible into an `Expression` (integers, booleans)
+// or built with the expression helpers below (`var`, `call`, `binary`, `not`, `parens`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramBuilder {
+    stmts: Vec<Statement>,
+}
+
+impl Default for ProgramBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgramBuilder {
+    pub fn new() -> Self {
+        Self { stmts: Vec::new() }
+    }
+
+    pub fn build(self) -> Ast {
+        let mut ast = Ast {
+            statements: self.stmts,
+            locations: Vec::new(),
+        };
+
+        // keeping the same invariant as `TytleParser` (an empty program consists of a single `EOF`)
+        if ast.statements.is_empty() {
+            ast.statements.push(Statement::EOF);
+        }
+
+        ast
+    }
+
+    pub fn stmt(mut self, stmt: Statement) -> Self {
+        self.stmts.push(stmt);
+        self
+    }
+
+    // directions
+    pub fn direction(self, direction: Direction, expr: impl Into<Expression>) -> Self {
+        let direct_stmt = DirectionStmt {
+            direction,
+            expr: expr.into(),
+        };
+
+        self.stmt(Statement::Direction(direct_stmt))
+    }
+
+    pub fn forward(self, expr: impl Into<Expression>) -> Self {
+        self.direction(Direction::Forward, expr)
+    }
+
+    pub fn backward(self, expr: impl Into<Expression>) -> Self {
+        self.direction(Direction::Backward, expr)
+    }
+
+    pub fn left(self, expr: impl Into<Expression>) -> Self {
+        self.direction(Direction::Left, expr)
+    }
+
+    pub fn right(self, expr: impl Into<Expression>) -> Self {
+        self.direction(Direction::Right, expr)
+    }
+
+    pub fn setx(self, expr: impl Into<Expression>) -> Self {
+        self.direction(Direction::SetX, expr)
+    }
+
+    pub fn sety(self, expr: impl Into<Expression>) -> Self {
+        self.direction(Direction::SetY, expr)
+    }
+
+    // commands
+    pub fn command(self, cmd: Command) -> Self {
+        self.stmt(Statement::Command(cmd))
+    }
+
+    pub fn pen_up(self) -> Self {
+        self.command(Command::PenUp)
+    }
+
+    pub fn pen_down(self) -> Self {
+        self.command(Command::PenDown)
+    }
+
+    pub fn print(self, expr: impl Into<Expression>) -> Self {
+        self.stmt(Statement::Print(expr.into()))
+    }
+
+    // variables
+    pub fn make_global(self, var_name: &str, expr: impl Into<Expression>) -> Self {
+        self.make_stmt(MakeStmtKind::Global, var_name, expr)
+    }
+
+    pub fn make_local(self, var_name: &str, expr: impl Into<Expression>) -> Self {
+        self.make_stmt(MakeStmtKind::Local, var_name, expr)
+    }
+
+    pub fn make(self, var_name: &str, expr: impl Into<Expression>) -> Self {
+        self.make_stmt(MakeStmtKind::Assign, var_name, expr)
+    }
+
+    pub fn make_const(self, var_name: &str, expr: impl Into<Expression>) -> Self {
+        self.make_stmt(MakeStmtKind::Const, var_name, expr)
+    }
+
+    fn make_stmt(self, kind: MakeStmtKind, var_name: &str, expr: impl Into<Expression>) -> Self {
+        let make_stmt = MakeStmt {
+            kind,
+            var_id: None,
+            var_name: var_name.to_string(),
+            expr: expr.into(),
+        };
+
+        self.stmt(Statement::Make(make_stmt))
+    }
+
+    // control-flow
+    pub fn repeat<F>(self, count: impl Into<Expression>, block: F) -> Self
+    where
+        F: FnOnce(ProgramBuilder) -> ProgramBuilder,
+    {
+        let repeat_stmt = RepeatStmt {
+            count_expr: count.into(),
+            block: Self::build_block(block),
+        };
+
+        self.stmt(Statement::Repeat(repeat_stmt))
+    }
+
+    pub fn if_then<F>(self, cond: impl Into<Expression>, true_block: F) -> Self
+    where
+        F: FnOnce(ProgramBuilder) -> ProgramBuilder,
+    {
+        let if_stmt = IfStmt {
+            cond_expr: cond.into(),
+            true_block: Self::build_block(true_block),
+            false_block: None,
+        };
+
+        self.stmt(Statement::If(if_stmt))
+    }
+
+    pub fn if_else<F, G>(self, cond: impl Into<Expression>, true_block: F, false_block: G) -> Self
+    where
+        F: FnOnce(ProgramBuilder) -> ProgramBuilder,
+        G: FnOnce(ProgramBuilder) -> ProgramBuilder,
+    {
+        let if_stmt = IfStmt {
+            cond_expr: cond.into(),
+            true_block: Self::build_block(true_block),
+            false_block: Some(Self::build_block(false_block)),
+        };
+
+        self.stmt(Statement::If(if_stmt))
+    }
+
+    // procedures.
+    // `params` are pairs of `(param name, param type)` and types are given as in the source code
+    // (`INT`, `BOOL`, ...). a procedure with no return value has a `UNIT` return type
+    pub fn procedure<F>(
+        self,
+        name: &str,
+        params: &[(&str, &str)],
+        return_type: &str,
+        block: F,
+    ) -> Self
+    where
+        F: FnOnce(ProgramBuilder) -> ProgramBuilder,
+    {
+        let params = params
+            .iter()
+            .map(|(param_name, param_type)| ProcParam {
+                param_name: param_name.to_string(),
+                param_type: param_type.to_string(),
+                rest: false,
+            })
+            .collect();
+
+        let proc_stmt = ProcedureStmt {
+            id: None,
+            name: name.to_string(),
+            params,
+            return_type: return_type.to_string(),
+            block: Self::build_block(block),
+            doc: None,
+        };
+
+        self.stmt(Statement::Procedure(proc_stmt))
+    }
+
+    pub fn call_proc(self, proc_name: &str, args: Vec<Expression>) -> Self {
+        self.stmt(Statement::Expression(call(proc_name, args)))
+    }
+
+    pub fn ret(self, expr: impl Into<Expression>) -> Self {
+        self.stmt(Statement::Return(ReturnStmt::new(Some(expr.into()))))
+    }
+
+    fn build_block<F>(block: F) -> BlockStatement
+    where
+        F: FnOnce(ProgramBuilder) -> ProgramBuilder,
+    {
+        let builder = block(ProgramBuilder::new());
+
+        let mut block_stmt = BlockStatement::new();
+
+        for stmt in builder.stmts {
+            block_stmt.add_statement(stmt);
+        }
+
+        block_stmt
+    }
+}
+
+// expression helpers
+pub fn var(var_name: &str) -> Expression {
+    let ast = ExpressionAst::Literal(LiteralExpr::Var(var_name.to_string()));
+    Expression::new(ast)
+}
+
+pub fn call(proc_name: &str, args: Vec<Expression>) -> Expression {
+    let ast = ExpressionAst::ProcCall(proc_name.to_string(), args);
+    Expression::new(ast)
+}
+
+pub fn binary(
+    bin_op: BinaryOp,
+    lexpr: impl Into<Expression>,
+    rexpr: impl Into<Expression>,
+) -> Expression {
+    let ast = ExpressionAst::Binary(bin_op, Box::new(lexpr.into()), Box::new(rexpr.into()));
+    Expression::new(ast)
+}
+
+pub fn not(expr: impl Into<Expression>) -> Expression {
+    let ast = ExpressionAst::Not(Box::new(expr.into()));
+    Expression::new(ast)
+}
+
+pub fn type_query(query: TypeQuery, expr: impl Into<Expression>) -> Expression {
+    let ast = ExpressionAst::TypeQuery(query, Box::new(expr.into()));
+    Expression::new(ast)
+}
+
+pub fn parens(expr: impl Into<Expression>) -> Expression {
+    let ast = ExpressionAst::Parentheses(Box::new(expr.into()));
+    Expression::new(ast)
+}
@@ -1,14 +1,18 @@
 use crate::ast::semantic::*;
 use crate::ast::Ast;
 use crate::ast::{expression::*, statement::*};
+use crate::compat::prelude::*;
 
 pub struct AstTypeCheck<'env> {
     env: &'env mut Environment,
+
+    // the procedure being checked (`None` under `__main__`, see `SymbolTableGenerator`)
+    proc_id: Option<SymbolId>,
 }
 
 impl<'env> AstTypeCheck<'env> {
     pub fn new(env: &'env mut Environment) -> Self {
-        Self { env }
+        Self { env, proc_id: None }
     }
 
     pub fn check(&mut self, ast: &mut Ast) -> AstWalkResult {
@@ -24,21 +28,19 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
             LiteralExpr::Bool(_) => ExpressionType::Bool,
             LiteralExpr::Int(_) => ExpressionType::Int,
             LiteralExpr::Str(_) => ExpressionType::Str,
-            LiteralExpr::Var(var_name, var_id) => {
-                let var = self.env.symbol_table.get_var_by_id(var_id.unwrap());
+            LiteralExpr::Var(var_name) => {
+                let var = self.env.symbol_table.get_var_by_id(self.env.expr_symbol(expr));
 
                 if let Some(ref var_type) = var.var_type {
                     var_type.to_owned()
                 } else {
-                    panic!(format!(
-                        "Variable `{}`, type couldn't be inferred",
-                        var_name
-                    ))
+                    // the variable is used before its type is known (e.g `MAKEGLOBAL X = X`)
+                    return Err(AstWalkError::VariableTypeMissing(var_name.to_string()));
                 }
             }
         };
 
-        expr.expr_type = Some(expr_type);
+        self.env.expr_types.insert(expr.node_id, expr_type);
 
         Ok(())
     }
@@ -47,7 +49,8 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
         let inner_expr = expr.as_parentheses_expr();
 
         // we copy the inner expresison to the outer parentheses expression
-        expr.expr_type = inner_expr.expr_type.clone();
+        let inner_type = self.env.expr_type(inner_expr).clone();
+        self.env.expr_types.insert(expr.node_id, inner_type);
 
         Ok(())
     }
@@ -55,27 +58,45 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
     fn on_not_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
         let inner_expr = expr.as_not_expr();
 
-        if inner_expr.expr_type != Some(ExpressionType::Bool) {
+        if *self.env.expr_type(inner_expr) != ExpressionType::Bool {
             let expr_str = PrettyPrintAst::pprint_expr(inner_expr);
             let err = AstWalkError::NotBooleanExpr(expr_str);
             return Err(err);
         }
 
-        expr.expr_type = Some(ExpressionType::Bool);
+        self.env.expr_types.insert(expr.node_id, ExpressionType::Bool);
 
         Ok(())
     }
 
     fn on_proc_call_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
-        let (proc_name, proc_args_exprs, _proc_id) = expr.as_proc_call_expr();
+        let (proc_name, proc_args_exprs) = expr.as_proc_call_expr();
 
-        let proc = self.env.symbol_table.get_proc_by_name(proc_name);
+        let proc = self.env.symbol_table.get_proc_by_id(self.env.expr_symbol(expr));
+        let proc_args_exprs = proc.order_args(proc_args_exprs)?;
 
-        let expected_params_types = proc.params_types.clone();
-        let expected_args_count = expected_params_types.len();
+        let mut expected_params_types = proc.params_types.clone();
+        let mut expected_args_count = expected_params_types.len();
         let actual_args_count = proc_args_exprs.len();
 
-        if expected_args_count != actual_args_count {
+        if proc.rest {
+            // the args past the other params are the items of the rest param list
+            // (collected at the call site, see `CfgBuilder`)
+            let rest_type = expected_params_types.pop().unwrap();
+
+            if actual_args_count < expected_params_types.len() {
+                let err = AstWalkError::TooFewProcCallArgs(
+                    proc_name.clone(),
+                    expected_params_types.len(),
+                    actual_args_count,
+                );
+                return Err(err);
+            }
+
+            let elem_type = rest_type.elem_type().unwrap().clone();
+            expected_params_types.resize(actual_args_count, elem_type);
+            expected_args_count = actual_args_count;
+        } else if expected_args_count != actual_args_count {
             let err = AstWalkError::InvalidProcCallArgsCount(
                 proc_name.clone(),
                 expected_args_count,
@@ -91,10 +112,17 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
         while arg_pos <= expected_args_count {
             let arg_expr: &Expression = actual_iter.next().unwrap();
 
-            let actual_type: ExpressionType = arg_expr.expr_type.clone().unwrap();
+            let actual_type: ExpressionType = self.env.expr_type(arg_expr).clone();
             let expected_type: &ExpressionType = expected_iter.next().unwrap();
 
-            if *expected_type != actual_type {
+            // an arg of any type (see `ExpressionType::accepts`) must still have a value
+            if *expected_type == ExpressionType::Unit && actual_type == ExpressionType::Unit {
+                let expr_str = PrettyPrintAst::pprint_expr(arg_expr);
+                let err = AstWalkError::NotValueExpr(expr_str);
+                return Err(err);
+            }
+
+            if !expected_type.accepts(&actual_type) {
                 let err = AstWalkError::InvalidProcCallArgType(
                     arg_pos,
                     expected_type.clone(),
@@ -106,7 +134,8 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
             arg_pos += 1;
         }
 
-        expr.expr_type = Some(proc.return_type.clone());
+        let return_type = proc.return_type.clone();
+        self.env.expr_types.insert(expr.node_id, return_type);
 
         Ok(())
     }
@@ -116,7 +145,165 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
 
         self.do_binary_expr_typecheck(bin_op, lexpr, rexpr)?;
 
-        expr.expr_type = Some(ExpressionType::from(bin_op));
+        self.env.expr_types.insert(expr.node_id, ExpressionType::from(bin_op));
+
+        Ok(())
+    }
+
+    // the query is answered at compile time (see `TypeQuery`), so it's folded into its answer
+    fn on_type_query_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let (query, inner_expr) = expr.as_type_query_expr();
+        let inner_type = self.env.expr_type(inner_expr).clone();
+
+        let inner_str = match ConstEval::new(self.env).eval(inner_expr) {
+            Some(LiteralExpr::Str(s)) => Some(s),
+            _ => None,
+        };
+
+        let lit = match query.eval(&inner_type, inner_str.as_deref()) {
+            Some(lit) => lit,
+            None => {
+                let expr_str = PrettyPrintAst::pprint_expr(inner_expr);
+                let err = AstWalkError::NotConstStr(query, expr_str);
+                return Err(err);
+            }
+        };
+
+        self.env.folded_exprs.insert(expr.node_id, lit);
+        self.env.expr_types.insert(expr.node_id, query.result_type());
+
+        Ok(())
+    }
+
+    fn on_list_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let items = expr.as_list_expr();
+
+        let elem_type = match items.first() {
+            Some(item) => self.env.expr_type(item).clone(),
+            None => return Err(AstWalkError::EmptyList),
+        };
+
+        if elem_type == ExpressionType::Unit {
+            let expr_str = PrettyPrintAst::pprint_expr(&items[0]);
+            let err = AstWalkError::NotValueExpr(expr_str);
+            return Err(err);
+        }
+
+        for item in items.iter().skip(1) {
+            let item_type = self.env.expr_type(item).clone();
+
+            if item_type != elem_type {
+                let err = AstWalkError::TypeMismatch(elem_type, item_type);
+                return Err(err);
+            }
+        }
+
+        self.env.expr_types.insert(expr.node_id, ExpressionType::list_of(elem_type));
+
+        Ok(())
+    }
+
+    fn on_map_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let (_proc_name, list_expr) = expr.as_map_expr();
+
+        let elem_type = self.list_elem_type(list_expr)?;
+        let proc = self.env.symbol_table.get_proc_by_id(self.env.expr_symbol(expr));
+
+        if proc.params_types.len() != 1 {
+            let err = AstWalkError::InvalidProcCallArgsCount(
+                proc.name.clone(),
+                proc.params_types.len(),
+                1,
+            );
+            return Err(err);
+        }
+
+        if proc.params_types[0] != elem_type {
+            let err =
+                AstWalkError::InvalidProcCallArgType(1, proc.params_types[0].clone(), elem_type);
+            return Err(err);
+        }
+
+        if proc.return_type == ExpressionType::Unit {
+            let expr_str = PrettyPrintAst::pprint_expr(expr);
+            let err = AstWalkError::NotValueExpr(expr_str);
+            return Err(err);
+        }
+
+        let expr_type = ExpressionType::list_of(proc.return_type.clone());
+        self.env.expr_types.insert(expr.node_id, expr_type);
+
+        Ok(())
+    }
+
+    fn on_proc_ref_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        self.env.expr_types.insert(expr.node_id, ExpressionType::Proc);
+
+        Ok(())
+    }
+
+    fn on_block_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        self.env.expr_types.insert(expr.node_id, ExpressionType::Block);
+
+        Ok(())
+    }
+
+    fn on_if_else_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let (cond_expr, true_expr, false_expr) = expr.as_if_else_expr();
+
+        if *self.env.expr_type(cond_expr) != ExpressionType::Bool {
+            let expr_str = PrettyPrintAst::pprint_expr(cond_expr);
+            let err = AstWalkError::NotBooleanExpr(expr_str);
+            return Err(err);
+        }
+
+        let true_type = self.env.expr_type(true_expr).clone();
+        let false_type = self.env.expr_type(false_expr).clone();
+
+        if true_type == ExpressionType::Unit {
+            let expr_str = PrettyPrintAst::pprint_expr(true_expr);
+            let err = AstWalkError::NotValueExpr(expr_str);
+            return Err(err);
+        }
+
+        if true_type != false_type {
+            let err = AstWalkError::TypeMismatch(true_type, false_type);
+            return Err(err);
+        }
+
+        self.env.expr_types.insert(expr.node_id, true_type);
+
+        Ok(())
+    }
+
+    fn on_named_arg_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let (_param_name, inner_expr) = expr.as_named_arg_expr();
+
+        let inner_type = self.env.expr_type(inner_expr).clone();
+        self.env.expr_types.insert(expr.node_id, inner_type);
+
+        Ok(())
+    }
+
+    fn on_apply_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let (proc_expr, args_exprs) = expr.as_apply_expr();
+
+        if *self.env.expr_type(proc_expr) != ExpressionType::Proc {
+            let expr_str = PrettyPrintAst::pprint_expr(proc_expr);
+            let err = AstWalkError::NotProcExpr(expr_str);
+            return Err(err);
+        }
+
+        // the args are checked against the referenced procedure params only at runtime
+        for arg_expr in args_exprs {
+            if *self.env.expr_type(arg_expr) == ExpressionType::Unit {
+                let expr_str = PrettyPrintAst::pprint_expr(arg_expr);
+                let err = AstWalkError::NotValueExpr(expr_str);
+                return Err(err);
+            }
+        }
+
+        self.env.expr_types.insert(expr.node_id, ExpressionType::Unit);
 
         Ok(())
     }
@@ -130,16 +317,32 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
         self.typecheck_var_declare(make_stmt)
     }
 
+    fn on_make_const_stmt(&mut self, _ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
+        self.typecheck_var_declare(make_stmt)?;
+
+        let const_value = ConstEval::new(self.env).eval(&make_stmt.expr);
+
+        if const_value.is_none() {
+            let err = AstWalkError::NotConstExpr(make_stmt.var_name.to_string());
+            return Err(err);
+        }
+
+        let var_id = make_stmt.var_id.unwrap();
+        self.env.symbol_table.get_var_by_id_mut(var_id).const_value = const_value;
+
+        Ok(())
+    }
+
     fn on_make_assign_stmt(&mut self, _ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
         let var_id = make_stmt.var_id.unwrap();
+        let expr_type = self.env.expr_type(&make_stmt.expr).clone();
         let var: &mut Variable = self.env.symbol_table.get_var_by_id_mut(var_id);
 
-        if var.var_type.is_none() {
-            panic!()
-        }
-
-        let expr_type = make_stmt.expr.expr_type.as_ref().unwrap().to_owned();
-        let var_type = var.var_type.clone().unwrap();
+        // a global assigned (within a procedure) ahead of its declaration
+        let var_type = match var.var_type.clone() {
+            Some(var_type) => var_type,
+            None => return Err(AstWalkError::AssignBeforeDeclaration(var.name.to_string())),
+        };
 
         if expr_type != var_type {
             let err = AstWalkError::TypeMismatch(var_type, expr_type);
@@ -154,9 +357,7 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
         _ctx_proc: &str,
         direct_stmt: &mut DirectionStmt,
     ) -> AstWalkResult {
-        let expr_type = &direct_stmt.expr.expr_type;
-
-        if *expr_type != Some(ExpressionType::Int) {
+        if *self.env.expr_type(&direct_stmt.expr) != ExpressionType::Int {
             let expr_str = PrettyPrintAst::pprint_expr(&direct_stmt.expr);
             let err = AstWalkError::NotIntExpr(expr_str);
             return Err(err);
@@ -165,10 +366,106 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
         Ok(())
     }
 
+    fn on_label_stmt(&mut self, _ctx_proc: &str, label_stmt: &mut LabelStmt) -> AstWalkResult {
+        let expected = match label_stmt.kind {
+            LabelKind::Text | LabelKind::Font => ExpressionType::Str,
+            LabelKind::Size => ExpressionType::Int,
+        };
+
+        let expr_type = self.env.expr_type(&label_stmt.expr);
+
+        if *expr_type != expected {
+            let err = AstWalkError::TypeMismatch(expected, expr_type.to_owned());
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn on_stream_stmt(&mut self, _ctx_proc: &str, stream_stmt: &mut StreamStmt) -> AstWalkResult {
+        let expr_type = self.env.expr_type(&stream_stmt.expr);
+
+        if *expr_type != ExpressionType::Str {
+            let err = AstWalkError::TypeMismatch(ExpressionType::Str, expr_type.to_owned());
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn on_print(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        if *self.env.expr_type(expr) == ExpressionType::Unit {
+            let expr_str = PrettyPrintAst::pprint_expr(expr);
+            let err = AstWalkError::NotValueExpr(expr_str);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn on_set_shape(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let expr_type = self.env.expr_type(expr);
+
+        if *expr_type != ExpressionType::Str {
+            let err = AstWalkError::TypeMismatch(ExpressionType::Str, expr_type.to_owned());
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    // a palette index (or a color name) is converted to its color at runtime
+    fn on_set_pen_color(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let expr_type = self.env.expr_type(expr);
+
+        match expr_type {
+            ExpressionType::Color | ExpressionType::Int | ExpressionType::Str => Ok(()),
+            _ => {
+                let err = AstWalkError::TypeMismatch(ExpressionType::Color, expr_type.to_owned());
+                Err(err)
+            }
+        }
+    }
+
+    fn on_tone_stmt(&mut self, _ctx_proc: &str, tone_stmt: &mut ToneStmt) -> AstWalkResult {
+        for expr in &[&tone_stmt.freq_expr, &tone_stmt.duration_expr] {
+            let expr_type = self.env.expr_type(expr);
+
+            if *expr_type != ExpressionType::Int {
+                let err = AstWalkError::TypeMismatch(ExpressionType::Int, expr_type.to_owned());
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_run_stmt(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        if *self.env.expr_type(expr) != ExpressionType::Block {
+            let expr_str = PrettyPrintAst::pprint_expr(expr);
+            let err = AstWalkError::NotBlockExpr(expr_str);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
     fn on_if_stmt(&mut self, _ctx_proc: &str, if_stmt: &mut IfStmt) -> AstWalkResult {
         let cond_expr = &if_stmt.cond_expr;
 
-        if cond_expr.expr_type != Some(ExpressionType::Bool) {
+        if *self.env.expr_type(cond_expr) != ExpressionType::Bool {
+            let expr_str = PrettyPrintAst::pprint_expr(cond_expr);
+            let err = AstWalkError::NotBooleanExpr(expr_str);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn on_assert_stmt(&mut self, _ctx_proc: &str, assert_stmt: &mut AssertStmt) -> AstWalkResult {
+        let cond_expr = &assert_stmt.cond_expr;
+
+        if *self.env.expr_type(cond_expr) != ExpressionType::Bool {
             let expr_str = PrettyPrintAst::pprint_expr(cond_expr);
             let err = AstWalkError::NotBooleanExpr(expr_str);
             return Err(err);
@@ -180,7 +477,7 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
     fn on_repeat_stmt(&mut self, _ctx_proc: &str, repeat_stmt: &mut RepeatStmt) -> AstWalkResult {
         let count_expr = &repeat_stmt.count_expr;
 
-        if count_expr.expr_type != Some(ExpressionType::Int) {
+        if *self.env.expr_type(count_expr) != ExpressionType::Int {
             let expr_str = PrettyPrintAst::pprint_expr(count_expr);
             let err = AstWalkError::NotIntExpr(expr_str);
             return Err(err);
@@ -189,12 +486,34 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
         Ok(())
     }
 
+    fn on_proc_start(&mut self, _ctx_proc: &str, proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+        self.proc_id = proc_stmt.id;
+        Ok(())
+    }
+
+    fn on_proc_end(&mut self, _ctx_proc: &str, _proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+        self.proc_id = None;
+        Ok(())
+    }
+
+    fn on_foreach_var(&mut self, _ctx_proc: &str, foreach_stmt: &mut ForeachStmt) -> AstWalkResult {
+        let elem_type = self.list_elem_type(&foreach_stmt.list_expr)?;
+
+        let var_id = foreach_stmt.var_id.unwrap();
+        self.env.symbol_table.get_var_by_id_mut(var_id).var_type = Some(elem_type);
+
+        Ok(())
+    }
+
     fn on_ret_stmt(&mut self, ctx_proc: &str, ret_stmt: &mut ReturnStmt) -> AstWalkResult {
-        let proc = self.env.symbol_table.get_proc_by_name(ctx_proc);
+        let proc = match self.proc_id {
+            Some(proc_id) => self.env.symbol_table.get_proc_by_id(proc_id),
+            None => self.env.symbol_table.get_proc_by_name(ctx_proc),
+        };
 
         let actual_ret_type = if ret_stmt.expr.is_some() {
             let ret_expr = ret_stmt.expr.as_ref().unwrap();
-            let ret_expr_type = ret_expr.expr_type.as_ref().unwrap();
+            let ret_expr_type = self.env.expr_type(ret_expr);
 
             ret_expr_type.clone()
         } else {
@@ -213,28 +532,40 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
 impl<'env> AstTypeCheck<'env> {
     fn typecheck_var_declare(&mut self, make_stmt: &mut MakeStmt) -> AstWalkResult {
         let var_id = make_stmt.var_id.unwrap();
+        let expr_type = self.env.expr_type(&make_stmt.expr).clone();
         let var: &mut Variable = self.env.symbol_table.get_var_by_id_mut(var_id);
 
-        let expr_type: &ExpressionType = make_stmt.expr.expr_type.as_ref().unwrap();
-
-        if *expr_type == ExpressionType::Unit {
+        if expr_type == ExpressionType::Unit {
             let err = AstWalkError::VariableTypeMissing(var.name.to_string());
             return Err(err);
         }
 
-        var.var_type = Some(expr_type.to_owned());
+        var.var_type = Some(expr_type);
 
         Ok(())
     }
 
+    fn list_elem_type(&self, list_expr: &Expression) -> Result<ExpressionType, AstWalkError> {
+        let list_type = self.env.expr_type(list_expr);
+
+        match list_type.elem_type() {
+            Some(elem_type) => Ok(elem_type.clone()),
+            None => {
+                let expr_str = PrettyPrintAst::pprint_expr(list_expr);
+                let err = AstWalkError::NotListExpr(expr_str);
+                Err(err)
+            }
+        }
+    }
+
     fn do_binary_expr_typecheck(
         &self,
         bin_op: &BinaryOp,
         lexpr: &Expression,
         rexpr: &Expression,
     ) -> AstWalkResult {
-        let ltype = lexpr.expr_type.clone().unwrap();
-        let rtype = rexpr.expr_type.clone().unwrap();
+        let ltype = self.env.expr_type(lexpr).clone();
+        let rtype = self.env.expr_type(rexpr).clone();
 
         if ltype != rtype {
             let err = AstWalkError::InvalidBinaryOp(bin_op.clone(), ltype, rtype);
@@ -246,33 +577,11 @@ impl<'env> AstTypeCheck<'env> {
         // if we're here we know that `left expression type == right expression type`
         let expr_type: ExpressionType = ltype;
 
-        match bin_op {
-            BinaryOp::Add | BinaryOp::Mul => {
-                if expr_type != ExpressionType::Int {
-                    let err = AstWalkError::InvalidBinaryOp(
-                        bin_op.clone(),
-                        expr_type.clone(),
-                        expr_type.clone(),
-                    );
-
-                    Err(err)
-                } else {
-                    Ok(())
-                }
-            }
-            BinaryOp::GreaterThan | BinaryOp::LessThan => {
-                if expr_type != ExpressionType::Int {
-                    let err = AstWalkError::InvalidBinaryOp(
-                        bin_op.clone(),
-                        expr_type.clone(),
-                        expr_type.clone(),
-                    );
-                    Err(err)
-                } else {
-                    Ok(())
-                }
-            }
-            _ => Ok(()),
+        if !bin_op.accepts(&expr_type) {
+            let err = AstWalkError::InvalidBinaryOp(bin_op.clone(), expr_type.clone(), expr_type);
+            return Err(err);
         }
+
+        Ok(())
     }
 }
@@ -2,6 +2,7 @@ use crate::ast::expression::*;
 use crate::ast::semantic::AstWalkError;
 use crate::ast::statement::*;
 use crate::ast::Ast;
+use crate::compat::prelude::*;
 
 pub type AstWalkResult = Result<(), AstWalkError>;
 
@@ -25,14 +26,59 @@ pub trait AstWalker {
             Statement::Direction(ref mut direct_stmt) => {
                 self.walk_direct_stmt(ctx_proc, direct_stmt)?
             }
+            Statement::Label(ref mut label_stmt) => {
+                self.walk_expr(ctx_proc, &mut label_stmt.expr)?;
+                self.on_label_stmt(ctx_proc, label_stmt)?
+            }
+            Statement::Stream(ref mut stream_stmt) => {
+                self.walk_expr(ctx_proc, &mut stream_stmt.expr)?;
+                self.on_stream_stmt(ctx_proc, stream_stmt)?
+            }
+            Statement::SetShape(ref mut expr) => {
+                self.walk_expr(ctx_proc, expr)?;
+                self.on_set_shape(ctx_proc, expr)?
+            }
+            Statement::SetPenColor(ref mut expr) => {
+                self.walk_expr(ctx_proc, expr)?;
+                self.on_set_pen_color(ctx_proc, expr)?
+            }
+            Statement::Tone(ref mut tone_stmt) => {
+                self.walk_expr(ctx_proc, &mut tone_stmt.freq_expr)?;
+                self.walk_expr(ctx_proc, &mut tone_stmt.duration_expr)?;
+                self.on_tone_stmt(ctx_proc, tone_stmt)?
+            }
+            Statement::Run(ref mut expr) => {
+                self.walk_expr(ctx_proc, expr)?;
+                self.on_run_stmt(ctx_proc, expr)?
+            }
             Statement::If(ref mut if_stmt) => self.walk_if_stmt(ctx_proc, if_stmt)?,
             Statement::Make(ref mut make_stmt) => self.walk_make_stmt(ctx_proc, make_stmt)?,
             Statement::Repeat(ref mut repeat_stmt) => {
                 self.walk_repeat_stmt(ctx_proc, repeat_stmt)?
             }
+            Statement::Foreach(ref mut foreach_stmt) => {
+                self.walk_foreach_stmt(ctx_proc, foreach_stmt)?
+            }
             Statement::Procedure(ref mut proc_stmt) => self.walk_proc_stmt(ctx_proc, proc_stmt)?,
+            Statement::OnEvent(ref mut on_event_stmt) => {
+                self.on_event_stmt(ctx_proc, on_event_stmt)?;
+                self.walk_proc_stmt(ctx_proc, &mut on_event_stmt.proc_stmt)?
+            }
+            Statement::Catch(ref mut catch_stmt) => {
+                self.walk_block_stmt(ctx_proc, &mut catch_stmt.block)?
+            }
+            Statement::Throw(_) => {}
+            Statement::Assert(ref mut assert_stmt) => {
+                self.walk_expr(ctx_proc, &mut assert_stmt.cond_expr)?;
+                self.on_assert_stmt(ctx_proc, assert_stmt)?
+            }
+            Statement::Trace(ref mut trace_stmt) => self.on_trace_stmt(ctx_proc, trace_stmt)?,
             Statement::Return(ref mut return_stmt) => self.walk_ret_stmt(ctx_proc, return_stmt)?,
             Statement::Expression(ref mut expr) => self.walk_expr_stmt(ctx_proc, expr)?,
+            Statement::Load(ref path) => {
+                // `LOAD` statements are expanded by the `SourceLoader` prior to the semantic analysis
+                return Err(AstWalkError::UnresolvedLoad(path.clone()));
+            }
         }
 
         Ok(())
@@ -114,7 +160,7 @@ pub trait AstWalker {
     fn walk_expr(&mut self, ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
         match expr.expr_ast {
             ExpressionAst::Literal(_) => self.on_literal_expr(ctx_proc, expr),
-            ExpressionAst::ProcCall(ref call_name, ref mut call_params, ref mut _call_proc_id) => {
+            ExpressionAst::ProcCall(ref call_name, ref mut call_params) => {
                 self.walk_proc_call_expr(ctx_proc, call_name, call_params)?;
 
                 self.on_proc_call_expr(ctx_proc, expr)
@@ -135,6 +181,51 @@ pub trait AstWalker {
 
                 self.on_not_expr(ctx_proc, expr)
             }
+            ExpressionAst::TypeQuery(_, ref mut inner_expr) => {
+                self.walk_expr(ctx_proc, inner_expr)?;
+
+                self.on_type_query_expr(ctx_proc, expr)
+            }
+            ExpressionAst::List(ref mut items) => {
+                for item in items {
+                    self.walk_expr(ctx_proc, item)?;
+                }
+
+                self.on_list_expr(ctx_proc, expr)
+            }
+            ExpressionAst::Map(_, ref mut list_expr) => {
+                self.walk_expr(ctx_proc, list_expr)?;
+
+                self.on_map_expr(ctx_proc, expr)
+            }
+            ExpressionAst::ProcRef(..) => self.on_proc_ref_expr(ctx_proc, expr),
+            ExpressionAst::Apply(ref mut proc_expr, ref mut args_exprs) => {
+                self.walk_expr(ctx_proc, proc_expr)?;
+
+                for arg_expr in args_exprs {
+                    self.walk_expr(ctx_proc, arg_expr)?;
+                }
+
+                self.on_apply_expr(ctx_proc, expr)
+            }
+            ExpressionAst::NamedArg(_, ref mut inner_expr) => {
+                self.walk_expr(ctx_proc, inner_expr)?;
+
+                self.on_named_arg_expr(ctx_proc, expr)
+            }
+            ExpressionAst::Block(ref mut block_stmt) => {
+                self.on_block_expr_start(ctx_proc, block_stmt)?;
+                self.walk_block_stmt(ctx_proc, block_stmt)?;
+
+                self.on_block_expr(ctx_proc, expr)
+            }
+            ExpressionAst::IfElse(ref mut cond_expr, ref mut true_expr, ref mut false_expr) => {
+                self.walk_expr(ctx_proc, cond_expr)?;
+                self.walk_expr(ctx_proc, true_expr)?;
+                self.walk_expr(ctx_proc, false_expr)?;
+
+                self.on_if_else_expr(ctx_proc, expr)
+            }
         }
     }
 
@@ -173,6 +264,7 @@ pub trait AstWalker {
             MakeStmtKind::Global => self.on_make_global_stmt(ctx_proc, make_stmt)?,
             MakeStmtKind::Local => self.on_make_local_stmt(ctx_proc, make_stmt)?,
             MakeStmtKind::Assign => self.on_make_assign_stmt(ctx_proc, make_stmt)?,
+            MakeStmtKind::Const => self.on_make_const_stmt(ctx_proc, make_stmt)?,
         }
 
         Ok(())
@@ -186,6 +278,27 @@ pub trait AstWalker {
         self.on_repeat_stmt(ctx_proc, repeat_stmt)
     }
 
+    fn walk_foreach_stmt(
+        &mut self,
+        ctx_proc: &str,
+        foreach_stmt: &mut ForeachStmt,
+    ) -> AstWalkResult {
+        self.walk_expr(ctx_proc, &mut foreach_stmt.list_expr)?;
+
+        // we don't call `walk_block_stmt` since the iteration variable
+        // should be declared under the block scope, prior to walking the block statements
+        self.on_block_stmt_start(ctx_proc, &mut foreach_stmt.block)?;
+        self.on_foreach_var(ctx_proc, foreach_stmt)?;
+
+        for stmt in &mut foreach_stmt.block.stmts {
+            self.walk_stmt(ctx_proc, stmt)?;
+        }
+
+        self.on_block_stmt_end(ctx_proc, &mut foreach_stmt.block)?;
+
+        self.on_foreach_stmt(ctx_proc, foreach_stmt)
+    }
+
     // hooks
     fn on_proc_start(&mut self, _ctx_proc: &str, _proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
         Ok(())
@@ -233,10 +346,51 @@ pub trait AstWalker {
         Ok(())
     }
 
+    fn on_type_query_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_list_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_map_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_proc_ref_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_apply_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_named_arg_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
     fn on_parentheses_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
         Ok(())
     }
 
+    // called prior to walking the statements of a block expression
+    fn on_block_expr_start(
+        &mut self,
+        _ctx_proc: &str,
+        _block_stmt: &mut BlockStatement,
+    ) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_block_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_if_else_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
     fn on_expr_stmt(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
         Ok(())
     }
@@ -279,6 +433,10 @@ pub trait AstWalker {
         Ok(())
     }
 
+    fn on_make_const_stmt(&mut self, _ctx_proc: &str, _make_stmt: &mut MakeStmt) -> AstWalkResult {
+        Ok(())
+    }
+
     // misc
     fn on_if_stmt(&mut self, _ctx_proc: &str, _if_stmt: &mut IfStmt) -> AstWalkResult {
         Ok(())
@@ -288,6 +446,40 @@ pub trait AstWalker {
         Ok(())
     }
 
+    fn on_assert_stmt(&mut self, _ctx_proc: &str, _assert_stmt: &mut AssertStmt) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_trace_stmt(&mut self, _ctx_proc: &str, _trace_stmt: &mut TraceStmt) -> AstWalkResult {
+        Ok(())
+    }
+
+    // called prior to walking the event handler procedure (see `OnEventStmt`)
+    fn on_event_stmt(
+        &mut self,
+        _ctx_proc: &str,
+        _on_event_stmt: &mut OnEventStmt,
+    ) -> AstWalkResult {
+        Ok(())
+    }
+
+    // called once the `FOREACH` block scope has been started (before walking the block statements)
+    fn on_foreach_var(
+        &mut self,
+        _ctx_proc: &str,
+        _foreach_stmt: &mut ForeachStmt,
+    ) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_foreach_stmt(
+        &mut self,
+        _ctx_proc: &str,
+        _foreach_stmt: &mut ForeachStmt,
+    ) -> AstWalkResult {
+        Ok(())
+    }
+
     fn on_ret_stmt(&mut self, _ctx_proc: &str, _return_stmt: &mut ReturnStmt) -> AstWalkResult {
         Ok(())
     }
@@ -304,7 +496,31 @@ pub trait AstWalker {
         Ok(())
     }
 
+    fn on_label_stmt(&mut self, _ctx_proc: &str, _label_stmt: &mut LabelStmt) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_stream_stmt(&mut self, _ctx_proc: &str, _stream_stmt: &mut StreamStmt) -> AstWalkResult {
+        Ok(())
+    }
+
     fn on_print(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
         Ok(())
     }
+
+    fn on_set_shape(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_set_pen_color(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_tone_stmt(&mut self, _ctx_proc: &str, _tone_stmt: &mut ToneStmt) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_run_stmt(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
 }
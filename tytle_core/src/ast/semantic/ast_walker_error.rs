@@ -1,4 +1,5 @@
-use crate::ast::expression::{BinaryOp, ExpressionType};
+use crate::ast::expression::{BinaryOp, ExpressionType, TypeQuery};
+use crate::compat::prelude::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AstWalkError {
@@ -6,17 +7,41 @@ pub enum AstWalkError {
     DuplicateProc(String),
     DuplicateProcLocalVar(String),
     DuplicateProcParam(String, String),
-    MissingVarDeclaration(String),
+    MissingVarDeclaration(String, Option<String>),
+    MissingProcDeclaration(String, Option<String>),
     ProcNotAllowedToDeclareGlobals(String),
     InvalidReturnType(ExpressionType, ExpressionType),
     LocalsNotAllowedUnderRootScope(String),
+    GlobalsNotAllowedUnderInnerScope(String),
+    AssignBeforeDeclaration(String),
+    AssignToConst(String),
+    NotConstExpr(String),
+    NotConstStr(TypeQuery, String),
     TypeMismatch(ExpressionType, ExpressionType),
     InvalidBinaryOp(BinaryOp, ExpressionType, ExpressionType),
     InvalidProcCallArgsCount(String, usize, usize),
+    TooFewProcCallArgs(String, usize, usize),
+    UnknownNamedArg(String, String),
+    DuplicateNamedArg(String, String),
+    MissingProcCallArg(String, String),
+    NoProcOverload(String, usize),
+    AmbiguousProcCall(String, usize),
+    AmbiguousProcRef(String),
     InvalidProcCallArgType(usize, ExpressionType, ExpressionType),
     VariableTypeMissing(String),
     NotBooleanExpr(String),
     NotIntExpr(String),
+    NotListExpr(String),
+    NotProcExpr(String),
+    NotBlockExpr(String),
+    NotValueExpr(String),
+    EmptyList,
+    UnresolvedLoad(String),
+    BuiltinProcRedefinition(String),
+    DuplicateEventHandler(String),
+    EventHandlerNotAtTopLevel(String),
+    ProcNotAtTopLevel(String),
+    ReturnWithinBlock,
 }
 
 impl ToString for AstWalkError {
@@ -31,9 +56,12 @@ impl ToString for AstWalkError {
                 "Duplicate procedure param: `{}` (procedure: `{}`)",
                 param, proc
             ),
-            AstWalkError::MissingVarDeclaration(var) => {
+            AstWalkError::MissingVarDeclaration(var, _) => {
                 format!("Missing variable declaration for `{}`", var)
             }
+            AstWalkError::MissingProcDeclaration(proc, _) => {
+                format!("Missing procedure declaration for `{}`", proc)
+            }
             AstWalkError::ProcNotAllowedToDeclareGlobals(proc) => format!(
                 "Procedure not allowed to declare globals (procedure `{}`)",
                 proc
@@ -47,6 +75,22 @@ impl ToString for AstWalkError {
                 "Local aren't allowed under the main procedure (variable: `{}`)",
                 var
             ),
+            AstWalkError::GlobalsNotAllowedUnderInnerScope(var) => format!(
+                "Globals must be declared at the top level, outside of any block (variable: `{}`)",
+                var
+            ),
+            AstWalkError::AssignBeforeDeclaration(var) => {
+                format!("Variable `{}` is assigned before its declaration", var)
+            }
+            AstWalkError::AssignToConst(var) => format!("Can't assign to constant `{}`", var),
+            AstWalkError::NotConstExpr(var) => format!(
+                "The value of constant `{}` can't be evaluated at compile time",
+                var
+            ),
+            AstWalkError::NotConstStr(query, expr) => format!(
+                "`{}` requires a string known at compile time (expression: `{}`)",
+                query, expr
+            ),
             AstWalkError::TypeMismatch(expected, actual) =>
                 format!("Type mismatch. expected: `{}`, actual: `{}`", expected.to_string(), actual.to_string()),
             AstWalkError::InvalidBinaryOp(bin_op, ltype, rtype) =>
@@ -54,9 +98,41 @@ impl ToString for AstWalkError {
             AstWalkError::InvalidProcCallArgsCount(proc, expected, actual) => {
                 format!("Prcedure call wrong number of arguments for `{}` (expected: {}, actual: {})", proc, expected, actual)
             },
+            AstWalkError::TooFewProcCallArgs(proc, at_least, actual) => {
+                format!("Procedure call too few arguments for `{}` (expected at least: {}, actual: {})", proc, at_least, actual)
+            },
+            AstWalkError::UnknownNamedArg(proc, param) => {
+                format!("Procedure `{}` has no param named `{}`", proc, param)
+            },
+            AstWalkError::DuplicateNamedArg(proc, param) => {
+                format!("Param `{}` is passed more than once (procedure: `{}`)", param, proc)
+            },
+            AstWalkError::MissingProcCallArg(proc, param) => {
+                format!("Procedure call is missing an argument for `{}` (procedure: `{}`)", param, proc)
+            },
+            AstWalkError::NoProcOverload(proc, nargs) => {
+                format!("No definition of procedure `{}` takes {} arguments", proc, nargs)
+            },
+            AstWalkError::AmbiguousProcCall(proc, nargs) => {
+                format!("Ambiguous call of procedure `{}` (more than one definition takes {} arguments)", proc, nargs)
+            },
+            AstWalkError::AmbiguousProcRef(proc) => {
+                format!("Procedure `{}` is overloaded, so it can only be called directly", proc)
+            },
             AstWalkError::VariableTypeMissing(var) => format!("Missing type for variable: `{}`", var),
             AstWalkError::NotBooleanExpr(expr) => format!("Expression `{}` isn't a Boolean expression", expr),
             AstWalkError::NotIntExpr(expr) => format!("Expression `{}` isn't an Integer expression", expr),
+            AstWalkError::NotListExpr(expr) => format!("Expression `{}` isn't a List expression", expr),
+            AstWalkError::NotProcExpr(expr) => format!("Expression `{}` isn't a Procedure expression", expr),
+            AstWalkError::NotBlockExpr(expr) => format!("Expression `{}` isn't a Block expression", expr),
+            AstWalkError::NotValueExpr(expr) => format!("Expression `{}` has no value", expr),
+            AstWalkError::EmptyList => "Can't infer the type of the elements of an empty list".to_string(),
+            AstWalkError::UnresolvedLoad(path) => format!("Unresolved `LOAD` of `{}`", path),
+            AstWalkError::BuiltinProcRedefinition(proc) => format!("Can't redefine built-in procedure: `{}`", proc),
+            AstWalkError::DuplicateEventHandler(event) => format!("Duplicate event handler: `{}`", event),
+            AstWalkError::EventHandlerNotAtTopLevel(event) => format!("Event handlers must be registered at the top level, outside of any block (event: `{}`)", event),
+            AstWalkError::ProcNotAtTopLevel(proc) => format!("Procedures must be declared at the top level, outside of any procedure or block (procedure: `{}`)", proc),
+            AstWalkError::ReturnWithinBlock => "Blocks can't `RETURN` (or `HALT`), since they run within another procedure".to_string(),
             AstWalkError::InvalidProcCallArgType(arg_index, expected, actual) =>
                 format!("expected the {} argument to be `{}` (actual: `{}`)", self.indexify_arg(*arg_index), expected.to_string(), actual.to_string())
         }
@@ -64,7 +140,16 @@ impl ToString for AstWalkError {
 }
 
 impl AstWalkError {
-    fn indexify_arg(&self, index: usize) -> String {
+    // a likely intended symbol name, for errors caused by a misspelled one
+    pub fn suggestion(&self) -> Option<&str> {
+        match self {
+            AstWalkError::MissingVarDeclaration(_, suggestion)
+            | AstWalkError::MissingProcDeclaration(_, suggestion) => suggestion.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn indexify_arg(&self, index: usize) -> String {
         match index {
             1 => "first".to_string(),
             2 => "second".to_string(),
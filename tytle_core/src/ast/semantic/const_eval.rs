@@ -0,0 +1,101 @@
+use crate::ast::expression::{BinaryOp, Expression, ExpressionAst, LiteralExpr};
+use crate::ast::semantic::Environment;
+
+// folds an expression into a literal at compile time.
+// an expression is constant when it's made only of literals and constants (see `CONST`),
+// procedure calls (and plain variables) are evaluated at runtime only.
+//
+// integer overflows and divisions by zero aren't folded either (returning `None`)
+pub struct ConstEval<'a> {
+    env: &'a Environment,
+}
+
+impl<'a> ConstEval<'a> {
+    pub fn new(env: &'a Environment) -> Self {
+        Self { env }
+    }
+
+    pub fn eval(&self, expr: &Expression) -> Option<LiteralExpr> {
+        match &expr.expr_ast {
+            ExpressionAst::Literal(LiteralExpr::Var(_)) => {
+                let var_id = self.env.expr_symbols.get(expr.node_id)?;
+                let var = self.env.symbol_table.get_var_by_id(*var_id);
+
+                var.const_value.clone()
+            }
+            ExpressionAst::Literal(lit) => Some(lit.clone()),
+            ExpressionAst::Parentheses(inner_expr) => self.eval(inner_expr),
+            ExpressionAst::Not(inner_expr) => match self.eval(inner_expr)? {
+                LiteralExpr::Bool(v) => Some(LiteralExpr::Bool(!v)),
+                _ => None,
+            },
+            ExpressionAst::Binary(bin_op, lexpr, rexpr) => {
+                let lval = self.eval(lexpr)?;
+                let rval = self.eval(rexpr)?;
+
+                Self::eval_binary(bin_op, lval, rval)
+            }
+            ExpressionAst::TypeQuery(query, inner_expr) => {
+                let inner_type = self.env.expr_types.get(inner_expr.node_id)?;
+
+                let inner_str = match self.eval(inner_expr) {
+                    Some(LiteralExpr::Str(s)) => Some(s),
+                    _ => None,
+                };
+
+                query.eval(inner_type, inner_str.as_deref())
+            }
+            ExpressionAst::IfElse(cond_expr, true_expr, false_expr) => match self.eval(cond_expr)? {
+                LiteralExpr::Bool(true) => self.eval(true_expr),
+                LiteralExpr::Bool(false) => self.eval(false_expr),
+                _ => None,
+            },
+            // lists, blocks and procedure references are always built at runtime
+            ExpressionAst::ProcCall(..)
+            | ExpressionAst::List(_)
+            | ExpressionAst::Map(..)
+            | ExpressionAst::ProcRef(..)
+            | ExpressionAst::Apply(..)
+            | ExpressionAst::NamedArg(..)
+            | ExpressionAst::Block(_) => None,
+        }
+    }
+
+    fn eval_binary(bin_op: &BinaryOp, lval: LiteralExpr, rval: LiteralExpr) -> Option<LiteralExpr> {
+        match (lval, rval) {
+            (LiteralExpr::Int(a), LiteralExpr::Int(b)) => {
+                let lit = match bin_op {
+                    BinaryOp::Add => LiteralExpr::Int(a.checked_add(b)?),
+                    BinaryOp::Mul => LiteralExpr::Int(a.checked_mul(b)?),
+                    BinaryOp::Div => LiteralExpr::Int(a.checked_div(b)?),
+                    BinaryOp::GreaterThan => LiteralExpr::Bool(a > b),
+                    BinaryOp::LessThan => LiteralExpr::Bool(a < b),
+                    BinaryOp::Equal => LiteralExpr::Bool(a == b),
+                    BinaryOp::NotEqual => LiteralExpr::Bool(a != b),
+                    BinaryOp::And | BinaryOp::Or => return None,
+                };
+
+                // the VM integers are signed
+                match lit {
+                    LiteralExpr::Int(v) if v > isize::MAX as usize => None,
+                    _ => Some(lit),
+                }
+            }
+            (LiteralExpr::Bool(a), LiteralExpr::Bool(b)) => match bin_op {
+                BinaryOp::And => Some(LiteralExpr::Bool(a && b)),
+                BinaryOp::Or => Some(LiteralExpr::Bool(a || b)),
+                BinaryOp::Equal => Some(LiteralExpr::Bool(a == b)),
+                BinaryOp::NotEqual => Some(LiteralExpr::Bool(a != b)),
+                _ => None,
+            },
+            (LiteralExpr::Str(a), LiteralExpr::Str(b)) => match bin_op {
+                BinaryOp::GreaterThan => Some(LiteralExpr::Bool(a > b)),
+                BinaryOp::LessThan => Some(LiteralExpr::Bool(a < b)),
+                BinaryOp::Equal => Some(LiteralExpr::Bool(a == b)),
+                BinaryOp::NotEqual => Some(LiteralExpr::Bool(a != b)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
@@ -1,6 +1,12 @@
-use crate::ast::expression::ExpressionType;
-use crate::ast::semantic::{IdGenerator, Procedure, SymbolId, SymbolTable, Variable};
-use std::collections::HashMap;
+use crate::ast::expression::{Expression, ExpressionType, LiteralExpr};
+use crate::ast::semantic::{
+    AstWalkError, IdGenerator, NativeProc, Primitive, Procedure, SymbolId, SymbolTable, Variable,
+};
+use crate::ast::statement::{BlockStatement, Event};
+use crate::ast::AnnotationMap;
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
+use crate::vm::{Host, MemoryValue};
 
 pub struct Environment {
     pub symbol_table: SymbolTable,
@@ -14,6 +20,33 @@ pub struct Environment {
     pub locals_symbols: HashMap<SymbolId, Vec<SymbolId>>,
 
     pub main_proc_id: Option<SymbolId>,
+
+    // whether to preload the stdlib prelude procedures
+    pub stdlib: bool,
+
+    // procedures implemented in Rust, registered by the embedder
+    pub natives: HashMap<SymbolId, NativeProc>,
+
+    // the `ONKEY` handlers procedures, by their key
+    pub key_handlers: HashMap<String, SymbolId>,
+
+    // the `ONTICK` handler procedure, along with its interval (in milliseconds)
+    pub tick_handler: Option<(usize, SymbolId)>,
+
+    // the statements of the generated block procedures (see `CfgBuilder::build_block_proc`)
+    pub blocks: HashMap<SymbolId, BlockStatement>,
+
+    // the initial values of the globals registered by the embedder (see `register_global`)
+    pub injected_globals: HashMap<SymbolId, MemoryValue>,
+
+    // the types of the expressions, annotated by the type checker
+    pub expr_types: AnnotationMap<ExpressionType>,
+
+    // the variables (and procedures) the expressions refer to, resolved by the symbol generator
+    pub expr_symbols: AnnotationMap<SymbolId>,
+
+    // the expressions folded by the type checker into a literal (see `TypeQuery`)
+    pub folded_exprs: AnnotationMap<LiteralExpr>,
 }
 
 impl Environment {
@@ -25,9 +58,41 @@ impl Environment {
             locals_symbols: HashMap::new(),
             symbol_table: SymbolTable::new(),
             id_generator: IdGenerator::new(),
+            stdlib: false,
+            natives: HashMap::new(),
+            key_handlers: HashMap::new(),
+            tick_handler: None,
+            blocks: HashMap::new(),
+            injected_globals: HashMap::new(),
+            expr_types: AnnotationMap::new(),
+            expr_symbols: AnnotationMap::new(),
+            folded_exprs: AnnotationMap::new(),
+        }
+    }
+
+    // the type of a type-checked expression
+    pub fn expr_type(&self, expr: &Expression) -> &ExpressionType {
+        match self.expr_types.get(expr.node_id) {
+            Some(expr_type) => expr_type,
+            None => panic!("expression `{:?}` hasn't been type-checked", expr.expr_ast),
+        }
+    }
+
+    // the symbol a variable (or a procedure call, `MAP` or `PROC`) expression refers to
+    pub fn expr_symbol(&self, expr: &Expression) -> SymbolId {
+        match self.expr_symbols.get(expr.node_id) {
+            Some(symbol_id) => *symbol_id,
+            None => panic!("expression `{:?}` hasn't been resolved", expr.expr_ast),
         }
     }
 
+    pub fn with_stdlib() -> Self {
+        let mut env = Self::new();
+        env.stdlib = true;
+
+        env
+    }
+
     pub fn create_proc(
         &mut self,
         name: &str,
@@ -40,7 +105,13 @@ impl Environment {
             id,
             name: name.to_string(),
             params_types,
+            params_names: Vec::new(),
             return_type,
+            builtin: false,
+            native: false,
+            primitive: None,
+            doc: None,
+            rest: false,
         };
 
         self.symbol_table.create_proc_symbol(proc);
@@ -48,6 +119,106 @@ impl Environment {
         id
     }
 
+    // registers a Rust closure as a tytle procedure named `name`.
+    // calls to it are type-checked against `params_types` and `return_type` like any other procedure.
+    // should be called before generating the program symbols
+    pub fn register_native<F>(
+        &mut self,
+        name: &str,
+        params_types: &[ExpressionType],
+        return_type: ExpressionType,
+        func: F,
+    ) -> SymbolId
+    where
+        F: Fn(&[MemoryValue], &mut dyn Host) -> Option<MemoryValue> + Send + Sync + 'static,
+    {
+        let proc_id = self.create_proc(name, params_types.to_vec(), return_type);
+
+        let proc = self.symbol_table.get_proc_by_id_mut(proc_id);
+        proc.native = true;
+
+        self.natives.insert(proc_id, NativeProc::new(func));
+
+        proc_id
+    }
+
+    // predeclares a global named `name`, typed (and initialized) by `value`, e.g a level number.
+    // the program may read and assign it, but not declare it again.
+    // the type of an empty list can't be told (use `register_typed_global` instead).
+    // should be called before generating the program symbols
+    pub fn register_global(
+        &mut self,
+        name: &str,
+        value: MemoryValue,
+    ) -> Result<SymbolId, AstWalkError> {
+        let var_type = value.value_type();
+
+        if var_type == ExpressionType::list_of(ExpressionType::Unit) {
+            return Err(AstWalkError::EmptyList);
+        }
+
+        Ok(self.register_typed_global(name, var_type, value))
+    }
+
+    // predeclares a global named `name` of type `var_type`, initialized by `value`
+    // (see `register_global`)
+    pub fn register_typed_global(
+        &mut self,
+        name: &str,
+        var_type: ExpressionType,
+        value: MemoryValue,
+    ) -> SymbolId {
+        let var_id = self.id_generator.get_next_id();
+        self.create_global_var(var_id, name, Some(var_type));
+        self.injected_globals.insert(var_id, value);
+
+        var_id
+    }
+
+    // creates the procedure symbol of a VM primitive (see `Primitive`)
+    pub fn create_primitive_proc(&mut self, prim: Primitive) -> SymbolId {
+        let id = self.id_generator.get_next_id();
+
+        let proc = Procedure {
+            id,
+            name: prim.name().to_string(),
+            params_types: prim.params_types(),
+            params_names: Vec::new(),
+            return_type: prim.return_type(),
+            builtin: true,
+            native: false,
+            primitive: Some(prim),
+            doc: None,
+            rest: prim.rest(),
+        };
+
+        self.symbol_table.create_root_proc_symbol(proc);
+
+        id
+    }
+
+    pub fn get_native(&self, proc_id: SymbolId) -> &NativeProc {
+        &self.natives[&proc_id]
+    }
+
+    // whether `proc_id` is one of the stdlib prelude procedures
+    // (other generated procedures, e.g the blocks ones, are marked `builtin` as well)
+    pub fn is_prelude_proc(&self, proc_id: SymbolId) -> bool {
+        let proc = self.symbol_table.get_proc_by_id(proc_id);
+
+        self.stdlib && proc.builtin && crate::stdlib::is_builtin(&proc.name)
+    }
+
+    // registers `proc_id` as the handler of `event` (replacing its existing handler, if any)
+    pub fn register_handler(&mut self, event: &Event, proc_id: SymbolId) {
+        match event {
+            Event::Key(key) => {
+                self.key_handlers.insert(key.clone(), proc_id);
+            }
+            Event::Tick(interval) => self.tick_handler = Some((*interval, proc_id)),
+        }
+    }
+
     pub fn create_tmp_var(
         &mut self,
         proc_id: SymbolId,
@@ -76,6 +247,8 @@ impl Environment {
             name: var_name.to_string(),
             var_type,
             index: Some(global_id),
+            constant: false,
+            const_value: None,
         };
 
         self.symbol_table.create_var_symbol(var);
@@ -101,6 +274,8 @@ impl Environment {
             global: false,
             param: is_param,
             index: Some(var_index),
+            constant: false,
+            const_value: None,
         };
 
         self.symbol_table.create_var_symbol(var);
@@ -1,8 +1,11 @@
 mod ast_typecheck;
 mod ast_walker;
 mod ast_walker_error;
+mod const_eval;
 mod environment;
 mod id_generator;
+mod native_proc;
+mod primitive;
 mod procedure;
 mod scope;
 mod symbol;
@@ -13,8 +16,11 @@ mod variable;
 pub use ast_typecheck::*;
 pub use ast_walker::*;
 pub use ast_walker_error::*;
+pub use const_eval::ConstEval;
 pub use environment::Environment;
 pub use id_generator::IdGenerator;
+pub use native_proc::{NativeFn, NativeProc};
+pub use primitive::Primitive;
 pub use procedure::*;
 pub use scope::*;
 pub use symbol::*;
@@ -0,0 +1,27 @@
+use crate::vm::{Host, MemoryValue};
+use alloc::sync::Arc;
+
+// a Rust closure callable from tytle code.
+// it receives the evaluated call arguments (in declaration order) and the interpreter host,
+// and returns the procedure return value (`None` for procedures returning `UNIT`)
+pub type NativeFn = dyn Fn(&[MemoryValue], &mut dyn Host) -> Option<MemoryValue> + Send + Sync;
+
+#[derive(Clone)]
+pub struct NativeProc {
+    pub func: Arc<NativeFn>,
+}
+
+impl NativeProc {
+    pub fn new<F>(func: F) -> Self
+    where
+        F: Fn(&[MemoryValue], &mut dyn Host) -> Option<MemoryValue> + Send + Sync + 'static,
+    {
+        Self {
+            func: Arc::new(func),
+        }
+    }
+
+    pub fn call(&self, args: &[MemoryValue], host: &mut dyn Host) -> Option<MemoryValue> {
+        (self.func)(args, host)
+    }
+}
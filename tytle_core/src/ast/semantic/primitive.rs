@@ -0,0 +1,183 @@
+use crate::ast::expression::ExpressionType;
+use crate::compat::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// the words (strings) manipulation built-ins, the colors constructors,
+// the screen & mouse queries and the persistent storage.
+// unlike the stdlib prelude procedures, they're implemented by the VM itself (see `CfgInstruction::Primitive`).
+// a primitive procedure symbol is created only once the primitive is called by the program,
+// and a user defined procedure of the same name takes precedence over it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Primitive {
+    // `WORD(A: STR, B: STR): STR` - the concatenation of `A` and `B`
+    Word,
+
+    // `COUNT(W: STR): INT` - the number of characters of `W`
+    Count,
+
+    // `ITEM(I: INT, W: STR): STR` - the `I`-th character of `W` (starting from 1)
+    Item,
+
+    // `UPPERCASE(W: STR): STR` / `LOWERCASE(W: STR): STR`
+    Uppercase,
+    Lowercase,
+
+    // `PARSE(W: STR): INT` - the number written in `W`
+    Parse,
+
+    // `SCREENWIDTH(): INT` / `SCREENHEIGHT(): INT` - the size of the host screen (canvas),
+    // kept up to date by `Interpreter::notify_resize`
+    ScreenWidth,
+    ScreenHeight,
+
+    // `MOUSEX(): INT` / `MOUSEY(): INT` - the pointer position (in the turtle coordinates),
+    // `BUTTONP(): BOOL` - whether the pointer button is pressed.
+    // kept up to date by `Interpreter::notify_mouse`
+    MouseX,
+    MouseY,
+    ButtonP,
+
+    // `RGB r g b` (i.e `RGB(R: INT, G: INT, B: INT): COLOR`) - the channels are clamped
+    // into the `0..=255` range
+    Rgb,
+
+    // `PALETTE(I: INT): COLOR` / `NAMEDCOLOR(W: STR): COLOR` - the palette colors
+    // (see `Color::palette`)
+    Palette,
+    NamedColor,
+
+    // `PENCOLOR(): COLOR` - the current pen color (see `SETPENCOLOR`)
+    PenColor,
+
+    // `FORMAT(F: STR, ARGS...): STR` - `F` with its placeholders replaced by the args (of any type).
+    // `~a` is an arg as printed by `PRINT` (a string without its quote),
+    // `~s` is an arg as written in the code (e.g `"abc`) and `~~` is a `~`
+    Format,
+
+    // `STORE(NAME: STR, VALUE)` - keeps the value (of any type, as printed by `PRINT`)
+    // under `NAME`, so a later run may `RETRIEVE` it (see `Host::kv_set`)
+    Store,
+
+    // `RETRIEVE(NAME: STR): STR` - the value stored under `NAME`, an empty string when there's none
+    // (see `Host::kv_get`)
+    Retrieve,
+}
+
+impl Primitive {
+    pub const ALL: [Primitive; 18] = [
+        Primitive::Word,
+        Primitive::Count,
+        Primitive::Item,
+        Primitive::Uppercase,
+        Primitive::Lowercase,
+        Primitive::Parse,
+        Primitive::ScreenWidth,
+        Primitive::ScreenHeight,
+        Primitive::MouseX,
+        Primitive::MouseY,
+        Primitive::ButtonP,
+        Primitive::Rgb,
+        Primitive::Palette,
+        Primitive::NamedColor,
+        Primitive::PenColor,
+        Primitive::Format,
+        Primitive::Store,
+        Primitive::Retrieve,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|prim| prim.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Primitive::Word => "WORD",
+            Primitive::Count => "COUNT",
+            Primitive::Item => "ITEM",
+            Primitive::Uppercase => "UPPERCASE",
+            Primitive::Lowercase => "LOWERCASE",
+            Primitive::Parse => "PARSE",
+            Primitive::ScreenWidth => "SCREENWIDTH",
+            Primitive::ScreenHeight => "SCREENHEIGHT",
+            Primitive::MouseX => "MOUSEX",
+            Primitive::MouseY => "MOUSEY",
+            Primitive::ButtonP => "BUTTONP",
+            Primitive::Rgb => "RGB",
+            Primitive::Palette => "PALETTE",
+            Primitive::NamedColor => "NAMEDCOLOR",
+            Primitive::PenColor => "PENCOLOR",
+            Primitive::Format => "FORMAT",
+            Primitive::Store => "STORE",
+            Primitive::Retrieve => "RETRIEVE",
+        }
+    }
+
+    pub fn params_types(&self) -> Vec<ExpressionType> {
+        match self {
+            Primitive::Word => vec![ExpressionType::Str, ExpressionType::Str],
+            Primitive::Item => vec![ExpressionType::Int, ExpressionType::Str],
+            Primitive::Count
+            | Primitive::Uppercase
+            | Primitive::Lowercase
+            | Primitive::Parse
+            | Primitive::NamedColor
+            | Primitive::Retrieve => vec![ExpressionType::Str],
+            Primitive::Rgb => vec![ExpressionType::Int; 3],
+            Primitive::Palette => vec![ExpressionType::Int],
+            Primitive::Format => vec![
+                ExpressionType::Str,
+                ExpressionType::list_of(ExpressionType::Unit),
+            ],
+            Primitive::Store => vec![ExpressionType::Str, ExpressionType::Unit],
+            Primitive::ScreenWidth
+            | Primitive::ScreenHeight
+            | Primitive::MouseX
+            | Primitive::MouseY
+            | Primitive::ButtonP
+            | Primitive::PenColor => vec![],
+        }
+    }
+
+    pub fn return_type(&self) -> ExpressionType {
+        match self {
+            Primitive::Count
+            | Primitive::Parse
+            | Primitive::ScreenWidth
+            | Primitive::ScreenHeight
+            | Primitive::MouseX
+            | Primitive::MouseY => ExpressionType::Int,
+            Primitive::ButtonP => ExpressionType::Bool,
+            Primitive::Rgb | Primitive::Palette | Primitive::NamedColor | Primitive::PenColor => {
+                ExpressionType::Color
+            }
+            Primitive::Word
+            | Primitive::Item
+            | Primitive::Uppercase
+            | Primitive::Lowercase
+            | Primitive::Format
+            | Primitive::Retrieve => ExpressionType::Str,
+            Primitive::Store => ExpressionType::Unit,
+        }
+    }
+
+    // whether the last param is a rest param (see `Procedure::rest`)
+    pub fn rest(&self) -> bool {
+        *self == Primitive::Format
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitive_from_name() {
+        for prim in Primitive::ALL.iter() {
+            assert_eq!(Some(*prim), Primitive::from_name(prim.name()));
+        }
+
+        assert_eq!(None, Primitive::from_name("word"));
+        assert_eq!(None, Primitive::from_name("WORDS"));
+    }
+}
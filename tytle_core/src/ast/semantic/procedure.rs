@@ -1,12 +1,32 @@
-use crate::ast::expression::ExpressionType;
-use crate::ast::semantic::SymbolId;
+use crate::ast::expression::{Expression, ExpressionAst, ExpressionType};
+use crate::ast::semantic::{AstWalkError, Primitive, SymbolId};
+use crate::ast::statement::ProcDoc;
+use crate::compat::prelude::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Procedure {
     pub id: SymbolId,
     pub name: String,
     pub params_types: Vec<ExpressionType>,
+
+    // the params names, for resolving named args (empty for primitives and native procedures)
+    pub params_names: Vec<String>,
     pub return_type: ExpressionType,
+
+    // whether the procedure is part of the stdlib prelude
+    pub builtin: bool,
+
+    // whether the procedure is implemented in Rust (see `Environment::register_native`)
+    pub native: bool,
+
+    // the VM primitive implementing the procedure (see `Primitive`)
+    pub primitive: Option<Primitive>,
+
+    // the documentation of the procedure's definition (see `ProcedureStmt::doc`)
+    pub doc: Option<ProcDoc>,
+
+    // whether the last param is a rest param (its type is a list of the remaining args types)
+    pub rest: bool,
 }
 
 impl Procedure {
@@ -15,7 +35,83 @@ impl Procedure {
             id,
             name: name.to_owned(),
             params_types: Vec::new(),
+            params_names: Vec::new(),
             return_type: ExpressionType::Unit,
+            builtin: false,
+            native: false,
+            primitive: None,
+            doc: None,
+            rest: false,
         }
     }
+    // whether a call with `nargs` args may call the procedure
+    pub fn accepts(&self, nargs: usize) -> bool {
+        match self.rest {
+            true => nargs + 1 >= self.params_types.len(),
+            false => nargs == self.params_types.len(),
+        }
+    }
+
+    // whether a procedure having `params_count` params can't overload this one
+    // (i.e both take the same number of params)
+    pub fn collides(&self, params_count: usize, rest: bool) -> bool {
+        self.rest == rest && self.params_types.len() == params_count
+    }
+
+    // the args of a call ordered like the params, each named arg placed at its param position
+    // (the parser ensures the positional args precede the named ones).
+    // the named args are evaluated in their params order rather than in the call's one
+    pub fn order_args<'a>(
+        &self,
+        args: &'a [Expression],
+    ) -> Result<Vec<&'a Expression>, AstWalkError> {
+        let (named, positional): (Vec<_>, Vec<_>) = args
+            .iter()
+            .partition(|arg| matches!(arg.expr_ast, ExpressionAst::NamedArg(..)));
+
+        if named.is_empty() {
+            return Ok(positional);
+        }
+
+        // a rest param receives positional args only.
+        // (the primitives, e.g `FORMAT`, have no params names)
+        let params_names = match self.rest {
+            true => &self.params_names[..self.params_names.len().saturating_sub(1)],
+            false => &self.params_names[..],
+        };
+
+        let mut ordered = positional.into_iter().map(Some).collect::<Vec<_>>();
+
+        for arg in named {
+            let (param_name, arg_expr) = arg.as_named_arg_expr();
+
+            let index = match params_names.iter().position(|name| name == param_name) {
+                Some(index) => index,
+                None => {
+                    let err = AstWalkError::UnknownNamedArg(self.name.clone(), param_name.clone());
+                    return Err(err);
+                }
+            };
+
+            if ordered.len() <= index {
+                ordered.resize(index + 1, None);
+            }
+
+            if ordered[index].is_some() {
+                let err = AstWalkError::DuplicateNamedArg(self.name.clone(), param_name.clone());
+                return Err(err);
+            }
+
+            ordered[index] = Some(arg_expr);
+        }
+
+        for (index, param_name) in params_names.iter().enumerate() {
+            if ordered.get(index).copied().flatten().is_none() {
+                let err = AstWalkError::MissingProcCallArg(self.name.clone(), param_name.clone());
+                return Err(err);
+            }
+        }
+
+        Ok(ordered.into_iter().flatten().collect())
+    }
 }
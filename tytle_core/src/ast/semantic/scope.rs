@@ -1,5 +1,6 @@
 use crate::ast::semantic::{SymbolId, SymbolKind};
-use std::collections::HashMap;
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
 
 pub type ScopeId = usize;
 
@@ -45,6 +46,10 @@ impl Scope {
         table.get(sym_name)
     }
 
+    pub fn names(&self, kind: &SymbolKind) -> impl Iterator<Item = &str> {
+        self.get_kind_table(kind).keys().map(|name| name.as_str())
+    }
+
     pub fn is_root_scope(&self) -> bool {
         self.parent_id.is_none()
     }
@@ -1,7 +1,11 @@
 use crate::ast::semantic::{Procedure, Variable};
-use std::fmt;
+use crate::compat::prelude::*;
+use core::fmt;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq)]
+#[derive(
+    Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
 pub struct SymbolId(pub usize);
 
 impl fmt::Display for SymbolId {
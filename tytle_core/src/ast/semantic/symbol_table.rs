@@ -1,5 +1,6 @@
 use crate::ast::semantic::*;
-use std::collections::HashMap;
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
 
 #[derive(Debug, Clone)]
 pub struct SymbolTable {
@@ -8,6 +9,9 @@ pub struct SymbolTable {
     depth_scopes_stack: HashMap<usize, Vec<ScopeId>>,
     next_scope_id: ScopeId,
     next_scope_depth: usize,
+
+    // the procedures overloading (by arity) the one registered under a scope by the same name
+    overloads: HashMap<String, Vec<SymbolId>>,
 }
 
 impl SymbolTable {
@@ -18,6 +22,7 @@ impl SymbolTable {
             depth_scopes_stack: Default::default(),
             next_scope_id: 0,
             next_scope_depth: 0,
+            overloads: Default::default(),
         };
 
         // we always start a new `SymbolTable` with a default scope (a.k.a the "root scope")
@@ -100,6 +105,14 @@ impl SymbolTable {
         symbol.unwrap().as_proc()
     }
 
+    // `None` when `proc_id` isn't a procedure symbol
+    pub fn try_get_proc_by_id(&self, proc_id: SymbolId) -> Option<&Procedure> {
+        match self.lookup_by_symbol_id(proc_id) {
+            Some(Symbol::Proc(proc)) => Some(proc),
+            _ => None,
+        }
+    }
+
     pub fn get_proc_by_id_mut(&mut self, proc_id: SymbolId) -> &mut Procedure {
         let symbol = self.lookup_by_symbol_id_mut(proc_id);
         symbol.unwrap().as_proc_mut()
@@ -161,23 +174,55 @@ impl SymbolTable {
         self.store_var(var);
     }
 
+    // a procedure named like one already under the scope is stored as its overload
+    // (the caller is expected to have checked their signatures don't collide)
     pub fn create_proc_symbol(&mut self, proc: Procedure) {
-        let proc_sym = self.lookup(self.next_scope_depth, &proc.name, &SymbolKind::Proc);
+        let scope_id = self.get_current_scope_id();
+        let proc_sym = self.lookup(scope_id, &proc.name, &SymbolKind::Proc);
 
         if proc_sym.is_some() {
-            panic!("Procedure `{}` already exists under the scope", proc.name);
+            let overloads = self.overloads.entry(proc.name.to_string()).or_default();
+            overloads.push(proc.id);
+
+            self.symbols.insert(proc.id, Symbol::Proc(proc));
+            return;
         }
 
         self.store_proc(proc);
     }
 
+    // creates a procedure symbol under the root scope (regardless of the current scope)
+    pub fn create_root_proc_symbol(&mut self, proc: Procedure) {
+        let proc_id = proc.id;
+
+        let root_scope = self.get_scope_mut(0);
+        root_scope.store(proc.name.to_string(), proc_id, &SymbolKind::Proc);
+
+        self.symbols.insert(proc_id, Symbol::Proc(proc));
+    }
+
     pub fn get_proc_by_name(&self, proc_name: &str) -> &Procedure {
         let symbol = self.lookup(0, proc_name, &SymbolKind::Proc);
         symbol.unwrap().as_proc()
     }
 
+    // all the (root) procedures named `proc_name`, in their definition order
+    pub fn get_proc_overloads(&self, proc_name: &str) -> Vec<&Procedure> {
+        let first = match self.lookup(0, proc_name, &SymbolKind::Proc) {
+            Some(symbol) => symbol.as_proc(),
+            None => return Vec::new(),
+        };
+
+        let overloads = self.overloads.get(proc_name).into_iter().flatten();
+
+        core::iter::once(first)
+            .chain(overloads.map(|proc_id| self.get_proc_by_id(*proc_id)))
+            .collect()
+    }
+
+    // the innermost open scope (not the last started one, which may have ended already)
     pub fn get_current_scope_id(&self) -> usize {
-        self.next_scope_id - 1
+        self.get_current_scope().id
     }
 
     pub fn get_next_scope_parent_id(&self) -> Option<usize> {
@@ -229,6 +274,16 @@ impl SymbolTable {
         self.symbols.insert(symbol_id, symbol);
     }
 
+    pub fn symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.values()
+    }
+
+    // stores a symbol that can only be looked up by its id (it isn't registered under any scope).
+    // used when restoring a symbol table out of its flattened form (e.g the JSON IR)
+    pub fn store_unscoped_symbol(&mut self, symbol_id: SymbolId, symbol: Symbol) {
+        self.symbols.insert(symbol_id, symbol);
+    }
+
     fn lookup_by_symbol_id(&self, symbol_id: SymbolId) -> Option<&Symbol> {
         self.symbols.get(&symbol_id)
     }
@@ -261,6 +316,24 @@ impl SymbolTable {
         }
     }
 
+    // the names of all the symbols of kind `sym_kind` visible from scope `start_scope_id`
+    pub fn visible_names(&self, start_scope_id: ScopeId, sym_kind: &SymbolKind) -> Vec<&str> {
+        let mut names = Vec::new();
+        let mut scope_id = start_scope_id;
+
+        loop {
+            let scope = self.get_scope(scope_id);
+
+            names.extend(scope.names(sym_kind));
+
+            if scope.is_root_scope() {
+                return names;
+            }
+
+            scope_id = scope.parent_id.unwrap();
+        }
+    }
+
     pub fn lookup_recur_mut(
         &mut self,
         start_scope_id: ScopeId,
@@ -2,40 +2,123 @@ use crate::ast::semantic::*;
 use crate::ast::Ast;
 use crate::ast::{expression::*, statement::*};
 
+use crate::compat::collections::HashSet;
+use crate::compat::prelude::*;
+
 pub struct SymbolTableGenerator {
     env: Environment,
+
+    // the globals declared so far by the walked top level statements (all globals are registered up front,
+    // so this is what tells an assignment preceding the declaration of its global)
+    declared_globals: HashSet<String>,
+
+    // the procedure being walked (`None` under `__main__`).
+    // procedures are told by their id rather than by their name, since they may be overloaded
+    proc_id: Option<SymbolId>,
+
+    // the number of block expressions enclosing the walked statement
+    blocks_depth: usize,
 }
 
 type EnvironmentResult = Result<Environment, AstWalkError>;
 
 impl AstWalker for SymbolTableGenerator {
-    fn on_make_global_stmt(&mut self, _ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
-        if self.env.symbol_table.is_inner_scope() {
-            let err = AstWalkError::ProcNotAllowedToDeclareGlobals(make_stmt.var_name.to_string());
+    fn on_make_global_stmt(&mut self, ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
+        if ctx_proc != "__main__" {
+            let err = AstWalkError::ProcNotAllowedToDeclareGlobals(ctx_proc.to_string());
+            Err(err)
+        } else if self.env.symbol_table.is_inner_scope() {
+            let err = AstWalkError::GlobalsNotAllowedUnderInnerScope(make_stmt.var_name.to_string());
             Err(err)
         } else {
+            self.declared_globals.insert(make_stmt.var_name.to_string());
             Ok(())
         }
     }
 
+    fn on_make_const_stmt(&mut self, ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
+        // constants are declared (and scoped) exactly like globals
+        self.on_make_global_stmt(ctx_proc, make_stmt)
+    }
+
     fn on_make_local_stmt(&mut self, ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
+        if ctx_proc == "__main__" {
+            let err = AstWalkError::LocalsNotAllowedUnderRootScope(make_stmt.var_name.to_string());
+            return Err(err);
+        }
+
         self.create_local_var_symbol(ctx_proc, make_stmt)
     }
 
-    fn on_make_assign_stmt(&mut self, _ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
+    fn on_make_assign_stmt(&mut self, ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
         let var = self.get_var_symbol(&make_stmt.var_name)?;
 
+        if var.constant {
+            let err = AstWalkError::AssignToConst(make_stmt.var_name.to_string());
+            return Err(err);
+        }
+
+        // assignments within procedures aren't checked, since when they run is known only at runtime
+        if ctx_proc == "__main__" && var.global && !self.declared_globals.contains(&var.name) {
+            let err = AstWalkError::AssignBeforeDeclaration(make_stmt.var_name.to_string());
+            return Err(err);
+        }
+
         make_stmt.var_id = Some(var.id);
 
         Ok(())
     }
 
     fn on_proc_call_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
-        let (proc_name, _proc_args, proc_id) = expr.as_proc_call_expr_mut();
+        let (proc_name, proc_args) = expr.as_proc_call_expr();
+
+        let id = self.resolve_proc_call(proc_name, proc_args.len())?;
+        self.env.expr_symbols.insert(expr.node_id, id);
 
-        let proc = self.env.symbol_table.get_proc_by_name(&proc_name);
+        Ok(())
+    }
 
-        proc_id.replace(proc.id);
+    fn on_map_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let (proc_name, _list_expr) = expr.as_map_expr();
+
+        // the mapped procedure is called with each element
+        let id = self.resolve_proc_call(proc_name, 1)?;
+        self.env.expr_symbols.insert(expr.node_id, id);
+
+        Ok(())
+    }
+
+    fn on_proc_ref_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let proc_name = expr.as_proc_ref_expr();
+
+        let id = self.resolve_proc(proc_name)?;
+        self.env.expr_symbols.insert(expr.node_id, id);
+
+        Ok(())
+    }
+
+    fn on_trace_stmt(&mut self, _ctx_proc: &str, trace_stmt: &mut TraceStmt) -> AstWalkResult {
+        let id = self.resolve_proc(&trace_stmt.proc_name)?;
+        trace_stmt.proc_id.replace(id);
+
+        Ok(())
+    }
+
+    fn on_event_stmt(&mut self, ctx_proc: &str, on_event_stmt: &mut OnEventStmt) -> AstWalkResult {
+        // only the top level handlers are registered (see `prewalk_ast`)
+        if ctx_proc != "__main__" || self.env.symbol_table.is_inner_scope() {
+            let err = AstWalkError::EventHandlerNotAtTopLevel(on_event_stmt.event.to_string());
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn on_foreach_var(&mut self, ctx_proc: &str, foreach_stmt: &mut ForeachStmt) -> AstWalkResult {
+        // the variable type is the list elements type, so it's known only once type-checking
+        let var_id = self.create_var_symbol(ctx_proc, FOREACH_VAR, None, false, false)?;
+
+        foreach_stmt.var_id = Some(var_id);
 
         Ok(())
     }
@@ -64,12 +147,20 @@ impl AstWalker for SymbolTableGenerator {
         }
     }
 
-    fn on_proc_start(&mut self, _ctx_proc: &str, _proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+    fn on_proc_start(&mut self, ctx_proc: &str, proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+        // only the top level procedures are registered (see `prewalk_ast`)
+        if ctx_proc != "__main__" || self.env.symbol_table.is_inner_scope() {
+            let err = AstWalkError::ProcNotAtTopLevel(proc_stmt.name.to_string());
+            return Err(err);
+        }
+
+        self.proc_id = proc_stmt.id;
         self.start_scope();
         Ok(())
     }
 
     fn on_proc_end(&mut self, _ctx_proc: &str, _proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+        self.proc_id = None;
         self.end_scope();
         Ok(())
     }
@@ -92,21 +183,37 @@ impl AstWalker for SymbolTableGenerator {
         Ok(())
     }
 
+    fn on_block_expr_start(
+        &mut self,
+        _ctx_proc: &str,
+        _block_stmt: &mut BlockStatement,
+    ) -> AstWalkResult {
+        self.blocks_depth += 1;
+        Ok(())
+    }
+
+    fn on_block_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        self.blocks_depth -= 1;
+        Ok(())
+    }
+
+    fn on_ret_stmt(&mut self, _ctx_proc: &str, _ret_stmt: &mut ReturnStmt) -> AstWalkResult {
+        // a block runs as a procedure of its own, so returning from it won't return its caller
+        if self.blocks_depth > 0 {
+            return Err(AstWalkError::ReturnWithinBlock);
+        }
+
+        Ok(())
+    }
+
     fn on_literal_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
-        let lit_expr: &mut LiteralExpr = expr.as_lit_expr_mut();
+        let lit_expr: &LiteralExpr = expr.as_lit_expr();
 
         match lit_expr {
-            LiteralExpr::Var(var_name, var_id) => {
-                let var = self.get_var_symbol(var_name);
-
-                if var.is_ok() {
-                    let var = var.unwrap();
+            LiteralExpr::Var(var_name) => {
+                let var_id = self.get_var_symbol(var_name)?.id;
 
-                    var_id.replace(var.id);
-                } else {
-                    // TODO: return error if variable isn't found
-                    unimplemented!()
-                }
+                self.env.expr_symbols.insert(expr.node_id, var_id);
             }
             _ => {}
         };
@@ -119,17 +226,70 @@ impl SymbolTableGenerator {
     pub fn new() -> Self {
         Self {
             env: Environment::new(),
+            declared_globals: HashSet::new(),
+            proc_id: None,
+            blocks_depth: 0,
+        }
+    }
+
+    pub fn with_env(env: Environment) -> Self {
+        // the globals registered by the embedder are declared before the program starts
+        let declared_globals = env
+            .injected_globals
+            .keys()
+            .map(|var_id| env.symbol_table.get_var_by_id(*var_id).name.clone())
+            .collect();
+
+        Self {
+            env,
+            declared_globals,
+            proc_id: None,
+            blocks_depth: 0,
         }
     }
 
     pub fn generate(mut self, ast: &mut Ast) -> EnvironmentResult {
         self.generate_main_symbol();
-        self.prewalk_ast(ast)?;
+
+        if self.env.stdlib {
+            self.generate_stdlib_symbols(ast)?;
+        } else {
+            self.prewalk_ast(ast)?;
+        }
+
         self.walk_ast(ast)?;
 
         Ok(self.env)
     }
 
+    fn generate_stdlib_symbols(&mut self, ast: &mut Ast) -> AstWalkResult {
+        let mut prelude = crate::stdlib::prelude_ast();
+
+        self.prewalk_ast(&mut prelude)?;
+
+        for stmt in prelude.statements.iter() {
+            if let Statement::Procedure(proc_stmt) = stmt {
+                let proc_id = proc_stmt.id.unwrap();
+                let proc = self.env.symbol_table.get_proc_by_id_mut(proc_id);
+
+                proc.builtin = true;
+            }
+        }
+
+        self.prewalk_ast(ast)?;
+
+        // the prelude procedures are walked (and later compiled) as if they were a part of the user code
+        let user_stmts = core::mem::replace(&mut ast.statements, prelude.statements);
+        ast.statements.extend(user_stmts);
+
+        // the prelude has no locations within the user code
+        let mut locations = vec![None; ast.statements.len() - ast.locations.len()];
+        locations.append(&mut ast.locations);
+        ast.locations = locations;
+
+        Ok(())
+    }
+
     pub fn prewalk_ast(&mut self, ast: &mut Ast) -> AstWalkResult {
         for stmt in &mut ast.statements {
             match stmt {
@@ -138,6 +298,12 @@ impl SymbolTableGenerator {
                         // only `__main__` can declare globals
                         self.create_global_var_symbol("__main__", make_stmt)?;
                     }
+                    MakeStmtKind::Const => {
+                        self.create_global_var_symbol("__main__", make_stmt)?;
+
+                        let var_id = make_stmt.var_id.unwrap();
+                        self.env.symbol_table.get_var_by_id_mut(var_id).constant = true;
+                    }
                     MakeStmtKind::Local => {
                         let err = AstWalkError::LocalsNotAllowedUnderRootScope(
                             make_stmt.var_name.clone(),
@@ -149,6 +315,9 @@ impl SymbolTableGenerator {
                 Statement::Procedure(proc_stmt) => {
                     self.create_proc_symbol(proc_stmt)?;
                 }
+                Statement::OnEvent(on_event_stmt) => {
+                    self.create_handler_symbol(on_event_stmt)?;
+                }
                 _ => continue,
             }
         }
@@ -156,6 +325,53 @@ impl SymbolTableGenerator {
         Ok(())
     }
 
+    // the id of the procedure named `proc_name`
+    // (creating its symbol when it's a not yet used primitive).
+    // an overloaded procedure can't be told without the args count (see `resolve_proc_call`)
+    fn resolve_proc(&mut self, proc_name: &str) -> Result<SymbolId, AstWalkError> {
+        let proc = self.env.symbol_table.lookup(0, proc_name, &SymbolKind::Proc);
+
+        if self.env.symbol_table.get_proc_overloads(proc_name).len() > 1 {
+            let err = AstWalkError::AmbiguousProcRef(proc_name.to_owned());
+            Err(err)
+        } else if let Some(proc) = proc {
+            Ok(proc.as_proc().id)
+        } else if let Some(prim) = Primitive::from_name(proc_name) {
+            Ok(self.env.create_primitive_proc(prim))
+        } else {
+            let suggestion = self.suggest_symbol(proc_name, SymbolKind::Proc);
+            let err = AstWalkError::MissingProcDeclaration(proc_name.to_owned(), suggestion);
+            Err(err)
+        }
+    }
+
+    // the id of the procedure named `proc_name` called with `nargs` args,
+    // choosing by the args count between the overloads of the name
+    fn resolve_proc_call(
+        &mut self,
+        proc_name: &str,
+        nargs: usize,
+    ) -> Result<SymbolId, AstWalkError> {
+        let overloads = self.env.symbol_table.get_proc_overloads(proc_name);
+
+        // a procedure which isn't overloaded is resolved regardless of the args count
+        // (so a wrong count is reported against its signature by the type checker)
+        if overloads.len() <= 1 {
+            return self.resolve_proc(proc_name);
+        }
+
+        let candidates = overloads
+            .into_iter()
+            .filter(|proc| proc.accepts(nargs))
+            .collect::<Vec<_>>();
+
+        match candidates.as_slice() {
+            [proc] => Ok(proc.id),
+            [] => Err(AstWalkError::NoProcOverload(proc_name.to_owned(), nargs)),
+            _ => Err(AstWalkError::AmbiguousProcCall(proc_name.to_owned(), nargs)),
+        }
+    }
+
     fn get_var_symbol(&self, var_name: &str) -> Result<&Variable, AstWalkError> {
         let symbol = self.try_get_symbol_recur(var_name, SymbolKind::Var);
 
@@ -166,35 +382,80 @@ impl SymbolTableGenerator {
                 panic!("symbol should have been a variable")
             }
         } else {
-            let err = AstWalkError::MissingVarDeclaration(var_name.to_owned());
+            let suggestion = self.suggest_symbol(var_name, SymbolKind::Var);
+            let err = AstWalkError::MissingVarDeclaration(var_name.to_owned(), suggestion);
             Err(err)
         }
     }
 
+    // a procedure may overload (by arity) the procedures of the same name,
+    // except for the native ones and the primitives
     fn create_proc_symbol(&mut self, proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
-        let symbol = self.try_get_symbol_recur(&proc_stmt.name, SymbolKind::Proc);
+        let ret_type_str = proc_stmt.return_type.clone();
+        let return_type = ExpressionType::from(ret_type_str.as_str());
 
-        if symbol.is_none() {
-            let ret_type_str = proc_stmt.return_type.clone();
-            let return_type = ExpressionType::from(ret_type_str.as_str());
+        let params_types = proc_stmt
+            .params
+            .iter()
+            .map(|param| ExpressionType::from(param.param_type.as_str()))
+            .collect::<Vec<ExpressionType>>();
 
-            let params_types = proc_stmt
-                .params
-                .iter()
-                .map(|param| ExpressionType::from(param.param_type.as_str()))
-                .collect::<Vec<ExpressionType>>();
+        let rest = proc_stmt.params.last().is_some_and(|param| param.rest);
 
-            let proc_id = self
-                .env
-                .create_proc(&proc_stmt.name, params_types, return_type);
+        let overloads = self.env.symbol_table.get_proc_overloads(&proc_stmt.name);
+        let collision = overloads.iter().find(|other| {
+            other.native || other.primitive.is_some() || other.collides(params_types.len(), rest)
+        });
 
-            proc_stmt.id = Some(proc_id);
+        if let Some(other) = collision {
+            let err = if other.builtin || other.native {
+                AstWalkError::BuiltinProcRedefinition(proc_stmt.name.to_owned())
+            } else {
+                AstWalkError::DuplicateProc(proc_stmt.name.to_owned())
+            };
 
-            Ok(())
-        } else {
-            let err = AstWalkError::DuplicateProc(proc_stmt.name.to_owned());
-            Err(err)
+            return Err(err);
+        }
+
+        let proc_id = self
+            .env
+            .create_proc(&proc_stmt.name, params_types, return_type);
+
+        proc_stmt.id = Some(proc_id);
+
+        let proc = self.env.symbol_table.get_proc_by_id_mut(proc_id);
+        proc.doc = proc_stmt.doc.clone();
+        proc.rest = rest;
+        proc.params_names = proc_stmt
+            .params
+            .iter()
+            .map(|param| param.param_name.clone())
+            .collect();
+
+        Ok(())
+    }
+
+    fn create_handler_symbol(&mut self, on_event_stmt: &mut OnEventStmt) -> AstWalkResult {
+        let proc_stmt = &mut on_event_stmt.proc_stmt;
+
+        // the handler procedure name is derived from the event
+        if self
+            .try_get_symbol_recur(&proc_stmt.name, SymbolKind::Proc)
+            .is_some()
+        {
+            let err = AstWalkError::DuplicateEventHandler(on_event_stmt.event.to_string());
+            return Err(err);
         }
+
+        self.create_proc_symbol(proc_stmt)?;
+
+        // handlers are called only by the host, so they're never reported as unused
+        let proc_id = proc_stmt.id.unwrap();
+        self.env.symbol_table.get_proc_by_id_mut(proc_id).builtin = true;
+
+        self.env.register_handler(&on_event_stmt.event, proc_id);
+
+        Ok(())
     }
 
     fn create_global_var_symbol(
@@ -251,15 +512,29 @@ impl SymbolTableGenerator {
         if is_global {
             self.env.create_global_var(var_id, var_name, var_type);
         } else {
-            let proc = self.env.symbol_table.get_proc_by_name(ctx_proc);
+            let proc_id = match self.proc_id {
+                Some(proc_id) => proc_id,
+                None => self.env.symbol_table.get_proc_by_name(ctx_proc).id,
+            };
 
             self.env
-                .create_local_var(proc.id, var_id, var_name, var_type, is_param);
+                .create_local_var(proc_id, var_id, var_name, var_type, is_param);
         }
 
         Ok(var_id)
     }
 
+    // the visible symbol of kind `kind` most similar to the (not found) `name`
+    fn suggest_symbol(&self, name: &str, kind: SymbolKind) -> Option<String> {
+        let current_scope_id = self.env.symbol_table.get_current_scope_id();
+        let names = self
+            .env
+            .symbol_table
+            .visible_names(current_scope_id, &kind);
+
+        crate::diagnostics::suggest(name, names.into_iter().filter(|name| *name != "__main__"))
+    }
+
     fn try_get_symbol_recur(&self, name: &str, kind: SymbolKind) -> Option<&Symbol> {
         let current_scope_id = self.env.symbol_table.get_current_scope_id();
 
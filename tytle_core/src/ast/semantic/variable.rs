@@ -1,5 +1,6 @@
-use crate::ast::expression::ExpressionType;
+use crate::ast::expression::{ExpressionType, LiteralExpr};
 use crate::ast::semantic::SymbolId;
+use crate::compat::prelude::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Variable {
@@ -12,6 +13,10 @@ pub struct Variable {
     // for global variables - `index` will be the index within the global variables
     // for local  variables - `index` will be the local index within the enclosing procedure
     pub index: Option<usize>,
+
+    // `CONST` globals can't be assigned, their value is evaluated at compile time (by the type-checker)
+    pub constant: bool,
+    pub const_value: Option<LiteralExpr>,
 }
 
 impl Variable {
@@ -35,10 +40,17 @@ impl Variable {
             name: name.to_string(),
             var_type: None,
             index: None,
+            constant: false,
+            const_value: None,
         }
     }
 
     pub fn is_param(&self) -> bool {
         self.param == true
     }
+
+    // temporaries are allocated by the compiler (see `Environment::create_tmp_var`)
+    pub fn is_tmp(&self) -> bool {
+        self.name.starts_with('$')
+    }
 }
@@ -0,0 +1,12 @@
+use crate::ast::expression::Expression;
+use crate::compat::prelude::*;
+use crate::lexer::Location;
+
+// `ASSERT <cond> "message` - stops the execution with a runtime error when `cond_expr` is false.
+// the message is optional, and `location` is the location of the `ASSERT` keyword
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssertStmt {
+    pub cond_expr: Expression,
+    pub message: Option<String>,
+    pub location: Location,
+}
@@ -1,14 +1,28 @@
 use crate::ast::statement::Statement;
+use crate::compat::prelude::*;
+use crate::lexer::Location;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct BlockStatement {
     pub stmts: Vec<Statement>,
+
+    // the location of each parsed statement (by its index within `stmts`),
+    // missing for the statements which weren't parsed from the source code
+    pub locations: Vec<Option<Location>>,
+}
+
+// the locations are a debugging aid, so blocks having the same statements are equal
+impl PartialEq for BlockStatement {
+    fn eq(&self, other: &Self) -> bool {
+        self.stmts == other.stmts
+    }
 }
 
 impl BlockStatement {
     pub fn new() -> Self {
         Self {
             stmts: Default::default(),
+            locations: Default::default(),
         }
     }
 
@@ -17,4 +31,17 @@ impl BlockStatement {
             self.stmts.push(stmt);
         }
     }
+
+    pub fn add_located_statement(&mut self, stmt: Statement, location: Location) {
+        if stmt != Statement::NOP {
+            self.locations.resize(self.stmts.len(), None);
+
+            self.stmts.push(stmt);
+            self.locations.push(Some(location));
+        }
+    }
+
+    pub fn stmt_location(&self, index: usize) -> Option<Location> {
+        self.locations.get(index).copied().flatten()
+    }
 }
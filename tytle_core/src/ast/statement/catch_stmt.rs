@@ -0,0 +1,13 @@
+use crate::ast::statement::BlockStatement;
+use crate::compat::prelude::*;
+
+// the tag caught by a `CATCH` of runtime errors, and thrown by `THROW "ERROR`
+pub const ERROR_TAG: &str = "ERROR";
+
+// `CATCH "TAG [ ... ]` - executes `block`, a `THROW "TAG` within it (or within any procedure it calls)
+// stops the block execution and resumes the execution right after the `CATCH` statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatchStmt {
+    pub tag: String,
+    pub block: BlockStatement,
+}
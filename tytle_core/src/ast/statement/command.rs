@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Command {
     XCor,
     YCor,
@@ -9,11 +11,21 @@ pub enum Command {
     HideTurtle,
     Clean,
     ClearScreen,
-    SetPenColor,
     SetBackgroundColor,
     Wait,
     Stop,
     Trap,
+
+    // draws the turtle shape at its position (see `Host::exec_stamp`)
+    Stamp,
+
+    // plays a short tone (`BEEP_FREQ` hertz for `BEEP_DURATION` ms, see `Host::play_tone`)
+    Beep,
+
+    // saves the turtle & pen state, restored by the matching `POPSTATE`
+    // (see `Host::state_restored`)
+    PushState,
+    PopState,
 }
 
 impl Command {
@@ -28,10 +40,13 @@ impl Command {
             "PENERASE" => Some(Command::PenErase),
             "CLEAN" => Some(Command::Clean),
             "CLEARSCREEN" => Some(Command::ClearScreen),
-            "SETPENCOLOR" => Some(Command::SetPenColor),
             "SETBACKGROUND" => Some(Command::SetBackgroundColor),
             "WAIT" => Some(Command::Wait),
             "STOP" => Some(Command::Stop),
+            "STAMP" => Some(Command::Stamp),
+            "BEEP" => Some(Command::Beep),
+            "PUSHSTATE" => Some(Command::PushState),
+            "POPSTATE" => Some(Command::PopState),
             _ => None,
         }
     }
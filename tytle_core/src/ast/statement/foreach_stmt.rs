@@ -0,0 +1,17 @@
+use crate::ast::expression::Expression;
+use crate::ast::semantic::SymbolId;
+use crate::ast::statement::BlockStatement;
+
+// the name of the implicit `FOREACH` iteration variable, holding the current list element
+pub const FOREACH_VAR: &str = "?";
+
+// `FOREACH LIST [ ... ]` - executes `block` once per element of `list_expr`.
+// the element is bound to the implicit variable `?`, a local of the block scope
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeachStmt {
+    pub list_expr: Expression,
+    pub block: BlockStatement,
+
+    // the iteration variable id (assigned by the symbol table generator)
+    pub var_id: Option<SymbolId>,
+}
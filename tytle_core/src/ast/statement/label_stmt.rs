@@ -0,0 +1,29 @@
+use crate::ast::expression::Expression;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LabelKind {
+    // `LABEL <text>` - draws the text at the turtle position, along its heading
+    Text,
+
+    // `SETLABELFONT <font>` - the font family of the next labels
+    Font,
+
+    // `SETLABELSIZE <size>` - the font size of the next labels
+    Size,
+}
+
+impl LabelKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            LabelKind::Text => "LABEL",
+            LabelKind::Font => "SETLABELFONT",
+            LabelKind::Size => "SETLABELSIZE",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelStmt {
+    pub kind: LabelKind,
+    pub expr: Expression,
+}
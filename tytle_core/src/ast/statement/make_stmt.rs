@@ -1,11 +1,15 @@
 use crate::ast::expression::Expression;
 use crate::ast::semantic::SymbolId;
+use crate::compat::prelude::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum MakeStmtKind {
     Global,
     Local,
     Assign,
+
+    // `CONST NAME = expr`, an immutable global whose value is evaluated at compile time
+    Const,
 }
 
 #[derive(Debug, Clone, PartialEq)]
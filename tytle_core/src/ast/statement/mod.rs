@@ -1,21 +1,39 @@
+mod assert_stmt;
 mod block_stmt;
+mod catch_stmt;
 mod command;
 mod direction;
 mod direction_stmt;
+mod foreach_stmt;
 mod if_stmt;
+mod label_stmt;
 mod make_stmt;
+mod on_event_stmt;
+mod proc_doc;
 mod procedure_stmt;
 mod repeat_stmt;
 mod return_stmt;
 mod stmt;
+mod stream_stmt;
+mod tone_stmt;
+mod trace_stmt;
 
+pub use assert_stmt::AssertStmt;
 pub use block_stmt::BlockStatement;
+pub use catch_stmt::{CatchStmt, ERROR_TAG};
 pub use command::Command;
 pub use direction::Direction;
 pub use direction_stmt::DirectionStmt;
+pub use foreach_stmt::{ForeachStmt, FOREACH_VAR};
 pub use if_stmt::IfStmt;
+pub use label_stmt::{LabelKind, LabelStmt};
 pub use make_stmt::*;
+pub use on_event_stmt::{Event, OnEventStmt};
+pub use proc_doc::ProcDoc;
 pub use procedure_stmt::{ProcParam, ProcedureStmt};
 pub use repeat_stmt::RepeatStmt;
 pub use return_stmt::ReturnStmt;
 pub use stmt::Statement;
+pub use stream_stmt::{StreamKind, StreamStmt};
+pub use tone_stmt::ToneStmt;
+pub use trace_stmt::TraceStmt;
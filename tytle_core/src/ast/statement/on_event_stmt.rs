@@ -0,0 +1,42 @@
+use crate::ast::statement::ProcedureStmt;
+use crate::compat::prelude::*;
+use core::fmt;
+
+// the host events a program may handle (see `Interpreter::dispatch_key` / `Interpreter::dispatch_tick`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Event {
+    // a key press, named by the host (e.g `"K` or `"LEFT`)
+    Key(String),
+
+    // the host timer, firing every `interval` milliseconds
+    Tick(usize),
+}
+
+impl Event {
+    // the name of the procedure generated for the event handler block.
+    // the `$` prefix makes sure it never collides with a user defined procedure
+    pub fn proc_name(&self) -> String {
+        match self {
+            Event::Key(key) => format!("$ONKEY.{}", key),
+            Event::Tick(_) => "$ONTICK".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Key(key) => write!(f, "ONKEY \"{}", key),
+            Event::Tick(interval) => write!(f, "ONTICK {}", interval),
+        }
+    }
+}
+
+// `ONKEY "K [ ... ]` / `ONTICK 100 [ ... ]` - registers the block as the handler of `event`.
+// the block is parsed into a `UNIT` procedure with no params, so it's analyzed and compiled like any other
+// procedure, and it's executed only when the host dispatches the event
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnEventStmt {
+    pub event: Event,
+    pub proc_stmt: ProcedureStmt,
+}
@@ -0,0 +1,90 @@
+// the documentation of a procedure, given by the `;;` doc comment lines preceding its `TO`:
+//
+// ```
+// ;; Draws a regular polygon.
+// ;; @param N the number of sides
+// ;; @param SIZE the length of each side
+// TO POLYGON(N: INT, SIZE: INT)
+// ```
+//
+// lines starting with `@param NAME` describe the parameter `NAME`, the rest make up the summary
+use crate::compat::prelude::*;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcDoc {
+    pub summary: String,
+    pub params: Vec<(String, String)>,
+}
+
+impl ProcDoc {
+    // builds the documentation out of the doc comment lines (without their leading `;;`)
+    pub fn parse(lines: &[String]) -> Self {
+        let mut doc = Self::default();
+        let mut summary = Vec::new();
+
+        for line in lines {
+            let line = line.trim();
+
+            match line.strip_prefix("@param ") {
+                Some(param) => {
+                    let param = param.trim_start();
+
+                    let (name, description) = match param.find(char::is_whitespace) {
+                        Some(end) => (&param[..end], param[end..].trim()),
+                        None => (param, ""),
+                    };
+
+                    doc.params.push((name.to_string(), description.to_string()));
+                }
+                None => summary.push(line),
+            }
+        }
+
+        doc.summary = summary.join("\n").trim().to_string();
+        doc
+    }
+
+    // the description of parameter `param_name` (if documented)
+    pub fn param(&self, param_name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(name, _)| name == param_name)
+            .map(|(_, description)| description.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    pub fn proc_doc_parse() {
+        let doc = ProcDoc::parse(&lines(&[
+            "Draws a regular polygon.",
+            "",
+            "The turtle ends up where it started.",
+            "@param N the number of sides",
+            "@param  SIZE   the length of each side ",
+        ]));
+
+        assert_eq!(
+            "Draws a regular polygon.\n\nThe turtle ends up where it started.",
+            doc.summary
+        );
+        assert_eq!(Some("the number of sides"), doc.param("N"));
+        assert_eq!(Some("the length of each side"), doc.param("SIZE"));
+        assert_eq!(None, doc.param("ANGLE"));
+    }
+
+    #[test]
+    pub fn proc_doc_parse_undescribed_param() {
+        let doc = ProcDoc::parse(&lines(&["@param N"]));
+
+        assert_eq!("", doc.summary);
+        assert_eq!(vec![("N".to_string(), "".to_string())], doc.params);
+    }
+}
@@ -1,11 +1,30 @@
 use crate::ast::semantic::SymbolId;
-use crate::ast::statement::BlockStatement;
-use std::default::Default;
+use crate::ast::statement::{BlockStatement, ProcDoc};
+use crate::compat::prelude::*;
+use core::default::Default;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ProcParam {
     pub param_name: String,
     pub param_type: String,
+
+    // whether it's a trailing rest param (`NUMS...: INT`), receiving the remaining args
+    // as a list. its `param_type` is the list type (e.g `[INT]`)
+    pub rest: bool,
+}
+
+impl ProcParam {
+    // the param as written in the procedure's signature (e.g `SIZE: INT` or `NUMS...: INT`)
+    pub fn signature(&self) -> String {
+        match self.rest {
+            true => format!(
+                "{}...: {}",
+                self.param_name,
+                &self.param_type[1..self.param_type.len() - 1]
+            ),
+            false => format!("{}: {}", self.param_name, self.param_type),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -15,6 +34,9 @@ pub struct ProcedureStmt {
     pub params: Vec<ProcParam>,
     pub return_type: String,
     pub block: BlockStatement,
+
+    // the procedure's `;;` doc comment (if any)
+    pub doc: Option<ProcDoc>,
 }
 
 impl ProcedureStmt {
@@ -25,6 +47,7 @@ impl ProcedureStmt {
             params: Default::default(),
             return_type: "".to_string(),
             block: BlockStatement::new(),
+            doc: None,
         }
     }
 }
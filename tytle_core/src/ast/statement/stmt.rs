@@ -1,8 +1,10 @@
 use crate::ast::expression::Expression;
 
 use crate::ast::statement::{
-    Command, DirectionStmt, IfStmt, MakeStmt, ProcedureStmt, RepeatStmt, ReturnStmt,
+    AssertStmt, CatchStmt, Command, DirectionStmt, ForeachStmt, IfStmt, LabelStmt, MakeStmt,
+    OnEventStmt, ProcedureStmt, RepeatStmt, ReturnStmt, StreamStmt, ToneStmt, TraceStmt,
 };
+use crate::compat::prelude::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Statement {
@@ -12,11 +14,33 @@ pub enum Statement {
     Print(Expression),
     Command(Command),
     Direction(DirectionStmt),
+    Label(LabelStmt),
+
+    // `SETSHAPE <name>` (see `TurtleShape::parse`)
+    SetShape(Expression),
+
+    // `SETPENCOLOR <color>`, the color may also be a palette index or a color name
+    SetPenColor(Expression),
+    Tone(ToneStmt),
+
+    // `OPENWRITE`, `SETWRITE` and `CLOSE` of a host stream (see `Host::open_stream`)
+    Stream(StreamStmt),
+
+    // `RUN <block>`, runs a block value (see `ExpressionAst::Block`)
+    Run(Expression),
+
     Make(MakeStmt),
     If(IfStmt),
     Repeat(RepeatStmt),
+    Foreach(ForeachStmt),
     Procedure(ProcedureStmt),
+    OnEvent(OnEventStmt),
+    Catch(CatchStmt),
+    Throw(String),
+    Assert(AssertStmt),
+    Trace(TraceStmt),
     Return(ReturnStmt),
+    Load(String),
 }
 
 impl Statement {
@@ -0,0 +1,29 @@
+use crate::ast::expression::Expression;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StreamKind {
+    // `OPENWRITE <name>` - opens (or truncates) a host stream of the name
+    Open,
+
+    // `SETWRITE <name>` - the next `PRINT`s write to the open stream (to the console for `"`)
+    Set,
+
+    // `CLOSE <name>` - closes the open stream, `PRINT` writes to the console again if it was set
+    Close,
+}
+
+impl StreamKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            StreamKind::Open => "OPENWRITE",
+            StreamKind::Set => "SETWRITE",
+            StreamKind::Close => "CLOSE",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamStmt {
+    pub kind: StreamKind,
+    pub expr: Expression,
+}
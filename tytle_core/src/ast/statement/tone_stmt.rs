@@ -0,0 +1,9 @@
+use crate::ast::expression::Expression;
+
+// `TONE <freq> <duration>` - plays a tone of `freq` hertz for `duration` milliseconds
+// (see `Host::play_tone`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToneStmt {
+    pub freq_expr: Expression,
+    pub duration_expr: Expression,
+}
@@ -0,0 +1,15 @@
+use crate::ast::semantic::SymbolId;
+use crate::compat::prelude::*;
+
+// `TRACE "NAME` / `UNTRACE "NAME` - starts (or stops) tracing the calls of procedure `NAME`.
+// a traced procedure reports each call args and its return value to the host (see `Host::exec_trace`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceStmt {
+    pub proc_name: String,
+
+    // filled-in when generating the symbol table
+    pub proc_id: Option<SymbolId>,
+
+    // `false` for `UNTRACE`
+    pub enabled: bool,
+}
@@ -0,0 +1,86 @@
+// running many programs at once, e.g a grader processing the submissions of a whole class:
+//
+// ```
+// let outcomes = tytle::batch::run_all(&submissions, &BatchOptions::default());
+// ```
+//
+// the programs are spread over a pool of threads. each program is compiled & executed on its own
+// (over a fresh `RecordingHost`), with the limits of `BatchOptions::run` applying to each program
+// separately, so a program looping forever doesn't starve the others
+
+use crate::runner::{RunOptions, RunSummary};
+use crate::vm::{DummyHost, HostCall, RecordingHost};
+use crate::TytleError;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    // the options of each program's execution
+    pub run: RunOptions,
+
+    // the number of threads (the available parallelism when `None`)
+    pub threads: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct BatchOutcome {
+    pub result: Result<RunSummary, TytleError>,
+
+    // the host-calls made by the program (up to its failure)
+    pub host_calls: Vec<HostCall>,
+}
+
+// runs the `programs` in parallel, the outcomes are in the order of the `programs`
+pub fn run_all<S>(programs: &[S], options: &BatchOptions) -> Vec<BatchOutcome>
+where
+    S: AsRef<str> + Sync,
+{
+    let threads = options
+        .threads
+        .or_else(|| thread::available_parallelism().ok().map(NonZeroUsize::get))
+        .unwrap_or(1)
+        .clamp(1, programs.len().max(1));
+
+    // the index of the next program to run
+    let next = AtomicUsize::new(0);
+
+    let mut outcomes = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut outcomes = Vec::new();
+
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+
+                        match programs.get(index) {
+                            Some(source) => {
+                                outcomes.push((index, run_one(source.as_ref(), options)))
+                            }
+                            None => return outcomes,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+
+    outcomes.sort_by_key(|(index, _)| *index);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+fn run_one(source: &str, options: &BatchOptions) -> BatchOutcome {
+    let mut host = RecordingHost::new(DummyHost::new());
+    let result = crate::run(source, &mut host, options.run.clone());
+
+    let (_, host_calls) = host.into_parts();
+
+    BatchOutcome { result, host_calls }
+}
@@ -0,0 +1,113 @@
+use crate::compat::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockProgram {
+    pub blocks: Vec<BlockStmt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BlockStmt {
+    Forward {
+        value: BlockValue,
+    },
+    Backward {
+        value: BlockValue,
+    },
+    Left {
+        value: BlockValue,
+    },
+    Right {
+        value: BlockValue,
+    },
+    Setx {
+        value: BlockValue,
+    },
+    Sety {
+        value: BlockValue,
+    },
+    Command {
+        name: String,
+    },
+    Print {
+        value: BlockValue,
+    },
+    MakeGlobal {
+        name: String,
+        value: BlockValue,
+    },
+    MakeLocal {
+        name: String,
+        value: BlockValue,
+    },
+    Make {
+        name: String,
+        value: BlockValue,
+    },
+    Repeat {
+        times: BlockValue,
+        body: Vec<BlockStmt>,
+    },
+    If {
+        condition: BlockValue,
+        then: Vec<BlockStmt>,
+        #[serde(rename = "else", default)]
+        otherwise: Option<Vec<BlockStmt>>,
+    },
+    Procedure {
+        name: String,
+        #[serde(default)]
+        params: Vec<BlockParam>,
+        #[serde(default)]
+        returns: Option<String>,
+        body: Vec<BlockStmt>,
+    },
+    Call {
+        name: String,
+        #[serde(default)]
+        args: Vec<BlockValue>,
+    },
+    Return {
+        value: BlockValue,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockParam {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub param_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BlockValue {
+    Int(usize),
+    Bool(bool),
+    Node(Box<BlockValueNode>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BlockValueNode {
+    Var {
+        name: String,
+    },
+    Op {
+        op: String,
+        left: BlockValue,
+        right: BlockValue,
+    },
+    Not {
+        value: BlockValue,
+    },
+    Parens {
+        value: BlockValue,
+    },
+    Call {
+        name: String,
+        #[serde(default)]
+        args: Vec<BlockValue>,
+    },
+}
@@ -0,0 +1,154 @@
+use crate::ast::expression::{BinaryOp, Expression};
+use crate::ast::program_builder::{binary, call, not, parens, var};
+use crate::ast::statement::Command;
+use crate::ast::{Ast, ProgramBuilder};
+use crate::blocks::{BlockProgram, BlockStmt, BlockValue, BlockValueNode, ImportError};
+use crate::compat::prelude::*;
+
+pub type ImportResult<T> = Result<T, ImportError>;
+
+pub struct BlocksImporter;
+
+impl BlocksImporter {
+    pub fn import_json(json: &str) -> ImportResult<Ast> {
+        let program: BlockProgram =
+            serde_json::from_str(json).map_err(|e| ImportError::InvalidJson(e.to_string()))?;
+
+        Self::import(&program)
+    }
+
+    pub fn import(program: &BlockProgram) -> ImportResult<Ast> {
+        let builder = Self::import_blocks(ProgramBuilder::new(), &program.blocks)?;
+
+        Ok(builder.build())
+    }
+
+    fn import_blocks(
+        builder: ProgramBuilder,
+        blocks: &[BlockStmt],
+    ) -> ImportResult<ProgramBuilder> {
+        blocks.iter().try_fold(builder, Self::import_block)
+    }
+
+    // nested blocks (loop bodies, procedures, ...) are imported ahead,
+    // since the `ProgramBuilder` closures can't fail
+    fn import_body(blocks: &[BlockStmt]) -> ImportResult<ProgramBuilder> {
+        Self::import_blocks(ProgramBuilder::new(), blocks)
+    }
+
+    fn import_block(builder: ProgramBuilder, block: &BlockStmt) -> ImportResult<ProgramBuilder> {
+        let builder = match block {
+            BlockStmt::Forward { value } => builder.forward(Self::import_value(value)?),
+            BlockStmt::Backward { value } => builder.backward(Self::import_value(value)?),
+            BlockStmt::Left { value } => builder.left(Self::import_value(value)?),
+            BlockStmt::Right { value } => builder.right(Self::import_value(value)?),
+            BlockStmt::Setx { value } => builder.setx(Self::import_value(value)?),
+            BlockStmt::Sety { value } => builder.sety(Self::import_value(value)?),
+            BlockStmt::Command { name } => {
+                let cmd = Command::parse(name)
+                    .ok_or_else(|| ImportError::UnknownCommand(name.to_owned()))?;
+
+                builder.command(cmd)
+            }
+            BlockStmt::Print { value } => builder.print(Self::import_value(value)?),
+            BlockStmt::MakeGlobal { name, value } => {
+                builder.make_global(name, Self::import_value(value)?)
+            }
+            BlockStmt::MakeLocal { name, value } => {
+                builder.make_local(name, Self::import_value(value)?)
+            }
+            BlockStmt::Make { name, value } => builder.make(name, Self::import_value(value)?),
+            BlockStmt::Repeat { times, body } => {
+                let body = Self::import_body(body)?;
+
+                builder.repeat(Self::import_value(times)?, |_| body)
+            }
+            BlockStmt::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let cond = Self::import_value(condition)?;
+                let then = Self::import_body(then)?;
+
+                match otherwise {
+                    None => builder.if_then(cond, |_| then),
+                    Some(otherwise) => {
+                        let otherwise = Self::import_body(otherwise)?;
+
+                        builder.if_else(cond, |_| then, |_| otherwise)
+                    }
+                }
+            }
+            BlockStmt::Procedure {
+                name,
+                params,
+                returns,
+                body,
+            } => {
+                let params = params
+                    .iter()
+                    .map(|param| {
+                        Self::validate_type(&param.param_type)?;
+                        Ok((param.name.as_str(), param.param_type.as_str()))
+                    })
+                    .collect::<ImportResult<Vec<(&str, &str)>>>()?;
+
+                let return_type = returns.as_deref().unwrap_or("UNIT");
+                Self::validate_type(return_type)?;
+
+                let body = Self::import_body(body)?;
+
+                builder.procedure(name, &params, return_type, |_| body)
+            }
+            BlockStmt::Call { name, args } => builder.call_proc(name, Self::import_values(args)?),
+            BlockStmt::Return { value } => builder.ret(Self::import_value(value)?),
+        };
+
+        Ok(builder)
+    }
+
+    fn import_values(values: &[BlockValue]) -> ImportResult<Vec<Expression>> {
+        values.iter().map(Self::import_value).collect()
+    }
+
+    fn import_value(value: &BlockValue) -> ImportResult<Expression> {
+        let node = match value {
+            BlockValue::Int(v) => return Ok(Expression::from(*v)),
+            BlockValue::Bool(v) => return Ok(Expression::from(*v)),
+            BlockValue::Node(node) => node,
+        };
+
+        let expr = match node.as_ref() {
+            BlockValueNode::Var { name } => var(name),
+            BlockValueNode::Op { op, left, right } => {
+                let bin_op = Self::import_op(op)?;
+
+                binary(
+                    bin_op,
+                    Self::import_value(left)?,
+                    Self::import_value(right)?,
+                )
+            }
+            BlockValueNode::Not { value } => not(Self::import_value(value)?),
+            BlockValueNode::Parens { value } => parens(Self::import_value(value)?),
+            BlockValueNode::Call { name, args } => call(name, Self::import_values(args)?),
+        };
+
+        Ok(expr)
+    }
+
+    fn import_op(op: &str) -> ImportResult<BinaryOp> {
+        match op {
+            "AND" | "OR" | "+" | "*" | "/" | ">" | "<" => Ok(BinaryOp::from(op)),
+            _ => Err(ImportError::UnknownOperator(op.to_owned())),
+        }
+    }
+
+    fn validate_type(type_str: &str) -> ImportResult<()> {
+        match type_str {
+            "INT" | "BOOL" | "STR" | "UNIT" => Ok(()),
+            _ => Err(ImportError::UnknownType(type_str.to_owned())),
+        }
+    }
+}
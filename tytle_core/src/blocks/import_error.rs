@@ -0,0 +1,41 @@
+use crate::compat::prelude::*;
+use core::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportError {
+    // the input isn't a valid blocks program (malformed JSON, unknown block type, missing field, ...)
+    InvalidJson(String),
+    UnknownCommand(String),
+    UnknownOperator(String),
+    UnknownType(String),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::InvalidJson(msg) => write!(f, "Invalid blocks program: {}", msg),
+            ImportError::UnknownCommand(cmd) => write!(f, "Unknown command block: `{}`", cmd),
+            ImportError::UnknownOperator(op) => write!(f, "Unknown operator: `{}`", op),
+            ImportError::UnknownType(t) => write!(f, "Unknown type: `{}`", t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn import_error_unknown_command() {
+        let err = ImportError::UnknownCommand("JUMP".to_string());
+
+        assert_eq!("Unknown command block: `JUMP`", err.to_string());
+    }
+
+    #[test]
+    pub fn import_error_unknown_operator() {
+        let err = ImportError::UnknownOperator("-".to_string());
+
+        assert_eq!("Unknown operator: `-`", err.to_string());
+    }
+}
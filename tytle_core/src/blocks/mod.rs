@@ -0,0 +1,44 @@
+// importing block-based programs (Blockly / Scratch-like editors) into a tytle `Ast`.
+//
+// a program is a JSON object holding a list of blocks:
+//
+// ```json
+// {
+//   "blocks": [
+//     { "type": "make_global", "name": "SIZE", "value": 50 },
+//     { "type": "repeat", "times": 4, "body": [
+//         { "type": "forward", "value": { "type": "var", "name": "SIZE" } },
+//         { "type": "right", "value": 90 }
+//     ] }
+//   ]
+// }
+// ```
+//
+// statement blocks (the `type` field):
+// * `forward` / `backward` / `left` / `right` / `setx` / `sety`: `value`
+// * `command`: `name` (a tytle command, e.g `PENUP`)
+// * `print`: `value`
+// * `make_global` / `make_local` / `make`: `name`, `value`
+// * `repeat`: `times`, `body`
+// * `if`: `condition`, `then`, optional `else`
+// * `procedure`: `name`, `params` (a list of `{ "name", "type" }`), optional `returns`, `body`
+// * `call`: `name`, `args`
+// * `return`: `value`
+//
+// value blocks are either a JSON integer, a JSON boolean or an object with a `type` field:
+// * `var`: `name`
+// * `op`: `op` (one of `+`, `*`, `/`, `>`, `<`, `AND`, `OR`), `left`, `right`
+// * `not`: `value`
+// * `parens`: `value`
+// * `call`: `name`, `args`
+//
+// names and types are given as in the tytle source code (`INT`, `BOOL`, ...).
+// the imported `Ast` should go through the same semantic analysis as a parsed one
+
+mod block;
+mod blocks_importer;
+mod import_error;
+
+pub use block::{BlockParam, BlockProgram, BlockStmt, BlockValue, BlockValueNode};
+pub use blocks_importer::BlocksImporter;
+pub use import_error::ImportError;
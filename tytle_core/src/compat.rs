@@ -0,0 +1,107 @@
+// the parts of `std` used by the crate, taken from `core` & `alloc` (and a few small crates)
+// when building without the `std` feature (see the `alloc` feature in `Cargo.toml`)
+
+// the `std` prelude items which aren't part of the `core` prelude
+pub(crate) mod prelude {
+    pub use alloc::borrow::ToOwned;
+    pub use alloc::boxed::Box;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec::Vec;
+}
+
+pub(crate) mod collections {
+    pub use alloc::collections::*;
+
+    #[cfg(not(feature = "std"))]
+    pub use hashbrown::{HashMap, HashSet};
+    #[cfg(feature = "std")]
+    pub use std::collections::{HashMap, HashSet};
+}
+
+// the `f64` methods implemented by `std` only (`libm` implements them for `no_std`)
+pub(crate) mod math {
+    #[cfg(feature = "std")]
+    pub fn floor(x: f64) -> f64 {
+        x.floor()
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn floor(x: f64) -> f64 {
+        libm::floor(x)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn round(x: f64) -> f64 {
+        x.round()
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn round(x: f64) -> f64 {
+        libm::round(x)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn rem_euclid(x: f64, y: f64) -> f64 {
+        x.rem_euclid(y)
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn rem_euclid(x: f64, y: f64) -> f64 {
+        let r = libm::fmod(x, y);
+
+        if r < 0.0 {
+            r + y.abs()
+        } else {
+            r
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn sqrt(x: f64) -> f64 {
+        x.sqrt()
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn sqrt(x: f64) -> f64 {
+        libm::sqrt(x)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn atan2(y: f64, x: f64) -> f64 {
+        y.atan2(x)
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn atan2(y: f64, x: f64) -> f64 {
+        libm::atan2(y, x)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn sin_cos(x: f64) -> (f64, f64) {
+        x.sin_cos()
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn sin_cos(x: f64) -> (f64, f64) {
+        libm::sincos(x)
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) use std::time::Instant;
+
+// there's no clock without `std`, so the profiled host-calls take no time
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Instant;
+
+#[cfg(not(feature = "std"))]
+impl Instant {
+    pub fn now() -> Self {
+        Instant
+    }
+
+    pub fn elapsed(&self) -> core::time::Duration {
+        core::time::Duration::ZERO
+    }
+}
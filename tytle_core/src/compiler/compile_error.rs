@@ -0,0 +1,153 @@
+use crate::ast::semantic::AstWalkError;
+use crate::compat::prelude::*;
+use crate::lexer::{LexError, Location};
+use crate::parser::ParseError;
+use crate::vm::Capability;
+use core::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum CompileError {
+    Lex(LexError),
+    Parse {
+        error: ParseError,
+        location: Location,
+    },
+    Semantic(AstWalkError),
+    TooManyProcParams {
+        proc_name: String,
+        max: usize,
+        actual: usize,
+    },
+    // a local variable read along a path on which it may have not been assigned yet
+    // (see `DefiniteAssignment`)
+    UnassignedLocal {
+        proc_name: String,
+        var_name: String,
+    },
+    // a procedure returning a value whose code may reach its `END`
+    MissingReturn {
+        proc_name: String,
+    },
+    // a feature (e.g `TONE`) requiring a capability which isn't enabled (see `CompileOptions`)
+    CapabilityDisabled {
+        capability: Capability,
+        feature: String,
+    },
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompileError::Lex(err) => write!(f, "{} (at {})", err, err.location()),
+            CompileError::Parse { error, location } => {
+                write!(f, "{} (at {})", error.to_string(), location)
+            }
+            CompileError::Semantic(err) => write!(f, "{}", err.to_string()),
+            CompileError::TooManyProcParams {
+                proc_name,
+                max,
+                actual,
+            } => write!(
+                f,
+                "Too many parameters for procedure `{}` (max: {}, actual: {})",
+                proc_name, max, actual
+            ),
+            CompileError::UnassignedLocal {
+                proc_name,
+                var_name,
+            } => write!(
+                f,
+                "Local variable `{}` may be used before being assigned (procedure `{}`)",
+                var_name, proc_name
+            ),
+            CompileError::MissingReturn { proc_name } => write!(
+                f,
+                "Procedure `{}` may end without returning a value",
+                proc_name
+            ),
+            CompileError::CapabilityDisabled {
+                capability,
+                feature,
+            } => write!(
+                f,
+                "`{}` requires the `{}` capability, which is disabled",
+                feature,
+                capability.name()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn compile_error_too_many_proc_params() {
+        let err = CompileError::TooManyProcParams {
+            proc_name: "MYPROC".to_string(),
+            max: 2,
+            actual: 3,
+        };
+
+        assert_eq!(
+            "Too many parameters for procedure `MYPROC` (max: 2, actual: 3)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    pub fn compile_error_unassigned_local() {
+        let err = CompileError::UnassignedLocal {
+            proc_name: "MYPROC".to_string(),
+            var_name: "A".to_string(),
+        };
+
+        assert_eq!(
+            "Local variable `A` may be used before being assigned (procedure `MYPROC`)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    pub fn compile_error_missing_return() {
+        let err = CompileError::MissingReturn {
+            proc_name: "MYPROC".to_string(),
+        };
+
+        assert_eq!(
+            "Procedure `MYPROC` may end without returning a value",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    pub fn compile_error_capability_disabled() {
+        let err = CompileError::CapabilityDisabled {
+            capability: Capability::Sound,
+            feature: "TONE".to_string(),
+        };
+
+        assert_eq!(
+            "`TONE` requires the `sound` capability, which is disabled",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    pub fn compile_error_parse_error_location() {
+        let err = CompileError::Parse {
+            error: ParseError::MissingColon,
+            location: Location(3, 7),
+        };
+
+        assert_eq!("Missing colon (at 3:7)", err.to_string());
+    }
+
+    #[test]
+    pub fn compile_error_wraps_semantic_error() {
+        let err = CompileError::Semantic(AstWalkError::DuplicateProc("MYPROC".to_string()));
+
+        assert_eq!("Duplicate procedure: `MYPROC`", err.to_string());
+    }
+}
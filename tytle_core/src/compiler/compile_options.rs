@@ -0,0 +1,35 @@
+use crate::ir::OptLevel;
+use crate::vm::Capabilities;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompileOptions {
+    // the optimization passes to run over the generated CFG
+    pub opt_level: OptLevel,
+
+    // whether to keep the checked `Ast` and the `DebugInfo` tables within the `CompiledProgram`
+    // (for pretty-printing, stack traces, breakpoints & profiling)
+    pub debug_info: bool,
+
+    // the maximum number of parameters a procedure may declare (unlimited when `None`)
+    pub max_proc_params: Option<usize>,
+
+    // whether the stdlib prelude procedures are available to the program
+    pub stdlib: bool,
+
+    // the host-facing features the program may use (all of them by default)
+    pub capabilities: Capabilities,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            opt_level: OptLevel::None,
+            debug_info: false,
+            max_proc_params: None,
+            stdlib: false,
+            capabilities: Capabilities::ALL,
+        }
+    }
+}
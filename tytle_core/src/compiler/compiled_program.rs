@@ -0,0 +1,34 @@
+use crate::ast::semantic::Environment;
+use crate::ast::Ast;
+use crate::ir::{CfgObject, DebugInfo};
+use crate::vm::{Host, Interpreter};
+
+// the output of `Compiler`: everything needed in order to execute a program
+pub struct CompiledProgram {
+    pub env: Environment,
+    pub cfg: CfgObject,
+
+    // the checked `Ast` (kept only when compiling with `debug_info`)
+    pub ast: Option<Ast>,
+
+    // the locations, variables names and procedures boundaries (kept only with `debug_info`)
+    pub debug_info: Option<DebugInfo>,
+}
+
+impl CompiledProgram {
+    pub fn interpreter<'host>(&self, host: &'host mut dyn Host) -> Interpreter<'_, '_, 'host> {
+        let mut interpreter = Interpreter::new(&self.cfg, &self.env, host);
+
+        if let Some(ref debug_info) = self.debug_info {
+            interpreter.set_debug_info(debug_info);
+        }
+
+        interpreter
+    }
+
+    // drops everything not needed in order to execute the program (e.g for production bytecode)
+    pub fn strip_debug_info(&mut self) {
+        self.ast = None;
+        self.debug_info = None;
+    }
+}
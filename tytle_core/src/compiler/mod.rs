@@ -0,0 +1,17 @@
+// the whole compilation pipeline behind a single call:
+// source code => parser => semantic analysis (symbols & types) => CFG code generation => optimizations
+//
+// ```
+// let program = Compiler::compile("FORWARD 10", CompileOptions::default())?;
+// let mut intr = program.interpreter(&mut host);
+// ```
+
+mod compile_error;
+mod compile_options;
+mod compiled_program;
+mod tytle_compiler;
+
+pub use compile_error::CompileError;
+pub use compile_options::CompileOptions;
+pub use compiled_program::CompiledProgram;
+pub use tytle_compiler::Compiler;
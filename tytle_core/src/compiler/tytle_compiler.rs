@@ -0,0 +1,224 @@
+use crate::ast::expression::ExpressionType;
+use crate::ast::semantic::{AstTypeCheck, Environment, SymbolTableGenerator};
+use crate::ast::statement::{BlockStatement, IfStmt, Statement};
+use crate::ast::Ast;
+use crate::compat::prelude::*;
+use crate::compiler::{CompileError, CompileOptions, CompiledProgram};
+use crate::ir::{CfgBuilder, CfgObject, DefiniteAssignment};
+use crate::lexer::TytleLexer;
+use crate::parser::{ParserConfig, TytleParser};
+use crate::vm::{Capabilities, Capability, MemoryValue};
+
+pub struct Compiler;
+
+impl Compiler {
+    pub fn compile(source: &str, options: CompileOptions) -> Result<CompiledProgram, CompileError> {
+        Self::compile_with_config(source, options, &ParserConfig::default())
+    }
+
+    // compiles `source` written using the translated keywords of `parser_config`
+    pub fn compile_with_config(
+        source: &str,
+        options: CompileOptions,
+        parser_config: &ParserConfig,
+    ) -> Result<CompiledProgram, CompileError> {
+        let ast = Self::parse(source, parser_config)?;
+
+        Self::compile_ast(ast, options)
+    }
+
+    // compiles `source` along with the globals injected by the embedder
+    // (see `Environment::register_global`)
+    pub fn compile_with_globals(
+        source: &str,
+        options: CompileOptions,
+        globals: &[(String, MemoryValue)],
+    ) -> Result<CompiledProgram, CompileError> {
+        let ast = Self::parse(source, &ParserConfig::default())?;
+
+        Self::compile_ast_with_globals(ast, options, globals)
+    }
+
+    // compiles an already parsed (or programmatically built) `Ast`
+    pub fn compile_ast(ast: Ast, options: CompileOptions) -> Result<CompiledProgram, CompileError> {
+        Self::compile_ast_with_globals(ast, options, &[])
+    }
+
+    pub fn compile_ast_with_globals(
+        mut ast: Ast,
+        options: CompileOptions,
+        globals: &[(String, MemoryValue)],
+    ) -> Result<CompiledProgram, CompileError> {
+        if let Some(max) = options.max_proc_params {
+            Self::check_proc_params(&ast, max)?;
+        }
+
+        let caps = options.capabilities;
+
+        if !caps.contains(Capability::Load) && Self::has_load(&ast) {
+            return Err(CompileError::CapabilityDisabled {
+                capability: Capability::Load,
+                feature: "LOAD".to_string(),
+            });
+        }
+
+        let mut env = match options.stdlib {
+            true => Environment::with_stdlib(),
+            false => Environment::new(),
+        };
+
+        for (name, value) in globals.iter() {
+            env.register_global(name, value.clone()).map_err(CompileError::Semantic)?;
+        }
+
+        let mut env = Self::analyze(&mut ast, env)?;
+
+        let builder = CfgBuilder::new(&mut env).opt_level(options.opt_level);
+        let (cfg, debug_info) = builder.build_with_debug_info(&ast);
+
+        Self::check_definite_assignment(&cfg, &env)?;
+        Self::check_capabilities(&cfg, &env, caps)?;
+
+        let (ast, debug_info) = match options.debug_info {
+            true => (Some(ast), Some(debug_info)),
+            false => (None, None),
+        };
+
+        Ok(CompiledProgram {
+            env,
+            cfg,
+            ast,
+            debug_info,
+        })
+    }
+
+    fn parse(source: &str, parser_config: &ParserConfig) -> Result<Ast, CompileError> {
+        enter_span!("parse", len = source.len());
+
+        TytleLexer::check_with_tab_width(source, parser_config.tab_width)
+            .map_err(CompileError::Lex)?;
+
+        TytleParser
+            .parse_located_with_config(source, parser_config)
+            .map_err(|(error, location)| CompileError::Parse { error, location })
+    }
+
+    // generates the symbols of `ast` (within `env`) and type checks it
+    fn analyze(ast: &mut Ast, env: Environment) -> Result<Environment, CompileError> {
+        enter_span!("analyze", stmts = ast.statements.len());
+
+        let generator = SymbolTableGenerator::with_env(env);
+        let mut env = generator.generate(ast).map_err(CompileError::Semantic)?;
+
+        let mut checker = AstTypeCheck::new(&mut env);
+        checker.check(ast).map_err(CompileError::Semantic)?;
+
+        Self::check_proc_returns(ast, &env)?;
+
+        Ok(env)
+    }
+
+    // the symbols generation makes sure each local is declared before being used,
+    // this verifies it also holds along each path of the built (and optimized) CFG
+    fn check_definite_assignment(cfg: &CfgObject, env: &Environment) -> Result<(), CompileError> {
+        match DefiniteAssignment::analyze_program(cfg, env).first() {
+            Some(unassigned) => Err(CompileError::UnassignedLocal {
+                proc_name: env.symbol_table.get_proc_by_id(unassigned.proc_id).name.clone(),
+                var_name: env.symbol_table.get_var_by_id(unassigned.var_id).name.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn has_load(ast: &Ast) -> bool {
+        ast.statements
+            .iter()
+            .any(|stmt| matches!(stmt, Statement::Load(_)))
+    }
+
+    // the features the program uses (including the code it never reaches), against `caps`
+    fn check_capabilities(
+        cfg: &CfgObject,
+        env: &Environment,
+        caps: Capabilities,
+    ) -> Result<(), CompileError> {
+        let mut used = Vec::new();
+
+        if !env.key_handlers.is_empty() {
+            used.push((Capability::Input, "ONKEY"));
+        }
+
+        if env.tick_handler.is_some() {
+            used.push((Capability::Clock, "ONTICK"));
+        }
+
+        let mut node_ids = cfg.graph.nodes.keys().collect::<Vec<_>>();
+        node_ids.sort();
+
+        let insts = node_ids
+            .into_iter()
+            .flat_map(|node_id| cfg.graph.nodes[node_id].insts.iter());
+
+        used.extend(insts.filter_map(Capability::required_by));
+
+        match used.into_iter().find(|(cap, _)| !caps.contains(*cap)) {
+            Some((capability, feature)) => Err(CompileError::CapabilityDisabled {
+                capability,
+                feature: feature.to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    // a procedure returning a value must end each of its paths with a `RETURN` (or a `THROW`),
+    // otherwise there'd be no value to return once it reaches its `END`
+    fn check_proc_returns(ast: &Ast, env: &Environment) -> Result<(), CompileError> {
+        for stmt in ast.statements.iter() {
+            if let Statement::Procedure(proc_stmt) = stmt {
+                let proc = env.symbol_table.get_proc_by_id(proc_stmt.id.unwrap());
+                let returns_value = proc.return_type != ExpressionType::Unit;
+
+                if returns_value && !Self::always_returns(&proc_stmt.block) {
+                    return Err(CompileError::MissingReturn {
+                        proc_name: proc_stmt.name.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // loops don't count, since their body may run no times at all
+    fn always_returns(block: &BlockStatement) -> bool {
+        let last_stmt = block.stmts.iter().rev().find(|stmt| **stmt != Statement::NOP);
+
+        match last_stmt {
+            Some(Statement::Return(_)) | Some(Statement::Throw(_)) => true,
+            Some(Statement::If(IfStmt {
+                true_block,
+                false_block: Some(false_block),
+                ..
+            })) => Self::always_returns(true_block) && Self::always_returns(false_block),
+            _ => false,
+        }
+    }
+
+    fn check_proc_params(ast: &Ast, max: usize) -> Result<(), CompileError> {
+        for stmt in ast.statements.iter() {
+            if let Statement::Procedure(proc_stmt) = stmt {
+                let actual = proc_stmt.params.len();
+
+                if actual > max {
+                    return Err(CompileError::TooManyProcParams {
+                        proc_name: proc_stmt.name.clone(),
+                        max,
+                        actual,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
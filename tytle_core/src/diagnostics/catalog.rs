@@ -0,0 +1,164 @@
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
+use core::fmt;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+// a source of localized message templates for a single locale.
+//
+// `Catalog` covers the built-in `key = template` text format,
+// other formats (e.g JSON files, gettext) can be plugged in by implementing this trait
+pub trait MessageCatalog {
+    fn locale(&self) -> &str;
+
+    fn template(&self, key: &str) -> Option<&str>;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CatalogError {
+    InvalidEntry { line: usize },
+    EmptyKey { line: usize },
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CatalogError::InvalidEntry { line } => {
+                write!(
+                    f,
+                    "Invalid catalog entry at line {} (expected `key = template`)",
+                    line
+                )
+            }
+            CatalogError::EmptyKey { line } => write!(f, "Empty catalog key at line {}", line),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Catalog {
+    locale: String,
+    templates: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn new(locale: &str) -> Self {
+        Self {
+            locale: locale.to_string(),
+            templates: HashMap::new(),
+        }
+    }
+
+    // the built-in English catalog, holding a template for every diagnostic message
+    pub fn english() -> Self {
+        Self::parse(DEFAULT_LOCALE, include_str!("locales/en.catalog")).unwrap()
+    }
+
+    pub fn spanish() -> Self {
+        Self::parse("es", include_str!("locales/es.catalog")).unwrap()
+    }
+
+    // parses the catalog text format. each line is either:
+    // * a `key = template` entry
+    // * a comment (starting with `#`)
+    // * blank
+    pub fn parse(locale: &str, text: &str) -> Result<Self, CatalogError> {
+        let mut catalog = Self::new(locale);
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, template) = match line.find('=') {
+                Some(eq) => (line[..eq].trim(), line[eq + 1..].trim()),
+                None => return Err(CatalogError::InvalidEntry { line: i + 1 }),
+            };
+
+            if key.is_empty() {
+                return Err(CatalogError::EmptyKey { line: i + 1 });
+            }
+
+            catalog.add(key, template);
+        }
+
+        Ok(catalog)
+    }
+
+    pub fn add(&mut self, key: &str, template: &str) {
+        self.templates.insert(key.to_string(), template.to_string());
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.templates.keys().map(|key| key.as_str())
+    }
+}
+
+impl MessageCatalog for Catalog {
+    fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    fn template(&self, key: &str) -> Option<&str> {
+        self.templates.get(key).map(|template| template.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn catalog_parse() {
+        let text =
+            "# comment\n\nlabel.error = erreur\nparse.syntax = Erreur de syntaxe: `{message}`\n";
+
+        let catalog = Catalog::parse("fr", text).unwrap();
+
+        assert_eq!("fr", catalog.locale());
+        assert_eq!(Some("erreur"), catalog.template("label.error"));
+        assert_eq!(
+            Some("Erreur de syntaxe: `{message}`"),
+            catalog.template("parse.syntax")
+        );
+        assert_eq!(None, catalog.template("label.help"));
+    }
+
+    #[test]
+    pub fn catalog_parse_template_containing_eq() {
+        let catalog = Catalog::parse("en", "a = x = y").unwrap();
+
+        assert_eq!(Some("x = y"), catalog.template("a"));
+    }
+
+    #[test]
+    pub fn catalog_parse_invalid_entry() {
+        let err = Catalog::parse("en", "label.error = error\nlabel.help").unwrap_err();
+
+        assert_eq!(CatalogError::InvalidEntry { line: 2 }, err);
+        assert_eq!(
+            "Invalid catalog entry at line 2 (expected `key = template`)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    pub fn catalog_parse_empty_key() {
+        let err = Catalog::parse("en", " = error").unwrap_err();
+
+        assert_eq!(CatalogError::EmptyKey { line: 1 }, err);
+        assert_eq!("Empty catalog key at line 1", err.to_string());
+    }
+
+    #[test]
+    pub fn catalog_builtin_translations_are_complete() {
+        let english = Catalog::english();
+        let spanish = Catalog::spanish();
+
+        for key in english.keys() {
+            assert!(spanish.template(key).is_some(), "missing `{}`", key);
+        }
+    }
+}
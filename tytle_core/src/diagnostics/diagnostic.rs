@@ -0,0 +1,307 @@
+use crate::compat::prelude::*;
+use crate::diagnostics::{messages, Message};
+use crate::lexer::{FileId, Location};
+use crate::parser::ParseError;
+use crate::vm::{IncomparableValues, InterpreterException, Resource, MAX_STATE_DEPTH};
+use crate::TytleError;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+
+    // where the diagnostic points at within the source code (when known)
+    pub location: Option<Location>,
+
+    // the `SourceMap` file `location` is within (when `None`, the program's entry code)
+    pub file: Option<FileId>,
+
+    // the number of characters (starting at `location`) to underline.
+    // when `None`, the whole word starting at `location` is underlined
+    pub len: Option<usize>,
+
+    pub help: Option<String>,
+
+    // the localizable forms of `message` and `help` (when available),
+    // used by `Diagnostics` for rendering them in other languages
+    pub localizable: Option<Message>,
+    pub localizable_help: Option<Message>,
+}
+
+impl Diagnostic {
+    pub fn error(message: &str) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    pub fn warning(message: &str) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    fn new(severity: Severity, message: &str) -> Self {
+        Self {
+            severity,
+            message: message.to_string(),
+            location: None,
+            file: None,
+            len: None,
+            help: None,
+            localizable: None,
+            localizable_help: None,
+        }
+    }
+
+    pub fn at(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub fn in_file(mut self, file: FileId) -> Self {
+        self.file = Some(file);
+        self
+    }
+
+    pub fn with_len(mut self, len: usize) -> Self {
+        self.len = Some(len);
+        self
+    }
+
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    pub fn localized(mut self, message: Message) -> Self {
+        self.localizable = Some(message);
+        self
+    }
+
+    pub fn localized_help(mut self, help: Message) -> Self {
+        self.localizable_help = Some(help);
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+fn with_suggestion(diag: Diagnostic, suggestion: Option<&str>) -> Diagnostic {
+    match suggestion {
+        Some(suggestion) => diag
+            .with_help(&format!("did you mean `{}`?", suggestion))
+            .localized_help(messages::suggestion_message(suggestion)),
+        None => diag,
+    }
+}
+
+impl From<&TytleError> for Diagnostic {
+    fn from(err: &TytleError) -> Self {
+        let diag = Self::from_error(err);
+
+        match messages::error_message(err) {
+            Some(message) => diag.localized(message),
+            None => diag,
+        }
+    }
+}
+
+impl Diagnostic {
+    fn from_error(err: &TytleError) -> Self {
+        match err {
+            TytleError::Lex(lex_err) => Diagnostic::error(&lex_err.to_string())
+                .at(lex_err.location())
+                .with_len(1),
+            TytleError::Parse { error, location } => {
+                let diag = Diagnostic::error(&error.to_string()).at(*location);
+
+                let diag = match error {
+                    ParseError::UnexpectedToken { actual, .. } => {
+                        diag.with_len(actual.to_string().chars().count())
+                    }
+                    ParseError::UnknownCommand { name, .. } => diag.with_len(name.chars().count()),
+                    ParseError::Unsupported(construct) => diag.with_len(construct.chars().count()),
+                    _ => diag,
+                };
+
+                with_suggestion(diag, error.suggestion())
+            }
+            TytleError::Semantic(err) => {
+                with_suggestion(Diagnostic::error(&err.to_string()), err.suggestion())
+            }
+            TytleError::Compile(err) => Diagnostic::error(&err.to_string()),
+            TytleError::Runtime(InterpreterException::StackOverflow) => {
+                Diagnostic::error("Stack overflow (too many nested procedure calls)")
+            }
+            TytleError::Runtime(InterpreterException::Cancelled) => {
+                Diagnostic::error("Execution cancelled")
+            }
+            TytleError::Runtime(InterpreterException::StateStackOverflow) => Diagnostic::error(
+                &format!("Too many saved states (over {} `PUSHSTATE`s)", MAX_STATE_DEPTH),
+            ),
+            TytleError::Runtime(InterpreterException::StateStackEmpty) => {
+                Diagnostic::error("`POPSTATE` with no state saved by `PUSHSTATE`")
+            }
+            TytleError::Runtime(InterpreterException::ResourceLimit {
+                resource,
+                limit,
+                actual,
+            }) => Diagnostic::error(&match resource {
+                Resource::Heap => format!(
+                    "The program uses {} bytes of memory (the limit is {})",
+                    actual, limit
+                ),
+                Resource::ListLength => format!(
+                    "A list of {} items exceeds the limit of {} items",
+                    actual, limit
+                ),
+                Resource::StringLength => format!(
+                    "A string of {} characters exceeds the limit of {} characters",
+                    actual, limit
+                ),
+            }),
+            TytleError::Runtime(InterpreterException::CapabilityDisabled {
+                capability,
+                feature,
+            }) => Diagnostic::error(&format!(
+                "`{}` requires the `{}` capability, which is disabled",
+                feature,
+                capability.name()
+            )),
+            TytleError::Runtime(InterpreterException::Incomparable(IncomparableValues(
+                left,
+                right,
+            ))) => Diagnostic::error(&format!(
+                "Can't compare a `{}` value with a `{}` value",
+                left.to_string(),
+                right.to_string()
+            )),
+            TytleError::Runtime(InterpreterException::IntegerOverflow { op, lhs, rhs }) => {
+                Diagnostic::error(&format!("Integer overflow computing `{} {} {}`", lhs, op, rhs))
+            }
+            TytleError::Runtime(InterpreterException::DivisionByZero) => {
+                Diagnostic::error("Division by zero")
+            }
+            TytleError::Runtime(InterpreterException::ItemOutOfRange { index, count }) => {
+                Diagnostic::error(&format!(
+                    "`ITEM` index {} is out of range (the word has {} characters)",
+                    index, count
+                ))
+            }
+            TytleError::Runtime(InterpreterException::InvalidNumber(word)) => {
+                Diagnostic::error(&format!("Can't parse `{}` as a number", word))
+            }
+            TytleError::Runtime(InterpreterException::FormatArgsCount { placeholders, args }) => {
+                Diagnostic::error(&format!(
+                    "`FORMAT` string has {} placeholders (got {} arguments)",
+                    placeholders, args
+                ))
+            }
+            TytleError::Runtime(InterpreterException::InvalidFormatDirective(directive)) => {
+                Diagnostic::error(&format!(
+                    "Unknown `FORMAT` directive `{}` (expected `~a`, `~s` or `~~`)",
+                    directive
+                ))
+            }
+            TytleError::Runtime(InterpreterException::ApplyArgsCount {
+                proc_name,
+                expected,
+                actual,
+            }) => Diagnostic::error(&format!(
+                "`APPLY` of procedure `{}` expects {} arguments (got {})",
+                proc_name, expected, actual
+            )),
+            TytleError::Runtime(InterpreterException::ApplyTooFewArgs {
+                proc_name,
+                at_least,
+                actual,
+            }) => Diagnostic::error(&format!(
+                "`APPLY` of procedure `{}` expects at least {} arguments (got {})",
+                proc_name, at_least, actual
+            )),
+            TytleError::Runtime(InterpreterException::ApplyUnassigned) => {
+                Diagnostic::error("`APPLY` of a procedure variable which hasn't been assigned yet")
+            }
+            TytleError::Runtime(InterpreterException::RunUnassigned) => {
+                Diagnostic::error("`RUN` of a block variable which hasn't been assigned yet")
+            }
+            TytleError::Runtime(InterpreterException::StaleBlockLocal(var_name)) => {
+                Diagnostic::error(&format!(
+                    "Block uses local `{}` of a procedure which has already returned",
+                    var_name
+                ))
+            }
+            TytleError::Runtime(InterpreterException::ApplyArgType {
+                proc_name,
+                index,
+                expected,
+                actual,
+            }) => Diagnostic::error(&format!(
+                "`APPLY` of procedure `{}` expects argument #{} to be of type `{}` (got `{}`)",
+                proc_name,
+                index,
+                expected.to_string(),
+                actual.to_string()
+            )),
+            TytleError::Runtime(InterpreterException::UncaughtThrow(tag)) => Diagnostic::error(
+                &format!("`THROW` of tag `{}` with no matching `CATCH`", tag),
+            ),
+            TytleError::Runtime(InterpreterException::UnknownShape(name)) => Diagnostic::error(
+                &format!("Unknown turtle shape `{}` (images must be registered)", name),
+            ),
+            TytleError::Runtime(InterpreterException::UnknownColor(color)) => Diagnostic::error(
+                &format!("Unknown color `{}` (not a palette index nor a color name)", color),
+            ),
+            TytleError::Runtime(InterpreterException::StreamNotOpen(name)) => Diagnostic::error(
+                &format!("Stream `{}` isn't open (see `OPENWRITE`)", name),
+            ),
+            TytleError::Runtime(InterpreterException::NoHost) => {
+                Diagnostic::error("The interpreter has no host attached (use `exec_async`)")
+            }
+            TytleError::Runtime(InterpreterException::NativeNoHost(proc_name)) => Diagnostic::error(
+                &format!("Native procedure `{}` requires a synchronous host", proc_name),
+            ),
+            TytleError::Runtime(InterpreterException::NativeReturnType {
+                proc_name,
+                expected,
+                actual,
+            }) => Diagnostic::error(&format!(
+                "Native procedure `{}` returned a value of type `{}` (expected `{}`)",
+                proc_name,
+                actual.to_string(),
+                expected.to_string()
+            )),
+            TytleError::Runtime(InterpreterException::AssertionFailed {
+                message,
+                location,
+                vars,
+            }) => {
+                let diag =
+                    Diagnostic::error(&format!("Assertion failed: {}", message)).at(*location);
+
+                if vars.is_empty() {
+                    diag
+                } else {
+                    diag.with_help(&format!("variables: {}", messages::format_vars(vars)))
+                        .localized_help(messages::assertion_vars_message(vars))
+                }
+            }
+            TytleError::Runtime(err) => Diagnostic::error(&format!("{:?}", err)),
+            TytleError::StepLimitExceeded(limit) => {
+                Diagnostic::error(&format!("Execution exceeded the limit of {} steps", limit))
+            }
+            TytleError::TimeLimitExceeded(limit) => Diagnostic::error(&format!(
+                "Execution exceeded the time limit of {} ms",
+                limit
+            )),
+            TytleError::Internal { context, message } => {
+                Diagnostic::error(&format!("Internal error in `{}`: {}", context, message))
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::compat::prelude::*;
+use crate::diagnostics::{Diagnostic, Severity, SourceMap};
+use crate::lexer::FileId;
+
+const RESET: &str = "\u{1b}[0m";
+const BOLD: &str = "\u{1b}[1m";
+const RED: &str = "\u{1b}[1;31m";
+const YELLOW: &str = "\u{1b}[1;33m";
+const BLUE: &str = "\u{1b}[1;34m";
+const CYAN: &str = "\u{1b}[1;36m";
+
+// the (localizable) words surrounding the rendered messages
+pub(crate) struct Labels<'a> {
+    pub error: &'a str,
+    pub warning: &'a str,
+    pub help: &'a str,
+}
+
+const ENGLISH_LABELS: Labels<'static> = Labels {
+    error: "error",
+    warning: "warning",
+    help: "help",
+};
+
+// renders diagnostics for a terminal: the severity, the message,
+// the relevant source line with the span underlined (`^^^`) and the help note.
+// coloring (ANSI escape codes) is opt-in
+pub struct DiagnosticRenderer {
+    colored: bool,
+}
+
+impl DiagnosticRenderer {
+    pub fn new() -> Self {
+        Self { colored: false }
+    }
+
+    pub fn colored() -> Self {
+        Self { colored: true }
+    }
+
+    pub fn render(&self, diag: &Diagnostic, source_map: &SourceMap) -> String {
+        self.render_labeled(diag, source_map, &ENGLISH_LABELS)
+    }
+
+    pub(crate) fn render_labeled(
+        &self,
+        diag: &Diagnostic,
+        source_map: &SourceMap,
+        labels: &Labels,
+    ) -> String {
+        let (severity, severity_color) = match diag.severity {
+            Severity::Error => (labels.error, RED),
+            Severity::Warning => (labels.warning, YELLOW),
+        };
+
+        let mut out = format!(
+            "{}: {}\n",
+            self.paint(severity_color, severity),
+            self.paint(BOLD, &diag.message)
+        );
+
+        let location = diag.location;
+        let file = source_map.file(diag.file.unwrap_or(FileId::MAIN));
+        let line_text = location.and_then(|loc| file?.expanded_line(loc.line()));
+
+        let gutter_width = location.map_or(0, |loc| loc.line().to_string().len());
+        let gutter = " ".repeat(gutter_width);
+        let bar = self.paint(BLUE, "|");
+
+        if let (Some(loc), Some(line_text)) = (location, line_text) {
+            let column = loc.column();
+            let len = diag
+                .len
+                .unwrap_or_else(|| Self::word_len(&line_text, column));
+
+            // the file name is omitted for diagnostics pointing into the entry code
+            let position = match (diag.file, file) {
+                (Some(_), Some(file)) => format!("{}:{}", file.name(), loc),
+                _ => loc.to_string(),
+            };
+
+            out.push_str(&format!(
+                "{}{} {}\n",
+                gutter,
+                self.paint(BLUE, "-->"),
+                position
+            ));
+            out.push_str(&format!("{} {}\n", gutter, bar));
+
+            out.push_str(&format!(
+                "{} {} {}\n",
+                self.paint(BLUE, &loc.line().to_string()),
+                bar,
+                line_text
+            ));
+
+            out.push_str(&format!(
+                "{} {} {}{}\n",
+                gutter,
+                bar,
+                " ".repeat(column.saturating_sub(1)),
+                self.paint(severity_color, &"^".repeat(len.max(1)))
+            ));
+        }
+
+        if let Some(help) = &diag.help {
+            out.push_str(&format!(
+                "{} {} {}\n",
+                gutter,
+                self.paint(BLUE, "="),
+                self.paint(CYAN, &format!("{}: {}", labels.help, help))
+            ));
+        }
+
+        out
+    }
+
+    pub fn render_all(&self, diags: &[Diagnostic], source_map: &SourceMap) -> String {
+        diags
+            .iter()
+            .map(|diag| self.render(diag, source_map))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // the length of the word (whitespace delimited) starting at `column`
+    fn word_len(line_text: &str, column: usize) -> usize {
+        line_text
+            .chars()
+            .skip(column.saturating_sub(1))
+            .take_while(|ch| !ch.is_whitespace())
+            .count()
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.colored && !text.is_empty() {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+impl Default for DiagnosticRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
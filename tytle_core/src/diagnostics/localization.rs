@@ -0,0 +1,143 @@
+use crate::compat::prelude::*;
+use crate::diagnostics::diagnostic_renderer::Labels;
+use crate::diagnostics::{
+    Catalog, Diagnostic, DiagnosticRenderer, Message, MessageCatalog, SourceMap, DEFAULT_LOCALE,
+};
+
+// the diagnostics of a program, renderable in any of the locales of its catalogs.
+//
+// a locale is resolved to its catalog by an exact match first and then by its language alone
+// (e.g `es-AR` => `es`). messages missing from the resolved catalog fall back to English,
+// and diagnostics without a localizable form are rendered as is
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+    source_map: SourceMap,
+    catalogs: Vec<Box<dyn MessageCatalog>>,
+    renderer: DiagnosticRenderer,
+}
+
+impl Diagnostics {
+    pub fn new(source: &str, diagnostics: Vec<Diagnostic>) -> Self {
+        Self::with_source_map(SourceMap::new(source), diagnostics)
+    }
+
+    // diagnostics of a multi-file program (see `SourceLoader::source_map`)
+    pub fn with_source_map(source_map: SourceMap, diagnostics: Vec<Diagnostic>) -> Self {
+        let mut diags = Self {
+            diagnostics,
+            source_map,
+            catalogs: Vec::new(),
+            renderer: DiagnosticRenderer::new(),
+        };
+
+        diags.add_catalog(Catalog::english());
+        diags.add_catalog(Catalog::spanish());
+
+        diags
+    }
+
+    // a catalog added for an already supported locale takes precedence over the existing one
+    pub fn add_catalog(&mut self, catalog: impl MessageCatalog + 'static) {
+        self.catalogs.push(Box::new(catalog));
+    }
+
+    pub fn set_renderer(&mut self, renderer: DiagnosticRenderer) {
+        self.renderer = renderer;
+    }
+
+    pub fn push(&mut self, diag: Diagnostic) {
+        self.diagnostics.push(diag);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales = self
+            .catalogs
+            .iter()
+            .map(|catalog| catalog.locale())
+            .collect::<Vec<_>>();
+
+        locales.sort();
+        locales.dedup();
+        locales
+    }
+
+    pub fn render(&self, locale: &str) -> String {
+        let error = self.translate(locale, &Message::new("label.error"), "error");
+        let warning = self.translate(locale, &Message::new("label.warning"), "warning");
+        let help = self.translate(locale, &Message::new("label.help"), "help");
+
+        let labels = Labels {
+            error: &error,
+            warning: &warning,
+            help: &help,
+        };
+
+        self.diagnostics
+            .iter()
+            .map(|diag| {
+                let diag = self.localize(diag, locale);
+
+                self.renderer
+                    .render_labeled(&diag, &self.source_map, &labels)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // a copy of `diag` having its message and help translated to `locale`
+    pub fn localize(&self, diag: &Diagnostic, locale: &str) -> Diagnostic {
+        let mut localized = diag.clone();
+
+        if let Some(message) = &diag.localizable {
+            localized.message = self.translate(locale, message, &diag.message);
+        }
+
+        if let (Some(help), Some(fallback)) = (&diag.localizable_help, &diag.help) {
+            localized.help = Some(self.translate(locale, help, fallback));
+        }
+
+        localized
+    }
+
+    fn translate(&self, locale: &str, message: &Message, fallback: &str) -> String {
+        let template = self
+            .resolve(locale)
+            .and_then(|catalog| catalog.template(&message.key))
+            .or_else(|| {
+                self.resolve(DEFAULT_LOCALE)
+                    .and_then(|catalog| catalog.template(&message.key))
+            });
+
+        match template {
+            Some(template) => message.format(template),
+            None => fallback.to_string(),
+        }
+    }
+
+    fn resolve(&self, locale: &str) -> Option<&dyn MessageCatalog> {
+        let language = locale.split(['-', '_']).next().unwrap();
+
+        self.find_catalog(locale)
+            .or_else(|| self.find_catalog(language))
+    }
+
+    fn find_catalog(&self, locale: &str) -> Option<&dyn MessageCatalog> {
+        self.catalogs
+            .iter()
+            .rev()
+            .find(|catalog| catalog.locale().eq_ignore_ascii_case(locale))
+            .map(|catalog| catalog.as_ref())
+    }
+}
@@ -0,0 +1,89 @@
+// a localizable message: the key of its template within a `MessageCatalog`
+// and the named arguments substituted into the template's `{placeholders}`
+use crate::compat::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub key: String,
+    pub args: Vec<(String, String)>,
+}
+
+impl Message {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, name: &str, value: impl ToString) -> Self {
+        self.args.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    // substitutes the message arguments into `template`.
+    // placeholders without a matching argument are left as is
+    pub fn format(&self, template: &str) -> String {
+        let mut text = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            let (before, placeholder) = rest.split_at(start);
+
+            let end = match placeholder.find('}') {
+                Some(end) => end,
+                None => break,
+            };
+
+            text.push_str(before);
+
+            let name = &placeholder[1..end];
+
+            match self.args.iter().find(|(arg_name, _)| arg_name == name) {
+                Some((_, value)) => text.push_str(value),
+                None => text.push_str(&placeholder[..=end]),
+            }
+
+            rest = &placeholder[end + 1..];
+        }
+
+        text.push_str(rest);
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn message_format() {
+        let msg = Message::new("semantic.type-mismatch")
+            .arg("expected", "INT")
+            .arg("actual", "BOOL");
+
+        assert_eq!(
+            "expected: `INT`, actual: `BOOL`",
+            msg.format("expected: `{expected}`, actual: `{actual}`")
+        );
+    }
+
+    #[test]
+    pub fn message_format_arg_value_not_substituted() {
+        let msg = Message::new("parse.syntax")
+            .arg("message", "{other}")
+            .arg("other", "bla");
+
+        assert_eq!(
+            "Syntax error: `{other}`",
+            msg.format("Syntax error: `{message}`")
+        );
+    }
+
+    #[test]
+    pub fn message_format_missing_arg() {
+        let msg = Message::new("semantic.duplicate-proc");
+
+        assert_eq!("Duplicate: `{proc}`", msg.format("Duplicate: `{proc}`"));
+    }
+}
@@ -0,0 +1,390 @@
+use crate::ast::semantic::AstWalkError;
+use crate::compat::prelude::*;
+use crate::compiler::CompileError;
+use crate::diagnostics::Message;
+use crate::lexer::LexError;
+use crate::parser::ParseError;
+use crate::vm::{
+    IncomparableValues, InterpreterException, MemoryValue, Resource, MAX_STATE_DEPTH,
+};
+use crate::TytleError;
+
+// the localizable form of the pipeline errors messages.
+// the keys are resolved against a `MessageCatalog` (see `locales/en.catalog` for the English templates)
+
+pub(crate) fn error_message(err: &TytleError) -> Option<Message> {
+    match err {
+        TytleError::Lex(err) => Some(lex_error_message(err)),
+        TytleError::Parse { error, .. } => Some(parse_error_message(error)),
+        TytleError::Semantic(err) => Some(semantic_error_message(err)),
+        TytleError::Compile(err) => compile_error_message(err),
+        TytleError::Runtime(err) => runtime_error_message(err),
+        TytleError::StepLimitExceeded(limit) => {
+            Some(Message::new("runtime.step-limit-exceeded").arg("limit", limit))
+        }
+        TytleError::TimeLimitExceeded(limit) => {
+            Some(Message::new("runtime.time-limit-exceeded").arg("limit", limit))
+        }
+        TytleError::Internal { context, message } => Some(
+            Message::new("internal.error")
+                .arg("context", context)
+                .arg("message", message),
+        ),
+    }
+}
+
+pub(crate) fn suggestion_message(suggestion: &str) -> Message {
+    Message::new("help.did-you-mean").arg("suggestion", suggestion)
+}
+
+pub(crate) fn assertion_vars_message(vars: &[(String, MemoryValue)]) -> Message {
+    Message::new("help.assertion-vars").arg("vars", format_vars(vars))
+}
+
+// `X = 1, NAME = "TURTLE, FLAGS = [TRUE FALSE]`
+pub(crate) fn format_vars(vars: &[(String, MemoryValue)]) -> String {
+    vars.iter()
+        .map(|(name, value)| format!("{} = {}", name, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn lex_error_message(err: &LexError) -> Message {
+    match err {
+        LexError::InvalidCharacter { ch, .. } => {
+            Message::new("lex.invalid-character").arg("ch", ch.escape_default())
+        }
+    }
+}
+
+fn parse_error_message(err: &ParseError) -> Message {
+    match err {
+        ParseError::MissingColon => Message::new("parse.missing-colon"),
+        ParseError::NewLineExpected => Message::new("parse.new-line-expected"),
+        ParseError::IdentifierExpected => Message::new("parse.identifier-expected"),
+        ParseError::MissingProcReturnType => Message::new("parse.missing-proc-return-type"),
+        ParseError::InvalidDataType(dt) => Message::new("parse.invalid-data-type").arg("type", dt),
+        ParseError::InvalidIdentifierDeclaration(ident) => {
+            Message::new("parse.invalid-identifier-declaration").arg("ident", ident)
+        }
+        ParseError::UnexpectedToken { expected, actual } => Message::new("parse.unexpected-token")
+            .arg("expected", expected.to_string())
+            .arg("actual", actual.to_string()),
+        ParseError::UnexpectedKeyword { keyword } => {
+            Message::new("parse.unexpected-keyword").arg("keyword", keyword)
+        }
+        ParseError::ReservedKeyword(keyword) => {
+            Message::new("parse.reserved-keyword").arg("keyword", keyword)
+        }
+        ParseError::UnknownCommand { name, .. } => {
+            Message::new("parse.unknown-command").arg("name", name)
+        }
+        ParseError::Syntax { message } => Message::new("parse.syntax").arg("message", message),
+        ParseError::Unsupported(construct) => {
+            Message::new("parse.unsupported").arg("construct", construct)
+        }
+    }
+}
+
+fn semantic_error_message(err: &AstWalkError) -> Message {
+    match err {
+        AstWalkError::DuplicateGlobalVar(var) => {
+            Message::new("semantic.duplicate-global-var").arg("var", var)
+        }
+        AstWalkError::DuplicateProc(proc) => {
+            Message::new("semantic.duplicate-proc").arg("proc", proc)
+        }
+        AstWalkError::DuplicateProcLocalVar(var) => {
+            Message::new("semantic.duplicate-proc-local-var").arg("var", var)
+        }
+        AstWalkError::DuplicateProcParam(proc, param) => {
+            Message::new("semantic.duplicate-proc-param")
+                .arg("proc", proc)
+                .arg("param", param)
+        }
+        AstWalkError::MissingVarDeclaration(var, _) => {
+            Message::new("semantic.missing-var-declaration").arg("var", var)
+        }
+        AstWalkError::MissingProcDeclaration(proc, _) => {
+            Message::new("semantic.missing-proc-declaration").arg("proc", proc)
+        }
+        AstWalkError::ProcNotAllowedToDeclareGlobals(proc) => {
+            Message::new("semantic.proc-not-allowed-to-declare-globals").arg("proc", proc)
+        }
+        AstWalkError::InvalidReturnType(expected, actual) => {
+            Message::new("semantic.invalid-return-type")
+                .arg("expected", expected.to_string())
+                .arg("actual", actual.to_string())
+        }
+        AstWalkError::LocalsNotAllowedUnderRootScope(var) => {
+            Message::new("semantic.locals-not-allowed-under-root-scope").arg("var", var)
+        }
+        AstWalkError::GlobalsNotAllowedUnderInnerScope(var) => {
+            Message::new("semantic.globals-not-allowed-under-inner-scope").arg("var", var)
+        }
+        AstWalkError::AssignBeforeDeclaration(var) => {
+            Message::new("semantic.assign-before-declaration").arg("var", var)
+        }
+        AstWalkError::AssignToConst(var) => {
+            Message::new("semantic.assign-to-const").arg("var", var)
+        }
+        AstWalkError::NotConstExpr(var) => Message::new("semantic.not-const-expr").arg("var", var),
+        AstWalkError::NotConstStr(query, expr) => Message::new("semantic.not-const-str")
+            .arg("query", query.to_string())
+            .arg("expr", expr),
+        AstWalkError::TypeMismatch(expected, actual) => Message::new("semantic.type-mismatch")
+            .arg("expected", expected.to_string())
+            .arg("actual", actual.to_string()),
+        AstWalkError::InvalidBinaryOp(op, left, right) => {
+            Message::new("semantic.invalid-binary-op")
+                .arg("op", op.to_string())
+                .arg("left", left.to_string())
+                .arg("right", right.to_string())
+        }
+        AstWalkError::InvalidProcCallArgsCount(proc, expected, actual) => {
+            Message::new("semantic.invalid-proc-call-args-count")
+                .arg("proc", proc)
+                .arg("expected", expected)
+                .arg("actual", actual)
+        }
+        AstWalkError::TooFewProcCallArgs(proc, at_least, actual) => {
+            Message::new("semantic.too-few-proc-call-args")
+                .arg("proc", proc)
+                .arg("at_least", at_least)
+                .arg("actual", actual)
+        }
+        AstWalkError::UnknownNamedArg(proc, param) => Message::new("semantic.unknown-named-arg")
+            .arg("proc", proc)
+            .arg("param", param),
+        AstWalkError::DuplicateNamedArg(proc, param) => {
+            Message::new("semantic.duplicate-named-arg")
+                .arg("proc", proc)
+                .arg("param", param)
+        }
+        AstWalkError::MissingProcCallArg(proc, param) => {
+            Message::new("semantic.missing-proc-call-arg")
+                .arg("proc", proc)
+                .arg("param", param)
+        }
+        AstWalkError::NoProcOverload(proc, nargs) => Message::new("semantic.no-proc-overload")
+            .arg("proc", proc)
+            .arg("nargs", nargs),
+        AstWalkError::AmbiguousProcCall(proc, nargs) => {
+            Message::new("semantic.ambiguous-proc-call")
+                .arg("proc", proc)
+                .arg("nargs", nargs)
+        }
+        AstWalkError::AmbiguousProcRef(proc) => {
+            Message::new("semantic.ambiguous-proc-ref").arg("proc", proc)
+        }
+        AstWalkError::InvalidProcCallArgType(index, expected, actual) => {
+            Message::new("semantic.invalid-proc-call-arg-type")
+                .arg("index", index)
+                .arg("ordinal", err.indexify_arg(*index))
+                .arg("expected", expected.to_string())
+                .arg("actual", actual.to_string())
+        }
+        AstWalkError::VariableTypeMissing(var) => {
+            Message::new("semantic.variable-type-missing").arg("var", var)
+        }
+        AstWalkError::NotBooleanExpr(expr) => {
+            Message::new("semantic.not-boolean-expr").arg("expr", expr)
+        }
+        AstWalkError::NotIntExpr(expr) => Message::new("semantic.not-int-expr").arg("expr", expr),
+        AstWalkError::NotListExpr(expr) => Message::new("semantic.not-list-expr").arg("expr", expr),
+        AstWalkError::NotProcExpr(expr) => Message::new("semantic.not-proc-expr").arg("expr", expr),
+        AstWalkError::NotBlockExpr(expr) => {
+            Message::new("semantic.not-block-expr").arg("expr", expr)
+        }
+        AstWalkError::NotValueExpr(expr) => {
+            Message::new("semantic.not-value-expr").arg("expr", expr)
+        }
+        AstWalkError::EmptyList => Message::new("semantic.empty-list"),
+        AstWalkError::UnresolvedLoad(path) => {
+            Message::new("semantic.unresolved-load").arg("path", path)
+        }
+        AstWalkError::BuiltinProcRedefinition(proc) => {
+            Message::new("semantic.builtin-proc-redefinition").arg("proc", proc)
+        }
+        AstWalkError::DuplicateEventHandler(event) => {
+            Message::new("semantic.duplicate-event-handler").arg("event", event)
+        }
+        AstWalkError::EventHandlerNotAtTopLevel(event) => {
+            Message::new("semantic.event-handler-not-at-top-level").arg("event", event)
+        }
+        AstWalkError::ProcNotAtTopLevel(proc) => {
+            Message::new("semantic.proc-not-at-top-level").arg("proc", proc)
+        }
+        AstWalkError::ReturnWithinBlock => Message::new("semantic.return-within-block"),
+    }
+}
+
+fn compile_error_message(err: &CompileError) -> Option<Message> {
+    match err {
+        CompileError::Lex(err) => Some(lex_error_message(err)),
+        CompileError::Parse { error, .. } => Some(parse_error_message(error)),
+        CompileError::Semantic(err) => Some(semantic_error_message(err)),
+        CompileError::TooManyProcParams {
+            proc_name,
+            max,
+            actual,
+        } => Some(
+            Message::new("compile.too-many-proc-params")
+                .arg("proc", proc_name)
+                .arg("max", max)
+                .arg("actual", actual),
+        ),
+        CompileError::UnassignedLocal {
+            proc_name,
+            var_name,
+        } => Some(
+            Message::new("compile.unassigned-local")
+                .arg("proc", proc_name)
+                .arg("var", var_name),
+        ),
+        CompileError::MissingReturn { proc_name } => {
+            Some(Message::new("compile.missing-return").arg("proc", proc_name))
+        }
+        CompileError::CapabilityDisabled {
+            capability,
+            feature,
+        } => Some(
+            Message::new("compile.capability-disabled")
+                .arg("feature", feature)
+                .arg("capability", capability.name()),
+        ),
+    }
+}
+
+fn runtime_error_message(err: &InterpreterException) -> Option<Message> {
+    match err {
+        InterpreterException::StackOverflow => Some(Message::new("runtime.stack-overflow")),
+        InterpreterException::Cancelled => Some(Message::new("runtime.cancelled")),
+        InterpreterException::StateStackOverflow => Some(
+            Message::new("runtime.state-stack-overflow").arg("limit", MAX_STATE_DEPTH.to_string()),
+        ),
+        InterpreterException::StateStackEmpty => Some(Message::new("runtime.state-stack-empty")),
+        InterpreterException::ResourceLimit {
+            resource,
+            limit,
+            actual,
+        } => {
+            let key = match resource {
+                Resource::Heap => "runtime.heap-limit",
+                Resource::ListLength => "runtime.list-length-limit",
+                Resource::StringLength => "runtime.string-length-limit",
+            };
+
+            Some(
+                Message::new(key)
+                    .arg("limit", limit.to_string())
+                    .arg("actual", actual.to_string()),
+            )
+        }
+        InterpreterException::CapabilityDisabled {
+            capability,
+            feature,
+        } => Some(
+            Message::new("runtime.capability-disabled")
+                .arg("feature", feature)
+                .arg("capability", capability.name()),
+        ),
+        InterpreterException::Incomparable(IncomparableValues(left, right)) => Some(
+            Message::new("runtime.incomparable-values")
+                .arg("left", left.to_string())
+                .arg("right", right.to_string()),
+        ),
+        InterpreterException::IntegerOverflow { op, lhs, rhs } => Some(
+            Message::new("runtime.integer-overflow")
+                .arg("op", op)
+                .arg("lhs", lhs.to_string())
+                .arg("rhs", rhs.to_string()),
+        ),
+        InterpreterException::DivisionByZero => Some(Message::new("runtime.division-by-zero")),
+        InterpreterException::ItemOutOfRange { index, count } => Some(
+            Message::new("runtime.item-out-of-range")
+                .arg("index", index.to_string())
+                .arg("count", count.to_string()),
+        ),
+        InterpreterException::InvalidNumber(word) => {
+            Some(Message::new("runtime.invalid-number").arg("word", word))
+        }
+        InterpreterException::FormatArgsCount { placeholders, args } => Some(
+            Message::new("runtime.format-args-count")
+                .arg("placeholders", placeholders.to_string())
+                .arg("args", args.to_string()),
+        ),
+        InterpreterException::InvalidFormatDirective(directive) => Some(
+            Message::new("runtime.invalid-format-directive").arg("directive", directive),
+        ),
+        InterpreterException::ApplyArgsCount {
+            proc_name,
+            expected,
+            actual,
+        } => Some(
+            Message::new("runtime.apply-args-count")
+                .arg("proc", proc_name)
+                .arg("expected", expected.to_string())
+                .arg("actual", actual.to_string()),
+        ),
+        InterpreterException::ApplyTooFewArgs {
+            proc_name,
+            at_least,
+            actual,
+        } => Some(
+            Message::new("runtime.apply-too-few-args")
+                .arg("proc", proc_name)
+                .arg("at_least", at_least.to_string())
+                .arg("actual", actual.to_string()),
+        ),
+        InterpreterException::ApplyUnassigned => Some(Message::new("runtime.apply-unassigned")),
+        InterpreterException::RunUnassigned => Some(Message::new("runtime.run-unassigned")),
+        InterpreterException::StaleBlockLocal(var_name) => {
+            Some(Message::new("runtime.stale-block-local").arg("var", var_name))
+        }
+        InterpreterException::ApplyArgType {
+            proc_name,
+            index,
+            expected,
+            actual,
+        } => Some(
+            Message::new("runtime.apply-arg-type")
+                .arg("proc", proc_name)
+                .arg("index", index.to_string())
+                .arg("expected", expected.to_string())
+                .arg("actual", actual.to_string()),
+        ),
+        InterpreterException::UncaughtThrow(tag) => {
+            Some(Message::new("runtime.uncaught-throw").arg("tag", tag))
+        }
+        InterpreterException::UnknownShape(name) => {
+            Some(Message::new("runtime.unknown-shape").arg("shape", name))
+        }
+        InterpreterException::UnknownColor(color) => {
+            Some(Message::new("runtime.unknown-color").arg("color", color))
+        }
+        InterpreterException::StreamNotOpen(name) => {
+            Some(Message::new("runtime.stream-not-open").arg("stream", name))
+        }
+        InterpreterException::NoHost => Some(Message::new("runtime.no-host")),
+        InterpreterException::NativeNoHost(proc_name) => {
+            Some(Message::new("runtime.native-no-host").arg("proc", proc_name))
+        }
+        InterpreterException::NativeReturnType {
+            proc_name,
+            expected,
+            actual,
+        } => Some(
+            Message::new("runtime.native-return-type")
+                .arg("proc", proc_name)
+                .arg("expected", expected.to_string())
+                .arg("actual", actual.to_string()),
+        ),
+        InterpreterException::AssertionFailed { message, .. } => {
+            Some(Message::new("runtime.assertion-failed").arg("message", message))
+        }
+        // watchpoints are a debugging aid, not meant to be reported to the program's author
+        InterpreterException::Watchpoint(_) => None,
+        InterpreterException::Breakpoint(_) => None,
+    }
+}
@@ -0,0 +1,34 @@
+// user-facing diagnostics (errors & warnings) and their rustc-like terminal rendering:
+//
+// ```
+// error: Unexpected token: `10` (expected `=`)
+//  --> 2:14
+//   |
+// 2 | MAKEGLOBAL A 10
+//   |              ^^
+//   = help: ...
+// ```
+//
+// diagnostics carrying a localizable `Message` can be rendered in other languages using `Diagnostics`,
+// which resolves the message keys against per-locale `MessageCatalog`s (English being the default).
+//
+// the code of multi-file programs is held by a `SourceMap`, having each diagnostic point into one of its files
+
+mod catalog;
+mod diagnostic;
+mod diagnostic_renderer;
+mod localization;
+mod message;
+mod messages;
+mod source_file;
+mod source_map;
+mod suggest;
+
+pub use catalog::{Catalog, CatalogError, MessageCatalog, DEFAULT_LOCALE};
+pub use diagnostic::{Diagnostic, Severity};
+pub use diagnostic_renderer::DiagnosticRenderer;
+pub use localization::Diagnostics;
+pub use message::Message;
+pub use source_file::SourceFile;
+pub use source_map::SourceMap;
+pub use suggest::{edit_distance, suggest};
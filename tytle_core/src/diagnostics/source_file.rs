@@ -0,0 +1,278 @@
+use crate::compat::prelude::*;
+use crate::lexer::{Location, DEFAULT_TAB_WIDTH};
+use core::ops::Range;
+
+// a single source file (its name and code) along with the offsets of its lines.
+//
+// maps `Location`s (whose columns account for tab stops, see `Location::advance_tab`)
+// to byte offsets within the source and back, so editors can point at exact buffer positions
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    name: String,
+    source: String,
+    lines_offsets: Vec<usize>,
+    tab_width: usize,
+}
+
+impl SourceFile {
+    pub fn new(name: &str, source: &str) -> Self {
+        Self::with_tab_width(name, source, DEFAULT_TAB_WIDTH)
+    }
+
+    pub fn with_tab_width(name: &str, source: &str, tab_width: usize) -> Self {
+        let mut lines_offsets = vec![0];
+
+        for (offset, ch) in source.char_indices() {
+            if ch == '\n' {
+                lines_offsets.push(offset + 1);
+            }
+        }
+
+        Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            lines_offsets,
+            tab_width,
+        }
+    }
+
+    pub fn tab_width(&self) -> usize {
+        self.tab_width
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn lines_count(&self) -> usize {
+        self.lines_offsets.len()
+    }
+
+    // returns the text of line `line` (1-based), without its line terminator
+    pub fn line(&self, line: usize) -> Option<&str> {
+        if line == 0 || line > self.lines_count() {
+            return None;
+        }
+
+        let start = self.lines_offsets[line - 1];
+
+        let end = match self.lines_offsets.get(line) {
+            Some(next_start) => next_start - 1,
+            None => self.source.len(),
+        };
+
+        let text = &self.source[start..end];
+
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+
+    // the text of line `line` with its tabs expanded into spaces (up to the next tab stop),
+    // so the text columns match the `Location` columns
+    pub fn expanded_line(&self, line: usize) -> Option<String> {
+        let text = self.line(line)?;
+        let mut expanded = String::with_capacity(text.len());
+        let mut location = Location(line, 1);
+
+        for ch in text.chars() {
+            if ch == '\t' {
+                let column = location.column();
+                location.advance_tab(self.tab_width);
+
+                expanded.push_str(&" ".repeat(location.column() - column));
+            } else {
+                location.increment_column();
+                expanded.push(ch);
+            }
+        }
+
+        Some(expanded)
+    }
+
+    // returns the byte offset of `location` within the source.
+    // a column within the span of a tab is mapped to the tab itself
+    pub fn offset(&self, location: Location) -> Option<usize> {
+        let line = self.line(location.line())?;
+        let start = self.lines_offsets[location.line() - 1];
+
+        let column_offset = self
+            .columns(location.line(), line)
+            .take_while(|(_, loc)| loc.column() <= location.column())
+            .last()
+            .filter(|(offset, loc)| {
+                loc.column() == location.column() || line[*offset..].starts_with('\t')
+            })?
+            .0;
+
+        Some(start + column_offset)
+    }
+
+    // returns the `Location` of byte offset `offset` within the source
+    // (`None` when out of bounds or when not at a char boundary)
+    pub fn location(&self, offset: usize) -> Option<Location> {
+        if offset > self.source.len() || !self.source.is_char_boundary(offset) {
+            return None;
+        }
+
+        let line_index = match self.lines_offsets.binary_search(&offset) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        };
+
+        let line = self.line(line_index + 1)?;
+        let start = self.lines_offsets[line_index];
+
+        // offsets past the line's text (i.e its `\r\n` line ending) are mapped to the line's end
+        self.columns(line_index + 1, line)
+            .find(|(column_offset, _)| start + column_offset >= offset)
+            .or_else(|| self.columns(line_index + 1, line).last())
+            .map(|(_, loc)| loc)
+    }
+
+    // the bytes range of the `len` chars starting at `location`
+    pub fn byte_range(&self, location: Location, len: usize) -> Option<Range<usize>> {
+        let start = self.offset(location)?;
+
+        let end = self.source[start..]
+            .char_indices()
+            .map(|(offset, _)| start + offset)
+            .chain(core::iter::once(self.source.len()))
+            .nth(len)?;
+
+        Some(start..end)
+    }
+
+    // the byte offset (within `text`) and `Location` of each char of line `line`,
+    // followed by the location right after the line's end
+    fn columns<'a>(
+        &self,
+        line: usize,
+        text: &'a str,
+    ) -> impl Iterator<Item = (usize, Location)> + 'a {
+        let tab_width = self.tab_width;
+        let mut location = Location(line, 1);
+
+        text.char_indices()
+            .map(Some)
+            .chain(core::iter::once(None))
+            .map(move |entry| match entry {
+                Some((offset, ch)) => {
+                    let current = location;
+
+                    if ch == '\t' {
+                        location.advance_tab(tab_width);
+                    } else {
+                        location.increment_column();
+                    }
+
+                    (offset, current)
+                }
+                None => (text.len(), location),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn source_file_lines() {
+        let file = SourceFile::new("main.logo", "FORWARD 10\r\nRIGHT 20\n\nPRINT 1");
+
+        assert_eq!(4, file.lines_count());
+        assert_eq!(Some("FORWARD 10"), file.line(1));
+        assert_eq!(Some("RIGHT 20"), file.line(2));
+        assert_eq!(Some(""), file.line(3));
+        assert_eq!(Some("PRINT 1"), file.line(4));
+        assert_eq!(None, file.line(0));
+        assert_eq!(None, file.line(5));
+    }
+
+    #[test]
+    pub fn source_file_offset_tabs() {
+        let file = SourceFile::with_tab_width("main.logo", "\tFORWARD\t10", 4);
+
+        assert_eq!(Some(0), file.offset(Location(1, 1)));
+        assert_eq!(Some(0), file.offset(Location(1, 3)));
+        assert_eq!(Some(1), file.offset(Location(1, 5)));
+        assert_eq!(Some(8), file.offset(Location(1, 12)));
+        assert_eq!(Some(9), file.offset(Location(1, 13)));
+        assert_eq!(Some(11), file.offset(Location(1, 15)));
+        assert_eq!(None, file.offset(Location(1, 16)));
+    }
+
+    #[test]
+    pub fn source_file_offset_multibyte() {
+        let file = SourceFile::new("main.logo", "\"🐢\" 10");
+
+        assert_eq!(Some(1), file.offset(Location(1, 2)));
+        assert_eq!(Some(5), file.offset(Location(1, 3)));
+        assert_eq!(Some(7), file.offset(Location(1, 5)));
+    }
+
+    #[test]
+    pub fn source_file_location() {
+        let file = SourceFile::with_tab_width("main.logo", "FORWARD 10\r\n\tRIGHT 🐢 20", 4);
+
+        assert_eq!(Some(Location(1, 1)), file.location(0));
+        assert_eq!(Some(Location(1, 9)), file.location(8));
+        assert_eq!(Some(Location(1, 11)), file.location(10));
+        assert_eq!(Some(Location(1, 11)), file.location(11));
+        assert_eq!(Some(Location(2, 1)), file.location(12));
+        assert_eq!(Some(Location(2, 5)), file.location(13));
+        assert_eq!(Some(Location(2, 11)), file.location(19));
+        assert_eq!(Some(Location(2, 13)), file.location(24));
+        assert_eq!(Some(Location(2, 15)), file.location(26));
+        assert_eq!(None, file.location(20));
+        assert_eq!(None, file.location(27));
+    }
+
+    #[test]
+    pub fn source_file_location_offset_roundtrip() {
+        let file = SourceFile::new("main.logo", "MAKEGLOBAL A = 1\r\n\tFORWARD\tA\n\"שלום\"");
+
+        for (offset, _) in file.source().char_indices() {
+            let loc = file.location(offset).unwrap();
+            let ch = file.source()[offset..].chars().next().unwrap();
+
+            // the line ending chars aren't a part of their line's text
+            if ch == '\r' || ch == '\n' {
+                continue;
+            }
+
+            assert_eq!(Some(offset), file.offset(loc));
+        }
+    }
+
+    #[test]
+    pub fn source_file_byte_range() {
+        let file = SourceFile::new("main.logo", "PRINT \"שלום\"");
+
+        assert_eq!(Some(0..5), file.byte_range(Location(1, 1), 5));
+        assert_eq!(Some(6..16), file.byte_range(Location(1, 7), 6));
+        assert_eq!(None, file.byte_range(Location(1, 7), 7));
+    }
+
+    #[test]
+    pub fn source_file_expanded_line() {
+        let file = SourceFile::with_tab_width("main.logo", "A\tB\n\t\tC", 4);
+
+        assert_eq!(Some("A   B".to_string()), file.expanded_line(1));
+        assert_eq!(Some("        C".to_string()), file.expanded_line(2));
+    }
+
+    #[test]
+    pub fn source_file_offset() {
+        let file = SourceFile::new("main.logo", "FORWARD 10\nRIGHT 20");
+
+        assert_eq!(Some(0), file.offset(Location(1, 1)));
+        assert_eq!(Some(8), file.offset(Location(1, 9)));
+        assert_eq!(Some(17), file.offset(Location(2, 7)));
+        assert_eq!(None, file.offset(Location(2, 20)));
+        assert_eq!(None, file.offset(Location(3, 1)));
+    }
+}
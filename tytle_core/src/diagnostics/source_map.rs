@@ -0,0 +1,154 @@
+use crate::compat::prelude::*;
+use crate::diagnostics::SourceFile;
+use crate::lexer::{FileId, FileLocation, DEFAULT_TAB_WIDTH};
+use crate::parser::MAIN_FILE;
+
+// the source files of a (possibly multi-file) program.
+//
+// each file is assigned a `FileId` when registered, the first file registered being the entry code
+// (`FileId::MAIN`). diagnostics pointing at a `FileLocation` are resolved against the map
+// for rendering the relevant file's name and code
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+    tab_width: usize,
+}
+
+impl SourceMap {
+    // a map holding `source` as its entry code
+    pub fn new(source: &str) -> Self {
+        Self::with_tab_width(source, DEFAULT_TAB_WIDTH)
+    }
+
+    pub fn with_tab_width(source: &str, tab_width: usize) -> Self {
+        let mut map = Self::empty(tab_width);
+        map.add_file(MAIN_FILE, source);
+
+        map
+    }
+
+    pub fn empty(tab_width: usize) -> Self {
+        Self {
+            files: Vec::new(),
+            tab_width,
+        }
+    }
+
+    pub fn tab_width(&self) -> usize {
+        self.tab_width
+    }
+
+    // registers file `name` and returns its id.
+    // registering an already registered file name replaces its code (keeping its id)
+    pub fn add_file(&mut self, name: &str, source: &str) -> FileId {
+        let file = SourceFile::with_tab_width(name, source, self.tab_width);
+
+        match self.file_id(name) {
+            Some(file_id) => {
+                self.files[file_id.0] = file;
+                file_id
+            }
+            None => {
+                self.files.push(file);
+                FileId(self.files.len() - 1)
+            }
+        }
+    }
+
+    pub fn file(&self, file_id: FileId) -> Option<&SourceFile> {
+        self.files.get(file_id.0)
+    }
+
+    pub fn file_id(&self, name: &str) -> Option<FileId> {
+        self.files
+            .iter()
+            .position(|file| file.name() == name)
+            .map(FileId)
+    }
+
+    pub fn main_file(&self) -> Option<&SourceFile> {
+        self.file(FileId::MAIN)
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| (FileId(index), file))
+    }
+
+    pub fn files_count(&self) -> usize {
+        self.files.len()
+    }
+
+    // returns the byte offset of `location` within its file
+    pub fn offset(&self, location: FileLocation) -> Option<usize> {
+        self.file(location.file)?.offset(location.location)
+    }
+
+    // formats `location` as `file:line:column`
+    pub fn display(&self, location: FileLocation) -> Option<String> {
+        let file = self.file(location.file)?;
+
+        Some(format!("{}:{}", file.name(), location.location))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Location;
+
+    #[test]
+    pub fn source_map_new() {
+        let map = SourceMap::new("FORWARD 10");
+
+        assert_eq!(1, map.files_count());
+        assert_eq!(Some(FileId::MAIN), map.file_id(MAIN_FILE));
+        assert_eq!(Some("FORWARD 10"), map.main_file().unwrap().line(1));
+    }
+
+    #[test]
+    pub fn source_map_add_file() {
+        let mut map = SourceMap::new("LOAD \"LIB\nSQUARE");
+
+        let lib = map.add_file("LIB", "TO SQUARE()\n\tFORWARD 10\nEND");
+
+        assert_eq!(FileId(1), lib);
+        assert_eq!(Some(lib), map.file_id("LIB"));
+        assert_eq!(None, map.file_id("MISSING"));
+        assert_eq!("LIB", map.file(lib).unwrap().name());
+        assert_eq!(Some("END"), map.file(lib).unwrap().line(3));
+        assert!(map.file(FileId(2)).is_none());
+
+        let names = map
+            .files()
+            .map(|(file_id, file)| (file_id, file.name()))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(FileId::MAIN, MAIN_FILE), (lib, "LIB")], names);
+    }
+
+    #[test]
+    pub fn source_map_add_existing_file() {
+        let mut map = SourceMap::empty(4);
+
+        let first = map.add_file("LIB", "FORWARD 10");
+        let second = map.add_file("LIB", "RIGHT 20");
+
+        assert_eq!(first, second);
+        assert_eq!(1, map.files_count());
+        assert_eq!("RIGHT 20", map.file(first).unwrap().source());
+    }
+
+    #[test]
+    pub fn source_map_file_locations() {
+        let mut map = SourceMap::with_tab_width("FORWARD 10", 8);
+        let lib = map.add_file("LIB", "RIGHT 10\n\tLEFT 20");
+
+        let loc = Location(2, 9).in_file(lib);
+
+        assert_eq!(Some(10), map.offset(loc));
+        assert_eq!(Some("LIB:2:9".to_string()), map.display(loc));
+        assert_eq!(None, map.display(Location(1, 1).in_file(FileId(5))));
+    }
+}
@@ -0,0 +1,97 @@
+// "did you mean" suggestions for misspelled keywords and symbols
+use crate::compat::prelude::*;
+
+
+// the Levenshtein distance between `a` and `b`
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars = b.chars().collect::<Vec<char>>();
+
+    let mut prev = (0..=b_chars.len()).collect::<Vec<usize>>();
+    let mut curr = vec![0; b_chars.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+
+        for (j, cb) in b_chars.iter().enumerate() {
+            let subst = if ca == *cb { prev[j] } else { prev[j] + 1 };
+
+            curr[j + 1] = subst.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+
+        core::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b_chars.len()]
+}
+
+// returns the candidate closest to `name`, as long as it's close enough to be a likely misspelling
+// (at most one edit per 3 characters). ties are broken alphabetically, so the result is deterministic
+pub fn suggest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let max_distance = core::cmp::max(1, name.chars().count() / 3);
+
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn edit_distance_identical() {
+        assert_eq!(0, edit_distance("FORWARD", "FORWARD"));
+    }
+
+    #[test]
+    pub fn edit_distance_empty() {
+        assert_eq!(3, edit_distance("", "FOO"));
+        assert_eq!(3, edit_distance("FOO", ""));
+    }
+
+    #[test]
+    pub fn edit_distance_edits() {
+        assert_eq!(1, edit_distance("FORWRD", "FORWARD"));
+        assert_eq!(1, edit_distance("PENUPP", "PENUP"));
+        assert_eq!(1, edit_distance("MAKR", "MAKE"));
+        assert_eq!(3, edit_distance("KITTEN", "SITTING"));
+    }
+
+    #[test]
+    pub fn suggest_closest() {
+        let candidates = ["FORWARD", "BACKWARD", "RIGHT", "LEFT"];
+
+        assert_eq!(
+            Some("FORWARD".to_string()),
+            suggest("FORWRD", candidates.iter().copied())
+        );
+    }
+
+    #[test]
+    pub fn suggest_too_far() {
+        let candidates = ["FORWARD", "BACKWARD"];
+
+        assert_eq!(None, suggest("JUMP", candidates.iter().copied()));
+    }
+
+    #[test]
+    pub fn suggest_ties_broken_alphabetically() {
+        let candidates = ["AC", "AB"];
+
+        assert_eq!(
+            Some("AB".to_string()),
+            suggest("AA", candidates.iter().copied())
+        );
+    }
+
+    #[test]
+    pub fn suggest_skips_exact_match() {
+        let candidates = ["FOO"];
+
+        assert_eq!(None, suggest("FOO", candidates.iter().copied()));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::ast::statement::Command;
+use crate::compat::prelude::*;
+use crate::grading::{Point, Segment};
+use crate::vm::{Bounds, HostCall, Movement, Pen, PenState, Turtle};
+
+// a mirror axis, passing through the center of the drawing bounds
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Axis {
+    // mirrors left and right
+    Vertical,
+
+    // mirrors top and bottom
+    Horizontal,
+}
+
+// the segments drawn by a program, in drawing order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drawing {
+    segments: Vec<Segment>,
+}
+
+impl Drawing {
+    pub fn new(segments: Vec<Segment>) -> Self {
+        Self { segments }
+    }
+
+    // replays the host-calls (e.g recorded by a `RecordingHost`) over a turtle & pen,
+    // collecting the moves made while the pen was down
+    pub fn from_host_calls(calls: &[HostCall]) -> Self {
+        let mut turtle = Turtle::new();
+        let mut pen = Pen::new();
+        let mut segments = Vec::new();
+
+        for call in calls {
+            match call {
+                HostCall::Cmd(Command::PenUp) => pen.up(),
+                HostCall::Cmd(Command::PenDown) => pen.down(),
+                HostCall::Cmd(Command::PenErase) => pen.erase(),
+                HostCall::StateRestored(saved_turtle, saved_pen) => {
+                    turtle = saved_turtle.clone();
+                    pen = saved_pen.clone();
+                }
+                HostCall::Turn(degrees) => turtle.turn_degrees(*degrees),
+                HostCall::Direct(direct, count)
+                | HostCall::Movement(Movement { direct, count, .. }) => {
+                    let from = (turtle.xcor(), turtle.ycor());
+                    turtle.exec_direct(direct, *count);
+                    let to = (turtle.xcor(), turtle.ycor());
+
+                    if *pen.get_state() == PenState::Down && from != to {
+                        segments.push(Segment::new(from, to));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self::new(segments)
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    pub fn path_length(&self) -> f64 {
+        self.segments.iter().map(Segment::length).sum()
+    }
+
+    // `None` when nothing was drawn
+    pub fn bounds(&self) -> Option<Bounds> {
+        let (first, rest) = self.segments.split_first()?;
+
+        let mut bounds = Bounds::at(first.from.0, first.from.1);
+        bounds.extend(first.to.0, first.to.1);
+
+        for segment in rest {
+            bounds.extend(segment.from.0, segment.from.1);
+            bounds.extend(segment.to.0, segment.to.1);
+        }
+
+        Some(bounds)
+    }
+
+    // the direction of each segment (see `Segment::angle`)
+    pub fn angles(&self) -> Vec<f64> {
+        self.segments.iter().map(Segment::angle).collect()
+    }
+
+    // the number of strokes (runs of segments, each starting where the previous one has ended)
+    // of at least 3 segments, which end where they've started.
+    // a stroke continuing past its start point may close a few polygons (e.g a figure eight)
+    pub fn closed_polygons(&self) -> usize {
+        let mut polygons = 0;
+        let mut stroke_start = 0;
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > stroke_start && segment.from != self.segments[i - 1].to {
+                stroke_start = i;
+            }
+
+            let stroke_len = i - stroke_start + 1;
+
+            if stroke_len >= 3 && segment.to == self.segments[stroke_start].from {
+                polygons += 1;
+                stroke_start = i + 1;
+            }
+        }
+
+        polygons
+    }
+
+    // the share (within `[0, 1]`) of segments the drawings have in common, regardless of their
+    // drawing order, where each segment endpoint may be off by up to `tolerance` steps.
+    // two empty drawings are the same
+    pub fn similarity(&self, other: &Drawing, tolerance: f64) -> f64 {
+        let total = self.segments.len().max(other.segments.len());
+
+        if total == 0 {
+            return 1.0;
+        }
+
+        count_matches(&self.segments, &other.segments, tolerance) as f64 / total as f64
+    }
+
+    // whether mirroring the drawing about `axis` results in the same segments
+    pub fn is_symmetric(&self, axis: Axis) -> bool {
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return true,
+        };
+
+        let mirror = |(x, y): Point| match axis {
+            Axis::Vertical => (bounds.min_x + bounds.max_x - x, y),
+            Axis::Horizontal => (x, bounds.min_y + bounds.max_y - y),
+        };
+
+        let mirrored = self
+            .segments
+            .iter()
+            .map(|segment| segment.map(mirror))
+            .collect::<Vec<_>>();
+
+        same_segments(&self.segments, &mirrored, 0.0)
+    }
+
+    // whether the drawing has the same shape as `reference`, regardless of their position,
+    // of rotations in multiples of 90 degrees and of reflections.
+    // each segment endpoint may be off by up to `tolerance` steps
+    pub fn is_congruent(&self, reference: &Drawing, tolerance: f64) -> bool {
+        if self.segments.len() != reference.segments.len() {
+            return false;
+        }
+
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return true,
+        };
+
+        let transforms: [fn(Point) -> Point; 8] = [
+            |(x, y)| (x, y),
+            |(x, y)| (-y, x),
+            |(x, y)| (-x, -y),
+            |(x, y)| (y, -x),
+            |(x, y)| (-x, y),
+            |(x, y)| (y, x),
+            |(x, y)| (x, -y),
+            |(x, y)| (-y, -x),
+        ];
+
+        transforms.iter().any(|transform| {
+            let transformed = reference
+                .segments
+                .iter()
+                .map(|segment| segment.map(transform))
+                .collect::<Vec<_>>();
+
+            // aligning the bounds of both drawings
+            let ref_bounds = Drawing::new(transformed.clone()).bounds().unwrap();
+            let (dx, dy) = (
+                bounds.min_x - ref_bounds.min_x,
+                bounds.min_y - ref_bounds.min_y,
+            );
+
+            let aligned = transformed
+                .iter()
+                .map(|segment| segment.map(|(x, y)| (x + dx, y + dy)))
+                .collect::<Vec<_>>();
+
+            same_segments(&self.segments, &aligned, tolerance)
+        })
+    }
+}
+
+// whether each segment of `a` matches a distinct segment of `b` (see `Segment::matches`)
+fn same_segments(a: &[Segment], b: &[Segment], tolerance: f64) -> bool {
+    a.len() == b.len() && count_matches(a, b, tolerance) == a.len()
+}
+
+// the number of segments of `a` matching a distinct segment of `b`
+fn count_matches(a: &[Segment], b: &[Segment], tolerance: f64) -> usize {
+    let mut matched = vec![false; b.len()];
+
+    a.iter()
+        .filter(|segment| {
+            let found = (0..b.len()).find(|&i| !matched[i] && segment.matches(&b[i], tolerance));
+
+            match found {
+                Some(i) => {
+                    matched[i] = true;
+                    true
+                }
+                None => false,
+            }
+        })
+        .count()
+}
@@ -0,0 +1,256 @@
+use crate::ast::expression::ExpressionType;
+use crate::ast::semantic::Procedure;
+use crate::compat::prelude::*;
+use crate::compiler::{CompileOptions, Compiler};
+use crate::diagnostics::{Diagnostic, Message};
+use crate::grading::{grade, GRADE_MAX_STEPS};
+use crate::runner::RunOptions;
+use crate::vm::DummyHost;
+use crate::TytleError;
+use core::fmt;
+
+// a procedure an exercise requires the student code to define
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcSignature {
+    pub name: String,
+    pub params_types: Vec<ExpressionType>,
+    pub return_type: ExpressionType,
+}
+
+impl ProcSignature {
+    pub fn new(name: &str, params_types: Vec<ExpressionType>, return_type: ExpressionType) -> Self {
+        Self {
+            name: name.to_string(),
+            params_types,
+            return_type,
+        }
+    }
+
+    fn matches(&self, proc: &Procedure) -> bool {
+        self.params_types == proc.params_types && self.return_type == proc.return_type
+    }
+}
+
+// e.g `POLYGON(INT, INT)` or `DOUBLE(INT): INT`
+impl fmt::Display for ProcSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let signature = format_signature(&self.name, &self.params_types, &self.return_type);
+
+        write!(f, "{}", signature)
+    }
+}
+
+// the outcome of running a single exercise case
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseResult {
+    // the code appended to the student code for running the case (e.g `SQUARE(50)`)
+    pub invocation: String,
+
+    pub passed: bool,
+
+    // why the case has failed
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseReport {
+    // errors preventing the cases from running (compilation errors and mismatching signatures)
+    pub diagnostics: Vec<Diagnostic>,
+
+    // empty when the cases couldn't run
+    pub cases: Vec<CaseResult>,
+}
+
+impl ExerciseReport {
+    pub fn passed(&self) -> bool {
+        self.diagnostics.is_empty() && self.cases.iter().all(|case| case.passed)
+    }
+
+    pub fn passed_cases(&self) -> usize {
+        self.cases.iter().filter(|case| case.passed).count()
+    }
+}
+
+// an exercise checking student code against the required procedures and a set of (hidden) cases:
+//
+// ```
+// let exercise = Exercise::new()
+//     .require_proc(ProcSignature::new("SQUARE", vec![ExpressionType::Int], ExpressionType::Unit))
+//     .case("SQUARE(50)")
+//     .case("SQUARE(120)")
+//     .reference(SQUARE_SOLUTION, 0.0);
+//
+// let report = exercise.check(student_src);
+// ```
+//
+// each case runs on a fresh VM, as the student code followed by the case invocation.
+// a case passes when it runs successfully and (given a reference solution) draws the same
+// segments as the reference solution running the same invocation (see `grade`)
+#[derive(Debug, Clone, Default)]
+pub struct Exercise {
+    signatures: Vec<ProcSignature>,
+    cases: Vec<String>,
+    reference: Option<(String, f64)>,
+}
+
+impl Exercise {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn require_proc(mut self, signature: ProcSignature) -> Self {
+        self.signatures.push(signature);
+        self
+    }
+
+    pub fn case(mut self, invocation: &str) -> Self {
+        self.cases.push(invocation.to_string());
+        self
+    }
+
+    // the solution the cases drawings are compared against, where each segment endpoint
+    // may be off by up to `tolerance` steps
+    pub fn reference(mut self, source: &str, tolerance: f64) -> Self {
+        self.reference = Some((source.to_string(), tolerance));
+        self
+    }
+
+    pub fn check(&self, student_src: &str) -> ExerciseReport {
+        let diagnostics = self.check_signatures(student_src);
+
+        if !diagnostics.is_empty() {
+            return ExerciseReport {
+                diagnostics,
+                cases: Vec::new(),
+            };
+        }
+
+        let cases = self
+            .cases
+            .iter()
+            .map(|invocation| self.check_case(student_src, invocation))
+            .collect();
+
+        ExerciseReport { diagnostics, cases }
+    }
+
+    fn check_signatures(&self, student_src: &str) -> Vec<Diagnostic> {
+        let program = match Compiler::compile(student_src, CompileOptions::default()) {
+            Ok(program) => program,
+            Err(err) => return vec![Diagnostic::from(&TytleError::from(err))],
+        };
+
+        let symbol_table = &program.env.symbol_table;
+
+        self.signatures
+            .iter()
+            .filter_map(|signature| {
+                // an overloaded procedure matches when any of its definitions does
+                let overloads = symbol_table.get_proc_overloads(&signature.name);
+                let proc = overloads
+                    .iter()
+                    .find(|proc| signature.matches(proc))
+                    .or_else(|| overloads.first())
+                    .copied();
+
+                match proc {
+                    Some(proc) if signature.matches(proc) => None,
+                    Some(proc) => {
+                        let expected = signature.to_string();
+                        let actual =
+                            format_signature(&proc.name, &proc.params_types, &proc.return_type);
+
+                        let msg = format!(
+                            "Procedure `{}` should be `{}` (got `{}`)",
+                            proc.name, expected, actual
+                        );
+
+                        let message = Message::new("exercise.proc-signature")
+                            .arg("proc", &proc.name)
+                            .arg("expected", expected)
+                            .arg("actual", actual);
+
+                        Some(Diagnostic::error(&msg).localized(message))
+                    }
+                    None => {
+                        let msg = format!("Missing procedure `{}`", signature);
+                        let message = Message::new("exercise.missing-proc")
+                            .arg("proc", signature.to_string());
+
+                        Some(Diagnostic::error(&msg).localized(message))
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn check_case(&self, student_src: &str, invocation: &str) -> CaseResult {
+        let source = format!("{}\n{}", student_src, invocation);
+
+        let result = match &self.reference {
+            Some((reference_src, tolerance)) => {
+                let reference_src = format!("{}\n{}", reference_src, invocation);
+
+                grade(&source, &reference_src, *tolerance).map(|report| report.score)
+            }
+            None => {
+                let options = RunOptions {
+                    max_steps: Some(GRADE_MAX_STEPS),
+                    ..RunOptions::default()
+                };
+
+                crate::run(&source, &mut DummyHost::new(), options).map(|_| 1.0)
+            }
+        };
+
+        let diagnostics = match result {
+            Ok(score) if score >= 1.0 => Vec::new(),
+            Ok(score) => {
+                let similarity = (score * 100.0).round();
+
+                let msg = format!(
+                    "The drawing of `{}` differs from the expected one ({}% similar)",
+                    invocation, similarity
+                );
+
+                let message = Message::new("exercise.drawing-mismatch")
+                    .arg("case", invocation)
+                    .arg("similarity", similarity);
+
+                vec![Diagnostic::error(&msg).localized(message)]
+            }
+            Err(err) => vec![Diagnostic::from(&err)],
+        };
+
+        CaseResult {
+            invocation: invocation.to_string(),
+            passed: diagnostics.is_empty(),
+            diagnostics,
+        }
+    }
+}
+
+fn format_signature(
+    name: &str,
+    params_types: &[ExpressionType],
+    return_type: &ExpressionType,
+) -> String {
+    let params = params_types
+        .iter()
+        .map(type_keyword)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match return_type {
+        ExpressionType::Unit => format!("{}({})", name, params),
+        _ => format!("{}({}): {}", name, params, type_keyword(return_type)),
+    }
+}
+
+// the type as written in code (e.g `[INT]`)
+fn type_keyword(expr_type: &ExpressionType) -> String {
+    match expr_type {
+        ExpressionType::List(elem_type) => format!("[{}]", type_keyword(elem_type)),
+        _ => expr_type.as_keyword().to_string(),
+    }
+}
@@ -0,0 +1,104 @@
+use crate::compat::prelude::*;
+use crate::grading::Drawing;
+use crate::runner::RunOptions;
+use crate::vm::{DummyHost, HostCall, RecordingHost};
+use crate::TytleError;
+
+// a program looping forever is aborted after that many interpreter steps
+pub const GRADE_MAX_STEPS: u64 = 1_000_000;
+
+// the first drawing host-call (a turtle move or a `Command`) in which the programs differ.
+// a `None` call means that program has made fewer drawing host-calls
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    // the index of the host-call, counting only drawing host-calls
+    pub index: usize,
+
+    pub student: Option<HostCall>,
+    pub reference: Option<HostCall>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GradeReport {
+    // the share (within `[0, 1]`) of drawn segments the programs have in common
+    // (see `Drawing::similarity`)
+    pub score: f64,
+
+    // `None` when both programs made the same drawing host-calls
+    pub divergence: Option<Divergence>,
+
+    pub student: Drawing,
+    pub reference: Drawing,
+}
+
+// runs both programs (each over a fresh `DummyHost`) and compares their drawings.
+// fails when either program fails to compile or to run
+pub fn grade(
+    student_src: &str,
+    reference_src: &str,
+    tolerance: f64,
+) -> Result<GradeReport, TytleError> {
+    let student_calls = record(student_src)?;
+    let reference_calls = record(reference_src)?;
+
+    let student = Drawing::from_host_calls(&student_calls);
+    let reference = Drawing::from_host_calls(&reference_calls);
+
+    Ok(GradeReport {
+        score: student.similarity(&reference, tolerance),
+        divergence: diverge(&student_calls, &reference_calls),
+        student,
+        reference,
+    })
+}
+
+fn record(source: &str) -> Result<Vec<HostCall>, TytleError> {
+    let options = RunOptions {
+        max_steps: Some(GRADE_MAX_STEPS),
+        ..RunOptions::default()
+    };
+
+    let mut host = RecordingHost::new(DummyHost::new());
+    crate::run(source, &mut host, options)?;
+
+    let (_, calls) = host.into_parts();
+    Ok(calls)
+}
+
+fn diverge(student_calls: &[HostCall], reference_calls: &[HostCall]) -> Option<Divergence> {
+    let is_drawing = |call: &&HostCall| {
+        matches!(
+            call,
+            HostCall::Cmd(_)
+                | HostCall::Direct(..)
+                | HostCall::Turn(_)
+                | HostCall::Movement(_)
+                | HostCall::StateRestored(..)
+                | HostCall::Stamp { .. }
+                | HostCall::Label(_)
+        )
+    };
+
+    let mut student = student_calls.iter().filter(is_drawing);
+    let mut reference = reference_calls.iter().filter(is_drawing);
+
+    let mut index = 0;
+
+    loop {
+        let (student, reference) = (student.next(), reference.next());
+
+        if student.is_none() && reference.is_none() {
+            return None;
+        }
+
+        if student != reference {
+            return Some(Divergence {
+                index,
+                student: student.cloned(),
+                reference: reference.cloned(),
+            });
+        }
+
+        index += 1;
+    }
+}
@@ -0,0 +1,19 @@
+// auto-grading of exercises over the drawing of a program (see `Drawing`):
+// * `closed_polygons` - the number of closed shapes drawn in a single stroke
+// * `angles` - the direction of each drawn segment
+// * `is_symmetric` - a mirror symmetry check about an axis of the drawing
+// * `is_congruent` - an approximate comparison against a reference drawing
+//
+// a drawing is usually built from the host-calls recorded by a `RecordingHost`.
+// `grade` runs a student program along with a reference solution and compares their drawings,
+// and an `Exercise` checks the procedures defined by student code over a set of cases
+
+mod drawing;
+mod exercise;
+mod grade;
+mod segment;
+
+pub use drawing::{Axis, Drawing};
+pub use exercise::{CaseResult, Exercise, ExerciseReport, ProcSignature};
+pub use grade::{grade, Divergence, GradeReport, GRADE_MAX_STEPS};
+pub use segment::{Point, Segment};
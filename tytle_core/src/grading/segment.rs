@@ -0,0 +1,59 @@
+// a position on the canvas, in turtle steps
+pub type Point = (isize, isize);
+
+// a straight line drawn while the pen was down
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub from: Point,
+    pub to: Point,
+}
+
+impl Segment {
+    pub fn new(from: Point, to: Point) -> Self {
+        Self { from, to }
+    }
+
+    pub fn length(&self) -> f64 {
+        let (dx, dy) = self.delta();
+
+        (dx as f64).hypot(dy as f64)
+    }
+
+    // the segment direction in degrees, counterclockwise from the x axis (within `[0, 360)`).
+    // e.g a `FORWARD` segment is at 90 degrees
+    pub fn angle(&self) -> f64 {
+        let (dx, dy) = self.delta();
+        let angle = (dy as f64).atan2(dx as f64).to_degrees();
+
+        if angle < 0.0 {
+            angle + 360.0
+        } else {
+            angle
+        }
+    }
+
+    // whether the segments have the same endpoints (regardless of their direction),
+    // where each endpoint may be off by up to `tolerance` steps
+    pub fn matches(&self, other: &Segment, tolerance: f64) -> bool {
+        let near = |a: Point, b: Point| distance(a, b) <= tolerance;
+
+        (near(self.from, other.from) && near(self.to, other.to))
+            || (near(self.from, other.to) && near(self.to, other.from))
+    }
+
+    // the segment with both endpoints mapped by `f`
+    pub fn map<F>(&self, f: F) -> Segment
+    where
+        F: Fn(Point) -> Point,
+    {
+        Segment::new(f(self.from), f(self.to))
+    }
+
+    fn delta(&self) -> (isize, isize) {
+        (self.to.0 - self.from.0, self.to.1 - self.from.1)
+    }
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    ((a.0 - b.0) as f64).hypot((a.1 - b.1) as f64)
+}
@@ -0,0 +1,42 @@
+// the safety net of the public API entry points (`run`, `render_to_svg`):
+// a panic (i.e a bug of tytle) which escapes the pipeline is returned as a `TytleError::Internal`,
+// so a malformed program can't take down the embedder (e.g a server running untrusted programs).
+//
+// the panic is still reported by the panic hook (printing it to `stderr` by default).
+// without `std` there's no unwinding to catch, so the entry point runs unguarded
+
+use crate::TytleError;
+
+#[cfg(feature = "std")]
+pub(crate) fn guard<T, F>(context: &str, f: F) -> Result<T, TytleError>
+where
+    F: FnOnce() -> Result<T, TytleError>,
+{
+    use std::panic::{self, AssertUnwindSafe};
+
+    // a caught panic leaves nothing of the failed call behind, except for the state of
+    // the host (which may have received only a part of the host-calls)
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        // `panic!` payloads are either a `&str` or a (formatted) `String`
+        let message = match payload.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => match payload.downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => "unknown panic".to_string(),
+            },
+        };
+
+        Err(TytleError::Internal {
+            context: context.to_string(),
+            message,
+        })
+    })
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn guard<T, F>(_context: &str, f: F) -> Result<T, TytleError>
+where
+    F: FnOnce() -> Result<T, TytleError>,
+{
+    f()
+}
@@ -0,0 +1,19 @@
+// the `tracing` instrumentation of the compiler and of the VM (under the `tracing` feature),
+// for the embedders debugging where the time goes or what the passes have done.
+// without the feature the macros expand to nothing
+
+// enters a span till the end of the enclosing block, e.g `enter_span!("parse")`
+macro_rules! enter_span {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!($($arg)+).entered();
+    };
+}
+
+// records an event within the current span, e.g `trace_event!(pass = "cse", rewrites)`
+macro_rules! trace_event {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)+);
+    };
+}
@@ -0,0 +1,181 @@
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::compat::collections::{HashMap, HashSet};
+use crate::compat::prelude::*;
+use crate::ir::{CfgGraph, CfgInstruction, CfgNodeId, CfgObject, CfgVisitor};
+
+// a `Load` of a local variable along a path on which the variable may not have been assigned yet
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnassignedUse {
+    pub proc_id: SymbolId,
+    pub var_id: SymbolId,
+
+    // the node loading the variable
+    pub use_node: CfgNodeId,
+
+    // the first node (in reverse post-order) storing the variable, i.e its `MAKELOCAL` (if any)
+    pub decl_node: Option<CfgNodeId>,
+}
+
+// a forward "must" data-flow analysis over a procedure's sub-graph.
+// a variable is definitely assigned at a node's entry when it's assigned along every path
+// from the procedure entry node to it (the procedure params are assigned by the call itself).
+//
+// unreachable nodes are ignored
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefiniteAssignment {
+    unassigned_uses: Vec<UnassignedUse>,
+}
+
+impl DefiniteAssignment {
+    // analyzes the procedure `proc_id` entered at `entry`, tracking the variables `tracked`
+    pub fn new(
+        graph: &CfgGraph,
+        entry: CfgNodeId,
+        proc_id: SymbolId,
+        tracked: &HashSet<SymbolId>,
+    ) -> Self {
+        let rpo = graph.rpo_from(entry);
+
+        // the variables definitely assigned at each node's exit (nodes not visited yet are missing)
+        let mut assigned_out: HashMap<CfgNodeId, HashSet<SymbolId>> = HashMap::new();
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for node_id in rpo.iter() {
+                let mut assigned = Self::assigned_in(graph, *node_id, entry, &assigned_out);
+
+                for inst in graph.get_node(*node_id).insts.iter() {
+                    if let CfgInstruction::Store(var_id) = inst {
+                        assigned.insert(*var_id);
+                    }
+                }
+
+                if assigned_out.get(node_id) != Some(&assigned) {
+                    assigned_out.insert(*node_id, assigned);
+                    changed = true;
+                }
+            }
+        }
+
+        let decl_nodes = Self::decl_nodes(graph, entry, tracked);
+        let mut unassigned_uses = Vec::new();
+
+        for node_id in rpo.iter() {
+            let mut assigned = Self::assigned_in(graph, *node_id, entry, &assigned_out);
+
+            for inst in graph.get_node(*node_id).insts.iter() {
+                match inst {
+                    CfgInstruction::Store(var_id) => {
+                        assigned.insert(*var_id);
+                    }
+                    CfgInstruction::Load(var_id)
+                        if tracked.contains(var_id) && !assigned.contains(var_id) =>
+                    {
+                        unassigned_uses.push(UnassignedUse {
+                            proc_id,
+                            var_id: *var_id,
+                            use_node: *node_id,
+                            decl_node: decl_nodes.get(var_id).copied(),
+                        });
+
+                        // reporting each variable once per node
+                        assigned.insert(*var_id);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Self { unassigned_uses }
+    }
+
+    // analyzes each procedure of `cfg`, tracking the procedures locals (excluding their params)
+    pub fn analyze_program(cfg: &CfgObject, env: &Environment) -> Vec<UnassignedUse> {
+        let mut unassigned_uses = Vec::new();
+
+        for (entry_id, proc_id) in cfg.jmp_table.iter() {
+            let tracked = env
+                .locals_symbols
+                .get(proc_id)
+                .into_iter()
+                .flatten()
+                .filter(|var_id| !env.symbol_table.get_var_by_id(**var_id).param)
+                .copied()
+                .collect::<HashSet<SymbolId>>();
+
+            if tracked.is_empty() {
+                continue;
+            }
+
+            let analysis = Self::new(&cfg.graph, *entry_id, *proc_id, &tracked);
+            unassigned_uses.extend(analysis.unassigned_uses);
+        }
+
+        unassigned_uses
+    }
+
+    pub fn unassigned_uses(&self) -> &[UnassignedUse] {
+        &self.unassigned_uses
+    }
+
+    pub fn is_sound(&self) -> bool {
+        self.unassigned_uses.is_empty()
+    }
+
+    // the intersection of the predecessors exits
+    // (the unreachable predecessors and those not computed yet are skipped)
+    fn assigned_in(
+        graph: &CfgGraph,
+        node_id: CfgNodeId,
+        entry: CfgNodeId,
+        assigned_out: &HashMap<CfgNodeId, HashSet<SymbolId>>,
+    ) -> HashSet<SymbolId> {
+        if node_id == entry {
+            return HashSet::new();
+        }
+
+        let mut assigned: Option<HashSet<SymbolId>> = None;
+
+        for pred_id in graph.predecessors(node_id) {
+            if let Some(pred_out) = assigned_out.get(&pred_id) {
+                assigned = match assigned {
+                    None => Some(pred_out.clone()),
+                    Some(assigned) => Some(assigned.intersection(pred_out).copied().collect()),
+                };
+            }
+        }
+
+        assigned.unwrap_or_default()
+    }
+
+    fn decl_nodes(
+        graph: &CfgGraph,
+        entry: CfgNodeId,
+        tracked: &HashSet<SymbolId>,
+    ) -> HashMap<SymbolId, CfgNodeId> {
+        let mut visitor = DeclNodes {
+            tracked,
+            decl_nodes: HashMap::new(),
+        };
+
+        visitor.visit_proc(graph, entry);
+        visitor.decl_nodes
+    }
+}
+
+// the first node (in reverse post-order) storing each tracked variable
+struct DeclNodes<'a> {
+    tracked: &'a HashSet<SymbolId>,
+    decl_nodes: HashMap<SymbolId, CfgNodeId>,
+}
+
+impl<'a> CfgVisitor for DeclNodes<'a> {
+    fn on_store(&mut self, node_id: CfgNodeId, var_id: SymbolId) {
+        if self.tracked.contains(&var_id) {
+            self.decl_nodes.entry(var_id).or_insert(node_id);
+        }
+    }
+}
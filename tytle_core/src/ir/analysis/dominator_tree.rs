@@ -0,0 +1,177 @@
+use crate::compat::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::compat::prelude::*;
+use crate::ir::{CfgGraph, CfgNodeId};
+
+// node `a` dominates node `b` if each path from the entry node to `b` goes through `a`.
+// computed using the iterative algorithm of Cooper, Harvey & Kennedy
+// ("A Simple, Fast Dominance Algorithm")
+#[derive(Debug, Clone, PartialEq)]
+pub struct DominatorTree {
+    entry: CfgNodeId,
+
+    // the nodes reachable from `entry` in reverse post-order
+    rpo: Vec<CfgNodeId>,
+
+    // the immediate dominator of each reachable node (the entry node is its own immediate dominator)
+    idoms: HashMap<CfgNodeId, CfgNodeId>,
+}
+
+impl DominatorTree {
+    pub fn new(graph: &CfgGraph, entry: CfgNodeId) -> Self {
+        let rpo = graph.rpo_from(entry);
+
+        let rpo_index = rpo
+            .iter()
+            .enumerate()
+            .map(|(i, node_id)| (*node_id, i))
+            .collect::<HashMap<CfgNodeId, usize>>();
+
+        let mut idoms = HashMap::new();
+        idoms.insert(entry, entry);
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for node_id in rpo.iter().skip(1) {
+                let mut new_idom = None;
+
+                // the unreachable predecessors (and those not computed yet) are skipped
+                for pred_id in graph.predecessors(*node_id) {
+                    if !idoms.contains_key(&pred_id) {
+                        continue;
+                    }
+
+                    new_idom = match new_idom {
+                        None => Some(pred_id),
+                        Some(idom) => Some(Self::intersect(&idoms, &rpo_index, pred_id, idom)),
+                    };
+                }
+
+                let new_idom = new_idom.unwrap();
+
+                if idoms.get(node_id) != Some(&new_idom) {
+                    idoms.insert(*node_id, new_idom);
+                    changed = true;
+                }
+            }
+        }
+
+        Self { entry, rpo, idoms }
+    }
+
+    pub fn entry(&self) -> CfgNodeId {
+        self.entry
+    }
+
+    // the nodes reachable from the entry node, in reverse post-order
+    pub fn nodes(&self) -> &[CfgNodeId] {
+        &self.rpo
+    }
+
+    pub fn is_reachable(&self, node_id: CfgNodeId) -> bool {
+        self.idoms.contains_key(&node_id)
+    }
+
+    // returns `None` for the entry node and for unreachable nodes
+    pub fn idom(&self, node_id: CfgNodeId) -> Option<CfgNodeId> {
+        if node_id == self.entry {
+            None
+        } else {
+            self.idoms.get(&node_id).copied()
+        }
+    }
+
+    // a node always dominates itself
+    pub fn dominates(&self, a: CfgNodeId, b: CfgNodeId) -> bool {
+        self.dominators(b).contains(&a)
+    }
+
+    // the dominators of `node_id`, starting at the node itself and ending at the entry node
+    pub fn dominators(&self, node_id: CfgNodeId) -> Vec<CfgNodeId> {
+        let mut doms = Vec::new();
+
+        if !self.is_reachable(node_id) {
+            return doms;
+        }
+
+        let mut current = Some(node_id);
+
+        while let Some(node_id) = current {
+            doms.push(node_id);
+            current = self.idom(node_id);
+        }
+
+        doms
+    }
+
+    // the nodes immediately dominated by `node_id` (sorted by id)
+    pub fn children(&self, node_id: CfgNodeId) -> Vec<CfgNodeId> {
+        let mut children = self
+            .rpo
+            .iter()
+            .filter(|child_id| self.idom(**child_id) == Some(node_id))
+            .copied()
+            .collect::<Vec<CfgNodeId>>();
+
+        children.sort();
+        children
+    }
+
+    // the dominance frontier of each reachable node: the nodes where its dominance ends,
+    // i.e the nodes it doesn't strictly dominate having a predecessor it dominates.
+    // (the nodes having an empty frontier are missing)
+    pub fn frontiers(&self, graph: &CfgGraph) -> BTreeMap<CfgNodeId, BTreeSet<CfgNodeId>> {
+        let mut frontiers: BTreeMap<CfgNodeId, BTreeSet<CfgNodeId>> = BTreeMap::new();
+
+        for node_id in self.rpo.iter() {
+            let preds = graph
+                .predecessors(*node_id)
+                .into_iter()
+                .filter(|pred_id| self.is_reachable(*pred_id))
+                .collect::<Vec<CfgNodeId>>();
+
+            if preds.len() < 2 {
+                continue;
+            }
+
+            let idom = self.idom(*node_id);
+
+            for pred_id in preds {
+                let mut runner = Some(pred_id);
+
+                while runner.is_some() && runner != idom {
+                    let runner_id = runner.unwrap();
+
+                    frontiers.entry(runner_id).or_default().insert(*node_id);
+                    runner = self.idom(runner_id);
+                }
+            }
+        }
+
+        frontiers
+    }
+
+    fn intersect(
+        idoms: &HashMap<CfgNodeId, CfgNodeId>,
+        rpo_index: &HashMap<CfgNodeId, usize>,
+        a: CfgNodeId,
+        b: CfgNodeId,
+    ) -> CfgNodeId {
+        let mut a = a;
+        let mut b = b;
+
+        while a != b {
+            while rpo_index[&a] > rpo_index[&b] {
+                a = idoms[&a];
+            }
+
+            while rpo_index[&b] > rpo_index[&a] {
+                b = idoms[&b];
+            }
+        }
+
+        a
+    }
+}
@@ -0,0 +1,114 @@
+use crate::compat::collections::HashSet;
+use crate::compat::prelude::*;
+use crate::ir::analysis::DominatorTree;
+use crate::ir::{CfgGraph, CfgNodeId};
+
+// a natural loop is defined by its back-edges (edges `latch -> header` where `header` dominates `latch`).
+// the loop body consists of the header and all the nodes that can reach a latch without going through the header
+#[derive(Debug, Clone, PartialEq)]
+pub struct NaturalLoop {
+    pub header: CfgNodeId,
+    pub latches: Vec<CfgNodeId>,
+    pub body: HashSet<CfgNodeId>,
+}
+
+impl NaturalLoop {
+    pub fn contains(&self, node_id: CfgNodeId) -> bool {
+        self.body.contains(&node_id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoopInfo {
+    back_edges: Vec<(CfgNodeId, CfgNodeId)>,
+
+    // loops sharing the same header are merged into a single loop. ordered by the header node id
+    loops: Vec<NaturalLoop>,
+}
+
+impl LoopInfo {
+    pub fn new(graph: &CfgGraph, dom_tree: &DominatorTree) -> Self {
+        let mut back_edges = Vec::new();
+
+        for src_id in dom_tree.nodes() {
+            for dst_id in graph.successors(*src_id) {
+                if dom_tree.dominates(dst_id, *src_id) {
+                    back_edges.push((*src_id, dst_id));
+                }
+            }
+        }
+
+        back_edges.sort();
+
+        let mut loops: Vec<NaturalLoop> = Vec::new();
+
+        for (latch, header) in back_edges.iter() {
+            let body = Self::loop_body(graph, dom_tree, *header, *latch);
+
+            match loops.iter_mut().find(|lp| lp.header == *header) {
+                Some(lp) => {
+                    lp.latches.push(*latch);
+                    lp.body.extend(body);
+                }
+                None => loops.push(NaturalLoop {
+                    header: *header,
+                    latches: vec![*latch],
+                    body,
+                }),
+            }
+        }
+
+        loops.sort_by_key(|lp| lp.header);
+
+        Self { back_edges, loops }
+    }
+
+    // the back-edges as `(source, destination)` pairs
+    pub fn back_edges(&self) -> &[(CfgNodeId, CfgNodeId)] {
+        &self.back_edges
+    }
+
+    pub fn loops(&self) -> &[NaturalLoop] {
+        &self.loops
+    }
+
+    pub fn is_loop_header(&self, node_id: CfgNodeId) -> bool {
+        self.loops.iter().any(|lp| lp.header == node_id)
+    }
+
+    pub fn loop_headers(&self) -> Vec<CfgNodeId> {
+        self.loops.iter().map(|lp| lp.header).collect()
+    }
+
+    // the number of loops containing `node_id` (`0` means it's not inside any loop)
+    pub fn loop_depth(&self, node_id: CfgNodeId) -> usize {
+        self.loops.iter().filter(|lp| lp.contains(node_id)).count()
+    }
+
+    fn loop_body(
+        graph: &CfgGraph,
+        dom_tree: &DominatorTree,
+        header: CfgNodeId,
+        latch: CfgNodeId,
+    ) -> HashSet<CfgNodeId> {
+        let mut body = HashSet::new();
+        body.insert(header);
+
+        let mut worklist = vec![latch];
+
+        // walking backwards from the latch, the header stops the walk since it's already in the body
+        while let Some(node_id) = worklist.pop() {
+            if !body.insert(node_id) {
+                continue;
+            }
+
+            for pred_id in graph.predecessors(node_id) {
+                if dom_tree.is_reachable(pred_id) {
+                    worklist.push(pred_id);
+                }
+            }
+        }
+
+        body
+    }
+}
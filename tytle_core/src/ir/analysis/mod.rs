@@ -0,0 +1,13 @@
+// analyses over a `CfgGraph`.
+// each procedure is a separate sub-graph (procedures are entered via `Call` instructions, not edges),
+// so the analyses are computed per procedure, starting at the procedure entry node
+
+mod definite_assignment;
+mod dominator_tree;
+mod loop_info;
+mod state_balance;
+
+pub use definite_assignment::{DefiniteAssignment, UnassignedUse};
+pub use dominator_tree::DominatorTree;
+pub use loop_info::{LoopInfo, NaturalLoop};
+pub use state_balance::StateBalance;
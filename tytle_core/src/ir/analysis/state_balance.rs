@@ -0,0 +1,97 @@
+use crate::ast::semantic::SymbolId;
+use crate::ast::statement::Command;
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
+use crate::ir::{CfgGraph, CfgInstruction, CfgNodeId, CfgObject};
+
+// the `PUSHSTATE` / `POPSTATE` nesting depth along a procedure's sub-graph.
+// a procedure is balanced when each of its nodes is entered at the same depth along every path,
+// no `POPSTATE` is executed at depth `0` and each of its exits is at depth `0`
+// (i.e the procedure restores every state it saves, and only the states it saves).
+//
+// a `THROW` leaves the procedure midway, so the paths ending with one aren't checked.
+// unreachable nodes are ignored
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateBalance {
+    // the first node (in reverse post-order) at which the nesting goes wrong
+    unbalanced_node: Option<CfgNodeId>,
+}
+
+impl StateBalance {
+    pub fn new(graph: &CfgGraph, entry: CfgNodeId) -> Self {
+        // the depth at each node's entry (nodes reachable only past an exit are missing)
+        let mut depth_in: HashMap<CfgNodeId, usize> = HashMap::new();
+        depth_in.insert(entry, 0);
+
+        for node_id in graph.rpo_from(entry).iter() {
+            let mut depth = match depth_in.get(node_id) {
+                Some(depth) => *depth,
+                None => continue,
+            };
+
+            let mut exit = None;
+
+            for inst in graph.get_node(*node_id).insts.iter() {
+                match inst {
+                    CfgInstruction::Command(Command::PushState) => depth += 1,
+                    CfgInstruction::Command(Command::PopState) if depth == 0 => {
+                        return Self::unbalanced(*node_id)
+                    }
+                    CfgInstruction::Command(Command::PopState) => depth -= 1,
+                    CfgInstruction::Return | CfgInstruction::EOC => exit = Some(depth),
+                    CfgInstruction::Throw(_) => exit = Some(0),
+                    _ => continue,
+                };
+
+                if exit.is_some() {
+                    break;
+                }
+            }
+
+            let succs = graph.successors(*node_id);
+
+            if exit.is_none() && succs.is_empty() {
+                exit = Some(depth);
+            }
+
+            match exit {
+                Some(0) => continue,
+                Some(_) => return Self::unbalanced(*node_id),
+                None => {}
+            }
+
+            for succ_id in succs {
+                if *depth_in.entry(succ_id).or_insert(depth) != depth {
+                    return Self::unbalanced(succ_id);
+                }
+            }
+        }
+
+        Self {
+            unbalanced_node: None,
+        }
+    }
+
+    // the procedures of `cfg` (including `main`) which are unbalanced, ordered by their entry node
+    pub fn analyze_program(cfg: &CfgObject) -> Vec<SymbolId> {
+        cfg.jmp_table
+            .iter()
+            .filter(|(entry_id, _)| !Self::new(&cfg.graph, **entry_id).is_balanced())
+            .map(|(_, proc_id)| *proc_id)
+            .collect()
+    }
+
+    pub fn unbalanced_node(&self) -> Option<CfgNodeId> {
+        self.unbalanced_node
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        self.unbalanced_node.is_none()
+    }
+
+    fn unbalanced(node_id: CfgNodeId) -> Self {
+        Self {
+            unbalanced_node: Some(node_id),
+        }
+    }
+}
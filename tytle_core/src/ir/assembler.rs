@@ -0,0 +1,412 @@
+use crate::ast::semantic::{Environment, Primitive, Symbol, SymbolId};
+use crate::compat::collections::{BTreeMap, BTreeSet};
+use crate::compat::prelude::*;
+use crate::ir::{
+    CfgCond, CfgGraph, CfgInstruction, CfgJumpType, CfgNode, CfgNodeId, CfgObject, IrError,
+};
+
+// reads a listing in the format of `CfgObject::disassemble` back into a `CfgObject`,
+// so tests can be written directly against IR fixtures, e.g:
+//
+// proc __main__ (entry @1)
+//   @1:
+//        0  int 10
+//        1  store A
+//     -> @2 always
+//   @2:
+//        0  eoc
+//
+// * the operands are resolved against `env`. a name is looked up in the locals of the procedure
+//   being listed, then in the globals (and the procedures), while a symbol id (e.g `#4`)
+//   is taken as is
+// * the comments (following a `;`), the blank lines and the procedures locations are ignored,
+//   and so are the names following the `call` instructions
+// * the nodes reachable from no procedure entry are listed under `unreachable`
+struct Assembler<'a> {
+    env: &'a Environment,
+    graph: CfgGraph,
+    jmp_table: BTreeMap<CfgNodeId, SymbolId>,
+    edges: Vec<ListedEdge>,
+    listed: BTreeSet<CfgNodeId>,
+
+    // the procedure (and the node) whose lines are being read
+    proc_id: Option<SymbolId>,
+    node_id: Option<CfgNodeId>,
+
+    // the number of the line being read (starting from 1)
+    line: usize,
+}
+
+// an edge is added once all the nodes have been read
+struct ListedEdge {
+    src_id: CfgNodeId,
+    dst_id: CfgNodeId,
+    jmp_type: CfgJumpType,
+    cond: Option<String>,
+    line: usize,
+}
+
+impl CfgObject {
+    pub fn assemble(listing: &str, env: &Environment) -> Result<CfgObject, IrError> {
+        let mut assembler = Assembler {
+            env,
+            graph: CfgGraph::new(),
+            jmp_table: BTreeMap::new(),
+            edges: Vec::new(),
+            listed: BTreeSet::new(),
+            proc_id: None,
+            node_id: None,
+            line: 0,
+        };
+
+        for (i, line) in listing.lines().enumerate() {
+            assembler.line = i + 1;
+            assembler.read_line(line)?;
+        }
+
+        assembler.finish()
+    }
+}
+
+impl<'a> Assembler<'a> {
+    fn read_line(&mut self, line: &str) -> Result<(), IrError> {
+        let line = line.trim();
+
+        if line.is_empty() {
+            return Ok(());
+        }
+
+        if line == "unreachable" {
+            self.proc_id = None;
+            self.node_id = None;
+            return Ok(());
+        }
+
+        if let Some(header) = line.strip_prefix("proc ") {
+            return self.read_proc(header);
+        }
+
+        if let Some(edge) = line.strip_prefix("->") {
+            return self.read_edge(edge.trim());
+        }
+
+        if line.starts_with('@') && line.ends_with(':') {
+            let node_id = self.node_ref(&line[..line.len() - 1])?;
+
+            if !self.listed.insert(node_id) {
+                return Err(self.error(format!("node `@{}` is listed twice", node_id)));
+            }
+
+            self.graph.add_node(CfgNode::new(node_id));
+            self.node_id = Some(node_id);
+
+            return Ok(());
+        }
+
+        self.read_inst(strip_comment(line))
+    }
+
+    // e.g `SQUARE (entry @2, 1:1)`
+    fn read_proc(&mut self, header: &str) -> Result<(), IrError> {
+        let (name, entry) = match header.find(" (entry ") {
+            Some(index) => (&header[..index], &header[index + " (entry ".len()..]),
+            None => return Err(self.error("expected the procedure entry".to_string())),
+        };
+
+        let entry = entry.trim_end_matches(')');
+        let entry = entry.split(',').next().unwrap_or_default();
+
+        let proc_id = self.proc_symbol(name)?;
+        let entry = self.node_ref(entry.trim())?;
+
+        self.jmp_table.insert(entry, proc_id);
+        self.proc_id = Some(proc_id);
+        self.node_id = None;
+
+        Ok(())
+    }
+
+    // e.g `@3 when_true (A > 5)`
+    fn read_edge(&mut self, edge: &str) -> Result<(), IrError> {
+        let src_id = self.current_node()?;
+
+        let (edge, cond) = match edge.find(" (") {
+            Some(index) if edge.ends_with(')') => {
+                let cond = &edge[index + 2..edge.len() - 1];
+                (&edge[..index], Some(cond.to_string()))
+            }
+            _ => (edge, None),
+        };
+
+        let mut words = edge.split_whitespace();
+
+        let dst_id = self.node_ref(words.next().unwrap_or_default())?;
+
+        let jmp_type = match words.next() {
+            Some("always") => CfgJumpType::Always,
+            Some("when_true") => CfgJumpType::WhenTrue,
+            Some("fallback") => CfgJumpType::Fallback,
+            _ => return Err(self.error(format!("invalid edge `{}`", edge))),
+        };
+
+        self.edges.push(ListedEdge {
+            src_id,
+            dst_id,
+            jmp_type,
+            cond,
+            line: self.line,
+        });
+
+        Ok(())
+    }
+
+    // e.g `3  load A`
+    fn read_inst(&mut self, line: &str) -> Result<(), IrError> {
+        let node_id = self.current_node()?;
+
+        let (ip, rest) = split_word(line);
+        let (mnemonic, operands) = split_word(rest);
+
+        let insts_count = self.graph.get_node(node_id).insts.len();
+
+        if ip.parse::<usize>() != Ok(insts_count) {
+            return Err(self.error(format!("expected instruction {}", insts_count)));
+        }
+
+        let inst = self.inst(mnemonic, operands)?;
+
+        self.graph.get_node_mut(node_id).append_inst(inst);
+
+        Ok(())
+    }
+
+    fn inst(&self, mnemonic: &str, operands: &str) -> Result<CfgInstruction, IrError> {
+        let inst = match mnemonic {
+            "command" => CfgInstruction::Command(self.variant(operands)?),
+            "direction" => CfgInstruction::Direction(self.variant(operands)?),
+            "load" => CfgInstruction::Load(self.var_symbol(operands)?),
+            "store" => CfgInstruction::Store(self.var_symbol(operands)?),
+            "dup" => CfgInstruction::Dup,
+            "pop" => CfgInstruction::Pop,
+            "call" => CfgInstruction::Call(self.node_ref(split_word(operands).0)?),
+            "native_call" => CfgInstruction::NativeCall(self.proc_symbol(operands)?),
+            "primitive" => match Primitive::from_name(operands) {
+                Some(prim) => CfgInstruction::Primitive(prim),
+                None => return Err(self.error(format!("unknown primitive `{}`", operands))),
+            },
+            "bool" => CfgInstruction::Bool(self.parse_operand(operands)?),
+            "int" => CfgInstruction::Int(self.parse_operand(operands)?),
+            "str" => CfgInstruction::Str(self.quoted(operands)?.0),
+            "return" => CfgInstruction::Return,
+            "trap" => CfgInstruction::Trap,
+            "print" => CfgInstruction::Print,
+            "label" => CfgInstruction::Label,
+            "set_label_font" => CfgInstruction::SetLabelFont,
+            "set_label_size" => CfgInstruction::SetLabelSize,
+            "set_shape" => CfgInstruction::SetShape,
+            "set_pen_color" => CfgInstruction::SetPenColor,
+            "tone" => CfgInstruction::Tone,
+            "open_write" => CfgInstruction::OpenWrite,
+            "set_write" => CfgInstruction::SetWrite,
+            "close" => CfgInstruction::Close,
+            "eoc" => CfgInstruction::EOC,
+            "add" => CfgInstruction::Add,
+            "mul" => CfgInstruction::Mul,
+            "div" => CfgInstruction::Div,
+            "not" => CfgInstruction::Not,
+            "and" => CfgInstruction::And,
+            "or" => CfgInstruction::Or,
+            "greater_than" => CfgInstruction::GreaterThan,
+            "less_than" => CfgInstruction::LessThan,
+            "equal" => CfgInstruction::Equal,
+            "not_equal" => CfgInstruction::NotEqual,
+            "make_list" => CfgInstruction::MakeList(self.parse_operand(operands)?),
+            "list_len" => CfgInstruction::ListLen,
+            "list_item" => CfgInstruction::ListItem,
+            "list_push" => CfgInstruction::ListPush,
+            "proc_ref" => CfgInstruction::ProcRef(self.proc_symbol(operands)?),
+            "apply" => CfgInstruction::Apply(self.parse_operand(operands)?),
+            "block" => CfgInstruction::Block(self.proc_symbol(operands)?),
+            "run" => CfgInstruction::Run,
+            "catch" => {
+                let (tag, rest) = self.quoted(operands)?;
+                CfgInstruction::Catch(tag, self.node_ref(rest.trim())?)
+            }
+            "end_catch" => CfgInstruction::EndCatch,
+            "throw" => CfgInstruction::Throw(self.quoted(operands)?.0),
+            "trace" => CfgInstruction::Trace(self.proc_symbol(operands)?),
+            "untrace" => CfgInstruction::Untrace(self.proc_symbol(operands)?),
+            "assert" => {
+                let (message, rest) = self.quoted(operands)?;
+                let (line, column) = rest.trim().split_once(':').unwrap_or_default();
+
+                CfgInstruction::Assert {
+                    message,
+                    line: self.parse_operand(line)?,
+                    column: self.parse_operand(column)?,
+                }
+            }
+            _ => return Err(self.error(format!("unknown instruction `{}`", mnemonic))),
+        };
+
+        Ok(inst)
+    }
+
+    fn finish(mut self) -> Result<CfgObject, IrError> {
+        for edge in core::mem::take(&mut self.edges) {
+            if !self.graph.nodes.contains_key(&edge.dst_id) {
+                let msg = format!("missing node `@{}`", edge.dst_id);
+                return Err(IrError::InvalidListing {
+                    line: edge.line,
+                    msg,
+                });
+            }
+
+            self.graph.add_edge(edge.src_id, edge.dst_id, edge.jmp_type);
+
+            if let (CfgJumpType::WhenTrue, Some(cond)) = (edge.jmp_type, edge.cond) {
+                self.graph.get_node_mut(edge.src_id).cond = Some(CfgCond::of_text(&cond));
+            }
+        }
+
+        Ok(CfgObject::new(self.graph, self.jmp_table, self.env))
+    }
+
+    fn current_node(&self) -> Result<CfgNodeId, IrError> {
+        self.node_id
+            .ok_or_else(|| self.error("expected a node (e.g `@1:`)".to_string()))
+    }
+
+    // e.g `@3`
+    fn node_ref(&self, word: &str) -> Result<CfgNodeId, IrError> {
+        match word.strip_prefix('@').map(str::parse) {
+            Some(Ok(node_id)) => Ok(node_id),
+            _ => Err(self.error(format!("invalid node `{}`", word))),
+        }
+    }
+
+    fn symbol_id(&self, word: &str) -> Option<SymbolId> {
+        let id = word.strip_prefix('#')?.parse().ok()?;
+
+        Some(SymbolId(id))
+    }
+
+    fn var_symbol(&self, word: &str) -> Result<SymbolId, IrError> {
+        if let Some(var_id) = self.symbol_id(word) {
+            return Ok(var_id);
+        }
+
+        let env = self.env;
+        let symbol_table = &env.symbol_table;
+
+        let locals = self
+            .proc_id
+            .and_then(|proc_id| env.locals_symbols.get(&proc_id))
+            .into_iter()
+            .flatten();
+
+        locals
+            .chain(env.globals_symbols.values())
+            .find(|var_id| symbol_table.get_var_by_id(**var_id).name == word)
+            .copied()
+            .ok_or_else(|| self.error(format!("unknown variable `{}`", word)))
+    }
+
+    fn proc_symbol(&self, word: &str) -> Result<SymbolId, IrError> {
+        if let Some(proc_id) = self.symbol_id(word) {
+            return Ok(proc_id);
+        }
+
+        let proc = self.env.symbol_table.symbols().find_map(|symbol| match symbol {
+            Symbol::Proc(proc) if proc.name == word => Some(proc.id),
+            _ => None,
+        });
+
+        proc.ok_or_else(|| self.error(format!("unknown procedure `{}`", word)))
+    }
+
+    fn parse_operand<T: core::str::FromStr>(&self, word: &str) -> Result<T, IrError> {
+        word.trim()
+            .parse()
+            .map_err(|_| self.error(format!("invalid operand `{}`", word)))
+    }
+
+    // a `Command` (or a `Direction`) variant by its name, e.g `PenUp`
+    fn variant<T: serde::de::DeserializeOwned>(&self, word: &str) -> Result<T, IrError> {
+        let value = serde_json::Value::String(word.to_string());
+
+        serde_json::from_value(value).map_err(|_| self.error(format!("invalid operand `{}`", word)))
+    }
+
+    // a string as escaped by the disassembler, returning the rest of `operands`
+    fn quoted<'o>(&self, operands: &'o str) -> Result<(String, &'o str), IrError> {
+        let error = || self.error(format!("invalid string `{}`", operands));
+
+        let mut chars = operands.strip_prefix('"').ok_or_else(error)?.char_indices();
+        let mut s = String::new();
+
+        while let Some((i, c)) = chars.next() {
+            let c = match c {
+                '"' => return Ok((s, &operands[i + 2..])),
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('0') => '\0',
+                    Some(c @ '\\') | Some(c @ '"') | Some(c @ '\'') => c,
+                    Some('u') => {
+                        let code = chars
+                            .by_ref()
+                            .map(|(_, c)| c)
+                            .skip_while(|c| *c == '{')
+                            .take_while(|c| *c != '}')
+                            .collect::<String>();
+
+                        u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(core::char::from_u32)
+                            .ok_or_else(error)?
+                    }
+                    _ => return Err(error()),
+                },
+                c => c,
+            };
+
+            s.push(c);
+        }
+
+        Err(error())
+    }
+
+    fn error(&self, msg: String) -> IrError {
+        IrError::InvalidListing {
+            line: self.line,
+            msg,
+        }
+    }
+}
+
+fn split_word(s: &str) -> (&str, &str) {
+    match s.find(char::is_whitespace) {
+        Some(index) => (&s[..index], s[index..].trim_start()),
+        None => (s, ""),
+    }
+}
+
+// drops the comment (if any) of an instruction line, skipping the `;` within its strings
+fn strip_comment(line: &str) -> &str {
+    let mut in_str = false;
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_str => escaped = true,
+            '"' => in_str = !in_str,
+            ';' if !in_str => return line[..i].trim_end(),
+            _ => {}
+        }
+    }
+
+    line
+}
@@ -1,12 +1,24 @@
 pub use crate::ast::{expression::*, semantic::*, statement::*, Ast};
+pub use crate::compat::collections::{BTreeMap, HashMap};
+use crate::compat::prelude::*;
 pub use crate::ir::*;
-pub use std::collections::HashMap;
+use crate::lexer::Location;
 
 pub struct CfgBuilder<'env> {
     cfg_graph: CfgGraph,
     env: &'env mut Environment,
     current_proc_id: SymbolId,
-    proc_jmp_table: HashMap<SymbolId, CfgProc>,
+    proc_jmp_table: HashMap<SymbolId, CfgProcEntry>,
+    opt_level: OptLevel,
+
+    // mapped procedure => its generated `MAP` procedure (see `build_map_proc`)
+    map_procs: HashMap<SymbolId, SymbolId>,
+
+    // the location of the statement being built (see `build_located_stmt`),
+    // and the locations of the instructions runs and of the procedures (see `DebugInfo`)
+    location: Option<Location>,
+    lines: BTreeMap<CfgNodeId, BTreeMap<usize, Location>>,
+    procs_locations: BTreeMap<SymbolId, Location>,
 }
 
 impl<'env> CfgBuilder<'env> {
@@ -20,22 +32,46 @@ impl<'env> CfgBuilder<'env> {
             cfg_graph,
             env,
             proc_jmp_table: HashMap::new(),
+            opt_level: OptLevel::None,
+            map_procs: HashMap::new(),
+            location: None,
+            lines: BTreeMap::new(),
+            procs_locations: BTreeMap::new(),
         }
     }
 
-    pub fn build(mut self, ast: &Ast) -> CfgObject {
+    // the optimizations to run over the built `CfgObject` (none by default)
+    pub fn opt_level(mut self, opt_level: OptLevel) -> Self {
+        self.opt_level = opt_level;
+        self
+    }
+
+    pub fn build(self, ast: &Ast) -> CfgObject {
+        self.build_with_debug_info(ast).0
+    }
+
+    // builds the `CfgObject` along with its `DebugInfo`
+    // (the statements locations are taken from `ast`, so it should be a parsed one)
+    pub fn build_with_debug_info(mut self, ast: &Ast) -> (CfgObject, DebugInfo) {
         let entry_id = self.cfg_graph.get_entry_node_id();
 
-        let mut node_id = entry_id;
+        // the statements instructions (the optimizations are traced by `optimize`)
+        {
+            enter_span!("codegen", stmts = ast.statements.len());
 
-        for stmt in &ast.statements {
-            node_id = self.build_stmt(node_id, stmt);
-        }
+            let mut node_id = entry_id;
+
+            for (i, stmt) in ast.statements.iter().enumerate() {
+                node_id = self.build_located_stmt(node_id, stmt, ast.stmt_location(i));
+            }
 
-        // appending `EOC` to the end of `main`
-        self.append_eoc(node_id);
+            // appending `EOC` to the end of `main`
+            self.append_eoc(node_id);
 
-        let mut jmp_table: HashMap<CfgNodeId, SymbolId> = self
+            trace_event!(nodes = self.cfg_graph.nodes.len(), procs = self.proc_jmp_table.len());
+        }
+
+        let mut jmp_table: BTreeMap<CfgNodeId, SymbolId> = self
             .proc_jmp_table
             .iter()
             .map(|(proc_id, cfg_proc)| (cfg_proc.node_id, *proc_id))
@@ -45,9 +81,44 @@ impl<'env> CfgBuilder<'env> {
         let main_proc = self.env.symbol_table.get_proc_by_name("__main__");
         jmp_table.insert(entry_id, main_proc.id);
 
-        CfgObject {
-            graph: self.cfg_graph,
-            jmp_table,
+        let mut cfg = CfgObject::new(self.cfg_graph, jmp_table, self.env);
+
+        crate::ir::opt::optimize(&mut cfg, self.env, self.opt_level);
+
+        let debug_info = DebugInfo::new(&cfg, self.env, self.lines, &self.procs_locations);
+
+        (cfg, debug_info)
+    }
+
+    fn build_located_stmt(
+        &mut self,
+        node_id: CfgNodeId,
+        stmt: &Statement,
+        location: Option<Location>,
+    ) -> CfgNodeId {
+        let parent_location = self.location;
+
+        if location.is_some() {
+            self.location = location;
+        }
+
+        self.mark_location(node_id);
+
+        let node_id = self.build_stmt(node_id, stmt);
+
+        // the instructions following the statement belong to the enclosing one (if any)
+        self.location = parent_location;
+        self.mark_location(node_id);
+
+        node_id
+    }
+
+    // starts a run of instructions generated for the current statement
+    fn mark_location(&mut self, node_id: CfgNodeId) {
+        if let Some(location) = self.location {
+            let ip = self.cfg_graph.get_node(node_id).insts.len();
+
+            self.lines.entry(node_id).or_default().insert(ip, location);
         }
     }
 
@@ -56,18 +127,63 @@ impl<'env> CfgBuilder<'env> {
             Statement::NOP | Statement::EOF => node_id,
             Statement::Command(cmd) => self.build_cmd(node_id, cmd),
             Statement::Direction(direct_stmt) => self.build_direct(node_id, direct_stmt),
+            Statement::Label(label_stmt) => self.build_label(node_id, label_stmt),
+            Statement::Stream(stream_stmt) => self.build_stream(node_id, stream_stmt),
+            Statement::SetShape(expr) => {
+                let node_id = self.build_expr(node_id, expr);
+                self.append_inst(node_id, CfgInstruction::SetShape);
+                node_id
+            }
+            Statement::SetPenColor(expr) => {
+                let node_id = self.build_expr(node_id, expr);
+                self.append_inst(node_id, CfgInstruction::SetPenColor);
+                node_id
+            }
+            Statement::Tone(tone_stmt) => {
+                let node_id = self.build_expr(node_id, &tone_stmt.freq_expr);
+                let node_id = self.build_expr(node_id, &tone_stmt.duration_expr);
+                self.append_inst(node_id, CfgInstruction::Tone);
+                node_id
+            }
             Statement::Expression(expr) => self.build_expr(node_id, expr),
             Statement::Make(make_stmt) => self.build_make(node_id, make_stmt),
             Statement::If(if_stmt) => self.build_if(node_id, if_stmt),
             Statement::Repeat(repeat_stmt) => self.build_repeat(node_id, repeat_stmt),
+            Statement::Foreach(foreach_stmt) => self.build_foreach(node_id, foreach_stmt),
             Statement::Procedure(proc_stmt) => self.build_proc(node_id, proc_stmt),
+            Statement::OnEvent(on_event_stmt) => {
+                self.build_proc(node_id, &on_event_stmt.proc_stmt)
+            }
+            Statement::Catch(catch_stmt) => self.build_catch(node_id, catch_stmt),
+            Statement::Throw(tag) => {
+                self.append_inst(node_id, CfgInstruction::Throw(tag.clone()));
+                node_id
+            }
+            Statement::Assert(assert_stmt) => self.build_assert(node_id, assert_stmt),
+            Statement::Trace(trace_stmt) => {
+                let proc_id = trace_stmt.proc_id.unwrap();
+
+                let inst = match trace_stmt.enabled {
+                    true => CfgInstruction::Trace(proc_id),
+                    false => CfgInstruction::Untrace(proc_id),
+                };
+
+                self.append_inst(node_id, inst);
+                node_id
+            }
             Statement::Return(return_stmt) => self.build_return(node_id, return_stmt),
             Statement::Print(expr) => self.build_print(node_id, expr),
+            Statement::Run(expr) => {
+                let node_id = self.build_expr(node_id, expr);
+                self.append_inst(node_id, CfgInstruction::Run);
+                node_id
+            }
+            Statement::Load(_) => unreachable!("`LOAD` statements are resolved before building the CFG"),
         }
     }
 
     fn build_print(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
-        self.build_expr(node_id, expr);
+        let node_id = self.build_expr(node_id, expr);
 
         let node = self.cfg_graph.get_node_mut(node_id);
         node.append_inst(CfgInstruction::Print);
@@ -75,11 +191,31 @@ impl<'env> CfgBuilder<'env> {
         node_id
     }
 
+    fn build_assert(&mut self, node_id: CfgNodeId, assert_stmt: &AssertStmt) -> CfgNodeId {
+        let node_id = self.build_expr(node_id, &assert_stmt.cond_expr);
+
+        // an `ASSERT` with no message reports its condition
+        let message = match assert_stmt.message {
+            Some(ref message) => message.clone(),
+            None => PrettyPrintAst::pprint_expr(&assert_stmt.cond_expr),
+        };
+
+        let inst = CfgInstruction::Assert {
+            message,
+            line: assert_stmt.location.line(),
+            column: assert_stmt.location.column(),
+        };
+
+        self.append_inst(node_id, inst);
+
+        node_id
+    }
+
     fn build_return(&mut self, node_id: CfgNodeId, return_stmt: &ReturnStmt) -> CfgNodeId {
-        if return_stmt.expr.is_some() {
-            let expr: &Expression = return_stmt.expr.as_ref().unwrap();
-            self.build_expr(node_id, expr);
-        }
+        let node_id = match return_stmt.expr {
+            Some(ref expr) => self.build_expr(node_id, expr),
+            None => node_id,
+        };
 
         let node = self.cfg_graph.get_node_mut(node_id);
         node.append_inst(CfgInstruction::Return);
@@ -116,7 +252,7 @@ impl<'env> CfgBuilder<'env> {
             // we explicitly save immediately the CFG proc in order
             // to support recursive procedures
 
-            let cfg_proc = CfgProc {
+            let cfg_proc = CfgProcEntry {
                 node_id: proc_node_id,
                 proc_id,
                 built: false,
@@ -124,10 +260,16 @@ impl<'env> CfgBuilder<'env> {
             self.proc_jmp_table.insert(proc_id, cfg_proc);
         }
 
+        if let Some(location) = self.location {
+            self.procs_locations.insert(proc_id, location);
+        }
+
+        enter_span!("proc", name = proc_stmt.name.as_str());
+
         let last_block_node_id = self.build_block(proc_node_id, &proc_stmt.block);
 
         // marking the CFG proc as built
-        let cfg_proc = CfgProc {
+        let cfg_proc = CfgProcEntry {
             node_id: proc_node_id,
             proc_id,
             built: true,
@@ -157,7 +299,7 @@ impl<'env> CfgBuilder<'env> {
     }
 
     fn build_direct(&mut self, node_id: CfgNodeId, direct_stmt: &DirectionStmt) -> CfgNodeId {
-        self.build_expr(node_id, &direct_stmt.expr);
+        let node_id = self.build_expr(node_id, &direct_stmt.expr);
 
         let direct = direct_stmt.direction.clone();
         let inst = CfgInstruction::Direction(direct);
@@ -167,6 +309,34 @@ impl<'env> CfgBuilder<'env> {
         node_id
     }
 
+    fn build_label(&mut self, node_id: CfgNodeId, label_stmt: &LabelStmt) -> CfgNodeId {
+        let node_id = self.build_expr(node_id, &label_stmt.expr);
+
+        let inst = match label_stmt.kind {
+            LabelKind::Text => CfgInstruction::Label,
+            LabelKind::Font => CfgInstruction::SetLabelFont,
+            LabelKind::Size => CfgInstruction::SetLabelSize,
+        };
+
+        self.append_inst(node_id, inst);
+
+        node_id
+    }
+
+    fn build_stream(&mut self, node_id: CfgNodeId, stream_stmt: &StreamStmt) -> CfgNodeId {
+        let node_id = self.build_expr(node_id, &stream_stmt.expr);
+
+        let inst = match stream_stmt.kind {
+            StreamKind::Open => CfgInstruction::OpenWrite,
+            StreamKind::Set => CfgInstruction::SetWrite,
+            StreamKind::Close => CfgInstruction::Close,
+        };
+
+        self.append_inst(node_id, inst);
+
+        node_id
+    }
+
     fn build_make(&mut self, node_id: CfgNodeId, make_stmt: &MakeStmt) -> CfgNodeId {
         let expr = &make_stmt.expr;
         let var_id = make_stmt.var_id.unwrap();
@@ -180,7 +350,7 @@ impl<'env> CfgBuilder<'env> {
         var_id: SymbolId,
         expr: &Expression,
     ) -> CfgNodeId {
-        self.build_expr(node_id, expr);
+        let node_id = self.build_expr(node_id, expr);
 
         let inst = CfgInstruction::Store(var_id);
 
@@ -189,6 +359,8 @@ impl<'env> CfgBuilder<'env> {
         node_id
     }
 
+    // returns the CFG node following the expression instructions
+    // (which differs from `node_id` when the expression branches, see `build_if_else_expr`)
     fn build_expr(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
         match expr.expr_ast {
             ExpressionAst::Literal(_) => self.build_lit_expr(node_id, expr),
@@ -196,25 +368,75 @@ impl<'env> CfgBuilder<'env> {
             ExpressionAst::Binary(..) => self.build_bin_expr(node_id, expr),
             ExpressionAst::Parentheses(_) => self.build_parentheses_expr(node_id, expr),
             ExpressionAst::ProcCall(..) => self.build_proc_call_expr(node_id, expr),
+            ExpressionAst::TypeQuery(..) => self.build_type_query_expr(node_id, expr),
+            ExpressionAst::List(_) => self.build_list_expr(node_id, expr),
+            ExpressionAst::Map(..) => self.build_map_expr(node_id, expr),
+            ExpressionAst::ProcRef(..) => self.build_proc_ref_expr(node_id, expr),
+            ExpressionAst::Apply(..) => self.build_apply_expr(node_id, expr),
+            ExpressionAst::NamedArg(..) => panic!("named args are ordered by their procedure call"),
+            ExpressionAst::Block(ref block_stmt) => {
+                let block_proc_id = self.build_block_proc(block_stmt);
+                self.append_inst(node_id, CfgInstruction::Block(block_proc_id));
+
+                node_id
+            }
+            ExpressionAst::IfElse(..) => self.build_if_else_expr(node_id, expr),
         }
+    }
+
+    fn build_proc_call_expr(&mut self, mut node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
+        let (_proc_name, proc_args_exprs) = expr.as_proc_call_expr();
+        let proc_id = self.env.expr_symbol(expr);
+
+        // the args are pushed in their params order (they've been checked by the type checker)
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+        let proc_args_exprs = proc.order_args(proc_args_exprs).unwrap();
+
+        for proc_arg_expr in proc_args_exprs.iter() {
+            node_id = self.build_expr(node_id, proc_arg_expr);
+        }
+
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+
+        // the args past the other params are collected into the rest param list
+        if proc.rest {
+            let rest_args = proc_args_exprs.len() + 1 - proc.params_types.len();
+            self.append_inst(node_id, CfgInstruction::MakeList(rest_args));
+        }
+
+        let inst = self.proc_call_inst(proc_id);
+
+        self.append_inst(node_id, inst);
 
         node_id
     }
 
-    fn build_proc_call_expr(&mut self, node_id: CfgNodeId, expr: &Expression) {
-        let (_proc_name, proc_args_exprs, proc_id) = expr.as_proc_call_expr();
+    // the instruction calling procedure `proc_id` (its args are expected to be on the stack)
+    fn proc_call_inst(&mut self, proc_id: SymbolId) -> CfgInstruction {
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
 
-        for proc_arg_expr in proc_args_exprs {
-            self.build_expr(node_id, proc_arg_expr);
+        if let Some(prim) = proc.primitive {
+            return CfgInstruction::Primitive(prim);
         }
 
-        let proc_id = *proc_id.unwrap();
+        // native procedures have no CFG node of their own, the interpreter dispatches them directly
+        if proc.native {
+            return CfgInstruction::NativeCall(proc_id);
+        }
+
+        let jmp_node_id = self.proc_entry(proc_id);
+
+        CfgInstruction::Call(jmp_node_id)
+    }
+
+    // the entry node of user procedure `proc_id` (allocated if the procedure hasn't been reached yet)
+    fn proc_entry(&mut self, proc_id: SymbolId) -> CfgNodeId {
         let cfg_proc = self.proc_jmp_table.get(&proc_id);
 
-        let jmp_node_id = if cfg_proc.is_none() {
+        if cfg_proc.is_none() {
             let proc_node_id = self.cfg_graph.new_node();
 
-            let cfg_proc = CfgProc {
+            let cfg_proc = CfgProcEntry {
                 node_id: proc_node_id,
                 proc_id,
                 built: false,
@@ -224,52 +446,327 @@ impl<'env> CfgBuilder<'env> {
             proc_node_id
         } else {
             cfg_proc.unwrap().node_id
+        }
+    }
+
+    fn build_proc_ref_expr(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
+        let proc_id = self.env.expr_symbol(expr);
+
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+
+        // the referenced procedure may be applied, so a user procedure must have a CFG of its own
+        if proc.primitive.is_none() && !proc.native {
+            self.proc_entry(proc_id);
+        }
+
+        self.append_inst(node_id, CfgInstruction::ProcRef(proc_id));
+
+        node_id
+    }
+
+    fn build_apply_expr(&mut self, mut node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
+        let (proc_expr, args_exprs) = expr.as_apply_expr();
+
+        for arg_expr in args_exprs {
+            node_id = self.build_expr(node_id, arg_expr);
+        }
+
+        let node_id = self.build_expr(node_id, proc_expr);
+
+        self.append_inst(node_id, CfgInstruction::Apply(args_exprs.len()));
+
+        node_id
+    }
+
+    fn build_list_expr(&mut self, mut node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
+        let items = expr.as_list_expr();
+
+        for item in items {
+            node_id = self.build_expr(node_id, item);
+        }
+
+        self.append_inst(node_id, CfgInstruction::MakeList(items.len()));
+
+        node_id
+    }
+
+    fn build_map_expr(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
+        let (_proc_name, list_expr) = expr.as_map_expr();
+        let proc_id = self.env.expr_symbol(expr);
+
+        let node_id = self.build_expr(node_id, list_expr);
+
+        let map_proc_id = match self.map_procs.get(&proc_id) {
+            Some(map_proc_id) => *map_proc_id,
+            None => self.build_map_proc(proc_id),
+        };
+
+        let inst = self.proc_call_inst(map_proc_id);
+        self.append_inst(node_id, inst);
+
+        node_id
+    }
+
+    // `MAP` expressions are compiled into calls to a generated procedure (one per mapped procedure):
+    //
+    // TO $MAP.PROC($LIST: [T]): [R]
+    //     $RESULT = (an empty list)
+    //     for each element `E` of `$LIST`: append `PROC(E)` to `$RESULT`
+    //     RETURN $RESULT
+    // END
+    fn build_map_proc(&mut self, proc_id: SymbolId) -> SymbolId {
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+
+        let map_proc_name = format!("$MAP.{}", proc.name);
+        let list_type = ExpressionType::list_of(proc.params_types[0].clone());
+        let result_type = ExpressionType::list_of(proc.return_type.clone());
+
+        let map_proc_id = self
+            .env
+            .create_proc(&map_proc_name, vec![list_type.clone()], result_type.clone());
+
+        // generated procedures aren't a part of the user code (e.g they aren't reported as unused)
+        self.env.symbol_table.get_proc_by_id_mut(map_proc_id).builtin = true;
+
+        let list_var_id = self.env.id_generator.get_next_id();
+        let list_var_name = format!("$LIST{}", list_var_id.0);
+
+        self.env
+            .create_local_var(map_proc_id, list_var_id, &list_var_name, Some(list_type), true);
+
+        let map_node_id = self.cfg_graph.new_node();
+
+        let cfg_proc = CfgProcEntry {
+            node_id: map_node_id,
+            proc_id: map_proc_id,
+            built: true,
+        };
+        self.proc_jmp_table.insert(map_proc_id, cfg_proc);
+        self.map_procs.insert(proc_id, map_proc_id);
+
+        let parent_proc_id = self.current_proc_id;
+        self.current_proc_id = map_proc_id;
+
+        let (result_var_id, _) = self.env.create_tmp_var(map_proc_id, result_type);
+
+        self.append_inst(map_node_id, CfgInstruction::MakeList(0));
+        self.append_inst(map_node_id, CfgInstruction::Store(result_var_id));
+
+        let call_inst = self.proc_call_inst(proc_id);
+
+        let cond = CfgCond::of_text(&map_proc_name);
+
+        let after_node_id =
+            self.build_list_loop(map_node_id, list_var_id, cond, &mut |builder, node_id| {
+                // the current element is at the top of the stack
+                builder.append_inst(node_id, call_inst.clone());
+                builder.append_inst(node_id, CfgInstruction::Load(result_var_id));
+                builder.append_inst(node_id, CfgInstruction::ListPush);
+                builder.append_inst(node_id, CfgInstruction::Store(result_var_id));
+
+                node_id
+            });
+
+        self.append_inst(after_node_id, CfgInstruction::Load(result_var_id));
+        self.append_inst(after_node_id, CfgInstruction::Return);
+
+        self.current_proc_id = parent_proc_id;
+
+        map_proc_id
+    }
+
+    // block expressions are compiled into a generated procedure (one per block):
+    //
+    // TO $BLOCK.ID
+    //     (the block statements)
+    // END
+    //
+    // the temporary variables of the block statements are allocated under the generated procedure,
+    // while their other variables are those of the frame the block is created within
+    fn build_block_proc(&mut self, block_stmt: &BlockStatement) -> SymbolId {
+        let block_id = self.env.id_generator.get_next_id();
+        let block_proc_name = format!("$BLOCK.{}", block_id.0);
+
+        let block_proc_id = self
+            .env
+            .create_proc(&block_proc_name, Vec::new(), ExpressionType::Unit);
+
+        self.env.symbol_table.get_proc_by_id_mut(block_proc_id).builtin = true;
+        self.env.blocks.insert(block_proc_id, block_stmt.clone());
+
+        let block_node_id = self.cfg_graph.new_node();
+
+        let cfg_proc = CfgProcEntry {
+            node_id: block_node_id,
+            proc_id: block_proc_id,
+            built: true,
         };
+        self.proc_jmp_table.insert(block_proc_id, cfg_proc);
+
+        let parent_proc_id = self.current_proc_id;
+        self.current_proc_id = block_proc_id;
+
+        let last_block_node_id = self.build_block(block_node_id, block_stmt);
+        self.append_ret(last_block_node_id);
+
+        self.current_proc_id = parent_proc_id;
 
-        self.append_inst(node_id, CfgInstruction::Call(jmp_node_id));
+        block_proc_id
     }
 
-    fn build_parentheses_expr(&mut self, node_id: CfgNodeId, expr: &Expression) {
+    fn build_parentheses_expr(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
         let expr = expr.as_parentheses_expr();
-        self.build_expr(node_id, expr);
+        self.build_expr(node_id, expr)
     }
 
-    fn build_bin_expr(&mut self, node_id: CfgNodeId, expr: &Expression) {
+    fn build_bin_expr(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
         let (bin_op, lexpr, rexpr) = expr.as_binary_expr();
 
-        self.build_expr(node_id, lexpr);
-        self.build_expr(node_id, rexpr);
-
         let inst = match bin_op {
             BinaryOp::Add => CfgInstruction::Add,
             BinaryOp::Mul => CfgInstruction::Mul,
             BinaryOp::Div => CfgInstruction::Div,
-            BinaryOp::And => CfgInstruction::And,
-            BinaryOp::Or => CfgInstruction::Or,
+            BinaryOp::And => return self.build_and_expr(node_id, lexpr, rexpr),
+            BinaryOp::Or => return self.build_or_expr(node_id, lexpr, rexpr),
             BinaryOp::LessThan => CfgInstruction::LessThan,
             BinaryOp::GreaterThan => CfgInstruction::GreaterThan,
+            BinaryOp::Equal => CfgInstruction::Equal,
+            BinaryOp::NotEqual => CfgInstruction::NotEqual,
         };
 
+        let node_id = self.build_expr(node_id, lexpr);
+        let node_id = self.build_expr(node_id, rexpr);
+
         self.append_inst(node_id, inst);
+
+        node_id
+    }
+
+    // `A AND B` short-circuits, `B` is evaluated only when `A` is true:
+    //
+    //      A
+    //      | \
+    //      |  B
+    //      | /
+    //    AFTER
+    //
+    // a fallback jump leaves the (false) condition on the stack, which is the result
+    fn build_and_expr(
+        &mut self,
+        node_id: CfgNodeId,
+        lexpr: &Expression,
+        rexpr: &Expression,
+    ) -> CfgNodeId {
+        let node_id = self.build_expr(node_id, lexpr);
+        self.set_cond(node_id, CfgCond::of_expr(lexpr));
+
+        let rexpr_node_id = self.cfg_graph.new_node();
+        self.add_edge(node_id, rexpr_node_id, CfgJumpType::WhenTrue);
+        let last_rexpr_node_id = self.build_expr(rexpr_node_id, rexpr);
+
+        let after_node_id = self.cfg_graph.new_node();
+        self.add_edge(last_rexpr_node_id, after_node_id, CfgJumpType::Always);
+        self.add_edge(node_id, after_node_id, CfgJumpType::Fallback);
+
+        after_node_id
+    }
+
+    // `A OR B` short-circuits, `B` is evaluated only when `A` is false
+    // (built like `IFELSE A [TRUE] [B]`, see `build_if_else_expr`)
+    fn build_or_expr(
+        &mut self,
+        node_id: CfgNodeId,
+        lexpr: &Expression,
+        rexpr: &Expression,
+    ) -> CfgNodeId {
+        let node_id = self.build_expr(node_id, lexpr);
+        self.set_cond(node_id, CfgCond::of_expr(lexpr));
+
+        let true_node_id = self.cfg_graph.new_node();
+        self.add_edge(node_id, true_node_id, CfgJumpType::WhenTrue);
+        self.append_bool_lit(true_node_id, true);
+
+        let rexpr_node_id = self.cfg_graph.new_node();
+        self.add_edge(node_id, rexpr_node_id, CfgJumpType::Fallback);
+        self.append_inst(rexpr_node_id, CfgInstruction::Pop);
+        let last_rexpr_node_id = self.build_expr(rexpr_node_id, rexpr);
+
+        let after_node_id = self.cfg_graph.new_node();
+        self.add_edge(true_node_id, after_node_id, CfgJumpType::Always);
+        self.add_edge(last_rexpr_node_id, after_node_id, CfgJumpType::Always);
+
+        after_node_id
     }
 
-    fn build_not_expr(&mut self, node_id: CfgNodeId, expr: &Expression) {
+    fn build_not_expr(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
         let expr = expr.as_not_expr();
 
-        self.build_expr(node_id, expr);
+        let node_id = self.build_expr(node_id, expr);
         self.append_inst(node_id, CfgInstruction::Not);
+
+        node_id
     }
 
-    fn build_lit_expr(&mut self, node_id: CfgNodeId, expr: &Expression) {
-        let expr = expr.as_lit_expr();
+    // `IFELSE COND [EXPR1] [EXPR2]` is built as a diamond, each branch pushing its value:
+    //
+    //        COND
+    //       /    \
+    //    EXPR1   POP, EXPR2
+    //       \    /
+    //       AFTER
+    //
+    // (a fallback jump leaves the condition on the stack, so the false branch pops it first).
+    // returns the `AFTER` node, where the chosen value is at the top of the stack
+    fn build_if_else_expr(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
+        let (cond_expr, true_expr, false_expr) = expr.as_if_else_expr();
+
+        let node_id = self.build_expr(node_id, cond_expr);
+        self.set_cond(node_id, CfgCond::of_expr(cond_expr));
+
+        let true_node_id = self.cfg_graph.new_node();
+        self.add_edge(node_id, true_node_id, CfgJumpType::WhenTrue);
+        let last_true_node_id = self.build_expr(true_node_id, true_expr);
+
+        let false_node_id = self.cfg_graph.new_node();
+        self.add_edge(node_id, false_node_id, CfgJumpType::Fallback);
+        self.append_inst(false_node_id, CfgInstruction::Pop);
+        let last_false_node_id = self.build_expr(false_node_id, false_expr);
+
+        let after_node_id = self.cfg_graph.new_node();
+        self.add_edge(last_true_node_id, after_node_id, CfgJumpType::Always);
+        self.add_edge(last_false_node_id, after_node_id, CfgJumpType::Always);
+
+        after_node_id
+    }
 
-        match expr {
+    fn build_lit_expr(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
+        match expr.as_lit_expr() {
+            LiteralExpr::Var(_) => {
+                let var_id = self.env.expr_symbol(expr);
+                self.append_var_lit(node_id, &var_id)
+            }
+            lit => self.append_const_lit(node_id, lit),
+        }
+
+        node_id
+    }
+
+    // the query has been answered by the type checker (see `Environment::folded_exprs`)
+    fn build_type_query_expr(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
+        let lit = self.env.folded_exprs.get(expr.node_id).cloned();
+        let lit = lit.expect("type queries are folded by the type checker");
+
+        self.append_const_lit(node_id, &lit);
+
+        node_id
+    }
+
+    fn append_const_lit(&mut self, node_id: CfgNodeId, lit: &LiteralExpr) {
+        match lit {
             LiteralExpr::Bool(v) => self.append_bool_lit(node_id, *v),
             LiteralExpr::Int(v) => self.append_int_lit(node_id, *v),
             LiteralExpr::Str(v) => self.append_str_lit(node_id, v),
-            LiteralExpr::Var(_, ref var_id) => {
-                self.append_var_lit(node_id, var_id.as_ref().unwrap())
-            }
+            LiteralExpr::Var(_) => unreachable!(),
         }
     }
 
@@ -286,11 +783,35 @@ impl<'env> CfgBuilder<'env> {
     }
 
     fn append_var_lit(&mut self, node_id: CfgNodeId, var_id: &SymbolId) {
-        let inst = CfgInstruction::Load(var_id.clone());
+        let var = self.env.symbol_table.get_var_by_id(*var_id);
+
+        // constants are inlined (their `CONST` statement still stores them, for the debugger's sake)
+        let inst = match var.const_value {
+            Some(LiteralExpr::Bool(v)) => CfgInstruction::Bool(v),
+            Some(LiteralExpr::Int(v)) => CfgInstruction::Int(v as isize),
+            Some(LiteralExpr::Str(ref v)) => CfgInstruction::Str(v.to_string()),
+            _ => CfgInstruction::Load(var_id.clone()),
+        };
 
         self.append_inst(node_id, inst);
     }
 
+    // an expression synthesized by the builder, annotated as if it was type-checked
+    fn typed_expr(&mut self, expr_ast: ExpressionAst, expr_type: ExpressionType) -> Expression {
+        let expr = Expression::new(expr_ast);
+        self.env.expr_types.insert(expr.node_id, expr_type);
+
+        expr
+    }
+
+    fn tmp_var_expr(&mut self, var_name: &str, var_id: SymbolId) -> Expression {
+        let var_lit = ExpressionAst::Literal(LiteralExpr::Var(var_name.to_string()));
+        let expr = self.typed_expr(var_lit, ExpressionType::Int);
+        self.env.expr_symbols.insert(expr.node_id, var_id);
+
+        expr
+    }
+
     fn build_repeat(&mut self, node_id: CfgNodeId, repeat_stmt: &RepeatStmt) -> CfgNodeId {
         // 1)  allocate a new local variable of type `INT`, let's call it `TMPVAR_A`
         // 2)  allocate a new local variable of type `INT`, let's call it `TMPVAR_B`
@@ -318,65 +839,47 @@ impl<'env> CfgBuilder<'env> {
             .create_tmp_var(self.current_proc_id, ExpressionType::Int);
 
         // MAKE TMPVAR_A = 0
-        let zero_lit = LiteralExpr::Int(0);
-        let zero_expr = Expression {
-            expr_type: Some(ExpressionType::Int),
-            expr_ast: ExpressionAst::Literal(zero_lit),
-        };
+        let zero_lit = ExpressionAst::Literal(LiteralExpr::Int(0));
+        let zero_expr = self.typed_expr(zero_lit, ExpressionType::Int);
         self.build_assign(node_id, var_id_a, &zero_expr);
 
         // MAKE TMPVAR_B = `cond_expr`
-        self.build_assign(node_id, var_id_b, &repeat_stmt.count_expr);
+        let node_id = self.build_assign(node_id, var_id_b, &repeat_stmt.count_expr);
 
         // TMPVAR_A < TMPVAR_B
-        let var_lit_a = LiteralExpr::Var(var_name_a, Some(var_id_a));
-        let var_lit_b = LiteralExpr::Var(var_name_b, Some(var_id_b));
-        let var_lit_a_clone = var_lit_a.clone();
+        let var_expr_a = self.tmp_var_expr(&var_name_a, var_id_a);
+        let var_expr_b = self.tmp_var_expr(&var_name_b, var_id_b);
 
-        let var_expr_a = Expression {
-            expr_ast: ExpressionAst::Literal(var_lit_a),
-            expr_type: Some(ExpressionType::Int),
-        };
-        let var_expr_b = Expression {
-            expr_ast: ExpressionAst::Literal(var_lit_b),
-            expr_type: Some(ExpressionType::Int),
-        };
         let cond_ast = ExpressionAst::Binary(
             BinaryOp::LessThan,
             Box::new(var_expr_a),
             Box::new(var_expr_b),
         );
-        let cond_expr = Expression {
-            expr_ast: cond_ast,
-            expr_type: Some(ExpressionType::Bool),
-        };
+        let cond_expr = self.typed_expr(cond_ast, ExpressionType::Bool);
         self.build_expr(node_id, &cond_expr);
 
+        let count_str = PrettyPrintAst::pprint_expr(&repeat_stmt.count_expr);
+        let cond = CfgCond::of_text(&format!("REPEAT {}", count_str));
+        self.set_cond(node_id, cond.clone());
+
         // `REPEAT block`
         let while_node_id = self.cfg_graph.new_node();
         self.add_edge(node_id, while_node_id, CfgJumpType::WhenTrue);
         let last_while_block_node_id = self.build_block(while_node_id, &repeat_stmt.block);
 
         // TMPVAR_A = TMPVAR_A + 1
-        let one_lit = LiteralExpr::Int(1);
-        let one_expr = Expression {
-            expr_type: Some(ExpressionType::Int),
-            expr_ast: ExpressionAst::Literal(one_lit),
-        };
-        let var_expr_a = Expression {
-            expr_ast: ExpressionAst::Literal(var_lit_a_clone),
-            expr_type: Some(ExpressionType::Int),
-        };
+        let one_lit = ExpressionAst::Literal(LiteralExpr::Int(1));
+        let one_expr = self.typed_expr(one_lit, ExpressionType::Int);
+        let var_expr_a = self.tmp_var_expr(&var_name_a, var_id_a);
+
         let incr_var_a_ast =
             ExpressionAst::Binary(BinaryOp::Add, Box::new(var_expr_a), Box::new(one_expr));
-        let incr_expr = Expression {
-            expr_type: Some(ExpressionType::Int),
-            expr_ast: incr_var_a_ast,
-        };
+        let incr_expr = self.typed_expr(incr_var_a_ast, ExpressionType::Int);
         self.build_assign(last_while_block_node_id, var_id_a, &incr_expr);
 
         // TMPVAR_A < TMPVAR_B
         self.build_expr(last_while_block_node_id, &cond_expr);
+        self.set_cond(last_while_block_node_id, cond);
 
         // jump when-true to the start of the loop
         self.add_edge(
@@ -396,6 +899,125 @@ impl<'env> CfgBuilder<'env> {
         after_node_id
     }
 
+    fn build_foreach(&mut self, node_id: CfgNodeId, foreach_stmt: &ForeachStmt) -> CfgNodeId {
+        let list_expr = &foreach_stmt.list_expr;
+        let list_type = self.env.expr_type(list_expr).clone();
+
+        // MAKE TMPVAR_LIST = `list_expr`
+        let (list_var_id, _) = self.env.create_tmp_var(self.current_proc_id, list_type);
+        let node_id = self.build_assign(node_id, list_var_id, list_expr);
+
+        let var_id = foreach_stmt.var_id.unwrap();
+
+        let list_str = PrettyPrintAst::pprint_expr(list_expr);
+        let cond = CfgCond::of_text(&format!("FOREACH {}", list_str));
+
+        self.build_list_loop(node_id, list_var_id, cond, &mut |builder, node_id| {
+            // MAKE ? = `the current element`
+            builder.append_inst(node_id, CfgInstruction::Store(var_id));
+
+            builder.build_block(node_id, &foreach_stmt.block)
+        })
+    }
+
+    // builds a loop over the elements of the list stored at `list_var_id` (similar to `build_repeat`).
+    //
+    // each iteration pushes the current element and calls `build_body` with the iteration node,
+    // `build_body` should pop the element and return the last node of the iteration body.
+    // returns the (empty) CFG node following the loop
+    fn build_list_loop(
+        &mut self,
+        node_id: CfgNodeId,
+        list_var_id: SymbolId,
+        cond: CfgCond,
+        build_body: &mut dyn FnMut(&mut Self, CfgNodeId) -> CfgNodeId,
+    ) -> CfgNodeId {
+        let (index_var_id, _) = self
+            .env
+            .create_tmp_var(self.current_proc_id, ExpressionType::Int);
+        let (len_var_id, _) = self
+            .env
+            .create_tmp_var(self.current_proc_id, ExpressionType::Int);
+
+        // MAKE TMPVAR_INDEX = 0
+        self.append_inst(node_id, CfgInstruction::Int(0));
+        self.append_inst(node_id, CfgInstruction::Store(index_var_id));
+
+        // MAKE TMPVAR_LEN = `the list length`
+        self.append_inst(node_id, CfgInstruction::Load(list_var_id));
+        self.append_inst(node_id, CfgInstruction::ListLen);
+        self.append_inst(node_id, CfgInstruction::Store(len_var_id));
+
+        let cond_insts = vec![
+            CfgInstruction::Load(index_var_id),
+            CfgInstruction::Load(len_var_id),
+            CfgInstruction::LessThan,
+        ];
+
+        // TMPVAR_INDEX < TMPVAR_LEN
+        for inst in cond_insts.iter() {
+            self.append_inst(node_id, inst.clone());
+        }
+        self.set_cond(node_id, cond.clone());
+
+        let while_node_id = self.cfg_graph.new_node();
+        self.add_edge(node_id, while_node_id, CfgJumpType::WhenTrue);
+
+        // pushing the element at TMPVAR_INDEX
+        self.append_inst(while_node_id, CfgInstruction::Load(list_var_id));
+        self.append_inst(while_node_id, CfgInstruction::Load(index_var_id));
+        self.append_inst(while_node_id, CfgInstruction::ListItem);
+
+        let last_while_block_node_id = build_body(self, while_node_id);
+
+        // TMPVAR_INDEX = TMPVAR_INDEX + 1
+        let incr_insts = vec![
+            CfgInstruction::Load(index_var_id),
+            CfgInstruction::Int(1),
+            CfgInstruction::Add,
+            CfgInstruction::Store(index_var_id),
+        ];
+
+        for inst in incr_insts.into_iter().chain(cond_insts) {
+            self.append_inst(last_while_block_node_id, inst);
+        }
+        self.set_cond(last_while_block_node_id, cond);
+
+        self.add_edge(
+            last_while_block_node_id,
+            while_node_id,
+            CfgJumpType::WhenTrue,
+        );
+
+        let after_node_id = self.cfg_graph.new_node();
+        self.add_edge(
+            last_while_block_node_id,
+            after_node_id,
+            CfgJumpType::Fallback,
+        );
+        self.add_edge(node_id, after_node_id, CfgJumpType::Fallback);
+
+        after_node_id
+    }
+
+    fn build_catch(&mut self, node_id: CfgNodeId, catch_stmt: &CatchStmt) -> CfgNodeId {
+        // the node following the block, where a `THROW` resumes the execution too.
+        // it starts with popping the `CATCH` marker, so it's never empty (and never skipped by jump threading)
+        let after_node_id = self.cfg_graph.new_node();
+        self.append_inst(after_node_id, CfgInstruction::EndCatch);
+
+        let catch_inst = CfgInstruction::Catch(catch_stmt.tag.clone(), after_node_id);
+        self.append_inst(node_id, catch_inst);
+
+        let last_block_node_id = self.build_block(node_id, &catch_stmt.block);
+
+        if !self.cfg_graph.ends_with_return(last_block_node_id) {
+            self.add_edge(last_block_node_id, after_node_id, CfgJumpType::Always);
+        }
+
+        after_node_id
+    }
+
     fn build_if(&mut self, node_id: CfgNodeId, if_stmt: &IfStmt) -> CfgNodeId {
         // 1)  let's mark current CFG node as `CURRENT_NODE_ID` (the `node_id` parameter)
         //     this node is assumed to be empty
@@ -417,7 +1039,8 @@ impl<'env> CfgBuilder<'env> {
         //       9.1) add edge `CURRENT_NODE_ID` --jmp-fallback--> `AFTER_NODE_ID`
         // 10) return `AFTER_NODE_ID` node_id (empty CFG node to be used for the next statement)
 
-        self.build_expr(node_id, &if_stmt.cond_expr);
+        let node_id = self.build_expr(node_id, &if_stmt.cond_expr);
+        self.set_cond(node_id, CfgCond::of_expr(&if_stmt.cond_expr));
 
         let true_node_id = self.cfg_graph.new_node();
         let last_true_block_node_id = self.build_block(true_node_id, &if_stmt.true_block);
@@ -441,7 +1064,7 @@ impl<'env> CfgBuilder<'env> {
 
         let mut draw_true_block_to_after_node_edge = false;
 
-        let after_node_id = match true_block_ends_with_empty_node {
+        let mut after_node_id = match true_block_ends_with_empty_node {
             true => Some(last_true_block_node_id), // we'll reuse this empty node
             false => {
                 // we know the `true-block last node` isn't empty
@@ -475,20 +1098,20 @@ impl<'env> CfgBuilder<'env> {
                 self.cfg_graph.ends_with_return(last_false_block_node_id);
 
             if false_block_ends_with_return == false {
-                self.add_edge(
-                    last_false_block_node_id,
-                    after_node_id.unwrap(),
-                    CfgJumpType::Always,
-                );
+                // the `true-block` may end with a `RETURN`, leaving no after node yet
+                let cfg_graph = &mut self.cfg_graph;
+                let after = *after_node_id.get_or_insert_with(|| cfg_graph.new_node());
+
+                self.add_edge(last_false_block_node_id, after, CfgJumpType::Always);
             }
         } else {
             // there is no `else-block`
             // we'll draw edge `CURRENT_NODE_ID` --jmp-fallback--> `AFTER_NODE_ID`
-            // in case there is an after node
+            // (allocating the after node when the `true-block` ends with a `RETURN`)
+            let cfg_graph = &mut self.cfg_graph;
+            let after = *after_node_id.get_or_insert_with(|| cfg_graph.new_node());
 
-            if after_node_id.is_some() {
-                self.add_edge(node_id, after_node_id.unwrap(), CfgJumpType::Fallback);
-            }
+            self.add_edge(node_id, after, CfgJumpType::Fallback);
         }
 
         if after_node_id.is_some() {
@@ -501,8 +1124,10 @@ impl<'env> CfgBuilder<'env> {
     fn build_block(&mut self, node_id: CfgNodeId, block_stmt: &BlockStatement) -> CfgNodeId {
         let mut last_node_id = node_id;
 
-        for stmt in &block_stmt.stmts {
-            last_node_id = self.build_stmt(node_id, stmt);
+        for (i, stmt) in block_stmt.stmts.iter().enumerate() {
+            let location = block_stmt.stmt_location(i);
+
+            last_node_id = self.build_located_stmt(last_node_id, stmt, location);
         }
 
         last_node_id
@@ -514,6 +1139,10 @@ impl<'env> CfgBuilder<'env> {
         node.append_inst(inst);
     }
 
+    fn set_cond(&mut self, node_id: CfgNodeId, cond: CfgCond) {
+        self.cfg_graph.get_node_mut(node_id).cond = Some(cond);
+    }
+
     fn add_edge(&mut self, src_id: CfgNodeId, dst_id: CfgNodeId, jmp_type: CfgJumpType) {
         self.cfg_graph.add_edge(src_id, dst_id, jmp_type);
     }
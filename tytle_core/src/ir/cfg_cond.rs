@@ -0,0 +1,34 @@
+use crate::ast::expression::{Expression, PrettyPrintAst};
+use crate::ast::NodeId;
+use crate::compat::prelude::*;
+
+// the condition a node ending with conditional jumps branches on, taking its `WhenTrue` edge
+// when it's true (see `CfgGraph::edge_cond`).
+// it's pushed by the last instruction of the node, or by its predecessors when the node is empty
+// (e.g the node following an `AND`, which pushes the condition along both of its branches)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfgCond {
+    // the AST expression of the condition (see `Environment::expr_types`).
+    // `None` for the conditions generated by the loops (e.g `REPEAT` counting its iterations)
+    pub expr_id: Option<NodeId>,
+
+    // the condition as written in the source (e.g `X > 10`), or the loop it's generated for
+    pub text: String,
+}
+
+impl CfgCond {
+    pub fn of_expr(expr: &Expression) -> Self {
+        Self {
+            expr_id: Some(expr.node_id),
+            text: PrettyPrintAst::pprint_expr(expr),
+        }
+    }
+
+    // a condition having no AST expression (e.g generated by a loop, or restored from the JSON IR)
+    pub fn of_text(text: &str) -> Self {
+        Self {
+            expr_id: None,
+            text: text.to_string(),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::ir::{CfgJumpType, CfgNodeId};
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub struct CfgEdge {
     pub node_id: CfgNodeId,
     pub jmp_type: CfgJumpType,
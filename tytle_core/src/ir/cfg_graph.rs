@@ -1,10 +1,15 @@
 use crate::ast::semantic::SymbolId;
-use crate::ir::CfgNode;
-use std::collections::HashMap;
+use crate::compat::collections::{BTreeMap, BTreeSet, HashSet};
+use crate::compat::prelude::*;
+use crate::ir::{CfgCond, CfgEdge, CfgInstruction, CfgNode};
+use serde::{Deserialize, Serialize};
 
 pub type CfgNodeId = usize;
 
-#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+#[derive(
+    Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
 pub enum CfgJumpType {
     WhenTrue,
     Always,
@@ -17,8 +22,9 @@ pub enum CfgElement {
     Edge(CfgNodeId, CfgNodeId, CfgJumpType),
 }
 
+// a procedure entry node allocated while building the CFG (see `CfgBuilder`)
 #[derive(Debug, Clone, PartialEq)]
-pub struct CfgProc {
+pub struct CfgProcEntry {
     pub node_id: CfgNodeId,
     pub proc_id: SymbolId,
     pub built: bool,
@@ -27,14 +33,16 @@ pub struct CfgProc {
 #[derive(Debug, Clone, PartialEq)]
 pub struct CfgGraph {
     next_id: CfgNodeId,
-    pub nodes: HashMap<CfgNodeId, CfgNode>,
+
+    // ordered by id, so iterating the graph (and serializing it) is deterministic
+    pub nodes: BTreeMap<CfgNodeId, CfgNode>,
 }
 
 impl CfgGraph {
     pub fn new() -> Self {
         let mut graph = Self {
             next_id: Self::default_entry_node_id(),
-            nodes: HashMap::new(),
+            nodes: BTreeMap::new(),
         };
 
         graph.new_node();
@@ -88,6 +96,99 @@ impl CfgGraph {
         dst_node.add_incoming_edge(src_id, jmp_type);
     }
 
+    pub fn remove_edge(&mut self, src_id: CfgNodeId, dst_id: CfgNodeId, jmp_type: CfgJumpType) {
+        let src_node = self.get_node_mut(src_id);
+        src_node.outgoing.remove(&CfgEdge {
+            node_id: dst_id,
+            jmp_type,
+        });
+
+        let dst_node = self.get_node_mut(dst_id);
+        dst_node.incoming.remove(&CfgEdge {
+            node_id: src_id,
+            jmp_type,
+        });
+    }
+
+    // makes the `src_id` edge jumping to `dst_id` jump to `new_dst_id` instead
+    pub fn retarget_edge(
+        &mut self,
+        src_id: CfgNodeId,
+        dst_id: CfgNodeId,
+        jmp_type: CfgJumpType,
+        new_dst_id: CfgNodeId,
+    ) {
+        self.remove_edge(src_id, dst_id, jmp_type);
+        self.add_edge(src_id, new_dst_id, jmp_type);
+    }
+
+    // splits the node before the instruction at `at_index`, returns the id of the new node.
+    // the new node gets the instructions from `at_index` onwards, the outgoing edges
+    // and the condition of the node, which falls through to it (using an `Always` edge)
+    pub fn split_node(&mut self, node_id: CfgNodeId, at_index: usize) -> CfgNodeId {
+        let new_id = self.new_node();
+
+        let node = self.get_node_mut(node_id);
+        let insts = node.insts.split_off(at_index);
+        let cond = node.cond.take();
+
+        let new_node = self.get_node_mut(new_id);
+        new_node.insts = insts;
+        new_node.cond = cond;
+
+        self.move_outgoing_edges(node_id, new_id);
+        self.add_edge(node_id, new_id, CfgJumpType::Always);
+
+        new_id
+    }
+
+    // merges into the node its successor, when the node always jumps to it
+    // and it's the only node jumping to it. returns whether the nodes have been merged.
+    //
+    // the successor mustn't be a procedure entry or the node a `CATCH` resumes at
+    // (those are addressed by instructions, and have no incoming edges or start with `EndCatch`)
+    pub fn merge_with_successor(&mut self, node_id: CfgNodeId) -> bool {
+        let node = self.get_node(node_id);
+
+        let succ_id = match node.outgoing.iter().collect::<Vec<_>>().as_slice() {
+            [edge] if edge.jmp_type == CfgJumpType::Always && edge.node_id != node_id => {
+                edge.node_id
+            }
+            _ => return false,
+        };
+
+        let succ = self.get_node(succ_id);
+
+        if succ.incoming.len() != 1 || succ.insts.first() == Some(&CfgInstruction::EndCatch) {
+            return false;
+        }
+
+        self.remove_edge(node_id, succ_id, CfgJumpType::Always);
+        self.move_outgoing_edges(succ_id, node_id);
+
+        let succ = self.nodes.remove(&succ_id).unwrap();
+        let node = self.get_node_mut(node_id);
+
+        node.insts.extend(succ.insts);
+        node.cond = succ.cond;
+
+        true
+    }
+
+    fn move_outgoing_edges(&mut self, from_id: CfgNodeId, to_id: CfgNodeId) {
+        let outgoing = core::mem::take(&mut self.get_node_mut(from_id).outgoing);
+
+        for edge in outgoing {
+            let dst_node = self.get_node_mut(edge.node_id);
+            dst_node.incoming.remove(&CfgEdge {
+                node_id: from_id,
+                jmp_type: edge.jmp_type,
+            });
+
+            self.add_edge(to_id, edge.node_id, edge.jmp_type);
+        }
+    }
+
     pub fn get_node(&self, node_id: CfgNodeId) -> &CfgNode {
         self.nodes.get(&node_id).unwrap()
     }
@@ -127,6 +228,77 @@ impl CfgGraph {
         Self::default_entry_node_id()
     }
 
+    // the condition under which the `src_id` outgoing `edge` is taken (only for `WhenTrue` edges)
+    pub fn edge_cond(&self, src_id: CfgNodeId, edge: &CfgEdge) -> Option<&CfgCond> {
+        match edge.jmp_type {
+            CfgJumpType::WhenTrue => self.get_node(src_id).cond.as_ref(),
+            _ => None,
+        }
+    }
+
+    // the distinct successors of the node (sorted by id)
+    pub fn successors(&self, node_id: CfgNodeId) -> Vec<CfgNodeId> {
+        let node = self.get_node(node_id);
+
+        let succs = node.outgoing.iter().map(|edge| edge.node_id);
+        succs.collect::<BTreeSet<CfgNodeId>>().into_iter().collect()
+    }
+
+    // the distinct predecessors of the node (sorted by id), including the unreachable ones
+    pub fn predecessors(&self, node_id: CfgNodeId) -> Vec<CfgNodeId> {
+        let node = self.get_node(node_id);
+
+        let preds = node.incoming.iter().map(|edge| edge.node_id);
+        preds.collect::<BTreeSet<CfgNodeId>>().into_iter().collect()
+    }
+
+    // the nodes reachable from `entry` in depth-first pre-order (the successors visited by id)
+    pub fn dfs_from(&self, entry: CfgNodeId) -> Vec<CfgNodeId> {
+        self.dfs(entry).0
+    }
+
+    // the nodes reachable from `entry` in reverse post-order,
+    // i.e a node comes before its successors (unless it's reached along a loop back-edge)
+    pub fn rpo_from(&self, entry: CfgNodeId) -> Vec<CfgNodeId> {
+        let mut postorder = self.dfs(entry).1;
+
+        postorder.reverse();
+        postorder
+    }
+
+    // the reverse post-order of the `main` sub-graph
+    pub fn rpo(&self) -> Vec<CfgNodeId> {
+        self.rpo_from(self.get_entry_node_id())
+    }
+
+    // returns the pre-order and the post-order of the nodes reachable from `entry`
+    fn dfs(&self, entry: CfgNodeId) -> (Vec<CfgNodeId>, Vec<CfgNodeId>) {
+        let mut visited = HashSet::new();
+        let mut preorder = vec![entry];
+        let mut postorder = Vec::new();
+
+        // an explicit stack of `(node, its successors, the next successor to visit)`
+        let mut stack = vec![(entry, self.successors(entry), 0)];
+        visited.insert(entry);
+
+        while let Some((node_id, succs, next)) = stack.last_mut() {
+            if *next < succs.len() {
+                let succ_id = succs[*next];
+                *next += 1;
+
+                if visited.insert(succ_id) {
+                    preorder.push(succ_id);
+                    stack.push((succ_id, self.successors(succ_id), 0));
+                }
+            } else {
+                postorder.push(*node_id);
+                stack.pop();
+            }
+        }
+
+        (preorder, postorder)
+    }
+
     pub fn compact(&mut self) {
         let orphan_ids: Vec<usize> = self
             .nodes
@@ -146,6 +318,87 @@ mod tests {
     use super::*;
     use crate::ir::CfgInstruction;
 
+    // 1 -> (2 | 3) -> 4 -> 1, and an unreachable node 5 jumping to 4
+    fn diamond_loop() -> CfgGraph {
+        let mut cfg_graph = CfgGraph::new();
+
+        for node_id in 2..=5 {
+            cfg_graph.add_node(CfgNode::new(node_id));
+        }
+
+        cfg_graph.add_edge(1, 3, CfgJumpType::Fallback);
+        cfg_graph.add_edge(1, 2, CfgJumpType::WhenTrue);
+        cfg_graph.add_edge(2, 4, CfgJumpType::Always);
+        cfg_graph.add_edge(3, 4, CfgJumpType::Always);
+        cfg_graph.add_edge(4, 1, CfgJumpType::WhenTrue);
+        cfg_graph.add_edge(5, 4, CfgJumpType::Always);
+
+        cfg_graph
+    }
+
+    #[test]
+    fn cfg_graph_traversal_orders() {
+        let cfg_graph = diamond_loop();
+
+        assert_eq!(vec![1, 2, 4, 3], cfg_graph.dfs_from(1));
+        assert_eq!(vec![1, 3, 2, 4], cfg_graph.rpo());
+        assert_eq!(vec![4, 1, 3, 2], cfg_graph.rpo_from(4));
+    }
+
+    #[test]
+    fn cfg_graph_split_node() {
+        let mut cfg_graph = diamond_loop();
+
+        let node = cfg_graph.get_node_mut(1);
+        node.append_inst(CfgInstruction::Int(1));
+        node.append_inst(CfgInstruction::Int(2));
+        node.append_inst(CfgInstruction::LessThan);
+
+        let new_id = cfg_graph.split_node(1, 1);
+        assert_eq!(6, new_id);
+
+        assert_eq!(vec![CfgInstruction::Int(1)], cfg_graph.get_node(1).insts);
+        assert_eq!(vec![new_id], cfg_graph.successors(1));
+
+        let new_node = cfg_graph.get_node(new_id);
+        assert_eq!(2, new_node.insts.len());
+        assert_eq!(vec![2, 3], cfg_graph.successors(new_id));
+        assert_eq!(vec![new_id], cfg_graph.predecessors(3));
+
+        // the loop back-edge still jumps to the split node start
+        assert_eq!(vec![1], cfg_graph.successors(4));
+        assert_eq!(vec![4], cfg_graph.predecessors(1));
+    }
+
+    #[test]
+    fn cfg_graph_merge_with_successor() {
+        let mut cfg_graph = diamond_loop();
+
+        // node `4` has two predecessors, and node `1` branches
+        assert!(!cfg_graph.merge_with_successor(2));
+        assert!(!cfg_graph.merge_with_successor(1));
+
+        let new_id = cfg_graph.split_node(4, 0);
+        cfg_graph.get_node_mut(new_id).append_inst(CfgInstruction::Trap);
+
+        assert!(cfg_graph.merge_with_successor(4));
+        assert!(!cfg_graph.nodes.contains_key(&new_id));
+
+        assert_eq!(vec![CfgInstruction::Trap], cfg_graph.get_node(4).insts);
+        assert_eq!(vec![1], cfg_graph.successors(4));
+        assert_eq!(vec![4], cfg_graph.predecessors(1));
+    }
+
+    #[test]
+    fn cfg_graph_successors_and_predecessors() {
+        let cfg_graph = diamond_loop();
+
+        assert_eq!(vec![2, 3], cfg_graph.successors(1));
+        assert_eq!(vec![1], cfg_graph.successors(4));
+        assert_eq!(vec![2, 3, 5], cfg_graph.predecessors(4));
+        assert!(cfg_graph.predecessors(5).is_empty());
+    }
+
     #[test]
     fn cfg_graph_node_is_empty() {
         let node = CfgNode::new(1);
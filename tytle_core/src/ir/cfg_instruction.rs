@@ -1,20 +1,50 @@
-use crate::ast::semantic::SymbolId;
+use crate::ast::semantic::{Primitive, SymbolId};
 use crate::ast::statement::{Command, Direction};
+use crate::compat::prelude::*;
 use crate::ir::CfgNodeId;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "op", content = "arg", rename_all = "snake_case")]
 pub enum CfgInstruction {
     Command(Command),
     Direction(Direction),
     Load(SymbolId),
     Store(SymbolId),
+    Dup,
+    Pop,
     Call(CfgNodeId),
+    NativeCall(SymbolId),
+    Primitive(Primitive),
     Bool(bool),
     Int(isize),
     Str(String),
     Return,
     Trap,
     Print,
+
+    // pops a string and draws it at the turtle position (see `Host::exec_label`)
+    Label,
+
+    // pops the font family (or the font size) of the next labels
+    SetLabelFont,
+    SetLabelSize,
+
+    // pops the name of the next turtle shape (see `Host::turtle_shape_changed`)
+    SetShape,
+
+    // pops the next pen color, a palette index or a color name (see `Host::pen_color_changed`)
+    SetPenColor,
+
+    // pops the duration and the frequency of a tone (see `Host::play_tone`)
+    Tone,
+
+    // pops the name of a stream and opens it (see `Host::open_stream`),
+    // makes it the stream `PRINT` writes to (`SETWRITE`) or closes it
+    OpenWrite,
+    SetWrite,
+    Close,
+    #[serde(rename = "eoc")]
     EOC,
     Add,
     Mul,
@@ -24,4 +54,109 @@ pub enum CfgInstruction {
     Or,
     GreaterThan,
     LessThan,
+    Equal,
+    NotEqual,
+
+    // pops `n` items and pushes the list of them (the first pushed item is the list head)
+    MakeList(usize),
+
+    // pops a list and pushes its length
+    ListLen,
+
+    // pops an index and a list and pushes the list element at the index (starting from 0)
+    ListItem,
+
+    // pops a list and an item (the list being at the top of the stack)
+    // and pushes the list with the item appended to its end
+    ListPush,
+
+    // pushes a reference to the procedure
+    ProcRef(SymbolId),
+
+    // pops a procedure reference and calls the procedure with the `n` args below it.
+    // the args are checked against the procedure params, and the return value (if any) is discarded
+    Apply(usize),
+
+    // pushes a block running the (generated) procedure. the block variables which aren't its own
+    // are those of the frame it's created within (see `CfgBuilder::build_block_proc`)
+    Block(SymbolId),
+
+    // pops a block and runs it
+    Run,
+
+    // pushes a `CATCH` marker of the tag. a `THROW` of the tag unwinds the callstack up to the marker
+    // and resumes the execution at the CFG node (starting with the matching `EndCatch`)
+    Catch(String, CfgNodeId),
+
+    // pops the `CATCH` marker
+    EndCatch,
+
+    // unwinds the callstack up to the innermost `CATCH` marker of the tag
+    Throw(String),
+
+    // starts (or stops) tracing the calls of the procedure (see `Host::exec_trace`)
+    Trace(SymbolId),
+    Untrace(SymbolId),
+
+    // pops a boolean and stops the execution with `InterpreterException::AssertionFailed` when it's false.
+    // `line` and `column` are the location of the `ASSERT` statement
+    Assert {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+}
+
+impl CfgInstruction {
+    // pushes a value without popping any and without side-effects
+    pub fn is_pure_leaf(&self) -> bool {
+        matches!(
+            self,
+            CfgInstruction::Int(_) | CfgInstruction::Bool(_) | CfgInstruction::Load(_)
+        )
+    }
+
+    // pops its operands and pushes the result without side-effects
+    pub fn is_pure_op(&self) -> bool {
+        self.is_unary_op() || self.is_binary_op()
+    }
+
+    // may stop the execution of its CFG node midway, with a `THROW` or a (catchable) runtime error
+    pub fn may_throw(&self) -> bool {
+        matches!(
+            self,
+            CfgInstruction::Throw(_)
+                | CfgInstruction::Assert { .. }
+                | CfgInstruction::Command(Command::PushState)
+                | CfgInstruction::Command(Command::PopState)
+                | CfgInstruction::Call(_)
+                | CfgInstruction::Apply(_)
+                | CfgInstruction::Run
+                | CfgInstruction::Primitive(_)
+                | CfgInstruction::ListItem
+                | CfgInstruction::GreaterThan
+                | CfgInstruction::LessThan
+                | CfgInstruction::Equal
+                | CfgInstruction::NotEqual
+        )
+    }
+
+    pub fn is_unary_op(&self) -> bool {
+        matches!(self, CfgInstruction::Not)
+    }
+
+    pub fn is_binary_op(&self) -> bool {
+        matches!(
+            self,
+            CfgInstruction::Add
+                | CfgInstruction::Mul
+                | CfgInstruction::Div
+                | CfgInstruction::And
+                | CfgInstruction::Or
+                | CfgInstruction::GreaterThan
+                | CfgInstruction::LessThan
+                | CfgInstruction::Equal
+                | CfgInstruction::NotEqual
+        )
+    }
 }
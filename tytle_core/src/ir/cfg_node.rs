@@ -1,12 +1,28 @@
-use crate::ir::{CfgEdge, CfgInstruction, CfgJumpType, CfgNodeId};
-use std::collections::HashSet;
+use crate::compat::collections::BTreeSet;
+use crate::compat::prelude::*;
+use crate::ir::{CfgCond, CfgEdge, CfgInstruction, CfgJumpType, CfgNodeId};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct CfgNode {
     pub id: CfgNodeId,
     pub insts: Vec<CfgInstruction>,
-    pub incoming: HashSet<CfgEdge>,
-    pub outgoing: HashSet<CfgEdge>,
+
+    // the edges are ordered by their node id (and then by their jump type)
+    pub incoming: BTreeSet<CfgEdge>,
+    pub outgoing: BTreeSet<CfgEdge>,
+
+    // what the node branches on, when it has conditional edges
+    pub cond: Option<CfgCond>,
+}
+
+// the condition is metadata (for tooling), so nodes are compared ignoring it
+impl PartialEq for CfgNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.insts == other.insts
+            && self.incoming == other.incoming
+            && self.outgoing == other.outgoing
+    }
 }
 
 impl CfgNode {
@@ -16,6 +32,7 @@ impl CfgNode {
             insts: Vec::new(),
             incoming: Default::default(),
             outgoing: Default::default(),
+            cond: None,
         }
     }
 
@@ -45,6 +62,11 @@ impl CfgNode {
         }
     }
 
+    // the instruction pushing the condition the node branches on (`None` for an empty node)
+    pub fn cond_inst(&self) -> Option<&CfgInstruction> {
+        self.cond.as_ref().and(self.insts.last())
+    }
+
     pub fn append_inst(&mut self, inst: CfgInstruction) {
         self.insts.push(inst);
     }
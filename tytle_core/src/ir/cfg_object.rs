@@ -1,8 +1,109 @@
-use crate::ast::semantic::SymbolId;
+use crate::ast::expression::ExpressionType;
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::compat::collections::BTreeMap;
+use crate::compat::prelude::*;
 use crate::ir::{CfgGraph, CfgNodeId};
-use std::collections::HashMap;
 
+// the output of the code generation.
+// all the procedures (including `__main__`) share `graph`, since `Call` instructions address nodes directly,
+// while `procs` holds what's needed for calling each of them (so it's precomputed once, at compile time)
 pub struct CfgObject {
     pub graph: CfgGraph,
-    pub jmp_table: HashMap<CfgNodeId, SymbolId>,
+    pub jmp_table: BTreeMap<CfgNodeId, SymbolId>,
+    pub procs: BTreeMap<SymbolId, CfgProc>,
+}
+
+// the layout of a procedure callstack frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfgProc {
+    pub proc_id: SymbolId,
+    pub entry: CfgNodeId,
+
+    // the number of params (which occupy the first frame slots)
+    pub args_count: usize,
+
+    // the types of the (non-param) locals, by their frame slots (following the params ones)
+    pub locals: Vec<ExpressionType>,
+
+    pub return_type: ExpressionType,
+}
+
+impl CfgProc {
+    pub fn locals_count(&self) -> usize {
+        self.locals.len()
+    }
+
+    // the number of frame slots allocated for the procedure variables
+    pub fn slots_count(&self) -> usize {
+        self.args_count + self.locals_count()
+    }
+
+    pub fn returns_value(&self) -> bool {
+        self.return_type != ExpressionType::Unit
+    }
+}
+
+impl CfgObject {
+    pub fn new(
+        graph: CfgGraph,
+        jmp_table: BTreeMap<CfgNodeId, SymbolId>,
+        env: &Environment,
+    ) -> Self {
+        let mut cfg = Self {
+            graph,
+            jmp_table,
+            procs: BTreeMap::new(),
+        };
+
+        cfg.update_procs(env);
+        cfg
+    }
+
+    pub fn get_proc(&self, proc_id: SymbolId) -> &CfgProc {
+        self.procs
+            .get(&proc_id)
+            .expect("procedure is missing from the `CfgObject`")
+    }
+
+    // recomputes the procedures frames layouts.
+    // should be called whenever new locals are allocated (e.g by the optimization passes)
+    pub fn update_procs(&mut self, env: &Environment) {
+        self.procs = self
+            .jmp_table
+            .iter()
+            .map(|(entry, proc_id)| (*proc_id, Self::build_proc(env, *proc_id, *entry)))
+            .collect();
+    }
+
+    fn build_proc(env: &Environment, proc_id: SymbolId, entry: CfgNodeId) -> CfgProc {
+        let proc = env.symbol_table.get_proc_by_id(proc_id);
+
+        let mut locals = env
+            .locals_symbols
+            .get(&proc_id)
+            .into_iter()
+            .flatten()
+            .map(|var_id| env.symbol_table.get_var_by_id(*var_id))
+            .filter(|var| !var.is_param())
+            .collect::<Vec<_>>();
+
+        locals.sort_by_key(|var| var.index);
+
+        let locals = locals
+            .iter()
+            .map(|var| {
+                var.var_type
+                    .clone()
+                    .expect("local variable type is missing")
+            })
+            .collect();
+
+        CfgProc {
+            proc_id,
+            entry,
+            args_count: proc.params_types.len(),
+            locals,
+            return_type: proc.return_type.clone(),
+        }
+    }
 }
@@ -0,0 +1,82 @@
+use crate::ast::semantic::SymbolId;
+use crate::ast::statement::Command;
+use crate::ir::{CfgEdge, CfgGraph, CfgInstruction, CfgNode, CfgNodeId, CfgObject};
+
+// a read-only walk over a `CfgGraph` (the IR counterpart of `AstWalker`).
+//
+// each procedure sub-graph is walked from its entry node in reverse post-order,
+// so a node is visited before its successors (unless it's reached along a loop back-edge).
+// the instructions of a node are visited in order, followed by its outgoing edges (by node id).
+// unreachable nodes aren't visited.
+//
+// the hooks do nothing by default, so a pass implements only the ones it's interested in
+pub trait CfgVisitor {
+    // walks each procedure of `cfg` (including `main`), ordered by their entry node
+    fn visit_program(&mut self, cfg: &CfgObject) {
+        for (entry_id, proc_id) in cfg.jmp_table.iter() {
+            self.on_proc_start(*proc_id, *entry_id);
+            self.visit_proc(&cfg.graph, *entry_id);
+            self.on_proc_end(*proc_id, *entry_id);
+        }
+    }
+
+    fn visit_proc(&mut self, graph: &CfgGraph, entry: CfgNodeId) {
+        for node_id in graph.rpo_from(entry) {
+            self.visit_node(graph.get_node(node_id));
+        }
+    }
+
+    fn visit_node(&mut self, node: &CfgNode) {
+        self.on_node_start(node.id);
+
+        for inst in node.insts.iter() {
+            self.visit_inst(node.id, inst);
+        }
+
+        for edge in node.outgoing.iter() {
+            self.on_edge(node.id, edge);
+        }
+
+        self.on_node_end(node.id);
+    }
+
+    fn visit_inst(&mut self, node_id: CfgNodeId, inst: &CfgInstruction) {
+        match inst {
+            CfgInstruction::Load(var_id) => self.on_load(node_id, *var_id),
+            CfgInstruction::Store(var_id) => self.on_store(node_id, *var_id),
+            CfgInstruction::Call(entry_id) => self.on_call(node_id, *entry_id),
+            CfgInstruction::Command(cmd) => self.on_command(node_id, cmd),
+            CfgInstruction::Bool(_) | CfgInstruction::Int(_) | CfgInstruction::Str(_) => {
+                self.on_literal(node_id, inst)
+            }
+            CfgInstruction::Return | CfgInstruction::EOC | CfgInstruction::Throw(_) => {
+                self.on_exit(node_id, inst)
+            }
+            _ => self.on_inst(node_id, inst),
+        }
+    }
+
+    fn on_proc_start(&mut self, _proc_id: SymbolId, _entry: CfgNodeId) {}
+    fn on_proc_end(&mut self, _proc_id: SymbolId, _entry: CfgNodeId) {}
+
+    fn on_node_start(&mut self, _node_id: CfgNodeId) {}
+    fn on_node_end(&mut self, _node_id: CfgNodeId) {}
+
+    fn on_edge(&mut self, _src_id: CfgNodeId, _edge: &CfgEdge) {}
+
+    fn on_load(&mut self, _node_id: CfgNodeId, _var_id: SymbolId) {}
+    fn on_store(&mut self, _node_id: CfgNodeId, _var_id: SymbolId) {}
+
+    // a call of the user procedure entered at `entry_id`
+    fn on_call(&mut self, _node_id: CfgNodeId, _entry_id: CfgNodeId) {}
+    fn on_command(&mut self, _node_id: CfgNodeId, _cmd: &Command) {}
+
+    // `Bool`, `Int` and `Str`
+    fn on_literal(&mut self, _node_id: CfgNodeId, _inst: &CfgInstruction) {}
+
+    // `Return`, `EOC` and `Throw`, leaving the procedure
+    fn on_exit(&mut self, _node_id: CfgNodeId, _inst: &CfgInstruction) {}
+
+    // the instructions having no hook of their own
+    fn on_inst(&mut self, _node_id: CfgNodeId, _inst: &CfgInstruction) {}
+}
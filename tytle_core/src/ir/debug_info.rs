@@ -0,0 +1,172 @@
+use crate::ast::semantic::{Environment, Symbol, SymbolId};
+use crate::compat::collections::{BTreeMap, BTreeSet};
+use crate::compat::prelude::*;
+use crate::ir::{CfgNodeId, CfgObject};
+use crate::lexer::Location;
+
+// the boundaries of a procedure within the CFG and within the source code
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcDebugInfo {
+    pub proc_id: SymbolId,
+    pub name: String,
+    pub entry: CfgNodeId,
+
+    // the location of the `TO` statement, `None` for `__main__` and the generated procedures
+    pub location: Option<Location>,
+
+    // the nodes of the procedure sub-graph
+    pub nodes: BTreeSet<CfgNodeId>,
+}
+
+// the debugging tables emitted alongside a `CfgObject` (see `CfgBuilder::build_with_debug_info`).
+// nothing in them is needed in order to execute the program, so they can be stripped
+// (see `CompiledProgram::strip_debug_info`).
+//
+// the instructions are mapped to the location of the statement they've been generated for.
+// the optimization passes don't keep the table up to date, so the locations of optimized code
+// are approximate (and missing for the nodes the passes have created)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DebugInfo {
+    // the location of each run of instructions, by node and by the index of its first instruction
+    lines: BTreeMap<CfgNodeId, BTreeMap<usize, Location>>,
+
+    // the variables names of each procedure frame, by their slots (params first)
+    slots: BTreeMap<SymbolId, BTreeMap<usize, String>>,
+
+    // the globals names by their index
+    globals: BTreeMap<usize, String>,
+
+    // the variables and procedures names by their symbol ids
+    names: BTreeMap<SymbolId, String>,
+
+    procs: BTreeMap<SymbolId, ProcDebugInfo>,
+}
+
+impl DebugInfo {
+    pub fn new(
+        cfg: &CfgObject,
+        env: &Environment,
+        lines: BTreeMap<CfgNodeId, BTreeMap<usize, Location>>,
+        procs_locations: &BTreeMap<SymbolId, Location>,
+    ) -> Self {
+        // dropping the runs having no instructions (e.g the ones of statements generating none)
+        let lines = lines
+            .into_iter()
+            .filter_map(|(node_id, runs)| {
+                let insts_count = cfg.graph.nodes.get(&node_id)?.insts.len();
+
+                let runs = runs
+                    .into_iter()
+                    .filter(|(ip, _location)| *ip < insts_count)
+                    .collect::<BTreeMap<usize, Location>>();
+
+                match runs.is_empty() {
+                    true => None,
+                    false => Some((node_id, runs)),
+                }
+            })
+            .collect();
+
+        let mut slots = BTreeMap::new();
+        let mut globals = BTreeMap::new();
+        let mut names = BTreeMap::new();
+
+        for (proc_id, var_ids) in env.locals_symbols.iter() {
+            let names = var_ids
+                .iter()
+                .map(|var_id| env.symbol_table.get_var_by_id(*var_id))
+                .filter_map(|var| Some((var.index?, var.name.clone())))
+                .collect();
+
+            slots.insert(*proc_id, names);
+        }
+
+        for symbol in env.symbol_table.symbols() {
+            match symbol {
+                Symbol::Var(var) => {
+                    if let (true, Some(index)) = (var.global, var.index) {
+                        globals.insert(index, var.name.clone());
+                    }
+
+                    names.insert(var.id, var.name.clone());
+                }
+                Symbol::Proc(proc) => {
+                    names.insert(proc.id, proc.name.clone());
+                }
+            }
+        }
+
+        let procs = cfg
+            .jmp_table
+            .iter()
+            .map(|(entry, proc_id)| {
+                let proc_info = ProcDebugInfo {
+                    proc_id: *proc_id,
+                    name: env.symbol_table.get_proc_by_id(*proc_id).name.clone(),
+                    entry: *entry,
+                    location: procs_locations.get(proc_id).copied(),
+                    nodes: cfg.graph.rpo_from(*entry).into_iter().collect(),
+                };
+
+                (*proc_id, proc_info)
+            })
+            .collect();
+
+        Self {
+            lines,
+            slots,
+            globals,
+            names,
+            procs,
+        }
+    }
+
+    // the location of the statement the instruction `ip` of node `node_id` has been generated for
+    pub fn location(&self, node_id: CfgNodeId, ip: usize) -> Option<Location> {
+        let runs = self.lines.get(&node_id)?;
+
+        runs.range(..=ip).next_back().map(|(_ip, location)| *location)
+    }
+
+    // the first instruction of each run generated for a statement on `line`
+    pub fn line_addrs(&self, line: usize) -> Vec<(CfgNodeId, usize)> {
+        let mut addrs = Vec::new();
+
+        for (node_id, runs) in self.lines.iter() {
+            for (ip, location) in runs.iter() {
+                if location.line() == line {
+                    addrs.push((*node_id, *ip));
+                }
+            }
+        }
+
+        addrs
+    }
+
+    pub fn slot_name(&self, proc_id: SymbolId, slot: usize) -> Option<&str> {
+        let names = self.slots.get(&proc_id)?;
+
+        names.get(&slot).map(|name| name.as_str())
+    }
+
+    pub fn global_name(&self, index: usize) -> Option<&str> {
+        self.globals.get(&index).map(|name| name.as_str())
+    }
+
+    pub fn symbol_name(&self, symbol_id: SymbolId) -> Option<&str> {
+        self.names.get(&symbol_id).map(|name| name.as_str())
+    }
+
+    pub fn get_proc(&self, proc_id: SymbolId) -> Option<&ProcDebugInfo> {
+        self.procs.get(&proc_id)
+    }
+
+    pub fn procs(&self) -> impl Iterator<Item = &ProcDebugInfo> {
+        self.procs.values()
+    }
+
+    // the procedure owning the node
+    pub fn proc_at(&self, node_id: CfgNodeId) -> Option<&ProcDebugInfo> {
+        self.procs.values().find(|proc| proc.nodes.contains(&node_id))
+    }
+}
@@ -0,0 +1,212 @@
+use crate::ast::semantic::SymbolId;
+use crate::compat::collections::BTreeSet;
+use crate::compat::prelude::*;
+use crate::ir::{CfgInstruction, CfgJumpType, CfgNodeId, CfgObject, DebugInfo};
+use core::fmt::Write;
+
+// the column the instructions comments (their statements locations) start at
+const COMMENT_COLUMN: usize = 36;
+
+// a human-readable listing of a `CfgObject` (for golden tests, teaching & bug reports), e.g:
+//
+// proc SQUARE (entry @2, 1:1)
+//   @2:
+//        0  load N                    ; 2:5
+//        1  load N
+//        2  mul
+//        3  return
+//
+// * each procedure is listed with the nodes of its sub-graph in reverse post-order,
+//   the nodes reachable from no procedure entry are listed last (under `unreachable`)
+// * the instructions are numbered by their index within the node, and followed by the
+//   node outgoing edges (e.g `-> @5 when_true (X > 10)`)
+// * the operands are named using the `DebugInfo` (when given), otherwise they're the symbol ids
+//   (e.g `load #4`). a comment marks the first instruction generated for each statement
+//
+// the listing can be read back by `CfgObject::assemble`
+struct Disassembler<'a> {
+    cfg: &'a CfgObject,
+    debug_info: Option<&'a DebugInfo>,
+    out: String,
+}
+
+impl CfgObject {
+    pub fn disassemble(&self) -> String {
+        Disassembler::new(self, None).disassemble()
+    }
+
+    pub fn disassemble_with_debug_info(&self, debug_info: &DebugInfo) -> String {
+        Disassembler::new(self, Some(debug_info)).disassemble()
+    }
+}
+
+impl<'a> Disassembler<'a> {
+    fn new(cfg: &'a CfgObject, debug_info: Option<&'a DebugInfo>) -> Self {
+        Self {
+            cfg,
+            debug_info,
+            out: String::new(),
+        }
+    }
+
+    fn disassemble(mut self) -> String {
+        let mut listed = BTreeSet::new();
+
+        for (entry, proc_id) in self.cfg.jmp_table.iter() {
+            if !listed.is_empty() {
+                self.out.push('\n');
+            }
+
+            let location = self
+                .debug_info
+                .and_then(|info| info.get_proc(*proc_id))
+                .and_then(|proc| proc.location);
+
+            let name = self.name(*proc_id);
+
+            write!(self.out, "proc {} (entry @{}", name, entry).unwrap();
+
+            if let Some(location) = location {
+                write!(self.out, ", {}", location).unwrap();
+            }
+
+            self.out.push_str(")\n");
+
+            for node_id in self.cfg.graph.rpo_from(*entry) {
+                if listed.insert(node_id) {
+                    self.node(node_id);
+                }
+            }
+        }
+
+        let unreachable = self
+            .cfg
+            .graph
+            .nodes
+            .keys()
+            .filter(|node_id| !listed.contains(node_id))
+            .copied()
+            .collect::<Vec<CfgNodeId>>();
+
+        if !unreachable.is_empty() {
+            self.out.push_str("\nunreachable\n");
+
+            for node_id in unreachable {
+                self.node(node_id);
+            }
+        }
+
+        self.out
+    }
+
+    fn node(&mut self, node_id: CfgNodeId) {
+        let graph = &self.cfg.graph;
+        let node = graph.get_node(node_id);
+
+        writeln!(self.out, "  @{}:", node_id).unwrap();
+
+        let mut last_location = None;
+
+        for (ip, inst) in node.insts.iter().enumerate() {
+            let mut line = format!("    {:>4}  {}", ip, self.inst(inst));
+
+            let location = self.debug_info.and_then(|info| info.location(node_id, ip));
+
+            if let (Some(loc), true) = (location, location != last_location) {
+                let padding = COMMENT_COLUMN.saturating_sub(line.len()).max(1);
+
+                write!(line, "{}; {}", " ".repeat(padding), loc).unwrap();
+            }
+
+            last_location = location;
+
+            self.out.push_str(&line);
+            self.out.push('\n');
+        }
+
+        for edge in node.outgoing.iter() {
+            let jmp_type = match edge.jmp_type {
+                CfgJumpType::Always => "always",
+                CfgJumpType::WhenTrue => "when_true",
+                CfgJumpType::Fallback => "fallback",
+            };
+
+            write!(self.out, "    -> @{} {}", edge.node_id, jmp_type).unwrap();
+
+            if let Some(cond) = graph.edge_cond(node_id, edge) {
+                write!(self.out, " ({})", cond.text).unwrap();
+            }
+
+            self.out.push('\n');
+        }
+    }
+
+    fn inst(&self, inst: &CfgInstruction) -> String {
+        match inst {
+            CfgInstruction::Command(cmd) => format!("command {:?}", cmd),
+            CfgInstruction::Direction(direct) => format!("direction {:?}", direct),
+            CfgInstruction::Load(var_id) => format!("load {}", self.name(*var_id)),
+            CfgInstruction::Store(var_id) => format!("store {}", self.name(*var_id)),
+            CfgInstruction::Dup => "dup".to_string(),
+            CfgInstruction::Pop => "pop".to_string(),
+            CfgInstruction::Call(node_id) => match self.cfg.jmp_table.get(node_id) {
+                Some(proc_id) => format!("call @{} ({})", node_id, self.name(*proc_id)),
+                None => format!("call @{}", node_id),
+            },
+            CfgInstruction::NativeCall(proc_id) => format!("native_call {}", self.name(*proc_id)),
+            CfgInstruction::Primitive(prim) => format!("primitive {}", prim.name()),
+            CfgInstruction::Bool(v) => format!("bool {}", v),
+            CfgInstruction::Int(v) => format!("int {}", v),
+            CfgInstruction::Str(s) => format!("str {:?}", s),
+            CfgInstruction::Return => "return".to_string(),
+            CfgInstruction::Trap => "trap".to_string(),
+            CfgInstruction::Print => "print".to_string(),
+            CfgInstruction::Label => "label".to_string(),
+            CfgInstruction::SetLabelFont => "set_label_font".to_string(),
+            CfgInstruction::SetLabelSize => "set_label_size".to_string(),
+            CfgInstruction::SetShape => "set_shape".to_string(),
+            CfgInstruction::SetPenColor => "set_pen_color".to_string(),
+            CfgInstruction::Tone => "tone".to_string(),
+            CfgInstruction::OpenWrite => "open_write".to_string(),
+            CfgInstruction::SetWrite => "set_write".to_string(),
+            CfgInstruction::Close => "close".to_string(),
+            CfgInstruction::EOC => "eoc".to_string(),
+            CfgInstruction::Add => "add".to_string(),
+            CfgInstruction::Mul => "mul".to_string(),
+            CfgInstruction::Div => "div".to_string(),
+            CfgInstruction::Not => "not".to_string(),
+            CfgInstruction::And => "and".to_string(),
+            CfgInstruction::Or => "or".to_string(),
+            CfgInstruction::GreaterThan => "greater_than".to_string(),
+            CfgInstruction::LessThan => "less_than".to_string(),
+            CfgInstruction::Equal => "equal".to_string(),
+            CfgInstruction::NotEqual => "not_equal".to_string(),
+            CfgInstruction::MakeList(n) => format!("make_list {}", n),
+            CfgInstruction::ListLen => "list_len".to_string(),
+            CfgInstruction::ListItem => "list_item".to_string(),
+            CfgInstruction::ListPush => "list_push".to_string(),
+            CfgInstruction::ProcRef(proc_id) => format!("proc_ref {}", self.name(*proc_id)),
+            CfgInstruction::Apply(n) => format!("apply {}", n),
+            CfgInstruction::Block(proc_id) => format!("block {}", self.name(*proc_id)),
+            CfgInstruction::Run => "run".to_string(),
+            CfgInstruction::Catch(tag, node_id) => format!("catch {:?} @{}", tag, node_id),
+            CfgInstruction::EndCatch => "end_catch".to_string(),
+            CfgInstruction::Throw(tag) => format!("throw {:?}", tag),
+            CfgInstruction::Trace(proc_id) => format!("trace {}", self.name(*proc_id)),
+            CfgInstruction::Untrace(proc_id) => format!("untrace {}", self.name(*proc_id)),
+            CfgInstruction::Assert {
+                message,
+                line,
+                column,
+            } => format!("assert {:?} {}:{}", message, line, column),
+        }
+    }
+
+    // the symbol name, or its id when there is no `DebugInfo`
+    fn name(&self, symbol_id: SymbolId) -> String {
+        match self.debug_info.and_then(|info| info.symbol_name(symbol_id)) {
+            Some(name) => name.to_string(),
+            None => symbol_id.to_string(),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::compat::prelude::*;
+use core::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IrError {
+    InvalidJson(String),
+    UnsupportedVersion(u32),
+    // native procedures are Rust closures, hence they can't be exported
+    NativeProc(String),
+    // a local variable of procedure `proc` read before being assigned (see `DefiniteAssignment`)
+    UnassignedLocal { proc: String, var: String },
+    // a malformed line of a textual IR listing (see `CfgObject::assemble`), the lines start from 1
+    InvalidListing { line: usize, msg: String },
+}
+
+impl fmt::Display for IrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IrError::InvalidJson(msg) => write!(f, "Invalid JSON IR: {}", msg),
+            IrError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported JSON IR version: {}", version)
+            }
+            IrError::NativeProc(proc) => {
+                write!(f, "Native procedure `{}` can't be exported", proc)
+            }
+            IrError::UnassignedLocal { proc, var } => write!(
+                f,
+                "Local variable `{}` may be used before being assigned (procedure `{}`)",
+                var, proc
+            ),
+            IrError::InvalidListing { line, msg } => {
+                write!(f, "Invalid IR listing (line {}): {}", line, msg)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn ir_error_unsupported_version() {
+        assert_eq!(
+            "Unsupported JSON IR version: 7",
+            IrError::UnsupportedVersion(7).to_string()
+        );
+    }
+
+    #[test]
+    pub fn ir_error_native_proc() {
+        assert_eq!(
+            "Native procedure `DISTANCE` can't be exported",
+            IrError::NativeProc("DISTANCE".to_string()).to_string()
+        );
+    }
+
+    #[test]
+    pub fn ir_error_unassigned_local() {
+        let err = IrError::UnassignedLocal {
+            proc: "MYPROC".to_string(),
+            var: "A".to_string(),
+        };
+
+        assert_eq!(
+            "Local variable `A` may be used before being assigned (procedure `MYPROC`)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    pub fn ir_error_invalid_listing() {
+        let err = IrError::InvalidListing {
+            line: 3,
+            msg: "unknown instruction `jump`".to_string(),
+        };
+
+        assert_eq!(
+            "Invalid IR listing (line 3): unknown instruction `jump`",
+            err.to_string()
+        );
+    }
+}
@@ -0,0 +1,489 @@
+use crate::ast::expression::ExpressionType;
+use crate::ast::semantic::{Environment, Procedure, Symbol, SymbolId, Variable};
+use crate::ast::statement::Event;
+use crate::compat::collections::{BTreeMap, HashMap};
+use crate::compat::prelude::*;
+use crate::ir::{
+    CfgCond, CfgGraph, CfgInstruction, CfgJumpType, CfgNode, CfgNodeId, CfgObject,
+    DefiniteAssignment, IrError,
+};
+use serde::{Deserialize, Serialize};
+
+// the portable JSON representation of a compiled program (`CfgObject` + its symbols metadata).
+//
+// * `nodes` - the CFG nodes, sorted by `id`. each node has its instructions and its outgoing edges.
+//   an instruction is encoded as `{ "op": <name> }` or `{ "op": <name>, "arg": <operand> }`
+//   (e.g `{ "op": "int", "arg": 10 }`, `{ "op": "load", "arg": 3 }`, `{ "op": "return" }`).
+//   literal constants are embedded as the `arg` of the `int` / `bool` / `str` instructions.
+//   an edge `jmp_type` is one of `always`, `when_true` or `fallback`
+//   a node with conditional edges has the `cond` it branches on (e.g `"cond": "X > 10"`)
+// * `procs` - the procedures. `node` is the CFG node of the procedure entry
+//   and `locals` are its locals (params first) ordered by their stack-frame index
+// * `vars` - all the variables (globals and locals).
+//   for globals `index` is the global address, for locals it's the stack-frame index
+// * `entry` - the CFG node of the `__main__` procedure, where the execution starts
+// * `handlers` - the event handlers procedures (see `OnEventStmt`).
+//   e.g `{ "event": "key", "key": "LEFT", "proc": 7 }`
+//   or `{ "event": "tick", "interval": 100, "proc": 8 }`
+//
+// the schema is versioned by `version`. adding fields is backward compatible, anything else bumps it
+pub const IR_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IrProgram {
+    pub version: u32,
+    pub entry: CfgNodeId,
+    pub nodes: Vec<IrNode>,
+    pub procs: Vec<IrProc>,
+    pub vars: Vec<IrVar>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub handlers: Vec<IrHandler>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IrNode {
+    pub id: CfgNodeId,
+    pub insts: Vec<CfgInstruction>,
+    pub edges: Vec<IrEdge>,
+
+    // what the node branches on (see `CfgCond`), e.g `"X > 10"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cond: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IrEdge {
+    pub dst: CfgNodeId,
+    pub jmp_type: CfgJumpType,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IrProc {
+    pub id: SymbolId,
+    pub name: String,
+    pub node: Option<CfgNodeId>,
+    pub params: Vec<ExpressionType>,
+    pub return_type: ExpressionType,
+    pub locals: Vec<SymbolId>,
+
+    // whether the last param is a rest param (see `Procedure::rest`)
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    pub rest: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IrVar {
+    pub id: SymbolId,
+    pub name: String,
+    pub global: bool,
+    pub param: bool,
+    pub var_type: Option<ExpressionType>,
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum IrHandler {
+    Key { key: String, proc: SymbolId },
+    Tick { interval: usize, proc: SymbolId },
+}
+
+impl IrHandler {
+    pub fn event(&self) -> Event {
+        match self {
+            IrHandler::Key { key, .. } => Event::Key(key.to_owned()),
+            IrHandler::Tick { interval, .. } => Event::Tick(*interval),
+        }
+    }
+
+    pub fn proc_id(&self) -> SymbolId {
+        match self {
+            IrHandler::Key { proc, .. } | IrHandler::Tick { proc, .. } => *proc,
+        }
+    }
+}
+
+impl IrProgram {
+    pub fn new(cfg: &CfgObject, env: &Environment) -> Result<Self, IrError> {
+        let mut nodes = cfg
+            .graph
+            .nodes
+            .values()
+            .map(|node| {
+                let mut edges = node
+                    .outgoing
+                    .iter()
+                    .map(|edge| IrEdge {
+                        dst: edge.node_id,
+                        jmp_type: edge.jmp_type,
+                    })
+                    .collect::<Vec<IrEdge>>();
+
+                edges.sort_by_key(|edge| (edge.dst, edge.jmp_type));
+
+                IrNode {
+                    id: node.id,
+                    insts: node.insts.clone(),
+                    edges,
+                    cond: node.cond.as_ref().map(|cond| cond.text.clone()),
+                }
+            })
+            .collect::<Vec<IrNode>>();
+
+        nodes.sort_by_key(|node| node.id);
+
+        let procs_nodes = cfg
+            .jmp_table
+            .iter()
+            .map(|(node_id, proc_id)| (*proc_id, *node_id))
+            .collect::<HashMap<SymbolId, CfgNodeId>>();
+
+        let mut procs = Vec::new();
+        let mut vars = Vec::new();
+
+        for symbol in env.symbol_table.symbols() {
+            match symbol {
+                // primitives are fully described by their `primitive` instructions
+                Symbol::Proc(proc) if proc.primitive.is_some() => continue,
+                Symbol::Proc(proc) => {
+                    if proc.native {
+                        return Err(IrError::NativeProc(proc.name.to_owned()));
+                    }
+
+                    procs.push(IrProc {
+                        id: proc.id,
+                        name: proc.name.to_owned(),
+                        node: procs_nodes.get(&proc.id).copied(),
+                        params: proc.params_types.clone(),
+                        return_type: proc.return_type.clone(),
+                        locals: env
+                            .locals_symbols
+                            .get(&proc.id)
+                            .cloned()
+                            .unwrap_or_default(),
+                        rest: proc.rest,
+                    })
+                }
+                Symbol::Var(var) => vars.push(IrVar {
+                    id: var.id,
+                    name: var.name.to_owned(),
+                    global: var.global,
+                    param: var.param,
+                    var_type: var.var_type.clone(),
+                    index: var.index.unwrap(),
+                }),
+            }
+        }
+
+        procs.sort_by_key(|proc| proc.id.0);
+        vars.sort_by_key(|var| var.id.0);
+
+        let mut keys = env.key_handlers.iter().collect::<Vec<_>>();
+        keys.sort_by_key(|(key, _)| *key);
+
+        let mut handlers = keys
+            .into_iter()
+            .map(|(key, proc_id)| IrHandler::Key {
+                key: key.to_owned(),
+                proc: *proc_id,
+            })
+            .collect::<Vec<IrHandler>>();
+
+        if let Some((interval, proc_id)) = env.tick_handler {
+            handlers.push(IrHandler::Tick {
+                interval,
+                proc: proc_id,
+            });
+        }
+
+        let program = Self {
+            version: IR_VERSION,
+            entry: cfg.graph.get_entry_node_id(),
+            nodes,
+            procs,
+            vars,
+            handlers,
+        };
+
+        Ok(program)
+    }
+
+    pub fn into_parts(self) -> Result<(CfgObject, Environment), IrError> {
+        if self.version != IR_VERSION {
+            return Err(IrError::UnsupportedVersion(self.version));
+        }
+
+        // the procedures frames layouts (see `CfgProc`) require the locals types
+        if let Some(var) = self.vars.iter().find(|var| !var.global && var.var_type.is_none()) {
+            let msg = format!("missing type for local variable `{}`", var.name);
+            return Err(IrError::InvalidJson(msg));
+        }
+
+        // a handler is executed from its procedure entry node
+        for handler in &self.handlers {
+            let proc_id = handler.proc_id();
+
+            if !self.procs.iter().any(|proc| proc.id == proc_id && proc.node.is_some()) {
+                let msg = format!("missing event handler procedure `{}`", proc_id.0);
+                return Err(IrError::InvalidJson(msg));
+            }
+        }
+
+        self.validate_refs()?;
+
+        let env = self.build_env();
+
+        let mut graph = CfgGraph::new();
+
+        for ir_node in &self.nodes {
+            let mut node = CfgNode::new(ir_node.id);
+            node.insts = ir_node.insts.clone();
+            node.cond = ir_node.cond.as_deref().map(CfgCond::of_text);
+
+            graph.add_node(node);
+        }
+
+        for ir_node in &self.nodes {
+            for edge in &ir_node.edges {
+                graph.add_edge(ir_node.id, edge.dst, edge.jmp_type);
+            }
+        }
+
+        let jmp_table = self
+            .procs
+            .iter()
+            .filter_map(|proc| proc.node.map(|node_id| (node_id, proc.id)))
+            .collect::<BTreeMap<CfgNodeId, SymbolId>>();
+
+        let cfg = CfgObject::new(graph, jmp_table, &env);
+
+        // unlike compiled programs, an imported program isn't guaranteed to assign its locals before using them
+        if let Some(unassigned) = DefiniteAssignment::analyze_program(&cfg, &env).first() {
+            return Err(IrError::UnassignedLocal {
+                proc: env.symbol_table.get_proc_by_id(unassigned.proc_id).name.clone(),
+                var: env.symbol_table.get_var_by_id(unassigned.var_id).name.clone(),
+            });
+        }
+
+        Ok((cfg, env))
+    }
+
+    // the nodes and symbols referred to by the program must exist,
+    // otherwise building (or executing) it would panic
+    fn validate_refs(&self) -> Result<(), IrError> {
+        let mut nodes = HashMap::new();
+
+        for node in &self.nodes {
+            if nodes.insert(node.id, node).is_some() {
+                let msg = format!("duplicate node `{}`", node.id);
+                return Err(IrError::InvalidJson(msg));
+            }
+        }
+
+        for node in &self.nodes {
+            if let Some(edge) = node.edges.iter().find(|edge| !nodes.contains_key(&edge.dst)) {
+                let msg = format!("missing node `{}` (an edge of node `{}`)", edge.dst, node.id);
+                return Err(IrError::InvalidJson(msg));
+            }
+        }
+
+        let vars = self
+            .vars
+            .iter()
+            .map(|var| (var.id, var))
+            .collect::<HashMap<SymbolId, &IrVar>>();
+
+        let procs = self
+            .procs
+            .iter()
+            .map(|proc| (proc.id, proc))
+            .collect::<HashMap<SymbolId, &IrProc>>();
+
+        // the procedures by their entry node
+        let mut entries = BTreeMap::new();
+
+        for proc in &self.procs {
+            if let Some(node_id) = proc.node {
+                if !nodes.contains_key(&node_id) {
+                    let msg = format!("missing node `{}` of procedure `{}`", node_id, proc.name);
+                    return Err(IrError::InvalidJson(msg));
+                }
+
+                if entries.insert(node_id, proc).is_some() {
+                    let msg = format!("node `{}` is the entry of a few procedures", node_id);
+                    return Err(IrError::InvalidJson(msg));
+                }
+            }
+
+            for var_id in &proc.locals {
+                if !matches!(vars.get(var_id), Some(var) if !var.global) {
+                    let msg = format!("missing local variable `{}` of `{}`", var_id.0, proc.name);
+                    return Err(IrError::InvalidJson(msg));
+                }
+            }
+        }
+
+        // the execution starts at the `__main__` entry (see `CfgGraph::get_entry_node_id`)
+        let main = entries.get(&self.entry).map(|proc| proc.name.as_str());
+
+        if self.entry != CfgGraph::default_entry_node_id() || main != Some("__main__") {
+            let msg = format!("invalid entry node `{}`", self.entry);
+            return Err(IrError::InvalidJson(msg));
+        }
+
+        // the procedure owning each node reachable from a procedure entry
+        let mut owners = HashMap::new();
+
+        for (entry_id, proc) in entries.iter() {
+            let mut pending = vec![*entry_id];
+
+            while let Some(node_id) = pending.pop() {
+                if owners.insert(node_id, *proc).is_some() {
+                    continue;
+                }
+
+                let node = nodes[&node_id];
+                pending.extend(node.edges.iter().map(|edge| edge.dst));
+
+                // a `THROW` resumes at the `CATCH` node, which isn't always reachable by an edge
+                pending.extend(node.insts.iter().filter_map(|inst| match inst {
+                    CfgInstruction::Catch(_, resume_id) if nodes.contains_key(resume_id) => {
+                        Some(*resume_id)
+                    }
+                    _ => None,
+                }));
+            }
+        }
+
+        for node in &self.nodes {
+            let owner = owners.get(&node.id);
+
+            for inst in &node.insts {
+                let error = match inst {
+                    CfgInstruction::Load(var_id) | CfgInstruction::Store(var_id) => {
+                        match vars.get(var_id) {
+                            None => Some(format!("missing variable `{}`", var_id.0)),
+                            Some(var) if var.global => None,
+                            // a local lives in the stack-frame of its own procedure
+                            Some(var) => owner
+                                .filter(|proc| !proc.locals.contains(var_id))
+                                .map(|proc| {
+                                    format!("`{}` isn't a local of `{}`", var.name, proc.name)
+                                }),
+                        }
+                    }
+                    CfgInstruction::Call(entry_id) if !entries.contains_key(entry_id) => {
+                        Some(format!("missing procedure entry node `{}`", entry_id))
+                    }
+                    CfgInstruction::Catch(_, resume_id) if !nodes.contains_key(resume_id) => {
+                        Some(format!("missing node `{}`", resume_id))
+                    }
+                    // `PROC` references and blocks are called via the procedure entry node
+                    CfgInstruction::ProcRef(proc_id) | CfgInstruction::Block(proc_id) => {
+                        match procs.get(proc_id) {
+                            Some(proc) if proc.node.is_some() => None,
+                            _ => Some(format!("missing procedure `{}`", proc_id.0)),
+                        }
+                    }
+                    CfgInstruction::Trace(proc_id) | CfgInstruction::Untrace(proc_id)
+                        if !procs.contains_key(proc_id) =>
+                    {
+                        Some(format!("missing procedure `{}`", proc_id.0))
+                    }
+                    CfgInstruction::NativeCall(proc_id) => {
+                        Some(format!("unsupported native procedure call `{}`", proc_id.0))
+                    }
+                    _ => None,
+                };
+
+                if let Some(msg) = error {
+                    let msg = format!("{} (in node `{}`)", msg, node.id);
+                    return Err(IrError::InvalidJson(msg));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn build_env(&self) -> Environment {
+        let mut env = Environment::new();
+
+        // procedures and globals are registered under the root scope, so they can be looked up by name
+        for proc in &self.procs {
+            env.symbol_table.create_proc_symbol(Procedure {
+                id: proc.id,
+                name: proc.name.to_owned(),
+                params_types: proc.params.clone(),
+                params_names: Vec::new(),
+                return_type: proc.return_type.clone(),
+                builtin: false,
+                native: false,
+                primitive: None,
+                doc: None,
+                rest: proc.rest,
+            });
+
+            env.locals_symbols.insert(proc.id, proc.locals.clone());
+        }
+
+        let mut globals = self
+            .vars
+            .iter()
+            .filter(|var| var.global)
+            .collect::<Vec<_>>();
+        globals.sort_by_key(|var| var.index);
+
+        for var in globals {
+            env.create_global_var(var.id, &var.name, var.var_type.clone());
+        }
+
+        for var in self.vars.iter().filter(|var| !var.global) {
+            let local = Variable {
+                id: var.id,
+                global: false,
+                param: var.param,
+                name: var.name.to_owned(),
+                var_type: var.var_type.clone(),
+                index: Some(var.index),
+                constant: false,
+                const_value: None,
+            };
+
+            env.symbol_table
+                .store_unscoped_symbol(var.id, Symbol::Var(local));
+        }
+
+        for handler in &self.handlers {
+            env.register_handler(&handler.event(), handler.proc_id());
+        }
+
+        let max_id = self
+            .procs
+            .iter()
+            .map(|proc| proc.id.0)
+            .chain(self.vars.iter().map(|var| var.id.0))
+            .max()
+            .unwrap_or(0);
+
+        env.id_generator.next_id = max_id + 1;
+
+        env
+    }
+}
+
+impl CfgObject {
+    pub fn to_json(&self, env: &Environment) -> Result<String, IrError> {
+        let program = IrProgram::new(self, env)?;
+
+        serde_json::to_string_pretty(&program).map_err(|e| IrError::InvalidJson(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<(CfgObject, Environment), IrError> {
+        let program: IrProgram =
+            serde_json::from_str(json).map_err(|e| IrError::InvalidJson(e.to_string()))?;
+
+        program.into_parts()
+    }
+}
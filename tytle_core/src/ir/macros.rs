@@ -78,6 +78,20 @@ macro_rules! lt_ins {
     }};
 }
 
+#[macro_export]
+macro_rules! eq_ins {
+    () => {{
+        $crate::ir::CfgInstruction::Equal
+    }};
+}
+
+#[macro_export]
+macro_rules! ne_ins {
+    () => {{
+        $crate::ir::CfgInstruction::NotEqual
+    }};
+}
+
 #[macro_export]
 macro_rules! store_ins {
     ($symbol_id:expr) => {{
@@ -94,6 +108,20 @@ macro_rules! load_ins {
     }};
 }
 
+#[macro_export]
+macro_rules! dup_ins {
+    () => {{
+        $crate::ir::CfgInstruction::Dup
+    }};
+}
+
+#[macro_export]
+macro_rules! pop_ins {
+    () => {{
+        $crate::ir::CfgInstruction::Pop
+    }};
+}
+
 #[macro_export]
 macro_rules! cmd_ins {
     ($cmd:ident) => {{
@@ -125,6 +153,16 @@ macro_rules! call_ins {
     }};
 }
 
+#[macro_export]
+macro_rules! native_call_ins {
+    ($proc_id:expr) => {{
+        use $crate::ast::semantic::SymbolId;
+        use $crate::ir::CfgInstruction;
+
+        CfgInstruction::NativeCall(SymbolId($proc_id))
+    }};
+}
+
 #[macro_export]
 macro_rules! ret_ins {
     () => {{
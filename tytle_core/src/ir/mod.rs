@@ -1,14 +1,36 @@
+pub(crate) mod analysis;
+mod assembler;
 mod cfg_builder;
+mod cfg_cond;
 mod cfg_edge;
 mod cfg_graph;
 mod cfg_instruction;
 mod cfg_node;
 mod cfg_object;
-pub mod macros;
+mod cfg_visitor;
+mod debug_info;
+mod disassembler;
+mod ir_error;
+mod json_ir;
+mod macros;
+pub(crate) mod opt;
+pub mod ssa;
 
+pub use analysis::{
+    DefiniteAssignment, DominatorTree, LoopInfo, NaturalLoop, StateBalance, UnassignedUse,
+};
 pub use cfg_builder::CfgBuilder;
+pub use cfg_cond::CfgCond;
 pub use cfg_edge::CfgEdge;
 pub use cfg_graph::*;
 pub use cfg_instruction::CfgInstruction;
 pub use cfg_node::CfgNode;
-pub use cfg_object::CfgObject;
+pub use cfg_object::{CfgObject, CfgProc};
+pub use cfg_visitor::CfgVisitor;
+pub use debug_info::{DebugInfo, ProcDebugInfo};
+pub use ir_error::IrError;
+pub use json_ir::{IrEdge, IrNode, IrProc, IrProgram, IrVar, IR_VERSION};
+pub use opt::{
+    AddZero, BlockMerge, CommonSubexprElim, CopyPropagation, DoubleNegation, MulByOne, OptLevel,
+    Peephole, PeepholeRule, ProcInliner, PushPopElim, DEFAULT_INLINE_THRESHOLD,
+};
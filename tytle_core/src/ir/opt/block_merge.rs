@@ -0,0 +1,82 @@
+use super::Peephole;
+use crate::compat::collections::HashSet;
+use crate::compat::prelude::*;
+use crate::ir::{CfgGraph, CfgJumpType, CfgNodeId, CfgObject};
+
+// Straight-line blocks merging.
+//
+// chains of tiny nodes hurt the interpreter locality (e.g the nodes left by `CfgGraph::split_node`
+// when inserting breakpoints, or the empty nodes jumping elsewhere), so the pass:
+// * threads the unconditional jump chains (see `Peephole`), and removes the empty nodes
+//   no longer jumped to
+// * merges each node always jumping to a successor which has no other predecessor
+//   (see `CfgGraph::merge_with_successor`)
+//
+// the procedures entries are never merged into their predecessors (they're entered by calls too)
+pub struct BlockMerge;
+
+impl BlockMerge {
+    pub fn new() -> Self {
+        Self
+    }
+
+    // returns the number of applied rewrites (threaded jumps and merged nodes)
+    pub fn optimize(&self, cfg: &mut CfgObject) -> usize {
+        let entries = cfg.jmp_table.keys().copied().collect::<HashSet<_>>();
+
+        let threaded = Peephole::thread_jumps(&mut cfg.graph);
+        Self::remove_dead_jumps(&mut cfg.graph, &entries);
+
+        threaded + Self::merge_blocks(&mut cfg.graph, &entries)
+    }
+
+    // removes the empty nodes having no incoming edges which only jump elsewhere
+    fn remove_dead_jumps(graph: &mut CfgGraph, entries: &HashSet<CfgNodeId>) {
+        let node_ids = graph.nodes.keys().copied().collect::<Vec<_>>();
+
+        for node_id in node_ids {
+            if entries.contains(&node_id) || !graph.get_node(node_id).incoming.is_empty() {
+                continue;
+            }
+
+            if let Some(dst_id) = Peephole::always_jmp(graph, node_id) {
+                graph.remove_edge(node_id, dst_id, CfgJumpType::Always);
+                graph.nodes.remove(&node_id);
+            }
+        }
+    }
+
+    fn merge_blocks(graph: &mut CfgGraph, entries: &HashSet<CfgNodeId>) -> usize {
+        let node_ids = graph.nodes.keys().copied().collect::<Vec<_>>();
+        let mut merged = 0;
+
+        for node_id in node_ids {
+            // the node has been merged into a preceding one
+            if !graph.nodes.contains_key(&node_id) {
+                continue;
+            }
+
+            loop {
+                let succ_ids = graph.successors(node_id);
+
+                if succ_ids.len() != 1 || entries.contains(&succ_ids[0]) {
+                    break;
+                }
+
+                if !graph.merge_with_successor(node_id) {
+                    break;
+                }
+
+                merged += 1;
+            }
+        }
+
+        merged
+    }
+}
+
+impl Default for BlockMerge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,190 @@
+use crate::ast::semantic::SymbolId;
+use crate::compat::collections::{HashMap, HashSet};
+use crate::compat::prelude::*;
+use crate::ir::{CfgInstruction, CfgObject};
+
+// Copy Propagation & Store-Load Forwarding within each `CfgNode` (a straight-line instructions block).
+//
+// the pass repeatedly applies the following rewrites until none applies:
+// * copy propagation: after `<leaf> Store(X)` (a leaf being `Int`, `Bool` or `Load(Y)`),
+//   later `Load(X)` are replaced with `<leaf>` as long as neither `X` nor `Y` have been modified
+// * store-load forwarding: `Store(X) Load(X)` is replaced with `Dup Store(X)`,
+//   so the stored value is reused instead of being read back from memory
+// * dead stores elimination: a `Store(X)` overwritten by a later `Store(X)` of the same block
+//   with no read of `X` in between is removed, together with the pure expression computing its value
+//
+// procedure calls, block runs and traps may read or modify any variable,
+// so nothing is carried across them.
+// a store followed by an instruction which may throw (see `CfgInstruction::may_throw`) is never dead,
+// since its value may be read once the execution is resumed after a `CATCH`.
+// since eliminated stores are never executed, watchpoints set on their variables won't fire for them
+pub struct CopyPropagation;
+
+impl CopyPropagation {
+    pub fn new() -> Self {
+        Self
+    }
+
+    // returns the number of applied rewrites
+    pub fn optimize(&self, cfg: &mut CfgObject) -> usize {
+        let mut node_ids = super::nodes_procs(cfg).keys().copied().collect::<Vec<_>>();
+        node_ids.sort();
+
+        let mut rewrites = 0;
+
+        for node_id in node_ids {
+            let node = cfg.graph.get_node_mut(node_id);
+
+            rewrites += self.optimize_insts(&mut node.insts);
+        }
+
+        rewrites
+    }
+
+    fn optimize_insts(&self, insts: &mut Vec<CfgInstruction>) -> usize {
+        let mut rewrites = 0;
+
+        loop {
+            let applied = Self::forward_copies(insts) + Self::eliminate_dead_stores(insts);
+
+            if applied == 0 {
+                return rewrites;
+            }
+
+            rewrites += applied;
+        }
+    }
+
+    fn forward_copies(insts: &mut [CfgInstruction]) -> usize {
+        // variable => the leaf instruction last stored into it
+        let mut copies: HashMap<SymbolId, CfgInstruction> = HashMap::new();
+        let mut rewrites = 0;
+
+        for i in 0..insts.len() {
+            match insts[i] {
+                CfgInstruction::Load(var_id) => {
+                    if let Some(leaf) = copies.get(&var_id) {
+                        insts[i] = leaf.clone();
+                        rewrites += 1;
+                    }
+                }
+                CfgInstruction::Store(var_id) => {
+                    copies
+                        .retain(|dst, src| *dst != var_id && *src != CfgInstruction::Load(var_id));
+
+                    let leaf = if i > 0 && insts[i - 1].is_pure_leaf() {
+                        Some(insts[i - 1].clone())
+                    } else {
+                        None
+                    };
+
+                    match leaf {
+                        Some(leaf) if leaf != CfgInstruction::Load(var_id) => {
+                            copies.insert(var_id, leaf);
+                        }
+                        Some(_) => {}
+                        None => {
+                            if insts.get(i + 1) == Some(&CfgInstruction::Load(var_id)) {
+                                insts[i] = CfgInstruction::Dup;
+                                insts[i + 1] = CfgInstruction::Store(var_id);
+                                rewrites += 1;
+                            }
+                        }
+                    }
+                }
+                ref inst if Self::is_barrier(inst) => copies.clear(),
+                _ => {}
+            }
+        }
+
+        rewrites
+    }
+
+    fn eliminate_dead_stores(insts: &mut Vec<CfgInstruction>) -> usize {
+        // variable => the index of its last `Store` not read so far
+        let mut pending: HashMap<SymbolId, usize> = HashMap::new();
+        let mut removed: HashSet<usize> = HashSet::new();
+        let mut rewrites = 0;
+
+        for (i, inst) in insts.iter().enumerate() {
+            match inst {
+                CfgInstruction::Load(var_id) => {
+                    pending.remove(var_id);
+                }
+                CfgInstruction::Store(var_id) => {
+                    if let Some(dead) = pending.insert(*var_id, i) {
+                        if let Some(start) = Self::value_start(insts, dead) {
+                            // the value range may contain loads, so we re-scan on the next iteration
+                            if (start..=dead).all(|j| !removed.contains(&j)) {
+                                removed.extend(start..=dead);
+                                rewrites += 1;
+                            }
+                        }
+                    }
+                }
+                inst if Self::is_barrier(inst) || inst.may_throw() => pending.clear(),
+                _ => {}
+            }
+        }
+
+        if rewrites > 0 {
+            let mut i = 0;
+
+            insts.retain(|_| {
+                let keep = !removed.contains(&i);
+                i += 1;
+                keep
+            });
+        }
+
+        rewrites
+    }
+
+    // returns the index where the computation of the value stored by `insts[store]` begins,
+    // when that value is computed by a pure expression (or duplicated by a `Dup`)
+    fn value_start(insts: &[CfgInstruction], store: usize) -> Option<usize> {
+        if store > 0 && insts[store - 1] == CfgInstruction::Dup {
+            return Some(store - 1);
+        }
+
+        let mut needed = 1;
+        let mut i = store;
+
+        while i > 0 {
+            i -= 1;
+
+            let inst = &insts[i];
+
+            if inst.is_pure_leaf() {
+                needed -= 1;
+            } else if inst.is_binary_op() {
+                needed += 1;
+            } else if !inst.is_unary_op() {
+                return None;
+            }
+
+            if needed == 0 {
+                return Some(i);
+            }
+        }
+
+        None
+    }
+
+    fn is_barrier(inst: &CfgInstruction) -> bool {
+        matches!(
+            inst,
+            CfgInstruction::Call(_)
+                | CfgInstruction::NativeCall(_)
+                | CfgInstruction::Apply(_)
+                | CfgInstruction::Run
+                | CfgInstruction::Trap
+        )
+    }
+}
+
+impl Default for CopyPropagation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
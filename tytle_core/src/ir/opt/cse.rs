@@ -0,0 +1,259 @@
+use crate::ast::expression::ExpressionType;
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
+use crate::ir::{CfgInstruction, CfgObject};
+
+// Common Subexpression Elimination within each `CfgNode` (a straight-line instructions block).
+//
+// a pure subexpression (e.g `X * Y`) evaluated more than once is computed only on its first occurrence,
+// stored into a new temporary variable, and each later occurrence is replaced with a load of the temporary.
+//
+// two occurrences are considered the same subexpression only when nothing in between
+// could have changed their value: a `Store` to a variable the subexpression loads,
+// or an instruction with unknown effects (procedure calls, traps, ...)
+pub struct CommonSubexprElim<'env> {
+    env: &'env mut Environment,
+}
+
+// an expression occurrence spanning the instructions `start..=end`
+#[derive(Debug, Clone, Copy)]
+struct Occurrence {
+    start: usize,
+    end: usize,
+}
+
+impl Occurrence {
+    fn overlaps(&self, other: &Occurrence) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+}
+
+// identifies an expression value: its instructions and the "version" of everything it depends on
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ExprKey {
+    insts: Vec<CfgInstruction>,
+    epoch: usize,
+    loads_versions: Vec<(usize, usize)>,
+}
+
+impl<'env> CommonSubexprElim<'env> {
+    pub fn new(env: &'env mut Environment) -> Self {
+        Self { env }
+    }
+
+    // returns the number of eliminated subexpression occurrences
+    pub fn optimize(&mut self, cfg: &mut CfgObject) -> usize {
+        let nodes_procs = super::nodes_procs(cfg);
+
+        let mut node_ids = nodes_procs.keys().copied().collect::<Vec<_>>();
+        node_ids.sort();
+
+        let mut eliminated = 0;
+
+        for node_id in node_ids {
+            let proc_id = nodes_procs[&node_id];
+            let node = cfg.graph.get_node_mut(node_id);
+
+            eliminated += self.optimize_insts(proc_id, &mut node.insts);
+        }
+
+        // the temporaries are new locals
+        cfg.update_procs(self.env);
+
+        eliminated
+    }
+
+    fn optimize_insts(&mut self, proc_id: SymbolId, insts: &mut Vec<CfgInstruction>) -> usize {
+        let groups = Self::find_common_exprs(insts);
+
+        let mut selected: Vec<Occurrence> = Vec::new();
+
+        // start index => (end index, replacement instructions)
+        let mut rewrites: HashMap<usize, (usize, Vec<CfgInstruction>)> = HashMap::new();
+        let mut eliminated = 0;
+
+        // longer expressions first, so that we eliminate the largest subexpressions possible
+        for (key, occurrences) in groups {
+            let occurrences = occurrences
+                .into_iter()
+                .filter(|occ| selected.iter().all(|sel| !sel.overlaps(occ)))
+                .collect::<Vec<Occurrence>>();
+
+            if occurrences.len() < 2 {
+                continue;
+            }
+
+            let (tmp_id, _) = self
+                .env
+                .create_tmp_var(proc_id, Self::expr_type(&key.insts));
+
+            let first = occurrences[0];
+
+            let mut compute = key.insts.clone();
+            compute.push(CfgInstruction::Store(tmp_id));
+            compute.push(CfgInstruction::Load(tmp_id));
+
+            rewrites.insert(first.start, (first.end, compute));
+
+            for occ in occurrences.iter().skip(1) {
+                rewrites.insert(occ.start, (occ.end, vec![CfgInstruction::Load(tmp_id)]));
+                eliminated += 1;
+            }
+
+            selected.extend(occurrences);
+        }
+
+        if eliminated == 0 {
+            return 0;
+        }
+
+        let mut optimized = Vec::with_capacity(insts.len());
+        let mut i = 0;
+
+        while i < insts.len() {
+            match rewrites.remove(&i) {
+                Some((end, replacement)) => {
+                    optimized.extend(replacement);
+                    i = end + 1;
+                }
+                None => {
+                    optimized.push(insts[i].clone());
+                    i += 1;
+                }
+            }
+        }
+
+        *insts = optimized;
+
+        eliminated
+    }
+
+    // simulates the operands stack in order to find the pure expressions and their instructions ranges.
+    // returns the expressions occurring more than once, ordered by length (longest first)
+    fn find_common_exprs(insts: &[CfgInstruction]) -> Vec<(ExprKey, Vec<Occurrence>)> {
+        // `None` stands for a stack value not computed by a pure expression of this block
+        let mut stack: Vec<Option<Occurrence>> = Vec::new();
+
+        let mut writes: HashMap<SymbolId, usize> = HashMap::new();
+        let mut epoch = 0;
+
+        let mut groups: Vec<(ExprKey, Vec<Occurrence>)> = Vec::new();
+        let mut groups_index: HashMap<ExprKey, usize> = HashMap::new();
+
+        for (i, inst) in insts.iter().enumerate() {
+            if inst.is_pure_leaf() {
+                stack.push(Some(Occurrence { start: i, end: i }));
+                continue;
+            }
+
+            if inst.is_pure_op() {
+                let operands = if inst.is_binary_op() { 2 } else { 1 };
+
+                let mut occ = Some(Occurrence { start: i, end: i });
+
+                for _ in 0..operands {
+                    let operand = stack.pop().flatten();
+
+                    // the operands must be contiguous and immediately precede the operator
+                    occ = match (occ, operand) {
+                        (Some(occ), Some(operand)) if operand.end + 1 == occ.start => {
+                            Some(Occurrence {
+                                start: operand.start,
+                                end: i,
+                            })
+                        }
+                        _ => None,
+                    };
+                }
+
+                if let Some(occ) = occ {
+                    let key = Self::expr_key(&insts[occ.start..=occ.end], epoch, &writes);
+
+                    match groups_index.get(&key) {
+                        Some(index) => groups[*index].1.push(occ),
+                        None => {
+                            groups_index.insert(key.clone(), groups.len());
+                            groups.push((key, vec![occ]));
+                        }
+                    }
+                }
+
+                stack.push(occ);
+                continue;
+            }
+
+            match inst {
+                CfgInstruction::Store(var_id) => {
+                    stack.pop();
+                    *writes.entry(*var_id).or_insert(0) += 1;
+                }
+                CfgInstruction::Direction(_)
+                | CfgInstruction::Pop
+                | CfgInstruction::Print
+                | CfgInstruction::Label
+                | CfgInstruction::SetLabelFont
+                | CfgInstruction::SetLabelSize
+                | CfgInstruction::OpenWrite
+                | CfgInstruction::SetWrite
+                | CfgInstruction::Close
+                | CfgInstruction::SetShape
+                | CfgInstruction::SetPenColor => {
+                    stack.pop();
+                }
+                CfgInstruction::Tone => {
+                    stack.pop();
+                    stack.pop();
+                }
+                _ => {
+                    // an instruction with unknown stack or memory effects
+                    stack.clear();
+                    epoch += 1;
+                }
+            }
+        }
+
+        let mut groups = groups
+            .into_iter()
+            .filter(|(_, occurrences)| occurrences.len() > 1)
+            .collect::<Vec<_>>();
+
+        groups.sort_by_key(|(key, occurrences)| {
+            (core::cmp::Reverse(key.insts.len()), occurrences[0].start)
+        });
+
+        groups
+    }
+
+    fn expr_key(
+        insts: &[CfgInstruction],
+        epoch: usize,
+        writes: &HashMap<SymbolId, usize>,
+    ) -> ExprKey {
+        let mut loads_versions = insts
+            .iter()
+            .filter_map(|inst| match inst {
+                CfgInstruction::Load(var_id) => {
+                    Some((var_id.0, writes.get(var_id).copied().unwrap_or(0)))
+                }
+                _ => None,
+            })
+            .collect::<Vec<(usize, usize)>>();
+
+        loads_versions.sort();
+        loads_versions.dedup();
+
+        ExprKey {
+            insts: insts.to_vec(),
+            epoch,
+            loads_versions,
+        }
+    }
+
+    fn expr_type(insts: &[CfgInstruction]) -> ExpressionType {
+        match insts.last().unwrap() {
+            CfgInstruction::Add | CfgInstruction::Mul | CfgInstruction::Div => ExpressionType::Int,
+            _ => ExpressionType::Bool,
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use crate::ast::expression::ExpressionType;
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::compat::collections::{HashMap, HashSet};
+use crate::compat::prelude::*;
+use crate::ir::{CfgInstruction, CfgNodeId, CfgObject};
+
+// the default maximum number of instructions of an inlined procedure body
+pub const DEFAULT_INLINE_THRESHOLD: usize = 16;
+
+// Procedures Inlining.
+//
+// calls to small procedures are replaced with the procedure body, saving the call overhead
+// (allocating a callstack frame, copying the args, returning).
+// a procedure is inlined when its body is a single straight-line `CfgNode` (no `IF`s or `REPEAT`s)
+// of at most `threshold` instructions, which leaves the operand stack balanced.
+//
+// the call args are stored into copies of the procedure params, allocated as new locals of the caller
+// (and so are the procedure locals), so the inlined variables never collide with the caller's own.
+// calls within inlined bodies aren't inlined themselves, so recursive procedures are expanded only once.
+//
+// inlined calls don't open a callstack frame, so they emit no `ProcEnter` / `ProcExit` VM events.
+// hence, procedures named by a `TRACE` aren't inlined (their calls are traced by their frames),
+// and neither are procedures having an `ASSERT` (its variables snapshot is of the failing frame)
+pub struct ProcInliner<'env> {
+    env: &'env mut Environment,
+    threshold: usize,
+
+    // (caller, inlined variable) => its copy within the caller
+    renames: HashMap<(SymbolId, SymbolId), SymbolId>,
+}
+
+// the body of an inlinable procedure
+#[derive(Debug, Clone)]
+struct InlineBody {
+    proc_id: SymbolId,
+    insts: Vec<CfgInstruction>,
+}
+
+impl<'env> ProcInliner<'env> {
+    pub fn new(env: &'env mut Environment) -> Self {
+        Self {
+            env,
+            threshold: DEFAULT_INLINE_THRESHOLD,
+            renames: HashMap::new(),
+        }
+    }
+
+    // the maximum number of instructions of an inlined procedure body
+    pub fn threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    // returns the number of inlined calls
+    pub fn optimize(&mut self, cfg: &mut CfgObject) -> usize {
+        let bodies = self.inlinable_bodies(cfg);
+
+        if bodies.is_empty() {
+            return 0;
+        }
+
+        let nodes_procs = super::nodes_procs(cfg);
+
+        let mut node_ids = nodes_procs.keys().copied().collect::<Vec<_>>();
+        node_ids.sort();
+
+        let mut inlined = 0;
+
+        for node_id in node_ids {
+            let caller_id = nodes_procs[&node_id];
+            let node = cfg.graph.get_node_mut(node_id);
+
+            let insts = core::mem::take(&mut node.insts);
+            let mut new_insts = Vec::with_capacity(insts.len());
+
+            for inst in insts {
+                match inst {
+                    CfgInstruction::Call(entry_id) if bodies.contains_key(&entry_id) => {
+                        let body = &bodies[&entry_id];
+
+                        new_insts.extend(self.inline_call(caller_id, body));
+                        inlined += 1;
+                    }
+                    _ => new_insts.push(inst),
+                }
+            }
+
+            cfg.graph.get_node_mut(node_id).insts = new_insts;
+        }
+
+        // the inlined variables copies are new locals
+        cfg.update_procs(self.env);
+
+        inlined
+    }
+
+    // the procedures bodies eligible for inlining, by their entry node
+    fn inlinable_bodies(&self, cfg: &CfgObject) -> HashMap<CfgNodeId, InlineBody> {
+        let mut bodies = HashMap::new();
+        let traced = Self::traced_procs(cfg);
+
+        for (entry_id, proc_id) in cfg.jmp_table.iter() {
+            let proc = self.env.symbol_table.get_proc_by_id(*proc_id);
+
+            // the prelude procedures turn by their own rules (see `Environment::is_prelude_proc`)
+            let prelude = self.env.is_prelude_proc(*proc_id);
+
+            if proc.name == "__main__" || prelude || traced.contains(proc_id) {
+                continue;
+            }
+
+            let node = cfg.graph.get_node(*entry_id);
+
+            if node.has_outgoing_edges() {
+                continue;
+            }
+
+            // instructions following the `RETURN` are never executed
+            let ret_index = node
+                .insts
+                .iter()
+                .position(|inst| *inst == CfgInstruction::Return);
+
+            let insts = match ret_index {
+                Some(ret_index) => node.insts[..ret_index].to_vec(),
+                None => continue,
+            };
+
+            let has_assert = insts
+                .iter()
+                .any(|inst| matches!(inst, CfgInstruction::Assert { .. }));
+
+            if has_assert {
+                continue;
+            }
+
+            if insts.len() > self.threshold || !self.is_balanced(cfg, &insts, &proc.return_type) {
+                continue;
+            }
+
+            let body = InlineBody {
+                proc_id: *proc_id,
+                insts,
+            };
+
+            bodies.insert(*entry_id, body);
+        }
+
+        bodies
+    }
+
+    // the procedures named by the `TRACE` statements of the program
+    fn traced_procs(cfg: &CfgObject) -> HashSet<SymbolId> {
+        cfg.graph
+            .nodes
+            .values()
+            .flat_map(|node| node.insts.iter())
+            .filter_map(|inst| match inst {
+                CfgInstruction::Trace(proc_id) => Some(*proc_id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // whether `insts` leave on the operand stack exactly the procedure return value (if any),
+    // without ever popping values they haven't pushed
+    fn is_balanced(
+        &self,
+        cfg: &CfgObject,
+        insts: &[CfgInstruction],
+        ret_type: &ExpressionType,
+    ) -> bool {
+        let mut depth: isize = 0;
+
+        for inst in insts {
+            let (pops, pushes) = match inst {
+                CfgInstruction::Int(_)
+                | CfgInstruction::Bool(_)
+                | CfgInstruction::Str(_)
+                | CfgInstruction::Load(_)
+                | CfgInstruction::ProcRef(_) => (0, 1),
+                CfgInstruction::Dup => (1, 2),
+                CfgInstruction::Store(_)
+                | CfgInstruction::Pop
+                | CfgInstruction::Print
+                | CfgInstruction::Label
+                | CfgInstruction::SetLabelFont
+                | CfgInstruction::SetLabelSize
+                | CfgInstruction::OpenWrite
+                | CfgInstruction::SetWrite
+                | CfgInstruction::Close
+                | CfgInstruction::SetShape
+                | CfgInstruction::SetPenColor
+                | CfgInstruction::Direction(_)
+                | CfgInstruction::Assert { .. } => (1, 0),
+                CfgInstruction::Tone => (2, 0),
+                CfgInstruction::Not | CfgInstruction::ListLen => (1, 1),
+                CfgInstruction::Add
+                | CfgInstruction::Mul
+                | CfgInstruction::Div
+                | CfgInstruction::And
+                | CfgInstruction::Or
+                | CfgInstruction::GreaterThan
+                | CfgInstruction::LessThan
+                | CfgInstruction::Equal
+                | CfgInstruction::NotEqual
+                | CfgInstruction::ListItem
+                | CfgInstruction::ListPush => (2, 1),
+                CfgInstruction::MakeList(n) => (*n as isize, 1),
+                CfgInstruction::Apply(n) => (*n as isize + 1, 0),
+                CfgInstruction::Run => (1, 0),
+                CfgInstruction::Command(_)
+                | CfgInstruction::Trap
+                | CfgInstruction::Throw(_)
+                | CfgInstruction::Trace(_)
+                | CfgInstruction::Untrace(_) => (0, 0),
+                CfgInstruction::Call(entry_id) => self.call_effect(cfg.jmp_table[entry_id]),
+                CfgInstruction::NativeCall(proc_id) => self.call_effect(*proc_id),
+                CfgInstruction::Primitive(prim) => {
+                    let pushes = match prim.return_type() {
+                        ExpressionType::Unit => 0,
+                        _ => 1,
+                    };
+
+                    (prim.params_types().len() as isize, pushes)
+                }
+                // a block made by the inlined procedure would use the variables of its caller frame
+                CfgInstruction::Return
+                | CfgInstruction::Block(_)
+                | CfgInstruction::EOC
+                | CfgInstruction::Catch(..)
+                | CfgInstruction::EndCatch => return false,
+            };
+
+            depth -= pops;
+
+            if depth < 0 {
+                return false;
+            }
+
+            depth += pushes;
+        }
+
+        match ret_type {
+            ExpressionType::Unit => depth == 0,
+            _ => depth == 1,
+        }
+    }
+
+    fn call_effect(&self, proc_id: SymbolId) -> (isize, isize) {
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+
+        let pushes = match proc.return_type {
+            ExpressionType::Unit => 0,
+            _ => 1,
+        };
+
+        (proc.params_types.len() as isize, pushes)
+    }
+
+    // the instructions replacing a call (from within `caller_id`) to the procedure of `body`
+    fn inline_call(&mut self, caller_id: SymbolId, body: &InlineBody) -> Vec<CfgInstruction> {
+        let mut insts = Vec::new();
+
+        // the call args have been pushed in order, so the last arg is at the top of the stack
+        let params = self.proc_params(body.proc_id);
+
+        for param_id in params.iter().rev() {
+            let var_id = self.rename_var(caller_id, body.proc_id, *param_id);
+            insts.push(CfgInstruction::Store(var_id));
+        }
+
+        for inst in body.insts.iter() {
+            let inst = match inst {
+                CfgInstruction::Load(var_id) if self.is_local(*var_id) => {
+                    CfgInstruction::Load(self.rename_var(caller_id, body.proc_id, *var_id))
+                }
+                CfgInstruction::Store(var_id) if self.is_local(*var_id) => {
+                    CfgInstruction::Store(self.rename_var(caller_id, body.proc_id, *var_id))
+                }
+                _ => inst.clone(),
+            };
+
+            insts.push(inst);
+        }
+
+        insts
+    }
+
+    // the procedure params, ordered by their index
+    fn proc_params(&self, proc_id: SymbolId) -> Vec<SymbolId> {
+        let mut params = self
+            .env
+            .locals_symbols
+            .get(&proc_id)
+            .into_iter()
+            .flatten()
+            .map(|var_id| self.env.symbol_table.get_var_by_id(*var_id))
+            .filter(|var| var.param)
+            .collect::<Vec<_>>();
+
+        params.sort_by_key(|var| var.index);
+
+        params.iter().map(|var| var.id).collect()
+    }
+
+    fn is_local(&self, var_id: SymbolId) -> bool {
+        !self.env.symbol_table.get_var_by_id(var_id).global
+    }
+
+    // the copy (within `caller_id`) of the inlined procedure variable `var_id`
+    fn rename_var(
+        &mut self,
+        caller_id: SymbolId,
+        callee_id: SymbolId,
+        var_id: SymbolId,
+    ) -> SymbolId {
+        if let Some(copy_id) = self.renames.get(&(caller_id, var_id)) {
+            return *copy_id;
+        }
+
+        let var = self.env.symbol_table.get_var_by_id(var_id);
+        let var_type = var.var_type.clone();
+        let var_name = var.name.trim_start_matches('$').to_string();
+
+        let proc_name = &self.env.symbol_table.get_proc_by_id(callee_id).name;
+
+        // the `$` prefix marks compiler allocated variables (see `Variable::is_tmp`),
+        // and the id suffix keeps the names unique when the procedure is inlined into a few callers
+        let copy_id = self.env.id_generator.get_next_id();
+        let copy_name = format!("${}.{}.{}", proc_name, var_name, copy_id.0);
+
+        self.env
+            .create_local_var(caller_id, copy_id, &copy_name, var_type, false);
+
+        self.renames.insert((caller_id, var_id), copy_id);
+
+        copy_id
+    }
+}
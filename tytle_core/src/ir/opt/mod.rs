@@ -0,0 +1,73 @@
+// optimization passes over a built `CfgObject`
+
+mod block_merge;
+mod copy_prop;
+mod cse;
+mod inline;
+mod opt_level;
+mod peephole;
+
+pub use block_merge::BlockMerge;
+pub use copy_prop::CopyPropagation;
+pub use cse::CommonSubexprElim;
+pub use inline::{ProcInliner, DEFAULT_INLINE_THRESHOLD};
+pub use opt_level::OptLevel;
+pub use peephole::{AddZero, DoubleNegation, MulByOne, Peephole, PeepholeRule, PushPopElim};
+
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::compat::collections::HashMap;
+use crate::ir::{CfgNodeId, CfgObject, CfgVisitor};
+
+// runs the passes of `opt_level` over `cfg`, returns the number of applied rewrites
+pub fn optimize(cfg: &mut CfgObject, env: &mut Environment, opt_level: OptLevel) -> usize {
+    enter_span!("optimize", ?opt_level);
+
+    let mut rewrites = 0;
+
+    if opt_level == OptLevel::Full {
+        rewrites += traced_pass("inline", ProcInliner::new(env).optimize(cfg));
+        rewrites += traced_pass("cse", CommonSubexprElim::new(env).optimize(cfg));
+        rewrites += traced_pass("copy_prop", CopyPropagation::new().optimize(cfg));
+    }
+
+    if opt_level != OptLevel::None {
+        rewrites += traced_pass("block_merge", BlockMerge::new().optimize(cfg));
+        rewrites += traced_pass("peephole", Peephole::new().optimize(cfg));
+    }
+
+    rewrites
+}
+
+// reports the number of rewrites a pass has applied
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+fn traced_pass(pass: &str, rewrites: usize) -> usize {
+    trace_event!(pass, rewrites);
+
+    rewrites
+}
+
+// maps each reachable CFG node to the procedure owning it
+pub(crate) fn nodes_procs(cfg: &CfgObject) -> HashMap<CfgNodeId, SymbolId> {
+    let mut visitor = NodesProcs {
+        proc_id: None,
+        nodes_procs: HashMap::new(),
+    };
+
+    visitor.visit_program(cfg);
+    visitor.nodes_procs
+}
+
+struct NodesProcs {
+    proc_id: Option<SymbolId>,
+    nodes_procs: HashMap<CfgNodeId, SymbolId>,
+}
+
+impl CfgVisitor for NodesProcs {
+    fn on_proc_start(&mut self, proc_id: SymbolId, _entry: CfgNodeId) {
+        self.proc_id = Some(proc_id);
+    }
+
+    fn on_node_start(&mut self, node_id: CfgNodeId) {
+        self.nodes_procs.insert(node_id, self.proc_id.unwrap());
+    }
+}
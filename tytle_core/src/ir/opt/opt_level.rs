@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+// the optimization passes applied to a freshly built `CfgObject`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptLevel {
+    // no optimizations
+    #[default]
+    None,
+
+    // peephole rules and jump threading
+    Basic,
+
+    // procedures inlining, common subexpression elimination, copy propagation and then the `Basic` passes
+    Full,
+}
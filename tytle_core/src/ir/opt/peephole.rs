@@ -0,0 +1,257 @@
+use crate::compat::collections::HashSet;
+use crate::compat::prelude::*;
+use crate::ir::{CfgGraph, CfgInstruction, CfgJumpType, CfgNodeId, CfgObject};
+
+// a rewrite rule over a short window of instructions.
+//
+// `rewrite` is given the instructions of a `CfgNode` starting at some index,
+// and returns the number of instructions it matched (from the start) along with their replacement.
+// a rule must preserve the program semantics, and should not grow the instructions stream.
+pub trait PeepholeRule {
+    fn name(&self) -> &str;
+
+    fn rewrite(&self, insts: &[CfgInstruction]) -> Option<(usize, Vec<CfgInstruction>)>;
+}
+
+// `Load(X) Store(X)`: a value pushed and immediately popped back into where it came from
+pub struct PushPopElim;
+
+impl PeepholeRule for PushPopElim {
+    fn name(&self) -> &str {
+        "push-pop"
+    }
+
+    fn rewrite(&self, insts: &[CfgInstruction]) -> Option<(usize, Vec<CfgInstruction>)> {
+        match insts {
+            [CfgInstruction::Load(src), CfgInstruction::Store(dst), ..] if src == dst => {
+                Some((2, vec![]))
+            }
+            _ => None,
+        }
+    }
+}
+
+// `<expr> 1 *` => `<expr>` and `1 <leaf> *` => `<leaf>`
+pub struct MulByOne;
+
+impl PeepholeRule for MulByOne {
+    fn name(&self) -> &str {
+        "mul-by-one"
+    }
+
+    fn rewrite(&self, insts: &[CfgInstruction]) -> Option<(usize, Vec<CfgInstruction>)> {
+        identity_operand(insts, 1, &CfgInstruction::Mul)
+    }
+}
+
+// `<expr> 0 +` => `<expr>` and `0 <leaf> +` => `<leaf>`
+pub struct AddZero;
+
+impl PeepholeRule for AddZero {
+    fn name(&self) -> &str {
+        "add-zero"
+    }
+
+    fn rewrite(&self, insts: &[CfgInstruction]) -> Option<(usize, Vec<CfgInstruction>)> {
+        identity_operand(insts, 0, &CfgInstruction::Add)
+    }
+}
+
+// `NOT NOT` => nothing
+pub struct DoubleNegation;
+
+impl PeepholeRule for DoubleNegation {
+    fn name(&self) -> &str {
+        "double-negation"
+    }
+
+    fn rewrite(&self, insts: &[CfgInstruction]) -> Option<(usize, Vec<CfgInstruction>)> {
+        match insts {
+            [CfgInstruction::Not, CfgInstruction::Not, ..] => Some((2, vec![])),
+            _ => None,
+        }
+    }
+}
+
+// matches a commutative binary operator `op` having `identity` as one of its operands
+fn identity_operand(
+    insts: &[CfgInstruction],
+    identity: isize,
+    op: &CfgInstruction,
+) -> Option<(usize, Vec<CfgInstruction>)> {
+    match insts {
+        [CfgInstruction::Int(v), inst, ..] if *v == identity && inst == op => Some((2, vec![])),
+        [CfgInstruction::Int(v), leaf, inst, ..]
+            if *v == identity && inst == op && leaf.is_pure_leaf() =>
+        {
+            Some((3, vec![leaf.clone()]))
+        }
+        _ => None,
+    }
+}
+
+// the maximum number of passes over a node's instructions,
+// guarding against custom rules rewriting each other's output forever
+const MAX_PASSES: usize = 16;
+
+// Peephole optimizer.
+//
+// applies its rules over the instructions of every `CfgNode` until none of them matches,
+// and then threads jumps: an edge targeting an empty node having a single `Always` edge
+// is redirected to that node's own target
+pub struct Peephole {
+    rules: Vec<Box<dyn PeepholeRule>>,
+}
+
+impl Peephole {
+    // a peephole optimizer with the built-in rules
+    pub fn new() -> Self {
+        let mut peephole = Self::empty();
+
+        peephole.add_rule(PushPopElim);
+        peephole.add_rule(MulByOne);
+        peephole.add_rule(AddZero);
+        peephole.add_rule(DoubleNegation);
+
+        peephole
+    }
+
+    // a peephole optimizer without any rules (jump threading only)
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    pub fn add_rule(&mut self, rule: impl PeepholeRule + 'static) {
+        self.rules.push(Box::new(rule));
+    }
+
+    pub fn rules_names(&self) -> Vec<&str> {
+        self.rules.iter().map(|rule| rule.name()).collect()
+    }
+
+    // returns the number of applied rewrites (including threaded jumps)
+    pub fn optimize(&self, cfg: &mut CfgObject) -> usize {
+        let mut node_ids = cfg.graph.nodes.keys().copied().collect::<Vec<_>>();
+        node_ids.sort();
+
+        let mut rewrites = 0;
+
+        for node_id in node_ids {
+            let node = cfg.graph.get_node_mut(node_id);
+
+            rewrites += self.optimize_insts(&mut node.insts);
+        }
+
+        rewrites + Self::thread_jumps(&mut cfg.graph)
+    }
+
+    fn optimize_insts(&self, insts: &mut Vec<CfgInstruction>) -> usize {
+        let mut rewrites = 0;
+
+        for _ in 0..MAX_PASSES {
+            let applied = self.apply_rules(insts);
+
+            if applied == 0 {
+                break;
+            }
+
+            rewrites += applied;
+        }
+
+        rewrites
+    }
+
+    fn apply_rules(&self, insts: &mut Vec<CfgInstruction>) -> usize {
+        let mut optimized = Vec::with_capacity(insts.len());
+        let mut rewrites = 0;
+        let mut i = 0;
+
+        while i < insts.len() {
+            let rewrite = self.rules.iter().find_map(|rule| rule.rewrite(&insts[i..]));
+
+            match rewrite {
+                Some((matched, replacement)) if matched > 0 => {
+                    optimized.extend(replacement);
+                    i += matched;
+                    rewrites += 1;
+                }
+                _ => {
+                    optimized.push(insts[i].clone());
+                    i += 1;
+                }
+            }
+        }
+
+        *insts = optimized;
+
+        rewrites
+    }
+
+    pub(crate) fn thread_jumps(graph: &mut CfgGraph) -> usize {
+        let mut node_ids = graph.nodes.keys().copied().collect::<Vec<_>>();
+        node_ids.sort();
+
+        let mut threaded = 0;
+
+        for node_id in node_ids {
+            let target = match Self::final_target(graph, node_id) {
+                Some(target) => target,
+                None => continue,
+            };
+
+            let incoming = graph.get_node(node_id).incoming.clone();
+
+            for edge in incoming {
+                graph.retarget_edge(edge.node_id, node_id, edge.jmp_type, target);
+
+                threaded += 1;
+            }
+        }
+
+        threaded
+    }
+
+    // for an empty node unconditionally jumping elsewhere,
+    // returns the first non-empty node reached when following its jumps
+    fn final_target(graph: &CfgGraph, node_id: CfgNodeId) -> Option<CfgNodeId> {
+        let mut visited = HashSet::new();
+        let mut current = node_id;
+
+        while let Some(next) = Self::always_jmp(graph, current) {
+            if !visited.insert(current) {
+                // an infinite loop of empty nodes
+                return None;
+            }
+
+            current = next;
+        }
+
+        if current == node_id {
+            None
+        } else {
+            Some(current)
+        }
+    }
+
+    pub(crate) fn always_jmp(graph: &CfgGraph, node_id: CfgNodeId) -> Option<CfgNodeId> {
+        let node = graph.get_node(node_id);
+
+        if !node.is_empty() || node.outgoing.len() != 1 {
+            return None;
+        }
+
+        let edge = node.outgoing.iter().next().unwrap();
+
+        if edge.jmp_type == CfgJumpType::Always {
+            Some(edge.node_id)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for Peephole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
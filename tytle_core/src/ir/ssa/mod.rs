@@ -0,0 +1,18 @@
+// the Static Single Assignment form of the IR.
+//
+// each procedure local (its params included) is split into versions, each assigned exactly once,
+// and the versions reaching a node along different paths are merged by the node's phis.
+// the other variables (e.g the globals, which calls may modify) are left as they are.
+//
+// converting back drops the versions, so the passes working over the SSA form must keep it
+// conventional: the versions of a variable mustn't be live at the same time
+// (e.g replacing the loads of a version with a constant is fine, while moving a load of it
+// past a later store of the variable isn't)
+
+mod ssa_form;
+mod ssa_inst;
+mod ssa_proc;
+
+pub use ssa_form::SsaForm;
+pub use ssa_inst::{Phi, SsaInstruction, SsaNode, SsaVar};
+pub use ssa_proc::SsaProc;
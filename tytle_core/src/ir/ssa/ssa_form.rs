@@ -0,0 +1,85 @@
+use super::SsaProc;
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::compat::collections::{BTreeMap, BTreeSet};
+use crate::ir::{CfgInstruction, CfgObject};
+
+// the SSA form of a whole `CfgObject`, renaming the locals of each procedure (see `SsaProc`).
+// the graph edges, the jump table and the frames layouts are kept by `cfg`
+// (whose nodes instructions are out of date until converted back).
+//
+// the variables used by block expressions aren't renamed,
+// since a block runs within the frame it's created in (so it may modify them behind its back)
+pub struct SsaForm {
+    pub cfg: CfgObject,
+    pub procs: BTreeMap<SymbolId, SsaProc>,
+}
+
+impl SsaForm {
+    pub fn new(cfg: CfgObject, env: &Environment) -> Self {
+        let mut procs = BTreeMap::new();
+        let blocks_vars = Self::blocks_vars(&cfg, env);
+
+        for (entry_id, proc_id) in cfg.jmp_table.iter() {
+            let vars = env
+                .locals_symbols
+                .get(proc_id)
+                .into_iter()
+                .flatten()
+                .filter(|var_id| !blocks_vars.contains(var_id))
+                .copied()
+                .collect::<BTreeSet<SymbolId>>();
+
+            let ssa_proc = SsaProc::new(&cfg.graph, *entry_id, *proc_id, &vars);
+            procs.insert(*proc_id, ssa_proc);
+        }
+
+        Self { cfg, procs }
+    }
+
+    pub fn get_proc(&self, proc_id: SymbolId) -> &SsaProc {
+        self.procs.get(&proc_id).unwrap()
+    }
+
+    pub fn get_proc_mut(&mut self, proc_id: SymbolId) -> &mut SsaProc {
+        self.procs.get_mut(&proc_id).unwrap()
+    }
+
+    pub fn phis_count(&self) -> usize {
+        self.procs.values().map(|ssa_proc| ssa_proc.phis_count()).sum()
+    }
+
+    // converts back to a `CfgObject` (see `SsaProc::apply`)
+    pub fn into_cfg(self) -> CfgObject {
+        let mut cfg = self.cfg;
+
+        for ssa_proc in self.procs.values() {
+            ssa_proc.apply(&mut cfg.graph);
+        }
+
+        cfg
+    }
+
+    // the variables loaded or stored by the blocks procedures
+    fn blocks_vars(cfg: &CfgObject, env: &Environment) -> BTreeSet<SymbolId> {
+        let mut vars = BTreeSet::new();
+
+        for (entry_id, proc_id) in cfg.jmp_table.iter() {
+            if !env.blocks.contains_key(proc_id) {
+                continue;
+            }
+
+            for node_id in cfg.graph.rpo_from(*entry_id) {
+                for inst in cfg.graph.get_node(node_id).insts.iter() {
+                    match inst {
+                        CfgInstruction::Load(var_id) | CfgInstruction::Store(var_id) => {
+                            vars.insert(*var_id);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        vars
+    }
+}
@@ -0,0 +1,64 @@
+use crate::ast::semantic::SymbolId;
+use crate::compat::collections::BTreeMap;
+use crate::compat::prelude::*;
+use crate::ir::{CfgInstruction, CfgNodeId};
+
+// a version of a variable.
+// version `0` is the value the variable has when the procedure is entered
+// (the argument of a param, and no value for the other locals)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SsaVar {
+    pub var_id: SymbolId,
+    pub version: usize,
+}
+
+impl SsaVar {
+    pub fn new(var_id: SymbolId, version: usize) -> Self {
+        Self { var_id, version }
+    }
+
+    pub fn is_initial(&self) -> bool {
+        self.version == 0
+    }
+}
+
+// `dst = phi(args)`: the version of the variable chosen by the predecessor entering the node
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phi {
+    pub dst: SsaVar,
+    pub args: BTreeMap<CfgNodeId, SsaVar>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SsaInstruction {
+    Load(SsaVar),
+    Store(SsaVar),
+
+    // an instruction not touching the renamed variables
+    Inst(CfgInstruction),
+}
+
+impl SsaInstruction {
+    pub fn to_cfg_inst(&self) -> CfgInstruction {
+        match self {
+            SsaInstruction::Load(var) => CfgInstruction::Load(var.var_id),
+            SsaInstruction::Store(var) => CfgInstruction::Store(var.var_id),
+            SsaInstruction::Inst(inst) => inst.clone(),
+        }
+    }
+}
+
+// the SSA counterpart of a `CfgNode` (its edges are kept by the `CfgGraph`).
+// the phis are evaluated simultaneously, when entering the node
+#[derive(Debug, Clone, PartialEq)]
+pub struct SsaNode {
+    pub id: CfgNodeId,
+    pub phis: Vec<Phi>,
+    pub insts: Vec<SsaInstruction>,
+}
+
+impl SsaNode {
+    pub fn phi(&self, var_id: SymbolId) -> Option<&Phi> {
+        self.phis.iter().find(|phi| phi.dst.var_id == var_id)
+    }
+}
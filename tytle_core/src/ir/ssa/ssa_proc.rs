@@ -0,0 +1,241 @@
+use super::{Phi, SsaInstruction, SsaNode, SsaVar};
+use crate::ast::semantic::SymbolId;
+use crate::compat::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::compat::prelude::*;
+use crate::ir::{CfgGraph, CfgInstruction, CfgNodeId, DominatorTree};
+
+// the SSA form of a procedure's sub-graph, renaming the variables `vars`.
+//
+// built using the algorithm of Cytron et al. ("Efficiently Computing Static Single Assignment Form
+// and the Control Dependence Graph"): the phis are placed at the iterated dominance frontiers
+// of the nodes storing each variable, and the versions are then numbered
+// walking the dominator tree.
+// the SSA is minimal (the phis of dead variables aren't pruned).
+//
+// the procedure entry node is assumed to have no incoming edges (as built by `CfgBuilder`).
+// unreachable nodes are left out.
+// a `THROW` resuming the execution after a `CATCH` isn't an edge,
+// so the versions stored before it don't flow into the phis of the node following the `CATCH`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SsaProc {
+    pub proc_id: SymbolId,
+    pub entry: CfgNodeId,
+    pub vars: BTreeSet<SymbolId>,
+    pub nodes: BTreeMap<CfgNodeId, SsaNode>,
+
+    // the number of versions of each variable (including its initial version)
+    versions: BTreeMap<SymbolId, usize>,
+}
+
+impl SsaProc {
+    pub fn new(
+        graph: &CfgGraph,
+        entry: CfgNodeId,
+        proc_id: SymbolId,
+        vars: &BTreeSet<SymbolId>,
+    ) -> Self {
+        let dom_tree = DominatorTree::new(graph, entry);
+
+        let mut nodes = dom_tree
+            .nodes()
+            .iter()
+            .map(|node_id| {
+                let node = SsaNode {
+                    id: *node_id,
+                    phis: Vec::new(),
+                    insts: Vec::new(),
+                };
+
+                (*node_id, node)
+            })
+            .collect::<BTreeMap<CfgNodeId, SsaNode>>();
+
+        Self::place_phis(graph, &dom_tree, vars, &mut nodes);
+
+        let mut renamer = Renamer {
+            graph,
+            dom_tree: &dom_tree,
+            vars,
+            nodes: &mut nodes,
+            stacks: vars.iter().map(|var_id| (*var_id, vec![0])).collect(),
+            versions: vars.iter().map(|var_id| (*var_id, 1)).collect(),
+        };
+
+        renamer.rename(entry);
+
+        let versions = renamer.versions;
+
+        Self {
+            proc_id,
+            entry,
+            vars: vars.clone(),
+            nodes,
+            versions,
+        }
+    }
+
+    pub fn get_node(&self, node_id: CfgNodeId) -> &SsaNode {
+        self.nodes.get(&node_id).unwrap()
+    }
+
+    pub fn get_node_mut(&mut self, node_id: CfgNodeId) -> &mut SsaNode {
+        self.nodes.get_mut(&node_id).unwrap()
+    }
+
+    // returns `0` for a variable which isn't renamed
+    pub fn versions_count(&self, var_id: SymbolId) -> usize {
+        self.versions.get(&var_id).copied().unwrap_or(0)
+    }
+
+    pub fn phis_count(&self) -> usize {
+        self.nodes.values().map(|node| node.phis.len()).sum()
+    }
+
+    // the node assigning the version `var` (by a phi or a `Store`),
+    // returns `None` for the initial versions
+    pub fn def_node(&self, var: SsaVar) -> Option<CfgNodeId> {
+        self.nodes
+            .values()
+            .find(|node| {
+                node.phis.iter().any(|phi| phi.dst == var)
+                    || node.insts.contains(&SsaInstruction::Store(var))
+            })
+            .map(|node| node.id)
+    }
+
+    // writes the instructions of the nodes back into `graph`, dropping the versions (and the phis)
+    pub fn apply(&self, graph: &mut CfgGraph) {
+        for node in self.nodes.values() {
+            let insts = node.insts.iter().map(|inst| inst.to_cfg_inst()).collect();
+
+            graph.get_node_mut(node.id).insts = insts;
+        }
+    }
+
+    fn place_phis(
+        graph: &CfgGraph,
+        dom_tree: &DominatorTree,
+        vars: &BTreeSet<SymbolId>,
+        nodes: &mut BTreeMap<CfgNodeId, SsaNode>,
+    ) {
+        let frontiers = dom_tree.frontiers(graph);
+
+        for var_id in vars.iter() {
+            let mut worklist = nodes
+                .keys()
+                .filter(|node_id| {
+                    let insts = &graph.get_node(**node_id).insts;
+
+                    insts.contains(&CfgInstruction::Store(*var_id))
+                })
+                .copied()
+                .collect::<Vec<CfgNodeId>>();
+
+            let mut has_phi = BTreeSet::new();
+
+            while let Some(node_id) = worklist.pop() {
+                for frontier_id in frontiers.get(&node_id).into_iter().flatten() {
+                    if !has_phi.insert(*frontier_id) {
+                        continue;
+                    }
+
+                    let phi = Phi {
+                        dst: SsaVar::new(*var_id, 0),
+                        args: BTreeMap::new(),
+                    };
+
+                    nodes.get_mut(frontier_id).unwrap().phis.push(phi);
+                    worklist.push(*frontier_id);
+                }
+            }
+        }
+    }
+}
+
+struct Renamer<'a> {
+    graph: &'a CfgGraph,
+    dom_tree: &'a DominatorTree,
+    vars: &'a BTreeSet<SymbolId>,
+    nodes: &'a mut BTreeMap<CfgNodeId, SsaNode>,
+
+    // the versions in scope of each variable (the current one last)
+    stacks: HashMap<SymbolId, Vec<usize>>,
+
+    // the next version of each variable
+    versions: BTreeMap<SymbolId, usize>,
+}
+
+impl<'a> Renamer<'a> {
+    fn rename(&mut self, node_id: CfgNodeId) {
+        let mut defined = Vec::new();
+
+        let node = self.nodes.get_mut(&node_id).unwrap();
+
+        for phi in node.phis.iter_mut() {
+            let var_id = phi.dst.var_id;
+            let version = Self::new_version(&mut self.versions, &mut self.stacks, var_id);
+
+            phi.dst = SsaVar::new(var_id, version);
+            defined.push(var_id);
+        }
+
+        for inst in self.graph.get_node(node_id).insts.iter() {
+            let ssa_inst = match inst {
+                CfgInstruction::Load(var_id) if self.vars.contains(var_id) => {
+                    SsaInstruction::Load(self.current(*var_id))
+                }
+                CfgInstruction::Store(var_id) if self.vars.contains(var_id) => {
+                    let version =
+                        Self::new_version(&mut self.versions, &mut self.stacks, *var_id);
+
+                    defined.push(*var_id);
+                    SsaInstruction::Store(SsaVar::new(*var_id, version))
+                }
+                _ => SsaInstruction::Inst(inst.clone()),
+            };
+
+            self.nodes.get_mut(&node_id).unwrap().insts.push(ssa_inst);
+        }
+
+        let currents = self
+            .vars
+            .iter()
+            .map(|var_id| (*var_id, self.current(*var_id)))
+            .collect::<HashMap<SymbolId, SsaVar>>();
+
+        for succ_id in self.graph.successors(node_id) {
+            if let Some(succ) = self.nodes.get_mut(&succ_id) {
+                for phi in succ.phis.iter_mut() {
+                    phi.args.insert(node_id, currents[&phi.dst.var_id]);
+                }
+            }
+        }
+
+        for child_id in self.dom_tree.children(node_id) {
+            self.rename(child_id);
+        }
+
+        for var_id in defined {
+            self.stacks.get_mut(&var_id).unwrap().pop();
+        }
+    }
+
+    fn current(&self, var_id: SymbolId) -> SsaVar {
+        let version = *self.stacks[&var_id].last().unwrap();
+
+        SsaVar::new(var_id, version)
+    }
+
+    fn new_version(
+        versions: &mut BTreeMap<SymbolId, usize>,
+        stacks: &mut HashMap<SymbolId, Vec<usize>>,
+        var_id: SymbolId,
+    ) -> usize {
+        let next = versions.get_mut(&var_id).unwrap();
+        let version = *next;
+        *next += 1;
+
+        stacks.get_mut(&var_id).unwrap().push(version);
+        version
+    }
+}
@@ -0,0 +1,41 @@
+use crate::lexer::Location;
+use core::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum LexError {
+    InvalidCharacter { ch: char, location: Location },
+}
+
+impl LexError {
+    pub fn location(&self) -> Location {
+        match self {
+            LexError::InvalidCharacter { location, .. } => *location,
+        }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LexError::InvalidCharacter { ch, .. } => {
+                write!(f, "Invalid character: `{}`", ch.escape_default())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn lex_error_invalid_character() {
+        let err = LexError::InvalidCharacter {
+            ch: '\u{1}',
+            location: Location(2, 3),
+        };
+
+        assert_eq!("Invalid character: `\\u{1}`", err.to_string());
+        assert_eq!(Location(2, 3), err.location());
+    }
+}
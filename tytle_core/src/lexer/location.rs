@@ -1,6 +1,11 @@
-use std::default::Default;
-use std::fmt;
+use core::default::Default;
+use core::fmt;
 
+// the number of columns between tab stops, unless configured otherwise
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+// a 1-based line & column position within the source code.
+// columns are counted in chars, where a tab advances to the next tab stop
 #[derive(PartialEq, Copy, Clone)]
 pub struct Location(pub usize, pub usize);
 
@@ -16,7 +21,20 @@ impl fmt::Debug for Location {
     }
 }
 
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line(), self.column())
+    }
+}
+
 impl Location {
+    pub fn in_file(self, file: FileId) -> FileLocation {
+        FileLocation {
+            file,
+            location: self,
+        }
+    }
+
     pub fn next_line(&mut self) {
         self.0 += 1;
         self.1 = 1;
@@ -26,6 +44,13 @@ impl Location {
         self.1 += 1;
     }
 
+    // advances to the next tab stop (tab stops are `tab_width` columns apart)
+    pub fn advance_tab(&mut self, tab_width: usize) {
+        let tab_width = tab_width.max(1);
+
+        self.1 = ((self.1 - 1) / tab_width + 1) * tab_width + 1;
+    }
+
     pub fn line(&self) -> usize {
         self.0
     }
@@ -34,3 +59,63 @@ impl Location {
         self.1
     }
 }
+
+// identifies a file registered in a `SourceMap` (assigned in registration order)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(pub usize);
+
+impl FileId {
+    // the first registered file, i.e the program's entry code
+    pub const MAIN: FileId = FileId(0);
+}
+
+// a `Location` within a specific file of a multi-file program
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FileLocation {
+    pub file: FileId,
+    pub location: Location,
+}
+
+impl FileLocation {
+    pub fn line(&self) -> usize {
+        self.location.line()
+    }
+
+    pub fn column(&self) -> usize {
+        self.location.column()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn location_advance_tab() {
+        let mut loc = Location(1, 1);
+
+        loc.advance_tab(4);
+        assert_eq!(Location(1, 5), loc);
+
+        loc.increment_column();
+        loc.increment_column();
+        loc.advance_tab(4);
+        assert_eq!(Location(1, 9), loc);
+
+        loc.advance_tab(1);
+        assert_eq!(Location(1, 10), loc);
+
+        loc.advance_tab(0);
+        assert_eq!(Location(1, 11), loc);
+    }
+
+    #[test]
+    pub fn location_in_file() {
+        let loc = Location(2, 7).in_file(FileId(1));
+
+        assert_eq!(FileId(1), loc.file);
+        assert_eq!(Location(2, 7), loc.location);
+        assert_eq!(2, loc.line());
+        assert_eq!(7, loc.column());
+    }
+}
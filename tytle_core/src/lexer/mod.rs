@@ -1,3 +1,4 @@
+mod lex_error;
 mod location;
 mod token;
 mod tytle_lexer;
@@ -9,6 +10,7 @@ pub trait Lexer {
     fn pop_current_token(&mut self) -> Option<(Token, Location)>;
 }
 
-pub use location::Location;
+pub use lex_error::LexError;
+pub use location::{FileId, FileLocation, Location, DEFAULT_TAB_WIDTH};
 pub use token::Token;
 pub use tytle_lexer::TytleLexer;
@@ -1,3 +1,5 @@
+use crate::compat::prelude::*;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     EOF,
@@ -26,6 +28,9 @@ pub enum Token {
     NOT, // `NOT`
 
     VALUE(String),
+
+    // a `;;` doc comment line (without the `;;`)
+    DOC(String),
 }
 
 impl ToString for Token {
@@ -49,6 +54,7 @@ impl ToString for Token {
             Token::OR => "OR",
             Token::NOT => "NOT",
             Token::VALUE(s) => s,
+            Token::DOC(s) => s,
         };
 
         s.to_string()
@@ -152,4 +158,9 @@ mod tests {
     pub fn token_value() {
         assert_token("ABC", Token::VALUE("ABC".to_string()));
     }
+
+    #[test]
+    pub fn token_doc() {
+        assert_token("Draws a square", Token::DOC("Draws a square".to_string()));
+    }
 }
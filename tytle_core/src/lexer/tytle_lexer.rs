@@ -1,31 +1,100 @@
-use super::location::Location;
+use super::location::{Location, DEFAULT_TAB_WIDTH};
 use super::token::Token;
-use crate::lexer::Lexer;
+use crate::compat::prelude::*;
+use crate::lexer::{LexError, Lexer};
+use crate::parser::{KeywordTable, ParserConfig};
 
-use std::collections::VecDeque;
+use crate::compat::collections::VecDeque;
 
-use std::str::Chars;
+use core::iter::Peekable;
+use core::str::Chars;
 
 pub struct TytleLexer<'lex> {
-    code_chars: Chars<'lex>,
+    code_chars: Peekable<Chars<'lex>>,
     location: Location,
     reached_eof: bool,
     tokens_buffer: VecDeque<(Token, Location)>,
+    last_popped: Option<Location>,
+    keywords: Option<&'lex KeywordTable>,
+    tab_width: usize,
+
+    // the line of the most recently buffered token (for telling whether a comment starts its line)
+    last_token_line: usize,
 }
 
 impl<'lex> TytleLexer<'lex> {
     pub fn new(code: &'lex str) -> Self {
+        Self::build(code, None, DEFAULT_TAB_WIDTH)
+    }
+
+    // a lexer normalizing the translated keywords of `config` into their canonical form,
+    // and computing the tokens columns using the configured tab width
+    pub fn with_config(code: &'lex str, config: &'lex ParserConfig) -> Self {
+        Self::build(code, Some(&config.keywords), config.tab_width)
+    }
+
+    fn build(code: &'lex str, keywords: Option<&'lex KeywordTable>, tab_width: usize) -> Self {
         let mut lexer = Self {
             location: Location::default(),
-            code_chars: code.chars(),
+            code_chars: code.chars().peekable(),
             reached_eof: false,
             tokens_buffer: Default::default(),
+            last_popped: None,
+            keywords,
+            tab_width,
+            last_token_line: 0,
         };
 
         lexer.buffer_more_tokens();
 
         lexer
     }
+
+    // rejects characters that can't be part of any tytle program (control characters other than whitespace).
+    // the lexer itself never fails, so it should be called before tokenizing untrusted code.
+    //
+    // any other Unicode character is allowed (e.g within identifiers and strings),
+    // and since the source is read char by char, `Location` columns are counted in chars (not bytes)
+    pub fn check(code: &str) -> Result<(), LexError> {
+        Self::check_with_tab_width(code, DEFAULT_TAB_WIDTH)
+    }
+
+    pub fn check_with_tab_width(code: &str, tab_width: usize) -> Result<(), LexError> {
+        let mut location = Location::default();
+        let mut chars = code.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\n' => location.next_line(),
+                ' ' | '\t' | '\r' => {
+                    Self::advance_whitespace(&mut location, ch, chars.peek(), tab_width)
+                }
+                _ if !ch.is_control() => location.increment_column(),
+                _ => return Err(LexError::InvalidCharacter { ch, location }),
+            }
+        }
+
+        Ok(())
+    }
+
+    // a tab advances to the next tab stop, and the `\r` of a `\r\n` line ending takes no column at all
+    fn advance_whitespace(
+        location: &mut Location,
+        ch: char,
+        next: Option<&char>,
+        tab_width: usize,
+    ) {
+        match ch {
+            '\t' => location.advance_tab(tab_width),
+            '\r' if next == Some(&'\n') => {}
+            _ => location.increment_column(),
+        }
+    }
+
+    // the location of the most recently popped token
+    pub fn last_popped_location(&self) -> Option<Location> {
+        self.last_popped
+    }
 }
 
 impl<'lex> Lexer for TytleLexer<'lex> {
@@ -40,7 +109,13 @@ impl<'lex> Lexer for TytleLexer<'lex> {
     fn pop_current_token(&mut self) -> Option<(Token, Location)> {
         self.buffer_more_tokens();
 
-        self.tokens_buffer.pop_front()
+        let entry = self.tokens_buffer.pop_front();
+
+        if let Some((_, location)) = entry {
+            self.last_popped = Some(location);
+        }
+
+        entry
     }
 
     fn buffer_more_tokens(&mut self) {
@@ -74,7 +149,8 @@ impl<'lex> Lexer for TytleLexer<'lex> {
                         self.location.increment_column();
                         break;
                     }
-                    ':' => {
+                    // a `:` within a string literal is a part of it (e.g `"image:ROCKET`)
+                    ':' if token.first() != Some(&'"') => {
                         self.push_token(&mut token);
                         self.push_colon();
                         self.location.increment_column();
@@ -110,17 +186,25 @@ impl<'lex> Lexer for TytleLexer<'lex> {
                         self.location.increment_column();
                         break;
                     }
-                    ' ' => match token.len() {
-                        0 => {
-                            self.location.increment_column();
-                            continue;
+                    // a comment lasts until the end of its line (a `;` within a string literal is a part of it)
+                    ';' if token.first() != Some(&'"') => {
+                        self.push_token(&mut token);
+
+                        if self.skip_comment() {
+                            break;
                         }
-                        _ => {
-                            self.push_token(&mut token);
-                            self.location.increment_column();
+                    }
+                    ' ' | '\t' | '\r' => {
+                        let ended_token = !token.is_empty();
+                        self.push_token(&mut token);
+
+                        let next = self.code_chars.peek();
+                        Self::advance_whitespace(&mut self.location, ch, next, self.tab_width);
+
+                        if ended_token {
                             break;
                         }
-                    },
+                    }
                     _ => {
                         self.location.increment_column();
                         token.push(ch);
@@ -144,9 +228,52 @@ impl<'lex> TytleLexer<'lex> {
         }
     }
 
+    // skips a comment (starting at its `;`) up to the end of its line.
+    // a `;;` comment standing on its own line is a doc comment, and is kept as a `Token::DOC`.
+    // returns whether a token has been buffered
+    fn skip_comment(&mut self) -> bool {
+        let location = self.location;
+        let own_line = self.last_token_line < location.line();
+
+        let mut comment = String::new();
+        self.location.increment_column();
+
+        while let Some(&ch) = self.code_chars.peek() {
+            if ch == '\n' {
+                break;
+            }
+
+            self.code_chars.next();
+
+            let next = self.code_chars.peek();
+            Self::advance_whitespace(&mut self.location, ch, next, self.tab_width);
+
+            comment.push(ch);
+        }
+
+        match comment.strip_prefix(';') {
+            Some(doc) if own_line => {
+                let doc = doc.strip_prefix(' ').unwrap_or(doc).trim_end();
+
+                self.push_back(Token::DOC(doc.to_string()), location);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn push_back(&mut self, token: Token, location: Location) {
+        self.last_token_line = location.line();
+        self.tokens_buffer.push_back((token, location));
+    }
+
     fn push_token(&mut self, token_chars: &mut Vec<char>) {
         if token_chars.len() > 0 {
-            let value = token_chars.iter().collect::<String>();
+            let mut value = token_chars.iter().collect::<String>();
+
+            if let Some(keyword) = self.keywords.and_then(|kws| kws.canonical(&value)) {
+                value = keyword.to_string();
+            }
 
             let loc = Location(
                 self.location.line(),
@@ -160,17 +287,14 @@ impl<'lex> TytleLexer<'lex> {
                 _ => Token::VALUE(value),
             };
 
-            let entry = (token, loc);
-
-            self.tokens_buffer.push_back(entry);
+            self.push_back(token, loc);
         }
 
         token_chars.clear();
     }
 
     fn push_newline(&mut self) {
-        self.tokens_buffer
-            .push_back((Token::NEWLINE, self.location));
+        self.push_back(Token::NEWLINE, self.location);
 
         self.location.next_line();
     }
@@ -182,27 +306,27 @@ impl<'lex> TytleLexer<'lex> {
             '/' => Token::DIV,
             _ => panic!(),
         };
-        self.tokens_buffer.push_back((token, self.location));
+        self.push_back(token, self.location);
     }
 
     fn push_comma(&mut self) {
-        self.tokens_buffer.push_back((Token::COMMA, self.location));
+        self.push_back(Token::COMMA, self.location);
     }
 
     fn push_assign(&mut self) {
-        self.tokens_buffer.push_back((Token::ASSIGN, self.location));
+        self.push_back(Token::ASSIGN, self.location);
     }
 
     fn push_colon(&mut self) {
-        self.tokens_buffer.push_back((Token::COLON, self.location));
+        self.push_back(Token::COLON, self.location);
     }
 
     fn push_less_than(&mut self) {
-        self.tokens_buffer.push_back((Token::LT, self.location));
+        self.push_back(Token::LT, self.location);
     }
 
     fn push_greater_than(&mut self) {
-        self.tokens_buffer.push_back((Token::GT, self.location));
+        self.push_back(Token::GT, self.location);
     }
 
     fn push_bracket(&mut self, op: char) {
@@ -213,13 +337,13 @@ impl<'lex> TytleLexer<'lex> {
             ']' => Token::RBRACKET,
             _ => panic!(),
         };
-        self.tokens_buffer.push_back((token, self.location));
+        self.push_back(token, self.location);
     }
 
     fn push_eof(&mut self) {
         self.location.next_line();
 
-        self.tokens_buffer.push_back((Token::EOF, self.location));
+        self.push_back(Token::EOF, self.location);
         self.reached_eof = true;
     }
 }
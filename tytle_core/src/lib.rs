@@ -1,19 +1,63 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("either the `std` or the `alloc` feature must be enabled");
+
+#[macro_use]
+extern crate alloc;
+
 #[macro_use]
 extern crate lazy_static;
 
+#[macro_use]
+mod instrument;
+
+mod compat;
+mod guard;
+
+// the pipeline plumbing, whose API is re-exported below (and by the prelude)
+pub(crate) mod render;
+pub(crate) mod runner;
+
+pub mod animation;
 pub mod ast;
+#[cfg(feature = "std")]
+pub mod batch;
+pub mod blocks;
+pub mod compiler;
+pub mod diagnostics;
+pub mod grading;
 pub mod ir;
 pub mod lexer;
 pub mod parser;
+pub mod project;
+pub mod session;
+pub mod stdlib;
+pub mod tooling;
+pub mod transpiler;
 pub mod vm;
 
+mod tytle_error;
+
+pub use render::{render_to_svg, RenderOptions, SvgHost};
+pub use runner::{run, RunOptions, RunStats, RunSummary};
+pub use tytle_error::TytleError;
+
+// the documented public surface, for `use tytle::prelude::*`.
+// the rest of the API (the IR, the semantic analysis, the tooling etc.) is reachable
+// through its module (e.g `tytle::ir::CfgObject`) but isn't part of the prelude
 pub mod prelude {
-    pub use crate::ast::expression::*;
-    pub use crate::ast::semantic::*;
-    pub use crate::ast::statement::*;
-    pub use crate::ast::*;
-    pub use crate::ir::*;
-    pub use crate::lexer::*;
-    pub use crate::parser::*;
-    pub use crate::vm::*;
+    pub use crate::ast::Ast;
+    pub use crate::compiler::{CompileError, CompileOptions, CompiledProgram, Compiler};
+    pub use crate::diagnostics::{Diagnostic, Diagnostics, Severity, SourceFile};
+    pub use crate::ir::OptLevel;
+    pub use crate::parser::{ParseError, Parser, TytleParser};
+    pub use crate::render::{render_to_svg, RenderOptions, SvgHost};
+    pub use crate::runner::{run, RunOptions, RunStats, RunSummary};
+    pub use crate::vm::{
+        AsyncHost, Capabilities, Capability, DummyHost, Host, HostCall, Interpreter,
+        InterpreterException, MemoryValue, OverflowPolicy, RecordingHost, ReplayHost, Resource,
+        ResourceLimits, VmEvent,
+    };
+    pub use crate::TytleError;
 }
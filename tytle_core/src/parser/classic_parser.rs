@@ -0,0 +1,888 @@
+use crate::ast::expression::*;
+use crate::ast::statement::*;
+use crate::ast::Ast;
+
+use crate::compat::prelude::*;
+use crate::lexer::{Lexer, Location, Token, TytleLexer};
+use crate::parser::{ParseError, Parser, ParserConfig, ParserResult};
+
+use crate::compat::collections::{HashMap, HashSet};
+
+// the classic Logo primitives having no tytle counterpart (reported as unsupported, not unknown)
+const UNSUPPORTED: &[&str] = &[
+    "ARC",
+    "BACKSLASH",
+    "BF",
+    "BL",
+    "BUTFIRST",
+    "BUTLAST",
+    "CIRCLE",
+    "COS",
+    "COUNT",
+    "DIFFERENCE",
+    "DOTIMES",
+    "FILL",
+    "FIRST",
+    "FOR",
+    "FPUT",
+    "HEADING",
+    "HOME",
+    "IFFALSE",
+    "IFTRUE",
+    "ITEM",
+    "LAST",
+    "LIST",
+    "LPUT",
+    "MINUS",
+    "POS",
+    "PRODUCT",
+    "QUOTIENT",
+    "RANDOM",
+    "READLIST",
+    "READWORD",
+    "REMAINDER",
+    "REPCOUNT",
+    "SE",
+    "SENTENCE",
+    "SETH",
+    "SETHEADING",
+    "SETPOS",
+    "SETXY",
+    "SIN",
+    "SQRT",
+    "SUM",
+    "TEST",
+    "THING",
+    "TOWARDS",
+    "TYPE",
+    "UNTIL",
+    "WHILE",
+    "WORD",
+];
+
+// the words a classic statement may start with, used for suggesting a replacement to an unknown one
+const STATEMENT_WORDS: &[&str] = &[
+    "TO",
+    "REPEAT",
+    "IF",
+    "IFELSE",
+    "MAKE",
+    "LOCAL",
+    "LOCALMAKE",
+    "OUTPUT",
+    "OP",
+    "STOP",
+    "PRINT",
+    "PR",
+    "SHOW",
+    "FORWARD",
+    "FD",
+    "BACK",
+    "BK",
+    "BACKWARD",
+    "RIGHT",
+    "RT",
+    "LEFT",
+    "LT",
+    "SETX",
+    "SETY",
+    "PENUP",
+    "PU",
+    "PENDOWN",
+    "PD",
+    "PENERASE",
+    "PE",
+    "HIDETURTLE",
+    "HT",
+    "SHOWTURTLE",
+    "ST",
+    "CLEARSCREEN",
+    "CS",
+    "CLEAN",
+    "SETPENCOLOR",
+    "SETPC",
+    "SETBACKGROUND",
+    "SETBG",
+    "WAIT",
+    "LABEL",
+    "SETLABELHEIGHT",
+    "RUN",
+];
+
+type ClassicResult<T> = Result<T, (ParseError, Location)>;
+
+// a compatibility parser for classic (UCBLogo / FMSLogo) sources, translating them into an `Ast`:
+//
+// ```
+// to square :size
+//   repeat 4 [fd :size rt 90]
+// end
+//
+// cs
+// square 50
+// ```
+//
+// * procedures are declared as `TO NAME :A :B`, their params are of type `INT`
+//   and the procedures having an `OUTPUT` (or `OP`) return an `INT`
+// * procedures are called without parentheses, consuming as many args as they have params
+// * variables are referenced as `:VAR` and assigned by `MAKE "VAR expr` (`LOCAL "VAR` and
+//   `LOCALMAKE "VAR expr` declare procedure locals). since tytle procedures can't declare globals,
+//   a procedure assigning a variable which isn't a known global declares a local
+// * the common abbreviations are accepted (`FD`, `BK`, `RT`, `LT`, `PU`, `PD`, `PE`, `HT`, `ST`,
+//   `CS`, `PR`, `OP`, `SETPC`, `SETBG`) and words are case-insensitive
+// * `IFELSE cond [...] [...]` is an `IF` having an `ELSE` block
+//   (or an `IFELSE` expression, when it's an operand, e.g `OP IFELSE :N > 0 [1] [0]`)
+// * the args of `SETPC`, `SETBG` and `WAIT` are dropped (the tytle commands take none)
+//
+// the tytle turtle semantics apply (e.g `RT 90` moves the turtle right rather than turning it).
+// other classic primitives (e.g `SETHEADING` or `RANDOM`) are reported as unsupported,
+// at the location of their first use
+pub struct ClassicParser;
+
+impl Parser for ClassicParser {
+    fn parse(&mut self, code: &str) -> ParserResult {
+        self.parse_located(code, &ParserConfig::default())
+            .map_err(|(err, _location)| err)
+    }
+}
+
+impl ClassicParser {
+    pub fn parse_located(
+        &mut self,
+        code: &str,
+        config: &ParserConfig,
+    ) -> Result<Ast, (ParseError, Location)> {
+        let mut lexer = TytleLexer::with_config(code, config);
+        let mut tokens = Vec::new();
+
+        while let Some((token, location)) = lexer.pop_current_token() {
+            // words are case-insensitive (unlike the quoted ones, which may be string literals)
+            let token = match token {
+                Token::VALUE(word) if !word.starts_with('"') => {
+                    match word.to_uppercase().as_str() {
+                        "AND" => Token::AND,
+                        "OR" => Token::OR,
+                        "NOT" => Token::NOT,
+                        word => Token::VALUE(word.to_string()),
+                    }
+                }
+                Token::DOC(_) => continue,
+                token => token,
+            };
+
+            tokens.push((token, location));
+        }
+
+        ClassicTranslator::new(tokens).translate()
+    }
+}
+
+// the signature of a classic procedure, collected ahead of parsing the procedures calls
+struct ClassicProc {
+    params: Vec<String>,
+    outputs: bool,
+}
+
+struct ClassicTranslator {
+    tokens: Vec<(Token, Location)>,
+    pos: usize,
+    procs: HashMap<String, ClassicProc>,
+    globals: HashSet<String>,
+
+    // the variables declared by the procedure currently parsed (`None` at the top-level)
+    locals: Option<HashSet<String>>,
+
+    // the `LOCAL "VAR` declared variables which haven't been assigned yet
+    pending_locals: HashSet<String>,
+}
+
+impl ClassicTranslator {
+    fn new(tokens: Vec<(Token, Location)>) -> Self {
+        let procs = Self::scan_procs(&tokens);
+
+        Self {
+            tokens,
+            pos: 0,
+            procs,
+            globals: HashSet::new(),
+            locals: None,
+            pending_locals: HashSet::new(),
+        }
+    }
+
+    // the signatures of all the procedures, since a procedure may be called before its definition
+    fn scan_procs(tokens: &[(Token, Location)]) -> HashMap<String, ClassicProc> {
+        let mut procs = HashMap::new();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let at_line_start = i == 0 || tokens[i - 1].0 == Token::NEWLINE;
+
+            match (&tokens[i].0, tokens.get(i + 1)) {
+                (Token::VALUE(to), Some((Token::VALUE(name), _)))
+                    if to == "TO" && at_line_start =>
+                {
+                    let mut params = Vec::new();
+                    i += 2;
+
+                    while let (Some((Token::COLON, _)), Some((Token::VALUE(param), _))) =
+                        (tokens.get(i), tokens.get(i + 1))
+                    {
+                        params.push(param.clone());
+                        i += 2;
+                    }
+
+                    let mut outputs = false;
+
+                    while i < tokens.len() && tokens[i].0 != Token::VALUE("END".to_string()) {
+                        if let Token::VALUE(word) = &tokens[i].0 {
+                            outputs |= word == "OUTPUT" || word == "OP";
+                        }
+
+                        i += 1;
+                    }
+
+                    procs.insert(name.clone(), ClassicProc { params, outputs });
+                }
+                _ => i += 1,
+            }
+        }
+
+        procs
+    }
+
+    fn translate(mut self) -> Result<Ast, (ParseError, Location)> {
+        let mut ast = Ast::default();
+
+        while self.peek() != &Token::EOF {
+            let stmt = self.parse_statement()?;
+
+            if stmt != Statement::NOP {
+                ast.statements.push(stmt);
+            }
+        }
+
+        if ast.statements.is_empty() {
+            ast.statements.push(Statement::EOF);
+        }
+
+        Ok(ast)
+    }
+
+    fn parse_statement(&mut self) -> ClassicResult<Statement> {
+        let word = match self.peek().clone() {
+            Token::NEWLINE => {
+                self.skip();
+                return Ok(Statement::NOP);
+            }
+            Token::VALUE(word) => word,
+            token => {
+                return Err(self.syntax_error(&format!("Invalid syntax: `{}`", token.to_string())))
+            }
+        };
+
+        match word.as_str() {
+            "TO" => self.parse_proc_stmt(),
+            "REPEAT" => self.parse_repeat_stmt(),
+            "IF" | "IFELSE" => self.parse_if_stmt(),
+            "MAKE" | "LOCALMAKE" => self.parse_make_stmt(),
+            "LOCAL" => self.parse_local_stmt(),
+            "OUTPUT" | "OP" => {
+                self.skip();
+
+                let expr = self.parse_expr()?;
+                Ok(Statement::Return(ReturnStmt::new(Some(expr))))
+            }
+            "STOP" => {
+                self.skip();
+                Ok(Statement::Return(ReturnStmt::new(None)))
+            }
+            "PRINT" | "PR" | "SHOW" => {
+                self.skip();
+
+                let expr = self.parse_expr()?;
+                Ok(Statement::Print(expr))
+            }
+            "RUN" => {
+                self.skip();
+
+                let expr = self.parse_expr()?;
+                Ok(Statement::Run(expr))
+            }
+            "SETPC" | "SETPENCOLOR" => self.parse_set_pen_color_stmt(),
+            "SETBG" | "SETBACKGROUND" => self.parse_dropped_arg_cmd(Command::SetBackgroundColor),
+            "WAIT" => self.parse_dropped_arg_cmd(Command::Wait),
+            "LABEL" | "SETLABELHEIGHT" => {
+                self.skip();
+
+                let kind = match word.as_str() {
+                    "LABEL" => LabelKind::Text,
+                    _ => LabelKind::Size,
+                };
+
+                let expr = self.parse_expr()?;
+                Ok(Statement::Label(LabelStmt { kind, expr }))
+            }
+            "END" => Err(self.error(ParseError::UnexpectedKeyword { keyword: word })),
+            _ => {
+                if let Some(direction) = Self::direction(&word) {
+                    self.skip();
+
+                    let expr = self.parse_expr()?;
+                    return Ok(Statement::Direction(DirectionStmt { direction, expr }));
+                }
+
+                if let Some(cmd) = Self::command(&word) {
+                    self.skip();
+                    return Ok(Statement::Command(cmd));
+                }
+
+                if self.procs.contains_key(&word) {
+                    let expr = self.parse_proc_call_expr(&word)?;
+                    return Ok(Statement::Expression(expr));
+                }
+
+                Err(self.unknown_word_error(&word))
+            }
+        }
+    }
+
+    fn parse_proc_stmt(&mut self) -> ClassicResult<Statement> {
+        self.skip(); // skipping the `TO` token
+
+        let name = self.expect_word()?;
+
+        let (params, return_type) = match self.procs.get(&name) {
+            Some(proc) => {
+                let return_type = if proc.outputs { "INT" } else { "UNIT" };
+
+                (proc.params.clone(), return_type.to_string())
+            }
+            None => {
+                let message = "Procedures are expected to be defined at the start of a line";
+                return Err(self.syntax_error(message));
+            }
+        };
+
+        // skipping the `:PARAM` tokens (already collected by `scan_procs`)
+        self.pos += params.len() * 2;
+
+        if self.peek() != &Token::NEWLINE {
+            let message = "Procedure params are expected as `:NAME` words";
+            return Err(self.syntax_error(message));
+        }
+
+        self.locals = Some(params.iter().cloned().collect());
+        self.pending_locals.clear();
+
+        let mut block = BlockStatement::new();
+
+        loop {
+            match self.peek() {
+                Token::VALUE(word) if word == "END" => break,
+                Token::EOF => {
+                    return Err(self.error(ParseError::UnexpectedToken {
+                        expected: Token::VALUE("END".to_string()),
+                        actual: Token::EOF,
+                    }))
+                }
+                _ => block.add_statement(self.parse_statement()?),
+            }
+        }
+
+        self.skip(); // skipping the `END` token
+        self.locals = None;
+
+        let params = params
+            .into_iter()
+            .map(|param_name| ProcParam {
+                param_name,
+                param_type: "INT".to_string(),
+                rest: false,
+            })
+            .collect();
+
+        let proc_stmt = ProcedureStmt {
+            id: None,
+            name,
+            block,
+            params,
+            return_type,
+            doc: None,
+        };
+
+        Ok(Statement::Procedure(proc_stmt))
+    }
+
+    fn parse_repeat_stmt(&mut self) -> ClassicResult<Statement> {
+        self.skip(); // skipping the `REPEAT` token
+
+        let count_expr = self.parse_expr()?;
+        let block = self.parse_block()?;
+
+        Ok(Statement::Repeat(RepeatStmt { count_expr, block }))
+    }
+
+    // `IF cond [...]`, `IF cond [...] [...]` (UCBLogo) or `IFELSE cond [...] [...]`
+    fn parse_if_stmt(&mut self) -> ClassicResult<Statement> {
+        let keyword = self.expect_word()?;
+
+        let cond_expr = self.parse_expr()?;
+        let true_block = self.parse_block()?;
+
+        let false_block = match (keyword.as_str(), self.peek()) {
+            ("IFELSE", _) | (_, Token::LBRACKET) => Some(self.parse_block()?),
+            _ => None,
+        };
+
+        let if_stmt = IfStmt {
+            cond_expr,
+            true_block,
+            false_block,
+        };
+
+        Ok(Statement::If(if_stmt))
+    }
+
+    fn parse_make_stmt(&mut self) -> ClassicResult<Statement> {
+        let keyword = self.expect_word()?;
+        let var_name = self.expect_quoted_word()?;
+
+        if keyword == "LOCALMAKE" {
+            self.pending_locals.insert(var_name.clone());
+        }
+
+        let kind = self.make_kind(&var_name);
+        let expr = self.parse_expr()?;
+
+        let make_stmt = MakeStmt {
+            kind,
+            var_name,
+            var_id: None,
+            expr,
+        };
+
+        Ok(Statement::Make(make_stmt))
+    }
+
+    // `LOCAL "VAR` declares a local, assigned by a later `MAKE`
+    fn parse_local_stmt(&mut self) -> ClassicResult<Statement> {
+        self.skip(); // skipping the `LOCAL` token
+
+        let var_name = self.expect_quoted_word()?;
+
+        if self.locals.is_none() {
+            let message = "`LOCAL` is allowed only within procedures";
+            return Err(self.syntax_error(message));
+        }
+
+        self.pending_locals.insert(var_name);
+
+        Ok(Statement::NOP)
+    }
+
+    // the kind of a `MAKE` of `var_name`
+    // (classic Logo makes no difference between declaring and assigning)
+    fn make_kind(&mut self, var_name: &str) -> MakeStmtKind {
+        match &mut self.locals {
+            Some(locals) => {
+                if self.pending_locals.remove(var_name) {
+                    locals.insert(var_name.to_string());
+                    MakeStmtKind::Local
+                } else if locals.contains(var_name) || self.globals.contains(var_name) {
+                    MakeStmtKind::Assign
+                } else {
+                    locals.insert(var_name.to_string());
+                    MakeStmtKind::Local
+                }
+            }
+            None => {
+                if self.globals.insert(var_name.to_string()) {
+                    MakeStmtKind::Global
+                } else {
+                    MakeStmtKind::Assign
+                }
+            }
+        }
+    }
+
+    // a command whose arg has no tytle counterpart (e.g the color of `SETPC 4`)
+    // the color is either a palette index or an `[r g b]` list
+    fn parse_set_pen_color_stmt(&mut self) -> ClassicResult<Statement> {
+        self.skip(); // skipping the `SETPC` token
+
+        if self.peek() != &Token::LBRACKET {
+            let expr = self.parse_expr()?;
+            return Ok(Statement::SetPenColor(expr));
+        }
+
+        self.skip(); // skipping the `[`
+
+        let mut channels = Vec::new();
+
+        for _ in 0..3 {
+            channels.push(self.parse_expr()?);
+        }
+
+        self.expect_token(Token::RBRACKET)?;
+
+        let ast = ExpressionAst::ProcCall("RGB".to_string(), channels);
+        Ok(Statement::SetPenColor(Expression::new(ast)))
+    }
+
+    fn parse_dropped_arg_cmd(&mut self, cmd: Command) -> ClassicResult<Statement> {
+        self.skip(); // skipping the command token
+
+        if self.peek() == &Token::LBRACKET {
+            self.skip_bracketed()?;
+        } else {
+            self.parse_expr()?;
+        }
+
+        Ok(Statement::Command(cmd))
+    }
+
+    fn parse_block(&mut self) -> ClassicResult<BlockStatement> {
+        self.expect_token(Token::LBRACKET)?;
+
+        let mut block = BlockStatement::new();
+
+        loop {
+            match self.peek() {
+                Token::RBRACKET => break,
+                Token::EOF => {
+                    return Err(self.error(ParseError::UnexpectedToken {
+                        expected: Token::RBRACKET,
+                        actual: Token::EOF,
+                    }))
+                }
+                _ => block.add_statement(self.parse_statement()?),
+            }
+        }
+
+        self.skip(); // skipping the `]`
+
+        Ok(block)
+    }
+
+    // classic Logo has no precedence levels beyond these (and no `AND` / `OR` infix operators)
+    fn parse_expr(&mut self) -> ClassicResult<Expression> {
+        let left_expr = self.parse_add_expr()?;
+
+        let op = match self.peek() {
+            Token::GT => BinaryOp::GreaterThan,
+            Token::ASSIGN => BinaryOp::Equal,
+            Token::LT if self.peek_nth(1) == &Token::GT => {
+                self.skip(); // skipping the `<` of `<>`
+                BinaryOp::NotEqual
+            }
+            Token::LT => BinaryOp::LessThan,
+            _ => return Ok(left_expr),
+        };
+
+        self.skip(); // skipping the operator
+
+        let right_expr = self.parse_add_expr()?;
+
+        Ok(Self::binary(op, left_expr, right_expr))
+    }
+
+    fn parse_add_expr(&mut self) -> ClassicResult<Expression> {
+        let mut expr = self.parse_mul_div_expr()?;
+
+        while self.peek() == &Token::ADD {
+            self.skip(); // skipping the `+`
+
+            let right_expr = self.parse_mul_div_expr()?;
+            expr = Self::binary(BinaryOp::Add, expr, right_expr);
+        }
+
+        Ok(expr)
+    }
+
+    fn parse_mul_div_expr(&mut self) -> ClassicResult<Expression> {
+        let mut expr = self.parse_primary_expr()?;
+
+        loop {
+            let op = match self.peek() {
+                Token::MUL => BinaryOp::Mul,
+                Token::DIV => BinaryOp::Div,
+                _ => return Ok(expr),
+            };
+
+            self.skip(); // skipping the `*` or `/`
+
+            let right_expr = self.parse_primary_expr()?;
+            expr = Self::binary(op, expr, right_expr);
+        }
+    }
+
+    fn parse_primary_expr(&mut self) -> ClassicResult<Expression> {
+        let word = match self.peek().clone() {
+            Token::LPAREN => {
+                self.skip(); // skipping the `(`
+
+                let inner_expr = self.parse_expr()?;
+                self.expect_token(Token::RPAREN)?;
+
+                let ast = ExpressionAst::Parentheses(Box::new(inner_expr));
+                return Ok(Expression::new(ast));
+            }
+            Token::COLON => {
+                self.skip(); // skipping the `:`
+
+                let var_name = self.expect_word()?;
+                let ast = ExpressionAst::Literal(LiteralExpr::Var(var_name));
+
+                return Ok(Expression::new(ast));
+            }
+            // the prefix forms `AND a b`, `OR a b` and `NOT a`
+            Token::AND | Token::OR => {
+                let op = match self.pop() {
+                    Token::AND => BinaryOp::And,
+                    _ => BinaryOp::Or,
+                };
+
+                let left_expr = self.parse_expr()?;
+                let right_expr = self.parse_expr()?;
+
+                return Ok(Self::binary(op, left_expr, right_expr));
+            }
+            Token::NOT => {
+                self.skip(); // skipping the `NOT`
+
+                let inner_expr = self.parse_expr()?;
+                return Ok(Expression::new(ExpressionAst::Not(Box::new(inner_expr))));
+            }
+            // a list of instructions (e.g `RUN [FD 10 RT 90]`) is a block
+            Token::LBRACKET => {
+                let block = self.parse_block()?;
+                return Ok(Expression::new(ExpressionAst::Block(block)));
+            }
+            Token::VALUE(word) => word,
+            token => {
+                return Err(self.syntax_error(&format!("Invalid syntax: `{}`", token.to_string())))
+            }
+        };
+
+        if let Ok(num) = word.parse::<usize>() {
+            self.skip();
+            return Ok(Expression::new(ExpressionAst::Literal(LiteralExpr::Int(
+                num,
+            ))));
+        }
+
+        if let Some(s) = word.strip_prefix('"') {
+            self.skip();
+
+            let lit_expr = LiteralExpr::Str(s.to_string());
+            return Ok(Expression::new(ExpressionAst::Literal(lit_expr)));
+        }
+
+        match word.as_str() {
+            "TRUE" | "FALSE" => {
+                self.skip();
+
+                let lit_expr = LiteralExpr::Bool(word == "TRUE");
+                Ok(Expression::new(ExpressionAst::Literal(lit_expr)))
+            }
+            "IFELSE" => self.parse_if_else_expr(),
+            _ if self.procs.contains_key(&word) => self.parse_proc_call_expr(&word),
+            _ => Err(self.unknown_word_error(&word)),
+        }
+    }
+
+    // `IFELSE cond [expr] [expr]` as an operand
+    fn parse_if_else_expr(&mut self) -> ClassicResult<Expression> {
+        self.skip(); // skipping the `IFELSE`
+
+        let cond_expr = self.parse_expr()?;
+
+        let mut branches = Vec::with_capacity(2);
+
+        for _ in 0..2 {
+            self.expect_token(Token::LBRACKET)?;
+            branches.push(self.parse_expr()?);
+            self.expect_token(Token::RBRACKET)?;
+        }
+
+        let false_expr = branches.pop().unwrap();
+        let true_expr = branches.pop().unwrap();
+
+        let ast = ExpressionAst::IfElse(
+            Box::new(cond_expr),
+            Box::new(true_expr),
+            Box::new(false_expr),
+        );
+
+        Ok(Expression::new(ast))
+    }
+
+    // a call of a procedure without parentheses, consuming an arg per procedure param
+    fn parse_proc_call_expr(&mut self, proc_name: &str) -> ClassicResult<Expression> {
+        self.skip(); // skipping the procedure name
+
+        let params_count = self.procs[proc_name].params.len();
+        let mut args = Vec::with_capacity(params_count);
+
+        for _ in 0..params_count {
+            args.push(self.parse_expr()?);
+        }
+
+        let ast = ExpressionAst::ProcCall(proc_name.to_string(), args);
+        Ok(Expression::new(ast))
+    }
+
+    fn binary(op: BinaryOp, left_expr: Expression, right_expr: Expression) -> Expression {
+        let ast = ExpressionAst::Binary(op, Box::new(left_expr), Box::new(right_expr));
+
+        Expression::new(ast)
+    }
+
+    fn direction(word: &str) -> Option<Direction> {
+        let direction = match word {
+            "FORWARD" | "FD" => Direction::Forward,
+            "BACKWARD" | "BACK" | "BK" => Direction::Backward,
+            "RIGHT" | "RT" => Direction::Right,
+            "LEFT" | "LT" => Direction::Left,
+            "SETX" => Direction::SetX,
+            "SETY" => Direction::SetY,
+            _ => return None,
+        };
+
+        Some(direction)
+    }
+
+    fn command(word: &str) -> Option<Command> {
+        let cmd = match word {
+            "PENUP" | "PU" => Command::PenUp,
+            "PENDOWN" | "PD" => Command::PenDown,
+            "PENERASE" | "PE" => Command::PenErase,
+            "HIDETURTLE" | "HT" => Command::HideTurtle,
+            "SHOWTURTLE" | "ST" => Command::ShowTurtle,
+            "CLEARSCREEN" | "CS" => Command::ClearScreen,
+            "CLEAN" => Command::Clean,
+            _ => return None,
+        };
+
+        Some(cmd)
+    }
+
+    // skips a (possibly nested) `[...]` list
+    fn skip_bracketed(&mut self) -> ClassicResult<()> {
+        let mut depth = 0;
+
+        loop {
+            match self.pop() {
+                Token::LBRACKET => depth += 1,
+                Token::RBRACKET => depth -= 1,
+                Token::EOF => {
+                    return Err(self.error(ParseError::UnexpectedToken {
+                        expected: Token::RBRACKET,
+                        actual: Token::EOF,
+                    }))
+                }
+                _ => {}
+            }
+
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    fn expect_word(&mut self) -> ClassicResult<String> {
+        match self.peek().clone() {
+            Token::VALUE(word) if !word.starts_with('"') => {
+                self.skip();
+                Ok(word)
+            }
+            _ => Err(self.error(ParseError::IdentifierExpected)),
+        }
+    }
+
+    // a variable name given as a quoted word (e.g the `"SIZE` of `MAKE "SIZE 10`)
+    fn expect_quoted_word(&mut self) -> ClassicResult<String> {
+        match self.peek().clone() {
+            Token::VALUE(word) if word.len() > 1 && word.starts_with('"') => {
+                self.skip();
+                Ok(word[1..].to_uppercase())
+            }
+            _ => Err(self.error(ParseError::IdentifierExpected)),
+        }
+    }
+
+    fn expect_token(&mut self, expected: Token) -> ClassicResult<()> {
+        let actual = self.peek().clone();
+
+        if actual == expected {
+            self.skip();
+            Ok(())
+        } else {
+            Err(self.error(ParseError::UnexpectedToken { expected, actual }))
+        }
+    }
+
+    fn unknown_word_error(&self, word: &str) -> (ParseError, Location) {
+        // tytle has neither a subtraction operator nor negative numbers
+        if UNSUPPORTED.contains(&word) || word.starts_with('-') {
+            return self.unsupported(word);
+        }
+
+        let words = STATEMENT_WORDS
+            .iter()
+            .copied()
+            .chain(self.procs.keys().map(String::as_str));
+
+        let err = ParseError::UnknownCommand {
+            name: word.to_string(),
+            suggestion: crate::diagnostics::suggest(word, words),
+        };
+
+        self.error(err)
+    }
+
+    fn unsupported(&self, construct: &str) -> (ParseError, Location) {
+        self.error(ParseError::Unsupported(construct.to_string()))
+    }
+
+    fn syntax_error(&self, message: &str) -> (ParseError, Location) {
+        let message = message.to_string();
+
+        self.error(ParseError::Syntax { message })
+    }
+
+    // the error is located at the current token
+    fn error(&self, err: ParseError) -> (ParseError, Location) {
+        let location = self
+            .tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map(|(_, location)| *location)
+            .unwrap_or_default();
+
+        (err, location)
+    }
+
+    fn peek(&self) -> &Token {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, nth: usize) -> &Token {
+        self.tokens
+            .get(self.pos + nth)
+            .map(|(token, _)| token)
+            .unwrap_or(&Token::EOF)
+    }
+
+    fn pop(&mut self) -> Token {
+        let token = self.peek().clone();
+        self.skip();
+
+        token
+    }
+
+    fn skip(&mut self) {
+        self.pos += 1;
+    }
+}
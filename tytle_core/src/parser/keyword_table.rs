@@ -0,0 +1,268 @@
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
+use core::fmt;
+
+// the canonical (English) words a `KeywordTable` may translate
+const CANONICAL_KEYWORDS: &[&str] = &[
+    "AND",
+    "APPLY",
+    "ASSERT",
+    "BACKWARD",
+    "BEEP",
+    "CATCH",
+    "CLEAN",
+    "CLEARSCREEN",
+    "CLOSE",
+    "CONST",
+    "EMPTYP",
+    "END",
+    "FALSE",
+    "FOREACH",
+    "FORWARD",
+    "HALT",
+    "HIDETURTLE",
+    "IF",
+    "IFELSE",
+    "LABEL",
+    "LEFT",
+    "LISTP",
+    "LOAD",
+    "MAKE",
+    "MAKEGLOBAL",
+    "MAKELOCAL",
+    "MAP",
+    "NOT",
+    "NUMBERP",
+    "ONKEY",
+    "ONTICK",
+    "OPENWRITE",
+    "OR",
+    "PENDOWN",
+    "PENERASE",
+    "PENUP",
+    "POPSTATE",
+    "PRINT",
+    "PRINTF",
+    "PROC",
+    "PUSHSTATE",
+    "REPEAT",
+    "RETRIEVE",
+    "RETURN",
+    "RGB",
+    "RIGHT",
+    "RUN",
+    "SETBACKGROUND",
+    "SETLABELFONT",
+    "SETLABELSIZE",
+    "SETPENCOLOR",
+    "SETSHAPE",
+    "SETWRITE",
+    "SETX",
+    "SETY",
+    "SHOWTURTLE",
+    "STAMP",
+    "STOP",
+    "STORE",
+    "THROW",
+    "TO",
+    "TONE",
+    "TRACE",
+    "TRAP",
+    "TRUE",
+    "TYPEOF",
+    "UNTRACE",
+    "WAIT",
+    "WORDP",
+    "XCOR",
+    "YCOR",
+];
+
+#[derive(Debug, PartialEq)]
+pub enum KeywordTableError {
+    UnknownKeyword(String),
+    ConflictingTranslation(String),
+}
+
+impl fmt::Display for KeywordTableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeywordTableError::UnknownKeyword(kw) => write!(f, "Unknown keyword: `{}`", kw),
+            KeywordTableError::ConflictingTranslation(word) => {
+                write!(f, "Conflicting keyword translation: `{}`", word)
+            }
+        }
+    }
+}
+
+// translated keywords (e.g `AVANZA` for `FORWARD`), accepted by the lexer in addition to the canonical ones.
+// translated words are replaced by their canonical keyword while tokenizing,
+// so the rest of the pipeline only ever sees the canonical forms
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeywordTable {
+    words: HashMap<String, String>,
+}
+
+impl KeywordTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spanish() -> Self {
+        let mut table = Self::new();
+
+        let words = [
+            ("AVANZA", "FORWARD"),
+            ("RETROCEDE", "BACKWARD"),
+            ("GIRADERECHA", "RIGHT"),
+            ("GIRAIZQUIERDA", "LEFT"),
+            ("PONX", "SETX"),
+            ("PONY", "SETY"),
+            ("REPITE", "REPEAT"),
+            ("SI", "IF"),
+            ("SISINO", "IFELSE"),
+            ("PARA", "TO"),
+            ("FIN", "END"),
+            ("DEVUELVE", "RETURN"),
+            ("HAZ", "MAKE"),
+            ("HAZGLOBAL", "MAKEGLOBAL"),
+            ("HAZLOCAL", "MAKELOCAL"),
+            ("CONSTANTE", "CONST"),
+            ("IMPRIME", "PRINT"),
+            ("IMPRIMEF", "PRINTF"),
+            ("GUARDA", "STORE"),
+            ("RECUPERA", "RETRIEVE"),
+            ("CARGA", "LOAD"),
+            ("DETENTE", "HALT"),
+            ("ALTO", "STOP"),
+            ("ESPERA", "WAIT"),
+            ("SUBELAPIZ", "PENUP"),
+            ("BAJALAPIZ", "PENDOWN"),
+            ("GOMA", "PENERASE"),
+            ("LIMPIA", "CLEAN"),
+            ("BORRAPANTALLA", "CLEARSCREEN"),
+            ("OCULTATORTUGA", "HIDETURTLE"),
+            ("MUESTRATORTUGA", "SHOWTURTLE"),
+            ("PONCOLORLAPIZ", "SETPENCOLOR"),
+            ("PONFONDO", "SETBACKGROUND"),
+            ("ESTAMPA", "STAMP"),
+            ("ROTULA", "LABEL"),
+            ("PONFORMA", "SETSHAPE"),
+            ("ABREESCRITURA", "OPENWRITE"),
+            ("PONESCRITURA", "SETWRITE"),
+            ("CIERRA", "CLOSE"),
+            ("TONO", "TONE"),
+            ("PITIDO", "BEEP"),
+            ("GUARDAESTADO", "PUSHSTATE"),
+            ("RESTAURAESTADO", "POPSTATE"),
+            ("EJECUTA", "RUN"),
+            ("VERDADERO", "TRUE"),
+            ("FALSO", "FALSE"),
+            ("Y", "AND"),
+            ("O", "OR"),
+            ("NO", "NOT"),
+        ];
+
+        for (word, keyword) in words.iter() {
+            table.add(word, keyword).unwrap();
+        }
+
+        table
+    }
+
+    // registers `word` as a translation of the canonical `keyword`.
+    // a word can translate a single keyword, and can't be a canonical keyword by itself
+    pub fn add(&mut self, word: &str, keyword: &str) -> Result<(), KeywordTableError> {
+        if !CANONICAL_KEYWORDS.contains(&keyword) {
+            return Err(KeywordTableError::UnknownKeyword(keyword.to_string()));
+        }
+
+        let conflicting = match self.words.get(word) {
+            Some(existing) => existing != keyword,
+            None => CANONICAL_KEYWORDS.contains(&word) && word != keyword,
+        };
+
+        if conflicting {
+            return Err(KeywordTableError::ConflictingTranslation(word.to_string()));
+        }
+
+        self.words.insert(word.to_string(), keyword.to_string());
+
+        Ok(())
+    }
+
+    // the canonical keyword `word` translates (if any)
+    pub fn canonical(&self, word: &str) -> Option<&str> {
+        self.words.get(word).map(|keyword| keyword.as_str())
+    }
+
+    // the words translating `keyword`, sorted
+    pub fn translations(&self, keyword: &str) -> Vec<&str> {
+        let mut words = self
+            .words
+            .iter()
+            .filter(|(_, kw)| *kw == keyword)
+            .map(|(word, _)| word.as_str())
+            .collect::<Vec<_>>();
+
+        words.sort();
+        words
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn keyword_table_add() {
+        let mut table = KeywordTable::new();
+
+        table.add("AVANZA", "FORWARD").unwrap();
+        table.add("AV", "FORWARD").unwrap();
+
+        assert_eq!(Some("FORWARD"), table.canonical("AVANZA"));
+        assert_eq!(None, table.canonical("FORWARD"));
+        assert_eq!(vec!["AV", "AVANZA"], table.translations("FORWARD"));
+    }
+
+    #[test]
+    pub fn keyword_table_unknown_keyword() {
+        let mut table = KeywordTable::new();
+
+        let err = table.add("SALTA", "JUMP").unwrap_err();
+
+        assert_eq!(KeywordTableError::UnknownKeyword("JUMP".to_string()), err);
+        assert_eq!("Unknown keyword: `JUMP`", err.to_string());
+    }
+
+    #[test]
+    pub fn keyword_table_conflicting_translation() {
+        let mut table = KeywordTable::new();
+        table.add("AVANZA", "FORWARD").unwrap();
+
+        let err = table.add("AVANZA", "BACKWARD").unwrap_err();
+        assert_eq!(
+            KeywordTableError::ConflictingTranslation("AVANZA".to_string()),
+            err
+        );
+        assert_eq!("Conflicting keyword translation: `AVANZA`", err.to_string());
+
+        let err = table.add("TO", "END").unwrap_err();
+        assert_eq!(
+            KeywordTableError::ConflictingTranslation("TO".to_string()),
+            err
+        );
+    }
+
+    #[test]
+    pub fn keyword_table_spanish() {
+        let table = KeywordTable::spanish();
+
+        assert_eq!(Some("REPEAT"), table.canonical("REPITE"));
+        assert_eq!(Some("IF"), table.canonical("SI"));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::compat::prelude::*;
+use crate::parser::ParseError;
+use core::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum LoadError {
+    FileNotFound(String),
+    CircularLoad(Vec<String>),
+    DuplicateProc {
+        proc_name: String,
+        first_file: String,
+        second_file: String,
+    },
+    Parse {
+        file: String,
+        error: ParseError,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::FileNotFound(file) => write!(f, "File not found: `{}`", file),
+            LoadError::CircularLoad(chain) => write!(f, "Circular load: `{}`", chain.join(" -> ")),
+            LoadError::DuplicateProc {
+                proc_name,
+                first_file,
+                second_file,
+            } => write!(
+                f,
+                "Duplicate procedure: `{}` (defined in `{}` and in `{}`)",
+                proc_name, first_file, second_file
+            ),
+            LoadError::Parse { file, error } => write!(f, "{} (in `{}`)", error.to_string(), file),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn load_error_file_not_found() {
+        let err = LoadError::FileNotFound("LIB".to_string());
+
+        assert_eq!("File not found: `LIB`", err.to_string());
+    }
+
+    #[test]
+    pub fn load_error_circular_load() {
+        let chain = vec!["A".to_string(), "B".to_string(), "A".to_string()];
+        let err = LoadError::CircularLoad(chain);
+
+        assert_eq!("Circular load: `A -> B -> A`", err.to_string());
+    }
+
+    #[test]
+    pub fn load_error_duplicate_proc() {
+        let err = LoadError::DuplicateProc {
+            proc_name: "SQUARE".to_string(),
+            first_file: "A".to_string(),
+            second_file: "B".to_string(),
+        };
+
+        assert_eq!(
+            "Duplicate procedure: `SQUARE` (defined in `A` and in `B`)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    pub fn load_error_parse() {
+        let err = LoadError::Parse {
+            file: "LIB".to_string(),
+            error: ParseError::MissingColon,
+        };
+
+        assert_eq!("Missing colon (in `LIB`)", err.to_string());
+    }
+}
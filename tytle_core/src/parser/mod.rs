@@ -1,7 +1,19 @@
+mod classic_parser;
+mod keyword_table;
+mod load_error;
 mod parse;
 mod parse_error;
+mod parser_config;
+mod source_loader;
+mod source_resolver;
 mod tytle_parser;
 
+pub use classic_parser::ClassicParser;
+pub use keyword_table::{KeywordTable, KeywordTableError};
+pub use load_error::LoadError;
 pub use parse::{Parser, ParserResult};
 pub use parse_error::ParseError;
+pub use parser_config::ParserConfig;
+pub use source_loader::{SourceLoader, MAIN_FILE};
+pub use source_resolver::{MapResolver, SourceResolver};
 pub use tytle_parser::TytleParser;
@@ -1,3 +1,4 @@
+use crate::compat::prelude::*;
 use crate::lexer::Token;
 
 #[derive(Debug, PartialEq)]
@@ -8,10 +9,23 @@ pub enum ParseError {
     MissingProcReturnType,
     InvalidDataType(String),
     InvalidIdentifierDeclaration(String),
-    UnexpectedToken { expected: Token, actual: Token },
-    UnexpectedKeyword { keyword: String },
+    UnexpectedToken {
+        expected: Token,
+        actual: Token,
+    },
+    UnexpectedKeyword {
+        keyword: String,
+    },
     ReservedKeyword(String),
-    Syntax { message: String },
+    UnknownCommand {
+        name: String,
+        suggestion: Option<String>,
+    },
+    Syntax {
+        message: String,
+    },
+    // a classic Logo construct having no tytle counterpart (see `ClassicParser`)
+    Unsupported(String),
 }
 
 impl ToString for ParseError {
@@ -37,7 +51,21 @@ impl ToString for ParseError {
                 format!("Unexpected keyword: `{}`", keyword)
             }
             ParseError::ReservedKeyword(ref kw) => format!("Reserved keyword: `{}`", kw),
+            ParseError::UnknownCommand { ref name, .. } => format!("Unknown command: `{}`", name),
             ParseError::Syntax { ref message } => format!("Syntax error: `{}`", message),
+            ParseError::Unsupported(ref construct) => {
+                format!("Unsupported construct: `{}`", construct)
+            }
+        }
+    }
+}
+
+impl ParseError {
+    // a likely intended keyword, for errors caused by a misspelled one
+    pub fn suggestion(&self) -> Option<&str> {
+        match self {
+            ParseError::UnknownCommand { suggestion, .. } => suggestion.as_deref(),
+            _ => None,
         }
     }
 }
@@ -118,6 +146,17 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn parse_error_unknown_command() {
+        let err = ParseError::UnknownCommand {
+            name: "FORWRD".to_string(),
+            suggestion: Some("FORWARD".to_string()),
+        };
+
+        assert_eq!(Some("FORWARD"), err.suggestion());
+        assert_parse_err("Unknown command: `FORWRD`", err);
+    }
+
     #[test]
     pub fn parse_error_syntax() {
         assert_parse_err(
@@ -127,4 +166,12 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    pub fn parse_error_unsupported() {
+        assert_parse_err(
+            "Unsupported construct: `SETHEADING`",
+            ParseError::Unsupported("SETHEADING".to_string()),
+        );
+    }
 }
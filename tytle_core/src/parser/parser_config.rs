@@ -0,0 +1,45 @@
+use crate::lexer::DEFAULT_TAB_WIDTH;
+use crate::parser::KeywordTable;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParserConfig {
+    // translated keywords accepted in addition to the canonical (English) ones
+    pub keywords: KeywordTable,
+
+    // the number of columns between tab stops, used for computing the `Location` columns
+    pub tab_width: usize,
+
+    // whether the code is classic (UCBLogo / FMSLogo) Logo, parsed by the `ClassicParser`
+    pub classic: bool,
+}
+
+impl ParserConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn keywords(mut self, keywords: KeywordTable) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    pub fn tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    pub fn classic(mut self, classic: bool) -> Self {
+        self.classic = classic;
+        self
+    }
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            keywords: KeywordTable::default(),
+            tab_width: DEFAULT_TAB_WIDTH,
+            classic: false,
+        }
+    }
+}
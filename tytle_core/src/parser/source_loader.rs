@@ -0,0 +1,127 @@
+use crate::ast::statement::Statement;
+use crate::ast::Ast;
+use crate::compat::prelude::*;
+use crate::diagnostics::SourceMap;
+use crate::lexer::DEFAULT_TAB_WIDTH;
+use crate::parser::{LoadError, Parser, SourceResolver, TytleParser};
+
+use crate::compat::collections::{HashMap, HashSet};
+
+// the name given to the entry code when it isn't loaded from a file (see `SourceLoader::load_code`)
+pub const MAIN_FILE: &str = "__main__";
+
+// parses a multi-file program, expanding each `LOAD "file` statement in-place with the file's statements.
+// a file is loaded only once, even when it's loaded by a few files.
+// the loaded files are registered (in loading order) in the loader's `SourceMap`
+pub struct SourceLoader<'r> {
+    resolver: &'r dyn SourceResolver,
+    loaded: HashSet<String>,
+    loading: Vec<String>,
+    procs_files: HashMap<String, String>,
+    source_map: SourceMap,
+}
+
+impl<'r> SourceLoader<'r> {
+    pub fn new(resolver: &'r dyn SourceResolver) -> Self {
+        Self {
+            resolver,
+            loaded: HashSet::new(),
+            loading: Vec::new(),
+            procs_files: HashMap::new(),
+            source_map: SourceMap::empty(DEFAULT_TAB_WIDTH),
+        }
+    }
+
+    pub fn load(&mut self, path: &str) -> Result<Ast, LoadError> {
+        let mut ast = Ast::default();
+
+        self.load_file(path, &mut ast.statements)?;
+
+        Ok(ast)
+    }
+
+    pub fn load_code(&mut self, code: &str) -> Result<Ast, LoadError> {
+        let mut ast = Ast::default();
+
+        self.loaded.insert(MAIN_FILE.to_string());
+        self.load_source(MAIN_FILE, code, &mut ast.statements)?;
+
+        Ok(ast)
+    }
+
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    pub fn into_source_map(self) -> SourceMap {
+        self.source_map
+    }
+
+    fn load_file(&mut self, path: &str, stmts: &mut Vec<Statement>) -> Result<(), LoadError> {
+        if self.loading.iter().any(|file| file == path) {
+            let mut chain = self.loading.clone();
+            chain.push(path.to_string());
+
+            return Err(LoadError::CircularLoad(chain));
+        }
+
+        if self.loaded.contains(path) {
+            return Ok(());
+        }
+
+        let code = self
+            .resolver
+            .resolve(path)
+            .ok_or_else(|| LoadError::FileNotFound(path.to_string()))?;
+
+        self.loaded.insert(path.to_string());
+        self.load_source(path, &code, stmts)
+    }
+
+    fn load_source(
+        &mut self,
+        path: &str,
+        code: &str,
+        stmts: &mut Vec<Statement>,
+    ) -> Result<(), LoadError> {
+        self.source_map.add_file(path, code);
+
+        let ast = TytleParser.parse(code).map_err(|error| LoadError::Parse {
+            file: path.to_string(),
+            error,
+        })?;
+
+        self.loading.push(path.to_string());
+
+        for stmt in ast.statements {
+            match stmt {
+                Statement::Load(ref load_path) => self.load_file(load_path, stmts)?,
+                Statement::EOF => continue,
+                Statement::Procedure(ref proc_stmt) => {
+                    self.register_proc(&proc_stmt.name, path)?;
+                    stmts.push(stmt);
+                }
+                _ => stmts.push(stmt),
+            }
+        }
+
+        self.loading.pop();
+
+        Ok(())
+    }
+
+    fn register_proc(&mut self, proc_name: &str, path: &str) -> Result<(), LoadError> {
+        if let Some(first_file) = self.procs_files.get(proc_name) {
+            return Err(LoadError::DuplicateProc {
+                proc_name: proc_name.to_string(),
+                first_file: first_file.clone(),
+                second_file: path.to_string(),
+            });
+        }
+
+        self.procs_files
+            .insert(proc_name.to_string(), path.to_string());
+
+        Ok(())
+    }
+}
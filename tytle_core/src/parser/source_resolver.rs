@@ -0,0 +1,29 @@
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
+
+// implemented by the embedder for providing the contents of the files loaded by `LOAD "file`
+pub trait SourceResolver {
+    fn resolve(&self, path: &str) -> Option<String>;
+}
+
+// an in-memory `SourceResolver` (useful for tests and for the browser playground)
+#[derive(Debug, Default)]
+pub struct MapResolver {
+    files: HashMap<String, String>,
+}
+
+impl MapResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_file(&mut self, path: &str, code: &str) {
+        self.files.insert(path.to_string(), code.to_string());
+    }
+}
+
+impl SourceResolver for MapResolver {
+    fn resolve(&self, path: &str) -> Option<String> {
+        self.files.get(path).cloned()
+    }
+}
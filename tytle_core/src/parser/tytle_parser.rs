@@ -2,10 +2,11 @@ use crate::ast::expression::*;
 use crate::ast::statement::*;
 use crate::ast::Ast;
 
+use crate::compat::prelude::*;
 use crate::lexer::{Lexer, Location, Token, TytleLexer};
-use crate::parser::{ParseError, Parser, ParserResult};
+use crate::parser::{ClassicParser, ParseError, Parser, ParserConfig, ParserResult};
 
-use std::collections::HashSet;
+use crate::compat::collections::HashSet;
 
 lazy_static! {
     static ref KEYWORDS: HashSet<&'static str> = {
@@ -16,16 +17,34 @@ lazy_static! {
         kws.insert("MAKEGLOBAL");
         kws.insert("MAKELOCAL");
         kws.insert("MAKE");
+        kws.insert("CONST");
         kws.insert("IF");
+        kws.insert("IFELSE");
         kws.insert("RETURN");
         kws.insert("HALT");
         kws.insert("WAIT");
         kws.insert("REPEAT");
+        kws.insert("FOREACH");
+        kws.insert("MAP");
+        kws.insert("PROC");
+        kws.insert("APPLY");
+        kws.insert("CATCH");
+        kws.insert("THROW");
+        kws.insert("ASSERT");
+        kws.insert("TRACE");
+        kws.insert("UNTRACE");
+        kws.insert("ONKEY");
+        kws.insert("ONTICK");
         kws.insert("TO");
         kws.insert("END");
         kws.insert("AND");
         kws.insert("OR");
         kws.insert("NOT");
+        kws.insert("TYPEOF");
+        kws.insert("NUMBERP");
+        kws.insert("WORDP");
+        kws.insert("LISTP");
+        kws.insert("EMPTYP");
         kws.insert("XCOR");
         kws.insert("YCOR");
         kws.insert("SETX");
@@ -41,11 +60,43 @@ lazy_static! {
         kws.insert("PENERASE");
         kws.insert("XOR");
         kws.insert("YOR");
+        kws.insert("STAMP");
+        kws.insert("LABEL");
+        kws.insert("SETLABELFONT");
+        kws.insert("SETLABELSIZE");
+        kws.insert("SETSHAPE");
+        kws.insert("OPENWRITE");
+        kws.insert("SETWRITE");
+        kws.insert("CLOSE");
+        kws.insert("TONE");
+        kws.insert("BEEP");
+        kws.insert("PUSHSTATE");
+        kws.insert("POPSTATE");
+        kws.insert("RUN");
+        kws.insert("RGB");
         kws.insert("PRINT");
+        kws.insert("PRINTF");
+        kws.insert("STORE");
+        kws.insert("RETRIEVE");
+        kws.insert("LOAD");
         kws
     };
 }
 
+// the token read past the end of the code
+static EOF_TOKEN: (Token, Location) = (Token::EOF, Location(1, 1));
+
+// the words a statement may start with, used for suggesting a replacement to an unknown command
+const STATEMENT_WORDS: &[&str] = &[
+    "PRINT", "LOAD", "TRAP", "HALT", "MAKE", "MAKEGLOBAL", "MAKELOCAL", "CONST", "FORWARD",
+    "BACKWARD", "RIGHT", "LEFT", "SETX", "SETY", "REPEAT", "FOREACH", "IF", "TO", "RETURN", "XCOR", "YCOR",
+    "PENUP", "PENDOWN", "SHOWTURTLE", "HIDETURTLE", "PENERASE", "CLEAN", "CLEARSCREEN",
+    "SETPENCOLOR", "SETBACKGROUND", "WAIT", "ONKEY", "ONTICK", "CATCH",
+    "THROW", "ASSERT", "TRACE", "UNTRACE", "STAMP", "LABEL", "SETLABELFONT", "SETLABELSIZE",
+    "SETSHAPE", "TONE", "BEEP", "PUSHSTATE", "POPSTATE", "RUN", "PRINTF", "STORE", "OPENWRITE",
+    "SETWRITE", "CLOSE",
+];
+
 pub type StatementResult = Result<Statement, ParseError>;
 pub type ExpressionResult = Result<Expression, ParseError>;
 
@@ -60,16 +111,83 @@ impl Parser for TytleParser {
 }
 
 impl TytleParser {
+    // same as `parse`, but on failure returns also the location of the last token read by the parser
+    pub fn parse_located(&mut self, code: &str) -> Result<Ast, (ParseError, Location)> {
+        self.parse_located_with_config(code, &ParserConfig::default())
+    }
+
+    pub fn parse_with_config(&mut self, code: &str, config: &ParserConfig) -> ParserResult {
+        if config.classic {
+            return ClassicParser.parse_located(code, config).map_err(|(err, _loc)| err);
+        }
+
+        let mut lexer = TytleLexer::with_config(code, config);
+
+        self.parse(&mut lexer)
+    }
+
+    pub fn parse_located_with_config(
+        &mut self,
+        code: &str,
+        config: &ParserConfig,
+    ) -> Result<Ast, (ParseError, Location)> {
+        if config.classic {
+            return ClassicParser.parse_located(code, config);
+        }
+
+        let mut lexer = TytleLexer::with_config(code, config);
+
+        self.parse(&mut lexer).map_err(|err| {
+            let location = lexer.last_popped_location().unwrap_or_default();
+
+            (err, location)
+        })
+    }
+
+    // parses `code` as a single standalone expression (e.g `X + 2 > Y`)
+    pub fn parse_expr_code(&mut self, code: &str) -> ExpressionResult {
+        if code.trim().is_empty() {
+            let err = ParseError::Syntax {
+                message: "Expected an expression".to_string(),
+            };
+            return Err(err);
+        }
+
+        let mut lexer = TytleLexer::new(code);
+
+        let expr = self.parse_expr(&mut lexer)?;
+
+        loop {
+            let (token, _loc) = self.pop_current_token(&mut lexer);
+
+            match token {
+                Token::NEWLINE => continue,
+                Token::EOF => return Ok(expr),
+                actual => {
+                    let err = ParseError::UnexpectedToken {
+                        expected: Token::EOF,
+                        actual,
+                    };
+                    return Err(err);
+                }
+            }
+        }
+    }
+
     fn parse(&mut self, lexer: &mut impl Lexer) -> ParserResult {
         let mut ast = Ast::default();
 
         loop {
+            let location = Some(self.peek_current_token(lexer).1);
             let stmt = self.parse_statement(lexer)?;
 
             match stmt {
                 Statement::NOP => continue,
                 Statement::EOF => break,
-                _ => ast.statements.push(stmt),
+                _ => {
+                    ast.statements.push(stmt);
+                    ast.locations.push(location);
+                }
             }
         }
 
@@ -81,12 +199,7 @@ impl TytleParser {
     }
 
     fn parse_statement(&self, lexer: &mut impl Lexer) -> StatementResult {
-        let tok_loc = self.peek_current_token(lexer);
-        if tok_loc.is_none() {
-            return Ok(Statement::EOF);
-        }
-
-        let (token, _location) = tok_loc.unwrap();
+        let (token, _location) = self.peek_current_token(lexer);
 
         match token {
             Token::EOF => Ok(Statement::EOF),
@@ -96,12 +209,22 @@ impl TytleParser {
             }
             Token::VALUE(val) => match val.as_str() {
                 "REPEAT" => self.parse_repeat_stmt(lexer),
+                "FOREACH" => self.parse_foreach_stmt(lexer),
                 "IF" => self.parse_if_stmt(lexer),
-                "TO" => self.parse_proc_stmt(lexer),
+                "TO" => self.parse_proc_stmt(lexer, None),
+                "ONKEY" | "ONTICK" => self.parse_on_event_stmt(lexer),
+                "CATCH" => self.parse_catch_stmt(lexer),
+                "THROW" => self.parse_throw_stmt(lexer),
+                "ASSERT" => self.parse_assert_stmt(lexer),
+                "TRACE" | "UNTRACE" => self.parse_trace_stmt(lexer),
                 "RETURN" => self.parse_ret_stmt(lexer),
                 _ => self.parse_basic_stmt(val.clone().as_str(), lexer),
             },
-            _ => unimplemented!(),
+            Token::DOC(_) => self.parse_doc_comment(lexer),
+            tok => {
+                let message = format!("a statement can't start with `{}`", tok.to_string());
+                Err(ParseError::Syntax { message })
+            }
         }
     }
 
@@ -116,7 +239,28 @@ impl TytleParser {
         Ok(stmt)
     }
 
-    fn parse_proc_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+    // a doc comment documents the procedure right after it, otherwise it's just a comment
+    fn parse_doc_comment(&self, lexer: &mut impl Lexer) -> StatementResult {
+        let mut lines = Vec::new();
+
+        while let (Token::DOC(line), _) = self.peek_current_token(lexer) {
+            lines.push(line.clone());
+            self.skip_token(lexer); // skipping the doc comment line
+
+            if let (Token::NEWLINE, _) = self.peek_current_token(lexer) {
+                self.skip_token(lexer);
+            }
+        }
+
+        match self.peek_current_token(lexer) {
+            (Token::VALUE(val), _) if val == "TO" => {
+                self.parse_proc_stmt(lexer, Some(ProcDoc::parse(&lines)))
+            }
+            _ => Ok(Statement::NOP),
+        }
+    }
+
+    fn parse_proc_stmt(&self, lexer: &mut impl Lexer, doc: Option<ProcDoc>) -> StatementResult {
         self.skip_token(lexer); // skipping the `TO` token
 
         let name = self.expect_value(lexer)?;
@@ -133,12 +277,134 @@ impl TytleParser {
             block,
             params,
             return_type,
+            doc,
         };
 
         let stmt = Statement::Procedure(proc_stmt);
         Ok(stmt)
     }
 
+    fn parse_on_event_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        let keyword = self.expect_value(lexer)?;
+
+        let event = match (keyword.as_str(), self.parse_literal_expr(lexer)?) {
+            ("ONKEY", LiteralExpr::Str(key)) => Event::Key(key),
+            ("ONTICK", LiteralExpr::Int(interval)) if interval > 0 => Event::Tick(interval),
+            ("ONKEY", _) => {
+                let message = "`ONKEY` expects a key name word (e.g `\"LEFT\"`)".to_string();
+                return Err(ParseError::Syntax { message });
+            }
+            _ => {
+                let message = "`ONTICK` expects a positive interval (in milliseconds)".to_string();
+                return Err(ParseError::Syntax { message });
+            }
+        };
+
+        let borders = (Some(Token::LBRACKET), Token::RBRACKET);
+        let block = self.parse_block_stmt(lexer, borders)?;
+
+        let mut proc_stmt = ProcedureStmt::new(event.proc_name());
+        proc_stmt.return_type = "UNIT".to_string();
+        proc_stmt.block = block;
+
+        let stmt = Statement::OnEvent(OnEventStmt { event, proc_stmt });
+        Ok(stmt)
+    }
+
+    fn parse_catch_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `CATCH` token
+
+        let tag = self.parse_tag_word(lexer, "CATCH")?;
+
+        let borders = (Some(Token::LBRACKET), Token::RBRACKET);
+        let block = self.parse_block_stmt(lexer, borders)?;
+
+        let stmt = Statement::Catch(CatchStmt { tag, block });
+        Ok(stmt)
+    }
+
+    fn parse_throw_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `THROW` token
+
+        let tag = self.parse_tag_word(lexer, "THROW")?;
+
+        let stmt = Statement::Throw(tag);
+        Ok(stmt)
+    }
+
+    fn parse_assert_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        let (_tok, location) = self.peek_current_token(lexer);
+        let location = *location;
+
+        self.skip_token(lexer); // skipping the `ASSERT` token
+
+        let cond_expr = self.parse_expr(lexer)?;
+        let message = self.parse_assert_message(lexer);
+
+        let assert_stmt = AssertStmt {
+            cond_expr,
+            message,
+            location,
+        };
+
+        let stmt = Statement::Assert(assert_stmt);
+        Ok(stmt)
+    }
+
+    // the optional message of an `ASSERT`, starting with a `"` and spanning the rest of the line
+    // (e.g `"X must be positive"`), since the lexer splits the message words
+    fn parse_assert_message(&self, lexer: &mut impl Lexer) -> Option<String> {
+        match self.peek_current_token(lexer) {
+            (Token::VALUE(v), _) if v.starts_with('"') => {}
+            _ => return None,
+        }
+
+        let mut words = Vec::new();
+
+        loop {
+            let (tok, _) = self.peek_current_token(lexer);
+
+            match tok {
+                Token::NEWLINE | Token::EOF | Token::RBRACKET => break,
+                _ => words.push(tok.to_string()),
+            }
+
+            self.skip_token(lexer);
+        }
+
+        let message = words.join(" ");
+        let message = message.strip_prefix('"').unwrap_or(&message);
+        let message = message.strip_suffix('"').unwrap_or(message);
+
+        Some(message.to_string())
+    }
+
+    fn parse_trace_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        let keyword = self.expect_value(lexer)?; // `TRACE` / `UNTRACE`
+
+        let proc_name = self.parse_proc_name_word(lexer, &keyword)?;
+
+        let trace_stmt = TraceStmt {
+            proc_name,
+            proc_id: None,
+            enabled: keyword == "TRACE",
+        };
+
+        let stmt = Statement::Trace(trace_stmt);
+        Ok(stmt)
+    }
+
+    // the tag word of `keyword` (e.g `CATCH`)
+    fn parse_tag_word(&self, lexer: &mut impl Lexer, keyword: &str) -> Result<String, ParseError> {
+        match self.parse_literal_expr(lexer)? {
+            LiteralExpr::Str(tag) => Ok(tag),
+            _ => {
+                let message = format!("`{}` expects a tag word (e.g `\"DONE\"`)", keyword);
+                Err(ParseError::Syntax { message })
+            }
+        }
+    }
+
     fn parse_proc_signature(
         &self,
         lexer: &mut impl Lexer,
@@ -150,7 +416,7 @@ impl TytleParser {
         self.expect_token(lexer, Token::LPAREN)?;
 
         while !completed {
-            let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+            let (tok, _loc) = self.peek_current_token(lexer);
 
             if *tok == Token::RPAREN {
                 self.skip_token(lexer); // skipping the `)`
@@ -158,16 +424,32 @@ impl TytleParser {
             } else {
                 let param_name = self.expect_value(lexer)?;
 
+                // a rest param (`NUMS...: INT`) must be the last one
+                if params.last().is_some_and(|param: &ProcParam| param.rest) {
+                    let message = "A rest param must be the last param".to_string();
+                    return Err(ParseError::Syntax { message });
+                }
+
+                let (param_name, rest) = match param_name.strip_suffix("...") {
+                    Some(name) => (name.to_string(), true),
+                    None => (param_name, false),
+                };
+
                 self.validate_name(param_name.as_str())?;
                 self.expect_token(lexer, Token::COLON)?;
 
-                let param_type = self.expect_value(lexer)?;
+                let param_type = self.parse_data_type(lexer)?;
 
-                self.validate_data_type(param_type.as_str())?;
+                let param_type = if rest {
+                    format!("[{}]", param_type)
+                } else {
+                    param_type
+                };
 
                 let param = ProcParam {
                     param_name,
                     param_type,
+                    rest,
                 };
 
                 params.push(param);
@@ -178,23 +460,20 @@ impl TytleParser {
             }
         }
 
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let (tok, _loc) = self.peek_current_token(lexer);
 
         let return_type = if *tok == Token::COLON {
             self.skip_token(lexer); // skipping the `:`
 
-            let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+            let (tok, _loc) = self.peek_current_token(lexer);
 
             if *tok == Token::NEWLINE {
                 return Err(ParseError::MissingProcReturnType);
             } else {
-                let return_type = self.expect_value(lexer)?;
-                self.validate_data_type(return_type.as_str())?;
-
-                return_type
+                self.parse_data_type(lexer)?
             }
         } else {
-            let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+            let (tok, _loc) = self.peek_current_token(lexer);
 
             if *tok == Token::NEWLINE {
                 "UNIT".to_string() // a Procedure with no return value
@@ -206,6 +485,24 @@ impl TytleParser {
         Ok((params, return_type))
     }
 
+    // a data type is either a type keyword (e.g `INT`) or a list type (e.g `[INT]`)
+    fn parse_data_type(&self, lexer: &mut impl Lexer) -> Result<String, ParseError> {
+        if self.peek_current_token_clone(lexer) == Token::LBRACKET {
+            self.skip_token(lexer); // skipping the `[`
+
+            let elem_type = self.parse_data_type(lexer)?;
+
+            self.expect_token(lexer, Token::RBRACKET)?;
+
+            return Ok(format!("[{}]", elem_type));
+        }
+
+        let data_type = self.expect_value(lexer)?;
+        self.validate_data_type(data_type.as_str())?;
+
+        Ok(data_type)
+    }
+
     fn parse_repeat_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
         self.skip_token(lexer); // skipping the `REPEAT` token
 
@@ -218,6 +515,23 @@ impl TytleParser {
         Ok(stmt)
     }
 
+    fn parse_foreach_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `FOREACH` token
+
+        let list_expr = self.parse_expr(lexer)?;
+        let borders = (Some(Token::LBRACKET), Token::RBRACKET);
+        let block = self.parse_block_stmt(lexer, borders)?;
+
+        let foreach_stmt = ForeachStmt {
+            list_expr,
+            block,
+            var_id: None, // we'll assign the iteration variable id in the symbols-generation process
+        };
+
+        let stmt = Statement::Foreach(foreach_stmt);
+        Ok(stmt)
+    }
+
     fn parse_if_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
         self.skip_token(lexer); // skipping the `IF` token
 
@@ -227,15 +541,11 @@ impl TytleParser {
         let true_block = self.parse_block_stmt(lexer, borders.clone())?;
         let mut false_block = None;
 
-        let tok_loc = self.peek_current_token(lexer);
-
-        if tok_loc.is_some() {
-            let (tok, _loc) = tok_loc.unwrap();
+        let (tok, _loc) = self.peek_current_token(lexer);
 
-            if *tok == Token::LBRACKET {
-                let block_stmt = self.parse_block_stmt(lexer, borders.clone())?;
-                false_block = Some(block_stmt);
-            }
+        if *tok == Token::LBRACKET {
+            let block_stmt = self.parse_block_stmt(lexer, borders.clone())?;
+            false_block = Some(block_stmt);
         }
 
         let if_stmt = IfStmt {
@@ -264,11 +574,22 @@ impl TytleParser {
         let mut completed = false;
 
         while !completed {
+            let (tok, location) = self.peek_current_token(lexer);
+            let location = *location;
+
+            // the block is missing its `ending token`
+            if *tok == Token::EOF {
+                return Err(ParseError::UnexpectedToken {
+                    expected: end_tok,
+                    actual: Token::EOF,
+                });
+            }
+
             let stmt = self.parse_statement(lexer)?;
 
-            block.add_statement(stmt);
+            block.add_located_statement(stmt, location);
 
-            let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+            let (tok, _loc) = self.peek_current_token(lexer);
 
             if *tok == end_tok {
                 self.skip_token(lexer); // skipping the block `ending token`
@@ -282,14 +603,28 @@ impl TytleParser {
     fn parse_basic_stmt(&self, val: &str, lexer: &mut impl Lexer) -> StatementResult {
         match val {
             "PRINT" => self.parse_print_stmt(lexer),
+            "PRINTF" => self.parse_printf_stmt(lexer),
+            "STORE" => self.parse_store_stmt(lexer),
+            "LOAD" => self.parse_load_stmt(lexer),
             "TRAP" => self.parse_trap_stmt(lexer),
             "HALT" => self.parse_halt_stmt(lexer),
             "MAKE" => self.parse_make_stmt(lexer),
             "MAKEGLOBAL" => self.parse_make_global_stmt(lexer),
             "MAKELOCAL" => self.parse_make_local_stmt(lexer),
+            "CONST" => self.parse_const_stmt(lexer),
             "FORWARD" | "BACKWARD" | "RIGHT" | "LEFT" | "SETX" | "SETY" => {
                 self.parse_direct_stmt(val, lexer)
             }
+            "LABEL" => self.parse_label_stmt(LabelKind::Text, lexer),
+            "SETLABELFONT" => self.parse_label_stmt(LabelKind::Font, lexer),
+            "SETLABELSIZE" => self.parse_label_stmt(LabelKind::Size, lexer),
+            "SETSHAPE" => self.parse_set_shape_stmt(lexer),
+            "OPENWRITE" => self.parse_stream_stmt(StreamKind::Open, lexer),
+            "SETWRITE" => self.parse_stream_stmt(StreamKind::Set, lexer),
+            "CLOSE" => self.parse_stream_stmt(StreamKind::Close, lexer),
+            "SETPENCOLOR" => self.parse_set_pen_color_stmt(lexer),
+            "TONE" => self.parse_tone_stmt(lexer),
+            "RUN" => self.parse_run_stmt(lexer),
             _ => self.parse_expr_stmt(val, lexer),
         }
     }
@@ -307,6 +642,18 @@ impl TytleParser {
         } else {
             let expr = self.parse_expr(lexer)?;
 
+            // a bare word standing as a statement of its own isn't meaningful,
+            // it's most likely a misspelled command (e.g `FORWRD 10`)
+            if let ExpressionAst::Literal(LiteralExpr::Var(name)) = &expr.expr_ast {
+                let suggestion = crate::diagnostics::suggest(name, STATEMENT_WORDS.iter().copied());
+
+                let err = ParseError::UnknownCommand {
+                    name: name.to_owned(),
+                    suggestion,
+                };
+                return Err(err);
+            }
+
             let stmt = Statement::Expression(expr);
             Ok(stmt)
         }
@@ -324,8 +671,12 @@ impl TytleParser {
         self.build_make_stmt(lexer, MakeStmtKind::Assign)
     }
 
+    fn parse_const_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.build_make_stmt(lexer, MakeStmtKind::Const)
+    }
+
     fn build_make_stmt(&self, lexer: &mut impl Lexer, kind: MakeStmtKind) -> StatementResult {
-        self.skip_token(lexer); // skipping the `MAKE/MAKEGLOBAL/MAKELOCAL` token
+        self.skip_token(lexer); // skipping the `MAKE/MAKEGLOBAL/MAKELOCAL/CONST` token
 
         let var_name = self.expect_value(lexer)?;
 
@@ -365,6 +716,47 @@ impl TytleParser {
         Ok(stmt)
     }
 
+    // `PRINTF(F, ARGS...)` is a shorthand of `PRINT FORMAT(F, ARGS...)`
+    fn parse_printf_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `PRINTF` token
+
+        self.expect_token(lexer, Token::LPAREN)?;
+        let args = self.parse_proc_call_params_expr(lexer)?;
+        self.expect_token(lexer, Token::RPAREN)?;
+
+        let ast = ExpressionAst::ProcCall("FORMAT".to_string(), args);
+        let stmt = Statement::Print(Expression::new(ast));
+        Ok(stmt)
+    }
+
+    // `STORE "name <value>`, a call of the `STORE` primitive
+    fn parse_store_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `STORE` token
+
+        let name_expr = self.parse_expr(lexer)?;
+        let value_expr = self.parse_expr(lexer)?;
+
+        let ast = ExpressionAst::ProcCall("STORE".to_string(), vec![name_expr, value_expr]);
+        let stmt = Statement::Expression(Expression::new(ast));
+        Ok(stmt)
+    }
+
+    fn parse_load_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `LOAD` token
+
+        let path = self.expect_value(lexer)?;
+
+        // both `LOAD "lib` (Logo style) and `LOAD "lib"` are accepted
+        if !path.starts_with('"') || path.len() < 2 {
+            let message = format!("`LOAD` expects a file name (got `{}`)", path);
+            return Err(ParseError::Syntax { message });
+        }
+
+        let path = path[1..].trim_end_matches('"').to_string();
+
+        Ok(Statement::Load(path))
+    }
+
     fn parse_trap_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
         self.skip_token(lexer); // skipping the `TRAP` token
 
@@ -390,62 +782,117 @@ impl TytleParser {
         Ok(stmt)
     }
 
-    fn parse_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
-        let left_expr = self.parse_and_expr(lexer)?;
+    fn parse_label_stmt(&self, kind: LabelKind, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `LABEL` / `SETLABELFONT` / `SETLABELSIZE` token
 
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let expr = self.parse_expr(lexer)?;
 
-        match tok {
-            Token::OR => {
-                self.skip_token(lexer); // we skip the `OR` token
+        let stmt = Statement::Label(LabelStmt { kind, expr });
+        Ok(stmt)
+    }
 
-                let right_expr = self.parse_and_expr(lexer)?;
+    fn parse_stream_stmt(&self, kind: StreamKind, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `OPENWRITE` / `SETWRITE` / `CLOSE` token
 
-                let ast =
-                    ExpressionAst::Binary(BinaryOp::Or, Box::new(left_expr), Box::new(right_expr));
+        let expr = self.parse_expr(lexer)?;
 
-                let expr = Expression::new(ast);
-                Ok(expr)
-            }
-            _ => Ok(left_expr),
+        let stmt = Statement::Stream(StreamStmt { kind, expr });
+        Ok(stmt)
+    }
+
+    fn parse_set_shape_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `SETSHAPE` token
+
+        let expr = self.parse_expr(lexer)?;
+
+        let stmt = Statement::SetShape(expr);
+        Ok(stmt)
+    }
+
+    fn parse_set_pen_color_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `SETPENCOLOR` token
+
+        let expr = self.parse_expr(lexer)?;
+
+        let stmt = Statement::SetPenColor(expr);
+        Ok(stmt)
+    }
+
+    fn parse_tone_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `TONE` token
+
+        let freq_expr = self.parse_expr(lexer)?;
+        let duration_expr = self.parse_expr(lexer)?;
+
+        let stmt = Statement::Tone(ToneStmt {
+            freq_expr,
+            duration_expr,
+        });
+        Ok(stmt)
+    }
+
+    fn parse_run_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `RUN` token
+
+        let expr = self.parse_expr(lexer)?;
+
+        let stmt = Statement::Run(expr);
+        Ok(stmt)
+    }
+
+    // `OR`s (and `AND`s) chain left-associatively (`A OR B OR C` is `(A OR B) OR C`)
+    fn parse_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
+        let mut left_expr = self.parse_and_expr(lexer)?;
+
+        while self.peek_current_token_clone(lexer) == Token::OR {
+            self.skip_token(lexer); // we skip the `OR` token
+
+            let right_expr = self.parse_and_expr(lexer)?;
+
+            let ast =
+                ExpressionAst::Binary(BinaryOp::Or, Box::new(left_expr), Box::new(right_expr));
+
+            left_expr = Expression::new(ast);
         }
+
+        Ok(left_expr)
     }
 
     fn parse_and_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
-        let left_expr = self.parse_cmp_expr(lexer)?;
-
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let mut left_expr = self.parse_cmp_expr(lexer)?;
 
-        match tok {
-            Token::AND => {
-                self.skip_token(lexer); // we skip the `AND` token
+        while self.peek_current_token_clone(lexer) == Token::AND {
+            self.skip_token(lexer); // we skip the `AND` token
 
-                let right_expr = self.parse_cmp_expr(lexer)?;
+            let right_expr = self.parse_cmp_expr(lexer)?;
 
-                let ast =
-                    ExpressionAst::Binary(BinaryOp::And, Box::new(left_expr), Box::new(right_expr));
+            let ast =
+                ExpressionAst::Binary(BinaryOp::And, Box::new(left_expr), Box::new(right_expr));
 
-                let expr = Expression::new(ast);
-                Ok(expr)
-            }
-            _ => Ok(left_expr),
+            left_expr = Expression::new(ast);
         }
+
+        Ok(left_expr)
     }
 
     fn parse_cmp_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
         let left_expr = self.parse_clause_expr(lexer)?;
 
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let (tok, loc) = self.peek_current_token(lexer);
 
         match tok {
-            Token::GT | Token::LT => {
-                let tok = tok.clone();
+            Token::GT | Token::LT | Token::ASSIGN => {
+                let binary_op = if self.is_not_equal_op(lexer, tok, *loc) {
+                    self.skip_token(lexer); // we skip the `<` of the `<>` token
 
-                self.skip_token(lexer); // we skip the `> / >= / < / <= / == / !=` token
+                    BinaryOp::NotEqual
+                } else {
+                    BinaryOp::from(tok)
+                };
 
-                let right_expr = self.parse_clause_expr(lexer)?;
+                self.skip_token(lexer); // we skip the `> / < / = / <>` token
 
-                let binary_op = BinaryOp::from(&tok);
+                let right_expr = self.parse_clause_expr(lexer)?;
 
                 let ast =
                     ExpressionAst::Binary(binary_op, Box::new(left_expr), Box::new(right_expr));
@@ -457,10 +904,22 @@ impl TytleParser {
         }
     }
 
+    // the lexer emits `<>` as a `<` token immediately followed by a `>` token
+    fn is_not_equal_op(&self, lexer: &impl Lexer, tok: &Token, loc: Location) -> bool {
+        if *tok != Token::LT {
+            return false;
+        }
+
+        match self.peek_next_token(lexer) {
+            (Token::GT, next_loc) => *next_loc == Location(loc.0, loc.1 + 1),
+            _ => false,
+        }
+    }
+
     fn parse_clause_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
         let left_expr = self.parse_mul_div_expr(lexer)?;
 
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let (tok, _loc) = self.peek_current_token(lexer);
 
         if *tok == Token::ADD {
             self.skip_token(lexer); // we skip the `+` token
@@ -480,7 +939,7 @@ impl TytleParser {
     fn parse_mul_div_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
         let lparen_expr = self.parse_parens_expr(lexer)?;
 
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let (tok, _loc) = self.peek_current_token(lexer);
 
         let tok = tok.clone();
 
@@ -502,7 +961,7 @@ impl TytleParser {
     }
 
     fn parse_parens_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let (tok, _loc) = self.peek_current_token(lexer);
 
         match tok {
             Token::LPAREN => {
@@ -515,11 +974,208 @@ impl TytleParser {
                 self.expect_token(lexer, Token::RPAREN)?;
                 Ok(expr)
             }
+            Token::LBRACKET if self.is_block_start(lexer) => self.parse_block_expr(lexer),
+            Token::LBRACKET => self.parse_list_expr(lexer),
             Token::NOT => self.parse_not_expr(lexer),
+            Token::VALUE(word) if word == "MAP" => self.parse_map_expr(lexer),
+            Token::VALUE(word) if word == "PROC" => self.parse_proc_ref_expr(lexer),
+            Token::VALUE(word) if word == "APPLY" => self.parse_apply_expr(lexer),
+            Token::VALUE(word) if word == "IFELSE" => self.parse_if_else_expr(lexer),
+            Token::VALUE(word) if word == "RGB" => self.parse_rgb_expr(lexer),
+            Token::VALUE(word) if word == "RETRIEVE" => self.parse_retrieve_expr(lexer),
+            Token::VALUE(word) => match TypeQuery::from_keyword(word) {
+                Some(query) => self.parse_type_query_expr(lexer, query),
+                None => self.parse_basic_expr(lexer),
+            },
             _ => self.parse_basic_expr(lexer),
         }
     }
 
+    // a type query binds tighter than the binary operators (`TYPEOF A = "INT"` is `(TYPEOF A) = "INT"`)
+    fn parse_type_query_expr(&self, lexer: &mut impl Lexer, query: TypeQuery) -> ExpressionResult {
+        self.skip_token(lexer); // skip the query keyword (e.g `TYPEOF`)
+
+        let inner_expr = self.parse_parens_expr(lexer)?;
+
+        let ast = ExpressionAst::TypeQuery(query, Box::new(inner_expr));
+        let expr = Expression::new(ast);
+        Ok(expr)
+    }
+
+    // a bracketed list starting with a statement word (or with a new line) is a block.
+    // `XCOR` and `YCOR` are expressions too, so `[XCOR, YCOR]` remains a list
+    fn is_block_start(&self, lexer: &impl Lexer) -> bool {
+        match self.peek_next_token(lexer) {
+            (Token::NEWLINE, _) => true,
+            (Token::VALUE(word), _) => {
+                STATEMENT_WORDS.contains(&word.as_str()) && word != "XCOR" && word != "YCOR"
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_block_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
+        let borders = (Some(Token::LBRACKET), Token::RBRACKET);
+        let block = self.parse_block_stmt(lexer, borders)?;
+
+        let ast = ExpressionAst::Block(block);
+        let expr = Expression::new(ast);
+        Ok(expr)
+    }
+
+    fn parse_list_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
+        self.skip_token(lexer); // skip the `[`
+
+        let mut items = Vec::new();
+
+        while self.peek_current_token_clone(lexer) != Token::RBRACKET {
+            let item = self.parse_expr(lexer)?;
+            items.push(item);
+
+            if self.peek_current_token_clone(lexer) != Token::COMMA {
+                break;
+            }
+
+            self.skip_token(lexer); // skip the `,`
+        }
+
+        self.expect_token(lexer, Token::RBRACKET)?;
+
+        let ast = ExpressionAst::List(items);
+        let expr = Expression::new(ast);
+        Ok(expr)
+    }
+
+    // `MAP("PROC", LIST)`, the procedure is referenced by a word naming it
+    fn parse_map_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
+        self.skip_token(lexer); // skip the `MAP`
+
+        self.expect_token(lexer, Token::LPAREN)?;
+
+        let proc_name = self.parse_proc_name_word(lexer, "MAP")?;
+
+        self.expect_token(lexer, Token::COMMA)?;
+
+        let list_expr = self.parse_expr(lexer)?;
+
+        self.expect_token(lexer, Token::RPAREN)?;
+
+        let ast = ExpressionAst::Map(proc_name, Box::new(list_expr));
+        let expr = Expression::new(ast);
+        Ok(expr)
+    }
+
+    // `PROC("NAME")`
+    fn parse_proc_ref_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
+        self.skip_token(lexer); // skip the `PROC`
+
+        self.expect_token(lexer, Token::LPAREN)?;
+
+        let proc_name = self.parse_proc_name_word(lexer, "PROC")?;
+
+        self.expect_token(lexer, Token::RPAREN)?;
+
+        let ast = ExpressionAst::ProcRef(proc_name);
+        let expr = Expression::new(ast);
+        Ok(expr)
+    }
+
+    // `APPLY(REF, ARG1, ARG2, ...)`
+    fn parse_apply_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
+        self.skip_token(lexer); // skip the `APPLY`
+
+        self.expect_token(lexer, Token::LPAREN)?;
+
+        let proc_expr = self.parse_expr(lexer)?;
+        let mut args_exprs = Vec::new();
+
+        while self.peek_current_token_clone(lexer) == Token::COMMA {
+            self.skip_token(lexer); // skip the `,`
+
+            let arg_expr = self.parse_expr(lexer)?;
+            args_exprs.push(arg_expr);
+        }
+
+        self.expect_token(lexer, Token::RPAREN)?;
+
+        let ast = ExpressionAst::Apply(Box::new(proc_expr), args_exprs);
+        let expr = Expression::new(ast);
+        Ok(expr)
+    }
+
+    // `IFELSE COND [EXPR1] [EXPR2]`
+    fn parse_if_else_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
+        self.skip_token(lexer); // skip the `IFELSE`
+
+        let cond_expr = self.parse_expr(lexer)?;
+        let true_expr = self.parse_bracketed_expr(lexer)?;
+        let false_expr = self.parse_bracketed_expr(lexer)?;
+
+        let ast = ExpressionAst::IfElse(
+            Box::new(cond_expr),
+            Box::new(true_expr),
+            Box::new(false_expr),
+        );
+        let expr = Expression::new(ast);
+        Ok(expr)
+    }
+
+    // `[EXPR]`, an `IFELSE` branch
+    fn parse_bracketed_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
+        self.expect_token(lexer, Token::LBRACKET)?;
+
+        let expr = self.parse_expr(lexer)?;
+
+        self.expect_token(lexer, Token::RBRACKET)?;
+        Ok(expr)
+    }
+
+    // `RGB r g b`, a call of the `RGB` primitive.
+    // like a type query, each channel binds tighter than the binary operators
+    fn parse_rgb_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
+        self.skip_token(lexer); // skip the `RGB`
+
+        let mut channels = Vec::new();
+
+        for _ in 0..3 {
+            let channel = self.parse_parens_expr(lexer)?;
+            channels.push(channel);
+        }
+
+        let ast = ExpressionAst::ProcCall("RGB".to_string(), channels);
+        let expr = Expression::new(ast);
+        Ok(expr)
+    }
+
+    // `RETRIEVE "name`, a call of the `RETRIEVE` primitive (binding like an `RGB` channel)
+    fn parse_retrieve_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
+        self.skip_token(lexer); // skip the `RETRIEVE`
+
+        let name_expr = self.parse_parens_expr(lexer)?;
+
+        let ast = ExpressionAst::ProcCall("RETRIEVE".to_string(), vec![name_expr]);
+        let expr = Expression::new(ast);
+        Ok(expr)
+    }
+
+    // the word naming the procedure referenced by `keyword` (e.g `MAP`)
+    fn parse_proc_name_word(
+        &self,
+        lexer: &mut impl Lexer,
+        keyword: &str,
+    ) -> Result<String, ParseError> {
+        match self.parse_literal_expr(lexer)? {
+            LiteralExpr::Str(proc_name) => Ok(proc_name),
+            _ => {
+                let message = format!(
+                    "`{}` expects a procedure name word (e.g `\"DOUBLE\"`)",
+                    keyword
+                );
+                Err(ParseError::Syntax { message })
+            }
+        }
+    }
+
     fn parse_not_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
         self.skip_token(lexer); // skip the `NOT`
 
@@ -531,12 +1187,12 @@ impl TytleParser {
     }
 
     fn parse_basic_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
-        let (token, _location) = self.peek_next_token(lexer).unwrap();
+        let (token, _location) = self.peek_next_token(lexer);
 
         let ast = match *token {
             Token::LPAREN => {
                 let (proc_name, proc_params) = self.parse_proc_call_expr(lexer)?;
-                ExpressionAst::ProcCall(proc_name, proc_params, None)
+                ExpressionAst::ProcCall(proc_name, proc_params)
             }
             _ => {
                 let lit_expr = self.parse_literal_expr(lexer)?;
@@ -552,7 +1208,7 @@ impl TytleParser {
         &self,
         lexer: &mut impl Lexer,
     ) -> Result<(String, Vec<Expression>), ParseError> {
-        let (token, _) = self.pop_current_token(lexer).unwrap();
+        let (token, _) = self.pop_current_token(lexer);
 
         if let Token::VALUE(proc_name) = token {
             self.expect_token(lexer, Token::LPAREN)?;
@@ -573,25 +1229,51 @@ impl TytleParser {
         &self,
         lexer: &mut impl Lexer,
     ) -> Result<Vec<Expression>, ParseError> {
-        let mut params = Vec::new();
+        let mut params: Vec<Expression> = Vec::new();
 
         while self.peek_current_token_clone(lexer) != Token::RPAREN {
-            let param_expr = self.parse_call_param_expr(lexer)?;
+            if let Some(param_expr) = self.parse_call_param_expr(lexer)? {
+                let named =
+                    |expr: &Expression| matches!(expr.expr_ast, ExpressionAst::NamedArg(..));
 
-            if param_expr.is_some() {
-                params.push(param_expr.unwrap());
+                if !named(&param_expr) && params.iter().any(named) {
+                    let message = "Positional args must precede the named args".to_string();
+                    return Err(ParseError::Syntax { message });
+                }
+
+                params.push(param_expr);
             }
         }
 
         Ok(params)
     }
 
+    // an arg is either an expression or a named arg (`NAME=EXPR`).
+    // hence, an equality test of a variable passed as an arg must be surrounded by parentheses
     fn parse_call_param_expr(
         &self,
         lexer: &mut impl Lexer,
     ) -> Result<Option<Expression>, ParseError> {
+        let param_name = match (
+            self.peek_current_token_clone(lexer),
+            &self.peek_next_token(lexer).0,
+        ) {
+            (Token::VALUE(name), Token::ASSIGN) if self.validate_name(&name).is_ok() => {
+                self.skip_token(lexer); // skipping the param name
+                self.skip_token(lexer); // skipping the `=`
+
+                Some(name)
+            }
+            _ => None,
+        };
+
         let expr = self.parse_expr(lexer)?;
 
+        let expr = match param_name {
+            Some(name) => Expression::new(ExpressionAst::NamedArg(name, Box::new(expr))),
+            None => expr,
+        };
+
         if self.peek_current_token_clone(lexer) == Token::COMMA {
             self.skip_token(lexer);
         }
@@ -600,26 +1282,21 @@ impl TytleParser {
     }
 
     fn parse_literal_expr(&self, lexer: &mut impl Lexer) -> Result<LiteralExpr, ParseError> {
-        let pair = self.pop_current_token(lexer);
-
-        let (tok, _loc) = pair.unwrap();
+        let (tok, _loc) = self.pop_current_token(lexer);
 
         if let Token::VALUE(v) = tok {
             match v.parse::<usize>() {
                 Ok(num) => Ok(LiteralExpr::Int(num)),
                 Err(_) => {
-                    if v.starts_with("\"") {
-                        let s = v[1..v.len() - 1].to_string();
+                    if let Some(s) = v.strip_prefix('"') {
+                        let s = s.strip_suffix('"').unwrap_or(s).to_string();
                         Ok(LiteralExpr::Str(s))
                     } else {
                         let lit_expr = match v.as_str() {
                             "TRUE" => LiteralExpr::Bool(true),
                             "FALSE" => LiteralExpr::Bool(false),
-                            _ => {
-                                // we'll fill-in the variable `Global id` when we'll generate the symbol table
-                                let var_global_id = None;
-                                LiteralExpr::Var(v.to_string(), var_global_id)
-                            }
+                            // the variable is resolved when we'll generate the symbol table
+                            _ => LiteralExpr::Var(v.to_string()),
                         };
 
                         Ok(lit_expr)
@@ -634,7 +1311,7 @@ impl TytleParser {
     }
 
     fn expect_value(&self, lexer: &mut impl Lexer) -> Result<String, ParseError> {
-        let (token, _loc) = self.pop_current_token(lexer).unwrap();
+        let (token, _loc) = self.pop_current_token(lexer);
 
         if let Token::VALUE(v) = token {
             Ok(v)
@@ -644,7 +1321,7 @@ impl TytleParser {
     }
 
     fn expect_token(&self, lexer: &mut impl Lexer, expected: Token) -> Result<(), ParseError> {
-        let (actual, _loc) = self.pop_current_token(lexer).unwrap();
+        let (actual, _loc) = self.pop_current_token(lexer);
 
         if actual == expected {
             Ok(())
@@ -658,16 +1335,18 @@ impl TytleParser {
         }
     }
 
-    fn peek_current_token<'lex>(&self, lexer: &'lex impl Lexer) -> Option<&'lex (Token, Location)> {
-        lexer.peek_current_token()
+    // past the end of the code the lexer has no tokens left, so the parser keeps reading `EOF`s
+    // (a truncated program is then rejected with a `ParseError`)
+    fn peek_current_token<'lex>(&self, lexer: &'lex impl Lexer) -> &'lex (Token, Location) {
+        lexer.peek_current_token().unwrap_or(&EOF_TOKEN)
     }
 
-    fn peek_next_token<'lex>(&self, lexer: &'lex impl Lexer) -> Option<&'lex (Token, Location)> {
-        lexer.peek_next_token()
+    fn peek_next_token<'lex>(&self, lexer: &'lex impl Lexer) -> &'lex (Token, Location) {
+        lexer.peek_next_token().unwrap_or(&EOF_TOKEN)
     }
 
     fn peek_current_token_clone<'lex>(&self, lexer: &'lex impl Lexer) -> Token {
-        let (token, _) = lexer.peek_current_token().unwrap();
+        let (token, _) = self.peek_current_token(lexer);
 
         token.clone()
     }
@@ -676,14 +1355,15 @@ impl TytleParser {
         self.pop_current_token(lexer);
     }
 
-    fn pop_current_token(&self, lexer: &mut impl Lexer) -> Option<(Token, Location)> {
-        lexer.pop_current_token()
+    fn pop_current_token(&self, lexer: &mut impl Lexer) -> (Token, Location) {
+        lexer.pop_current_token().unwrap_or_else(|| EOF_TOKEN.clone())
     }
 
     fn validate_name(&self, name: &str) -> Result<(), ParseError> {
+        // letters of scripts without letter case (e.g Hebrew) are allowed as is
         let upper = name
             .chars()
-            .all(|c| c.is_ascii_uppercase() || c.is_digit(10) || c == '_');
+            .all(|c| (c.is_alphabetic() && !c.is_lowercase()) || c.is_ascii_digit() || c == '_');
 
         if !upper {
             let err = ParseError::InvalidIdentifierDeclaration(format!(
@@ -713,7 +1393,7 @@ impl TytleParser {
 
     fn validate_data_type(&self, data_type: &str) -> Result<(), ParseError> {
         match data_type {
-            "STR" | "INT" | "BOOL" => Ok(()),
+            "STR" | "INT" | "BOOL" | "PROC" | "COLOR" | "BLOCK" => Ok(()),
             _ => Err(ParseError::InvalidDataType(data_type.to_owned())),
         }
     }
@@ -0,0 +1,32 @@
+// the `.tytle` project format, shared by the playground and the desktop front-ends.
+//
+// a project is a JSON object holding the program files along with its settings:
+//
+// ```json
+// {
+//   "version": 1,
+//   "entry": "MAIN",
+//   "files": [
+//     { "path": "MAIN", "source": "LOAD \"SHAPES\nSQUARE(50)" },
+//     { "path": "SHAPES", "source": "TO SQUARE(SIZE: INT)\n..." }
+//   ],
+//   "options": { "compile": { "opt_level": "basic", "stdlib": true }, "max_steps": 100000 },
+//   "seed": 7,
+//   "preview": "<svg ...>...</svg>"
+// }
+// ```
+//
+// * `entry` - the path of the file the program starts at. the other files are loaded by
+//   `LOAD "path` statements (a `Project` is a `SourceResolver` of its files)
+// * `options` - the `RunOptions` (missing fields have their default value)
+// * `seed` - the random seed front-ends run the program with (optional)
+// * `preview` - a cached SVG rendering of the program drawing (optional), for listing
+//   projects without running them
+//
+// the schema is versioned by `version`. adding fields is backward compatible, anything else bumps it
+
+mod project_error;
+mod tytle_project;
+
+pub use project_error::ProjectError;
+pub use tytle_project::{Project, ProjectFile, PROJECT_VERSION};
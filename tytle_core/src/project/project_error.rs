@@ -0,0 +1,47 @@
+use crate::compat::prelude::*;
+use core::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProjectError {
+    InvalidJson(String),
+    UnsupportedVersion(u32),
+    // the project `entry` isn't one of its files
+    MissingEntry(String),
+    DuplicateFile(String),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProjectError::InvalidJson(msg) => write!(f, "Invalid project: {}", msg),
+            ProjectError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported project version: {}", version)
+            }
+            ProjectError::MissingEntry(path) => {
+                write!(f, "Missing the project entry file `{}`", path)
+            }
+            ProjectError::DuplicateFile(path) => write!(f, "Duplicate project file `{}`", path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn project_error_unsupported_version() {
+        assert_eq!(
+            "Unsupported project version: 7",
+            ProjectError::UnsupportedVersion(7).to_string()
+        );
+    }
+
+    #[test]
+    pub fn project_error_missing_entry() {
+        assert_eq!(
+            "Missing the project entry file `MAIN`",
+            ProjectError::MissingEntry("MAIN".to_string()).to_string()
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::compat::collections::HashSet;
+use crate::compat::prelude::*;
+use crate::parser::{SourceResolver, MAIN_FILE};
+use crate::project::ProjectError;
+use crate::runner::RunOptions;
+use serde::{Deserialize, Serialize};
+
+// the current project format version (see the format description in `project/mod.rs`)
+pub const PROJECT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectFile {
+    pub path: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub version: u32,
+    pub entry: String,
+    pub files: Vec<ProjectFile>,
+
+    #[serde(default)]
+    pub options: RunOptions,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+}
+
+impl Project {
+    // a single-file project (its file is named `MAIN_FILE`)
+    pub fn new(source: &str) -> Self {
+        let entry = ProjectFile {
+            path: MAIN_FILE.to_string(),
+            source: source.to_string(),
+        };
+
+        Self {
+            version: PROJECT_VERSION,
+            entry: MAIN_FILE.to_string(),
+            files: vec![entry],
+            options: RunOptions::default(),
+            seed: None,
+            preview: None,
+        }
+    }
+
+    // adds a file (or replaces the source of the file at `path`)
+    pub fn add_file(&mut self, path: &str, source: &str) {
+        match self.files.iter_mut().find(|file| file.path == path) {
+            Some(file) => file.source = source.to_string(),
+            None => self.files.push(ProjectFile {
+                path: path.to_string(),
+                source: source.to_string(),
+            }),
+        }
+    }
+
+    pub fn file(&self, path: &str) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|file| file.path == path)
+            .map(|file| file.source.as_str())
+    }
+
+    // the source of the `entry` file
+    pub fn entry_source(&self) -> &str {
+        self.file(&self.entry).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<String, ProjectError> {
+        self.validate()?;
+
+        serde_json::to_string_pretty(self).map_err(|e| ProjectError::InvalidJson(e.to_string()))
+    }
+
+    pub fn load(json: &str) -> Result<Project, ProjectError> {
+        let project: Project =
+            serde_json::from_str(json).map_err(|e| ProjectError::InvalidJson(e.to_string()))?;
+
+        project.validate()?;
+
+        Ok(project)
+    }
+
+    fn validate(&self) -> Result<(), ProjectError> {
+        if self.version != PROJECT_VERSION {
+            return Err(ProjectError::UnsupportedVersion(self.version));
+        }
+
+        let mut paths = HashSet::new();
+
+        for file in &self.files {
+            if !paths.insert(file.path.as_str()) {
+                return Err(ProjectError::DuplicateFile(file.path.clone()));
+            }
+        }
+
+        if !paths.contains(self.entry.as_str()) {
+            return Err(ProjectError::MissingEntry(self.entry.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+impl SourceResolver for Project {
+    fn resolve(&self, path: &str) -> Option<String> {
+        self.file(path).map(str::to_string)
+    }
+}
@@ -0,0 +1,67 @@
+// headless rendering of a program's drawing, e.g by a web backend:
+//
+// ```
+// let svg = tytle::render_to_svg("FORWARD 10", RenderOptions::default())?;
+// ```
+//
+// the program runs on a fresh `SvgHost` with the steps, time & memory limits of the
+// `RenderOptions`, and any error (a compilation error or a runtime one, e.g an exceeded limit)
+// converts into a `Diagnostic` for the response
+
+mod svg_host;
+
+pub use svg_host::SvgHost;
+
+use crate::compat::prelude::*;
+use crate::guard::guard;
+use crate::runner::RunOptions;
+use crate::vm::{ResourceLimits, DEFAULT_SCREEN_SIZE};
+use crate::TytleError;
+use serde::{Deserialize, Serialize};
+
+// the limits of `RenderOptions::default()`
+pub const DEFAULT_RENDER_MAX_STEPS: u64 = 1_000_000;
+pub const DEFAULT_RENDER_TIMEOUT_MS: u64 = 1_000;
+
+pub const DEFAULT_RENDER_LIMITS: ResourceLimits = ResourceLimits {
+    max_heap: Some(16 << 20),
+    max_list_len: Some(100_000),
+    max_str_len: Some(1 << 20),
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderOptions {
+    // the options of the execution (`max_steps`, `timeout_ms` & `limits` are the limits)
+    pub run: RunOptions,
+
+    // the size of the SVG canvas (the screen seen by the program)
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            run: RunOptions {
+                max_steps: Some(DEFAULT_RENDER_MAX_STEPS),
+                timeout_ms: Some(DEFAULT_RENDER_TIMEOUT_MS),
+                limits: DEFAULT_RENDER_LIMITS,
+                ..RunOptions::default()
+            },
+            width: DEFAULT_SCREEN_SIZE.0,
+            height: DEFAULT_SCREEN_SIZE.1,
+        }
+    }
+}
+
+// runs `source` and returns an SVG document of its drawing
+pub fn render_to_svg(source: &str, options: RenderOptions) -> Result<String, TytleError> {
+    guard("render_to_svg", || {
+        let mut host = SvgHost::new(options.width, options.height);
+
+        crate::run(source, &mut host, options.run)?;
+
+        Ok(host.to_svg())
+    })
+}
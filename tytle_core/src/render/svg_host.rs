@@ -0,0 +1,180 @@
+use crate::ast::statement::{Command, Direction};
+use crate::compat::prelude::*;
+use crate::vm::{Color, Host, Label, Movement, Pen, PenState, Turtle};
+use core::fmt::Write;
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+// the color of the canvas, which erasing draws with
+const BACKGROUND: &str = "#ffffff";
+
+// the radius of the dot drawn by `STAMP`
+const STAMP_RADIUS: usize = 3;
+
+// a host drawing the program onto an SVG canvas (see `to_svg`).
+// the canvas origin is at its bottom-left corner (as the turtle's), so the y-axis is flipped.
+// the other host-calls (e.g `PRINT`) are ignored
+pub struct SvgHost {
+    width: usize,
+    height: usize,
+    turtle: Turtle,
+    pen: Pen,
+
+    // the SVG elements drawn since the last `CLEAN` / `CLEARSCREEN`
+    elements: Vec<String>,
+}
+
+impl SvgHost {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            turtle: Turtle::new(),
+            pen: Pen::new(),
+            elements: Vec::new(),
+        }
+    }
+
+    pub fn elements(&self) -> &[String] {
+        &self.elements
+    }
+
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::new();
+
+        let (w, h) = (self.width, self.height);
+
+        writeln!(
+            svg,
+            r#"<svg xmlns="{}" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+            SVG_NAMESPACE, w, h, w, h
+        )
+        .unwrap();
+
+        writeln!(svg, r#"<rect width="100%" height="100%" fill="{}"/>"#, BACKGROUND).unwrap();
+
+        for element in self.elements.iter() {
+            svg.push_str(element);
+            svg.push('\n');
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn draw_direct(&mut self, direct: &Direction, count: isize) {
+        let from = self.point(self.turtle.xcor(), self.turtle.ycor());
+        self.turtle.exec_direct(direct, count);
+        let to = self.point(self.turtle.xcor(), self.turtle.ycor());
+
+        let stroke = match self.pen.get_state() {
+            PenState::Up => return,
+            PenState::Down => hex(self.pen.get_color()),
+            PenState::Erase => BACKGROUND.to_string(),
+        };
+
+        if from != to {
+            self.elements.push(format!(
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}"/>"#,
+                from.0, from.1, to.0, to.1, stroke
+            ));
+        }
+    }
+
+    // the SVG coordinates of a turtle position
+    fn point(&self, x: isize, y: isize) -> (isize, isize) {
+        (x, (self.height as isize).saturating_sub(y))
+    }
+}
+
+// `#rrggbb`
+fn hex(color: Color) -> String {
+    let (r, g, b) = color.to_rgb();
+
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Host for SvgHost {
+    fn exec_cmd(&mut self, cmd: &Command) {
+        match cmd {
+            Command::PenUp => self.pen.up(),
+            Command::PenDown => self.pen.down(),
+            Command::PenErase => self.pen.erase(),
+            Command::Clean | Command::ClearScreen => self.elements.clear(),
+            _ => {}
+        }
+    }
+
+    fn exec_direct(&mut self, direct: &Direction, count: isize) {
+        self.draw_direct(direct, count);
+    }
+
+    fn exec_turn(&mut self, degrees: f64) {
+        self.turtle.turn_degrees(degrees);
+    }
+
+    fn exec_trap(&mut self, _node: usize, _ip: usize) {}
+
+    fn exec_print(&mut self, _value: isize) {}
+
+    // the turtle shape isn't drawn, so a stamp is a dot
+    fn exec_stamp(&mut self, position: (isize, isize), _heading: f64) {
+        let (x, y) = self.point(position.0, position.1);
+
+        self.elements.push(format!(
+            r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+            x,
+            y,
+            STAMP_RADIUS,
+            hex(self.pen.get_color())
+        ));
+    }
+
+    // the text runs along the turtle heading (a heading of `90` is a horizontal text)
+    fn exec_label(&mut self, label: &Label) {
+        let (x, y) = self.point(label.position.0, label.position.1);
+        let rotation = self.turtle.config().to_degrees(label.heading) - 90.0;
+
+        let font = format!(
+            r#"font-family="{}" font-size="{}""#,
+            escape(&label.style.font),
+            label.style.size
+        );
+
+        self.elements.push(format!(
+            r#"<text x="{x}" y="{y}" {} fill="{}" transform="rotate({} {x} {y})">{}</text>"#,
+            font,
+            hex(self.pen.get_color()),
+            rotation,
+            escape(&label.text),
+            x = x,
+            y = y
+        ));
+    }
+
+    fn screen_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn pen_color_changed(&mut self, color: Color) {
+        self.pen.set_color(color);
+    }
+
+    fn movement_completed(&mut self, movement: &Movement) {
+        self.draw_direct(&movement.direct, movement.count);
+    }
+
+    fn state_restored(&mut self, turtle: &Turtle, pen: &Pen) {
+        self.turtle = turtle.clone();
+        self.pen = pen.clone();
+    }
+
+    fn compilation_error(&mut self, _error: &str) {}
+}
@@ -0,0 +1,212 @@
+// runs a tytle program with a single call, hiding the whole pipeline (parsing, analysis, CFG, interpreter):
+//
+// ```
+// let summary = tytle::run("FORWARD 10", &mut host, RunOptions::default())?;
+// assert_eq!(10, summary.turtle.ycor());
+// ```
+
+mod run_options;
+mod run_summary;
+mod tracking_host;
+
+pub use run_options::RunOptions;
+pub use run_summary::{RunStats, RunSummary};
+
+use crate::ast::statement::ERROR_TAG;
+use crate::compat::collections::HashSet;
+use crate::compat::prelude::*;
+use crate::compat::Instant;
+use crate::compiler::{CompiledProgram, Compiler};
+use crate::diagnostics::{Diagnostic, Message};
+use crate::guard::guard;
+use crate::ir::{CfgInstruction, StateBalance};
+use crate::vm::{Host, Interpreter};
+use crate::TytleError;
+use core::time::Duration;
+use tracking_host::TrackingHost;
+
+// a panic while running `source` is returned as a `TytleError::Internal` (see `guard`)
+pub fn run(
+    source: &str,
+    host: &mut dyn Host,
+    options: RunOptions,
+) -> Result<RunSummary, TytleError> {
+    guard("run", || run_program(source, host, options))
+}
+
+fn run_program(
+    source: &str,
+    host: &mut dyn Host,
+    options: RunOptions,
+) -> Result<RunSummary, TytleError> {
+    let program = Compiler::compile_with_globals(source, options.compile, &options.globals)?;
+    let diagnostics = diagnose(&program);
+
+    let mut host = TrackingHost::new(host);
+    let mut intr = Interpreter::new(&program.cfg, &program.env, &mut host);
+    intr.set_overflow_policy(options.overflow);
+    intr.set_resource_limits(options.limits);
+    intr.set_capabilities(options.compile.capabilities);
+
+    if options.profile {
+        intr.enable_profiling();
+    }
+
+    // the wall-clock is checked on every step, since a single step may take long
+    // (e.g doubling a long string)
+    let deadline = options.timeout_ms.map(|timeout| (timeout, Duration::from_millis(timeout)));
+    let started = Instant::now();
+    let mut steps = 0;
+
+    loop {
+        if options.max_steps == Some(steps) {
+            return Err(TytleError::StepLimitExceeded(steps));
+        }
+
+        if let Some((timeout, duration)) = deadline {
+            if started.elapsed() >= duration {
+                return Err(TytleError::TimeLimitExceeded(timeout));
+            }
+        }
+
+        let completed = intr.exec_next()?;
+        steps += 1;
+
+        if completed {
+            break;
+        }
+    }
+
+    let profile = intr.profile_report().cloned();
+    drop(intr);
+
+    let stats = RunStats {
+        steps,
+        host_calls: host.host_calls(),
+        profile,
+    };
+
+    let bounds = host.bounds();
+    let path_length = host.path_length();
+    let (turtle, pen) = host.into_state();
+
+    Ok(RunSummary {
+        diagnostics,
+        stats,
+        turtle,
+        pen,
+        bounds,
+        path_length,
+    })
+}
+
+// warnings about valid (yet suspicious) code
+fn diagnose(program: &CompiledProgram) -> Vec<Diagnostic> {
+    let mut diagnostics = diagnose_uncalled_procs(program);
+    diagnostics.extend(diagnose_unknown_tags(program));
+    diagnostics.extend(diagnose_unbalanced_states(program));
+
+    diagnostics
+}
+
+fn diagnose_uncalled_procs(program: &CompiledProgram) -> Vec<Diagnostic> {
+    let env = &program.env;
+
+    // the procedures resolved by the calls (and `MAP`s) of the program, rather than by the
+    // `Call`s of the CFG (since the optimizer may inline them away).
+    // a procedure referenced by a `PROC` expression may be called using `APPLY`
+    let mut called = env
+        .expr_symbols
+        .iter()
+        .map(|(_, symbol_id)| *symbol_id)
+        .collect::<HashSet<_>>();
+
+    // the blocks and the events handlers are run by the VM
+    called.extend(env.blocks.keys().copied());
+    called.extend(env.key_handlers.values().copied());
+    called.extend(env.tick_handler.map(|(_, proc_id)| proc_id));
+
+    let mut uncalled = program
+        .cfg
+        .jmp_table
+        .values()
+        .filter(|proc_id| !called.contains(*proc_id))
+        .map(|proc_id| env.symbol_table.get_proc_by_id(*proc_id))
+        .filter(|proc| !proc.builtin && !proc.native && proc.name != "__main__")
+        .map(|proc| proc.name.clone())
+        .collect::<Vec<_>>();
+
+    uncalled.sort();
+
+    uncalled
+        .into_iter()
+        .map(|name| {
+            Diagnostic::warning(&format!("Procedure `{}` is never called", name))
+                .localized(Message::new("warning.unused-proc").arg("proc", name))
+        })
+        .collect()
+}
+
+// `THROW`s of tags which aren't caught by any `CATCH` of the program.
+// `THROW "ERROR` isn't reported, since an uncaught one is a way to stop the program with an error
+fn diagnose_unknown_tags(program: &CompiledProgram) -> Vec<Diagnostic> {
+    let insts = program
+        .cfg
+        .graph
+        .nodes
+        .values()
+        .flat_map(|node| node.insts.iter());
+
+    let mut caught = HashSet::new();
+    let mut thrown = HashSet::new();
+
+    for inst in insts {
+        match inst {
+            CfgInstruction::Catch(tag, _) => caught.insert(tag),
+            CfgInstruction::Throw(tag) => thrown.insert(tag),
+            _ => continue,
+        };
+    }
+
+    let mut unknown = thrown
+        .difference(&caught)
+        .filter(|tag| tag.as_str() != ERROR_TAG)
+        .collect::<Vec<_>>();
+
+    unknown.sort();
+
+    unknown
+        .into_iter()
+        .map(|tag| {
+            Diagnostic::warning(&format!("`THROW` of tag `{}` which is never caught", tag))
+                .localized(Message::new("warning.unknown-tag").arg("tag", tag))
+        })
+        .collect()
+}
+
+// procedures (or the root statements) which don't restore each state they save via `PUSHSTATE`,
+// see `StateBalance`
+fn diagnose_unbalanced_states(program: &CompiledProgram) -> Vec<Diagnostic> {
+    StateBalance::analyze_program(&program.cfg)
+        .into_iter()
+        .map(|proc_id| program.env.symbol_table.get_proc_by_id(proc_id).name.clone())
+        .map(|name| {
+            if name == "__main__" {
+                let message = "The program doesn't restore each state it saves \
+                               (unbalanced `PUSHSTATE` / `POPSTATE`)";
+
+                return Diagnostic::warning(message)
+                    .localized(Message::new("warning.unbalanced-main-state"));
+            }
+
+            let message = format!(
+                "Procedure `{}` doesn't restore each state it saves \
+                 (unbalanced `PUSHSTATE` / `POPSTATE`)",
+                name
+            );
+
+            Diagnostic::warning(&message)
+                .localized(Message::new("warning.unbalanced-state").arg("proc", name))
+        })
+        .collect()
+}
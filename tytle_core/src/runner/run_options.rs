@@ -0,0 +1,30 @@
+use crate::compat::prelude::*;
+use crate::compiler::CompileOptions;
+use crate::vm::{MemoryValue, OverflowPolicy, ResourceLimits};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunOptions {
+    pub compile: CompileOptions,
+
+    // aborts the execution after that many interpreter steps (unlimited when `None`)
+    pub max_steps: Option<u64>,
+
+    // aborts the execution once it's run for that many milliseconds (unlimited when `None`).
+    // without the `std` feature there's no clock, so it's ignored
+    pub timeout_ms: Option<u64>,
+
+    // whether to gather a `ProfileReport` (see `RunStats::profile`)
+    pub profile: bool,
+
+    // what an overflowing integer arithmetic results in
+    pub overflow: OverflowPolicy,
+
+    // the globals the program starts with, e.g the canvas size or a level number
+    // (see `Environment::register_global`)
+    pub globals: Vec<(String, MemoryValue)>,
+
+    // the memory caps of the program (unlimited by default)
+    pub limits: ResourceLimits,
+}
@@ -0,0 +1,32 @@
+use crate::compat::prelude::*;
+use crate::diagnostics::Diagnostic;
+use crate::vm::{Bounds, Pen, ProfileReport, Turtle};
+
+#[derive(Debug, Clone)]
+pub struct RunStats {
+    // the number of executed interpreter steps (instructions and jumps)
+    pub steps: u64,
+
+    // the number of calls made to the host
+    pub host_calls: u64,
+
+    pub profile: Option<ProfileReport>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunSummary {
+    // compilation warnings
+    pub diagnostics: Vec<Diagnostic>,
+
+    pub stats: RunStats,
+
+    // the turtle & pen state at the end of the execution
+    pub turtle: Turtle,
+    pub pen: Pen,
+
+    // the bounding box of the lines drawn while the pen was down (`None` when nothing was drawn)
+    pub bounds: Option<Bounds>,
+
+    // the total length of the lines drawn while the pen was down
+    pub path_length: u64,
+}
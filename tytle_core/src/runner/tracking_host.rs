@@ -0,0 +1,202 @@
+use crate::ast::statement::{Command, Direction};
+use crate::compat::prelude::*;
+#[cfg(feature = "bigint")]
+use crate::vm::BigInt;
+use crate::vm::{
+    Bounds, Color, Host, Label, Movement, Pen, PenState, Turtle, TurtlePose, TurtleShape,
+};
+
+// forwards each host-call to the wrapped `Host` while keeping track of the turtle & pen state,
+// and of the drawing geometry (the lines drawn while the pen is down)
+pub(crate) struct TrackingHost<'h> {
+    inner: &'h mut dyn Host,
+    turtle: Turtle,
+    pen: Pen,
+    host_calls: u64,
+    bounds: Option<Bounds>,
+    path_length: u64,
+}
+
+impl<'h> TrackingHost<'h> {
+    pub(crate) fn new(inner: &'h mut dyn Host) -> Self {
+        Self {
+            inner,
+            turtle: Turtle::new(),
+            pen: Pen::new(),
+            host_calls: 0,
+            bounds: None,
+            path_length: 0,
+        }
+    }
+
+    pub(crate) fn host_calls(&self) -> u64 {
+        self.host_calls
+    }
+
+    pub(crate) fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    pub(crate) fn path_length(&self) -> u64 {
+        self.path_length
+    }
+
+    pub(crate) fn into_state(self) -> (Turtle, Pen) {
+        (self.turtle, self.pen)
+    }
+
+    fn track_direct(&mut self, direct: &Direction, count: isize) {
+        let (x0, y0) = (self.turtle.xcor(), self.turtle.ycor());
+        self.turtle.exec_direct(direct, count);
+
+        if *self.pen.get_state() == PenState::Down {
+            self.draw_line((x0, y0), (self.turtle.xcor(), self.turtle.ycor()));
+        }
+    }
+
+    // the turtle moves along the axes only, so a line length is the sum of its deltas
+    // (saturating, since the turtle may move as far as the `isize` bounds)
+    fn draw_line(&mut self, from: (isize, isize), to: (isize, isize)) {
+        let length = (to.0.abs_diff(from.0) as u64).saturating_add(to.1.abs_diff(from.1) as u64);
+        self.path_length = self.path_length.saturating_add(length);
+
+        let bounds = self.bounds.get_or_insert(Bounds::at(from.0, from.1));
+        bounds.extend(from.0, from.1);
+        bounds.extend(to.0, to.1);
+    }
+}
+
+impl<'h> Host for TrackingHost<'h> {
+    fn exec_cmd(&mut self, cmd: &Command) {
+        self.host_calls += 1;
+
+        match cmd {
+            Command::PenUp => self.pen.up(),
+            Command::PenDown => self.pen.down(),
+            Command::PenErase => self.pen.erase(),
+            Command::ShowTurtle => self.turtle.show(),
+            Command::HideTurtle => self.turtle.hide(),
+            _ => {}
+        }
+
+        self.inner.exec_cmd(cmd);
+    }
+
+    fn exec_direct(&mut self, direct: &Direction, count: isize) {
+        self.host_calls += 1;
+        self.track_direct(direct, count);
+        self.inner.exec_direct(direct, count);
+    }
+
+    fn exec_turn(&mut self, degrees: f64) {
+        self.host_calls += 1;
+        self.turtle.turn_degrees(degrees);
+        self.inner.exec_turn(degrees);
+    }
+
+    fn exec_trap(&mut self, node: usize, ip: usize) {
+        self.host_calls += 1;
+        self.inner.exec_trap(node, ip);
+    }
+
+    fn exec_print(&mut self, value: isize) {
+        self.host_calls += 1;
+        self.inner.exec_print(value);
+    }
+
+    #[cfg(feature = "bigint")]
+    fn exec_print_big(&mut self, value: &BigInt) {
+        self.host_calls += 1;
+        self.inner.exec_print_big(value);
+    }
+
+    fn exec_print_text(&mut self, text: &str) {
+        self.host_calls += 1;
+        self.inner.exec_print_text(text);
+    }
+
+    fn exec_trace(&mut self, line: &str) {
+        self.host_calls += 1;
+        self.inner.exec_trace(line);
+    }
+
+    fn exec_stamp(&mut self, position: (isize, isize), heading: f64) {
+        self.host_calls += 1;
+        self.inner.exec_stamp(position, heading);
+    }
+
+    fn exec_label(&mut self, label: &Label) {
+        self.host_calls += 1;
+        self.inner.exec_label(label);
+    }
+
+    fn turtle_shape_changed(&mut self, shape: &TurtleShape) {
+        self.host_calls += 1;
+        self.inner.turtle_shape_changed(shape);
+    }
+
+    fn play_tone(&mut self, freq: usize, ms: usize) {
+        self.host_calls += 1;
+        self.inner.play_tone(freq, ms);
+    }
+
+    fn pen_color_changed(&mut self, color: Color) {
+        self.host_calls += 1;
+        self.pen.set_color(color);
+        self.inner.pen_color_changed(color);
+    }
+
+    // a movement counts as a single host-call (like `exec_direct`)
+    fn movement_started(&mut self, movement: &Movement) {
+        self.host_calls += 1;
+        self.inner.movement_started(movement);
+    }
+
+    fn movement_progress(&mut self, movement: &Movement, frame: usize, pose: &TurtlePose) {
+        self.inner.movement_progress(movement, frame, pose);
+    }
+
+    fn movement_completed(&mut self, movement: &Movement) {
+        self.track_direct(&movement.direct, movement.count);
+        self.inner.movement_completed(movement);
+    }
+
+    fn state_restored(&mut self, turtle: &Turtle, pen: &Pen) {
+        self.host_calls += 1;
+        self.turtle = turtle.clone();
+        self.pen = pen.clone();
+        self.inner.state_restored(turtle, pen);
+    }
+
+    fn kv_set(&mut self, key: &str, value: &str) {
+        self.host_calls += 1;
+        self.inner.kv_set(key, value);
+    }
+
+    fn kv_get(&self, key: &str) -> Option<String> {
+        self.inner.kv_get(key)
+    }
+
+    fn open_stream(&mut self, name: &str) {
+        self.host_calls += 1;
+        self.inner.open_stream(name);
+    }
+
+    fn write_stream(&mut self, name: &str, text: &str) {
+        self.host_calls += 1;
+        self.inner.write_stream(name, text);
+    }
+
+    fn close_stream(&mut self, name: &str) {
+        self.host_calls += 1;
+        self.inner.close_stream(name);
+    }
+
+    fn screen_size(&self) -> (usize, usize) {
+        self.inner.screen_size()
+    }
+
+    fn compilation_error(&mut self, error: &str) {
+        self.inner.compilation_error(error);
+    }
+}
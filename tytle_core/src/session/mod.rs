@@ -0,0 +1,5 @@
+mod repl_session;
+mod session_error;
+
+pub use repl_session::Session;
+pub use session_error::SessionError;
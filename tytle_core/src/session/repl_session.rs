@@ -0,0 +1,291 @@
+use crate::ast::expression::*;
+use crate::ast::semantic::*;
+use crate::ast::statement::*;
+use crate::ast::Ast;
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
+use crate::ir::*;
+use crate::parser::{Parser, TytleParser};
+use crate::session::SessionError;
+use crate::vm::*;
+
+// an interactive (REPL-like) session.
+// each call to `exec` runs only the given snippet, while the procedures defined so far
+// and the global variables (with their current values) remain available to it.
+// procedures can be redefined at any time (see `redefine_proc`) without losing the globals
+pub struct Session {
+    procs: Vec<ProcedureStmt>,
+
+    // the global variables by declaration order
+    globals: Vec<SessionGlobal>,
+
+    // the names of the procedures referenced by the globals values.
+    // the procedures symbols are re-generated by each compilation, so they're re-declared by name
+    procs_names: HashMap<SymbolId, String>,
+
+    // the statements of the blocks held by the globals values, re-declared as block expressions
+    blocks: HashMap<SymbolId, BlockStatement>,
+}
+
+// a global variable kept by the session, along with its declared type
+// (the type of an empty list can't be told by its value)
+struct SessionGlobal {
+    name: String,
+    var_type: ExpressionType,
+    value: MemoryValue,
+    constant: bool,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self {
+            procs: Vec::new(),
+            globals: Vec::new(),
+            procs_names: HashMap::new(),
+            blocks: HashMap::new(),
+        }
+    }
+
+    pub fn get_global(&self, var_name: &str) -> Option<&MemoryValue> {
+        self.globals
+            .iter()
+            .find(|global| global.name == var_name)
+            .map(|global| &global.value)
+    }
+
+    // parses, checks and executes `code` within the session.
+    // procedures defined by `code` are added to the session (replacing ones having the same name).
+    // on a compilation error the session is left untouched
+    pub fn exec(&mut self, code: &str, host: &mut dyn Host) -> Result<(), SessionError> {
+        let snippet = TytleParser.parse(code).map_err(SessionError::Parse)?;
+
+        let mut procs = self.procs.clone();
+        let mut stmts = Vec::new();
+
+        for stmt in snippet.statements {
+            match stmt {
+                Statement::Procedure(proc_stmt) => Self::define_proc(&mut procs, proc_stmt),
+                Statement::EOF => continue,
+                _ => stmts.push(stmt),
+            }
+        }
+
+        let (env, cfg) = self.compile(&procs, stmts)?;
+
+        self.procs = procs;
+
+        let mut intr = Interpreter::new(&cfg, &env, host);
+        let res = intr.exec_code();
+
+        // saving the globals (including the ones declared by `code`) for the next executions
+        self.globals = (0..env.globals_index)
+            .map(|index| {
+                let var_id = env.globals_symbols[&index];
+                let var = env.symbol_table.get_var_by_id(var_id);
+                let addr = Address(var.index.unwrap());
+                let value = intr.memory.get_global(addr).unwrap();
+
+                SessionGlobal {
+                    name: var.name.clone(),
+                    var_type: var.var_type.clone().unwrap(),
+                    value: value.clone(),
+                    constant: var.constant,
+                }
+            })
+            .collect();
+
+        self.procs_names.clear();
+        self.blocks.clear();
+
+        for global in self.globals.iter() {
+            Self::collect_procs_names(&global.value, &env, &mut self.procs_names);
+            Self::collect_blocks(&global.value, &env, &mut self.blocks);
+        }
+
+        res.map_err(SessionError::Runtime)
+    }
+
+    // re-parses and re-checks a single `TO ... END` procedure definition and swaps it into the session.
+    // the session is recompiled on the next `exec`, so all call targets are re-resolved
+    pub fn redefine_proc(&mut self, code: &str) -> Result<(), SessionError> {
+        let ast = TytleParser.parse(code).map_err(SessionError::Parse)?;
+
+        let proc_stmt = match ast.statements.as_slice() {
+            [Statement::Procedure(proc_stmt)] => proc_stmt.clone(),
+            _ => return Err(SessionError::NotAProcedure),
+        };
+
+        let mut procs = self.procs.clone();
+        Self::define_proc(&mut procs, proc_stmt);
+
+        // making sure the session still compiles
+        self.compile(&procs, Vec::new())?;
+
+        self.procs = procs;
+
+        Ok(())
+    }
+
+    // procedures are overloaded by arity,
+    // so a definition replaces only the one of the same name and params count
+    fn define_proc(procs: &mut Vec<ProcedureStmt>, proc_stmt: ProcedureStmt) {
+        let existing = procs
+            .iter_mut()
+            .find(|p| p.name == proc_stmt.name && p.params.len() == proc_stmt.params.len());
+
+        match existing {
+            Some(existing) => *existing = proc_stmt,
+            None => procs.push(proc_stmt),
+        }
+    }
+
+    fn collect_procs_names(
+        value: &MemoryValue,
+        env: &Environment,
+        procs_names: &mut HashMap<SymbolId, String>,
+    ) {
+        match value {
+            MemoryValue::ProcRef(proc_id) => {
+                if let Some(proc) = env.symbol_table.try_get_proc_by_id(*proc_id) {
+                    procs_names.insert(*proc_id, proc.name.clone());
+                }
+            }
+            MemoryValue::List(items) => {
+                for item in items {
+                    Self::collect_procs_names(item, env, procs_names);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_blocks(
+        value: &MemoryValue,
+        env: &Environment,
+        blocks: &mut HashMap<SymbolId, BlockStatement>,
+    ) {
+        match value {
+            MemoryValue::Block(proc_id, _) => {
+                if let Some(block_stmt) = env.blocks.get(proc_id) {
+                    blocks.insert(*proc_id, block_stmt.clone());
+                }
+            }
+            MemoryValue::List(items) => {
+                for item in items {
+                    Self::collect_blocks(item, env, blocks);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // the expression re-creating `value`, when it references code (procedures or blocks).
+    // the procedures and blocks are compiled again by each execution, so they're referenced
+    // by name (or by their statements) rather than by their symbols.
+    // an unassigned reference (e.g of a failed execution) can't be re-created
+    fn code_expr(&self, value: &MemoryValue) -> Option<Expression> {
+        let expr_ast = match value {
+            MemoryValue::List(items) if !items.is_empty() => ExpressionAst::List(
+                items
+                    .iter()
+                    .map(|item| self.code_expr(item))
+                    .collect::<Option<Vec<_>>>()?,
+            ),
+            MemoryValue::ProcRef(proc_id) => {
+                ExpressionAst::ProcRef(self.procs_names.get(proc_id)?.clone())
+            }
+            MemoryValue::Block(proc_id, _) => {
+                ExpressionAst::Block(self.blocks.get(proc_id)?.clone())
+            }
+            _ => return None,
+        };
+
+        Some(Expression::new(expr_ast))
+    }
+
+    // `value` having its references to code unassigned, since the symbols they point to
+    // belong to the previous execution (see `code_expr`)
+    fn unassign_code(value: &MemoryValue) -> MemoryValue {
+        match value {
+            MemoryValue::ProcRef(_) => MemoryValue::UNASSIGNED_PROC,
+            MemoryValue::Block(..) => MemoryValue::UNASSIGNED_BLOCK,
+            MemoryValue::List(items) => {
+                MemoryValue::List(items.iter().map(Self::unassign_code).collect())
+            }
+            value => value.clone(),
+        }
+    }
+
+    // the literal of a `CONST` value, so it's still evaluated at compile time
+    // (there are no negative integer literals)
+    fn const_value(value: &MemoryValue) -> Option<LiteralExpr> {
+        match value {
+            MemoryValue::Int(v) if *v >= 0 => Some(LiteralExpr::Int(*v as usize)),
+            MemoryValue::Bool(v) => Some(LiteralExpr::Bool(*v)),
+            MemoryValue::Str(v) => Some(LiteralExpr::Str(v.clone())),
+            _ => None,
+        }
+    }
+
+    fn compile(
+        &self,
+        procs: &[ProcedureStmt],
+        stmts: Vec<Statement>,
+    ) -> Result<(Environment, CfgObject), SessionError> {
+        let mut ast = Ast::default();
+        let mut env = Environment::new();
+
+        // the session globals keep their current values (within the interpreter memory),
+        // except for the ones referencing code, which are re-declared when they can be
+        for global in self.globals.iter() {
+            if let Some(expr) = self.code_expr(&global.value) {
+                let make_stmt = MakeStmt {
+                    kind: MakeStmtKind::Global,
+                    var_name: global.name.clone(),
+                    var_id: None,
+                    expr,
+                };
+
+                ast.statements.push(Statement::Make(make_stmt));
+                continue;
+            }
+
+            let var_id = env.register_typed_global(
+                &global.name,
+                global.var_type.clone(),
+                Self::unassign_code(&global.value),
+            );
+
+            if global.constant {
+                let var = env.symbol_table.get_var_by_id_mut(var_id);
+                var.constant = true;
+                var.const_value = Self::const_value(&global.value);
+            }
+        }
+
+        for proc_stmt in procs {
+            ast.statements.push(Statement::Procedure(proc_stmt.clone()));
+        }
+
+        ast.statements.extend(stmts);
+
+        let generator = SymbolTableGenerator::with_env(env);
+        let mut env = generator
+            .generate(&mut ast)
+            .map_err(SessionError::Semantic)?;
+
+        let mut checker = AstTypeCheck::new(&mut env);
+        checker.check(&mut ast).map_err(SessionError::Semantic)?;
+
+        let builder = CfgBuilder::new(&mut env);
+        let cfg = builder.build(&ast);
+
+        Ok((env, cfg))
+    }
+}
@@ -0,0 +1,45 @@
+use crate::ast::semantic::AstWalkError;
+use crate::compat::prelude::*;
+use crate::parser::ParseError;
+use crate::vm::InterpreterException;
+use core::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum SessionError {
+    Parse(ParseError),
+    Semantic(AstWalkError),
+    NotAProcedure,
+    Runtime(InterpreterException),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SessionError::Parse(err) => write!(f, "{}", err.to_string()),
+            SessionError::Semantic(err) => write!(f, "{}", err.to_string()),
+            SessionError::NotAProcedure => write!(f, "Expected a single procedure definition"),
+            SessionError::Runtime(err) => write!(f, "Runtime error: `{:?}`", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn session_error_not_a_procedure() {
+        assert_eq!(
+            "Expected a single procedure definition",
+            SessionError::NotAProcedure.to_string()
+        );
+    }
+
+    #[test]
+    pub fn session_error_wraps_parse_error() {
+        assert_eq!(
+            "Missing colon",
+            SessionError::Parse(ParseError::MissingColon).to_string()
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use crate::ast::statement::Statement;
+use crate::ast::Ast;
+use crate::parser::{Parser, TytleParser};
+
+// the standard library procedures, written in tytle.
+// it's preloaded before the user code when using `Environment::with_stdlib`.
+// within its procedures `LEFT` / `RIGHT` always turn (in degrees), whatever the turtle config
+pub const PRELUDE: &str = include_str!("prelude.tytle");
+
+lazy_static! {
+    static ref PRELUDE_AST: Ast = TytleParser
+        .parse(PRELUDE)
+        .expect("the stdlib prelude should be valid tytle code");
+}
+
+pub fn prelude_ast() -> Ast {
+    PRELUDE_AST.clone()
+}
+
+pub fn is_builtin(proc_name: &str) -> bool {
+    PRELUDE_AST.statements.iter().any(|stmt| match stmt {
+        Statement::Procedure(proc_stmt) => proc_stmt.name == proc_name,
+        _ => false,
+    })
+}
@@ -0,0 +1,16 @@
+use crate::compat::prelude::*;
+use crate::tooling::proc_info::{program_procs, ProcInfo};
+
+// the procedures (defined by `code` or by the stdlib prelude) whose name starts with `prefix`, sorted by name
+pub fn completions(code: &str, prefix: &str) -> Vec<ProcInfo> {
+    let prefix = prefix.to_uppercase();
+
+    let mut procs = program_procs(code)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|proc_info| proc_info.name.starts_with(&prefix))
+        .collect::<Vec<_>>();
+
+    procs.sort_by(|a, b| a.name.cmp(&b.name));
+    procs
+}
@@ -0,0 +1,56 @@
+use crate::compat::prelude::*;
+use crate::lexer::{Lexer, Location, Token, TytleLexer};
+use crate::tooling::proc_info::{program_procs, ProcInfo};
+
+// the description of the procedure named at the hovered location
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hover {
+    // the span of the hovered procedure name
+    pub location: Location,
+    pub len: usize,
+
+    pub proc_info: ProcInfo,
+}
+
+impl Hover {
+    pub fn contents(&self) -> String {
+        self.proc_info.describe()
+    }
+}
+
+// describes the procedure whose name (either in a call or in its `TO` line) covers `location`
+pub fn hover(code: &str, location: Location) -> Option<Hover> {
+    let (name, name_location) = word_at(code, location)?;
+
+    let proc_info = program_procs(code)?
+        .into_iter()
+        .find(|proc_info| proc_info.name == name)?;
+
+    Some(Hover {
+        location: name_location,
+        len: name.chars().count(),
+        proc_info,
+    })
+}
+
+fn word_at(code: &str, location: Location) -> Option<(String, Location)> {
+    let mut lexer = TytleLexer::new(code);
+
+    while let Some((token, loc)) = lexer.pop_current_token() {
+        if token == Token::EOF || loc.line() > location.line() {
+            break;
+        }
+
+        if let Token::VALUE(word) = token {
+            let end_column = loc.column() + word.chars().count();
+
+            if loc.line() == location.line()
+                && (loc.column()..end_column).contains(&location.column())
+            {
+                return Some((word, loc));
+            }
+        }
+    }
+
+    None
+}
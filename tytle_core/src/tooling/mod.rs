@@ -0,0 +1,13 @@
+// editor tooling over the source code of a program (along with the stdlib prelude procedures):
+// * `hover` - describes the procedure named at a given `Location`
+// * `completions` - the procedures matching a typed prefix
+//
+// both work on any program that parses, even when it doesn't pass the semantic analysis yet
+
+mod completion;
+mod hover;
+mod proc_info;
+
+pub use completion::completions;
+pub use hover::{hover, Hover};
+pub use proc_info::ProcInfo;
@@ -0,0 +1,137 @@
+use crate::ast::statement::{ProcDoc, ProcParam, ProcedureStmt, Statement};
+use crate::compat::prelude::*;
+use crate::parser::{Parser, TytleParser};
+use crate::stdlib;
+
+// the description of a procedure, as shown by editors
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcInfo {
+    pub name: String,
+    pub params: Vec<ProcParam>,
+    pub return_type: String,
+    pub doc: Option<ProcDoc>,
+
+    // whether the procedure is part of the stdlib prelude
+    pub builtin: bool,
+}
+
+impl ProcInfo {
+    pub fn new(proc_stmt: &ProcedureStmt, builtin: bool) -> Self {
+        Self {
+            name: proc_stmt.name.clone(),
+            params: proc_stmt.params.clone(),
+            return_type: proc_stmt.return_type.clone(),
+            doc: proc_stmt.doc.clone(),
+            builtin,
+        }
+    }
+
+    // the procedure's signature, as written in its `TO` line (e.g `POLYGON(N: INT, SIZE: INT)`)
+    pub fn signature(&self) -> String {
+        let params = self
+            .params
+            .iter()
+            .map(|param| param.signature())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match self.return_type.as_str() {
+            "UNIT" => format!("{}({})", self.name, params),
+            return_type => format!("{}({}): {}", self.name, params, return_type),
+        }
+    }
+
+    // the signature followed by the documented summary and parameters:
+    //
+    // ```
+    // POLYGON(N: INT, SIZE: INT)
+    //
+    // Draws a regular polygon.
+    //
+    // N: INT - the number of sides
+    // SIZE: INT - the length of each side
+    // ```
+    pub fn describe(&self) -> String {
+        let mut sections = vec![self.signature()];
+
+        let doc = match &self.doc {
+            Some(doc) => doc,
+            None => return self.signature(),
+        };
+
+        if !doc.summary.is_empty() {
+            sections.push(doc.summary.clone());
+        }
+
+        let params = self
+            .params
+            .iter()
+            .filter_map(|param| {
+                doc.param(&param.param_name).map(|description| {
+                    format!("{} - {}", param.signature(), description)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if !params.is_empty() {
+            sections.push(params.join("\n"));
+        }
+
+        sections.join("\n\n")
+    }
+}
+
+// the procedures defined by `code` followed by the stdlib prelude ones
+// (`None` when `code` doesn't parse)
+pub(crate) fn program_procs(code: &str) -> Option<Vec<ProcInfo>> {
+    let ast = TytleParser.parse(code).ok()?;
+
+    let user_procs = proc_stmts(&ast.statements).map(|proc_stmt| ProcInfo::new(proc_stmt, false));
+
+    let prelude = stdlib::prelude_ast();
+    let builtin_procs =
+        proc_stmts(&prelude.statements).map(|proc_stmt| ProcInfo::new(proc_stmt, true));
+
+    Some(user_procs.chain(builtin_procs).collect())
+}
+
+fn proc_stmts(stmts: &[Statement]) -> impl Iterator<Item = &ProcedureStmt> {
+    stmts.iter().filter_map(|stmt| match stmt {
+        Statement::Procedure(proc_stmt) => Some(proc_stmt),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proc_info(code: &str) -> ProcInfo {
+        program_procs(code).unwrap().remove(0)
+    }
+
+    #[test]
+    pub fn proc_info_signature() {
+        let info = proc_info("TO ONE(): INT\n RETURN 1\nEND");
+        assert_eq!("ONE(): INT", info.signature());
+
+        let info = proc_info("TO MOVE(A: INT, B: BOOL)\nEND");
+        assert_eq!("MOVE(A: INT, B: BOOL)", info.signature());
+    }
+
+    #[test]
+    pub fn proc_info_describe() {
+        let code =
+            ";; Moves around.\n;; @param B whether to move back\nTO MOVE(A: INT, B: BOOL)\nEND";
+
+        assert_eq!(
+            "MOVE(A: INT, B: BOOL)\n\nMoves around.\n\nB: BOOL - whether to move back",
+            proc_info(code).describe()
+        );
+    }
+
+    #[test]
+    pub fn proc_info_describe_undocumented() {
+        assert_eq!("MOVE()", proc_info("TO MOVE()\nEND").describe());
+    }
+}
@@ -0,0 +1,550 @@
+use crate::ast::expression::ExpressionType;
+use crate::ast::semantic::{Environment, Primitive, SymbolId};
+use crate::ast::statement::{Command, Direction};
+use crate::compat::collections::{BTreeSet, VecDeque};
+use crate::compat::prelude::*;
+use crate::compiler::CompiledProgram;
+use crate::ir::{CfgInstruction, CfgJumpType, CfgNodeId, CfgObject, CfgProc};
+use crate::transpiler::{TranspileReport, Transpiled};
+
+// the id of the canvas element the script draws on (the same one as the browser front-end)
+pub const JS_CANVAS_ID: &str = "tytle-canvas";
+
+const JS_RUNTIME: &str = include_str!("js_runtime.js");
+
+// JavaScript Canvas Transpiler.
+//
+// lowers the CFG of a compiled program into a self-contained JS script, drawing the program picture
+// on an HTML5 canvas without the VM. the script bundles a tiny runtime (see `js_runtime.js`)
+// and a JS function per procedure, holding the procedure frame (the slots `v` and the stack `s`).
+// each CFG node is lowered into a straight-line `case` of a `switch` over the current node `n`,
+// with one JS statement per instruction, followed by a jump to the next node.
+// user procedure calls are plain JS calls, and `THROW`s are JS exceptions, unwound by the frame
+// owning the matching `CATCH` marker.
+//
+// the script renders the final picture, so `WAIT` and the turtle visibility commands are dropped.
+// the host defined constructs (e.g `SETBACKGROUND`, native procedures and `TRACE`) are listed
+// in the `TranspileReport`
+pub struct JsCanvasTranspiler<'p> {
+    env: &'p Environment,
+    cfg: &'p CfgObject,
+
+    lines: Vec<String>,
+    depth: usize,
+    report: TranspileReport,
+}
+
+impl<'p> JsCanvasTranspiler<'p> {
+    pub fn transpile(program: &'p CompiledProgram) -> Transpiled {
+        let mut transpiler = Self {
+            env: &program.env,
+            cfg: &program.cfg,
+            lines: Vec::new(),
+            depth: 1,
+            report: TranspileReport::default(),
+        };
+
+        transpiler.transpile_cfg();
+
+        let source = transpiler.lines.join("\n") + "\n";
+
+        Transpiled {
+            source,
+            report: transpiler.report,
+        }
+    }
+
+    fn transpile_cfg(&mut self) {
+        self.lines.extend(vec![
+            format!(
+                "// transpiled from a tytle program, drawing on the `{}` canvas",
+                JS_CANVAS_ID
+            ),
+            "(function (canvas) {".to_string(),
+            "  \"use strict\";".to_string(),
+            String::new(),
+        ]);
+
+        self.lines
+            .extend(JS_RUNTIME.lines().map(|line| line.to_string()));
+        self.lines.push(String::new());
+
+        let globals = self.globals();
+        self.emit(&format!("const g = [{}];", globals.join(", ")));
+
+        let mut procs = self.cfg.procs.values().collect::<Vec<_>>();
+        procs.sort_by_key(|proc| proc.proc_id.0);
+
+        for proc in procs {
+            self.lines.push(String::new());
+            self.emit_proc(proc);
+        }
+
+        let main_entry = self.cfg.graph.get_entry_node_id();
+        let main_proc_id = self.cfg.jmp_table[&main_entry];
+
+        self.lines.push(String::new());
+        self.emit(&format!("rt.run({});", proc_fn(main_proc_id)));
+
+        self.emit_handlers();
+
+        self.lines.push(format!(
+            "}})(document.getElementById(\"{}\"));",
+            JS_CANVAS_ID
+        ));
+    }
+
+    fn emit(&mut self, line: &str) {
+        let line = format!("{}{}", "  ".repeat(self.depth), line);
+        self.lines.push(line);
+    }
+
+    fn untranslated(&mut self, construct: &str, reason: &str) {
+        self.report.add(construct, reason);
+        self.emit(&format!("// untranslated: {}", construct));
+    }
+
+    // the initial values of the globals, by their index (see `Memory::init_globals`)
+    fn globals(&self) -> Vec<String> {
+        (0..self.env.globals_index)
+            .map(|index| {
+                let var_id = self.env.globals_symbols[&index];
+                let var = self.env.symbol_table.get_var_by_id(var_id);
+
+                match var.var_type.as_ref().unwrap() {
+                    ExpressionType::Int => "0",
+                    ExpressionType::Bool => "false",
+                    ExpressionType::Str => "\"\"",
+                    ExpressionType::List(_) => "[]",
+                    ExpressionType::Proc | ExpressionType::Block => "null",
+                    ExpressionType::Color => "[0, 0, 0]",
+                    ExpressionType::Unit => panic!("variable can't be of type `Unit`"),
+                }
+                .to_string()
+            })
+            .collect()
+    }
+
+    fn emit_proc(&mut self, proc: &CfgProc) {
+        let name = &self.env.symbol_table.get_proc_by_id(proc.proc_id).name;
+        let node_ids = self.proc_nodes(proc.entry);
+
+        let catches = node_ids.iter().any(|node_id| {
+            let node = self.cfg.graph.get_node(*node_id);
+
+            node.insts
+                .iter()
+                .any(|inst| matches!(inst, CfgInstruction::Catch(..)))
+        });
+
+        // a block gets the slots of the frame it's been created within (see `rt.block`)
+        let params = match self.is_block(proc) {
+            true => vec!["cv".to_string()],
+            false => (0..proc.args_count)
+                .map(|index| format!("a{}", index))
+                .collect::<Vec<_>>(),
+        };
+
+        // the locals are initialized like the VM does (see `Interpreter::init_proc_locals`)
+        let locals = proc.locals.iter().map(|local_type| match local_type {
+            ExpressionType::Int => "-1",
+            ExpressionType::Bool => "false",
+            ExpressionType::Str => "\"\"",
+            ExpressionType::List(_) => "[]",
+            ExpressionType::Proc | ExpressionType::Block => "null",
+            ExpressionType::Color => "[0, 0, 0]",
+            ExpressionType::Unit => panic!("proc can't have a local of type `Unit`"),
+        });
+
+        let slots = params
+            .iter()
+            .map(|param| param.as_str())
+            .chain(locals)
+            .collect::<Vec<_>>();
+
+        self.emit(&format!("// {}", name));
+        self.emit(&format!(
+            "function {}({}) {{",
+            proc_fn(proc.proc_id),
+            params.join(", ")
+        ));
+        self.depth += 1;
+
+        self.emit(&format!("const v = [{}];", slots.join(", ")));
+        self.emit("const s = [];");
+
+        if catches {
+            self.emit("const c = [];");
+        }
+
+        self.emit(&format!("let n = {};", proc.entry));
+        self.lines.push(String::new());
+        self.emit("for (;;) {");
+        self.depth += 1;
+
+        if catches {
+            self.emit("try {");
+            self.depth += 1;
+        }
+
+        self.emit("switch (n) {");
+        self.depth += 1;
+
+        for node_id in node_ids {
+            self.emit_node(proc, node_id, catches);
+        }
+
+        self.depth -= 1;
+        self.emit("}");
+
+        if catches {
+            self.depth -= 1;
+            self.emit("} catch (err) {");
+            self.emit("  n = rt.unwind(err, c, s);");
+            self.emit("}");
+        }
+
+        self.depth -= 1;
+        self.emit("}");
+        self.depth -= 1;
+        self.emit("}");
+    }
+
+    // the nodes of the procedure starting at `entry`, reached by the CFG edges or by a `THROW`
+    fn proc_nodes(&self, entry: CfgNodeId) -> BTreeSet<CfgNodeId> {
+        let mut nodes = BTreeSet::new();
+        let mut pending = VecDeque::new();
+
+        pending.push_back(entry);
+
+        while let Some(node_id) = pending.pop_front() {
+            if !nodes.insert(node_id) {
+                continue;
+            }
+
+            let node = self.cfg.graph.get_node(node_id);
+
+            pending.extend(node.outgoing.iter().map(|edge| edge.node_id));
+
+            for inst in node.insts.iter() {
+                if let CfgInstruction::Catch(_, resume_id) = inst {
+                    pending.push_back(*resume_id);
+                }
+            }
+        }
+
+        nodes
+    }
+
+    fn emit_node(&mut self, proc: &CfgProc, node_id: CfgNodeId, catches: bool) {
+        let node = self.cfg.graph.get_node(node_id);
+
+        self.emit(&format!("case {}:", node_id));
+        self.depth += 1;
+
+        for inst in node.insts.iter() {
+            self.emit_inst(proc, inst, catches);
+        }
+
+        // the instructions following these are never executed (and so are the node edges)
+        let terminated = matches!(
+            node.insts.last(),
+            Some(CfgInstruction::Return)
+                | Some(CfgInstruction::EOC)
+                | Some(CfgInstruction::Throw(_))
+        );
+
+        if terminated {
+            self.depth -= 1;
+            return;
+        }
+
+        let mut edges = node.outgoing.iter().collect::<Vec<_>>();
+        edges.sort_by_key(|edge| edge.jmp_type);
+
+        // the jump rules of `Interpreter::choose_outgoing_edge`
+        match edges.as_slice() {
+            [] => self.emit_return(catches, "return;"),
+            [edge] if edge.jmp_type == CfgJumpType::Always => {
+                self.emit(&format!("n = {};", edge.node_id));
+                self.emit("break;");
+            }
+            _ => {
+                let when_true = edges
+                    .iter()
+                    .find(|edge| edge.jmp_type == CfgJumpType::WhenTrue);
+                let fallback = edges
+                    .iter()
+                    .find(|edge| edge.jmp_type == CfgJumpType::Fallback);
+
+                self.emit("if (s[s.length - 1]) {");
+
+                if let Some(edge) = when_true {
+                    self.emit("  s.pop();");
+                    self.emit(&format!("  n = {};", edge.node_id));
+                }
+
+                self.emit("} else {");
+
+                if let Some(edge) = fallback {
+                    self.emit(&format!("  n = {};", edge.node_id));
+                }
+
+                self.emit("}");
+                self.emit("break;");
+            }
+        }
+
+        self.depth -= 1;
+    }
+
+    fn emit_inst(&mut self, proc: &CfgProc, inst: &CfgInstruction, catches: bool) {
+        let line = match inst {
+            CfgInstruction::Command(cmd) => match self.command(cmd) {
+                Some(line) => line,
+                None => return,
+            },
+            CfgInstruction::Direction(direct) => {
+                let func = match direct {
+                    Direction::Forward => "forward",
+                    Direction::Backward => "backward",
+                    Direction::Right => "right",
+                    Direction::Left => "left",
+                    Direction::SetX => "setx",
+                    Direction::SetY => "sety",
+                };
+
+                format!("rt.{}(s.pop());", func)
+            }
+            CfgInstruction::Load(var_id) => format!("s.push({});", self.var_slot(proc, *var_id)),
+            CfgInstruction::Store(var_id) => {
+                format!("{} = s.pop();", self.var_slot(proc, *var_id))
+            }
+            CfgInstruction::Dup => "s.push(s[s.length - 1]);".to_string(),
+            CfgInstruction::Pop => "s.pop();".to_string(),
+            CfgInstruction::Call(entry) => {
+                let callee = self.cfg.get_proc(self.cfg.jmp_table[entry]);
+                let call = call_expr(&proc_fn(callee.proc_id), callee.args_count);
+
+                match callee.returns_value() {
+                    true => format!("s.push({});", call),
+                    false => format!("{};", call),
+                }
+            }
+            CfgInstruction::NativeCall(proc_id) => {
+                let name = &self.env.symbol_table.get_proc_by_id(*proc_id).name;
+                self.report
+                    .add(name, "native procedures are implemented by the embedder");
+
+                format!(
+                    "throw new Error({});",
+                    js_str(&format!("the native procedure `{}` isn't available", name))
+                )
+            }
+            CfgInstruction::Primitive(prim) => {
+                let call = call_expr(&format!("rt.{}", prim.name()), prim.params_types().len());
+
+                match prim.return_type() {
+                    ExpressionType::Unit => format!("{};", call),
+                    _ => format!("s.push({});", call),
+                }
+            }
+            CfgInstruction::Bool(v) => format!("s.push({});", v),
+            CfgInstruction::Int(v) => format!("s.push({});", v),
+            CfgInstruction::Str(v) => format!("s.push({});", js_str(v)),
+            CfgInstruction::Return => {
+                let ret = match proc.returns_value() {
+                    true => "return s.pop();",
+                    false => "return;",
+                };
+
+                return self.emit_return(catches, ret);
+            }
+            CfgInstruction::Trap => "debugger;".to_string(),
+            CfgInstruction::Print => "rt.print(s.pop());".to_string(),
+            CfgInstruction::Label => "rt.label(s.pop());".to_string(),
+            CfgInstruction::SetLabelFont => "rt.labelFont(s.pop());".to_string(),
+            CfgInstruction::SetLabelSize => "rt.labelSize(s.pop());".to_string(),
+            CfgInstruction::SetShape => "rt.setShape(s.pop());".to_string(),
+            CfgInstruction::OpenWrite => "rt.openWrite(s.pop());".to_string(),
+            CfgInstruction::SetWrite => "rt.setWrite(s.pop());".to_string(),
+            CfgInstruction::Close => "rt.close(s.pop());".to_string(),
+            CfgInstruction::SetPenColor => "rt.setPenColor(s.pop());".to_string(),
+            CfgInstruction::Tone => "rt.tone(s);".to_string(),
+            CfgInstruction::EOC => return self.emit_return(catches, "return;"),
+            CfgInstruction::Add => "rt.add(s);".to_string(),
+            CfgInstruction::Mul => "rt.mul(s);".to_string(),
+            CfgInstruction::Div => "rt.div(s);".to_string(),
+            CfgInstruction::Not => "rt.not(s);".to_string(),
+            CfgInstruction::And => "rt.and(s);".to_string(),
+            CfgInstruction::Or => "rt.or(s);".to_string(),
+            CfgInstruction::GreaterThan => "rt.gt(s);".to_string(),
+            CfgInstruction::LessThan => "rt.lt(s);".to_string(),
+            CfgInstruction::Equal => "rt.eq(s);".to_string(),
+            CfgInstruction::NotEqual => "rt.ne(s);".to_string(),
+            CfgInstruction::MakeList(n) => format!("rt.makeList(s, {});", n),
+            CfgInstruction::ListLen => "rt.listLen(s);".to_string(),
+            CfgInstruction::ListItem => "rt.listItem(s);".to_string(),
+            CfgInstruction::ListPush => "rt.listPush(s);".to_string(),
+            CfgInstruction::ProcRef(proc_id) => format!("s.push({});", self.proc_ref(*proc_id)),
+            CfgInstruction::Apply(n) => format!("rt.apply(s, {});", n),
+            CfgInstruction::Block(proc_id) => {
+                let slots = match self.is_block(proc) {
+                    true => "cv",
+                    false => "v",
+                };
+
+                format!("s.push(rt.block({}, {}));", proc_fn(*proc_id), slots)
+            }
+            CfgInstruction::Run => "rt.runBlock(s);".to_string(),
+            CfgInstruction::Catch(tag, resume_id) => {
+                format!("rt.catch(c, s, {}, {});", js_str(tag), resume_id)
+            }
+            CfgInstruction::EndCatch => "rt.endCatch(c);".to_string(),
+            CfgInstruction::Throw(tag) => format!("throw new Throw({});", js_str(tag)),
+            CfgInstruction::Trace(proc_id) | CfgInstruction::Untrace(proc_id) => {
+                let keyword = match inst {
+                    CfgInstruction::Trace(_) => "TRACE",
+                    _ => "UNTRACE",
+                };
+
+                let name = &self.env.symbol_table.get_proc_by_id(*proc_id).name;
+                let construct = format!("{} \"{}", keyword, name);
+
+                return self.untranslated(&construct, "the calls are traced by the host");
+            }
+            CfgInstruction::Assert { message, .. } => format!("rt.assert(s, {});", js_str(message)),
+        };
+
+        self.emit(&line);
+    }
+
+    // the frame `CATCH` markers are popped on return (see `rt.leave`)
+    fn emit_return(&mut self, catches: bool, ret: &str) {
+        if catches {
+            self.emit("rt.leave(c);");
+        }
+
+        self.emit(ret);
+    }
+
+    fn command(&mut self, cmd: &Command) -> Option<String> {
+        let func = match cmd {
+            Command::XCor => "xcor",
+            Command::YCor => "ycor",
+            Command::PenUp => "penup",
+            Command::PenDown => "pendown",
+            Command::PenErase => "penerase",
+            Command::Clean | Command::ClearScreen => "clean",
+            Command::Trap => return Some("debugger;".to_string()),
+            Command::Stamp => "stamp",
+            Command::Beep => "beep",
+            Command::PushState => "pushState",
+            Command::PopState => "popState",
+            Command::ShowTurtle | Command::HideTurtle | Command::Wait => return None,
+            Command::SetBackgroundColor => {
+                self.untranslated("SETBACKGROUND", "the background is picked by the host");
+                return None;
+            }
+            Command::Stop => {
+                self.untranslated("STOP", "it's handled by the host");
+                return None;
+            }
+        };
+
+        Some(format!("rt.{}();", func))
+    }
+
+    // the JS expression of the variable slot (within the frame of `proc`)
+    fn var_slot(&self, proc: &CfgProc, var_id: SymbolId) -> String {
+        let var = self.env.symbol_table.get_var_by_id(var_id);
+        let index = var.index.unwrap();
+
+        match var.global {
+            true => format!("g[{}]", index),
+            false if self.is_block(proc) && !var.is_tmp() => format!("cv[{}]", index),
+            false => format!("v[{}]", index),
+        }
+    }
+
+    fn is_block(&self, proc: &CfgProc) -> bool {
+        self.env.blocks.contains_key(&proc.proc_id)
+    }
+
+    fn proc_ref(&mut self, proc_id: SymbolId) -> String {
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+
+        if let Some(prim) = proc.primitive {
+            return match prim.rest() {
+                true => format!("rt.rest(rt.{})", Primitive::name(&prim)),
+                false => format!("rt.{}", Primitive::name(&prim)),
+            };
+        }
+
+        if proc.native {
+            let reason = "native procedures are implemented by the embedder";
+            self.report.add(&proc.name, reason);
+
+            return "null".to_string();
+        }
+
+        match proc.rest {
+            true => format!("rt.rest({})", proc_fn(proc_id)),
+            false => proc_fn(proc_id),
+        }
+    }
+
+    fn emit_handlers(&mut self) {
+        let mut keys = self.env.key_handlers.iter().collect::<Vec<_>>();
+        keys.sort_by_key(|(key, _)| key.as_str());
+
+        if !keys.is_empty() {
+            let handlers = keys
+                .iter()
+                .map(|(key, proc_id)| format!("{}: {}", js_str(key), proc_fn(**proc_id)))
+                .collect::<Vec<_>>();
+
+            // the keys are named the way the tytle hosts name them (e.g `"K` or `"LEFT`)
+            self.lines.push(String::new());
+            self.emit(&format!(
+                "const keyHandlers = {{ {} }};",
+                handlers.join(", ")
+            ));
+            self.emit("document.addEventListener(\"keydown\", (event) => {");
+            self.emit(concat!(
+                "  const key = event.key === \" \" ? \"SPACE\" : ",
+                "event.key.replace(/^Arrow/, \"\");"
+            ));
+            self.emit("  const handler = keyHandlers[key.toUpperCase()];");
+            self.emit("  if (handler) rt.run(handler);");
+            self.emit("});");
+        }
+
+        if let Some((interval, proc_id)) = self.env.tick_handler {
+            self.lines.push(String::new());
+            self.emit(&format!(
+                "setInterval(() => rt.run({}), {});",
+                proc_fn(proc_id),
+                interval
+            ));
+        }
+    }
+}
+
+// the name of the JS function of the procedure
+fn proc_fn(proc_id: SymbolId) -> String {
+    format!("p{}", proc_id.0)
+}
+
+// a call of `func` with the last `args_count` items of the operand stack
+fn call_expr(func: &str, args_count: usize) -> String {
+    match args_count {
+        0 => format!("{}()", func),
+        _ => format!("{}(...rt.args(s, {}))", func, args_count),
+    }
+}
+
+// a JS string literal
+fn js_str(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
+}
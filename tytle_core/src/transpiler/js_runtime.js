@@ -0,0 +1,394 @@
+  // the tytle runtime: the turtle (moving the tytle way), the operators and the `CATCH` markers
+
+  const ctx = canvas.getContext("2d");
+  const turtle = { x: 0, y: 0, pen: "DOWN", shape: "arrow", color: [0, 0, 0] };
+  const label = { font: "sans-serif", size: 12 };
+  const mouse = { x: 0, y: 0, pressed: false };
+
+  // the turtle states saved by `PUSHSTATE` (the last saved is last), see `MAX_STATE_DEPTH`
+  const states = [];
+
+  // the values kept by `STORE` (in memory when the page has no local storage)
+  const storage = (() => {
+    try {
+      return window.localStorage;
+    } catch (_) {
+      const values = new Map();
+      return {
+        setItem: (key, value) => values.set(key, value),
+        getItem: (key) => (values.has(key) ? values.get(key) : null),
+      };
+    }
+  })();
+
+  // the lines written to the streams opened by `OPENWRITE`, and the one `PRINT` writes to
+  const streams = new Map();
+  let writeStream = null;
+
+  // the pointer position, in the tytle coordinates
+  function trackMouse(event) {
+    const rect = canvas.getBoundingClientRect();
+
+    mouse.x = Math.round(event.clientX - rect.left);
+    mouse.y = Math.round(canvas.height - (event.clientY - rect.top));
+  }
+
+  canvas.addEventListener("mousemove", trackMouse);
+  canvas.addEventListener("mousedown", (event) => {
+    trackMouse(event);
+    mouse.pressed = true;
+  });
+  window.addEventListener("mouseup", () => (mouse.pressed = false));
+
+  // the tags of the active `CATCH` markers of all the frames (the innermost is last)
+  const catching = [];
+
+  class Throw extends Error {
+    constructor(tag) {
+      super(`THROW "${tag}`);
+      this.tag = tag;
+    }
+  }
+
+  // the canvas y axis goes down, while the tytle one goes up
+  function moveTo(x, y) {
+    if (turtle.pen !== "UP") {
+      ctx.save();
+
+      if (turtle.pen === "ERASE") {
+        ctx.globalCompositeOperation = "destination-out";
+        ctx.lineWidth = 3;
+      }
+
+      ctx.beginPath();
+      ctx.moveTo(turtle.x, canvas.height - turtle.y);
+      ctx.lineTo(x, canvas.height - y);
+      ctx.stroke();
+      ctx.restore();
+    }
+
+    turtle.x = x;
+    turtle.y = y;
+  }
+
+  // the tones are queued one after the other (created lazily, browsers start audio on demand)
+  const audio = { ctx: null, at: 0 };
+
+  function playTone(freq, ms) {
+    if (audio.ctx === null) audio.ctx = new AudioContext();
+
+    const start = Math.max(audio.at, audio.ctx.currentTime);
+    const oscillator = audio.ctx.createOscillator();
+
+    oscillator.frequency.value = freq;
+    oscillator.connect(audio.ctx.destination);
+    oscillator.start(start);
+    oscillator.stop(start + ms / 1000);
+
+    audio.at = start + ms / 1000;
+  }
+
+  // the named colors, ordered by their palette index (see `Color::palette`).
+  // a color is an `[r, g, b]` array
+  const palette = [
+    ["BLACK", [0, 0, 0]],
+    ["BLUE", [0, 0, 255]],
+    ["GREEN", [0, 255, 0]],
+    ["CYAN", [0, 255, 255]],
+    ["RED", [255, 0, 0]],
+    ["MAGENTA", [255, 0, 255]],
+    ["YELLOW", [255, 255, 0]],
+    ["WHITE", [255, 255, 255]],
+    ["BROWN", [155, 96, 59]],
+    ["TAN", [197, 136, 18]],
+    ["FOREST", [100, 162, 64]],
+    ["AQUA", [120, 187, 187]],
+    ["SALMON", [255, 149, 119]],
+    ["PURPLE", [144, 113, 208]],
+    ["ORANGE", [255, 163, 0]],
+    ["GREY", [183, 183, 183]],
+  ];
+
+  // a palette index (or a color name) is converted to its color
+  function toColor(value) {
+    if (Array.isArray(value)) return value;
+
+    const entry =
+      typeof value === "number"
+        ? palette[value]
+        : palette.find(([name]) => name === value.toUpperCase());
+
+    if (entry === undefined) throw new Error(`unknown color \`${value}\``);
+    return entry[1];
+  }
+
+  function equals(a, b) {
+    return JSON.stringify(a) === JSON.stringify(b);
+  }
+
+  // a value as written in code (`quoted`), or as printed by `PRINT`
+  function show(value, quoted) {
+    if (typeof value === "boolean") return value ? "TRUE" : "FALSE";
+    if (typeof value === "string") return quoted ? `"${value}` : value;
+    if (Array.isArray(value)) return `[${value.map((item) => show(item, true)).join(" ")}]`;
+    return String(value);
+  }
+
+  const rt = {
+    forward: (count) => moveTo(turtle.x, Math.max(turtle.y + count, 0)),
+    backward: (count) => moveTo(turtle.x, Math.max(turtle.y - count, 0)),
+    right: (count) => moveTo(Math.max(turtle.x + count, 0), turtle.y),
+    left: (count) => moveTo(Math.max(turtle.x - count, 0), turtle.y),
+    setx: (x) => moveTo(Math.max(x, 0), turtle.y),
+    sety: (y) => moveTo(turtle.x, Math.max(y, 0)),
+
+    penup: () => (turtle.pen = "UP"),
+    pendown: () => (turtle.pen = "DOWN"),
+    penerase: () => (turtle.pen = "ERASE"),
+    clean: () => ctx.clearRect(0, 0, canvas.width, canvas.height),
+    xcor: () => console.log(`XCOR = ${turtle.x}`),
+    ycor: () => console.log(`YCOR = ${turtle.y}`),
+
+    // the turtle shape (a circle, or a triangle pointing up, the tytle heading).
+    // the images registered by the embedder aren't known to the canvas, so they stamp a triangle
+    setShape: (shape) => (turtle.shape = shape.toLowerCase()),
+    setPenColor(value) {
+      turtle.color = toColor(value);
+      ctx.strokeStyle = `rgb(${turtle.color.join(", ")})`;
+    },
+    stamp() {
+      const x = turtle.x;
+      const y = canvas.height - turtle.y;
+
+      ctx.beginPath();
+
+      if (turtle.shape === "circle") {
+        ctx.arc(x, y, 6, 0, 2 * Math.PI);
+        ctx.fill();
+        return;
+      }
+
+      ctx.moveTo(x, y - 8);
+      ctx.lineTo(x - 5, y + 5);
+      ctx.lineTo(x + 5, y + 5);
+      ctx.closePath();
+      ctx.fill();
+    },
+    label(text) {
+      ctx.font = `${label.size}px ${label.font}`;
+      ctx.fillText(text, turtle.x, canvas.height - turtle.y);
+    },
+    tone(s) {
+      const ms = Math.max(s.pop(), 0);
+      playTone(Math.max(s.pop(), 0), ms);
+    },
+    beep: () => playTone(880, 200),
+    pushState() {
+      if (states.length === 1000) throw new Error("too many saved states");
+      states.push({ ...turtle });
+    },
+    popState() {
+      if (states.length === 0) throw new Error("`POPSTATE` with no saved state");
+
+      Object.assign(turtle, states.pop());
+      ctx.strokeStyle = `rgb(${turtle.color.join(", ")})`;
+    },
+    print(value) {
+      if (writeStream === null) return console.log(value);
+      streams.get(writeStream).push(show(value, false));
+    },
+    openWrite: (name) => streams.set(name, []),
+    setWrite(name) {
+      if (name !== "" && !streams.has(name)) throw new Error(`stream \`${name}\` isn't open`);
+      writeStream = name === "" ? null : name;
+    },
+    close(name) {
+      if (!streams.has(name)) throw new Error(`stream \`${name}\` isn't open`);
+
+      console.log(`[${name}]\n${streams.get(name).join("\n")}`);
+      streams.delete(name);
+      if (writeStream === name) writeStream = null;
+    },
+    labelFont: (font) => (label.font = font),
+    labelSize: (size) => (label.size = size),
+
+    // pops the last `n` items (in the order they've been pushed)
+    args: (s, n) => s.splice(s.length - n, n),
+
+    add: (s) => s.push(s.pop() + s.pop()),
+    mul: (s) => s.push(s.pop() * s.pop()),
+    div(s) {
+      const a = s.pop();
+      const b = s.pop();
+
+      if (a === 0) throw new Error("division by zero");
+      s.push(Math.trunc(b / a));
+    },
+    not: (s) => s.push(!s.pop()),
+    and(s) {
+      const a = s.pop();
+      s.push(s.pop() && a);
+    },
+    or(s) {
+      const a = s.pop();
+      s.push(s.pop() || a);
+    },
+    gt(s) {
+      const a = s.pop();
+      s.push(s.pop() > a);
+    },
+    lt(s) {
+      const a = s.pop();
+      s.push(s.pop() < a);
+    },
+    eq: (s) => s.push(equals(s.pop(), s.pop())),
+    ne: (s) => s.push(!equals(s.pop(), s.pop())),
+
+    makeList: (s, n) => s.push(rt.args(s, n)),
+    listLen: (s) => s.push(s.pop().length),
+    listItem(s) {
+      const index = s.pop();
+      const items = s.pop();
+
+      if (index < 0 || index >= items.length) {
+        throw new Error(`item ${index + 1} is out of range (the list has ${items.length} items)`);
+      }
+      s.push(items[index]);
+    },
+    listPush(s) {
+      const items = s.pop();
+      s.push([...items, s.pop()]);
+    },
+
+    WORD: (a, b) => a + b,
+    COUNT: (w) => [...w].length,
+    ITEM(index, w) {
+      const chars = [...w];
+
+      if (index < 1 || index > chars.length) {
+        throw new Error(`item ${index} is out of range (the word has ${chars.length} items)`);
+      }
+      return chars[index - 1];
+    },
+    UPPERCASE: (w) => w.toUpperCase(),
+    LOWERCASE: (w) => w.toLowerCase(),
+    PARSE(w) {
+      if (!/^[+-]?\d+$/.test(w)) throw new Error(`\`${w}\` isn't a number`);
+      return parseInt(w, 10);
+    },
+    SCREENWIDTH: () => canvas.width,
+    SCREENHEIGHT: () => canvas.height,
+    MOUSEX: () => mouse.x,
+    MOUSEY: () => mouse.y,
+    BUTTONP: () => mouse.pressed,
+    RGB: (r, g, b) => [r, g, b].map((v) => Math.min(Math.max(v, 0), 255)),
+    PALETTE: (index) => toColor(index),
+    NAMEDCOLOR: (name) => toColor(name),
+    PENCOLOR: () => turtle.color,
+    FORMAT(f, args) {
+      let next = 0;
+      const text = f.replace(/~(.?)/g, (_, directive) => {
+        if (directive === "~") return "~";
+        if (directive === "" || !"aAsS".includes(directive)) {
+          throw new Error(`unknown FORMAT directive \`~${directive}\``);
+        }
+        if (next >= args.length) throw new Error("FORMAT string has more placeholders than args");
+        return show(args[next++], "sS".includes(directive));
+      });
+
+      if (next < args.length) throw new Error("FORMAT string has fewer placeholders than args");
+      return text;
+    },
+
+    STORE: (key, value) => storage.setItem(`tytle:${key}`, show(value, false)),
+    RETRIEVE: (key) => storage.getItem(`tytle:${key}`) ?? "",
+
+    // calls the popped procedure reference with the `n` args below it, discarding its return value
+    apply(s, n) {
+      const proc = s.pop();
+      const args = rt.args(s, n);
+
+      if (proc === null) throw new Error("APPLY of an unassigned procedure reference");
+      if (proc.fixed !== undefined) {
+        if (n < proc.fixed) {
+          throw new Error(`APPLY of a procedure expecting at least ${proc.fixed} args`);
+        }
+      } else if (proc.length !== n) {
+        throw new Error(`APPLY of a procedure expecting ${proc.length} args`);
+      }
+      proc(...args);
+    },
+
+    // a reference to a procedure having a rest param, collecting the args past its other params.
+    // it's created once, so references of the same procedure are still equal
+    rest(proc) {
+      if (proc.ref === undefined) {
+        const fixed = proc.length - 1;
+        proc.ref = (...args) => proc(...args.slice(0, fixed), args.slice(fixed));
+        proc.ref.fixed = fixed;
+      }
+      return proc.ref;
+    },
+
+    // a block, running its procedure with the slots `cv` of the frame it's been created within
+    block(proc, cv) {
+      return () => proc(cv);
+    },
+
+    runBlock(s) {
+      const block = s.pop();
+
+      if (block === null) throw new Error("RUN of an unassigned block");
+      block();
+    },
+
+    assert(s, message) {
+      if (!s.pop()) throw new Error(`assertion failed: ${message}`);
+    },
+
+    catch(c, s, tag, node) {
+      c.push([tag, node, s.length]);
+      catching.push(tag);
+    },
+    endCatch(c) {
+      c.pop();
+      catching.pop();
+    },
+
+    // pops the markers of a frame which is returning
+    leave(c) {
+      catching.length -= c.length;
+      c.length = 0;
+    },
+
+    // returns the node of the innermost `CATCH` (of the frame) of the thrown tag, or rethrows.
+    // runtime errors (including a `THROW` of a tag which isn't caught) are thrown as `ERROR`
+    unwind(err, c, s) {
+      let tag = err instanceof Throw ? err.tag : "ERROR";
+
+      if (!catching.includes(tag)) tag = "ERROR";
+
+      while (c.length > 0) {
+        const [markerTag, node, depth] = c[c.length - 1];
+
+        if (markerTag === tag) {
+          s.length = depth;
+          return node;
+        }
+
+        rt.endCatch(c);
+      }
+
+      throw err;
+    },
+
+    // runs a procedure of the program, reporting the errors it doesn't catch
+    run(proc) {
+      try {
+        proc();
+      } catch (err) {
+        catching.length = 0;
+        console.error(err.message);
+      }
+    },
+  };
@@ -0,0 +1,15 @@
+// transpiling tytle programs into programs of other ecosystems:
+// * `PythonTranspiler` - a Python script over the standard `turtle` module
+// * `JsCanvasTranspiler` - a self-contained JavaScript script drawing on an HTML5 canvas
+//
+// the Python transpiler walks the parsed AST (so the program doesn't have to pass the semantic
+// analysis), while the JavaScript one lowers the CFG of a compiled program.
+// both list the constructs they couldn't translate in a `TranspileReport`
+
+mod js_canvas_transpiler;
+mod python_transpiler;
+mod transpile_report;
+
+pub use js_canvas_transpiler::{JsCanvasTranspiler, JS_CANVAS_ID};
+pub use python_transpiler::PythonTranspiler;
+pub use transpile_report::{TranspileReport, Transpiled, Untranslated};
@@ -0,0 +1,971 @@
+use crate::ast::expression::*;
+use crate::ast::semantic::Primitive;
+use crate::ast::statement::*;
+use crate::ast::Ast;
+use crate::compat::collections::{BTreeSet, HashMap, HashSet};
+use crate::compat::prelude::*;
+use crate::transpiler::{TranspileReport, Transpiled};
+use crate::vm::{TurtleShape, COLOR_PALETTE};
+
+const INDENT: &str = "    ";
+
+// the name of the function holding the root statements of the program
+const MAIN_FN: &str = "main";
+
+// the helper moving the turtle the tytle way (see `Turtle::exec_direct`)
+const MOVE_FN: &[&str] = &[
+    "def move(dx, dy):",
+    "    turtle.goto(max(turtle.xcor() + dx, 0), max(turtle.ycor() + dy, 0))",
+];
+
+// the exception raised by `THROW` (emitted only when the program uses `CATCH` / `THROW`)
+const THROW_CLASS: &[&str] = &[
+    "class Throw(Exception):",
+    "    def __init__(self, tag):",
+    "        super().__init__(tag)",
+    "        self.tag = tag",
+];
+
+// the font of `LABEL` (emitted only when the program uses the label commands),
+// a list so `SETLABELFONT` / `SETLABELSIZE` can update it without a `global` declaration
+const LABEL_FONT: &str = "label_font = [\"sans-serif\", 12]";
+
+// the colors are `(r, g, b)` tuples
+// (emitted only when the program uses colors, see `COLOR_PALETTE`).
+// the pen color is a list so `SETPENCOLOR` can update it without a `global` declaration
+const COLOR_FN: &[&str] = &[
+    "pen_color = [(0, 0, 0)]",
+    "",
+    "",
+    "def color(value):",
+    "    if isinstance(value, tuple):",
+    "        return tuple(min(max(v, 0), 255) for v in value)",
+    "    if isinstance(value, int):",
+    "        return palette[value][1]",
+    "    return dict(palette)[value.upper()]",
+];
+
+// the stack of `PUSHSTATE` (emitted only when the program uses the state commands).
+// the turtle is moved back with its pen up, so `POPSTATE` doesn't draw
+const STATE_FNS: &[&str] = &[
+    "states = []",
+    "",
+    "",
+    "def push_state():",
+    "    state = (turtle.position(), turtle.heading(), turtle.isdown(), turtle.pencolor())",
+    "    states.append(state + (turtle.isvisible(), turtle.shape()))",
+    "",
+    "",
+    "def pop_state():",
+    "    position, heading, down, color, visible, shape = states.pop()",
+    "    turtle.penup()",
+    "    turtle.goto(position)",
+    "    turtle.setheading(heading)",
+    "    turtle.pencolor(color)",
+    "    turtle.shape(shape)",
+    "    if visible:",
+    "        turtle.showturtle()",
+    "    else:",
+    "        turtle.hideturtle()",
+    "    if down:",
+    "        turtle.pendown()",
+];
+
+// the `FORMAT` helpers (emitted only when the program uses `FORMAT`), see `Primitive::Format`
+const FORMAT_FNS: &[&str] = &[
+    "def show(value, quoted):",
+    "    if isinstance(value, bool):",
+    "        return \"TRUE\" if value else \"FALSE\"",
+    "    if isinstance(value, str):",
+    "        return \"\\\"\" + value if quoted else value",
+    "    if isinstance(value, list):",
+    "        return \"[\" + \" \".join(show(item, True) for item in value) + \"]\"",
+    "    return str(value)",
+    "",
+    "",
+    "def format_text(f, *args):",
+    "    text, args, i = \"\", list(args), 0",
+    "    while i < len(f):",
+    "        directive = f[i:i + 2]",
+    "        if directive == \"~~\":",
+    "            text += \"~\"",
+    "        elif directive in (\"~a\", \"~A\", \"~s\", \"~S\"):",
+    "            text += show(args.pop(0), directive in (\"~s\", \"~S\"))",
+    "        else:",
+    "            text += f[i]",
+    "            i -= 1",
+    "        i += 2",
+    "    return text",
+];
+
+// the `STORE` / `RETRIEVE` helpers (emitted only when the program uses them), keeping the values
+// in a JSON file across the runs of the script. `store` prints the value via `show`
+const STORE_FNS: &[&str] = &[
+    "STORE_FILE = \"tytle_store.json\"",
+    "",
+    "",
+    "def stored_values():",
+    "    import json",
+    "    try:",
+    "        with open(STORE_FILE) as f:",
+    "            return json.load(f)",
+    "    except FileNotFoundError:",
+    "        return {}",
+    "",
+    "",
+    "def store(key, value):",
+    "    import json",
+    "    values = stored_values()",
+    "    values[key] = show(value, False)",
+    "    with open(STORE_FILE, \"w\") as f:",
+    "        json.dump(values, f)",
+    "",
+    "",
+    "def retrieve(key):",
+    "    return stored_values().get(key, \"\")",
+];
+
+// the streams of `OPENWRITE` are files (named as the streams),
+// written by `print` after a `SETWRITE`
+const STREAM_FNS: &[&str] = &[
+    "import builtins",
+    "",
+    "streams = {}",
+    "write_stream = [None]",
+    "",
+    "",
+    "def print(*args):",
+    "    builtins.print(*args, file=streams.get(write_stream[0]))",
+    "",
+    "",
+    "def open_write(name):",
+    "    streams[name] = open(name, \"w\")",
+    "",
+    "",
+    "def set_write(name):",
+    "    if name and name not in streams:",
+    "        raise RuntimeError(\"stream `%s` isn't open\" % name)",
+    "    write_stream[0] = name or None",
+    "",
+    "",
+    "def close_stream(name):",
+    "    streams.pop(name).close()",
+    "    if write_stream[0] == name:",
+    "        write_stream[0] = None",
+];
+
+// Python `turtle` Transpiler.
+//
+// walks the AST of a program and emits an equivalent Python script over the `turtle` module:
+// * procedures become functions (with their doc comment summary as a docstring),
+//   and the root statements become the `main` function
+// * `REPEAT` and `FOREACH` become `for` loops, `CATCH` / `THROW` become `try` / `raise`
+// * `ONKEY` / `ONTICK` handlers become functions registered via `turtle.onkey` / `turtle.ontimer`
+// * the turtle keeps moving the tytle way: `FORWARD` / `BACKWARD` along the y axis,
+//   `RIGHT` / `LEFT` along the x axis, and its coordinates never go below 0
+//
+// the procedures are emitted before `main`, since tytle allows calling them above their definition.
+// untranslatable constructs (e.g `SETBACKGROUND`, whose color is picked by the host) are replaced
+// with a `pass` statement commented with the construct, and are listed in the `TranspileReport`
+pub struct PythonTranspiler {
+    lines: Vec<String>,
+    depth: usize,
+
+    // the procedures defined by the program, with their signatures (params count and rest param).
+    // Python functions can't be overloaded, so an overloaded procedure is named by its arity
+    procs: HashMap<String, Vec<(usize, bool)>>,
+
+    // the Python names of the enclosing `FOREACH` loops variables (the innermost is last)
+    foreach_vars: Vec<String>,
+
+    uses_throw: bool,
+    uses_keys: bool,
+    uses_labels: bool,
+    uses_colors: bool,
+    uses_format: bool,
+    uses_store: bool,
+    uses_streams: bool,
+    uses_states: bool,
+
+    // whether the program has a `SETPENCOLOR`,
+    // so `PENDOWN` restores the pen color (after `PENERASE`)
+    sets_pen_color: bool,
+    report: TranspileReport,
+}
+
+impl PythonTranspiler {
+    pub fn transpile(ast: &Ast) -> Transpiled {
+        let mut transpiler = Self {
+            lines: Vec::new(),
+            depth: 0,
+            procs: HashMap::new(),
+            foreach_vars: Vec::new(),
+            uses_throw: false,
+            uses_keys: false,
+            uses_labels: false,
+            uses_colors: false,
+            uses_format: false,
+            uses_store: false,
+            uses_streams: false,
+            uses_states: false,
+            sets_pen_color: false,
+            report: TranspileReport::default(),
+        };
+
+        let source = transpiler.transpile_ast(ast);
+
+        Transpiled {
+            source,
+            report: transpiler.report,
+        }
+    }
+
+    fn transpile_ast(&mut self, ast: &Ast) -> String {
+        for stmt in &ast.statements {
+            if let Statement::Procedure(proc_stmt) = stmt {
+                let rest = proc_stmt.params.last().is_some_and(|param| param.rest);
+                let signature = (proc_stmt.params.len(), rest);
+
+                self.procs
+                    .entry(proc_stmt.name.clone())
+                    .or_default()
+                    .push(signature);
+            }
+        }
+
+        self.sets_pen_color = sets_pen_color(&ast.statements);
+
+        let mut main_stmt = ProcedureStmt::new(MAIN_FN.to_string());
+
+        for stmt in &ast.statements {
+            match stmt {
+                Statement::Procedure(proc_stmt) => {
+                    let name = self.def_name(proc_stmt);
+                    self.emit_proc(proc_stmt, &name, None)
+                }
+                Statement::OnEvent(on_event_stmt) => {
+                    let event = &on_event_stmt.event;
+
+                    // a timer handler schedules its next tick first, so `HALT` won't stop the timer
+                    let prologue = match event {
+                        Event::Tick(_) => Some(Self::registration(event)),
+                        Event::Key(_) => None,
+                    };
+
+                    self.emit_proc(&on_event_stmt.proc_stmt, &handler_name(event), prologue);
+                    main_stmt.block.add_statement(stmt.clone());
+                }
+                _ => main_stmt.block.add_statement(stmt.clone()),
+            }
+        }
+
+        self.emit_proc(&main_stmt, MAIN_FN, None);
+
+        let mut lines = vec![
+            "# transpiled from a tytle program".to_string(),
+            "import turtle".to_string(),
+            String::new(),
+            String::new(),
+        ];
+
+        if self.uses_throw {
+            lines.extend(THROW_CLASS.iter().map(|line| line.to_string()));
+            lines.extend(vec![String::new(), String::new()]);
+        }
+
+        if self.uses_labels {
+            lines.push(LABEL_FONT.to_string());
+            lines.extend(vec![String::new(), String::new()]);
+        }
+
+        if self.uses_colors || self.sets_pen_color {
+            lines.push("turtle.colormode(255)".to_string());
+            lines.push("palette = [".to_string());
+
+            for (name, color) in COLOR_PALETTE.iter() {
+                let (r, g, b) = color.to_rgb();
+                lines.push(format!("{}({}, ({}, {}, {})),", INDENT, py_str(name), r, g, b));
+            }
+
+            lines.push("]".to_string());
+            lines.extend(COLOR_FN.iter().map(|line| line.to_string()));
+            lines.extend(vec![String::new(), String::new()]);
+        }
+
+        if self.uses_states {
+            lines.extend(STATE_FNS.iter().map(|line| line.to_string()));
+            lines.extend(vec![String::new(), String::new()]);
+        }
+
+        if self.uses_format {
+            lines.extend(FORMAT_FNS.iter().map(|line| line.to_string()));
+            lines.extend(vec![String::new(), String::new()]);
+        }
+
+        if self.uses_store {
+            lines.extend(STORE_FNS.iter().map(|line| line.to_string()));
+            lines.extend(vec![String::new(), String::new()]);
+        }
+
+        if self.uses_streams {
+            lines.extend(STREAM_FNS.iter().map(|line| line.to_string()));
+            lines.extend(vec![String::new(), String::new()]);
+        }
+
+        lines.extend(MOVE_FN.iter().map(|line| line.to_string()));
+        lines.extend(vec![String::new(), String::new()]);
+        lines.append(&mut self.lines);
+
+        lines.push(format!("{}()", MAIN_FN));
+
+        if self.uses_keys {
+            lines.push("turtle.listen()".to_string());
+        }
+
+        lines.push("turtle.done()".to_string());
+
+        lines.join("\n") + "\n"
+    }
+
+    fn emit(&mut self, line: &str) {
+        let line = format!("{}{}", INDENT.repeat(self.depth), line);
+        self.lines.push(line);
+    }
+
+    fn untranslated(&mut self, construct: &str, reason: &str) {
+        self.report.add(construct, reason);
+        self.emit(&format!("pass  # untranslated: {}", construct));
+    }
+
+    fn emit_proc(&mut self, proc_stmt: &ProcedureStmt, name: &str, prologue: Option<String>) {
+        let params = proc_stmt
+            .params
+            .iter()
+            .map(|param| match param.rest {
+                true => format!("*{}", param.param_name),
+                false => param.param_name.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        self.emit(&format!("def {}({}):", name, params.join(", ")));
+        self.depth += 1;
+
+        let summary = proc_stmt.doc.as_ref().map(|doc| doc.summary.as_str());
+
+        if let Some(summary) = summary.filter(|summary| !summary.is_empty()) {
+            self.emit(&format!("\"\"\"{}\"\"\"", summary.replace('"', "\\\"")));
+        }
+
+        let globals = assigned_globals(proc_stmt);
+
+        if !globals.is_empty() {
+            let globals = globals.into_iter().collect::<Vec<_>>();
+            self.emit(&format!("global {}", globals.join(", ")));
+        }
+
+        // the remaining args are packed into a tuple, while tytle lists are Python lists
+        if let Some(param) = proc_stmt.params.last().filter(|param| param.rest) {
+            let name = &param.param_name;
+            self.emit(&format!("{} = list({})", name, name));
+        }
+
+        if let Some(prologue) = prologue {
+            self.emit(&prologue);
+        }
+
+        self.depth -= 1;
+        self.emit_block(&proc_stmt.block);
+
+        self.lines.push(String::new());
+        self.lines.push(String::new());
+    }
+
+    fn emit_block(&mut self, block: &BlockStatement) {
+        self.depth += 1;
+
+        let start = self.lines.len();
+
+        for stmt in &block.stmts {
+            self.emit_stmt(stmt);
+        }
+
+        // Python blocks can't be empty
+        if self.lines.len() == start {
+            self.emit("pass");
+        }
+
+        self.depth -= 1;
+    }
+
+    fn emit_stmt(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::NOP | Statement::EOF => {}
+            Statement::Expression(expr) => {
+                let expr = self.expr(expr);
+                self.emit(&expr);
+            }
+            Statement::Print(expr) => {
+                let expr = self.expr(expr);
+                self.emit(&format!("print({})", expr));
+            }
+            Statement::Command(cmd) => self.emit_command(cmd),
+            Statement::Label(label_stmt) => {
+                self.uses_labels = true;
+                let expr = self.expr(&label_stmt.expr);
+
+                let line = match label_stmt.kind {
+                    LabelKind::Text => format!(
+                        "turtle.write({}, font=(label_font[0], label_font[1], \"normal\"))",
+                        expr
+                    ),
+                    LabelKind::Font => format!("label_font[0] = {}", expr),
+                    LabelKind::Size => format!("label_font[1] = {}", expr),
+                };
+
+                self.emit(&line);
+            }
+            Statement::Stream(stream_stmt) => {
+                self.uses_streams = true;
+                let expr = self.expr(&stream_stmt.expr);
+
+                let func = match stream_stmt.kind {
+                    StreamKind::Open => "open_write",
+                    StreamKind::Set => "set_write",
+                    StreamKind::Close => "close_stream",
+                };
+
+                self.emit(&format!("{}({})", func, expr));
+            }
+            Statement::SetShape(expr) => self.emit_set_shape(stmt, expr),
+            Statement::SetPenColor(expr) => {
+                self.uses_colors = true;
+                let expr = self.expr(expr);
+
+                self.emit(&format!("pen_color[0] = color({})", expr));
+                self.emit("turtle.pencolor(pen_color[0])");
+            }
+            Statement::Direction(direct_stmt) => self.emit_direction(direct_stmt),
+            Statement::Make(make_stmt) => {
+                let expr = self.expr(&make_stmt.expr);
+                self.emit(&format!("{} = {}", make_stmt.var_name, expr));
+            }
+            Statement::If(if_stmt) => self.emit_if(if_stmt),
+            Statement::Repeat(repeat_stmt) => {
+                let count = self.expr(&repeat_stmt.count_expr);
+
+                self.emit(&format!("for _ in range({}):", count));
+                self.emit_block(&repeat_stmt.block);
+            }
+            Statement::Foreach(foreach_stmt) => {
+                let list = self.expr(&foreach_stmt.list_expr);
+                let var = format!("item{}", self.foreach_vars.len());
+
+                self.emit(&format!("for {} in {}:", var, list));
+
+                self.foreach_vars.push(var);
+                self.emit_block(&foreach_stmt.block);
+                self.foreach_vars.pop();
+            }
+            Statement::Procedure(proc_stmt) => {
+                let construct = format!("TO {}", proc_stmt.name);
+                self.untranslated(&construct, "procedures are defined only at the top level");
+            }
+            Statement::OnEvent(on_event_stmt) => {
+                let event = &on_event_stmt.event;
+
+                if let Event::Key(_) = event {
+                    self.uses_keys = true;
+                }
+
+                self.emit(&Self::registration(event));
+            }
+            Statement::Catch(catch_stmt) => self.emit_catch(catch_stmt),
+            Statement::Throw(tag) => {
+                self.uses_throw = true;
+                self.emit(&format!("raise Throw({})", py_str(tag)));
+            }
+            Statement::Assert(assert_stmt) => {
+                let cond = self.expr(&assert_stmt.cond_expr);
+
+                let line = match &assert_stmt.message {
+                    Some(message) => format!("assert {}, {}", cond, py_str(message)),
+                    None => format!("assert {}", cond),
+                };
+
+                self.emit(&line);
+            }
+            Statement::Tone(_) => {
+                let construct = PrettyPrintAst::pprint_stmt(stmt);
+                self.untranslated(&construct, "the Python turtle has no sound");
+            }
+            Statement::Trace(_) => {
+                let construct = PrettyPrintAst::pprint_stmt(stmt);
+                self.untranslated(
+                    &construct,
+                    "the Python turtle has no procedure calls tracing",
+                );
+            }
+            Statement::Return(ret_stmt) => match &ret_stmt.expr {
+                Some(expr) => {
+                    let expr = self.expr(expr);
+                    self.emit(&format!("return {}", expr));
+                }
+                None => self.emit("return"),
+            },
+            Statement::Load(_) => {
+                let construct = PrettyPrintAst::pprint_stmt(stmt);
+                self.untranslated(
+                    &construct,
+                    "the loaded file should be merged into the program (see `SourceLoader`)",
+                );
+            }
+            Statement::Run(_) => {
+                let construct = PrettyPrintAst::pprint_stmt(stmt);
+                self.untranslated(&construct, "blocks aren't translated to Python");
+            }
+        }
+    }
+
+    fn emit_command(&mut self, cmd: &Command) {
+        let lines: &[&str] = match cmd {
+            Command::XCor => &["print(\"XCOR =\", int(turtle.xcor()))"],
+            Command::YCor => &["print(\"YCOR =\", int(turtle.ycor()))"],
+            Command::PenUp => &["turtle.penup()"],
+            Command::PenDown if self.sets_pen_color => {
+                &["turtle.pencolor(pen_color[0])", "turtle.pendown()"]
+            }
+            Command::PenDown => &["turtle.pencolor(\"black\")", "turtle.pendown()"],
+            Command::PenErase => &["turtle.pencolor(turtle.bgcolor())", "turtle.pendown()"],
+            Command::ShowTurtle => &["turtle.showturtle()"],
+            Command::HideTurtle => &["turtle.hideturtle()"],
+            Command::Clean => &["turtle.clear()"],
+            Command::ClearScreen => &["turtle.reset()"],
+            Command::Trap => &["breakpoint()"],
+            Command::Stamp => &["turtle.stamp()"],
+            Command::PushState => {
+                self.uses_states = true;
+                &["push_state()"]
+            }
+            Command::PopState => {
+                self.uses_states = true;
+                &["pop_state()"]
+            }
+            Command::SetBackgroundColor => {
+                return self.untranslated("SETBACKGROUND", "the background is picked by the host");
+            }
+            Command::Wait => {
+                return self
+                    .untranslated("WAIT", "the Python turtle animates each move on its own");
+            }
+            Command::Stop => return self.untranslated("STOP", "it has no Python equivalent"),
+            Command::Beep => return self.untranslated("BEEP", "the Python turtle has no sound"),
+        };
+
+        for line in lines {
+            self.emit(line);
+        }
+    }
+
+    // the image shapes are expected to be registered under their name (`turtle.register_shape`)
+    fn emit_set_shape(&mut self, stmt: &Statement, expr: &Expression) {
+        let shape = match expr.expr_ast {
+            ExpressionAst::Literal(LiteralExpr::Str(ref name)) => TurtleShape::parse(name),
+            _ => None,
+        };
+
+        let name = match shape {
+            Some(TurtleShape::Arrow) => "classic".to_string(),
+            Some(TurtleShape::Triangle) => "triangle".to_string(),
+            Some(TurtleShape::Circle) => "circle".to_string(),
+            Some(TurtleShape::Image(image)) => image,
+            None => {
+                let construct = PrettyPrintAst::pprint_stmt(stmt);
+                return self.untranslated(&construct, "the shape isn't a literal shape name");
+            }
+        };
+
+        self.emit(&format!("turtle.shape({})", py_str(&name)));
+    }
+
+    fn emit_direction(&mut self, direct_stmt: &DirectionStmt) {
+        let count = self.operand(&direct_stmt.expr);
+
+        let line = match direct_stmt.direction {
+            Direction::Forward => format!("move(0, {})", count),
+            Direction::Backward => format!("move(0, -{})", count),
+            Direction::Right => format!("move({}, 0)", count),
+            Direction::Left => format!("move(-{}, 0)", count),
+            Direction::SetX => format!("turtle.setx(max({}, 0))", count),
+            Direction::SetY => format!("turtle.sety(max({}, 0))", count),
+        };
+
+        self.emit(&line);
+    }
+
+    fn emit_if(&mut self, if_stmt: &IfStmt) {
+        let cond = self.expr(&if_stmt.cond_expr);
+
+        self.emit(&format!("if {}:", cond));
+        self.emit_block(&if_stmt.true_block);
+
+        if let Some(false_block) = &if_stmt.false_block {
+            self.emit("else:");
+            self.emit_block(false_block);
+        }
+    }
+
+    fn emit_catch(&mut self, catch_stmt: &CatchStmt) {
+        self.uses_throw = true;
+
+        self.emit("try:");
+        self.emit_block(&catch_stmt.block);
+
+        // catching `"ERROR` catches the runtime errors too
+        let (except, rethrow) = match catch_stmt.tag.as_str() {
+            ERROR_TAG => (
+                "except Exception as error:",
+                "if isinstance(error, Throw) and error.tag != \"ERROR\":".to_string(),
+            ),
+            tag => (
+                "except Throw as error:",
+                format!("if error.tag != {}:", py_str(tag)),
+            ),
+        };
+
+        self.emit(except);
+        self.depth += 1;
+        self.emit(&rethrow);
+        self.emit(&format!("{}raise", INDENT));
+        self.depth -= 1;
+    }
+
+    // the registration of the handler of `event`
+    fn registration(event: &Event) -> String {
+        match event {
+            Event::Key(key) => {
+                format!(
+                    "turtle.onkey({}, {})",
+                    handler_name(event),
+                    py_str(&key_sym(key))
+                )
+            }
+            Event::Tick(interval) => {
+                format!("turtle.ontimer({}, {})", handler_name(event), interval)
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expression) -> String {
+        match &expr.expr_ast {
+            ExpressionAst::Literal(lit_expr) => self.lit_expr(lit_expr),
+            ExpressionAst::ProcCall(name, args) => self.proc_call(name, args),
+            ExpressionAst::Binary(op, lexpr, rexpr) => {
+                let lexpr = self.operand(lexpr);
+                let rexpr = self.operand(rexpr);
+
+                format!("{} {} {}", lexpr, binary_op(op), rexpr)
+            }
+            ExpressionAst::Parentheses(inner) => format!("({})", self.expr(inner)),
+            ExpressionAst::Not(inner) => format!("not {}", self.operand(inner)),
+            ExpressionAst::TypeQuery(query, inner) => {
+                let inner_str = self.operand(inner);
+
+                match query {
+                    TypeQuery::NumberP => format!("type({}) is int", inner_str),
+                    TypeQuery::WordP => format!("type({}) is str", inner_str),
+                    TypeQuery::ListP => format!("type({}) is list", inner_str),
+                    TypeQuery::EmptyP => format!("len({}) == 0", inner_str),
+                    TypeQuery::TypeOf => {
+                        let construct = PrettyPrintAst::pprint_expr(expr);
+                        let reason = "Python types are named differently than tytle's";
+                        self.report.add(&construct, reason);
+
+                        "None".to_string()
+                    }
+                }
+            }
+            ExpressionAst::List(items) => format!("[{}]", self.args(items)),
+            ExpressionAst::Map(proc_name, list) => {
+                let proc_name = self.call_name(proc_name, 1);
+                format!("list(map({}, {}))", proc_name, self.expr(list))
+            }
+            ExpressionAst::ProcRef(proc_name) => proc_name.clone(),
+            ExpressionAst::Apply(target, args) => {
+                format!("{}({})", self.operand(target), self.args(args))
+            }
+            // the tytle params are named like the Python ones
+            ExpressionAst::NamedArg(param_name, inner) => {
+                format!("{}={}", param_name, self.expr(inner))
+            }
+            ExpressionAst::IfElse(cond, true_expr, false_expr) => {
+                let cond = self.operand(cond);
+                let true_expr = self.operand(true_expr);
+                let false_expr = self.operand(false_expr);
+
+                format!("{} if {} else {}", true_expr, cond, false_expr)
+            }
+            ExpressionAst::Block(_) => {
+                let construct = PrettyPrintAst::pprint_expr(expr);
+                self.report.add(&construct, "blocks aren't translated to Python");
+
+                "None".to_string()
+            }
+        }
+    }
+
+    // an operand of an operator, parenthesized when it's an operation itself
+    // (the tytle operators precedence differs from the Python one)
+    fn operand(&mut self, expr: &Expression) -> String {
+        let expr_str = self.expr(expr);
+
+        match expr.expr_ast {
+            ExpressionAst::Binary(..)
+            | ExpressionAst::Not(_)
+            | ExpressionAst::TypeQuery(..)
+            | ExpressionAst::IfElse(..) => format!("({})", expr_str),
+            _ => expr_str,
+        }
+    }
+
+    fn args(&mut self, args: &[Expression]) -> String {
+        let args = args.iter().map(|arg| self.expr(arg)).collect::<Vec<_>>();
+
+        args.join(", ")
+    }
+
+    fn lit_expr(&self, lit_expr: &LiteralExpr) -> String {
+        match lit_expr {
+            LiteralExpr::Bool(true) => "True".to_string(),
+            LiteralExpr::Bool(false) => "False".to_string(),
+            LiteralExpr::Int(v) => v.to_string(),
+            LiteralExpr::Str(s) => py_str(s),
+            LiteralExpr::Var(name) if name == FOREACH_VAR => match self.foreach_vars.last() {
+                Some(var) => var.clone(),
+                None => name.clone(),
+            },
+            LiteralExpr::Var(name) => name.clone(),
+        }
+    }
+
+    // the Python name of a procedure definition (`SQUARE_2` when `SQUARE` is overloaded)
+    fn def_name(&self, proc_stmt: &ProcedureStmt) -> String {
+        match self.procs.get(&proc_stmt.name) {
+            Some(signatures) if signatures.len() > 1 => {
+                format!("{}_{}", proc_stmt.name, proc_stmt.params.len())
+            }
+            _ => proc_stmt.name.clone(),
+        }
+    }
+
+    // the Python name of the procedure called by name with `nargs` args
+    fn call_name(&self, name: &str, nargs: usize) -> String {
+        let signatures = match self.procs.get(name) {
+            Some(signatures) if signatures.len() > 1 => signatures,
+            _ => return name.to_string(),
+        };
+
+        let accepts = |(count, rest): &&(usize, bool)| match rest {
+            true => nargs + 1 >= *count,
+            false => nargs == *count,
+        };
+
+        match signatures.iter().find(accepts) {
+            Some((count, _)) => format!("{}_{}", name, count),
+            None => name.to_string(),
+        }
+    }
+
+    fn proc_call(&mut self, name: &str, args: &[Expression]) -> String {
+        if !self.procs.contains_key(name) {
+            if let Some(prim) = Primitive::from_name(name) {
+                return self.primitive_call(prim, args);
+            }
+
+            let reason = "the procedure isn't defined by the program (e.g a stdlib procedure)";
+            self.report.add(name, reason);
+        }
+
+        format!("{}({})", self.call_name(name, args.len()), self.args(args))
+    }
+
+    fn primitive_call(&mut self, prim: Primitive, args: &[Expression]) -> String {
+        let mut args = args.iter().map(|arg| self.operand(arg)).collect::<Vec<_>>();
+
+        // the only primitive having a rest param
+        if prim == Primitive::Format {
+            self.uses_format = true;
+            return format!("format_text({})", args.join(", "));
+        }
+
+        // the primitives are type checked, so the args count is known
+        args.resize(prim.params_types().len(), "None".to_string());
+
+        match prim {
+            Primitive::Word => format!("({} + {})", args[0], args[1]),
+            Primitive::Count => format!("len({})", args[0]),
+            Primitive::Item => format!("{}[{} - 1]", args[1], args[0]),
+            Primitive::Uppercase => format!("{}.upper()", args[0]),
+            Primitive::Lowercase => format!("{}.lower()", args[0]),
+            Primitive::Parse => format!("int({})", args[0]),
+            Primitive::ScreenWidth => "turtle.window_width()".to_string(),
+            Primitive::ScreenHeight => "turtle.window_height()".to_string(),
+            Primitive::MouseX | Primitive::MouseY | Primitive::ButtonP => {
+                self.report.add(prim.name(), "the Python turtle can't poll the mouse");
+
+                match prim {
+                    Primitive::ButtonP => "False".to_string(),
+                    _ => "0".to_string(),
+                }
+            }
+            Primitive::Rgb | Primitive::Palette | Primitive::NamedColor | Primitive::PenColor => {
+                self.uses_colors = true;
+
+                match prim {
+                    Primitive::Rgb => format!("color(({}, {}, {}))", args[0], args[1], args[2]),
+                    Primitive::PenColor => "pen_color[0]".to_string(),
+                    _ => format!("color({})", args[0]),
+                }
+            }
+            Primitive::Store | Primitive::Retrieve => {
+                self.uses_store = true;
+                self.uses_format = true;
+
+                match prim {
+                    Primitive::Store => format!("store({}, {})", args[0], args[1]),
+                    _ => format!("retrieve({})", args[0]),
+                }
+            }
+            Primitive::Format => unreachable!(),
+        }
+    }
+}
+
+// the names of the global variables assigned by the procedure.
+// Python functions must declare them, or else the assignments would create new locals
+fn assigned_globals(proc_stmt: &ProcedureStmt) -> BTreeSet<String> {
+    let mut makes = Vec::new();
+    collect_makes(&proc_stmt.block, &mut makes);
+
+    let mut locals = proc_stmt
+        .params
+        .iter()
+        .map(|param| param.param_name.as_str())
+        .collect::<HashSet<_>>();
+
+    for make_stmt in makes.iter() {
+        if make_stmt.kind == MakeStmtKind::Local {
+            locals.insert(&make_stmt.var_name);
+        }
+    }
+
+    makes
+        .iter()
+        .filter(|make_stmt| match make_stmt.kind {
+            MakeStmtKind::Global | MakeStmtKind::Const => true,
+            MakeStmtKind::Assign => !locals.contains(make_stmt.var_name.as_str()),
+            MakeStmtKind::Local => false,
+        })
+        .map(|make_stmt| make_stmt.var_name.clone())
+        .collect()
+}
+
+fn collect_makes<'a>(block: &'a BlockStatement, makes: &mut Vec<&'a MakeStmt>) {
+    for stmt in &block.stmts {
+        match stmt {
+            Statement::Make(make_stmt) => makes.push(make_stmt),
+            Statement::If(if_stmt) => {
+                collect_makes(&if_stmt.true_block, makes);
+
+                if let Some(false_block) = &if_stmt.false_block {
+                    collect_makes(false_block, makes);
+                }
+            }
+            Statement::Repeat(repeat_stmt) => collect_makes(&repeat_stmt.block, makes),
+            Statement::Foreach(foreach_stmt) => collect_makes(&foreach_stmt.block, makes),
+            Statement::Catch(catch_stmt) => collect_makes(&catch_stmt.block, makes),
+            _ => {}
+        }
+    }
+}
+
+// whether any of the statements (or of their nested blocks) is a `SETPENCOLOR`
+fn sets_pen_color(stmts: &[Statement]) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        Statement::SetPenColor(_) => true,
+        Statement::If(if_stmt) => {
+            let false_stmts = if_stmt.false_block.as_ref().map_or(&[][..], |b| &b.stmts[..]);
+
+            sets_pen_color(&if_stmt.true_block.stmts) || sets_pen_color(false_stmts)
+        }
+        Statement::Repeat(repeat_stmt) => sets_pen_color(&repeat_stmt.block.stmts),
+        Statement::Foreach(foreach_stmt) => sets_pen_color(&foreach_stmt.block.stmts),
+        Statement::Catch(catch_stmt) => sets_pen_color(&catch_stmt.block.stmts),
+        Statement::Procedure(proc_stmt) => sets_pen_color(&proc_stmt.block.stmts),
+        Statement::OnEvent(on_event_stmt) => sets_pen_color(&on_event_stmt.proc_stmt.block.stmts),
+        _ => false,
+    })
+}
+
+// the name of the function generated for the handler of `event`
+fn handler_name(event: &Event) -> String {
+    match event {
+        Event::Key(key) => {
+            let key = key
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect::<String>();
+
+            format!("on_key_{}", key.to_lowercase())
+        }
+        Event::Tick(_) => "on_tick".to_string(),
+    }
+}
+
+// the Tk key name of a tytle key name
+fn key_sym(key: &str) -> String {
+    let sym = match key {
+        "LEFT" => "Left",
+        "RIGHT" => "Right",
+        "UP" => "Up",
+        "DOWN" => "Down",
+        "SPACE" => "space",
+        "ENTER" => "Return",
+        "ESCAPE" => "Escape",
+        _ => return key.to_lowercase(),
+    };
+
+    sym.to_string()
+}
+
+fn binary_op(op: &BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::And => "and",
+        BinaryOp::Or => "or",
+        BinaryOp::Add => "+",
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "//",
+        BinaryOp::GreaterThan => ">",
+        BinaryOp::LessThan => "<",
+        BinaryOp::Equal => "==",
+        BinaryOp::NotEqual => "!=",
+    }
+}
+
+// a Python string literal
+fn py_str(s: &str) -> String {
+    let mut literal = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\t' => literal.push_str("\\t"),
+            '\r' => literal.push_str("\\r"),
+            _ => literal.push(c),
+        }
+    }
+
+    literal.push('"');
+    literal
+}
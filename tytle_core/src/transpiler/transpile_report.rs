@@ -0,0 +1,46 @@
+use crate::compat::prelude::*;
+use core::fmt;
+
+// a construct of the tytle program without an equivalent in the target language.
+// it's emitted as a comment (in place of the construct) so the output still runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Untranslated {
+    // the construct as it appears in the tytle program (e.g `SETPENCOLOR` or `TRACE "SQUARE`)
+    pub construct: String,
+    pub reason: String,
+}
+
+impl fmt::Display for Untranslated {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}`: {}", self.construct, self.reason)
+    }
+}
+
+// the mapping report of a transpilation, listing the untranslated constructs in program order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranspileReport {
+    pub untranslated: Vec<Untranslated>,
+}
+
+impl TranspileReport {
+    // whether the whole program has been translated
+    pub fn is_complete(&self) -> bool {
+        self.untranslated.is_empty()
+    }
+
+    pub(crate) fn add(&mut self, construct: &str, reason: &str) {
+        let untranslated = Untranslated {
+            construct: construct.to_string(),
+            reason: reason.to_string(),
+        };
+
+        self.untranslated.push(untranslated);
+    }
+}
+
+// the output of a transpiler
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transpiled {
+    pub source: String,
+    pub report: TranspileReport,
+}
@@ -0,0 +1,189 @@
+use crate::ast::semantic::AstWalkError;
+use crate::compat::prelude::*;
+use crate::compiler::CompileError;
+use crate::lexer::{LexError, Location};
+use crate::parser::ParseError;
+use crate::vm::InterpreterException;
+use core::fmt;
+
+// any error of the tytle pipeline, by the phase it originates from.
+// every phase's error converts into it, so the whole pipeline can be chained using `?`
+#[derive(Debug, PartialEq)]
+pub enum TytleError {
+    Lex(LexError),
+    Parse {
+        error: ParseError,
+        location: Location,
+    },
+    Semantic(AstWalkError),
+    Compile(CompileError),
+    Runtime(InterpreterException),
+    StepLimitExceeded(u64),
+
+    // the execution has run for longer than the time limit (in milliseconds)
+    TimeLimitExceeded(u64),
+
+    // a bug of tytle (a panic caught at the public API boundary, see `guard`).
+    // `context` is the API entry point which has failed
+    Internal {
+        context: String,
+        message: String,
+    },
+}
+
+impl TytleError {
+    // the source code location of the error (when known)
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            TytleError::Lex(err) => Some(err.location()),
+            TytleError::Parse { location, .. } => Some(*location),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TytleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TytleError::Lex(err) => write!(f, "Lexical error at {}: {}", err.location(), err),
+            TytleError::Parse { error, location } => {
+                write!(f, "Parse error at {}: {}", location, error.to_string())
+            }
+            TytleError::Semantic(err) => write!(f, "Semantic error: {}", err.to_string()),
+            TytleError::Compile(err) => write!(f, "Compilation error: {}", err),
+            TytleError::Runtime(err) => write!(f, "Runtime error: {:?}", err),
+            TytleError::StepLimitExceeded(limit) => write!(
+                f,
+                "Runtime error: execution exceeded the limit of {} steps",
+                limit
+            ),
+            TytleError::TimeLimitExceeded(limit) => write!(
+                f,
+                "Runtime error: execution exceeded the time limit of {} ms",
+                limit
+            ),
+            TytleError::Internal { context, message } => {
+                write!(f, "Internal error in `{}`: {}", context, message)
+            }
+        }
+    }
+}
+
+impl From<LexError> for TytleError {
+    fn from(err: LexError) -> Self {
+        TytleError::Lex(err)
+    }
+}
+
+impl From<AstWalkError> for TytleError {
+    fn from(err: AstWalkError) -> Self {
+        TytleError::Semantic(err)
+    }
+}
+
+impl From<CompileError> for TytleError {
+    fn from(err: CompileError) -> Self {
+        match err {
+            CompileError::Lex(err) => TytleError::Lex(err),
+            CompileError::Parse { error, location } => TytleError::Parse { error, location },
+            CompileError::Semantic(err) => TytleError::Semantic(err),
+            err => TytleError::Compile(err),
+        }
+    }
+}
+
+impl From<InterpreterException> for TytleError {
+    fn from(err: InterpreterException) -> Self {
+        TytleError::Runtime(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn tytle_error_lex() {
+        let err = TytleError::from(LexError::InvalidCharacter {
+            ch: '\u{7}',
+            location: Location(1, 9),
+        });
+
+        assert_eq!(
+            "Lexical error at 1:9: Invalid character: `\\u{7}`",
+            err.to_string()
+        );
+        assert_eq!(Some(Location(1, 9)), err.location());
+    }
+
+    #[test]
+    pub fn tytle_error_parse() {
+        let err = TytleError::from(CompileError::Parse {
+            error: ParseError::MissingColon,
+            location: Location(2, 4),
+        });
+
+        assert_eq!("Parse error at 2:4: Missing colon", err.to_string());
+        assert_eq!(Some(Location(2, 4)), err.location());
+    }
+
+    #[test]
+    pub fn tytle_error_semantic() {
+        let err = TytleError::from(CompileError::Semantic(AstWalkError::DuplicateProc(
+            "FOO".to_string(),
+        )));
+
+        assert_eq!(
+            "Semantic error: Duplicate procedure: `FOO`",
+            err.to_string()
+        );
+        assert_eq!(None, err.location());
+    }
+
+    #[test]
+    pub fn tytle_error_compile() {
+        let err = TytleError::from(CompileError::TooManyProcParams {
+            proc_name: "FOO".to_string(),
+            max: 1,
+            actual: 2,
+        });
+
+        assert_eq!(
+            "Compilation error: Too many parameters for procedure `FOO` (max: 1, actual: 2)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    pub fn tytle_error_step_limit_exceeded() {
+        assert_eq!(
+            "Runtime error: execution exceeded the limit of 100 steps",
+            TytleError::StepLimitExceeded(100).to_string()
+        );
+    }
+
+    #[test]
+    pub fn tytle_error_time_limit_exceeded() {
+        assert_eq!(
+            "Runtime error: execution exceeded the time limit of 500 ms",
+            TytleError::TimeLimitExceeded(500).to_string()
+        );
+    }
+
+    #[test]
+    pub fn tytle_error_internal() {
+        let err = TytleError::Internal {
+            context: "run".to_string(),
+            message: "index out of bounds".to_string(),
+        };
+
+        assert_eq!("Internal error in `run`: index out of bounds", err.to_string());
+    }
+
+    #[test]
+    pub fn tytle_error_runtime() {
+        let err = TytleError::from(InterpreterException::StackOverflow);
+
+        assert_eq!("Runtime error: StackOverflow", err.to_string());
+    }
+}
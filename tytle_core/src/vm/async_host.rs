@@ -0,0 +1,155 @@
+use crate::ast::statement::{Command, Direction};
+use crate::compat::prelude::*;
+#[cfg(feature = "bigint")]
+use crate::vm::{big_int, BigInt};
+use crate::vm::{
+    Color, HostCall, Label, Movement, Pen, Turtle, TurtlePose, TurtleShape, DEFAULT_SCREEN_SIZE,
+};
+
+use core::future::Future;
+use core::pin::Pin;
+
+pub type HostFuture<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+// the asynchronous counterpart of `Host`, driven by `Interpreter::exec_async`.
+// the interpreter awaits each returned future before executing the next instruction,
+// so a host can e.g animate a `FORWARD` or implement `WAIT` without blocking the thread.
+// like the `Host` ones, the callbacks added along the language features do nothing by default
+pub trait AsyncHost {
+    fn exec_cmd<'a>(&'a mut self, cmd: &'a Command) -> HostFuture<'a>;
+    fn exec_direct<'a>(&'a mut self, direct: &'a Direction, count: isize) -> HostFuture<'a>;
+
+    // see `Host::exec_turn`
+    fn exec_turn(&mut self, _degrees: f64) -> HostFuture<'_> {
+        Box::pin(async {})
+    }
+
+    fn exec_trap(&mut self, node: usize, ip: usize) -> HostFuture<'_>;
+    fn exec_print(&mut self, value: isize) -> HostFuture<'_>;
+
+    // see `Host::exec_print_big`
+    #[cfg(feature = "bigint")]
+    fn exec_print_big<'a>(&'a mut self, value: &'a BigInt) -> HostFuture<'a> {
+        self.exec_print(big_int::saturate(value))
+    }
+
+    fn exec_print_text<'a>(&'a mut self, _text: &'a str) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    // see `Host::exec_trace`
+    fn exec_trace<'a>(&'a mut self, line: &'a str) -> HostFuture<'a> {
+        self.exec_print_text(line)
+    }
+
+    fn exec_stamp(&mut self, _position: (isize, isize), _heading: f64) -> HostFuture<'_> {
+        Box::pin(async {})
+    }
+
+    fn exec_label<'a>(&'a mut self, _label: &'a Label) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    fn turtle_shape_changed<'a>(&'a mut self, _shape: &'a TurtleShape) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    fn play_tone(&mut self, _freq: usize, _ms: usize) -> HostFuture<'_> {
+        Box::pin(async {})
+    }
+
+    fn pen_color_changed(&mut self, _color: Color) -> HostFuture<'_> {
+        Box::pin(async {})
+    }
+
+    fn movement_started<'a>(&'a mut self, _movement: &'a Movement) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    fn movement_progress<'a>(
+        &'a mut self,
+        _movement: &'a Movement,
+        _frame: usize,
+        _pose: &'a TurtlePose,
+    ) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    fn movement_completed<'a>(&'a mut self, _movement: &'a Movement) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    fn state_restored<'a>(&'a mut self, _turtle: &'a Turtle, _pen: &'a Pen) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    // see `Host::kv_set` and `Host::kv_get`
+    fn kv_set<'a>(&'a mut self, _key: &'a str, _value: &'a str) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    fn kv_get(&self, _key: &str) -> Option<String> {
+        None
+    }
+
+    // see `Host::open_stream`, `Host::write_stream` and `Host::close_stream`
+    fn open_stream<'a>(&'a mut self, _name: &'a str) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    fn write_stream<'a>(&'a mut self, _name: &'a str, _text: &'a str) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    fn close_stream<'a>(&'a mut self, _name: &'a str) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    // queried when the execution starts (see `Host::screen_size`)
+    fn screen_size(&self) -> (usize, usize) {
+        DEFAULT_SCREEN_SIZE
+    }
+
+    // awaited at the end of each frame of a throttled execution (see `Interpreter::set_speed`),
+    // e.g till the next animation frame
+    fn exec_yield(&mut self) -> HostFuture<'_> {
+        Box::pin(async {})
+    }
+}
+
+impl HostCall {
+    pub async fn dispatch_async(&self, host: &mut dyn AsyncHost) {
+        match self {
+            HostCall::Cmd(cmd) => host.exec_cmd(cmd).await,
+            HostCall::Direct(direct, count) => host.exec_direct(direct, *count).await,
+            HostCall::Turn(degrees) => host.exec_turn(*degrees).await,
+            HostCall::Trap(node_id, ip) => host.exec_trap(*node_id, *ip).await,
+            HostCall::Print(value) => host.exec_print(*value).await,
+            #[cfg(feature = "bigint")]
+            HostCall::PrintBig(value) => host.exec_print_big(value).await,
+            HostCall::PrintText(text) => host.exec_print_text(text).await,
+            HostCall::Trace(line) => host.exec_trace(line).await,
+            HostCall::Stamp { position, heading } => host.exec_stamp(*position, *heading).await,
+            HostCall::Label(label) => host.exec_label(label).await,
+            HostCall::ShapeChanged(shape) => host.turtle_shape_changed(shape).await,
+            HostCall::Tone { freq, ms } => host.play_tone(*freq, *ms).await,
+            HostCall::PenColor(color) => host.pen_color_changed(*color).await,
+            HostCall::Movement(movement) => {
+                host.movement_started(movement).await;
+
+                for frame in 1..=movement.frames {
+                    let pose = movement.pose_at(frame);
+                    host.movement_progress(movement, frame, &pose).await;
+                }
+
+                host.movement_completed(movement).await;
+            }
+            HostCall::StateRestored(turtle, pen) => host.state_restored(turtle, pen).await,
+            HostCall::KvSet { key, value } => host.kv_set(key, value).await,
+            HostCall::KvGet { .. } | HostCall::ScreenSize(..) => {}
+            HostCall::OpenStream(name) => host.open_stream(name).await,
+            HostCall::WriteStream { stream, text } => host.write_stream(stream, text).await,
+            HostCall::CloseStream(name) => host.close_stream(name).await,
+        }
+    }
+}
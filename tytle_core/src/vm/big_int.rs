@@ -0,0 +1,45 @@
+use crate::ast::expression::BinaryOp;
+use crate::vm::MemoryValue;
+use core::convert::TryFrom;
+use num_bigint::Sign;
+
+pub use num_bigint::BigInt;
+
+// the arbitrary-precision `+`, `*` or `/` (see `OverflowPolicy::Promote`)
+pub(crate) fn eval_big_int(op: &BinaryOp, a: BigInt, b: BigInt) -> MemoryValue {
+    let v = match op {
+        BinaryOp::Add => a + b,
+        BinaryOp::Mul => a * b,
+        BinaryOp::Div => a / b,
+        _ => panic!("invalid integer binary-op: `{:?}`", op),
+    };
+
+    MemoryValue::from_big_int(v)
+}
+
+// the machine integer closest to a `BigInt` value (which never fits one)
+pub(crate) fn saturate(v: &BigInt) -> isize {
+    match v.sign() {
+        Sign::Minus => isize::MIN,
+        _ => isize::MAX,
+    }
+}
+
+impl MemoryValue {
+    // an integer is kept as a `BigInt` value only when it doesn't fit a machine integer,
+    // so an `Int` value and a `BigInt` value are never equal
+    pub fn from_big_int(v: BigInt) -> Self {
+        match isize::try_from(&v) {
+            Ok(v) => MemoryValue::Int(v),
+            Err(_) => MemoryValue::BigInt(v),
+        }
+    }
+
+    pub fn to_big_int(&self) -> BigInt {
+        match self {
+            MemoryValue::Int(v) => BigInt::from(*v),
+            MemoryValue::BigInt(v) => v.clone(),
+            _ => panic!("expected an integer"),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+// an axis aligned bounding box (inclusive)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub min_x: isize,
+    pub min_y: isize,
+    pub max_x: isize,
+    pub max_y: isize,
+}
+
+impl Bounds {
+    // the bounds of a single point
+    pub fn at(x: isize, y: isize) -> Self {
+        Self {
+            min_x: x,
+            min_y: y,
+            max_x: x,
+            max_y: y,
+        }
+    }
+
+    pub fn extend(&mut self, x: isize, y: isize) {
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+
+    pub fn width(&self) -> isize {
+        self.max_x - self.min_x
+    }
+
+    pub fn height(&self) -> isize {
+        self.max_y - self.min_y
+    }
+}
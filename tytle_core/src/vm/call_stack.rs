@@ -1,18 +1,55 @@
 use crate::ast::semantic::SymbolId;
+use crate::compat::prelude::*;
 use crate::ir::CfgNodeId;
+#[cfg(feature = "bigint")]
+use crate::vm::{big_int, BigInt};
+use crate::vm::{Color, MemoryValue};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CallStackItem {
     Int(isize),
     Bool(bool),
+    Str(String),
+    List(Vec<MemoryValue>),
+    Color(Color),
+    ProcRef(SymbolId),
+
+    // a block: its procedure and the id of the frame it's been created within
+    // (see `CfgInstruction::Block`)
+    Block(SymbolId, usize),
     Addr(CfgNodeId, usize),
-    // StrRef
+
+    // see `MemoryValue::BigInt`
+    #[cfg(feature = "bigint")]
+    BigInt(BigInt),
+
+    // a `CATCH` marker: its tag and the CFG node resuming the execution after a matching `THROW`
+    Catch(String, CfgNodeId),
+}
+
+impl From<MemoryValue> for CallStackItem {
+    fn from(value: MemoryValue) -> Self {
+        match value {
+            MemoryValue::Int(v) => CallStackItem::Int(v),
+            MemoryValue::Bool(v) => CallStackItem::Bool(v),
+            MemoryValue::Str(v) => CallStackItem::Str(v),
+            MemoryValue::List(items) => CallStackItem::List(items),
+            MemoryValue::Color(color) => CallStackItem::Color(color),
+            MemoryValue::ProcRef(proc_id) => CallStackItem::ProcRef(proc_id),
+            MemoryValue::Block(proc_id, frame_id) => CallStackItem::Block(proc_id, frame_id),
+            #[cfg(feature = "bigint")]
+            MemoryValue::BigInt(v) => CallStackItem::BigInt(v),
+        }
+    }
 }
 
 impl CallStackItem {
     pub fn is_int(&self) -> bool {
         match self {
             CallStackItem::Int(_) => true,
+            #[cfg(feature = "bigint")]
+            CallStackItem::BigInt(_) => true,
             _ => false,
         }
     }
@@ -31,9 +68,27 @@ impl CallStackItem {
         }
     }
 
+    // see `MemoryValue::heap_size`
+    pub fn heap_size(&self) -> usize {
+        let size = core::mem::size_of::<CallStackItem>();
+
+        match self {
+            CallStackItem::Str(v) => size + v.len(),
+            CallStackItem::List(items) => {
+                size + items.iter().map(MemoryValue::heap_size).sum::<usize>()
+            }
+            #[cfg(feature = "bigint")]
+            CallStackItem::BigInt(v) => size + (v.bits() / 8) as usize,
+            _ => size,
+        }
+    }
+
+    // a `BigInt` is saturated, where a machine integer is expected (e.g `FORWARD` or `REPEAT`)
     pub fn to_int(&self) -> isize {
         match self {
             CallStackItem::Int(v) => *v,
+            #[cfg(feature = "bigint")]
+            CallStackItem::BigInt(v) => big_int::saturate(v),
             _ => panic!("expected an integer"),
         }
     }
@@ -45,6 +100,41 @@ impl CallStackItem {
         }
     }
 
+    pub fn to_str(self) -> String {
+        match self {
+            CallStackItem::Str(v) => v,
+            _ => panic!("expected a string"),
+        }
+    }
+
+    pub fn to_list(self) -> Vec<MemoryValue> {
+        match self {
+            CallStackItem::List(items) => items,
+            _ => panic!("expected a list"),
+        }
+    }
+
+    pub fn to_color(&self) -> Color {
+        match self {
+            CallStackItem::Color(color) => *color,
+            _ => panic!("expected a color"),
+        }
+    }
+
+    pub fn to_proc_ref(&self) -> SymbolId {
+        match self {
+            CallStackItem::ProcRef(proc_id) => *proc_id,
+            _ => panic!("expected a procedure reference"),
+        }
+    }
+
+    pub fn to_block(&self) -> (SymbolId, usize) {
+        match self {
+            CallStackItem::Block(proc_id, frame_id) => (*proc_id, *frame_id),
+            _ => panic!("expected a block"),
+        }
+    }
+
     pub fn to_addr(&self) -> (CfgNodeId, usize) {
         match self {
             CallStackItem::Addr(node_id, ip) => (*node_id, *ip),
@@ -53,17 +143,33 @@ impl CallStackItem {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CallStackFrame {
+    // the procedure variables (params first, then the locals), addressed by their `Variable::index`.
+    // the slots are allocated once, when the frame is opened (see `CfgProc::slots_count`)
+    slots: Vec<CallStackItem>,
+
+    // the operand stack
     items: Vec<CallStackItem>,
+
     pub ctx_proc: SymbolId,
+
+    // unique within the callstack, so a block can tell whether the frame it's been created within
+    // is still open (see `CallStack::frame_index`)
+    pub id: usize,
+
+    // the id of the frame whose variables a running block uses (`None` for procedures frames)
+    pub captured: Option<usize>,
 }
 
 impl CallStackFrame {
-    pub fn new(ctx_proc: SymbolId) -> Self {
+    pub fn new(ctx_proc: SymbolId, slots: Vec<CallStackItem>) -> Self {
         Self {
+            slots,
             items: Vec::new(),
             ctx_proc,
+            id: 0,
+            captured: None,
         }
     }
 
@@ -71,16 +177,20 @@ impl CallStackFrame {
         self.items.is_empty()
     }
 
+    pub fn slots_count(&self) -> usize {
+        self.slots.len()
+    }
+
     pub fn push(&mut self, item: CallStackItem) {
         self.items.push(item);
     }
 
-    pub fn load(&mut self, index: usize) -> &CallStackItem {
-        self.items.get(index).unwrap()
+    pub fn load(&self, index: usize) -> &CallStackItem {
+        &self.slots[index]
     }
 
     pub fn store(&mut self, index: usize, item: CallStackItem) {
-        std::mem::replace(&mut self.items[index], item);
+        self.slots[index] = item;
     }
 
     pub fn peek(&self) -> &CallStackItem {
@@ -90,16 +200,41 @@ impl CallStackFrame {
     pub fn pop(&mut self) -> CallStackItem {
         self.items.pop().unwrap()
     }
+
+    // drops the operand stack items above the first `len` ones
+    pub fn truncate(&mut self, len: usize) {
+        self.items.truncate(len);
+    }
+
+    // the item `depth` items below the top of the operand stack (`peek_at(0)` is `peek()`)
+    pub fn peek_at(&self, depth: usize) -> &CallStackItem {
+        &self.items[self.items.len() - 1 - depth]
+    }
+
+    // the slots and the operand stack items sizes (see `MemoryValue::heap_size`)
+    pub fn heap_size(&self) -> usize {
+        self.slots
+            .iter()
+            .chain(self.items.iter())
+            .map(CallStackItem::heap_size)
+            .sum()
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CallStack {
     pub frames: Vec<CallStackFrame>,
+
+    // the id of the next opened frame
+    next_frame_id: usize,
 }
 
 impl CallStack {
     pub fn new() -> Self {
-        Self { frames: Vec::new() }
+        Self {
+            frames: Vec::new(),
+            next_frame_id: 0,
+        }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -116,6 +251,15 @@ impl CallStack {
         frame.store(index, item);
     }
 
+    // loads a slot of the frame at `frame_index` (rather than of the current frame)
+    pub fn load_frame_item(&self, frame_index: usize, index: usize) -> &CallStackItem {
+        self.frames[frame_index].load(index)
+    }
+
+    pub fn store_frame_item(&mut self, frame_index: usize, index: usize, item: CallStackItem) {
+        self.frames[frame_index].store(index, item);
+    }
+
     pub fn push_item(&mut self, item: CallStackItem) {
         let frame = self.current_frame_mut();
         frame.push(item);
@@ -131,13 +275,54 @@ impl CallStack {
         frame.peek()
     }
 
-    pub fn open_stackframe(&mut self, ctx_proc: SymbolId) -> &mut CallStackFrame {
-        let frame = CallStackFrame::new(ctx_proc);
+    pub fn peek_item_at(&self, depth: usize) -> &CallStackItem {
+        let frame = self.current_frame();
+        frame.peek_at(depth)
+    }
+
+    pub fn open_stackframe(
+        &mut self,
+        ctx_proc: SymbolId,
+        slots: Vec<CallStackItem>,
+    ) -> &mut CallStackFrame {
+        let mut frame = CallStackFrame::new(ctx_proc, slots);
+        frame.id = self.next_frame_id;
+        self.next_frame_id += 1;
+
         self.frames.push(frame);
 
         self.current_frame_mut()
     }
 
+    // opens the frame of a running block, using the variables of frame `captured`
+    pub fn open_block_stackframe(
+        &mut self,
+        ctx_proc: SymbolId,
+        slots: Vec<CallStackItem>,
+        captured: usize,
+    ) -> &mut CallStackFrame {
+        let frame = self.open_stackframe(ctx_proc, slots);
+        frame.captured = Some(captured);
+
+        frame
+    }
+
+    // closes all the frames, the ids of the frames opened next remain unique
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    // the index of the (still open) frame having id `frame_id`
+    pub fn frame_index(&self, frame_id: usize) -> Option<usize> {
+        self.frames.iter().rposition(|frame| frame.id == frame_id)
+    }
+
+    // the id of the frame whose variables the current frame uses
+    pub fn vars_frame_id(&self) -> usize {
+        let frame = self.current_frame();
+        frame.captured.unwrap_or(frame.id)
+    }
+
     pub fn close_stackframe(&mut self) {
         self.frames.pop();
     }
@@ -153,6 +338,26 @@ impl CallStack {
     pub fn depth(&mut self) -> usize {
         self.frames.len()
     }
+
+    pub fn heap_size(&self) -> usize {
+        self.frames.iter().map(CallStackFrame::heap_size).sum()
+    }
+
+    // the innermost `CATCH` marker of `tag`, as the callstack depth of its frame,
+    // its index within the frame operand stack and its resume CFG node
+    pub fn find_catch(&self, tag: &str) -> Option<(usize, usize, CfgNodeId)> {
+        for (frame_index, frame) in self.frames.iter().enumerate().rev() {
+            for (index, item) in frame.items.iter().enumerate().rev() {
+                if let CallStackItem::Catch(catch_tag, node_id) = item {
+                    if catch_tag == tag {
+                        return Some((frame_index + 1, index, *node_id));
+                    }
+                }
+            }
+        }
+
+        None
+    }
 }
 
 #[cfg(tests)]
@@ -0,0 +1,125 @@
+use crate::ast::semantic::Primitive;
+use crate::ast::statement::Command;
+use crate::ir::CfgInstruction;
+use serde::{Deserialize, Serialize};
+
+// a host-facing feature a program may be denied, e.g by a grading server.
+// using a disabled capability fails the compilation with `CompileError::CapabilityDisabled`
+// (or stops the execution with `InterpreterException::CapabilityDisabled`)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    // the mouse queries (`MOUSEX`, `MOUSEY` and `BUTTONP`) and the `ONKEY` handlers
+    Input,
+
+    // `TONE` and `BEEP`
+    Sound,
+
+    // `LOAD` of another file
+    Load,
+
+    // `WAIT` and the `ONTICK` handler
+    Clock,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 4] = [
+        Capability::Input,
+        Capability::Sound,
+        Capability::Load,
+        Capability::Clock,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Capability::Input => "input",
+            Capability::Sound => "sound",
+            Capability::Load => "load",
+            Capability::Clock => "clock",
+        }
+    }
+
+    // the capability required for executing `inst` (if any), along with the feature using it
+    pub fn required_by(inst: &CfgInstruction) -> Option<(Capability, &'static str)> {
+        match inst {
+            CfgInstruction::Tone => Some((Capability::Sound, "TONE")),
+            CfgInstruction::Command(Command::Beep) => Some((Capability::Sound, "BEEP")),
+            CfgInstruction::Command(Command::Wait) => Some((Capability::Clock, "WAIT")),
+            CfgInstruction::Primitive(prim @ Primitive::MouseX)
+            | CfgInstruction::Primitive(prim @ Primitive::MouseY)
+            | CfgInstruction::Primitive(prim @ Primitive::ButtonP) => {
+                Some((Capability::Input, prim.name()))
+            }
+            _ => None,
+        }
+    }
+
+    fn bit(self) -> u8 {
+        match self {
+            Capability::Input => 1,
+            Capability::Sound => 1 << 1,
+            Capability::Load => 1 << 2,
+            Capability::Clock => 1 << 3,
+        }
+    }
+}
+
+// the set of the capabilities enabled for a program (all of them by default)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Capabilities(u8);
+
+impl Capabilities {
+    pub const NONE: Capabilities = Capabilities(0);
+    pub const ALL: Capabilities = Capabilities(0b1111);
+
+    pub fn contains(self, cap: Capability) -> bool {
+        self.0 & cap.bit() != 0
+    }
+
+    pub fn with(self, cap: Capability) -> Self {
+        Capabilities(self.0 | cap.bit())
+    }
+
+    pub fn without(self, cap: Capability) -> Self {
+        Capabilities(self.0 & !cap.bit())
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Capabilities::ALL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn capabilities_with_and_without() {
+        let caps = Capabilities::NONE.with(Capability::Sound);
+
+        assert!(caps.contains(Capability::Sound));
+        assert!(!caps.contains(Capability::Input));
+
+        let caps = Capabilities::ALL.without(Capability::Load);
+
+        assert!(!caps.contains(Capability::Load));
+        assert!(Capability::ALL
+            .iter()
+            .filter(|cap| **cap != Capability::Load)
+            .all(|cap| caps.contains(*cap)));
+    }
+
+    #[test]
+    pub fn capability_required_by_inst() {
+        let inst = CfgInstruction::Primitive(Primitive::ButtonP);
+
+        assert_eq!(
+            Some((Capability::Input, "BUTTONP")),
+            Capability::required_by(&inst)
+        );
+        assert_eq!(None, Capability::required_by(&CfgInstruction::Print));
+    }
+}
@@ -0,0 +1,107 @@
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+// a color value (see `ExpressionType::Color`), e.g the pen color picked by `SETPENCOLOR`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+// the named colors, ordered by their palette index (the classic Logo palette)
+pub const COLOR_PALETTE: [(&str, Color); 16] = [
+    ("BLACK", Color::new(0, 0, 0)),
+    ("BLUE", Color::new(0, 0, 255)),
+    ("GREEN", Color::new(0, 255, 0)),
+    ("CYAN", Color::new(0, 255, 255)),
+    ("RED", Color::new(255, 0, 0)),
+    ("MAGENTA", Color::new(255, 0, 255)),
+    ("YELLOW", Color::new(255, 255, 0)),
+    ("WHITE", Color::new(255, 255, 255)),
+    ("BROWN", Color::new(155, 96, 59)),
+    ("TAN", Color::new(197, 136, 18)),
+    ("FOREST", Color::new(100, 162, 64)),
+    ("AQUA", Color::new(120, 187, 187)),
+    ("SALMON", Color::new(255, 149, 119)),
+    ("PURPLE", Color::new(144, 113, 208)),
+    ("ORANGE", Color::new(255, 163, 0)),
+    ("GREY", Color::new(183, 183, 183)),
+];
+
+impl Color {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    // the `RGB r g b` constructor, the channels are clamped into the `0..=255` range
+    pub fn rgb(r: isize, g: isize, b: isize) -> Self {
+        let channel = |v: isize| v.clamp(0, 255) as u8;
+
+        Self::new(channel(r), channel(g), channel(b))
+    }
+
+    // the color of the palette index (see `COLOR_PALETTE`)
+    pub fn palette(index: isize) -> Option<Self> {
+        match index {
+            i if i >= 0 => COLOR_PALETTE.get(i as usize).map(|(_, color)| *color),
+            _ => None,
+        }
+    }
+
+    // the color of the name (case insensitive), e.g `red`
+    pub fn named(name: &str) -> Option<Self> {
+        COLOR_PALETTE
+            .iter()
+            .find(|(color_name, _)| color_name.eq_ignore_ascii_case(name))
+            .map(|(_, color)| *color)
+    }
+
+    // the name of the color, when it's one of the palette colors
+    pub fn name(&self) -> Option<&'static str> {
+        COLOR_PALETTE
+            .iter()
+            .find(|(_, color)| color == self)
+            .map(|(color_name, _)| *color_name)
+    }
+
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        (self.r, self.g, self.b)
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Self::new(r, g, b)
+    }
+}
+
+// the color as written in code (i.e `RGB 255 0 0`)
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RGB {} {} {}", self.r, self.g, self.b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_rgb_clamps_the_channels() {
+        assert_eq!(Color::new(255, 0, 10), Color::rgb(300, -5, 10));
+    }
+
+    #[test]
+    fn color_palette_and_names() {
+        assert_eq!(Some(Color::new(255, 0, 0)), Color::palette(4));
+        assert_eq!(None, Color::palette(16));
+        assert_eq!(None, Color::palette(-1));
+
+        assert_eq!(Color::palette(4), Color::named("red"));
+        assert_eq!(None, Color::named("PINK"));
+
+        assert_eq!(Some("RED"), Color::new(255, 0, 0).name());
+        assert_eq!(None, Color::new(1, 2, 3).name());
+    }
+}
@@ -1,11 +1,27 @@
 use crate::ast::statement::{Command, Direction};
-use crate::vm::{Host, Pen, Turtle};
-use std::cell::RefCell;
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
+#[cfg(feature = "bigint")]
+use crate::vm::BigInt;
+use crate::vm::{
+    Color, Host, Label, Movement, Pen, Turtle, TurtlePose, TurtleShape, DEFAULT_SCREEN_SIZE,
+};
+use core::cell::RefCell;
 
 #[derive(Debug)]
 pub struct DummyHost {
     pen: Pen,
     turtle: Turtle,
+    screen_size: (usize, usize),
+
+    // the `(freq, ms)` of the played tones
+    tones: Vec<(usize, usize)>,
+
+    // the values kept by `STORE`
+    store: HashMap<String, String>,
+
+    // the lines written to each stream opened by `OPENWRITE`
+    streams: HashMap<String, Vec<String>>,
     log: RefCell<Vec<String>>,
 }
 
@@ -17,21 +33,56 @@ impl Host for DummyHost {
         self.append_log(msg);
     }
 
+    #[cfg(feature = "bigint")]
+    fn exec_print_big(&mut self, value: &BigInt) {
+        self.append_log(value.to_string());
+    }
+
+    fn exec_print_text(&mut self, text: &str) {
+        self.append_log(text.to_string());
+    }
+
+    fn exec_trace(&mut self, line: &str) {
+        self.append_log(line.to_string());
+    }
+
     fn exec_trap(&mut self, node_id: usize, ip: usize) {
         let msg = format!("trapping at ({}, {})", node_id, ip);
         self.append_log(msg);
     }
 
+    fn exec_stamp(&mut self, position: (isize, isize), heading: f64) {
+        let msg = format!("STAMP at {:?} heading {}", position, heading);
+        self.append_log(msg);
+    }
+
+    fn exec_label(&mut self, label: &Label) {
+        let msg = format!(
+            "LABEL \"{}\" at {:?} heading {} ({} {})",
+            label.text, label.position, label.heading, label.style.font, label.style.size
+        );
+        self.append_log(msg);
+    }
+
+    fn turtle_shape_changed(&mut self, shape: &TurtleShape) {
+        let msg = format!("SHAPE {}", shape.name());
+        self.append_log(msg);
+    }
+
     fn exec_cmd(&mut self, cmd: &Command) {
         match cmd {
             Command::XCor => self.xcor(),
             Command::YCor => self.ycor(),
             Command::PenUp => self.pen_up(),
+            Command::PenDown => self.pen_down(),
             Command::PenErase => self.pen_erase(),
             Command::Clean => self.clean(),
             Command::ClearScreen => self.clear_screen(),
             Command::ShowTurtle => self.show_turtle(),
             Command::HideTurtle => self.hide_turtle(),
+            Command::Wait => self.wait(),
+            Command::Stop => self.stop(),
+            Command::SetBackgroundColor => self.set_bg_color(),
             _ => unimplemented!(),
         };
     }
@@ -39,6 +90,70 @@ impl Host for DummyHost {
     fn exec_direct(&mut self, direct: &Direction, count: isize) {
         self.turtle.exec_direct(direct, count);
     }
+
+    fn exec_turn(&mut self, degrees: f64) {
+        self.turtle.turn_degrees(degrees);
+    }
+
+    fn screen_size(&self) -> (usize, usize) {
+        self.screen_size
+    }
+
+    fn play_tone(&mut self, freq: usize, ms: usize) {
+        self.tones.push((freq, ms));
+        self.append_log(format!("TONE {} {}", freq, ms));
+    }
+
+    fn pen_color_changed(&mut self, color: Color) {
+        self.set_pen_color(color);
+    }
+
+    fn movement_started(&mut self, movement: &Movement) {
+        let msg = format!("MOVE {:?} {}", movement.direct, movement.count);
+        self.append_log(msg);
+    }
+
+    fn movement_progress(&mut self, _movement: &Movement, frame: usize, pose: &TurtlePose) {
+        let msg = format!("FRAME {} at {:?} heading {}", frame, pose.position, pose.heading);
+        self.append_log(msg);
+    }
+
+    fn movement_completed(&mut self, movement: &Movement) {
+        self.turtle.exec_direct(&movement.direct, movement.count);
+    }
+
+    fn state_restored(&mut self, turtle: &Turtle, pen: &Pen) {
+        let msg = format!(
+            "POPSTATE at ({}, {}) heading {}",
+            turtle.xcor(),
+            turtle.ycor(),
+            turtle.heading()
+        );
+        self.append_log(msg);
+
+        self.turtle = turtle.clone();
+        self.pen = pen.clone();
+    }
+
+    fn kv_set(&mut self, key: &str, value: &str) {
+        self.store.insert(key.to_string(), value.to_string());
+    }
+
+    fn kv_get(&self, key: &str) -> Option<String> {
+        self.store.get(key).cloned()
+    }
+
+    fn open_stream(&mut self, name: &str) {
+        self.streams.insert(name.to_string(), Vec::new());
+    }
+
+    fn write_stream(&mut self, name: &str, text: &str) {
+        self.streams.get_mut(name).unwrap().push(text.to_string());
+    }
+
+    fn close_stream(&mut self, name: &str) {
+        self.append_log(format!("CLOSE {}", name));
+    }
 }
 
 impl DummyHost {
@@ -46,10 +161,27 @@ impl DummyHost {
         Self {
             pen: Pen::new(),
             turtle: Turtle::new(),
+            screen_size: DEFAULT_SCREEN_SIZE,
+            tones: Vec::new(),
+            store: HashMap::new(),
+            streams: HashMap::new(),
             log: RefCell::new(Vec::new()),
         }
     }
 
+    pub fn tones(&self) -> &[(usize, usize)] {
+        &self.tones
+    }
+
+    // the lines written to the stream, `None` when it's never been opened
+    pub fn stream(&self, name: &str) -> Option<&[String]> {
+        self.streams.get(name).map(|lines| lines.as_slice())
+    }
+
+    pub fn set_screen_size(&mut self, width: usize, height: usize) {
+        self.screen_size = (width, height);
+    }
+
     pub fn xcor(&self) {
         let x = self.turtle.xcor();
 
@@ -107,18 +239,19 @@ impl DummyHost {
         self.append_log("CLEARSCREEN".to_string());
     }
 
-    pub fn set_pen_color(&mut self, color: (u8, u8, u8)) {
+    pub fn set_pen_color(&mut self, color: Color) {
+        self.append_log(format!("SETPENCOLOR {}", color));
         self.pen.set_color(color);
     }
 
     pub fn set_bg_color(&mut self) {
-        unimplemented!()
+        self.append_log("SETBACKGROUND".to_string());
     }
 
     pub fn wait(&mut self) {}
 
     pub fn stop(&mut self) {
-        unimplemented!()
+        self.append_log("STOP".to_string());
     }
 
     pub fn get_turtle(&self) -> &Turtle {
@@ -0,0 +1,53 @@
+use crate::ast::semantic::AstWalkError;
+use crate::compat::prelude::*;
+use crate::parser::ParseError;
+use core::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum EvalError {
+    Parse(ParseError),
+    Semantic(AstWalkError),
+    ProcCallNotAllowed(String),
+    DivisionByZero,
+
+    // the overflowing arithmetic (e.g `9223372036854775807 + 1`), see `OverflowPolicy::Checked`
+    IntegerOverflow(String),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::Parse(err) => write!(f, "{}", err.to_string()),
+            EvalError::Semantic(err) => write!(f, "{}", err.to_string()),
+            EvalError::ProcCallNotAllowed(proc) => write!(
+                f,
+                "Procedure calls aren't allowed in evaluated expressions (procedure: `{}`)",
+                proc
+            ),
+            EvalError::DivisionByZero => write!(f, "Division by zero"),
+            EvalError::IntegerOverflow(expr) => write!(f, "Integer overflow computing `{}`", expr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn eval_error_proc_call_not_allowed() {
+        let err = EvalError::ProcCallNotAllowed("SQUARE".to_string());
+
+        assert_eq!(
+            "Procedure calls aren't allowed in evaluated expressions (procedure: `SQUARE`)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    pub fn eval_error_wraps_semantic_error() {
+        let err = EvalError::Semantic(AstWalkError::MissingVarDeclaration("X".to_string(), None));
+
+        assert_eq!("Missing variable declaration for `X`", err.to_string());
+    }
+}
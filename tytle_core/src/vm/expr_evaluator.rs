@@ -0,0 +1,315 @@
+use crate::ast::expression::*;
+use crate::ast::semantic::*;
+use crate::compat::prelude::*;
+use crate::vm::*;
+
+pub type EvalResult<T> = Result<T, EvalError>;
+
+// evaluates a standalone expression against the VM state (globals and the current stack frame).
+// the evaluation is side-effect free, hence procedure calls aren't allowed
+pub struct ExprEvaluator<'a> {
+    env: &'a Environment,
+    memory: &'a Memory,
+    frame: Option<&'a CallStackFrame>,
+    overflow: OverflowPolicy,
+}
+
+impl<'a> ExprEvaluator<'a> {
+    pub fn new(env: &'a Environment, memory: &'a Memory, call_stack: &'a CallStack) -> Self {
+        let frame = if call_stack.is_empty() {
+            None
+        } else {
+            Some(call_stack.current_frame())
+        };
+
+        Self {
+            env,
+            memory,
+            frame,
+            overflow: OverflowPolicy::default(),
+        }
+    }
+
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = policy;
+        self
+    }
+
+    pub fn eval(&self, expr: &Expression) -> EvalResult<MemoryValue> {
+        // we type-check the whole expression first, so a failing evaluation is never partial
+        self.typecheck(expr)?;
+
+        self.eval_expr(expr)
+    }
+
+    fn typecheck(&self, expr: &Expression) -> EvalResult<ExpressionType> {
+        match &expr.expr_ast {
+            ExpressionAst::Literal(lit_expr) => match lit_expr {
+                LiteralExpr::Bool(_) => Ok(ExpressionType::Bool),
+                LiteralExpr::Int(_) => Ok(ExpressionType::Int),
+                LiteralExpr::Str(_) => Ok(ExpressionType::Str),
+                LiteralExpr::Var(var_name) => {
+                    let var = self.lookup_var(var_name)?;
+
+                    match var.var_type {
+                        Some(ref var_type) => Ok(var_type.clone()),
+                        None => {
+                            let err = AstWalkError::VariableTypeMissing(var_name.to_owned());
+                            Err(EvalError::Semantic(err))
+                        }
+                    }
+                }
+            },
+            ExpressionAst::Parentheses(inner_expr) => self.typecheck(inner_expr),
+            ExpressionAst::Not(inner_expr) => {
+                if self.typecheck(inner_expr)? != ExpressionType::Bool {
+                    let expr_str = PrettyPrintAst::pprint_expr(inner_expr);
+                    let err = AstWalkError::NotBooleanExpr(expr_str);
+                    return Err(EvalError::Semantic(err));
+                }
+
+                Ok(ExpressionType::Bool)
+            }
+            ExpressionAst::TypeQuery(query, inner_expr) => {
+                self.typecheck(inner_expr)?;
+
+                Ok(query.result_type())
+            }
+            ExpressionAst::Binary(bin_op, lexpr, rexpr) => {
+                let ltype = self.typecheck(lexpr)?;
+                let rtype = self.typecheck(rexpr)?;
+
+                if ltype != rtype || !bin_op.accepts(&ltype) {
+                    let err = AstWalkError::InvalidBinaryOp(bin_op.clone(), ltype, rtype);
+                    return Err(EvalError::Semantic(err));
+                }
+
+                match bin_op {
+                    BinaryOp::And | BinaryOp::Or => Ok(ExpressionType::Bool),
+                    _ => Ok(ExpressionType::from(bin_op)),
+                }
+            }
+            ExpressionAst::List(items) => {
+                let mut items_types = items.iter().map(|item| self.typecheck(item));
+
+                let elem_type = match items_types.next() {
+                    Some(elem_type) => elem_type?,
+                    None => return Err(EvalError::Semantic(AstWalkError::EmptyList)),
+                };
+
+                for item_type in items_types {
+                    let item_type = item_type?;
+
+                    if item_type != elem_type {
+                        let err = AstWalkError::TypeMismatch(elem_type, item_type);
+                        return Err(EvalError::Semantic(err));
+                    }
+                }
+
+                Ok(ExpressionType::list_of(elem_type))
+            }
+            ExpressionAst::ProcRef(proc_name) => {
+                self.lookup_proc(proc_name)?;
+
+                Ok(ExpressionType::Proc)
+            }
+            ExpressionAst::ProcCall(proc_name, ..) | ExpressionAst::Map(proc_name, ..) => {
+                Err(EvalError::ProcCallNotAllowed(proc_name.to_owned()))
+            }
+            ExpressionAst::Apply(..) => Err(EvalError::ProcCallNotAllowed("APPLY".to_string())),
+            // blocks are compiled into procedures of their own
+            ExpressionAst::Block(_) => Err(EvalError::ProcCallNotAllowed("BLOCK".to_string())),
+            ExpressionAst::NamedArg(_, inner_expr) => self.typecheck(inner_expr),
+            ExpressionAst::IfElse(cond_expr, true_expr, false_expr) => {
+                if self.typecheck(cond_expr)? != ExpressionType::Bool {
+                    let expr_str = PrettyPrintAst::pprint_expr(cond_expr);
+                    let err = AstWalkError::NotBooleanExpr(expr_str);
+                    return Err(EvalError::Semantic(err));
+                }
+
+                let true_type = self.typecheck(true_expr)?;
+                let false_type = self.typecheck(false_expr)?;
+
+                if true_type != false_type {
+                    let err = AstWalkError::TypeMismatch(true_type, false_type);
+                    return Err(EvalError::Semantic(err));
+                }
+
+                Ok(true_type)
+            }
+        }
+    }
+
+    fn eval_expr(&self, expr: &Expression) -> EvalResult<MemoryValue> {
+        match &expr.expr_ast {
+            ExpressionAst::Literal(lit_expr) => match lit_expr {
+                LiteralExpr::Bool(v) => Ok(MemoryValue::Bool(*v)),
+                LiteralExpr::Int(v) => Ok(MemoryValue::Int(*v as isize)),
+                LiteralExpr::Str(v) => Ok(MemoryValue::Str(v.clone())),
+                LiteralExpr::Var(var_name) => {
+                    let var = self.lookup_var(var_name)?;
+                    Ok(self.load_var(var))
+                }
+            },
+            ExpressionAst::Parentheses(inner_expr) => self.eval_expr(inner_expr),
+            ExpressionAst::Not(inner_expr) => {
+                let v = self.eval_bool(inner_expr)?;
+                Ok(MemoryValue::Bool(!v))
+            }
+            ExpressionAst::TypeQuery(query, inner_expr) => {
+                let inner_type = self.typecheck(inner_expr)?;
+
+                // unlike in compiled code, the (side-effect free) operand can be evaluated
+                let inner_str = match self.eval_expr(inner_expr)? {
+                    MemoryValue::Str(s) => Some(s),
+                    _ => None,
+                };
+
+                let value = match query.eval(&inner_type, inner_str.as_deref()) {
+                    Some(LiteralExpr::Str(s)) => MemoryValue::Str(s),
+                    Some(LiteralExpr::Bool(v)) => MemoryValue::Bool(v),
+                    lit => unreachable!("unexpected type query answer: `{:?}`", lit),
+                };
+
+                Ok(value)
+            }
+            ExpressionAst::Binary(bin_op, lexpr, rexpr) => match bin_op {
+                BinaryOp::And => {
+                    let v = self.eval_bool(lexpr)? && self.eval_bool(rexpr)?;
+                    Ok(MemoryValue::Bool(v))
+                }
+                BinaryOp::Or => {
+                    let v = self.eval_bool(lexpr)? || self.eval_bool(rexpr)?;
+                    Ok(MemoryValue::Bool(v))
+                }
+                BinaryOp::Equal
+                | BinaryOp::NotEqual
+                | BinaryOp::GreaterThan
+                | BinaryOp::LessThan => self.eval_cmp(bin_op, lexpr, rexpr),
+                _ => {
+                    let a = self.eval_expr(lexpr)?;
+                    let b = self.eval_expr(rexpr)?;
+
+                    if *bin_op == BinaryOp::Div && b == MemoryValue::Int(0) {
+                        return Err(EvalError::DivisionByZero);
+                    }
+
+                    self.overflow.eval(bin_op, &a, &b).ok_or_else(|| {
+                        let expr_str = format!("{} {} {}", a, bin_op.to_string(), b);
+                        EvalError::IntegerOverflow(expr_str)
+                    })
+                }
+            },
+            ExpressionAst::List(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| self.eval_expr(item))
+                    .collect::<EvalResult<Vec<MemoryValue>>>()?;
+
+                Ok(MemoryValue::List(items))
+            }
+            ExpressionAst::ProcRef(proc_name) => {
+                let proc_id = self.lookup_proc(proc_name)?;
+
+                Ok(MemoryValue::ProcRef(proc_id))
+            }
+            ExpressionAst::ProcCall(proc_name, ..) | ExpressionAst::Map(proc_name, ..) => {
+                Err(EvalError::ProcCallNotAllowed(proc_name.to_owned()))
+            }
+            ExpressionAst::Apply(..) => Err(EvalError::ProcCallNotAllowed("APPLY".to_string())),
+            // blocks are compiled into procedures of their own
+            ExpressionAst::Block(_) => Err(EvalError::ProcCallNotAllowed("BLOCK".to_string())),
+            ExpressionAst::NamedArg(_, inner_expr) => self.eval_expr(inner_expr),
+            ExpressionAst::IfElse(cond_expr, true_expr, false_expr) => {
+                match self.eval_bool(cond_expr)? {
+                    true => self.eval_expr(true_expr),
+                    false => self.eval_expr(false_expr),
+                }
+            }
+        }
+    }
+
+    fn eval_cmp(
+        &self,
+        bin_op: &BinaryOp,
+        lexpr: &Expression,
+        rexpr: &Expression,
+    ) -> EvalResult<MemoryValue> {
+        let a = self.eval_expr(lexpr)?;
+        let b = self.eval_expr(rexpr)?;
+
+        let res = match bin_op {
+            BinaryOp::Equal => a.equals(&b),
+            BinaryOp::NotEqual => a.equals(&b).map(|eq| !eq),
+            BinaryOp::GreaterThan => a.compare(&b).map(|ord| ord.is_gt()),
+            BinaryOp::LessThan => a.compare(&b).map(|ord| ord.is_lt()),
+            _ => unreachable!(),
+        };
+
+        // the operands have already been type-checked (see `typecheck`)
+        let v = res.map_err(|IncomparableValues(ltype, rtype)| {
+            let err = AstWalkError::InvalidBinaryOp(bin_op.clone(), ltype, rtype);
+            EvalError::Semantic(err)
+        })?;
+
+        Ok(MemoryValue::Bool(v))
+    }
+
+    fn eval_bool(&self, expr: &Expression) -> EvalResult<bool> {
+        match self.eval_expr(expr)? {
+            MemoryValue::Bool(v) => Ok(v),
+            v => panic!("expected a boolean value (actual: `{:?}`)", v),
+        }
+    }
+
+    // the current frame procedure locals shadow the globals
+    fn lookup_var(&self, var_name: &str) -> EvalResult<&'a Variable> {
+        let local = self.frame.and_then(|frame| {
+            self.env
+                .locals_symbols
+                .get(&frame.ctx_proc)?
+                .iter()
+                .map(|var_id| self.env.symbol_table.get_var_by_id(*var_id))
+                .find(|var| var.name == var_name)
+        });
+
+        let var = local.or_else(|| {
+            self.env
+                .globals_symbols
+                .values()
+                .map(|var_id| self.env.symbol_table.get_var_by_id(*var_id))
+                .find(|var| var.name == var_name)
+        });
+
+        var.ok_or_else(|| {
+            let err = AstWalkError::MissingVarDeclaration(var_name.to_owned(), None);
+            EvalError::Semantic(err)
+        })
+    }
+
+    fn lookup_proc(&self, proc_name: &str) -> EvalResult<SymbolId> {
+        let symbol = self
+            .env
+            .symbol_table
+            .lookup(0, proc_name, &SymbolKind::Proc);
+
+        match symbol {
+            Some(symbol) => Ok(symbol.as_proc().id),
+            None => {
+                let err = AstWalkError::MissingProcDeclaration(proc_name.to_owned(), None);
+                Err(EvalError::Semantic(err))
+            }
+        }
+    }
+
+    fn load_var(&self, var: &Variable) -> MemoryValue {
+        let index = var.index.unwrap();
+
+        if var.global {
+            self.memory.get_global(Address(index)).unwrap().clone()
+        } else {
+            let frame = self.frame.unwrap();
+            MemoryValue::from(frame.load(index))
+        }
+    }
+}
@@ -0,0 +1,22 @@
+// the point at which `Interpreter::exec_frame` has returned control to the host
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum YieldPoint {
+    // the frame has used up its budget (see `Interpreter::set_speed`)
+    Budget,
+
+    // a `WAIT` command has been executed, the host should let (at least) a frame pass
+    // before resuming the execution
+    Wait,
+
+    // the program has completed
+    Completed,
+}
+
+// the outcome of executing a single animation frame (see `Interpreter::exec_frame`)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FrameReport {
+    // the number of executed interpreter steps (the same unit as `RunOptions::max_steps`)
+    pub steps: usize,
+
+    pub yield_point: YieldPoint,
+}
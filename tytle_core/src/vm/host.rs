@@ -1,9 +1,177 @@
 use crate::ast::statement::{Command, Direction};
+use crate::compat::prelude::*;
+use crate::ir::CfgNodeId;
+#[cfg(feature = "bigint")]
+use crate::vm::{big_int, BigInt};
+use crate::vm::{Color, Label, Movement, Pen, Turtle, TurtlePose, TurtleShape};
+use serde::{Deserialize, Serialize};
 
+// the screen size of a host which has no screen of its own
+pub const DEFAULT_SCREEN_SIZE: (usize, usize) = (800, 600);
+
+// the tone played by `BEEP`
+pub const BEEP_FREQ: usize = 880;
+pub const BEEP_DURATION: usize = 200;
+
+// the callbacks added along the language features (drawing, sound, storage, streams)
+// have no-op defaults, so a host implements only the ones it supports
 pub trait Host {
     fn exec_cmd(&mut self, cmd: &Command);
     fn exec_direct(&mut self, direct: &Direction, count: isize);
     fn exec_trap(&mut self, node: usize, ip: usize);
     fn exec_print(&mut self, value: isize);
+
+    // `PRINT` of a non-integer value (e.g a `FORMAT` string), already formatted
+    fn exec_print_text(&mut self, _text: &str) {}
+
+    // `PRINT` of an integer which doesn't fit `isize` (see `OverflowPolicy::Promote`).
+    // a host which doesn't override it prints the saturated value
+    #[cfg(feature = "bigint")]
+    fn exec_print_big(&mut self, value: &BigInt) {
+        self.exec_print(big_int::saturate(value));
+    }
+
+    // a line reporting a call of a traced procedure (or its return), see `TRACE`.
+    // a host which doesn't override it prints the line
+    fn exec_trace(&mut self, line: &str) {
+        self.exec_print_text(line);
+    }
+
+    // the turtle turns clockwise by `degrees`, whatever its `Steering` and `AngleUnit`.
+    // reported for the `LEFT` / `RIGHT` of the stdlib prelude procedures, which draw by turning
+    fn exec_turn(&mut self, _degrees: f64) {}
+
+    // draws the turtle shape at `position`, facing `heading` (see `STAMP`)
+    fn exec_stamp(&mut self, _position: (isize, isize), _heading: f64) {}
+
+    // draws the text of a `LABEL`
+    fn exec_label(&mut self, _label: &Label) {}
+
+    // the turtle is drawn with a new shape from now on (see `SETSHAPE`)
+    fn turtle_shape_changed(&mut self, _shape: &TurtleShape) {}
+
+    // the `(width, height)` of the screen (canvas) the host draws on, see `SCREENWIDTH`.
+    // a host whose screen gets resized should report it via `Interpreter::notify_resize`
+    fn screen_size(&self) -> (usize, usize) {
+        DEFAULT_SCREEN_SIZE
+    }
+
+    // plays a tone of `freq` hertz for `ms` milliseconds (see `TONE` and `BEEP`).
+    // a host which doesn't support audio may ignore it
+    fn play_tone(&mut self, _freq: usize, _ms: usize) {}
+
+    // the lines are drawn with a new color from now on (see `SETPENCOLOR`)
+    fn pen_color_changed(&mut self, _color: Color) {}
+
+    // a direction statement split into frames (see `Interpreter::set_movement_granularity`).
+    // it's reported instead of `exec_direct` as a `movement_started`, a `movement_progress`
+    // per frame and a `movement_completed` (once the turtle has reached `movement.to`)
+    fn movement_started(&mut self, _movement: &Movement) {}
+    fn movement_progress(&mut self, _movement: &Movement, _frame: usize, _pose: &TurtlePose) {}
+    fn movement_completed(&mut self, _movement: &Movement) {}
+
+    // the turtle & pen jump back to the state saved by the matching `PUSHSTATE` (see `POPSTATE`)
+    fn state_restored(&mut self, _turtle: &Turtle, _pen: &Pen) {}
+
+    // keeps `value` under `key` across the runs of the program (see `STORE`),
+    // e.g in a file or in the browser local storage
+    fn kv_set(&mut self, _key: &str, _value: &str) {}
+
+    // the value kept under `key`, `None` when there's none (see `RETRIEVE`)
+    fn kv_get(&self, _key: &str) -> Option<String> {
+        None
+    }
+
+    // opens the stream of `name` (empty, if it's already been written to), see `OPENWRITE`.
+    // streams are provided by the host (e.g as the files of an exercise),
+    // so programs have no filesystem access
+    fn open_stream(&mut self, _name: &str) {}
+
+    // a `PRINT` to the open stream of `name`, set by `SETWRITE` (the text is already formatted)
+    fn write_stream(&mut self, _name: &str, _text: &str) {}
+
+    // no more writes to the stream of `name` (see `CLOSE`)
+    fn close_stream(&mut self, _name: &str) {}
+
     fn compilation_error(&mut self, error: &str);
 }
+
+// a side-effect the interpreter delegates to its host
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HostCall {
+    Cmd(Command),
+    Direct(Direction, isize),
+    Turn(f64),
+    Trap(CfgNodeId, usize),
+    Print(isize),
+    #[cfg(feature = "bigint")]
+    PrintBig(BigInt),
+    PrintText(String),
+    Trace(String),
+    Stamp {
+        position: (isize, isize),
+        heading: f64,
+    },
+    Label(Label),
+    ShapeChanged(TurtleShape),
+    Tone {
+        freq: usize,
+        ms: usize,
+    },
+    PenColor(Color),
+    Movement(Movement),
+    StateRestored(Turtle, Pen),
+    KvSet {
+        key: String,
+        value: String,
+    },
+    // the answers of the host queries (see `RecordingHost`)
+    KvGet {
+        key: String,
+        value: Option<String>,
+    },
+    ScreenSize(usize, usize),
+    OpenStream(String),
+    WriteStream {
+        stream: String,
+        text: String,
+    },
+    CloseStream(String),
+}
+
+impl HostCall {
+    pub fn dispatch(&self, host: &mut dyn Host) {
+        match self {
+            HostCall::Cmd(cmd) => host.exec_cmd(cmd),
+            HostCall::Direct(direct, count) => host.exec_direct(direct, *count),
+            HostCall::Turn(degrees) => host.exec_turn(*degrees),
+            HostCall::Trap(node_id, ip) => host.exec_trap(*node_id, *ip),
+            HostCall::Print(value) => host.exec_print(*value),
+            #[cfg(feature = "bigint")]
+            HostCall::PrintBig(value) => host.exec_print_big(value),
+            HostCall::PrintText(text) => host.exec_print_text(text),
+            HostCall::Trace(line) => host.exec_trace(line),
+            HostCall::Stamp { position, heading } => host.exec_stamp(*position, *heading),
+            HostCall::Label(label) => host.exec_label(label),
+            HostCall::ShapeChanged(shape) => host.turtle_shape_changed(shape),
+            HostCall::Tone { freq, ms } => host.play_tone(*freq, *ms),
+            HostCall::PenColor(color) => host.pen_color_changed(*color),
+            HostCall::Movement(movement) => {
+                host.movement_started(movement);
+
+                for frame in 1..=movement.frames {
+                    host.movement_progress(movement, frame, &movement.pose_at(frame));
+                }
+
+                host.movement_completed(movement);
+            }
+            HostCall::StateRestored(turtle, pen) => host.state_restored(turtle, pen),
+            HostCall::KvSet { key, value } => host.kv_set(key, value),
+            // a query has no side-effect to dispatch
+            HostCall::KvGet { .. } | HostCall::ScreenSize(..) => {}
+            HostCall::OpenStream(name) => host.open_stream(name),
+            HostCall::WriteStream { stream, text } => host.write_stream(stream, text),
+            HostCall::CloseStream(name) => host.close_stream(name),
+        }
+    }
+}
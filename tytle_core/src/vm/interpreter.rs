@@ -1,261 +1,1799 @@
-use crate::prelude::*;
+use crate::ast::expression::*;
+use crate::ast::semantic::*;
+use crate::ast::statement::*;
+use crate::compat::collections::HashSet;
+use crate::compat::prelude::*;
+use crate::compat::Instant;
+use crate::ir::*;
+use crate::lexer::Location;
+use crate::parser::TytleParser;
+use crate::vm::*;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 static MAX_STACK_DEPTH: usize = 10_000;
 
+// the maximum number of states saved by `PUSHSTATE` (and not restored yet)
+pub static MAX_STATE_DEPTH: usize = 1_000;
+
+// the cancellation token is polled once every `CANCEL_CHECK_INTERVAL` executed instructions
+static CANCEL_CHECK_INTERVAL: usize = 1_000;
+
 #[derive(Debug, PartialEq)]
 pub enum InterpreterException {
     StackOverflow,
+    Cancelled,
+
+    // `PUSHSTATE` of more than `MAX_STATE_DEPTH` states
+    StateStackOverflow,
+
+    // a value (or the whole memory) exceeding its cap, see `Interpreter::set_resource_limits`
+    ResourceLimit {
+        resource: Resource,
+        limit: usize,
+        actual: usize,
+    },
+
+    // a feature (e.g `TONE`) requiring a capability which isn't enabled,
+    // see `Interpreter::set_capabilities`
+    CapabilityDisabled {
+        capability: Capability,
+        feature: String,
+    },
+
+    // `POPSTATE` with no state saved by `PUSHSTATE`
+    StateStackEmpty,
+    Watchpoint(WatchHit),
+
+    // the execution has reached a breakpoint (see `Interpreter::set_breakpoint`),
+    // it's resumed by executing again (starting at the instruction the breakpoint is on)
+    Breakpoint(Location),
+    Incomparable(IncomparableValues),
+
+    // an integer arithmetic overflowing under the `OverflowPolicy::Checked` policy,
+    // as the operator and its operands
+    IntegerOverflow {
+        op: String,
+        lhs: isize,
+        rhs: isize,
+    },
+
+    // an integer division whose divisor is zero
+    DivisionByZero,
+
+    // `ITEM` of a word with no such character (the index starts from 1)
+    ItemOutOfRange {
+        index: isize,
+        count: usize,
+    },
+
+    // `PARSE` of a word that isn't a number
+    InvalidNumber(String),
+
+    // `FORMAT` of a string whose placeholders don't match the args
+    FormatArgsCount {
+        placeholders: usize,
+        args: usize,
+    },
+
+    // `FORMAT` of a string having an unknown `~` directive (e.g `~x`)
+    InvalidFormatDirective(String),
+
+    // `APPLY` of a procedure with a wrong number of args
+    ApplyArgsCount {
+        proc_name: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    // `APPLY` of a procedure having a rest param with fewer args than its other params
+    ApplyTooFewArgs {
+        proc_name: String,
+        at_least: usize,
+        actual: usize,
+    },
+
+    // `APPLY` of a procedure with an arg of the wrong type (the index starts from 1)
+    ApplyArgType {
+        proc_name: String,
+        index: usize,
+        expected: ExpressionType,
+        actual: ExpressionType,
+    },
+
+    // `APPLY` of a `PROC` variable which hasn't been assigned yet
+    ApplyUnassigned,
+
+    // `RUN` of a `BLOCK` variable which hasn't been assigned yet
+    RunUnassigned,
+
+    // a block using a local variable of the procedure it's been created within,
+    // once the procedure has returned
+    StaleBlockLocal(String),
+
+    // `THROW` of a tag with no enclosing `CATCH` of it
+    UncaughtThrow(String),
+
+    // `SETSHAPE` of a name which isn't a built-in shape nor a registered image
+    // (see `Interpreter::register_shape`)
+    UnknownShape(String),
+
+    // `SETPENCOLOR`, `PALETTE` or `NAMEDCOLOR` of a palette index (or a color name) which isn't
+    // in the palette (see `Color::palette`)
+    UnknownColor(String),
+
+    // `SETWRITE` (or `CLOSE`) of a stream which hasn't been opened by `OPENWRITE`
+    StreamNotOpen(String),
+
+    // a host-call (or a `RETRIEVE`) executed synchronously (e.g by `exec_code`)
+    // by an interpreter with no host attached (see `Interpreter::new_async`)
+    NoHost,
+
+    // a call of a native procedure by an interpreter with no synchronous host attached
+    NativeNoHost(String),
+
+    // a native procedure returning a value of another type than its declared return type
+    // (`Unit` when it has returned no value)
+    NativeReturnType {
+        proc_name: String,
+        expected: ExpressionType,
+        actual: ExpressionType,
+    },
+
+    // `ASSERT` of a false condition. `vars` are the values of the globals and of the current procedure
+    // locals at the time of the failure (ordered by their index)
+    AssertionFailed {
+        message: String,
+        location: Location,
+        vars: Vec<(String, MemoryValue)>,
+    },
+}
+
+impl InterpreterException {
+    // whether the exception can be caught by a `CATCH "ERROR`.
+    // cancellations, watchpoints and breakpoints are requested by the host,
+    // so they always stop the execution.
+    // so do the resource limits and the disabled capabilities, which a program mustn't get around,
+    // and the errors of the embedder (a missing host, a misbehaving native procedure)
+    pub fn is_catchable(&self) -> bool {
+        match self {
+            InterpreterException::Cancelled
+            | InterpreterException::Watchpoint(_)
+            | InterpreterException::Breakpoint(_)
+            | InterpreterException::ResourceLimit { .. }
+            | InterpreterException::CapabilityDisabled { .. }
+            | InterpreterException::NoHost
+            | InterpreterException::NativeNoHost(_)
+            | InterpreterException::NativeReturnType { .. } => false,
+            InterpreterException::UncaughtThrow(tag) => tag != ERROR_TAG,
+            _ => true,
+        }
+    }
+}
+
+pub type InterpreterResult = Result<(), InterpreterException>;
+
+// the outcome of executing a single instruction (or edge jump)
+enum Step {
+    Continue,
+    Completed,
+    Host(HostCall),
+
+    // a `RETRIEVE` of the key, the value is pushed once the host has been queried
+    Retrieve(String),
+}
+
+type VmObserver = Box<dyn FnMut(&VmEvent)>;
+
+pub struct Interpreter<'env, 'cfg, 'host> {
+    pub ip: usize,
+    pub node_id: CfgNodeId,
+    pub memory: Memory,
+    pub call_stack: CallStack,
+    env: &'env Environment,
+    cfg: &'cfg CfgObject,
+    host: Option<&'host mut dyn Host>,
+    observers: Vec<VmObserver>,
+    loop_heads: HashSet<CfgNodeId>,
+    watches: HashSet<SymbolId>,
+
+    // the procedures traced by `TRACE`
+    traces: HashSet<SymbolId>,
+
+    // the image shapes registered by the embedder (see `register_shape`)
+    shapes: HashSet<String>,
+
+    // the number of steps executed per animation frame (see `set_speed`), unthrottled when `None`
+    speed: Option<usize>,
+
+    // the number of frames a direction statement is split into (see `set_movement_granularity`),
+    // reported as a single `exec_direct` when `None`
+    movement_frames: Option<usize>,
+    profile: Option<ProfileReport>,
+
+    // what an overflowing integer arithmetic results in (see `set_overflow_policy`)
+    overflow: OverflowPolicy,
+
+    // the host-facing features the program may use (see `set_capabilities`)
+    capabilities: Capabilities,
+
+    // the debugging tables of the program (see `set_debug_info`)
+    debug_info: Option<&'cfg DebugInfo>,
+
+    // the addresses of the instructions the execution pauses at (see `set_breakpoint`),
+    // and the one it has paused at last (so resuming executes it rather than pausing again)
+    breakpoints: HashSet<(CfgNodeId, usize)>,
+    paused_at: Option<(CfgNodeId, usize)>,
+
+    #[cfg(feature = "tracing")]
+    proc_spans: ProcSpans,
 }
 
-pub type InterpreterResult = Result<(), InterpreterException>;
+impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
+    pub fn new(cfg: &'cfg CfgObject, env: &'env Environment, host: &'host mut dyn Host) -> Self {
+        Self::init(cfg, env, Some(host))
+    }
+
+    // creates an interpreter with no synchronous host attached.
+    // such an interpreter should be executed using `exec_async`
+    pub fn new_async(cfg: &'cfg CfgObject, env: &'env Environment) -> Self {
+        Self::init(cfg, env, None)
+    }
+
+    fn init(
+        cfg: &'cfg CfgObject,
+        env: &'env Environment,
+        host: Option<&'host mut dyn Host>,
+    ) -> Self {
+        // node with `id = 0` is reserved for the `main wrapper`
+        // while node having `id = 1` is reserved for the `main`
+
+        let main_node_id = cfg.graph.get_entry_node_id();
+
+        let mut intr = Self {
+            ip: 0,
+            env,
+            host,
+            cfg,
+            observers: Vec::new(),
+            loop_heads: HashSet::new(),
+            watches: HashSet::new(),
+            traces: HashSet::new(),
+            shapes: HashSet::new(),
+            speed: None,
+            movement_frames: None,
+            profile: None,
+            overflow: OverflowPolicy::default(),
+            capabilities: Capabilities::ALL,
+            debug_info: None,
+            breakpoints: HashSet::new(),
+            paused_at: None,
+            #[cfg(feature = "tracing")]
+            proc_spans: ProcSpans::default(),
+            memory: Memory::new(),
+            call_stack: CallStack::new(),
+            node_id: main_node_id,
+        };
+
+        if let Some(host) = intr.host.as_ref() {
+            intr.memory.screen_size = host.screen_size();
+        }
+
+        intr.init_memory();
+        intr.init_callstack();
+
+        intr
+    }
+
+    pub fn exec_code(&mut self) -> InterpreterResult {
+        enter_span!("exec");
+
+        loop {
+            let completed = self.exec_next()?;
+
+            if completed {
+                assert!(self.call_stack.is_empty());
+                return Ok(());
+            }
+        }
+    }
+
+    // executes the program while polling `cancel` periodically.
+    // setting `cancel` to `true` (e.g from another thread) aborts the execution
+    // with `InterpreterException::Cancelled`
+    pub fn exec_with_cancel(&mut self, cancel: Arc<AtomicBool>) -> InterpreterResult {
+        enter_span!("exec");
+
+        let mut executed = 0;
+
+        loop {
+            if executed % CANCEL_CHECK_INTERVAL == 0 && cancel.load(Ordering::Relaxed) {
+                return Err(InterpreterException::Cancelled);
+            }
+
+            let completed = self.exec_next()?;
+
+            if completed {
+                assert!(self.call_stack.is_empty());
+                return Ok(());
+            }
+
+            executed += 1;
+        }
+    }
+
+    // throttles the execution to `instructions_per_frame` interpreter steps per animation frame,
+    // so a GUI host can animate the drawing at a human-visible pace (see `exec_frame`)
+    pub fn set_speed(&mut self, instructions_per_frame: usize) {
+        assert!(
+            instructions_per_frame > 0,
+            "a frame must execute at least one step"
+        );
+
+        self.speed = Some(instructions_per_frame);
+    }
+
+    // splits each direction statement into `frames` steps, reported to the host as a
+    // `Host::movement_started`, a `Host::movement_progress` per frame and a
+    // `Host::movement_completed` (instead of `Host::exec_direct`),
+    // so a GUI host can tween the turtle along the way
+    pub fn set_movement_granularity(&mut self, frames: usize) {
+        assert!(frames > 0, "a movement must have at least one frame");
+
+        self.movement_frames = Some(frames);
+    }
+
+    // chooses whether an overflowing `+`, `*` or `/` wraps around, saturates or stops the execution
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow = policy;
+    }
+
+    // caps the memory the program may use (see `ResourceLimits`)
+    pub fn set_resource_limits(&mut self, limits: ResourceLimits) {
+        self.memory.limits = limits;
+    }
+
+    // restricts the host-facing features the program may use, e.g a program compiled elsewhere
+    // (see `CompileOptions::capabilities`)
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+    }
+
+    // the approximate number of bytes held by the globals and the callstack
+    // (see `MemoryValue::heap_size`)
+    pub fn heap_size(&self) -> usize {
+        self.memory.heap_size() + self.call_stack.heap_size()
+    }
+
+    // configures the coordinates convention of the turtle (resetting it), so the positions
+    // reported by `VmEvent::TurtleMove` & the snapshots match the canvas of the host
+    pub fn set_turtle_config(&mut self, config: TurtleConfig) {
+        self.memory.turtle = Turtle::with_config(config);
+    }
+
+    // updates the screen size seen by the program (`SCREENWIDTH` / `SCREENHEIGHT`),
+    // e.g when the canvas of the host gets resized while the program is running
+    pub fn notify_resize(&mut self, width: usize, height: usize) {
+        self.memory.screen_size = (width, height);
+    }
+
+    // updates the pointer state seen by the program (`MOUSEX`, `MOUSEY` and `BUTTONP`).
+    // the host reports the position in the turtle coordinates (see `set_turtle_config`)
+    pub fn notify_mouse(&mut self, x: isize, y: isize, pressed: bool) {
+        self.memory.mouse = MouseState {
+            position: (x, y),
+            pressed,
+        };
+    }
+
+    // registers an image shape the program may pick via `SETSHAPE "image:<name>`.
+    // the host is expected to know how to draw it (e.g a sprite it has loaded under that name)
+    pub fn register_shape(&mut self, name: &str) {
+        self.shapes.insert(name.to_string());
+    }
+
+    // executes a single animation frame, i.e till either:
+    // * the frame budget is used up (see `set_speed`), an unthrottled frame has no budget
+    // * a `WAIT` command is executed (after the host has executed it)
+    // * the program completes
+    //
+    // a host calls it once per frame (e.g on each `requestAnimationFrame`)
+    // till `YieldPoint::Completed`. a host enforcing a total steps budget
+    // (see `RunOptions::max_steps`) may sum the frames `steps`
+    pub fn exec_frame(&mut self) -> Result<FrameReport, InterpreterException> {
+        let mut steps = 0;
+
+        loop {
+            if self.speed == Some(steps) {
+                let yield_point = YieldPoint::Budget;
+                return Ok(FrameReport { steps, yield_point });
+            }
+
+            let step = self.step()?;
+            steps += 1;
+
+            match step {
+                Step::Continue => {}
+                Step::Completed => {
+                    assert!(self.call_stack.is_empty());
+
+                    let yield_point = YieldPoint::Completed;
+                    return Ok(FrameReport { steps, yield_point });
+                }
+                Step::Retrieve(key) => self.exec_kv_get(&key)?,
+                Step::Host(call) => {
+                    self.dispatch_host_call(&call)?;
+
+                    if call == HostCall::Cmd(Command::Wait) {
+                        let yield_point = YieldPoint::Wait;
+                        return Ok(FrameReport { steps, yield_point });
+                    }
+                }
+            }
+        }
+    }
+
+    // executes the program while awaiting `host` on each host-call.
+    // a throttled execution (see `set_speed`) awaits `AsyncHost::exec_yield` at each frame end
+    pub async fn exec_async(&mut self, host: &mut dyn AsyncHost) -> InterpreterResult {
+        let mut frame_steps = 0;
+        self.memory.screen_size = host.screen_size();
+
+        loop {
+            if self.speed == Some(frame_steps) {
+                host.exec_yield().await;
+                frame_steps = 0;
+            }
+
+            frame_steps += 1;
+
+            match self.step()? {
+                Step::Continue => {}
+                Step::Host(call) => {
+                    let started = self.profile.as_ref().map(|_| Instant::now());
+
+                    call.dispatch_async(host).await;
+
+                    self.record_host_call(started);
+                }
+                Step::Retrieve(key) => {
+                    let value = host.kv_get(&key);
+                    self.push_retrieved(value)?;
+                }
+                Step::Completed => {
+                    assert!(self.call_stack.is_empty());
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    // runs the `ONKEY` handler of `key` (if any) against the current VM state.
+    // events are dispatched by the host once the program has completed (see `exec_code`).
+    // returns whether `key` has a handler
+    pub fn dispatch_key(&mut self, key: &str) -> Result<bool, InterpreterException> {
+        self.check_capability(Capability::Input, "ONKEY")?;
+
+        match self.env.key_handlers.get(key) {
+            Some(proc_id) => self.exec_handler(*proc_id).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    // runs the `ONTICK` handler (if any), the host should dispatch a tick every `tick_interval` milliseconds.
+    // returns whether the program has a tick handler
+    pub fn dispatch_tick(&mut self) -> Result<bool, InterpreterException> {
+        self.check_capability(Capability::Clock, "ONTICK")?;
+
+        match self.env.tick_handler {
+            Some((_, proc_id)) => self.exec_handler(proc_id).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    // the `ONTICK` handler interval (in milliseconds)
+    pub fn tick_interval(&self) -> Option<usize> {
+        self.env.tick_handler.map(|(interval, _)| interval)
+    }
+
+    fn check_capability(&self, capability: Capability, feature: &str) -> InterpreterResult {
+        match self.capabilities.contains(capability) {
+            true => Ok(()),
+            false => Err(InterpreterException::CapabilityDisabled {
+                capability,
+                feature: feature.to_string(),
+            }),
+        }
+    }
+
+    // executes the handler procedure `proc_id` till it returns
+    fn exec_handler(&mut self, proc_id: SymbolId) -> InterpreterResult {
+        assert!(
+            self.call_stack.is_empty(),
+            "events can be dispatched only once the program has completed"
+        );
+
+        let (node_id, ip) = (self.node_id, self.ip);
+
+        // the handler returns into an empty `__main__` stack-frame
+        let main_proc_id = self.cfg.jmp_table[&self.cfg.graph.get_entry_node_id()];
+        self.call_stack.open_stackframe(main_proc_id, Vec::new());
+        self.call_stack.push_item(CallStackItem::Addr(node_id, ip));
+
+        let proc = self.cfg.get_proc(proc_id);
+        let mut slots = Vec::with_capacity(proc.slots_count());
+
+        Self::init_proc_locals(proc, &mut slots);
+
+        self.call_stack.open_stackframe(proc_id, slots);
+        self.emit(|| VmEvent::ProcEnter(proc_id));
+
+        self.node_id = proc.entry;
+        self.ip = 0;
+
+        let mut res = Ok(());
+
+        while self.call_stack.depth() > 1 {
+            if let Err(err) = self.exec_next() {
+                res = Err(err);
+                break;
+            }
+        }
+
+        // a failed handler is unwound too, so the next events can still be dispatched
+        self.call_stack.clear();
+        self.node_id = node_id;
+        self.ip = ip;
+
+        res
+    }
+
+    // attaches the debugging tables the program has been compiled with
+    // (see `CompileOptions::debug_info`), used for the locations, the stack traces,
+    // the breakpoints and the profiled lines
+    pub fn set_debug_info(&mut self, debug_info: &'cfg DebugInfo) {
+        self.debug_info = Some(debug_info);
+    }
+
+    // the location of the statement about to be executed
+    pub fn current_location(&self) -> Option<Location> {
+        self.debug_info?.location(self.node_id, self.ip)
+    }
+
+    // the frames of the call stack (the innermost frame is last)
+    pub fn stack_trace(&self) -> Vec<StackTraceFrame> {
+        let frames = &self.call_stack.frames;
+
+        frames
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                // a caller frame is returned to at the address on top of its operand stack
+                let addr = match frames.get(i + 1) {
+                    None => Some((self.node_id, self.ip)),
+                    Some(_) if !frame.is_empty() && frame.peek().is_addr() => {
+                        Some(frame.peek().to_addr())
+                    }
+                    Some(_) => None,
+                };
+
+                let location = match (self.debug_info, addr) {
+                    (Some(debug_info), Some((node_id, ip))) => debug_info.location(node_id, ip),
+                    _ => None,
+                };
+
+                let proc_id = frame.ctx_proc;
+                let proc_name = self.env.symbol_table.get_proc_by_id(proc_id).name.clone();
+
+                StackTraceFrame {
+                    proc_id,
+                    proc_name,
+                    location,
+                }
+            })
+            .collect()
+    }
+
+    // pauses the execution (with `InterpreterException::Breakpoint`) before executing
+    // the statements on `line`.
+    // returns `false` when there is no debugging info or no code on `line`
+    pub fn set_breakpoint(&mut self, line: usize) -> bool {
+        let addrs = match self.debug_info {
+            Some(debug_info) => debug_info.line_addrs(line),
+            None => Vec::new(),
+        };
+
+        let found = !addrs.is_empty();
+
+        self.breakpoints.extend(addrs);
+
+        found
+    }
+
+    pub fn clear_breakpoint(&mut self, line: usize) {
+        if let Some(debug_info) = self.debug_info {
+            for addr in debug_info.line_addrs(line) {
+                self.breakpoints.remove(&addr);
+            }
+        }
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    // starts gathering execution statistics (see `ProfileReport`).
+    // profiling is opt-in since it adds some overhead to each executed instruction
+    pub fn enable_profiling(&mut self) {
+        if self.profile.is_none() {
+            let mut profile = ProfileReport::new();
+            profile.record_stack_depth(self.call_stack.depth());
+
+            self.profile = Some(profile);
+        }
+    }
+
+    // the statistics gathered so far, `None` when profiling isn't enabled
+    pub fn profile_report(&self) -> Option<&ProfileReport> {
+        self.profile.as_ref()
+    }
+
+    fn record_host_call(&mut self, started: Option<Instant>) {
+        if let (Some(profile), Some(started)) = (self.profile.as_mut(), started) {
+            profile.record_host_call(started.elapsed());
+        }
+    }
+
+    // registers an observer to be notified of every `VmEvent`
+    pub fn on_event<F>(&mut self, observer: F)
+    where
+        F: FnMut(&VmEvent) + 'static,
+    {
+        if self.observers.is_empty() {
+            self.find_loop_heads();
+        }
+
+        self.observers.push(Box::new(observer));
+    }
+
+    fn find_loop_heads(&mut self) {
+        // each procedure is a separate sub-graph, so we look for loops starting at each procedure entry
+        for entry_id in self.cfg.jmp_table.keys() {
+            let dom_tree = DominatorTree::new(&self.cfg.graph, *entry_id);
+            let loop_info = LoopInfo::new(&self.cfg.graph, &dom_tree);
+
+            self.loop_heads.extend(loop_info.loop_headers());
+        }
+    }
+
+    fn emit<F>(&mut self, build_event: F)
+    where
+        F: FnOnce() -> VmEvent,
+    {
+        // building the event is deferred, so there is no overhead when nobody is listening
+        if self.observers.is_empty() && !cfg!(feature = "tracing") {
+            return;
+        }
+
+        let event = build_event();
+
+        #[cfg(feature = "tracing")]
+        self.proc_spans.on_event(&event, self.env);
+
+        for observer in self.observers.iter_mut() {
+            observer(&event);
+        }
+    }
+
+    // watches the global variable named `var_name`.
+    // each write to it pauses the execution with `InterpreterException::Watchpoint`.
+    // returns `None` when there is no such global variable
+    pub fn watch_global(&mut self, var_name: &str) -> Option<SymbolId> {
+        let symbol_table = &self.env.symbol_table;
+
+        let var_id = self
+            .env
+            .globals_symbols
+            .values()
+            .find(|var_id| symbol_table.get_var_by_id(**var_id).name == var_name)?;
+
+        self.watches.insert(*var_id);
+
+        Some(*var_id)
+    }
+
+    // same as `watch_global`, but for the local variable (or param) `var_name` of procedure `proc_name`
+    pub fn watch_local(&mut self, proc_name: &str, var_name: &str) -> Option<SymbolId> {
+        let symbol_table = &self.env.symbol_table;
+
+        let proc = symbol_table
+            .lookup(0, proc_name, &SymbolKind::Proc)?
+            .as_proc();
+
+        let var_id = self
+            .env
+            .locals_symbols
+            .get(&proc.id)?
+            .iter()
+            .find(|var_id| symbol_table.get_var_by_id(**var_id).name == var_name)?;
+
+        self.watches.insert(*var_id);
+
+        Some(*var_id)
+    }
+
+    pub fn unwatch(&mut self, var_id: SymbolId) {
+        self.watches.remove(&var_id);
+    }
+
+    pub fn snapshot(&self) -> VmSnapshot {
+        VmSnapshot {
+            node_id: self.node_id,
+            ip: self.ip,
+            memory: self.memory.clone(),
+            call_stack: self.call_stack.clone(),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: VmSnapshot) {
+        self.node_id = snapshot.node_id;
+        self.ip = snapshot.ip;
+        self.memory = snapshot.memory;
+        self.call_stack = snapshot.call_stack;
+    }
+
+    // evaluates `source` as an expression against the current VM state.
+    // while the execution is paused inside a procedure, its locals are visible too (shadowing the globals)
+    pub fn eval_expr(&self, source: &str) -> Result<MemoryValue, EvalError> {
+        let expr = TytleParser
+            .parse_expr_code(source)
+            .map_err(EvalError::Parse)?;
+
+        let evaluator = ExprEvaluator::new(self.env, &self.memory, &self.call_stack)
+            .with_overflow_policy(self.overflow);
+        evaluator.eval(&expr)
+    }
+
+    pub fn exec_next(&mut self) -> Result<bool, InterpreterException> {
+        match self.step()? {
+            Step::Continue => Ok(false),
+            Step::Completed => Ok(true),
+            Step::Host(call) => {
+                self.dispatch_host_call(&call)?;
+                Ok(false)
+            }
+            Step::Retrieve(key) => {
+                self.exec_kv_get(&key)?;
+                Ok(false)
+            }
+        }
+    }
+
+    fn exec_kv_get(&mut self, key: &str) -> InterpreterResult {
+        let host = self.host.as_ref().ok_or(InterpreterException::NoHost)?;
+
+        let value = host.kv_get(key);
+        self.push_retrieved(value)
+    }
+
+    // a missing value is retrieved as an empty string
+    fn push_retrieved(&mut self, value: Option<String>) -> InterpreterResult {
+        let value = value.unwrap_or_default();
+
+        self.push_allocated(CallStackItem::Str(value))
+    }
+
+    fn dispatch_host_call(&mut self, call: &HostCall) -> InterpreterResult {
+        let host = self.host.as_mut().ok_or(InterpreterException::NoHost)?;
+
+        let started = self.profile.as_ref().map(|_| Instant::now());
+
+        call.dispatch(&mut **host);
+
+        self.record_host_call(started);
+
+        Ok(())
+    }
+
+    fn step(&mut self) -> Result<Step, InterpreterException> {
+        match self.exec_step() {
+            // runtime errors (including a `THROW` of a tag which isn't caught) are thrown as `ERROR`
+            Err(err) if err.is_catchable() && self.unwind(ERROR_TAG) => Ok(Step::Continue),
+            res => res,
+        }
+    }
+
+    fn exec_step(&mut self) -> Result<Step, InterpreterException> {
+        let node = self.cfg.graph.get_node(self.node_id);
+
+        let inst = node.insts.get(self.ip);
+
+        if inst.is_none() {
+            if node.has_outgoing_edges() {
+                self.choose_outgoing_edge();
+                return Ok(Step::Continue);
+            } else {
+                // we've completed program execution
+                return Ok(Step::Completed);
+            }
+        }
+
+        let inst = inst.unwrap();
+
+        let addr = (self.node_id, self.ip);
+        let resumed = self.paused_at.take() == Some(addr);
+
+        if !resumed && self.breakpoints.contains(&addr) {
+            self.paused_at = Some(addr);
+
+            let location = self.current_location().unwrap_or_default();
+            return Err(InterpreterException::Breakpoint(location));
+        }
+
+        if let Some((capability, feature)) = Capability::required_by(inst) {
+            self.check_capability(capability, feature)?;
+        }
+
+        // the line is looked up only when profiling
+        let location = self.profile.as_ref().and_then(|_| self.current_location());
+
+        if let Some(profile) = self.profile.as_mut() {
+            let proc_id = self.call_stack.current_frame().ctx_proc;
+            profile.record_inst(self.node_id, proc_id);
+
+            if let Some(location) = location {
+                profile.record_line(location.line());
+            }
+        }
+
+        let mut is_call = false;
+        let mut host_call = None;
+        let mut watch_hit = None;
+        let mut retrieve = None;
+
+        match inst {
+            CfgInstruction::Trap => host_call = Some(self.exec_trap()),
+            CfgInstruction::Print => host_call = Some(self.exec_print()),
+            CfgInstruction::Label => host_call = Some(self.exec_label()),
+            CfgInstruction::SetLabelFont => {
+                self.memory.label_style.font = self.call_stack.pop_item().to_str()
+            }
+            CfgInstruction::SetLabelSize => {
+                self.memory.label_style.size = self.call_stack.pop_item().to_int()
+            }
+            CfgInstruction::SetShape => host_call = Some(self.exec_set_shape()?),
+            CfgInstruction::SetPenColor => host_call = Some(self.exec_set_pen_color()?),
+            CfgInstruction::Tone => host_call = Some(self.exec_tone()),
+            CfgInstruction::OpenWrite => host_call = Some(self.exec_open_write()),
+            CfgInstruction::SetWrite => self.exec_set_write()?,
+            CfgInstruction::Close => host_call = Some(self.exec_close()?),
+            CfgInstruction::EOC => {
+                // reached `EOC` (END-OF-CODE)
+                // unwinding the last stackframe
+                self.call_stack.close_stackframe();
+
+                return Ok(Step::Completed);
+            }
+            CfgInstruction::Call(ref node_id) => {
+                is_call = true;
+                self.exec_call(*node_id)?;
+            }
+            CfgInstruction::NativeCall(proc_id) => self.exec_native_call(*proc_id)?,
+            CfgInstruction::Primitive(Primitive::Store) => host_call = Some(self.exec_kv_set()),
+            CfgInstruction::Primitive(Primitive::Retrieve) => {
+                retrieve = Some(self.call_stack.pop_item().to_str())
+            }
+            CfgInstruction::Primitive(prim) => self.exec_primitive(*prim)?,
+            CfgInstruction::Command(Command::PushState) => self.exec_push_state()?,
+            CfgInstruction::Command(Command::PopState) => host_call = Some(self.exec_pop_state()?),
+            CfgInstruction::Command(ref cmd) => host_call = Some(self.exec_cmd(cmd)),
+            CfgInstruction::Direction(ref direct) => host_call = Some(self.exec_direct(direct)),
+            CfgInstruction::Bool(v) => self.exec_bool(*v),
+            CfgInstruction::Int(v) => self.exec_int(*v),
+            CfgInstruction::Return if self.call_stack.depth() == 1 => {
+                // a `HALT` of `__main__` (there's no caller to return to)
+                self.call_stack.close_stackframe();
+
+                return Ok(Step::Completed);
+            }
+            CfgInstruction::Return => host_call = self.exec_ret(),
+            CfgInstruction::Not => self.exec_not(),
+            CfgInstruction::Add | CfgInstruction::Mul | CfgInstruction::Div => {
+                self.exec_int_binary(inst.clone())?
+            }
+            CfgInstruction::Or | CfgInstruction::And => self.exec_bool_binary(inst.clone()),
+            CfgInstruction::GreaterThan
+            | CfgInstruction::LessThan
+            | CfgInstruction::Equal
+            | CfgInstruction::NotEqual => self.exec_cmp(inst.clone())?,
+            CfgInstruction::Load(var_id) => self.exec_load(*var_id)?,
+            CfgInstruction::Store(var_id) => watch_hit = self.exec_store(*var_id)?,
+            CfgInstruction::Dup => self.exec_dup(),
+            CfgInstruction::Pop => {
+                self.call_stack.pop_item();
+            }
+            CfgInstruction::Str(v) => self.exec_str(v.clone()),
+            CfgInstruction::MakeList(n) => self.exec_make_list(*n)?,
+            CfgInstruction::ListLen => self.exec_list_len(),
+            CfgInstruction::ListItem => self.exec_list_item()?,
+            CfgInstruction::ListPush => self.exec_list_push()?,
+            CfgInstruction::ProcRef(proc_id) => {
+                self.call_stack.push_item(CallStackItem::ProcRef(*proc_id))
+            }
+            CfgInstruction::Apply(nargs) => {
+                let (called, applied_call) = self.exec_apply(*nargs)?;
+
+                is_call = called;
+                host_call = applied_call;
+            }
+            CfgInstruction::Block(proc_id) => {
+                let frame_id = self.call_stack.vars_frame_id();
+                self.call_stack.push_item(CallStackItem::Block(*proc_id, frame_id))
+            }
+            CfgInstruction::Run => {
+                is_call = true;
+                self.exec_run()?;
+            }
+            CfgInstruction::Catch(tag, node_id) => {
+                let marker = CallStackItem::Catch(tag.clone(), *node_id);
+                self.call_stack.push_item(marker)
+            }
+            CfgInstruction::EndCatch => {
+                self.call_stack.pop_item();
+            }
+            CfgInstruction::Trace(proc_id) => {
+                self.traces.insert(*proc_id);
+            }
+            CfgInstruction::Untrace(proc_id) => {
+                self.traces.remove(proc_id);
+            }
+            CfgInstruction::Assert {
+                message,
+                line,
+                column,
+            } => self.exec_assert(message, Location(*line, *column))?,
+            CfgInstruction::Throw(tag) => {
+                self.exec_throw(tag)?;
+
+                // the execution resumes at the start of the `CATCH` resume node
+                return Ok(Step::Continue);
+            }
+        };
+
+        if is_call == false {
+            self.ip += 1;
+        }
+
+        if is_call {
+            host_call = self.trace_enter();
+        }
+
+        if let Some(hit) = watch_hit {
+            return Err(InterpreterException::Watchpoint(hit));
+        }
+
+        if let Some(key) = retrieve {
+            return Ok(Step::Retrieve(key));
+        }
+
+        match host_call {
+            Some(call) => Ok(Step::Host(call)),
+            None => Ok(Step::Continue),
+        }
+    }
+
+    fn exec_load(&mut self, var_id: SymbolId) -> InterpreterResult {
+        let var = self.env.symbol_table.get_var_by_id(var_id);
+        let index = var.index.unwrap();
+
+        if var.global {
+            // for global variables the rule is: `addr` <=> `global index`
+            let addr = Address(index);
+
+            let value = self.memory.get_global(addr).unwrap().clone();
+
+            self.call_stack.push_item(CallStackItem::from(value));
+        } else {
+            let item = match self.block_var_frame(var)? {
+                Some(frame_index) => self.call_stack.load_frame_item(frame_index, index),
+                None => self.call_stack.load_item(index),
+            };
+
+            let item_clone = item.clone();
+            self.call_stack.push_item(item_clone);
+        }
+
+        Ok(())
+    }
+
+    fn exec_store(&mut self, var_id: SymbolId) -> Result<Option<WatchHit>, InterpreterException> {
+        let var = self.env.symbol_table.get_var_by_id(var_id);
+        let index = var.index.unwrap();
+
+        let frame_index = match var.global {
+            true => None,
+            false => self.block_var_frame(var)?,
+        };
+
+        let stack_value = self.call_stack.pop_item();
+
+        if !var.is_tmp() {
+            let global = var.global;
+
+            self.emit(|| VmEvent::VarWrite {
+                var_id,
+                global,
+                value: MemoryValue::from(&stack_value),
+            });
+        }
+
+        let watched = self.watches.contains(&var_id);
+
+        let old_value = if !watched {
+            None
+        } else if var.global {
+            self.memory.get_global(Address(index)).cloned()
+        } else if let Some(frame_index) = frame_index {
+            let item = self.call_stack.load_frame_item(frame_index, index);
+            Some(MemoryValue::from(item))
+        } else {
+            Some(MemoryValue::from(self.call_stack.load_item(index)))
+        };
+
+        let new_value = MemoryValue::from(&stack_value);
+
+        if var.global {
+            self.memory.set_global(Address(index), new_value.clone());
+        } else if let Some(frame_index) = frame_index {
+            self.call_stack
+                .store_frame_item(frame_index, index, stack_value);
+        } else {
+            self.call_stack.store_item(index, stack_value);
+        }
+
+        let hit = old_value.map(|old_value| WatchHit {
+            var_id,
+            old_value,
+            new_value,
+            node_id: self.node_id,
+            ip: self.ip,
+            call_stack: self.call_stack.frames.iter().map(|f| f.ctx_proc).collect(),
+        });
+
+        Ok(hit)
+    }
+
+    // the index of the frame holding the local `var` when the current frame is of a block,
+    // since a block uses the variables of the frame it's been created within
+    // (except its temporaries). `None` when the variable is of the current frame
+    fn block_var_frame(&self, var: &Variable) -> Result<Option<usize>, InterpreterException> {
+        let captured = match self.call_stack.current_frame().captured {
+            Some(frame_id) if !var.is_tmp() => frame_id,
+            _ => return Ok(None),
+        };
+
+        match self.call_stack.frame_index(captured) {
+            Some(frame_index) => Ok(Some(frame_index)),
+            None => Err(InterpreterException::StaleBlockLocal(var.name.clone())),
+        }
+    }
+
+    fn exec_call(&mut self, callee_id: CfgNodeId) -> InterpreterResult {
+        let old_frame = self.call_stack.current_frame_mut();
+
+        let cfg = self.cfg;
+
+        let proc_id = cfg.jmp_table[&callee_id];
+        let proc = cfg.get_proc(proc_id);
+
+        let mut slots = Vec::with_capacity(proc.slots_count());
+
+        // allocate procedure params by copying the call args from the current (old) stack-frame
+        (1..=proc.args_count).into_iter().for_each(|_| {
+            let param = old_frame.pop();
+
+            slots.push(param);
+        });
+
+        // the args have been pushed in order, so they're popped in reverse
+        slots.reverse();
+
+        // pushing the return address to the top of the old stack-frame
+        // reminder: `self.ip` already point to the next node instruction
+        let ret_addr = CallStackItem::Addr(self.node_id, self.ip);
+        old_frame.push(ret_addr);
+
+        // the new callstack frame will exceed the maximum allowed call-stack depth
+        // so we return an error
+
+        if self.call_stack.depth() >= MAX_STACK_DEPTH {
+            return Err(InterpreterException::StackOverflow);
+        }
+
+        // allocate callee locals (non-params) slots
+        // the first non-local #index is the successor of the last proc-param #index
+        Self::init_proc_locals(proc, &mut slots);
+
+        // callee allocates a new callstack frame
+        self.call_stack.open_stackframe(proc_id, slots);
+
+        if let Some(profile) = self.profile.as_mut() {
+            profile.record_stack_depth(self.call_stack.depth());
+        }
+
+        self.emit(|| VmEvent::ProcEnter(proc_id));
+
+        // pointing the next instruction, to the first instruction of the destination CFG node
+        self.node_id = callee_id;
+        self.ip = 0;
+
+        Ok(())
+    }
+
+    // runs the popped block within a frame of its own (holding only the block temporaries)
+    fn exec_run(&mut self) -> InterpreterResult {
+        let (proc_id, frame_id) = self.call_stack.pop_item().to_block();
+
+        let proc = match self.cfg.procs.get(&proc_id) {
+            Some(proc) => proc,
+            None => return Err(InterpreterException::RunUnassigned),
+        };
+
+        // pushing the return address to the top of the current stack-frame (see `exec_call`)
+        let ret_addr = CallStackItem::Addr(self.node_id, self.ip);
+        self.call_stack.push_item(ret_addr);
+
+        if self.call_stack.depth() >= MAX_STACK_DEPTH {
+            return Err(InterpreterException::StackOverflow);
+        }
+
+        let mut slots = Vec::with_capacity(proc.slots_count());
+        Self::init_proc_locals(proc, &mut slots);
+
+        self.call_stack
+            .open_block_stackframe(proc_id, slots, frame_id);
+
+        if let Some(profile) = self.profile.as_mut() {
+            profile.record_stack_depth(self.call_stack.depth());
+        }
+
+        self.emit(|| VmEvent::ProcEnter(proc_id));
+
+        self.node_id = proc.entry;
+        self.ip = 0;
+
+        Ok(())
+    }
+
+    fn exec_native_call(&mut self, proc_id: SymbolId) -> InterpreterResult {
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+        let native = self.env.get_native(proc_id);
+
+        let nparams = proc.params_types.len();
+
+        // the primitives take machine integers (see `CallStackItem::to_int`)
+        let mut args = (1..=nparams)
+            .map(|_| match self.call_stack.pop_item() {
+                item if item.is_int() => MemoryValue::Int(item.to_int()),
+                item => MemoryValue::from(&item),
+            })
+            .collect::<Vec<MemoryValue>>();
+
+        // the call args have been popped in reverse order
+        args.reverse();
+
+        let host = match self.host.as_mut() {
+            Some(host) => host,
+            None => return Err(InterpreterException::NativeNoHost(proc.name.clone())),
+        };
+
+        let ret_value = native.call(&args, &mut **host);
+
+        match (&proc.return_type, ret_value) {
+            (ExpressionType::Unit, None) => {}
+            (ExpressionType::Int, Some(MemoryValue::Int(v))) => self.exec_int(v),
+            (ExpressionType::Bool, Some(MemoryValue::Bool(v))) => self.exec_bool(v),
+            (ExpressionType::Str, Some(MemoryValue::Str(v))) => self.exec_str(v),
+            (ExpressionType::List(_), Some(MemoryValue::List(items))) => {
+                self.call_stack.push_item(CallStackItem::List(items))
+            }
+            (ExpressionType::Proc, Some(MemoryValue::ProcRef(proc_id))) => {
+                self.call_stack.push_item(CallStackItem::ProcRef(proc_id))
+            }
+            (ret_type, ret_value) => {
+                return Err(InterpreterException::NativeReturnType {
+                    proc_name: proc.name.clone(),
+                    expected: ret_type.clone(),
+                    actual: ret_value.map_or(ExpressionType::Unit, |value| value.value_type()),
+                })
+            }
+        }
+
+        Ok(())
+    }
+
+    fn exec_primitive(&mut self, prim: Primitive) -> InterpreterResult {
+        let nparams = prim.params_types().len();
+
+        let mut args = (1..=nparams)
+            .map(|_| MemoryValue::from(&self.call_stack.pop_item()))
+            .collect::<Vec<MemoryValue>>();
+
+        // the call args have been popped in reverse order
+        args.reverse();
+
+        let (width, height) = self.memory.screen_size;
+        let mouse = self.memory.mouse;
+
+        let value = match prim {
+            Primitive::ScreenWidth => MemoryValue::Int(width as isize),
+            Primitive::ScreenHeight => MemoryValue::Int(height as isize),
+            Primitive::MouseX => MemoryValue::Int(mouse.position.0),
+            Primitive::MouseY => MemoryValue::Int(mouse.position.1),
+            Primitive::ButtonP => MemoryValue::Bool(mouse.pressed),
+            Primitive::PenColor => MemoryValue::Color(self.memory.pen.get_color()),
+            _ => prim.eval(&args)?,
+        };
+
+        self.push_allocated(CallStackItem::from(value))
+    }
+
+    // pushes a newly created list (or string), unless it exceeds the `ResourceLimits`.
+    // the heap size is computed only when it's capped, since it walks the whole memory
+    fn push_allocated(&mut self, item: CallStackItem) -> InterpreterResult {
+        self.memory.check_item(&item)?;
+
+        if self.memory.limits.max_heap.is_some() {
+            let used = self.heap_size() + item.heap_size();
+            self.memory.check_heap(used)?;
+        }
+
+        self.call_stack.push_item(item);
+
+        Ok(())
+    }
+
+    // returns whether a new callstack frame has been opened (i.e a user procedure has been called),
+    // along with the host-call of an applied `STORE`
+    fn exec_apply(
+        &mut self,
+        nargs: usize,
+    ) -> Result<(bool, Option<HostCall>), InterpreterException> {
+        let proc_id = self.call_stack.pop_item().to_proc_ref();
+
+        let proc = match self.env.symbol_table.try_get_proc_by_id(proc_id) {
+            Some(proc) => proc,
+            None => return Err(InterpreterException::ApplyUnassigned),
+        };
+
+        // the args past the other params are collected into the rest param list
+        let rest_args = match proc.rest {
+            true if nargs + 1 < proc.params_types.len() => {
+                return Err(InterpreterException::ApplyTooFewArgs {
+                    proc_name: proc.name.clone(),
+                    at_least: proc.params_types.len() - 1,
+                    actual: nargs,
+                })
+            }
+            true => Some(nargs + 1 - proc.params_types.len()),
+            false if proc.params_types.len() != nargs => {
+                return Err(InterpreterException::ApplyArgsCount {
+                    proc_name: proc.name.clone(),
+                    expected: proc.params_types.len(),
+                    actual: nargs,
+                })
+            }
+            false => None,
+        };
+
+        let args_types = match rest_args {
+            Some(n) => {
+                let (rest_type, params_types) = proc.params_types.split_last().unwrap();
+                let elem_type = rest_type.elem_type().unwrap();
+
+                params_types
+                    .iter()
+                    .chain(core::iter::repeat_n(elem_type, n))
+                    .collect::<Vec<_>>()
+            }
+            None => proc.params_types.iter().collect(),
+        };
+
+        // the args have been pushed in order, so the last arg is at the top of the stack
+        for (i, param_type) in args_types.into_iter().enumerate() {
+            let arg = self.call_stack.peek_item_at(nargs - 1 - i);
+            let arg_type = MemoryValue::from(arg).value_type();
+
+            if !param_type.accepts(&arg_type) {
+                return Err(InterpreterException::ApplyArgType {
+                    proc_name: proc.name.clone(),
+                    index: i + 1,
+                    expected: param_type.clone(),
+                    actual: arg_type,
+                });
+            }
+        }
+
+        let returns_value = proc.return_type != ExpressionType::Unit;
+        let (primitive, native) = (proc.primitive, proc.native);
+
+        if let Some(n) = rest_args {
+            self.exec_make_list(n)?;
+        }
+
+        match primitive {
+            Some(Primitive::Store) => return Ok((false, Some(self.exec_kv_set()))),
+            // the retrieved value would be discarded, so the host isn't queried
+            Some(Primitive::Retrieve) => {
+                self.call_stack.pop_item();
+                return Ok((false, None));
+            }
+            Some(prim) => self.exec_primitive(prim)?,
+            None if native => self.exec_native_call(proc_id)?,
+            None => {
+                // the return value is discarded by `exec_ret`
+                let entry = self.cfg.get_proc(proc_id).entry;
+                self.exec_call(entry)?;
+
+                return Ok((true, None));
+            }
+        }
+
+        // the return value of an applied procedure is discarded
+        if returns_value {
+            self.call_stack.pop_item();
+        }
+
+        Ok((false, None))
+    }
+
+    fn exec_ret(&mut self) -> Option<HostCall> {
+        let current_frame = self.call_stack.current_frame();
+        let current_proc = self.cfg.get_proc(current_frame.ctx_proc);
+
+        let ret_item = match current_proc.returns_value() {
+            false => None,
+            true => Some(self.call_stack.pop_item()),
+        };
+
+        let trace = self.trace_exit(ret_item.as_ref());
+
+        // unwinding the procedure callstack frame
+        self.call_stack.close_stackframe();
 
-pub struct Interpreter<'env, 'cfg, 'host> {
-    pub ip: usize,
-    pub node_id: CfgNodeId,
-    pub memory: Memory,
-    pub call_stack: CallStack,
-    env: &'env Environment,
-    cfg: &'cfg CfgObject,
-    host: &'host mut Host,
-}
+        let proc_id = current_proc.proc_id;
+        self.emit(|| VmEvent::ProcExit(proc_id));
 
-impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
-    pub fn new(cfg: &'cfg CfgObject, env: &'env Environment, host: &'host mut Host) -> Self {
-        // node with `id = 0` is reserved for the `main wrapper`
-        // while node having `id = 1` is reserved for the `main`
+        let ret_addr = self.call_stack.pop_item();
+        let (ret_node_id, ret_ip) = ret_addr.to_addr();
 
-        let main_node_id = cfg.graph.get_entry_node_id();
+        // pointing one instruction after the `call`
+        self.node_id = ret_node_id;
+        self.ip = ret_ip;
 
-        let mut intr = Self {
-            ip: 0,
-            env,
-            host,
-            cfg,
-            memory: Memory::new(),
-            call_stack: CallStack::new(),
-            node_id: main_node_id,
+        // the return value of an applied procedure is discarded (see `exec_apply`)
+        let ret_inst = self.cfg.graph.get_node(ret_node_id).insts.get(ret_ip);
+        let applied = matches!(ret_inst, Some(CfgInstruction::Apply(_)));
+
+        if ret_item.is_some() && !applied {
+            let ret_value = ret_item.unwrap();
+            self.call_stack.push_item(ret_value);
+        }
+
+        trace
+    }
+
+    // the trace line of the call which has just opened the current frame
+    // (when its procedure is traced), e.g `FACTORIAL(1, 5)`. nested calls are indented by their depth
+    fn trace_enter(&self) -> Option<HostCall> {
+        let frame = self.call_stack.current_frame();
+        let proc_id = frame.ctx_proc;
+
+        if !self.traces.contains(&proc_id) {
+            return None;
+        }
+
+        let args_count = self.cfg.get_proc(proc_id).args_count;
+
+        let args = (0..args_count)
+            .map(|index| MemoryValue::from(frame.load(index)).to_string())
+            .collect::<Vec<_>>();
+
+        let line = format!("{}({})", self.trace_prefix(proc_id), args.join(", "));
+
+        Some(HostCall::Trace(line))
+    }
+
+    // the trace line of the return from the current frame (when its procedure is traced),
+    // e.g `FACTORIAL outputs 120` or `DRAW stops` (for a procedure returning no value)
+    fn trace_exit(&self, ret_item: Option<&CallStackItem>) -> Option<HostCall> {
+        let proc_id = self.call_stack.current_frame().ctx_proc;
+
+        if !self.traces.contains(&proc_id) {
+            return None;
+        }
+
+        let prefix = self.trace_prefix(proc_id);
+
+        let line = match ret_item {
+            Some(item) => format!("{} outputs {}", prefix, MemoryValue::from(item)),
+            None => format!("{} stops", prefix),
         };
 
-        intr.init_memory();
-        intr.init_callstack();
+        Some(HostCall::Trace(line))
+    }
 
-        intr
+    // the (indented) name of the procedure of the current frame
+    fn trace_prefix(&self, proc_id: SymbolId) -> String {
+        // the calls made by the main procedure aren't indented
+        let depth = self.call_stack.frames.len().saturating_sub(2);
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+
+        format!("{}{}", "  ".repeat(depth), proc.name)
     }
 
-    pub fn exec_code(&mut self) -> InterpreterResult {
-        loop {
-            let completed = self.exec_next()?;
+    fn exec_throw(&mut self, tag: &str) -> InterpreterResult {
+        if self.unwind(tag) {
+            Ok(())
+        } else {
+            Err(InterpreterException::UncaughtThrow(tag.to_string()))
+        }
+    }
 
-            if completed {
-                assert!(self.call_stack.is_empty());
-                return Ok(());
-            }
+    // unwinds the callstack up to the innermost `CATCH` marker of `tag`,
+    // and resumes the execution right after the `CATCH` block. returns whether there's such a marker
+    fn unwind(&mut self, tag: &str) -> bool {
+        let (depth, index, node_id) = match self.call_stack.find_catch(tag) {
+            Some(catch) => catch,
+            None => return false,
+        };
+
+        while self.call_stack.depth() > depth {
+            let proc_id = self.call_stack.current_frame().ctx_proc;
+            self.call_stack.close_stackframe();
+
+            self.emit(|| VmEvent::ProcExit(proc_id));
         }
+
+        // the marker itself is popped by the `EndCatch` starting the resume node
+        self.call_stack.current_frame_mut().truncate(index + 1);
+
+        self.node_id = node_id;
+        self.ip = 0;
+
+        true
     }
 
-    pub fn exec_next(&mut self) -> Result<bool, InterpreterException> {
-        let node = self.cfg.graph.get_node(self.node_id);
+    fn exec_assert(&mut self, message: &str, location: Location) -> InterpreterResult {
+        if self.call_stack.pop_item().to_bool() {
+            return Ok(());
+        }
 
-        let inst = node.insts.get(self.ip);
+        Err(InterpreterException::AssertionFailed {
+            message: message.to_string(),
+            location,
+            vars: self.vars_snapshot(),
+        })
+    }
 
-        if inst.is_none() {
-            if node.has_outgoing_edges() {
-                self.choose_outgoing_edge();
-                return Ok(false);
-            } else {
-                // we've completed program execution
-                return Ok(true);
+    // the (non-temporary) globals, followed by the current procedure locals, ordered by their index
+    fn vars_snapshot(&self) -> Vec<(String, MemoryValue)> {
+        let symbol_table = &self.env.symbol_table;
+        let mut vars = Vec::new();
+
+        for index in 0..self.env.globals_index {
+            let var = symbol_table.get_var_by_id(self.env.globals_symbols[&index]);
+
+            if let Some(value) = self.memory.get_global(Address(index)) {
+                if !var.is_tmp() {
+                    vars.push((var.name.clone(), value.clone()));
+                }
             }
         }
 
-        let inst = inst.unwrap();
-        let mut is_call = false;
+        let frame = self.call_stack.current_frame();
 
-        match inst {
-            CfgInstruction::Trap => self.exec_trap(),
-            CfgInstruction::Print => self.exec_print(),
-            CfgInstruction::EOC => {
-                // reached `EOC` (END-OF-CODE)
-                // unwinding the last stackframe
-                self.call_stack.close_stackframe();
+        let mut locals = self
+            .env
+            .locals_symbols
+            .get(&frame.ctx_proc)
+            .into_iter()
+            .flatten()
+            .map(|var_id| symbol_table.get_var_by_id(*var_id))
+            .filter(|var| !var.is_tmp())
+            .collect::<Vec<_>>();
 
-                return Ok(true);
-            }
-            CfgInstruction::Call(ref node_id) => {
-                is_call = true;
-                self.exec_call(*node_id)?;
-            }
-            CfgInstruction::Command(ref cmd) => self.exec_cmd(cmd),
-            CfgInstruction::Direction(ref direct) => self.exec_direct(direct),
-            CfgInstruction::Bool(v) => self.exec_bool(*v),
-            CfgInstruction::Int(v) => self.exec_int(*v),
-            CfgInstruction::Return => self.exec_ret(),
-            CfgInstruction::Not => self.exec_not(),
-            CfgInstruction::Add | CfgInstruction::Mul | CfgInstruction::Div => {
-                self.exec_int_binary(inst.clone())
-            }
-            CfgInstruction::Or
-            | CfgInstruction::And
-            | CfgInstruction::GreaterThan
-            | CfgInstruction::LessThan => self.exec_bool_binary(inst.clone()),
-            CfgInstruction::Load(var_id) => self.exec_load(*var_id),
-            CfgInstruction::Store(var_id) => self.exec_store(*var_id),
-            CfgInstruction::Str(_) => unimplemented!(),
-        };
+        locals.sort_by_key(|var| var.index);
 
-        if is_call == false {
-            self.ip += 1;
+        for var in locals {
+            let value = MemoryValue::from(frame.load(var.index.unwrap()));
+            vars.push((var.name.clone(), value));
         }
 
-        Ok(false)
+        vars
     }
 
-    fn exec_load(&mut self, var_id: SymbolId) {
-        let var = self.env.symbol_table.get_var_by_id(var_id);
-        let index = var.index.unwrap();
+    fn exec_trap(&mut self) -> HostCall {
+        HostCall::Trap(self.node_id, self.ip)
+    }
 
-        if var.global {
-            // for global variables the rule is: `addr` <=> `global index`
-            let addr = Address(index);
+    fn exec_print(&mut self) -> HostCall {
+        let item = self.call_stack.pop_item();
 
-            let value = self.memory.get_global(addr).unwrap();
+        if let Some(stream) = self.memory.write_stream.clone() {
+            let text = MemoryValue::from(&item).to_text();
+            return HostCall::WriteStream { stream, text };
+        }
 
-            match value {
-                MemoryValue::Int(v) => self.exec_int(*v),
-                MemoryValue::Bool(v) => self.exec_bool(*v),
-                MemoryValue::Str(_) => unimplemented!(),
-            };
-        } else {
-            let item = self.call_stack.load_item(index);
-            let item_clone = item.clone();
-            self.call_stack.push_item(item_clone);
+        match item {
+            CallStackItem::Int(v) => HostCall::Print(v),
+            #[cfg(feature = "bigint")]
+            CallStackItem::BigInt(v) => HostCall::PrintBig(v),
+            item => HostCall::PrintText(MemoryValue::from(&item).to_text()),
         }
     }
 
-    fn exec_store(&mut self, var_id: SymbolId) {
-        let var = self.env.symbol_table.get_var_by_id(var_id);
-        let index = var.index.unwrap();
+    fn exec_label(&mut self) -> HostCall {
+        let text = self.call_stack.pop_item().to_str();
+        let turtle = &self.memory.turtle;
 
-        let stack_value = self.call_stack.pop_item();
+        HostCall::Label(Label {
+            text,
+            position: (turtle.xcor(), turtle.ycor()),
+            heading: turtle.heading(),
+            style: self.memory.label_style.clone(),
+        })
+    }
 
-        if var.global {
-            let mem_value = match stack_value {
-                CallStackItem::Int(v) => MemoryValue::Int(v),
-                CallStackItem::Bool(v) => MemoryValue::Bool(v),
-                _ => unimplemented!(),
-            };
+    fn exec_set_shape(&mut self) -> Result<HostCall, InterpreterException> {
+        let name = self.call_stack.pop_item().to_str();
 
-            self.memory.set_global(Address(index), mem_value);
-        } else {
-            self.call_stack.store_item(index, stack_value);
+        let shape = match TurtleShape::parse(&name) {
+            Some(TurtleShape::Image(ref image)) if !self.shapes.contains(image) => None,
+            shape => shape,
+        };
+
+        match shape {
+            Some(shape) => {
+                self.memory.turtle.set_shape(shape.clone());
+                Ok(HostCall::ShapeChanged(shape))
+            }
+            None => Err(InterpreterException::UnknownShape(name)),
         }
     }
 
-    fn exec_call(&mut self, callee_id: CfgNodeId) -> InterpreterResult {
-        let old_frame = self.call_stack.current_frame_mut();
+    fn exec_set_pen_color(&mut self) -> Result<HostCall, InterpreterException> {
+        let color = match self.call_stack.pop_item() {
+            CallStackItem::Color(color) => Ok(color),
+            CallStackItem::Int(index) => Color::palette(index).ok_or_else(|| index.to_string()),
+            CallStackItem::Str(name) => Color::named(&name).ok_or(name),
+            _ => panic!("expected a color"),
+        };
 
-        let proc_id = self.cfg.jmp_table[&callee_id];
-        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+        match color {
+            Ok(color) => {
+                self.memory.pen.set_color(color);
+                Ok(HostCall::PenColor(color))
+            }
+            Err(color) => Err(InterpreterException::UnknownColor(color)),
+        }
+    }
 
-        let mut params = Vec::new();
-        let nparams = proc.params_types.len();
+    // the value is kept as printed by `PRINT` (see `MemoryValue::to_text`)
+    fn exec_kv_set(&mut self) -> HostCall {
+        let value = MemoryValue::from(&self.call_stack.pop_item()).to_text();
+        let key = self.call_stack.pop_item().to_str();
 
-        // allocate procedure params by copying the call args from the current (old) stack-frame
-        (1..=nparams).into_iter().for_each(|_| {
-            let param = old_frame.pop();
+        HostCall::KvSet { key, value }
+    }
 
-            params.push(param);
-        });
+    fn exec_open_write(&mut self) -> HostCall {
+        let name = self.call_stack.pop_item().to_str();
+        self.memory.streams.insert(name.clone());
 
-        // pushing the return address to the top of the old stack-frame
-        // reminder: `self.ip` already point to the next node instruction
-        let ret_addr = CallStackItem::Addr(self.node_id, self.ip);
-        old_frame.push(ret_addr);
+        HostCall::OpenStream(name)
+    }
 
-        // the new callstack frame will exceed the maximum allowed call-stack depth
-        // so we return an error
+    // `SETWRITE "` writes to the console again
+    fn exec_set_write(&mut self) -> InterpreterResult {
+        let name = self.call_stack.pop_item().to_str();
 
-        if self.call_stack.depth() >= MAX_STACK_DEPTH {
-            return Err(InterpreterException::StackOverflow);
+        if name.is_empty() {
+            self.memory.write_stream = None;
+            return Ok(());
         }
 
-        // callee allocates a new callstack frame
-        let new_frame = self.call_stack.open_stackframe(proc_id);
+        if !self.memory.streams.contains(&name) {
+            return Err(InterpreterException::StreamNotOpen(name));
+        }
+
+        self.memory.write_stream = Some(name);
+        Ok(())
+    }
 
-        for param in params.iter().rev() {
-            new_frame.push(param.clone());
+    fn exec_close(&mut self) -> Result<HostCall, InterpreterException> {
+        let name = self.call_stack.pop_item().to_str();
+
+        if !self.memory.streams.remove(&name) {
+            return Err(InterpreterException::StreamNotOpen(name));
         }
 
-        // allocate callee locals (non-params) on the new callstack frame
-        // the first non-local #index is the successor of the last proc-param #index
-        self.init_proc_locals(proc_id);
+        if self.memory.write_stream.as_ref() == Some(&name) {
+            self.memory.write_stream = None;
+        }
 
-        // pointing the next instruction, to the first instruction of the destination CFG node
-        self.node_id = callee_id;
-        self.ip = 0;
+        Ok(HostCall::CloseStream(name))
+    }
 
-        Ok(())
+    // a negative frequency (or duration) is played as `0`
+    fn exec_tone(&mut self) -> HostCall {
+        let ms = self.call_stack.pop_item().to_int().max(0) as usize;
+        let freq = self.call_stack.pop_item().to_int().max(0) as usize;
+
+        HostCall::Tone { freq, ms }
     }
 
-    fn exec_ret(&mut self) {
-        let current_frame = self.call_stack.current_frame();
-        let current_proc = self.env.symbol_table.get_proc_by_id(current_frame.ctx_proc);
+    fn exec_push_state(&mut self) -> InterpreterResult {
+        if self.memory.states.len() >= MAX_STATE_DEPTH {
+            return Err(InterpreterException::StateStackOverflow);
+        }
 
-        let ret_item = match current_proc.return_type {
-            ExpressionType::Unit => None,
-            _ => Some(self.call_stack.pop_item()),
-        };
+        let state = (self.memory.turtle.clone(), self.memory.pen.clone());
+        self.memory.states.push(state);
 
-        // unwinding the procedure callstack frame
-        self.call_stack.close_stackframe();
+        Ok(())
+    }
 
-        let ret_addr = self.call_stack.pop_item();
-        let (ret_node_id, ret_ip) = ret_addr.to_addr();
+    fn exec_pop_state(&mut self) -> Result<HostCall, InterpreterException> {
+        let (turtle, pen) = self
+            .memory
+            .states
+            .pop()
+            .ok_or(InterpreterException::StateStackEmpty)?;
 
-        // pointing one instruction after the `call`
-        self.node_id = ret_node_id;
-        self.ip = ret_ip;
+        self.memory.turtle = turtle.clone();
+        self.memory.pen = pen.clone();
 
-        if ret_item.is_some() {
-            let ret_value = ret_item.unwrap();
-            self.call_stack.push_item(ret_value);
-        }
+        Ok(HostCall::StateRestored(turtle, pen))
     }
 
-    fn exec_trap(&mut self) {
-        self.host.exec_trap(self.node_id, self.ip);
+    fn exec_cmd(&mut self, cmd: &Command) -> HostCall {
+        // keeping track of the turtle & pen state, so it'll be part of the VM snapshots
+        match cmd {
+            Command::PenUp => self.memory.pen.up(),
+            Command::PenDown => self.memory.pen.down(),
+            Command::PenErase => self.memory.pen.erase(),
+            Command::ShowTurtle => self.memory.turtle.show(),
+            Command::HideTurtle => self.memory.turtle.hide(),
+            Command::Beep => {
+                return HostCall::Tone {
+                    freq: BEEP_FREQ,
+                    ms: BEEP_DURATION,
+                }
+            }
+            Command::Stamp => {
+                let turtle = &self.memory.turtle;
+
+                return HostCall::Stamp {
+                    position: (turtle.xcor(), turtle.ycor()),
+                    heading: turtle.heading(),
+                };
+            }
+            _ => {}
+        }
+
+        HostCall::Cmd(cmd.clone())
     }
 
-    fn exec_print(&mut self) {
-        let value = self.call_stack.pop_item().to_int();
-        self.host.exec_print(value);
+    fn exec_direct(&mut self, direct: &Direction) -> HostCall {
+        let count = self.call_stack.pop_item().to_int();
+
+        // the prelude procedures always turn (in degrees),
+        // so their shapes close whatever the turtle config
+        if self.in_prelude_proc() {
+            let degrees = match direct {
+                Direction::Right => Some(count as f64),
+                Direction::Left => Some(-count as f64),
+                _ => None,
+            };
+
+            if let Some(degrees) = degrees {
+                self.memory.turtle.turn_degrees(degrees);
+                return HostCall::Turn(degrees);
+            }
+        }
+
+        let from = self.turtle_pose();
+        self.memory.turtle.exec_direct(direct, count);
+
+        let direct = *direct;
+        let position = (self.memory.turtle.xcor(), self.memory.turtle.ycor());
+        self.emit(|| VmEvent::TurtleMove {
+            direct,
+            count,
+            position,
+        });
+
+        let frames = match self.movement_frames {
+            Some(frames) => frames,
+            None => return HostCall::Direct(direct, count),
+        };
+
+        // the heading is tweened by the turn itself, so a `RIGHT 360` makes a whole turn
+        let turn = match (direct, self.memory.turtle.config().steering) {
+            (Direction::Right, Steering::Turn) => count as f64,
+            (Direction::Left, Steering::Turn) => -count as f64,
+            _ => 0.0,
+        };
+
+        let to = TurtlePose {
+            position: (position.0 as f64, position.1 as f64),
+            heading: from.heading + turn,
+        };
+
+        HostCall::Movement(Movement {
+            direct,
+            count,
+            from,
+            to,
+            frames,
+        })
     }
 
-    fn exec_cmd(&mut self, cmd: &Command) {
-        self.host.exec_cmd(cmd);
+    fn turtle_pose(&self) -> TurtlePose {
+        let turtle = &self.memory.turtle;
+
+        TurtlePose {
+            position: (turtle.xcor() as f64, turtle.ycor() as f64),
+            heading: turtle.heading(),
+        }
     }
 
-    fn exec_direct(&mut self, direct: &Direction) {
-        let count = self.call_stack.pop_item().to_int();
+    // whether the executed code is of a stdlib prelude procedure
+    // (see `Environment::is_prelude_proc`)
+    fn in_prelude_proc(&self) -> bool {
+        let proc_id = self.call_stack.current_frame().ctx_proc;
 
-        self.host.exec_direct(direct, count)
+        self.env.is_prelude_proc(proc_id)
     }
 
-    fn exec_int_binary(&mut self, op: CfgInstruction) {
+    fn exec_int_binary(&mut self, op: CfgInstruction) -> Result<(), InterpreterException> {
         let a = self.call_stack.pop_item();
         let b = self.call_stack.pop_item();
 
         assert!(a.is_int() && b.is_int());
 
-        let a = a.to_int();
-        let b = b.to_int();
-
-        match op {
-            CfgInstruction::Add => self.exec_int(a + b),
-            CfgInstruction::Mul => self.exec_int(a * b),
-            CfgInstruction::Div => self.exec_int(b / a),
+        let bin_op = match op {
+            CfgInstruction::Add => BinaryOp::Add,
+            CfgInstruction::Mul => BinaryOp::Mul,
+            CfgInstruction::Div => BinaryOp::Div,
             _ => panic!("invalid binary-op: `{:?}`", op),
+        };
+
+        // `b` is the left operand (it's pushed first)
+        let (lhs, rhs) = (MemoryValue::from(&b), MemoryValue::from(&a));
+
+        if bin_op == BinaryOp::Div && rhs == MemoryValue::Int(0) {
+            return Err(InterpreterException::DivisionByZero);
         }
+
+        let v = self.overflow.eval(&bin_op, &lhs, &rhs).ok_or_else(|| {
+            InterpreterException::IntegerOverflow {
+                op: bin_op.to_string(),
+                lhs: b.to_int(),
+                rhs: a.to_int(),
+            }
+        })?;
+
+        self.call_stack.push_item(CallStackItem::from(v));
+
+        Ok(())
+    }
+
+    fn exec_dup(&mut self) {
+        let item = self.call_stack.peek_item().clone();
+
+        self.call_stack.push_item(item);
     }
 
     fn exec_not(&mut self) {
@@ -275,12 +1813,79 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
         match op {
             CfgInstruction::And => self.exec_bool(a.to_bool() && b.to_bool()),
             CfgInstruction::Or => self.exec_bool(a.to_bool() || b.to_bool()),
-            CfgInstruction::GreaterThan => self.exec_bool(b.to_int() > a.to_int()),
-            CfgInstruction::LessThan => self.exec_bool(b.to_int() < a.to_int()),
             _ => panic!("invalid binary-op: `{:?}`", op),
         }
     }
 
+    fn exec_cmp(&mut self, op: CfgInstruction) -> InterpreterResult {
+        let a = MemoryValue::from(&self.call_stack.pop_item());
+        let b = MemoryValue::from(&self.call_stack.pop_item());
+
+        // the operands are type-checked at compile time, but an imported (JSON IR) program isn't
+        let res = match op {
+            CfgInstruction::Equal => b.equals(&a),
+            CfgInstruction::NotEqual => b.equals(&a).map(|eq| !eq),
+            CfgInstruction::GreaterThan => b.compare(&a).map(|ord| ord.is_gt()),
+            CfgInstruction::LessThan => b.compare(&a).map(|ord| ord.is_lt()),
+            _ => panic!("invalid comparison-op: `{:?}`", op),
+        };
+
+        let v = res.map_err(InterpreterException::Incomparable)?;
+        self.exec_bool(v);
+
+        Ok(())
+    }
+
+    fn exec_make_list(&mut self, n: usize) -> InterpreterResult {
+        let mut items = (1..=n)
+            .map(|_| MemoryValue::from(&self.call_stack.pop_item()))
+            .collect::<Vec<MemoryValue>>();
+
+        // the items have been popped in reverse order
+        items.reverse();
+
+        self.push_allocated(CallStackItem::List(items))
+    }
+
+    fn exec_list_len(&mut self) {
+        let items = self.call_stack.pop_item().to_list();
+
+        self.exec_int(items.len() as isize);
+    }
+
+    fn exec_list_item(&mut self) -> InterpreterResult {
+        let index = self.call_stack.pop_item().to_int();
+        let items = self.call_stack.pop_item().to_list();
+
+        // the index is computed by the compiler, but an imported (JSON IR) program isn't checked
+        let item = match index {
+            i if i >= 0 => items.get(i as usize),
+            _ => None,
+        };
+
+        match item {
+            Some(item) => {
+                let item = CallStackItem::from(item.clone());
+                self.call_stack.push_item(item);
+
+                Ok(())
+            }
+            None => Err(InterpreterException::ItemOutOfRange {
+                index: index + 1,
+                count: items.len(),
+            }),
+        }
+    }
+
+    fn exec_list_push(&mut self) -> InterpreterResult {
+        let mut items = self.call_stack.pop_item().to_list();
+        let item = MemoryValue::from(&self.call_stack.pop_item());
+
+        items.push(item);
+
+        self.push_allocated(CallStackItem::List(items))
+    }
+
     fn exec_bool(&mut self, v: bool) {
         self.call_stack.push_item(CallStackItem::Bool(v));
     }
@@ -289,6 +1894,10 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
         self.call_stack.push_item(CallStackItem::Int(v));
     }
 
+    fn exec_str(&mut self, v: String) {
+        self.call_stack.push_item(CallStackItem::Str(v));
+    }
+
     fn init_memory(&mut self) {
         self.memory.init_globals(self.env);
     }
@@ -298,11 +1907,13 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
 
         let main_proc_id = self.cfg.jmp_table[&self.node_id];
 
-        self.call_stack.open_stackframe(main_proc_id);
-
         // allocate `__main__` locals
-        let main_proc = self.env.symbol_table.get_proc_by_name("__main__");
-        self.init_proc_locals(main_proc.id);
+        let main_proc = self.cfg.get_proc(main_proc_id);
+        let mut slots = Vec::with_capacity(main_proc.slots_count());
+
+        Self::init_proc_locals(main_proc, &mut slots);
+
+        self.call_stack.open_stackframe(main_proc_id, slots);
     }
 
     fn choose_outgoing_edge(&mut self) {
@@ -343,36 +1954,30 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
             self.call_stack.pop_item();
         }
 
+        if self.loop_heads.contains(&edge.node_id) {
+            let node_id = edge.node_id;
+            self.emit(|| VmEvent::LoopIteration(node_id));
+        }
+
         self.node_id = edge.node_id;
         self.ip = 0;
     }
 
-    fn init_proc_locals(&mut self, proc_id: SymbolId) {
-        let proc_locals = self.env.locals_symbols.get(&proc_id);
-
-        if proc_locals.is_none() {
-            // procedure has no locals (also no params which are treated as locals)
-            return;
-        }
-
-        let proc_locals = proc_locals.unwrap();
-
-        for var_id in proc_locals {
-            let var = self.env.symbol_table.get_var_by_id(*var_id);
-
-            // we skip the params since they are allocated as part of the calling-convention
-            if var.is_param() {
-                continue;
-            }
-
-            let var_type = var.var_type.as_ref().unwrap();
-
-            match var_type {
-                ExpressionType::Int => self.exec_int(-1),
-                ExpressionType::Bool => self.exec_bool(false),
-                ExpressionType::Str => unimplemented!(),
+    fn init_proc_locals(proc: &CfgProc, slots: &mut Vec<CallStackItem>) {
+        // the params aren't included since they are allocated as part of the calling-convention
+        for var_type in proc.locals.iter() {
+            let item = match var_type {
+                ExpressionType::Int => CallStackItem::Int(-1),
+                ExpressionType::Bool => CallStackItem::Bool(false),
+                ExpressionType::Str => CallStackItem::Str(String::new()),
+                ExpressionType::List(_) => CallStackItem::List(Vec::new()),
+                ExpressionType::Proc => CallStackItem::from(MemoryValue::UNASSIGNED_PROC),
+                ExpressionType::Block => CallStackItem::from(MemoryValue::UNASSIGNED_BLOCK),
+                ExpressionType::Color => CallStackItem::Color(Color::default()),
                 ExpressionType::Unit => panic!("proc can't have a local of type `Unit`"),
-            }
+            };
+
+            slots.push(item);
         }
     }
 }
@@ -0,0 +1,31 @@
+use crate::compat::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_LABEL_FONT: &str = "sans-serif";
+pub const DEFAULT_LABEL_SIZE: isize = 12;
+
+// the font of the labels, set by `SETLABELFONT` and `SETLABELSIZE`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelStyle {
+    pub font: String,
+    pub size: isize,
+}
+
+impl Default for LabelStyle {
+    fn default() -> Self {
+        Self {
+            font: DEFAULT_LABEL_FONT.to_string(),
+            size: DEFAULT_LABEL_SIZE,
+        }
+    }
+}
+
+// a text drawn by `LABEL` at the turtle position, along the turtle heading
+// (in the `AngleUnit` of the turtle config)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Label {
+    pub text: String,
+    pub position: (isize, isize),
+    pub heading: f64,
+    pub style: LabelStyle,
+}
@@ -1,48 +1,141 @@
 use crate::ast::expression::ExpressionType;
 use crate::ast::semantic::Environment;
-use crate::vm::{Address, MemoryValue, Pen, Turtle};
+use crate::compat::prelude::*;
+use crate::vm::{
+    Address, CallStackItem, Color, InterpreterException, LabelStyle, MemoryValue, MouseState, Pen,
+    Resource, ResourceLimits, Turtle, DEFAULT_SCREEN_SIZE,
+};
 
-use std::collections::HashMap;
+use crate::compat::collections::BTreeSet;
+use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Memory {
     pub turtle: Turtle,
     pub pen: Pen,
-    pub cells: HashMap<Address, MemoryValue>,
+
+    #[serde(default)]
+    pub label_style: LabelStyle,
+
+    // the `(width, height)` of the host screen (see `Host::screen_size`)
+    #[serde(default = "default_screen_size")]
+    pub screen_size: (usize, usize),
+
+    #[serde(default)]
+    pub mouse: MouseState,
+
+    // the turtle & pen states saved by `PUSHSTATE` (the last saved is last)
+    #[serde(default)]
+    pub states: Vec<(Turtle, Pen)>,
+
+    // the host streams opened by `OPENWRITE` (and not closed yet)
+    #[serde(default)]
+    pub streams: BTreeSet<String>,
+
+    // the open stream `PRINT` writes to (see `SETWRITE`), the console when `None`
+    #[serde(default)]
+    pub write_stream: Option<String>,
+
+    // the globals values, indexed by their address (the global `Variable::index`)
+    pub cells: Vec<MemoryValue>,
+
+    #[serde(default)]
+    pub limits: ResourceLimits,
 }
 
 impl Memory {
     pub fn new() -> Self {
         Self {
-            cells: HashMap::new(),
+            cells: Vec::new(),
             turtle: Turtle::new(),
             pen: Pen::new(),
+            label_style: LabelStyle::default(),
+            screen_size: DEFAULT_SCREEN_SIZE,
+            mouse: MouseState::default(),
+            states: Vec::new(),
+            streams: BTreeSet::new(),
+            write_stream: None,
+            limits: ResourceLimits::default(),
         }
     }
 
     pub fn init_globals(&mut self, env: &Environment) {
-        (0..env.globals_index).into_iter().for_each(|i| {
-            let var_id = env.globals_symbols[&i];
-            let var = env.symbol_table.get_var_by_id(var_id);
+        self.cells = (0..env.globals_index)
+            .map(|i| {
+                let var_id = env.globals_symbols[&i];
+                let var = env.symbol_table.get_var_by_id(var_id);
 
-            let addr = Address(var.index.unwrap());
-            let var_type = var.var_type.as_ref().unwrap();
+                assert_eq!(Some(i), var.index);
 
-            let value = match var_type {
-                ExpressionType::Int => MemoryValue::Int(0),
-                ExpressionType::Bool => MemoryValue::Bool(false),
-                ExpressionType::Str => MemoryValue::Str("".to_string()),
-                ExpressionType::Unit => panic!("variable can't be of type `Unit`"),
-            };
+                if let Some(value) = env.injected_globals.get(&var_id) {
+                    return value.clone();
+                }
 
-            self.set_global(addr, value);
-        });
+                match var.var_type.as_ref().unwrap() {
+                    ExpressionType::Int => MemoryValue::Int(0),
+                    ExpressionType::Bool => MemoryValue::Bool(false),
+                    ExpressionType::Str => MemoryValue::Str("".to_string()),
+                    ExpressionType::List(_) => MemoryValue::List(Vec::new()),
+                    ExpressionType::Proc => MemoryValue::UNASSIGNED_PROC,
+                    ExpressionType::Block => MemoryValue::UNASSIGNED_BLOCK,
+                    ExpressionType::Color => MemoryValue::Color(Color::default()),
+                    ExpressionType::Unit => panic!("variable can't be of type `Unit`"),
+                }
+            })
+            .collect();
     }
 
     pub fn get_global(&self, address: Address) -> Option<&MemoryValue> {
-        self.cells.get(&address)
+        self.cells.get(address.0)
     }
 
     pub fn set_global(&mut self, address: Address, value: MemoryValue) {
-        self.cells.insert(address, value);
+        self.cells[address.0] = value;
+    }
+
+    pub fn heap_size(&self) -> usize {
+        self.cells.iter().map(MemoryValue::heap_size).sum()
     }
+
+    // a newly created list (or string) must fit the `ResourceLimits` caps.
+    // its items were checked when they were created, so they aren't checked again
+    pub fn check_item(&self, item: &CallStackItem) -> Result<(), InterpreterException> {
+        let (resource, limit, actual) = match item {
+            CallStackItem::List(items) => {
+                (Resource::ListLength, self.limits.max_list_len, items.len())
+            }
+            CallStackItem::Str(v) => (
+                Resource::StringLength,
+                self.limits.max_str_len,
+                v.chars().count(),
+            ),
+            _ => return Ok(()),
+        };
+
+        Self::check_limit(resource, limit, actual)
+    }
+
+    // `used` is the heap size of the whole VM (the globals along with the callstack)
+    pub fn check_heap(&self, used: usize) -> Result<(), InterpreterException> {
+        Self::check_limit(Resource::Heap, self.limits.max_heap, used)
+    }
+
+    fn check_limit(
+        resource: Resource,
+        limit: Option<usize>,
+        actual: usize,
+    ) -> Result<(), InterpreterException> {
+        match limit {
+            Some(limit) if actual > limit => Err(InterpreterException::ResourceLimit {
+                resource,
+                limit,
+                actual,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn default_screen_size() -> (usize, usize) {
+    DEFAULT_SCREEN_SIZE
 }
@@ -1,6 +1,191 @@
-#[derive(Debug, Clone, PartialEq)]
+use crate::ast::expression::ExpressionType;
+use crate::ast::semantic::SymbolId;
+use crate::compat::prelude::*;
+#[cfg(feature = "bigint")]
+use crate::vm::BigInt;
+use crate::vm::{CallStackItem, Color};
+use core::cmp::Ordering;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MemoryValue {
     Int(isize),
     Bool(bool),
     Str(String),
+    List(Vec<MemoryValue>),
+    Color(Color),
+
+    // a procedure reference (see `ExpressionAst::ProcRef`)
+    ProcRef(SymbolId),
+
+    // a block: its procedure and the id of the frame it's been created within
+    // (see `CallStackItem::Block`)
+    Block(SymbolId, usize),
+
+    // an integer which doesn't fit `Int` (see `OverflowPolicy::Promote`)
+    #[cfg(feature = "bigint")]
+    BigInt(BigInt),
+}
+
+// returned when comparing values of incomparable types (e.g `1 = TRUE` or `TRUE < FALSE`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncomparableValues(pub ExpressionType, pub ExpressionType);
+
+impl MemoryValue {
+    // the value of a `PROC` variable before it's assigned, referencing no procedure.
+    // (locals are never read before being assigned, see `DefiniteAssignment`)
+    pub const UNASSIGNED_PROC: MemoryValue = MemoryValue::ProcRef(SymbolId(usize::MAX));
+
+    // the value of a `BLOCK` variable before it's assigned, running no block
+    pub const UNASSIGNED_BLOCK: MemoryValue = MemoryValue::Block(SymbolId(usize::MAX), usize::MAX);
+
+    pub fn value_type(&self) -> ExpressionType {
+        match self {
+            MemoryValue::Int(_) => ExpressionType::Int,
+            MemoryValue::Bool(_) => ExpressionType::Bool,
+            MemoryValue::Str(_) => ExpressionType::Str,
+            MemoryValue::List(items) => {
+                // list literals are never empty (see `AstWalkError::EmptyList`),
+                // but a list variable default is, and its elements type is unknown at runtime
+                let elem_type = items
+                    .first()
+                    .map_or(ExpressionType::Unit, |v| v.value_type());
+
+                ExpressionType::list_of(elem_type)
+            }
+            MemoryValue::Color(_) => ExpressionType::Color,
+            MemoryValue::ProcRef(_) => ExpressionType::Proc,
+            MemoryValue::Block(..) => ExpressionType::Block,
+            #[cfg(feature = "bigint")]
+            MemoryValue::BigInt(_) => ExpressionType::Int,
+        }
+    }
+
+    // the approximate number of bytes taken by the value (see `ResourceLimits::max_heap`)
+    pub fn heap_size(&self) -> usize {
+        let size = core::mem::size_of::<MemoryValue>();
+
+        match self {
+            MemoryValue::Str(v) => size + v.len(),
+            MemoryValue::List(items) => {
+                size + items.iter().map(MemoryValue::heap_size).sum::<usize>()
+            }
+            #[cfg(feature = "bigint")]
+            MemoryValue::BigInt(v) => size + (v.bits() / 8) as usize,
+            _ => size,
+        }
+    }
+
+    // the value as printed by `PRINT`, i.e a string without its quote
+    // (the other values as written in code, see `Display`)
+    pub fn to_text(&self) -> String {
+        match self {
+            MemoryValue::Str(v) => v.clone(),
+            _ => self.to_string(),
+        }
+    }
+
+    // the `=` / `<>` semantics (see `ExpressionType::is_equatable`)
+    pub fn equals(&self, other: &MemoryValue) -> Result<bool, IncomparableValues> {
+        match (self, other) {
+            // the items type of an empty list is unknown at runtime (see `value_type`)
+            (MemoryValue::List(a), MemoryValue::List(b)) if a.is_empty() || b.is_empty() => {
+                Ok(a.is_empty() && b.is_empty())
+            }
+            (MemoryValue::List(a), MemoryValue::List(b)) => {
+                self.check_comparable(other, ExpressionType::is_equatable)?;
+
+                if a.len() != b.len() {
+                    return Ok(false);
+                }
+
+                for (a, b) in a.iter().zip(b.iter()) {
+                    if !a.equals(b)? {
+                        return Ok(false);
+                    }
+                }
+
+                Ok(true)
+            }
+            _ => {
+                self.check_comparable(other, ExpressionType::is_equatable)?;
+
+                Ok(self == other)
+            }
+        }
+    }
+
+    // the `<` / `>` semantics (see `ExpressionType::is_ordered`).
+    // strings are ordered lexicographically
+    pub fn compare(&self, other: &MemoryValue) -> Result<Ordering, IncomparableValues> {
+        self.check_comparable(other, ExpressionType::is_ordered)?;
+
+        let ord = match (self, other) {
+            (MemoryValue::Int(a), MemoryValue::Int(b)) => a.cmp(b),
+            (MemoryValue::Str(a), MemoryValue::Str(b)) => a.cmp(b),
+            // an `Int` value compared with a `BigInt` value (or two `BigInt` values)
+            #[cfg(feature = "bigint")]
+            (a, b) if a.value_type() == ExpressionType::Int => a.to_big_int().cmp(&b.to_big_int()),
+            _ => unreachable!(),
+        };
+
+        Ok(ord)
+    }
+
+    fn check_comparable(
+        &self,
+        other: &MemoryValue,
+        rule: fn(&ExpressionType) -> bool,
+    ) -> Result<(), IncomparableValues> {
+        let (ltype, rtype) = (self.value_type(), other.value_type());
+
+        if ltype != rtype || !rule(&ltype) {
+            return Err(IncomparableValues(ltype, rtype));
+        }
+
+        Ok(())
+    }
+}
+
+impl From<&CallStackItem> for MemoryValue {
+    fn from(item: &CallStackItem) -> Self {
+        match item {
+            CallStackItem::Int(v) => MemoryValue::Int(*v),
+            CallStackItem::Bool(v) => MemoryValue::Bool(*v),
+            CallStackItem::Str(v) => MemoryValue::Str(v.clone()),
+            CallStackItem::List(items) => MemoryValue::List(items.clone()),
+            CallStackItem::Color(color) => MemoryValue::Color(*color),
+            CallStackItem::ProcRef(proc_id) => MemoryValue::ProcRef(*proc_id),
+            CallStackItem::Block(proc_id, frame_id) => MemoryValue::Block(*proc_id, *frame_id),
+            #[cfg(feature = "bigint")]
+            CallStackItem::BigInt(v) => MemoryValue::BigInt(v.clone()),
+            CallStackItem::Addr(..) => panic!("an address can't be stored in memory"),
+            CallStackItem::Catch(..) => panic!("a `CATCH` marker can't be stored in memory"),
+        }
+    }
+}
+
+// the value as written in code (e.g `"TURTLE` or `[1 2 3]`)
+impl fmt::Display for MemoryValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MemoryValue::Int(v) => write!(f, "{}", v),
+            MemoryValue::Bool(true) => write!(f, "TRUE"),
+            MemoryValue::Bool(false) => write!(f, "FALSE"),
+            MemoryValue::Str(v) => write!(f, "\"{}", v),
+            MemoryValue::List(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<_>>();
+                write!(f, "[{}]", items.join(" "))
+            }
+            MemoryValue::Color(color) => write!(f, "{}", color),
+            MemoryValue::ProcRef(_) => write!(f, "PROC"),
+            MemoryValue::Block(..) => write!(f, "BLOCK"),
+            #[cfg(feature = "bigint")]
+            MemoryValue::BigInt(v) => write!(f, "{}", v),
+        }
+    }
 }
@@ -1,19 +1,72 @@
 mod address;
+mod async_host;
+#[cfg(feature = "bigint")]
+mod big_int;
+mod bounds;
 mod call_stack;
+mod capabilities;
+mod color;
 mod dummy_host;
+mod eval_error;
+mod expr_evaluator;
+mod frame_report;
 mod host;
 mod interpreter;
+mod label;
 mod memory;
 mod memory_value;
+mod mouse;
+mod movement;
+mod overflow_policy;
 mod pen;
+mod physical_host;
+mod primitive_eval;
+#[cfg(feature = "tracing")]
+mod proc_spans;
+mod profile_report;
+mod recording_host;
+mod replay_host;
+mod resource_limits;
+mod snapshot;
+mod stack_trace;
 mod turtle;
+mod turtle_config;
+mod turtle_shape;
+mod vm_event;
+mod watch_hit;
 
 pub use address::Address;
+pub use async_host::{AsyncHost, HostFuture};
+#[cfg(feature = "bigint")]
+pub use big_int::BigInt;
+pub use bounds::Bounds;
 pub use call_stack::*;
+pub use capabilities::{Capabilities, Capability};
+pub use color::{Color, COLOR_PALETTE};
 pub use dummy_host::DummyHost;
-pub use host::Host;
+pub use eval_error::EvalError;
+pub use expr_evaluator::{EvalResult, ExprEvaluator};
+pub use frame_report::{FrameReport, YieldPoint};
+pub use host::{Host, HostCall, BEEP_DURATION, BEEP_FREQ, DEFAULT_SCREEN_SIZE};
 pub use interpreter::*;
+pub use label::{Label, LabelStyle, DEFAULT_LABEL_FONT, DEFAULT_LABEL_SIZE};
 pub use memory::Memory;
-pub use memory_value::MemoryValue;
+pub use memory_value::{IncomparableValues, MemoryValue};
+pub use mouse::MouseState;
+pub use movement::{Movement, TurtlePose};
+pub use overflow_policy::OverflowPolicy;
 pub use pen::{Pen, PenState};
+pub use physical_host::{Calibration, MotionQueue, PhysicalHost, WheelCommand};
+#[cfg(feature = "tracing")]
+pub(crate) use proc_spans::ProcSpans;
+pub use profile_report::ProfileReport;
+pub use recording_host::RecordingHost;
+pub use replay_host::ReplayHost;
+pub use resource_limits::{Resource, ResourceLimits};
+pub use snapshot::VmSnapshot;
+pub use stack_trace::StackTraceFrame;
 pub use turtle::Turtle;
+pub use turtle_config::{AngleUnit, Origin, Steering, TurtleConfig, YAxis};
+pub use turtle_shape::{TurtleShape, IMAGE_SHAPE_PREFIX};
+pub use vm_event::VmEvent;
+pub use watch_hit::WatchHit;
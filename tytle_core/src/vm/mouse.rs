@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+// the pointer state reported by the host (see `Interpreter::notify_mouse`),
+// read by `MOUSEX`, `MOUSEY` and `BUTTONP`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MouseState {
+    // in the turtle coordinates
+    pub position: (isize, isize),
+    pub pressed: bool,
+}
@@ -0,0 +1,66 @@
+use crate::ast::statement::Direction;
+use serde::{Deserialize, Serialize};
+
+// the pose of the turtle somewhere along a `Movement`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TurtlePose {
+    pub position: (f64, f64),
+
+    // in the configured `AngleUnit`. it isn't normalized, so a tween of a `RIGHT 450`
+    // ends `450` past its start heading (rather than `90`)
+    pub heading: f64,
+}
+
+// a direction statement split into `frames` steps, so a GUI host can tween the turtle
+// along it without re-deriving the geometry (see `Interpreter::set_movement_granularity`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Movement {
+    pub direct: Direction,
+    pub count: isize,
+    pub from: TurtlePose,
+    pub to: TurtlePose,
+    pub frames: usize,
+}
+
+impl Movement {
+    // the pose at `frame`, interpolated linearly between `from` (frame `0`) and `to` (frame `frames`)
+    pub fn pose_at(&self, frame: usize) -> TurtlePose {
+        let t = frame.min(self.frames) as f64 / self.frames as f64;
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+        TurtlePose {
+            position: (
+                lerp(self.from.position.0, self.to.position.0),
+                lerp(self.from.position.1, self.to.position.1),
+            ),
+            heading: lerp(self.from.heading, self.to.heading),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn movement_pose_at() {
+        let movement = Movement {
+            direct: Direction::Forward,
+            count: 10,
+            from: TurtlePose {
+                position: (0.0, 0.0),
+                heading: 90.0,
+            },
+            to: TurtlePose {
+                position: (10.0, 0.0),
+                heading: 90.0,
+            },
+            frames: 4,
+        };
+
+        assert_eq!(movement.from, movement.pose_at(0));
+        assert_eq!((2.5, 0.0), movement.pose_at(1).position);
+        assert_eq!(movement.to, movement.pose_at(4));
+        assert_eq!(movement.to, movement.pose_at(5));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::ast::expression::BinaryOp;
+#[cfg(feature = "bigint")]
+use crate::vm::big_int;
+use crate::vm::MemoryValue;
+use serde::{Deserialize, Serialize};
+
+// what an integer arithmetic overflowing `isize` results in (see `Interpreter::set_overflow_policy`)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    // wraps around the boundary of the type (e.g `MAX + 1` is `MIN`)
+    Wrapping,
+
+    // clamps to the type boundary (e.g `MAX + 1` is `MAX`)
+    Saturating,
+
+    // stops the execution with `InterpreterException::IntegerOverflow`
+    #[default]
+    Checked,
+
+    // switches to arbitrary-precision integers (see `MemoryValue::BigInt`)
+    #[cfg(feature = "bigint")]
+    Promote,
+}
+
+impl OverflowPolicy {
+    // applies the integer `op` (`+`, `*` or `/`) over the (integer) values.
+    // `None` when the result overflows under the `Checked` policy
+    pub fn eval(self, op: &BinaryOp, a: &MemoryValue, b: &MemoryValue) -> Option<MemoryValue> {
+        let (a, b) = match (a, b) {
+            (MemoryValue::Int(a), MemoryValue::Int(b)) => (*a, *b),
+
+            // a `BigInt` operand is there only under the `Promote` policy
+            #[cfg(feature = "bigint")]
+            _ => return Some(big_int::eval_big_int(op, a.to_big_int(), b.to_big_int())),
+
+            #[cfg(not(feature = "bigint"))]
+            _ => panic!("expected integer operands: `{:?}` and `{:?}`", a, b),
+        };
+
+        let v = match op {
+            BinaryOp::Add => self.add(a, b),
+            BinaryOp::Mul => self.mul(a, b),
+            BinaryOp::Div => self.div(a, b),
+            _ => panic!("invalid integer binary-op: `{:?}`", op),
+        };
+
+        match v {
+            Some(v) => Some(MemoryValue::Int(v)),
+            #[cfg(feature = "bigint")]
+            None if self == OverflowPolicy::Promote => {
+                Some(big_int::eval_big_int(op, a.into(), b.into()))
+            }
+            None => None,
+        }
+    }
+
+    // `None` when the result overflows under the `Checked` (or the `Promote`) policy
+    pub fn add(self, a: isize, b: isize) -> Option<isize> {
+        match self {
+            OverflowPolicy::Wrapping => Some(a.wrapping_add(b)),
+            OverflowPolicy::Saturating => Some(a.saturating_add(b)),
+            OverflowPolicy::Checked => a.checked_add(b),
+            #[cfg(feature = "bigint")]
+            OverflowPolicy::Promote => a.checked_add(b),
+        }
+    }
+
+    pub fn mul(self, a: isize, b: isize) -> Option<isize> {
+        match self {
+            OverflowPolicy::Wrapping => Some(a.wrapping_mul(b)),
+            OverflowPolicy::Saturating => Some(a.saturating_mul(b)),
+            OverflowPolicy::Checked => a.checked_mul(b),
+            #[cfg(feature = "bigint")]
+            OverflowPolicy::Promote => a.checked_mul(b),
+        }
+    }
+
+    // the only overflowing division is `MIN / -1`. `b` must not be zero
+    pub fn div(self, a: isize, b: isize) -> Option<isize> {
+        match self {
+            OverflowPolicy::Wrapping => Some(a.wrapping_div(b)),
+            OverflowPolicy::Saturating => Some(a.saturating_div(b)),
+            OverflowPolicy::Checked => match a.overflowing_div(b) {
+                (v, false) => Some(v),
+                (_, true) => None,
+            },
+            #[cfg(feature = "bigint")]
+            OverflowPolicy::Promote => OverflowPolicy::Checked.div(a, b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn overflow_policy_add() {
+        assert_eq!(
+            Some(isize::MIN),
+            OverflowPolicy::Wrapping.add(isize::MAX, 1)
+        );
+        assert_eq!(
+            Some(isize::MAX),
+            OverflowPolicy::Saturating.add(isize::MAX, 1)
+        );
+        assert_eq!(None, OverflowPolicy::Checked.add(isize::MAX, 1));
+        assert_eq!(Some(3), OverflowPolicy::Checked.add(1, 2));
+    }
+
+    #[test]
+    pub fn overflow_policy_mul() {
+        assert_eq!(Some(-2), OverflowPolicy::Wrapping.mul(isize::MAX, 2));
+        assert_eq!(
+            Some(isize::MIN),
+            OverflowPolicy::Saturating.mul(isize::MIN, 2)
+        );
+        assert_eq!(None, OverflowPolicy::Checked.mul(isize::MIN, 2));
+        assert_eq!(Some(6), OverflowPolicy::Checked.mul(2, 3));
+    }
+
+    #[test]
+    pub fn overflow_policy_div() {
+        assert_eq!(
+            Some(isize::MIN),
+            OverflowPolicy::Wrapping.div(isize::MIN, -1)
+        );
+        assert_eq!(
+            Some(isize::MAX),
+            OverflowPolicy::Saturating.div(isize::MIN, -1)
+        );
+        assert_eq!(None, OverflowPolicy::Checked.div(isize::MIN, -1));
+        assert_eq!(Some(-3), OverflowPolicy::Checked.div(7, -2));
+    }
+}
@@ -1,21 +1,24 @@
-#[derive(Debug)]
+use crate::vm::Color;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PenState {
     Up,
     Down,
     Erase,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Pen {
     state: PenState,
-    color: (u8, u8, u8),
+    color: Color,
 }
 
 impl Pen {
     pub fn new() -> Self {
         Self {
             state: PenState::Down,
-            color: (0, 0, 0),
+            color: Color::default(),
         }
     }
 
@@ -31,7 +34,7 @@ impl Pen {
         self.state = PenState::Erase;
     }
 
-    pub fn set_color(&mut self, color: (u8, u8, u8)) {
+    pub fn set_color(&mut self, color: Color) {
         self.color = color
     }
 
@@ -39,7 +42,7 @@ impl Pen {
         &self.state
     }
 
-    pub fn get_color(&self) -> (u8, u8, u8) {
+    pub fn get_color(&self) -> Color {
         self.color
     }
 }
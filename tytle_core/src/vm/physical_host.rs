@@ -0,0 +1,535 @@
+use crate::ast::statement::{Command, Direction};
+use crate::compat::math;
+use crate::compat::prelude::*;
+#[cfg(feature = "bigint")]
+use crate::vm::BigInt;
+use crate::vm::{
+    AsyncHost, Color, Host, HostFuture, Label, Movement, Pen, PenState, Steering, Turtle,
+    TurtleConfig, TurtlePose, TurtleShape, YAxis,
+};
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use serde::{Deserialize, Serialize};
+
+// relates the turtle steps & degrees to the wheels of a differential-drive robot
+// (two wheels on a common axle, with the pen half way between them)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    // the ticks (e.g stepper motor steps) a wheel turns for the robot to travel a turtle step
+    pub ticks_per_unit: f64,
+
+    // the distance (in turtle steps) between the pen and each wheel. turning in place
+    // by `a` radians drives the wheels `a * turn_radius` steps in opposite directions
+    pub turn_radius: f64,
+}
+
+impl Calibration {
+    pub fn new(ticks_per_unit: f64, turn_radius: f64) -> Self {
+        Self {
+            ticks_per_unit,
+            turn_radius,
+        }
+    }
+
+    // the ticks of each wheel for traveling `distance` steps
+    pub fn drive_ticks(&self, distance: f64) -> isize {
+        math::round(distance * self.ticks_per_unit) as isize
+    }
+
+    // the ticks of the left wheel for turning clockwise in place by `degrees`
+    // (the right wheel turns the same ticks backwards)
+    pub fn turn_ticks(&self, degrees: f64) -> isize {
+        self.drive_ticks(degrees.to_radians() * self.turn_radius)
+    }
+}
+
+// a command for the robot hardware
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WheelCommand {
+    // turns the wheels by the given ticks (negative ticks turn backwards), both ending together.
+    // e.g `left == right` drives straight and `left == -right` turns in place
+    Drive { left: isize, right: isize },
+    PenUp,
+    PenDown,
+}
+
+// the wheel commands planned by a `PhysicalHost`, waiting for the hardware.
+// the motors driver pops the next command once the previous one is done, so the VM doesn't
+// wait for the (slow) motion. it's shared by cloning (within a single thread)
+#[derive(Debug, Clone)]
+pub struct MotionQueue {
+    state: Rc<RefCell<QueueState>>,
+}
+
+#[derive(Debug)]
+struct QueueState {
+    commands: VecDeque<WheelCommand>,
+    capacity: usize,
+
+    // the execution waiting for room in the queue (see `AsyncHost for PhysicalHost`)
+    waker: Option<Waker>,
+}
+
+impl MotionQueue {
+    // an `exec_async` execution waits whenever `capacity` commands are queued
+    pub fn new(capacity: usize) -> Self {
+        let state = QueueState {
+            commands: VecDeque::new(),
+            capacity,
+            waker: None,
+        };
+
+        Self {
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+
+    pub fn pop(&self) -> Option<WheelCommand> {
+        let mut state = self.state.borrow_mut();
+        let cmd = state.commands.pop_front();
+
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+
+        cmd
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.borrow().commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        let state = self.state.borrow();
+
+        state.commands.len() >= state.capacity
+    }
+
+    fn push(&self, cmd: WheelCommand) {
+        self.state.borrow_mut().commands.push_back(cmd);
+    }
+
+    // resolves once the queue isn't full
+    fn room(&self) -> Room<'_> {
+        Room(self)
+    }
+}
+
+struct Room<'a>(&'a MotionQueue);
+
+impl Future for Room<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.0.is_full() {
+            self.0.state.borrow_mut().waker = Some(cx.waker().clone());
+
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+}
+
+// wraps a `Host` and drives a robot along with it: the turtle motion and the pen state
+// are translated into `WheelCommand`s (using the `Calibration`) and pushed to the `MotionQueue`.
+// the robot is expected to start at the turtle home pose, with its pen down.
+//
+// the `Host` implementation never waits (the queue grows as needed), while the `AsyncHost`
+// implementation waits for room in the queue before each host-call (see `Interpreter::exec_async`)
+pub struct PhysicalHost<H: Host> {
+    inner: H,
+    calibration: Calibration,
+    queue: MotionQueue,
+
+    // the turtle of the program (tracked from the host-calls), standing for the robot pose
+    turtle: Turtle,
+    pen_down: bool,
+}
+
+impl<H: Host> PhysicalHost<H> {
+    pub fn new(inner: H, calibration: Calibration, queue: MotionQueue) -> Self {
+        Self::with_config(inner, calibration, queue, TurtleConfig::default())
+    }
+
+    // the `config` should be the one the program is executed with
+    // (see `Interpreter::set_turtle_config`)
+    pub fn with_config(
+        inner: H,
+        calibration: Calibration,
+        queue: MotionQueue,
+        config: TurtleConfig,
+    ) -> Self {
+        Self {
+            inner,
+            calibration,
+            queue,
+            turtle: Turtle::with_config(config),
+            pen_down: true,
+        }
+    }
+
+    pub fn inner(&self) -> &H {
+        &self.inner
+    }
+
+    pub fn queue(&self) -> &MotionQueue {
+        &self.queue
+    }
+
+    pub fn calibration(&self) -> &Calibration {
+        &self.calibration
+    }
+
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+
+    fn exec_motion(&mut self, direct: &Direction, count: isize) {
+        let from = self.turtle.clone();
+        self.turtle.exec_direct(direct, count);
+
+        let turns = self.turtle.config().steering == Steering::Turn;
+
+        // driving & turning by the exact counts (the positions are rounded to whole steps)
+        match direct {
+            Direction::Forward => self.drive(count as f64),
+            Direction::Backward => self.drive(-count as f64),
+            Direction::Right if turns => self.turn(from.config().to_degrees(count as f64)),
+            Direction::Left if turns => self.turn(-from.config().to_degrees(count as f64)),
+            _ => {
+                let to = self.turtle.clone();
+                self.travel(&from, &to);
+            }
+        }
+    }
+
+    // drives from the `from` pose to the `to` pose: turns towards `to`, drives straight
+    // (backing up rather than turning around) and turns into the `to` heading
+    fn travel(&mut self, from: &Turtle, to: &Turtle) {
+        let dx = (to.xcor() - from.xcor()) as f64;
+        let dy = (to.ycor() - from.ycor()) as f64;
+
+        let up = match from.config().y_axis {
+            YAxis::Up => dy,
+            YAxis::Down => -dy,
+        };
+
+        let mut heading = degrees(from);
+
+        if dx != 0.0 || up != 0.0 {
+            let bearing = math::atan2(dx, up).to_degrees();
+            let mut distance = math::sqrt(dx * dx + up * up);
+            let mut turn = normalize(bearing - heading);
+
+            if turn.abs() > 90.0 {
+                turn = normalize(turn + 180.0);
+                distance = -distance;
+            }
+
+            self.turn(turn);
+            self.drive(distance);
+
+            heading += turn;
+        }
+
+        self.turn(normalize(degrees(to) - heading));
+    }
+
+    fn drive(&mut self, distance: f64) {
+        let ticks = self.calibration.drive_ticks(distance);
+
+        if ticks != 0 {
+            self.queue.push(WheelCommand::Drive {
+                left: ticks,
+                right: ticks,
+            });
+        }
+    }
+
+    // turns clockwise by `degrees`
+    fn turn(&mut self, degrees: f64) {
+        let ticks = self.calibration.turn_ticks(degrees);
+
+        if ticks != 0 {
+            self.queue.push(WheelCommand::Drive {
+                left: ticks,
+                right: -ticks,
+            });
+        }
+    }
+
+    fn set_pen_down(&mut self, down: bool) {
+        if self.pen_down != down {
+            self.pen_down = down;
+
+            let cmd = if down {
+                WheelCommand::PenDown
+            } else {
+                WheelCommand::PenUp
+            };
+
+            self.queue.push(cmd);
+        }
+    }
+
+    // waits for room in the queue, then makes the host-call `f`
+    fn after_room<'a, F>(&'a mut self, f: F) -> HostFuture<'a>
+    where
+        F: FnOnce(&mut Self) + 'a,
+    {
+        Box::pin(async move {
+            self.queue.room().await;
+
+            f(self);
+        })
+    }
+}
+
+// the heading of `turtle` in degrees
+fn degrees(turtle: &Turtle) -> f64 {
+    turtle.config().to_degrees(turtle.heading())
+}
+
+// `degrees` normalized into `(-180, 180]`, i.e the shortest turn
+fn normalize(degrees: f64) -> f64 {
+    let degrees = math::rem_euclid(degrees + 180.0, 360.0) - 180.0;
+
+    if degrees == -180.0 {
+        180.0
+    } else {
+        degrees
+    }
+}
+
+impl<H: Host> Host for PhysicalHost<H> {
+    fn exec_cmd(&mut self, cmd: &Command) {
+        match cmd {
+            Command::PenDown => self.set_pen_down(true),
+
+            // erasing would need a robot of its own, so the pen is lifted instead
+            Command::PenUp | Command::PenErase => self.set_pen_down(false),
+            _ => {}
+        }
+
+        self.inner.exec_cmd(cmd);
+    }
+
+    fn exec_direct(&mut self, direct: &Direction, count: isize) {
+        self.exec_motion(direct, count);
+        self.inner.exec_direct(direct, count);
+    }
+
+    fn exec_turn(&mut self, degrees: f64) {
+        self.turtle.turn_degrees(degrees);
+        self.turn(degrees);
+        self.inner.exec_turn(degrees);
+    }
+
+    fn exec_trap(&mut self, node: usize, ip: usize) {
+        self.inner.exec_trap(node, ip);
+    }
+
+    fn exec_print(&mut self, value: isize) {
+        self.inner.exec_print(value);
+    }
+
+    #[cfg(feature = "bigint")]
+    fn exec_print_big(&mut self, value: &BigInt) {
+        self.inner.exec_print_big(value);
+    }
+
+    fn exec_print_text(&mut self, text: &str) {
+        self.inner.exec_print_text(text);
+    }
+
+    fn exec_trace(&mut self, line: &str) {
+        self.inner.exec_trace(line);
+    }
+
+    fn exec_stamp(&mut self, position: (isize, isize), heading: f64) {
+        self.inner.exec_stamp(position, heading);
+    }
+
+    fn exec_label(&mut self, label: &Label) {
+        self.inner.exec_label(label);
+    }
+
+    fn turtle_shape_changed(&mut self, shape: &TurtleShape) {
+        self.inner.turtle_shape_changed(shape);
+    }
+
+    fn screen_size(&self) -> (usize, usize) {
+        self.inner.screen_size()
+    }
+
+    fn play_tone(&mut self, freq: usize, ms: usize) {
+        self.inner.play_tone(freq, ms);
+    }
+
+    fn pen_color_changed(&mut self, color: Color) {
+        self.inner.pen_color_changed(color);
+    }
+
+    // the robot drives the whole movement once it starts
+    fn movement_started(&mut self, movement: &Movement) {
+        self.exec_motion(&movement.direct, movement.count);
+        self.inner.movement_started(movement);
+    }
+
+    fn movement_progress(&mut self, movement: &Movement, frame: usize, pose: &TurtlePose) {
+        self.inner.movement_progress(movement, frame, pose);
+    }
+
+    fn movement_completed(&mut self, movement: &Movement) {
+        self.inner.movement_completed(movement);
+    }
+
+    // the robot can't jump, so it drives back to the restored pose with its pen up
+    fn state_restored(&mut self, turtle: &Turtle, pen: &Pen) {
+        let from = self.turtle.clone();
+
+        self.set_pen_down(false);
+        self.travel(&from, turtle);
+        self.set_pen_down(pen.get_state() == &PenState::Down);
+
+        self.turtle = turtle.clone();
+        self.inner.state_restored(turtle, pen);
+    }
+
+    fn kv_set(&mut self, key: &str, value: &str) {
+        self.inner.kv_set(key, value);
+    }
+
+    fn kv_get(&self, key: &str) -> Option<String> {
+        self.inner.kv_get(key)
+    }
+
+    fn open_stream(&mut self, name: &str) {
+        self.inner.open_stream(name);
+    }
+
+    fn write_stream(&mut self, name: &str, text: &str) {
+        self.inner.write_stream(name, text);
+    }
+
+    fn close_stream(&mut self, name: &str) {
+        self.inner.close_stream(name);
+    }
+
+    fn compilation_error(&mut self, error: &str) {
+        self.inner.compilation_error(error);
+    }
+}
+
+impl<H: Host> AsyncHost for PhysicalHost<H> {
+    fn exec_cmd<'a>(&'a mut self, cmd: &'a Command) -> HostFuture<'a> {
+        self.after_room(move |host| Host::exec_cmd(host, cmd))
+    }
+
+    fn exec_direct<'a>(&'a mut self, direct: &'a Direction, count: isize) -> HostFuture<'a> {
+        self.after_room(move |host| Host::exec_direct(host, direct, count))
+    }
+
+    fn exec_turn(&mut self, degrees: f64) -> HostFuture<'_> {
+        self.after_room(move |host| Host::exec_turn(host, degrees))
+    }
+
+    fn exec_trap(&mut self, node: usize, ip: usize) -> HostFuture<'_> {
+        self.after_room(move |host| Host::exec_trap(host, node, ip))
+    }
+
+    fn exec_print(&mut self, value: isize) -> HostFuture<'_> {
+        self.after_room(move |host| Host::exec_print(host, value))
+    }
+
+    #[cfg(feature = "bigint")]
+    fn exec_print_big<'a>(&'a mut self, value: &'a BigInt) -> HostFuture<'a> {
+        self.after_room(move |host| Host::exec_print_big(host, value))
+    }
+
+    fn exec_print_text<'a>(&'a mut self, text: &'a str) -> HostFuture<'a> {
+        self.after_room(move |host| Host::exec_print_text(host, text))
+    }
+
+    fn exec_trace<'a>(&'a mut self, line: &'a str) -> HostFuture<'a> {
+        self.after_room(move |host| Host::exec_trace(host, line))
+    }
+
+    fn exec_stamp(&mut self, position: (isize, isize), heading: f64) -> HostFuture<'_> {
+        self.after_room(move |host| Host::exec_stamp(host, position, heading))
+    }
+
+    fn exec_label<'a>(&'a mut self, label: &'a Label) -> HostFuture<'a> {
+        self.after_room(move |host| Host::exec_label(host, label))
+    }
+
+    fn turtle_shape_changed<'a>(&'a mut self, shape: &'a TurtleShape) -> HostFuture<'a> {
+        self.after_room(move |host| Host::turtle_shape_changed(host, shape))
+    }
+
+    fn play_tone(&mut self, freq: usize, ms: usize) -> HostFuture<'_> {
+        self.after_room(move |host| Host::play_tone(host, freq, ms))
+    }
+
+    fn pen_color_changed(&mut self, color: Color) -> HostFuture<'_> {
+        self.after_room(move |host| Host::pen_color_changed(host, color))
+    }
+
+    fn movement_started<'a>(&'a mut self, movement: &'a Movement) -> HostFuture<'a> {
+        self.after_room(move |host| Host::movement_started(host, movement))
+    }
+
+    fn movement_progress<'a>(
+        &'a mut self,
+        movement: &'a Movement,
+        frame: usize,
+        pose: &'a TurtlePose,
+    ) -> HostFuture<'a> {
+        self.after_room(move |host| Host::movement_progress(host, movement, frame, pose))
+    }
+
+    fn movement_completed<'a>(&'a mut self, movement: &'a Movement) -> HostFuture<'a> {
+        self.after_room(move |host| Host::movement_completed(host, movement))
+    }
+
+    fn state_restored<'a>(&'a mut self, turtle: &'a Turtle, pen: &'a Pen) -> HostFuture<'a> {
+        self.after_room(move |host| Host::state_restored(host, turtle, pen))
+    }
+
+    fn kv_set<'a>(&'a mut self, key: &'a str, value: &'a str) -> HostFuture<'a> {
+        self.after_room(move |host| Host::kv_set(host, key, value))
+    }
+
+    fn kv_get(&self, key: &str) -> Option<String> {
+        Host::kv_get(self, key)
+    }
+
+    fn open_stream<'a>(&'a mut self, name: &'a str) -> HostFuture<'a> {
+        self.after_room(move |host| Host::open_stream(host, name))
+    }
+
+    fn write_stream<'a>(&'a mut self, name: &'a str, text: &'a str) -> HostFuture<'a> {
+        self.after_room(move |host| Host::write_stream(host, name, text))
+    }
+
+    fn close_stream<'a>(&'a mut self, name: &'a str) -> HostFuture<'a> {
+        self.after_room(move |host| Host::close_stream(host, name))
+    }
+
+    fn screen_size(&self) -> (usize, usize) {
+        Host::screen_size(self)
+    }
+
+    fn exec_yield(&mut self) -> HostFuture<'_> {
+        Box::pin(self.queue.room())
+    }
+}
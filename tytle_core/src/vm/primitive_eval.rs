@@ -0,0 +1,108 @@
+use crate::ast::semantic::Primitive;
+use crate::compat::prelude::*;
+use crate::vm::{Color, InterpreterException, MemoryValue};
+
+impl Primitive {
+    // applies the primitive over the call args (in declaration order).
+    // the args are type-checked at compile time, but an imported (JSON IR) program isn't.
+    // the screen, mouse & pen color queries depend on the VM state,
+    // so they're evaluated by the interpreter
+    pub fn eval(&self, args: &[MemoryValue]) -> Result<MemoryValue, InterpreterException> {
+        let value = match (self, args) {
+            (Primitive::Word, [MemoryValue::Str(a), MemoryValue::Str(b)]) => {
+                MemoryValue::Str(format!("{}{}", a, b))
+            }
+            (Primitive::Count, [MemoryValue::Str(w)]) => {
+                MemoryValue::Int(w.chars().count() as isize)
+            }
+            (Primitive::Item, [MemoryValue::Int(index), MemoryValue::Str(w)]) => {
+                let ch = match *index {
+                    i if i >= 1 => w.chars().nth(i as usize - 1),
+                    _ => None,
+                };
+
+                match ch {
+                    Some(ch) => MemoryValue::Str(ch.to_string()),
+                    None => {
+                        return Err(InterpreterException::ItemOutOfRange {
+                            index: *index,
+                            count: w.chars().count(),
+                        })
+                    }
+                }
+            }
+            (Primitive::Uppercase, [MemoryValue::Str(w)]) => MemoryValue::Str(w.to_uppercase()),
+            (Primitive::Lowercase, [MemoryValue::Str(w)]) => MemoryValue::Str(w.to_lowercase()),
+            (Primitive::Parse, [MemoryValue::Str(w)]) => match w.parse::<isize>() {
+                Ok(v) => MemoryValue::Int(v),
+                Err(_) => return Err(InterpreterException::InvalidNumber(w.clone())),
+            },
+            (Primitive::Rgb, [MemoryValue::Int(r), MemoryValue::Int(g), MemoryValue::Int(b)]) => {
+                MemoryValue::Color(Color::rgb(*r, *g, *b))
+            }
+            (Primitive::Palette, [MemoryValue::Int(index)]) => match Color::palette(*index) {
+                Some(color) => MemoryValue::Color(color),
+                None => return Err(InterpreterException::UnknownColor(index.to_string())),
+            },
+            (Primitive::NamedColor, [MemoryValue::Str(name)]) => match Color::named(name) {
+                Some(color) => MemoryValue::Color(color),
+                None => return Err(InterpreterException::UnknownColor(name.clone())),
+            },
+            (Primitive::Format, [MemoryValue::Str(f), MemoryValue::List(items)]) => {
+                MemoryValue::Str(format(f, items)?)
+            }
+            _ => panic!("invalid args for primitive `{}`: `{:?}`", self.name(), args),
+        };
+
+        Ok(value)
+    }
+}
+
+// replaces the `~a` / `~s` placeholders of `f` by the args (in order), see `Primitive::Format`
+fn format(f: &str, args: &[MemoryValue]) -> Result<String, InterpreterException> {
+    let mut text = String::new();
+    let mut placeholders = 0;
+    let mut chars = f.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '~' {
+            text.push(ch);
+            continue;
+        }
+
+        let directive = chars.next();
+
+        let arg = match directive {
+            Some('~') => {
+                text.push('~');
+                continue;
+            }
+            Some('a') | Some('A') | Some('s') | Some('S') => {
+                placeholders += 1;
+                args.get(placeholders - 1)
+            }
+            Some(other) => {
+                let directive = format!("~{}", other);
+                return Err(InterpreterException::InvalidFormatDirective(directive));
+            }
+            None => return Err(InterpreterException::InvalidFormatDirective("~".to_string())),
+        };
+
+        match (arg, directive) {
+            (Some(arg), Some('a')) | (Some(arg), Some('A')) => text.push_str(&arg.to_text()),
+            (Some(arg), _) => text.push_str(&arg.to_string()),
+
+            // the count mismatch is reported once all the placeholders are counted
+            (None, _) => (),
+        }
+    }
+
+    if placeholders != args.len() {
+        return Err(InterpreterException::FormatArgsCount {
+            placeholders,
+            args: args.len(),
+        });
+    }
+
+    Ok(text)
+}
@@ -0,0 +1,32 @@
+use crate::ast::semantic::Environment;
+use crate::vm::VmEvent;
+use tracing::Span;
+
+// a `tracing` span per running procedure (under the `tracing` feature), a child of its caller's.
+// since the execution may pause (e.g between animation frames) the spans aren't entered,
+// each lasts from the procedure call till its return
+#[derive(Default)]
+pub(crate) struct ProcSpans {
+    spans: Vec<Span>,
+}
+
+impl ProcSpans {
+    pub(crate) fn on_event(&mut self, event: &VmEvent, env: &Environment) {
+        match event {
+            VmEvent::ProcEnter(proc_id) => {
+                let name = env.symbol_table.get_proc_by_id(*proc_id).name.as_str();
+
+                let span = match self.spans.last() {
+                    Some(caller) => tracing::info_span!(parent: caller, "proc", name),
+                    None => tracing::info_span!("proc", name),
+                };
+
+                self.spans.push(span);
+            }
+            VmEvent::ProcExit(_) => {
+                self.spans.pop();
+            }
+            _ => {}
+        }
+    }
+}
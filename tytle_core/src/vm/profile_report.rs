@@ -0,0 +1,118 @@
+use crate::ast::semantic::SymbolId;
+use crate::compat::collections::HashMap;
+use crate::compat::prelude::*;
+use crate::ir::CfgNodeId;
+use core::time::Duration;
+
+// execution statistics gathered by the interpreter once profiling is enabled
+// (see `Interpreter::enable_profiling`)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProfileReport {
+    // the number of instructions executed in total, per CFG node and per procedure
+    pub total_insts: usize,
+    pub node_insts: HashMap<CfgNodeId, usize>,
+    pub proc_insts: HashMap<SymbolId, usize>,
+
+    // the number of instructions executed per source line
+    // (gathered only when the program has been compiled with `debug_info`)
+    pub line_insts: HashMap<usize, usize>,
+
+    // the number of host-calls and the time spent inside the host while serving them
+    pub host_calls: usize,
+    pub host_time: Duration,
+
+    pub peak_stack_depth: usize,
+}
+
+impl ProfileReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_inst(&mut self, node_id: CfgNodeId, proc_id: SymbolId) {
+        self.total_insts += 1;
+
+        *self.node_insts.entry(node_id).or_insert(0) += 1;
+        *self.proc_insts.entry(proc_id).or_insert(0) += 1;
+    }
+
+    pub fn record_line(&mut self, line: usize) {
+        *self.line_insts.entry(line).or_insert(0) += 1;
+    }
+
+    pub fn record_host_call(&mut self, elapsed: Duration) {
+        self.host_calls += 1;
+        self.host_time += elapsed;
+    }
+
+    pub fn record_stack_depth(&mut self, depth: usize) {
+        self.peak_stack_depth = self.peak_stack_depth.max(depth);
+    }
+
+    // the CFG nodes ordered by the number of instructions executed (the "hottest" first)
+    pub fn hottest_nodes(&self) -> Vec<(CfgNodeId, usize)> {
+        let mut nodes = self
+            .node_insts
+            .iter()
+            .map(|(node_id, count)| (*node_id, *count))
+            .collect::<Vec<(CfgNodeId, usize)>>();
+
+        nodes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        nodes
+    }
+
+    // the source lines ordered by the number of instructions executed (the "hottest" first)
+    pub fn hottest_lines(&self) -> Vec<(usize, usize)> {
+        let mut lines = self
+            .line_insts
+            .iter()
+            .map(|(line, count)| (*line, *count))
+            .collect::<Vec<(usize, usize)>>();
+
+        lines.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn profile_report_hottest_nodes() {
+        let mut report = ProfileReport::new();
+
+        report.record_inst(1, SymbolId(0));
+        report.record_inst(2, SymbolId(1));
+        report.record_inst(2, SymbolId(1));
+        report.record_inst(3, SymbolId(1));
+
+        assert_eq!(4, report.total_insts);
+        assert_eq!(Some(&3), report.proc_insts.get(&SymbolId(1)));
+        assert_eq!(vec![(2, 2), (1, 1), (3, 1)], report.hottest_nodes());
+    }
+
+    #[test]
+    pub fn profile_report_hottest_lines() {
+        let mut report = ProfileReport::new();
+
+        report.record_line(4);
+        report.record_line(2);
+        report.record_line(4);
+        report.record_line(1);
+
+        assert_eq!(vec![(4, 2), (1, 1), (2, 1)], report.hottest_lines());
+    }
+
+    #[test]
+    pub fn profile_report_peak_stack_depth() {
+        let mut report = ProfileReport::new();
+
+        report.record_stack_depth(3);
+        report.record_stack_depth(1);
+
+        assert_eq!(3, report.peak_stack_depth);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::ast::statement::{Command, Direction};
+use crate::compat::prelude::*;
+#[cfg(feature = "bigint")]
+use crate::vm::BigInt;
+use crate::vm::{Color, Host, HostCall, Label, Movement, Pen, Turtle, TurtleShape};
+use core::cell::{Ref, RefCell};
+
+// wraps a `Host` and records every host-call made by the interpreter.
+// the recorded log can be serialized and later fed into a `ReplayHost`.
+//
+// the answers of the host queries (`kv_get`, `screen_size`) are recorded too,
+// since a replay can't rely on the host answering them the same way
+pub struct RecordingHost<H: Host> {
+    inner: H,
+
+    // the queries take `&self`, so they record via a `RefCell`
+    log: RefCell<Vec<HostCall>>,
+}
+
+impl<H: Host> RecordingHost<H> {
+    pub fn new(inner: H) -> Self {
+        Self {
+            inner,
+            log: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn log(&self) -> Ref<'_, [HostCall]> {
+        Ref::map(self.log.borrow(), Vec::as_slice)
+    }
+
+    pub fn inner(&self) -> &H {
+        &self.inner
+    }
+
+    pub fn into_parts(self) -> (H, Vec<HostCall>) {
+        (self.inner, self.log.into_inner())
+    }
+
+    fn record(&mut self, call: HostCall) {
+        call.dispatch(&mut self.inner);
+        self.log.get_mut().push(call);
+    }
+}
+
+impl<H: Host> Host for RecordingHost<H> {
+    fn exec_cmd(&mut self, cmd: &Command) {
+        self.record(HostCall::Cmd(cmd.clone()));
+    }
+
+    fn exec_direct(&mut self, direct: &Direction, count: isize) {
+        self.record(HostCall::Direct(*direct, count));
+    }
+
+    fn exec_turn(&mut self, degrees: f64) {
+        self.record(HostCall::Turn(degrees));
+    }
+
+    fn exec_trap(&mut self, node: usize, ip: usize) {
+        self.record(HostCall::Trap(node, ip));
+    }
+
+    fn exec_print(&mut self, value: isize) {
+        self.record(HostCall::Print(value));
+    }
+
+    #[cfg(feature = "bigint")]
+    fn exec_print_big(&mut self, value: &BigInt) {
+        self.record(HostCall::PrintBig(value.clone()));
+    }
+
+    fn exec_print_text(&mut self, text: &str) {
+        self.record(HostCall::PrintText(text.to_string()));
+    }
+
+    fn exec_trace(&mut self, line: &str) {
+        self.record(HostCall::Trace(line.to_string()));
+    }
+
+    fn exec_stamp(&mut self, position: (isize, isize), heading: f64) {
+        self.record(HostCall::Stamp { position, heading });
+    }
+
+    fn exec_label(&mut self, label: &Label) {
+        self.record(HostCall::Label(label.clone()));
+    }
+
+    fn turtle_shape_changed(&mut self, shape: &TurtleShape) {
+        self.record(HostCall::ShapeChanged(shape.clone()));
+    }
+
+    fn play_tone(&mut self, freq: usize, ms: usize) {
+        self.record(HostCall::Tone { freq, ms });
+    }
+
+    fn pen_color_changed(&mut self, color: Color) {
+        self.record(HostCall::PenColor(color));
+    }
+
+    // the whole movement is recorded (and dispatched to the inner host) once it starts
+    fn movement_started(&mut self, movement: &Movement) {
+        self.record(HostCall::Movement(movement.clone()));
+    }
+
+    fn state_restored(&mut self, turtle: &Turtle, pen: &Pen) {
+        self.record(HostCall::StateRestored(turtle.clone(), pen.clone()));
+    }
+
+    fn kv_set(&mut self, key: &str, value: &str) {
+        self.record(HostCall::KvSet {
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+    }
+
+    fn kv_get(&self, key: &str) -> Option<String> {
+        let value = self.inner.kv_get(key);
+
+        self.log.borrow_mut().push(HostCall::KvGet {
+            key: key.to_string(),
+            value: value.clone(),
+        });
+
+        value
+    }
+
+    fn open_stream(&mut self, name: &str) {
+        self.record(HostCall::OpenStream(name.to_string()));
+    }
+
+    fn write_stream(&mut self, name: &str, text: &str) {
+        self.record(HostCall::WriteStream {
+            stream: name.to_string(),
+            text: text.to_string(),
+        });
+    }
+
+    fn close_stream(&mut self, name: &str) {
+        self.record(HostCall::CloseStream(name.to_string()));
+    }
+
+    fn screen_size(&self) -> (usize, usize) {
+        let (width, height) = self.inner.screen_size();
+        self.log.borrow_mut().push(HostCall::ScreenSize(width, height));
+
+        (width, height)
+    }
+
+    fn compilation_error(&mut self, error: &str) {
+        self.inner.compilation_error(error);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::ast::statement::{Command, Direction};
+use crate::compat::prelude::*;
+#[cfg(feature = "bigint")]
+use crate::vm::BigInt;
+use crate::vm::{Color, Host, HostCall, Label, Movement, Pen, Turtle, TurtleShape};
+use core::cell::Cell;
+
+// replays a log recorded by a `RecordingHost`.
+// each host-call made by the interpreter is checked against the next recorded call
+// and is then forwarded to the inner host. a divergence from the recording panics.
+//
+// the host queries (`kv_get`, `screen_size`) are answered by the recording (rather than
+// by the inner host), so the replayed execution takes the same path as the recorded one
+pub struct ReplayHost<H: Host> {
+    inner: H,
+    log: Vec<HostCall>,
+
+    // the queries take `&self`, so they advance it via a `Cell`
+    cursor: Cell<usize>,
+}
+
+impl<H: Host> ReplayHost<H> {
+    pub fn new(inner: H, log: Vec<HostCall>) -> Self {
+        Self {
+            inner,
+            log,
+            cursor: Cell::new(0),
+        }
+    }
+
+    pub fn inner(&self) -> &H {
+        &self.inner
+    }
+
+    // returns whether all the recorded host-calls have been replayed
+    pub fn is_finished(&self) -> bool {
+        self.cursor.get() == self.log.len()
+    }
+
+    fn replay(&mut self, call: HostCall) {
+        self.next_recorded(&call, |expected| match *expected == call {
+            true => Some(()),
+            false => None,
+        });
+
+        call.dispatch(&mut self.inner);
+    }
+
+    // moves past the next recorded host-call, returning what `answer` makes of it.
+    // a recorded call which doesn't fit the one made (`call`) is a divergence
+    fn next_recorded<T, F>(&self, call: &HostCall, answer: F) -> T
+    where
+        F: FnOnce(&HostCall) -> Option<T>,
+    {
+        let cursor = self.cursor.get();
+
+        match self.log.get(cursor) {
+            Some(expected) => match answer(expected) {
+                Some(answer) => {
+                    self.cursor.set(cursor + 1);
+                    answer
+                }
+                None => panic!(
+                    "replay diverged at host-call #{}: expected `{:?}`, got `{:?}`",
+                    cursor, expected, call
+                ),
+            },
+            None => panic!(
+                "replay diverged at host-call #{}: log exhausted, got `{:?}`",
+                cursor, call
+            ),
+        }
+    }
+}
+
+impl<H: Host> Host for ReplayHost<H> {
+    fn exec_cmd(&mut self, cmd: &Command) {
+        self.replay(HostCall::Cmd(cmd.clone()));
+    }
+
+    fn exec_direct(&mut self, direct: &Direction, count: isize) {
+        self.replay(HostCall::Direct(*direct, count));
+    }
+
+    fn exec_turn(&mut self, degrees: f64) {
+        self.replay(HostCall::Turn(degrees));
+    }
+
+    fn exec_trap(&mut self, node: usize, ip: usize) {
+        self.replay(HostCall::Trap(node, ip));
+    }
+
+    fn exec_print(&mut self, value: isize) {
+        self.replay(HostCall::Print(value));
+    }
+
+    #[cfg(feature = "bigint")]
+    fn exec_print_big(&mut self, value: &BigInt) {
+        self.replay(HostCall::PrintBig(value.clone()));
+    }
+
+    fn exec_print_text(&mut self, text: &str) {
+        self.replay(HostCall::PrintText(text.to_string()));
+    }
+
+    fn exec_trace(&mut self, line: &str) {
+        self.replay(HostCall::Trace(line.to_string()));
+    }
+
+    fn exec_stamp(&mut self, position: (isize, isize), heading: f64) {
+        self.replay(HostCall::Stamp { position, heading });
+    }
+
+    fn exec_label(&mut self, label: &Label) {
+        self.replay(HostCall::Label(label.clone()));
+    }
+
+    fn turtle_shape_changed(&mut self, shape: &TurtleShape) {
+        self.replay(HostCall::ShapeChanged(shape.clone()));
+    }
+
+    fn play_tone(&mut self, freq: usize, ms: usize) {
+        self.replay(HostCall::Tone { freq, ms });
+    }
+
+    fn pen_color_changed(&mut self, color: Color) {
+        self.replay(HostCall::PenColor(color));
+    }
+
+    // the whole movement is replayed (and dispatched to the inner host) once it starts
+    fn movement_started(&mut self, movement: &Movement) {
+        self.replay(HostCall::Movement(movement.clone()));
+    }
+
+    fn state_restored(&mut self, turtle: &Turtle, pen: &Pen) {
+        self.replay(HostCall::StateRestored(turtle.clone(), pen.clone()));
+    }
+
+    fn kv_set(&mut self, key: &str, value: &str) {
+        self.replay(HostCall::KvSet {
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+    }
+
+    fn kv_get(&self, key: &str) -> Option<String> {
+        // the inner host answer is only reported by a divergence
+        let call = HostCall::KvGet {
+            key: key.to_string(),
+            value: self.inner.kv_get(key),
+        };
+
+        self.next_recorded(&call, |expected| match expected {
+            HostCall::KvGet { key: recorded, value } if recorded == key => Some(value.clone()),
+            _ => None,
+        })
+    }
+
+    fn open_stream(&mut self, name: &str) {
+        self.replay(HostCall::OpenStream(name.to_string()));
+    }
+
+    fn write_stream(&mut self, name: &str, text: &str) {
+        self.replay(HostCall::WriteStream {
+            stream: name.to_string(),
+            text: text.to_string(),
+        });
+    }
+
+    fn close_stream(&mut self, name: &str) {
+        self.replay(HostCall::CloseStream(name.to_string()));
+    }
+
+    fn screen_size(&self) -> (usize, usize) {
+        let (width, height) = self.inner.screen_size();
+        let call = HostCall::ScreenSize(width, height);
+
+        self.next_recorded(&call, |expected| match expected {
+            HostCall::ScreenSize(width, height) => Some((*width, *height)),
+            _ => None,
+        })
+    }
+
+    fn compilation_error(&mut self, error: &str) {
+        self.inner.compilation_error(error);
+    }
+}
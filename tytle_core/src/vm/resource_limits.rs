@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+// caps on the memory used by a program (unlimited when `None`), so a server running untrusted
+// programs can't be exhausted by one of them. exceeding a cap stops the execution with
+// `InterpreterException::ResourceLimit` (see `Memory::check_item` and `Memory::check_heap`)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceLimits {
+    // the approximate number of bytes held by the globals, the locals and the operand stacks
+    // altogether (see `MemoryValue::heap_size`)
+    pub max_heap: Option<usize>,
+
+    // the number of items of a list
+    pub max_list_len: Option<usize>,
+
+    // the number of characters of a string
+    pub max_str_len: Option<usize>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resource {
+    Heap,
+    ListLength,
+    StringLength,
+}
@@ -0,0 +1,15 @@
+use crate::ir::CfgNodeId;
+use crate::vm::{CallStack, Memory};
+
+use serde::{Deserialize, Serialize};
+
+// a point-in-time copy of the interpreter state (see `Interpreter::snapshot` and `Interpreter::restore`).
+// the snapshot doesn't include the compiled program, so it should be restored
+// only into an interpreter executing the same program it was taken from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VmSnapshot {
+    pub node_id: CfgNodeId,
+    pub ip: usize,
+    pub memory: Memory,
+    pub call_stack: CallStack,
+}
@@ -0,0 +1,23 @@
+use crate::ast::semantic::SymbolId;
+use crate::compat::prelude::*;
+use crate::lexer::Location;
+use core::fmt;
+
+// a frame of `Interpreter::stack_trace`, locating the instruction the frame is executing
+// (or the call it'll return to).
+// the location is `None` when the program hasn't been compiled with `debug_info`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackTraceFrame {
+    pub proc_id: SymbolId,
+    pub proc_name: String,
+    pub location: Option<Location>,
+}
+
+impl fmt::Display for StackTraceFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.location {
+            Some(location) => write!(f, "at {} ({})", self.proc_name, location),
+            None => write!(f, "at {}", self.proc_name),
+        }
+    }
+}
@@ -1,29 +1,57 @@
 use crate::ast::statement::Direction;
-use std::cmp;
+use crate::compat::math;
+use crate::vm::{Origin, Steering, TurtleConfig, TurtleShape, YAxis};
+use core::cmp;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Turtle {
     position: (isize, isize),
     visible: bool,
+
+    // in degrees, clockwise from the top of the canvas (normalized into `[0, 360)`).
+    // the turns are summed along with a compensation term (Neumaier), so the heading
+    // doesn't drift over thousands of small turns
+    #[serde(default)]
+    heading: f64,
+
+    #[serde(default)]
+    heading_error: f64,
+
+    #[serde(default)]
+    config: TurtleConfig,
+
+    #[serde(default)]
+    shape: TurtleShape,
 }
 
 impl Turtle {
     pub fn new() -> Self {
-        Self {
+        Self::with_config(TurtleConfig::default())
+    }
+
+    pub fn with_config(config: TurtleConfig) -> Self {
+        let mut turtle = Self {
             visible: true,
             position: (0, 0),
-        }
+            heading: 0.0,
+            heading_error: 0.0,
+            config,
+            shape: TurtleShape::default(),
+        };
+
+        turtle.turn(config.heading);
+        turtle
     }
 
     pub fn exec_direct(&mut self, direct: &Direction, count: isize) {
         match direct {
-            Direction::Forward => self.position.1 += count,
-            Direction::Backward => self.position.1 = cmp::max(self.position.1 - count, 0),
-            Direction::Right => self.position.0 += count,
-            Direction::Left => self.position.0 = cmp::max(self.position.0 - count, 0),
-            Direction::SetX => self.position.0 = cmp::max(count, 0),
-            Direction::SetY => self.position.1 = cmp::max(count, 0),
-            _ => unimplemented!(),
+            Direction::Forward => self.advance(self.degrees(), count),
+            Direction::Backward => self.advance(self.degrees(), -count),
+            Direction::Right => self.steer(count),
+            Direction::Left => self.steer(-count),
+            Direction::SetX => self.position.0 = self.clamp(count),
+            Direction::SetY => self.position.1 = self.clamp(count),
         };
     }
 
@@ -46,4 +74,93 @@ impl Turtle {
     pub fn ycor(&self) -> isize {
         self.position.1
     }
+
+    // the current heading (in the configured `AngleUnit`)
+    pub fn heading(&self) -> f64 {
+        self.config.in_unit(self.degrees())
+    }
+
+    // turns clockwise by `angle` (in the configured `AngleUnit`).
+    // the language counts are integers, but fractional turns are accumulated exactly as well
+    pub fn turn(&mut self, angle: f64) {
+        self.turn_degrees(self.config.to_degrees(angle));
+    }
+
+    // turns clockwise by `degrees`, whatever the configured `Steering` and `AngleUnit`
+    // (the stdlib prelude procedures draw their shapes by turning in degrees)
+    pub fn turn_degrees(&mut self, degrees: f64) {
+        self.add_heading(degrees);
+
+        // moving the whole turns out of the sum
+        let turns = math::floor(self.heading / 360.0);
+        self.add_heading(-turns * 360.0);
+    }
+
+    pub fn config(&self) -> &TurtleConfig {
+        &self.config
+    }
+
+    pub fn shape(&self) -> &TurtleShape {
+        &self.shape
+    }
+
+    pub fn set_shape(&mut self, shape: TurtleShape) {
+        self.shape = shape;
+    }
+
+    fn steer(&mut self, count: isize) {
+        match self.config.steering {
+            Steering::Strafe => self.advance(self.degrees() + 90.0, count),
+            Steering::Turn => self.turn(count as f64),
+        }
+    }
+
+    // the heading in degrees, rounded to a billionth of a degree so a sequence of turns
+    // adding up to whole turns (e.g `N` turns of `360 / N`) returns to the exact start
+    fn degrees(&self) -> f64 {
+        let degrees = math::round((self.heading + self.heading_error) * 1e9) / 1e9;
+
+        // adding `0.0` turns a `-0.0` into a `0.0`
+        math::rem_euclid(degrees, 360.0) + 0.0
+    }
+
+    // the Neumaier summation step
+    fn add_heading(&mut self, value: f64) {
+        let sum = self.heading + value;
+
+        if self.heading.abs() >= value.abs() {
+            self.heading_error += (self.heading - sum) + value;
+        } else {
+            self.heading_error += (value - sum) + self.heading;
+        }
+
+        self.heading = sum;
+    }
+
+    // moves `distance` along `angle` (in degrees), rounding to the nearest point.
+    // the coordinates saturate at the `isize` bounds (the float to int casts saturate as well)
+    fn advance(&mut self, angle: f64, distance: isize) {
+        let angle = angle.to_radians();
+        let distance = distance as f64;
+        let (sin, cos) = math::sin_cos(angle);
+        let dx = math::round(distance * sin) as isize;
+        let up = math::round(distance * cos) as isize;
+
+        let dy = match self.config.y_axis {
+            YAxis::Up => up,
+            YAxis::Down => -up,
+        };
+
+        self.position = (
+            self.clamp(self.position.0.saturating_add(dx)),
+            self.clamp(self.position.1.saturating_add(dy)),
+        );
+    }
+
+    fn clamp(&self, coord: isize) -> isize {
+        match self.config.origin {
+            Origin::Corner => cmp::max(coord, 0),
+            Origin::Center => coord,
+        }
+    }
 }
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+// where the point `(0, 0)` is
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Origin {
+    // the corner the axes grow from (the bottom-left one for an upward y axis,
+    // the top-left one for a downward y axis). the coordinates are clamped at `0`
+    Corner,
+
+    // the center of the canvas, the coordinates may go negative
+    Center,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum YAxis {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AngleUnit {
+    Degrees,
+    Radians,
+}
+
+// what `LEFT` / `RIGHT` do
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Steering {
+    // moving sideways (perpendicular to the heading) by the count
+    Strafe,
+
+    // turning by the count (in the configured `AngleUnit`), `RIGHT` turns clockwise
+    Turn,
+}
+
+// the coordinates convention of the turtle kept by the interpreter
+// (see `Interpreter::set_turtle_config`).
+//
+// the heading is measured clockwise from the top of the canvas, `FORWARD` moves along it.
+// the default is the classic tytle convention: the origin at the bottom-left corner,
+// `FORWARD` going up and `LEFT` / `RIGHT` moving sideways
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TurtleConfig {
+    pub origin: Origin,
+    pub y_axis: YAxis,
+    pub angle_unit: AngleUnit,
+
+    // the initial heading (in `angle_unit`)
+    pub heading: f64,
+    pub steering: Steering,
+}
+
+impl Default for TurtleConfig {
+    fn default() -> Self {
+        Self {
+            origin: Origin::Corner,
+            y_axis: YAxis::Up,
+            angle_unit: AngleUnit::Degrees,
+            heading: 0.0,
+            steering: Steering::Strafe,
+        }
+    }
+}
+
+impl TurtleConfig {
+    pub fn to_degrees(&self, angle: f64) -> f64 {
+        match self.angle_unit {
+            AngleUnit::Degrees => angle,
+            AngleUnit::Radians => angle.to_degrees(),
+        }
+    }
+
+    // converts an angle in degrees into the configured unit
+    pub fn in_unit(&self, degrees: f64) -> f64 {
+        match self.angle_unit {
+            AngleUnit::Degrees => degrees,
+            AngleUnit::Radians => degrees.to_radians(),
+        }
+    }
+}
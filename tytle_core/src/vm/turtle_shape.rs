@@ -0,0 +1,51 @@
+use crate::compat::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// the prefix of the shapes registered by the embedder (see `Interpreter::register_shape`)
+pub const IMAGE_SHAPE_PREFIX: &str = "image:";
+
+// the shape the turtle is drawn with (see `SETSHAPE`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TurtleShape {
+    // the default cursor
+    #[default]
+    Arrow,
+    Triangle,
+    Circle,
+
+    // a shape registered by the embedder under this name (e.g a rocket sprite)
+    Image(String),
+}
+
+impl TurtleShape {
+    // parses the name given to `SETSHAPE` (`arrow`, `triangle`, `circle` or `image:<name>`).
+    // the built-in names are case insensitive, while the image names are kept as is
+    pub fn parse(name: &str) -> Option<Self> {
+        let prefix = name.get(..IMAGE_SHAPE_PREFIX.len()).unwrap_or("");
+
+        if prefix.eq_ignore_ascii_case(IMAGE_SHAPE_PREFIX) {
+            let image = &name[IMAGE_SHAPE_PREFIX.len()..];
+
+            return match image.is_empty() {
+                true => None,
+                false => Some(TurtleShape::Image(image.to_string())),
+            };
+        }
+
+        match name.to_ascii_lowercase().as_str() {
+            "arrow" => Some(TurtleShape::Arrow),
+            "triangle" => Some(TurtleShape::Triangle),
+            "circle" => Some(TurtleShape::Circle),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            TurtleShape::Arrow => "arrow".to_string(),
+            TurtleShape::Triangle => "triangle".to_string(),
+            TurtleShape::Circle => "circle".to_string(),
+            TurtleShape::Image(image) => format!("{}{}", IMAGE_SHAPE_PREFIX, image),
+        }
+    }
+}
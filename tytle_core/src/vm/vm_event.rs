@@ -0,0 +1,23 @@
+use crate::ast::semantic::SymbolId;
+use crate::ast::statement::Direction;
+use crate::ir::CfgNodeId;
+use crate::vm::MemoryValue;
+
+// structured events emitted by the interpreter to the observers registered via `Interpreter::on_event`
+#[derive(Debug, Clone, PartialEq)]
+pub enum VmEvent {
+    VarWrite {
+        var_id: SymbolId,
+        global: bool,
+        value: MemoryValue,
+    },
+    ProcEnter(SymbolId),
+    ProcExit(SymbolId),
+    // emitted when entering the head of a loop (`node_id`), once per iteration
+    LoopIteration(CfgNodeId),
+    TurtleMove {
+        direct: Direction,
+        count: isize,
+        position: (isize, isize),
+    },
+}
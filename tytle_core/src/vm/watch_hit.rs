@@ -0,0 +1,20 @@
+use crate::ast::semantic::SymbolId;
+use crate::compat::prelude::*;
+use crate::ir::CfgNodeId;
+use crate::vm::MemoryValue;
+
+// reported (as `InterpreterException::Watchpoint`) when the interpreter writes a watched variable.
+// the write has already been applied, so execution can be resumed right after it
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchHit {
+    pub var_id: SymbolId,
+    pub old_value: MemoryValue,
+    pub new_value: MemoryValue,
+
+    // the location of the `Store` instruction
+    pub node_id: CfgNodeId,
+    pub ip: usize,
+
+    // the procedures of the call stack frames, the innermost frame is last
+    pub call_stack: Vec<SymbolId>,
+}
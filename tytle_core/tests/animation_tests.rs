@@ -0,0 +1,409 @@
+extern crate tytle;
+
+use std::collections::HashMap;
+use tytle::animation::{
+    encode_apng, encode_gif, AnimationHost, EncodeError, Framebuffer, BACKGROUND, INK,
+};
+use tytle::prelude::{DummyHost, RunOptions};
+
+fn animate(code: &str, every: usize) -> AnimationHost<DummyHost> {
+    let mut host = AnimationHost::new(DummyHost::new(), 20, 20).every(every);
+    tytle::run(code, &mut host, RunOptions::default()).unwrap();
+    host.finish();
+
+    host
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> usize {
+    usize::from(bytes[at]) | usize::from(bytes[at + 1]) << 8
+}
+
+fn read_u32_be(bytes: &[u8], at: usize) -> u32 {
+    u32::from_be_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+// decodes the pixels of the GIF images (assuming the layout written by `encode_gif`)
+fn decode_gif(bytes: &[u8]) -> Vec<Vec<u8>> {
+    // the header, the screen descriptor, the color table and the NETSCAPE extension
+    let mut at = 6 + 7 + 6 + 19;
+    let mut images = Vec::new();
+
+    while bytes[at] != 0x3B {
+        // the graphic control extension and the image descriptor
+        at += 8 + 10;
+
+        let min_code_size = u32::from(bytes[at]);
+        at += 1;
+
+        let mut data = Vec::new();
+
+        while bytes[at] != 0 {
+            let len = usize::from(bytes[at]);
+            data.extend_from_slice(&bytes[at + 1..at + 1 + len]);
+            at += 1 + len;
+        }
+
+        at += 1;
+        images.push(lzw_decode(&data, min_code_size));
+    }
+
+    images
+}
+
+fn lzw_decode(data: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear = 1usize << min_code_size;
+    let end = clear + 1;
+
+    let mut pixels = Vec::new();
+    let mut table: HashMap<usize, Vec<u8>> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut code_size = min_code_size + 1;
+    let mut prev: Option<Vec<u8>> = None;
+    let mut bit = 0;
+
+    loop {
+        let mut code = 0;
+
+        for i in 0..code_size as usize {
+            let byte = data[(bit + i) / 8];
+            code |= usize::from((byte >> ((bit + i) % 8)) & 1) << i;
+        }
+
+        bit += code_size as usize;
+
+        if code == clear {
+            table.clear();
+            next_code = end + 1;
+            code_size = min_code_size + 1;
+            prev = None;
+            continue;
+        }
+
+        if code == end {
+            return pixels;
+        }
+
+        let entry = if code < clear {
+            vec![code as u8]
+        } else if let Some(entry) = table.get(&code) {
+            entry.clone()
+        } else {
+            let mut entry = prev.clone().unwrap();
+            entry.push(entry[0]);
+            entry
+        };
+
+        if let Some(mut prev) = prev {
+            prev.push(entry[0]);
+            table.insert(next_code, prev);
+            next_code += 1;
+
+            if next_code == 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+        }
+
+        pixels.extend_from_slice(&entry);
+        prev = Some(entry);
+    }
+}
+
+// the kinds of the PNG chunks, checking their CRCs
+fn png_chunks(bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut at = 8;
+    let mut chunks = Vec::new();
+
+    while at < bytes.len() {
+        let len = read_u32_be(bytes, at) as usize;
+        let kind = String::from_utf8(bytes[at + 4..at + 8].to_vec()).unwrap();
+        let data = bytes[at + 8..at + 8 + len].to_vec();
+
+        let crc = read_u32_be(bytes, at + 8 + len);
+        assert_eq!(
+            crc32(&bytes[at + 4..at + 8 + len]),
+            crc,
+            "the CRC of `{}`",
+            kind
+        );
+
+        chunks.push((kind, data));
+        at += 12 + len;
+    }
+
+    chunks
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in data {
+        crc ^= u32::from(byte);
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+// inflates a zlib stream of stored blocks
+fn inflate_stored(data: &[u8]) -> Vec<u8> {
+    let mut at = 2;
+    let mut inflated = Vec::new();
+
+    loop {
+        let last = data[at] & 1 == 1;
+        assert_eq!(0, data[at] >> 1, "only stored blocks are expected");
+
+        let len = read_u16(data, at + 1);
+        inflated.extend_from_slice(&data[at + 5..at + 5 + len]);
+        at += 5 + len;
+
+        if last {
+            return inflated;
+        }
+    }
+}
+
+#[test]
+fn framebuffer_draws_lines_with_y_going_up() {
+    let mut framebuffer = Framebuffer::new(5, 4);
+
+    framebuffer.draw_line((0, 0), (3, 3), INK);
+    framebuffer.draw_line((4, 0), (4, 10), INK);
+
+    assert_eq!(Some(INK), framebuffer.pixel(0, 0));
+    assert_eq!(Some(INK), framebuffer.pixel(2, 2));
+    assert_eq!(Some(INK), framebuffer.pixel(4, 3));
+    assert_eq!(Some(BACKGROUND), framebuffer.pixel(1, 0));
+    assert_eq!(None, framebuffer.pixel(5, 0));
+
+    // the bottom-left pixel is the first one of the last row
+    assert_eq!(INK, framebuffer.pixels()[3 * 5]);
+
+    framebuffer.clear();
+    assert!(framebuffer
+        .pixels()
+        .iter()
+        .all(|&pixel| pixel == BACKGROUND));
+}
+
+#[test]
+fn framebuffer_clips_lines_reaching_far_beyond_it() {
+    let mut framebuffer = Framebuffer::new(5, 4);
+
+    framebuffer.draw_line((2, 0), (2, isize::MAX), INK);
+    framebuffer.draw_line((isize::MIN, 1), (isize::MAX, 1), INK);
+    framebuffer.draw_line((isize::MIN, isize::MIN), (isize::MIN, isize::MAX), INK);
+
+    assert_eq!(Some(INK), framebuffer.pixel(2, 0));
+    assert_eq!(Some(INK), framebuffer.pixel(2, 3));
+    assert_eq!(Some(INK), framebuffer.pixel(0, 1));
+    assert_eq!(Some(INK), framebuffer.pixel(4, 1));
+
+    let inked = framebuffer.pixels().iter().filter(|&&pixel| pixel == INK).count();
+    assert_eq!(4 + 5 - 1, inked);
+
+    framebuffer.clear();
+    framebuffer.draw_line((isize::MIN, isize::MIN), (isize::MAX, isize::MAX), INK);
+
+    let diagonal = (0..4).all(|i| framebuffer.pixel(i, i) == Some(INK));
+    let inked = framebuffer.pixels().iter().filter(|&&pixel| pixel == INK).count();
+    assert!(diagonal);
+    assert_eq!(4, inked);
+}
+
+#[test]
+fn animation_host_captures_a_frame_every_n_drawing_events() {
+    let code = r#"
+        FORWARD 5
+        PENUP
+        RIGHT 5
+        PENDOWN
+        BACKWARD 5
+        RIGHT 5
+    "#;
+
+    let host = animate(code, 2);
+    let frames = host.frames();
+
+    // 3 drawing events (the pen-up move doesn't draw): a frame after the 2nd and a final one
+    assert_eq!(2, frames.len());
+
+    assert_eq!(Some(INK), frames[0].pixel(0, 5));
+    assert_eq!(Some(INK), frames[0].pixel(5, 0));
+    assert_eq!(Some(BACKGROUND), frames[0].pixel(8, 0));
+    assert_eq!(Some(INK), frames[1].pixel(8, 0));
+    assert_eq!(&frames[1], host.framebuffer());
+
+    assert_eq!(vec!["PENUP", "PENDOWN"], host.inner().get_log());
+}
+
+#[test]
+fn animation_host_erases_and_cleans() {
+    let code = r#"
+        FORWARD 5
+        PENERASE
+        BACKWARD 2
+        CLEAN
+    "#;
+
+    let frames = animate(code, 1).frames().to_vec();
+
+    assert_eq!(3, frames.len());
+    assert_eq!(Some(INK), frames[0].pixel(0, 4));
+    assert_eq!(Some(BACKGROUND), frames[1].pixel(0, 4));
+    assert_eq!(Some(INK), frames[1].pixel(0, 2));
+    assert_eq!(Framebuffer::new(20, 20), frames[2]);
+}
+
+#[test]
+fn animation_host_finish_captures_a_blank_frame_of_an_empty_drawing() {
+    let frames = animate("PRINT 1", 3).frames().to_vec();
+
+    assert_eq!(vec![Framebuffer::new(20, 20)], frames);
+}
+
+#[test]
+fn encode_gif_roundtrips_the_frames() {
+    let code = r#"
+        REPEAT 4 [
+            FORWARD 19
+            RIGHT 19
+            BACKWARD 19
+            RIGHT 1
+        ]
+    "#;
+
+    let host = animate(code, 3);
+    let gif = host.to_gif(120).unwrap();
+
+    assert_eq!(b"GIF89a", &gif[..6]);
+    assert_eq!((20, 20), (read_u16(&gif, 6), read_u16(&gif, 8)));
+    assert_eq!(b"NETSCAPE2.0", &gif[22..33]);
+    assert_eq!(0x3B, gif[gif.len() - 1]);
+
+    // the delay (in hundredths of a second) of the first frame
+    assert_eq!(12, read_u16(&gif, 42));
+
+    let images = decode_gif(&gif);
+    let expected = host
+        .frames()
+        .iter()
+        .map(|frame| frame.pixels().to_vec())
+        .collect::<Vec<_>>();
+
+    assert_eq!(6, images.len());
+    assert_eq!(expected, images);
+}
+
+#[test]
+fn encode_gif_resets_a_full_code_table() {
+    // a noisy frame, overflowing the 4096 codes of the LZW table
+    let mut frame = Framebuffer::new(200, 200);
+    let mut seed: u32 = 7;
+
+    for y in 0..200 {
+        for x in 0..200 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+
+            if (seed >> 16) & 1 == 1 {
+                frame.draw_line((x, y), (x, y), INK);
+            }
+        }
+    }
+
+    let images = decode_gif(&encode_gif(&[frame.clone()], 50).unwrap());
+
+    assert_eq!(vec![frame.pixels().to_vec()], images);
+}
+
+#[test]
+fn encode_apng_writes_a_frame_per_capture() {
+    let host = animate("FORWARD 10\nRIGHT 10\nBACKWARD 10", 1);
+    let apng = host.to_apng(250).unwrap();
+
+    assert_eq!(
+        &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A],
+        &apng[..8]
+    );
+
+    let chunks = png_chunks(&apng);
+    let kinds = chunks
+        .iter()
+        .map(|(kind, _)| kind.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec!["IHDR", "PLTE", "acTL", "fcTL", "IDAT", "fcTL", "fdAT", "fcTL", "fdAT", "IEND"],
+        kinds
+    );
+
+    // 3 frames, looping forever
+    assert_eq!(
+        (3, 0),
+        (read_u32_be(&chunks[2].1, 0), read_u32_be(&chunks[2].1, 4))
+    );
+
+    // the sequence numbers and the delay (250/1000 of a second)
+    assert_eq!(0, read_u32_be(&chunks[3].1, 0));
+    assert_eq!([0, 250, 3, 232], chunks[3].1[20..24]);
+    assert_eq!(1, read_u32_be(&chunks[5].1, 0));
+    assert_eq!(2, read_u32_be(&chunks[6].1, 0));
+    assert_eq!(4, read_u32_be(&chunks[8].1, 0));
+
+    let last = inflate_stored(&chunks[8].1[4..]);
+    let rows = last.chunks(21).map(|row| row.to_vec()).collect::<Vec<_>>();
+    let expected = host.frames()[2]
+        .pixels()
+        .chunks(20)
+        .map(|row| [&[0], row].concat())
+        .collect::<Vec<_>>();
+
+    assert_eq!(expected, rows);
+}
+
+#[test]
+fn encode_apng_splits_large_frames_into_stored_blocks() {
+    let frame = Framebuffer::new(300, 300);
+    let apng = encode_apng(&[frame], 100).unwrap();
+    let chunks = png_chunks(&apng);
+
+    let image = inflate_stored(&chunks[4].1);
+
+    assert_eq!(301 * 300, image.len());
+    assert!(image.iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn encoders_reject_frames_they_cannot_encode() {
+    assert_eq!(Err(EncodeError::NoFrames), encode_gif(&[], 100));
+    assert_eq!(Err(EncodeError::NoFrames), encode_apng(&[], 100));
+
+    // a GIF size is a 16 bits number
+    let too_large = EncodeError::FrameTooLarge {
+        width: 70_000,
+        height: 1,
+    };
+    let frames = [Framebuffer::new(70_000, 1)];
+    assert_eq!(Err(too_large), encode_gif(&frames, 100));
+    assert!(encode_apng(&frames, 100).is_ok());
+
+    let frames = [Framebuffer::new(10, 10), Framebuffer::new(10, 20)];
+    let mismatch = EncodeError::FrameSizeMismatch { index: 1 };
+    assert_eq!(Err(mismatch.clone()), encode_gif(&frames, 100));
+    assert_eq!(Err(mismatch), encode_apng(&frames, 100));
+}
+
+#[test]
+fn animation_host_has_no_frames_before_the_first_capture() {
+    let host = AnimationHost::new(DummyHost::new(), 20, 20);
+
+    assert_eq!(Err(EncodeError::NoFrames), host.to_gif(100));
+    assert_eq!(Err(EncodeError::NoFrames), host.to_apng(100));
+}
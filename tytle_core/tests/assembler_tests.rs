@@ -0,0 +1,182 @@
+extern crate tytle;
+
+use tytle::ast::semantic::Environment;
+use tytle::compiler::*;
+use tytle::ir::*;
+use tytle::vm::*;
+
+// compiles `code` for its symbols (the variables and procedures the fixtures refer to)
+fn compile(code: &str) -> CompiledProgram {
+    let options = CompileOptions {
+        debug_info: true,
+        ..Default::default()
+    };
+
+    Compiler::compile(code, options).unwrap()
+}
+
+fn run(env: &Environment, cfg: &CfgObject) -> (Vec<String>, (isize, isize)) {
+    let mut host = DummyHost::new();
+
+    let mut intr = Interpreter::new(cfg, env, &mut host);
+    intr.exec_code().unwrap();
+
+    (host.get_log(), host.xycors())
+}
+
+#[test]
+fn assemble_disassembled_program() {
+    let code = r#"
+        TO SQUARE(N: INT): INT
+            MAKELOCAL M = N * N
+            RETURN M
+        END
+
+        MAKEGLOBAL A = SQUARE(3)
+
+        REPEAT 2 [
+            IF A > 5 [
+                FORWARD A
+            ]
+        ]
+
+        CATCH "ERROR [
+            THROW "OOPS
+        ]
+
+        PRINT "Hello
+    "#;
+
+    let program = compile(code);
+    let debug_info = program.debug_info.as_ref().unwrap();
+
+    let listing = program.cfg.disassemble_with_debug_info(debug_info);
+    let cfg = CfgObject::assemble(&listing, &program.env).unwrap();
+
+    assert_eq!(listing, cfg.disassemble_with_debug_info(debug_info));
+    assert_eq!(program.cfg.disassemble(), cfg.disassemble());
+    assert_eq!(program.cfg.procs, cfg.procs);
+
+    assert_eq!(run(&program.env, &program.cfg), run(&program.env, &cfg));
+}
+
+#[test]
+fn assemble_symbol_ids() {
+    let program = compile("MAKEGLOBAL A = 1\nPRINT A");
+
+    let cfg = CfgObject::assemble(&program.cfg.disassemble(), &program.env).unwrap();
+
+    assert_eq!(program.cfg.disassemble(), cfg.disassemble());
+}
+
+#[test]
+fn assemble_fixture_for_interpreter() {
+    let program = compile("MAKEGLOBAL A = 0");
+
+    let listing = r#"
+        proc __main__ (entry @1)
+          @1:
+               0  int 10           ; A = 10
+               1  store A
+               2  load A
+               3  int 5
+               4  greater_than
+            -> @2 when_true (A > 5)
+            -> @3 fallback
+          @2:
+               0  str "big;"
+               1  print
+            -> @3 always
+          @3:
+               0  load A
+               1  direction Forward
+               2  eoc
+    "#;
+
+    let cfg = CfgObject::assemble(listing, &program.env).unwrap();
+
+    assert_eq!(Some("A > 5"), cfg.graph.get_node(1).cond.as_ref().map(|c| c.text.as_str()));
+    assert_eq!((vec!["big;".to_string()], (0, 10)), run(&program.env, &cfg));
+}
+
+#[test]
+fn assemble_fixture_for_optimizer() {
+    let program = compile("MAKEGLOBAL A = 0\nMAKEGLOBAL B = 0");
+
+    let listing = r#"
+        proc __main__ (entry @1)
+          @1:
+               0  int 2
+               1  store A
+               2  load A
+               3  store B
+               4  load B
+               5  print
+               6  eoc
+    "#;
+
+    let mut cfg = CfgObject::assemble(listing, &program.env).unwrap();
+
+    let expected = r#"
+        proc __main__ (entry @1)
+          @1:
+               0  int 2
+               1  store A
+               2  int 2
+               3  store B
+               4  int 2
+               5  print
+               6  eoc
+    "#;
+
+    let expected = CfgObject::assemble(expected, &program.env).unwrap();
+
+    let rewrites = CopyPropagation::new().optimize(&mut cfg);
+
+    assert_eq!(2, rewrites);
+    assert_eq!(expected.disassemble(), cfg.disassemble());
+    assert_eq!(vec!["2".to_string()], run(&program.env, &cfg).0);
+}
+
+#[test]
+fn assemble_errors() {
+    let program = compile("MAKEGLOBAL A = 0");
+    let env = &program.env;
+
+    let error = |line: usize, msg: &str| {
+        Some(IrError::InvalidListing {
+            line,
+            msg: msg.to_string(),
+        })
+    };
+
+    assert_eq!(
+        error(1, "expected a node (e.g `@1:`)"),
+        CfgObject::assemble("0  int 1", env).err()
+    );
+
+    assert_eq!(
+        error(3, "unknown instruction `jump`"),
+        CfgObject::assemble("proc __main__ (entry @1)\n@1:\n0  jump", env).err()
+    );
+
+    assert_eq!(
+        error(3, "expected instruction 0"),
+        CfgObject::assemble("proc __main__ (entry @1)\n@1:\n1  eoc", env).err()
+    );
+
+    assert_eq!(
+        error(3, "unknown variable `B`"),
+        CfgObject::assemble("proc __main__ (entry @1)\n@1:\n0  load B", env).err()
+    );
+
+    assert_eq!(
+        error(3, "missing node `@7`"),
+        CfgObject::assemble("proc __main__ (entry @1)\n@1:\n-> @7 always", env).err()
+    );
+
+    assert_eq!(
+        error(4, "node `@1` is listed twice"),
+        CfgObject::assemble("proc __main__ (entry @1)\n@1:\n0  eoc\n@1:", env).err()
+    );
+}
@@ -2,6 +2,7 @@ extern crate tytle;
 
 use tytle::ast::expression::*;
 use tytle::ast::semantic::*;
+use tytle::ast::statement::Statement;
 use tytle::parser::{Parser, TytleParser};
 
 macro_rules! assert_type_err {
@@ -182,13 +183,55 @@ fn ast_typecheck_error_cannot_order_bools() {
 }
 
 #[test]
-fn ast_typecheck_error_cannot_order_strings() {
+fn ast_typecheck_var_assign_strings_ordering() {
     let code = r#"
             MAKEGLOBAL A = "Hello" < "World"
         "#;
 
+    do_typecheck!(code, env);
+
+    let symbol = env.symbol_table.lookup(0, "A", &SymbolKind::Var);
+    let var_a = symbol.unwrap().as_var();
+    assert_eq!(var_a.var_type, Some(ExpressionType::Bool));
+}
+
+#[test]
+fn ast_typecheck_var_assign_eq_exprs() {
+    let code = r#"
+            MAKEGLOBAL A = 1 = 2
+            MAKEGLOBAL B = TRUE <> A
+            MAKEGLOBAL C = "Hello" = "World"
+        "#;
+
+    do_typecheck!(code, env);
+
+    for name in &["A", "B", "C"] {
+        let symbol = env.symbol_table.lookup(0, name, &SymbolKind::Var);
+        let var = symbol.unwrap().as_var();
+        assert_eq!(var.var_type, Some(ExpressionType::Bool));
+    }
+}
+
+#[test]
+fn ast_typecheck_error_cannot_compare_int_and_bool() {
+    let code = r#"
+            MAKEGLOBAL A = 1 = TRUE
+        "#;
+
+    let expected =
+        AstWalkError::InvalidBinaryOp(BinaryOp::Equal, ExpressionType::Int, ExpressionType::Bool);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_cannot_compare_str_and_int() {
+    let code = r#"
+            MAKEGLOBAL A = "1" <> 1
+        "#;
+
     let expected =
-        AstWalkError::InvalidBinaryOp(BinaryOp::LessThan, ExpressionType::Str, ExpressionType::Str);
+        AstWalkError::InvalidBinaryOp(BinaryOp::NotEqual, ExpressionType::Str, ExpressionType::Int);
 
     assert_type_err!(expected, code);
 }
@@ -243,6 +286,18 @@ fn ast_typecheck_error_if_stmt_expr_must_be_bool() {
     assert_type_err!(expected, code);
 }
 
+#[test]
+fn ast_typecheck_error_assert_stmt_expr_must_be_bool() {
+    let code = r#"
+            MAKEGLOBAL A = 10
+            ASSERT A + 1
+        "#;
+
+    let expected = AstWalkError::NotBooleanExpr("A + 1".to_string());
+
+    assert_type_err!(expected, code);
+}
+
 #[test]
 fn ast_typecheck_error_repeat_count_expr_must_be_int() {
     let code = r#"
@@ -357,6 +412,117 @@ fn ast_typecheck_error_proc_call_wrong_args_count() {
     assert_type_err!(expected, code);
 }
 
+#[test]
+fn ast_typecheck_proc_call_with_rest_args() {
+    let code = r#"
+            TO SUMALL(FIRST: INT, NUMS...: INT): INT
+                FOREACH NUMS [ MAKE FIRST = FIRST + ? ]
+                RETURN FIRST
+            END
+
+            MAKEGLOBAL A = SUMALL(1)
+            MAKEGLOBAL B = SUMALL(1, 2, 3)
+        "#;
+
+    do_typecheck!(code, env);
+
+    let proc = env.symbol_table.get_proc_by_name("SUMALL");
+
+    assert!(proc.rest);
+    assert_eq!(
+        vec![ExpressionType::Int, ExpressionType::list_of(ExpressionType::Int)],
+        proc.params_types
+    );
+}
+
+#[test]
+fn ast_typecheck_error_proc_call_too_few_args() {
+    let code = r#"
+            TO SUMALL(FIRST: INT, NUMS...: INT): INT
+                RETURN FIRST
+            END
+
+            MAKEGLOBAL A = SUMALL()
+        "#;
+
+    let expected = AstWalkError::TooFewProcCallArgs("SUMALL".to_string(), 1, 0);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_proc_call_rest_arg_type_mismatch() {
+    let code = r#"
+            TO SUMALL(FIRST: INT, NUMS...: INT): INT
+                RETURN FIRST
+            END
+
+            MAKEGLOBAL A = SUMALL(1, 2, "3")
+        "#;
+
+    let expected =
+        AstWalkError::InvalidProcCallArgType(3, ExpressionType::Int, ExpressionType::Str);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_proc_call_with_named_args() {
+    let code = r#"
+            TO POLY(N: INT, SIZE: INT, FILLED: BOOL)
+            END
+
+            POLY(SIZE=100, FILLED=TRUE, N=6)
+            POLY(6, FILLED=FALSE, SIZE=100)
+        "#;
+
+    do_typecheck!(code, env);
+}
+
+#[test]
+fn ast_typecheck_error_proc_call_named_args() {
+    let code = r#"
+            TO POLY(N: INT, SIZE: INT)
+            END
+
+            POLY(SIZE=100, SIDES=6)
+        "#;
+
+    let expected = AstWalkError::UnknownNamedArg("POLY".to_string(), "SIDES".to_string());
+    assert_type_err!(expected, code);
+
+    let code = r#"
+            TO POLY(N: INT, SIZE: INT)
+            END
+
+            POLY(6, SIZE=100, N=6)
+        "#;
+
+    let expected = AstWalkError::DuplicateNamedArg("POLY".to_string(), "N".to_string());
+    assert_type_err!(expected, code);
+
+    let code = r#"
+            TO POLY(N: INT, SIZE: INT)
+            END
+
+            POLY(SIZE=100)
+        "#;
+
+    let expected = AstWalkError::MissingProcCallArg("POLY".to_string(), "N".to_string());
+    assert_type_err!(expected, code);
+
+    let code = r#"
+            TO POLY(N: INT, SIZE: INT)
+            END
+
+            POLY(SIZE="100", N=6)
+        "#;
+
+    let expected =
+        AstWalkError::InvalidProcCallArgType(2, ExpressionType::Int, ExpressionType::Str);
+    assert_type_err!(expected, code);
+}
+
 #[test]
 fn ast_typecheck_error_proc_call_args_type_mismatch() {
     let code = r#"
@@ -373,6 +539,74 @@ fn ast_typecheck_error_proc_call_args_type_mismatch() {
     assert_type_err!(expected, code);
 }
 
+#[test]
+fn ast_typecheck_string_primitives_calls() {
+    let code = r#"
+            MAKEGLOBAL W = WORD("A", "B")
+            MAKEGLOBAL N = COUNT(W) + PARSE("2")
+        "#;
+
+    do_typecheck!(code, env);
+
+    let word = env.symbol_table.get_proc_by_name("WORD");
+    assert_eq!(Some(Primitive::Word), word.primitive);
+    assert!(word.builtin);
+}
+
+#[test]
+fn ast_typecheck_format_args_of_any_type() {
+    let code = r#"
+            MAKEGLOBAL S = FORMAT("~a~a~a", 1, "A", [TRUE])
+        "#;
+
+    do_typecheck!(code, env);
+
+    let format = env.symbol_table.get_proc_by_name("FORMAT");
+    assert_eq!(Some(Primitive::Format), format.primitive);
+    assert!(format.rest);
+}
+
+#[test]
+fn ast_typecheck_error_format_arg_has_no_value() {
+    let code = r#"
+            TO NOOP()
+            END
+
+            MAKEGLOBAL S = FORMAT("~a", NOOP())
+        "#;
+
+    let expected = AstWalkError::NotValueExpr("NOOP()".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_string_primitive_arg_type_mismatch() {
+    let code = r#"
+            MAKEGLOBAL C = ITEM("1", "ABC")
+        "#;
+
+    let expected =
+        AstWalkError::InvalidProcCallArgType(1, ExpressionType::Int, ExpressionType::Str);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_user_proc_shadows_string_primitive() {
+    let code = r#"
+            TO COUNT(A: INT, B: INT): INT
+                RETURN A + B
+            END
+
+            MAKEGLOBAL N = COUNT(1, 2)
+        "#;
+
+    do_typecheck!(code, env);
+
+    assert_eq!(None, env.symbol_table.get_proc_by_name("COUNT").primitive);
+}
+
 #[test]
 fn ast_typecheck_error_adding_int_and_proc_call_having_no_return_type() {
     let code = r#"
@@ -462,3 +696,477 @@ fn ast_typecheck_error_cannot_return_from_root_scope() {
 
     assert_type_err!(expected, code);
 }
+
+#[test]
+fn ast_typecheck_const_folded() {
+    let code = r#"
+            CONST SIDE = (1 + 2) * 5
+            CONST HALF = SIDE / 2
+            CONST BIG = NOT (HALF < 10)
+            CONST NAME = "SQUARE"
+            CONST ODD = HALF <> 14 / 2
+            CONST FIRST = NAME < "TRIANGLE"
+        "#;
+
+    do_typecheck!(code, env);
+
+    let const_value = |name| {
+        let symbol = env.symbol_table.lookup(0, name, &SymbolKind::Var);
+        symbol.unwrap().as_var().const_value.clone()
+    };
+
+    assert_eq!(Some(LiteralExpr::Int(15)), const_value("SIDE"));
+    assert_eq!(Some(LiteralExpr::Int(7)), const_value("HALF"));
+    assert_eq!(Some(LiteralExpr::Bool(false)), const_value("BIG"));
+    assert_eq!(Some(LiteralExpr::Str("SQUARE".to_string())), const_value("NAME"));
+    assert_eq!(Some(LiteralExpr::Bool(false)), const_value("ODD"));
+    assert_eq!(Some(LiteralExpr::Bool(true)), const_value("FIRST"));
+}
+
+#[test]
+fn ast_typecheck_error_const_of_var() {
+    let code = r#"
+            MAKEGLOBAL A = 10
+            CONST B = A + 1
+        "#;
+
+    let expected = AstWalkError::NotConstExpr("B".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_const_of_proc_call() {
+    let code = r#"
+            TO TEN(): INT
+                RETURN 10
+            END
+
+            CONST A = TEN()
+        "#;
+
+    let expected = AstWalkError::NotConstExpr("A".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_const_division_by_zero() {
+    let code = r#"
+            CONST A = 10 / 0
+        "#;
+
+    let expected = AstWalkError::NotConstExpr("A".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_const_type_mismatch() {
+    let code = r#"
+            CONST A = 1 + TRUE
+        "#;
+
+    let expected =
+        AstWalkError::InvalidBinaryOp(BinaryOp::Add, ExpressionType::Int, ExpressionType::Bool);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_type_queries_folded() {
+    let code = r#"
+            CONST NAME = ""
+            MAKEGLOBAL A = TYPEOF (1 + 2)
+            MAKEGLOBAL B = NUMBERP TRUE
+            MAKEGLOBAL C = WORDP NAME
+            MAKEGLOBAL D = LISTP 1
+            MAKEGLOBAL E = EMPTYP NAME
+            MAKEGLOBAL F = TYPEOF A
+        "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    assert!(AstTypeCheck::new(&mut env).check(&mut ast).is_ok());
+
+    let lits = ast.statements[1..7]
+        .iter()
+        .map(|stmt| match stmt {
+            Statement::Make(make_stmt) => env.folded_exprs.get(make_stmt.expr.node_id).cloned(),
+            _ => panic!(),
+        })
+        .collect::<Vec<_>>();
+
+    let expected = vec![
+        Some(LiteralExpr::Str("INT".to_string())),
+        Some(LiteralExpr::Bool(false)),
+        Some(LiteralExpr::Bool(true)),
+        Some(LiteralExpr::Bool(false)),
+        Some(LiteralExpr::Bool(true)),
+        Some(LiteralExpr::Str("STR".to_string())),
+    ];
+
+    assert_eq!(expected, lits);
+}
+
+#[test]
+fn ast_typecheck_records_expr_types_by_node_id() {
+    let code = r#"
+            MAKEGLOBAL A = (1 < 2)
+        "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    assert!(AstTypeCheck::new(&mut env).check(&mut ast).is_ok());
+
+    let expr = match ast.statements[0] {
+        Statement::Make(ref make_stmt) => &make_stmt.expr,
+        _ => panic!(),
+    };
+    let inner_expr = expr.as_parentheses_expr();
+
+    assert_eq!(&ExpressionType::Bool, env.expr_type(expr));
+    assert_eq!(&ExpressionType::Bool, env.expr_type(inner_expr));
+    assert_ne!(expr.node_id, inner_expr.node_id);
+}
+
+#[test]
+fn ast_typecheck_error_emptyp_of_non_const_str() {
+    let code = r#"
+            TO P(S: STR): BOOL
+                RETURN EMPTYP S
+            END
+        "#;
+
+    let expected = AstWalkError::NotConstStr(TypeQuery::EmptyP, "S".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_list_lit_and_map_expr() {
+    let code = r#"
+            TO COUNTS(W: STR): INT
+                RETURN COUNT(W)
+            END
+
+            MAKEGLOBAL WORDS = ["A", "BC"]
+            MAKEGLOBAL LENS = MAP("COUNTS", WORDS)
+            MAKEGLOBAL UPPERS = MAP("UPPERCASE", WORDS)
+        "#;
+
+    do_typecheck!(code, env);
+
+    let var_type = |name| {
+        let symbol = env.symbol_table.lookup(0, name, &SymbolKind::Var);
+        symbol.unwrap().as_var().var_type.clone()
+    };
+
+    assert_eq!(Some(ExpressionType::from("[STR]")), var_type("WORDS"));
+    assert_eq!(Some(ExpressionType::from("[INT]")), var_type("LENS"));
+    assert_eq!(Some(ExpressionType::from("[STR]")), var_type("UPPERS"));
+}
+
+#[test]
+fn ast_typecheck_error_foreach_var_has_the_list_elem_type() {
+    let code = r#"
+            MAKEGLOBAL S = ""
+
+            FOREACH ["A", "B"] [
+                MAKE S = WORD(S, ?)
+            ]
+
+            FOREACH ["A", "B"] [
+                MAKE S = ? + 1
+            ]
+        "#;
+
+    let expected =
+        AstWalkError::InvalidBinaryOp(BinaryOp::Add, ExpressionType::Str, ExpressionType::Int);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_empty_list() {
+    let code = r#"
+            MAKEGLOBAL L = []
+        "#;
+
+    assert_type_err!(AstWalkError::EmptyList, code);
+}
+
+#[test]
+fn ast_typecheck_error_list_items_type_mismatch() {
+    let code = r#"
+            MAKEGLOBAL L = [1, "A"]
+        "#;
+
+    let expected = AstWalkError::TypeMismatch(ExpressionType::Int, ExpressionType::Str);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_foreach_over_non_list() {
+    let code = r#"
+            FOREACH 1 + 2 [
+                PRINT ?
+            ]
+        "#;
+
+    let expected = AstWalkError::NotListExpr("1 + 2".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_map_proc_param_type_mismatch() {
+    let code = r#"
+            TO DOUBLE(X: INT): INT
+                RETURN X * 2
+            END
+
+            MAKEGLOBAL L = MAP("DOUBLE", ["A"])
+        "#;
+
+    let expected =
+        AstWalkError::InvalidProcCallArgType(1, ExpressionType::Int, ExpressionType::Str);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_print_has_no_value() {
+    let code = r#"
+            TO NOOP(X: INT)
+            END
+
+            PRINT NOOP(100)
+        "#;
+
+    let expected = AstWalkError::NotValueExpr("NOOP(100)".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_global_assigned_by_proc_before_declaration() {
+    let code = r#"
+            TO RESET()
+                MAKE A = 0
+            END
+
+            MAKEGLOBAL A = 10
+        "#;
+
+    let expected = AstWalkError::AssignBeforeDeclaration("A".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_global_used_by_its_declaration() {
+    let code = r#"
+            MAKEGLOBAL X = X
+        "#;
+
+    let expected = AstWalkError::VariableTypeMissing("X".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_map_proc_without_a_return_value() {
+    let code = r#"
+            TO WALK(X: INT)
+                FORWARD X
+            END
+
+            MAKEGLOBAL L = MAP("WALK", [1])
+        "#;
+
+    let expected = AstWalkError::NotValueExpr("MAP(\"WALK\", [1])".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_proc_ref_var() {
+    let code = r#"
+            TO SHOW(X: INT)
+                PRINT X
+            END
+
+            MAKEGLOBAL F = PROC("SHOW")
+            APPLY(F, 1)
+        "#;
+
+    do_typecheck!(code, env);
+
+    let symbol = env.symbol_table.lookup(0, "F", &SymbolKind::Var);
+    let var_f = symbol.unwrap().as_var();
+    assert_eq!(var_f.var_type, Some(ExpressionType::Proc));
+}
+
+#[test]
+fn ast_typecheck_error_apply_of_non_proc_expr() {
+    let code = r#"
+            MAKEGLOBAL F = "SHOW"
+            APPLY(F, 1)
+        "#;
+
+    let expected = AstWalkError::NotProcExpr("F".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_block_var() {
+    let code = r#"
+            MAKEGLOBAL B = [ FORWARD 10 ]
+            RUN B
+        "#;
+
+    do_typecheck!(code, env);
+
+    let symbol = env.symbol_table.lookup(0, "B", &SymbolKind::Var);
+    let var_b = symbol.unwrap().as_var();
+    assert_eq!(var_b.var_type, Some(ExpressionType::Block));
+}
+
+#[test]
+fn ast_typecheck_error_run_of_non_block_expr() {
+    let code = r#"
+            MAKEGLOBAL B = 1
+            RUN B
+        "#;
+
+    let expected = AstWalkError::NotBlockExpr("B".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_if_else_expr() {
+    let code = r#"
+            CONST LIMIT = IFELSE TRUE [10] [20]
+            MAKEGLOBAL S = IFELSE LIMIT > 5 ["BIG] ["SMALL]
+        "#;
+
+    do_typecheck!(code, env);
+
+    let symbol = env.symbol_table.lookup(0, "S", &SymbolKind::Var);
+    let var_s = symbol.unwrap().as_var();
+    assert_eq!(var_s.var_type, Some(ExpressionType::Str));
+
+    let symbol = env.symbol_table.lookup(0, "LIMIT", &SymbolKind::Var);
+    let var_limit = symbol.unwrap().as_var();
+    assert_eq!(var_limit.const_value, Some(LiteralExpr::Int(10)));
+}
+
+#[test]
+fn ast_typecheck_error_if_else_expr() {
+    let code = "MAKEGLOBAL X = IFELSE 1 [2] [3]";
+    let expected = AstWalkError::NotBooleanExpr("1".to_string());
+    assert_type_err!(expected, code);
+
+    let code = "MAKEGLOBAL X = IFELSE TRUE [2] [\"TWO]";
+    let expected = AstWalkError::TypeMismatch(ExpressionType::Int, ExpressionType::Str);
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_label_commands() {
+    let code = r#"
+            SETLABELFONT "MONO
+            SETLABELSIZE 10 * 2
+            LABEL WORD("A, "B)
+        "#;
+
+    do_typecheck!(code, env);
+}
+
+#[test]
+fn ast_typecheck_error_label_of_an_int() {
+    let code = r#"
+            LABEL 5
+        "#;
+
+    let expected = AstWalkError::TypeMismatch(ExpressionType::Str, ExpressionType::Int);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_label_size_of_a_string() {
+    let code = r#"
+            SETLABELSIZE "BIG
+        "#;
+
+    let expected = AstWalkError::TypeMismatch(ExpressionType::Int, ExpressionType::Str);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_set_shape_of_an_int() {
+    let code = r#"
+            SETSHAPE 3
+        "#;
+
+    let expected = AstWalkError::TypeMismatch(ExpressionType::Str, ExpressionType::Int);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_tone_duration_of_a_string() {
+    let code = r#"
+            TONE 440 "LONG
+        "#;
+
+    let expected = AstWalkError::TypeMismatch(ExpressionType::Int, ExpressionType::Str);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_color_var() {
+    let code = r#"
+            MAKEGLOBAL C = RGB 255 0 0
+            MAKE C = PALETTE(2)
+            SETPENCOLOR C
+            SETPENCOLOR 4
+            SETPENCOLOR "RED
+        "#;
+
+    do_typecheck!(code, env);
+
+    let symbol = env.symbol_table.lookup(0, "C", &SymbolKind::Var);
+    let var_c = symbol.unwrap().as_var();
+    assert_eq!(var_c.var_type, Some(ExpressionType::Color));
+}
+
+#[test]
+fn ast_typecheck_error_set_pen_color_of_a_bool() {
+    let code = r#"
+            SETPENCOLOR TRUE
+        "#;
+
+    let expected = AstWalkError::TypeMismatch(ExpressionType::Color, ExpressionType::Bool);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_rgb_of_a_string() {
+    let code = r#"
+            MAKEGLOBAL C = RGB 255 "GREEN 0
+        "#;
+
+    let expected =
+        AstWalkError::InvalidProcCallArgType(2, ExpressionType::Int, ExpressionType::Str);
+
+    assert_type_err!(expected, code);
+}
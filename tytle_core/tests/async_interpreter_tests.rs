@@ -0,0 +1,349 @@
+extern crate tytle;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+use tytle::ast::semantic::*;
+use tytle::ast::statement::{Command, Direction};
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::vm::*;
+
+// a future which is pending on its first poll, mimicking a host waiting on e.g an animation frame
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+struct YieldingHost {
+    inner: DummyHost,
+    yields: usize,
+    frames: usize,
+}
+
+impl YieldingHost {
+    fn new() -> Self {
+        Self {
+            inner: DummyHost::new(),
+            yields: 0,
+            frames: 0,
+        }
+    }
+
+    fn yield_then<'a>(&'a mut self, call: HostCall) -> HostFuture<'a> {
+        Box::pin(async move {
+            YieldOnce(false).await;
+            self.yields += 1;
+
+            call.dispatch(&mut self.inner);
+        })
+    }
+}
+
+impl AsyncHost for YieldingHost {
+    fn exec_cmd<'a>(&'a mut self, cmd: &'a Command) -> HostFuture<'a> {
+        self.yield_then(HostCall::Cmd(cmd.clone()))
+    }
+
+    fn exec_direct<'a>(&'a mut self, direct: &'a Direction, count: isize) -> HostFuture<'a> {
+        self.yield_then(HostCall::Direct(*direct, count))
+    }
+
+    fn exec_trap(&mut self, node: usize, ip: usize) -> HostFuture<'_> {
+        self.yield_then(HostCall::Trap(node, ip))
+    }
+
+    fn exec_print(&mut self, value: isize) -> HostFuture<'_> {
+        self.yield_then(HostCall::Print(value))
+    }
+
+    fn exec_print_text<'a>(&'a mut self, text: &'a str) -> HostFuture<'a> {
+        self.yield_then(HostCall::PrintText(text.to_string()))
+    }
+
+    fn exec_trace<'a>(&'a mut self, line: &'a str) -> HostFuture<'a> {
+        self.yield_then(HostCall::Trace(line.to_string()))
+    }
+
+    fn exec_stamp(&mut self, position: (isize, isize), heading: f64) -> HostFuture<'_> {
+        self.yield_then(HostCall::Stamp { position, heading })
+    }
+
+    fn exec_label<'a>(&'a mut self, label: &'a Label) -> HostFuture<'a> {
+        self.yield_then(HostCall::Label(label.clone()))
+    }
+
+    fn turtle_shape_changed<'a>(&'a mut self, shape: &'a TurtleShape) -> HostFuture<'a> {
+        self.yield_then(HostCall::ShapeChanged(shape.clone()))
+    }
+
+    fn play_tone(&mut self, freq: usize, ms: usize) -> HostFuture<'_> {
+        self.yield_then(HostCall::Tone { freq, ms })
+    }
+
+    fn pen_color_changed(&mut self, color: Color) -> HostFuture<'_> {
+        self.yield_then(HostCall::PenColor(color))
+    }
+
+    fn movement_started<'a>(&'a mut self, movement: &'a Movement) -> HostFuture<'a> {
+        self.yield_then(HostCall::Movement(movement.clone()))
+    }
+
+    fn movement_progress<'a>(
+        &'a mut self,
+        _movement: &'a Movement,
+        _frame: usize,
+        _pose: &'a TurtlePose,
+    ) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    fn movement_completed<'a>(&'a mut self, _movement: &'a Movement) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    fn state_restored<'a>(&'a mut self, turtle: &'a Turtle, pen: &'a Pen) -> HostFuture<'a> {
+        self.yield_then(HostCall::StateRestored(turtle.clone(), pen.clone()))
+    }
+
+    fn kv_set<'a>(&'a mut self, key: &'a str, value: &'a str) -> HostFuture<'a> {
+        let key = key.to_string();
+        let value = value.to_string();
+
+        self.yield_then(HostCall::KvSet { key, value })
+    }
+
+    fn kv_get(&self, key: &str) -> Option<String> {
+        self.inner.kv_get(key)
+    }
+
+    fn open_stream<'a>(&'a mut self, name: &'a str) -> HostFuture<'a> {
+        self.yield_then(HostCall::OpenStream(name.to_string()))
+    }
+
+    fn write_stream<'a>(&'a mut self, name: &'a str, text: &'a str) -> HostFuture<'a> {
+        let stream = name.to_string();
+        let text = text.to_string();
+
+        self.yield_then(HostCall::WriteStream { stream, text })
+    }
+
+    fn close_stream<'a>(&'a mut self, name: &'a str) -> HostFuture<'a> {
+        self.yield_then(HostCall::CloseStream(name.to_string()))
+    }
+
+    fn screen_size(&self) -> (usize, usize) {
+        self.inner.screen_size()
+    }
+
+    fn exec_yield(&mut self) -> HostFuture<'_> {
+        Box::pin(async move {
+            YieldOnce(false).await;
+            self.frames += 1;
+        })
+    }
+}
+
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = Box::pin(fut);
+    let mut cx = Context::from_waker(Waker::noop());
+
+    loop {
+        if let Poll::Ready(v) = fut.as_mut().poll(&mut cx) {
+            return v;
+        }
+    }
+}
+
+macro_rules! setup_async_interpreter {
+    ($code: expr, $env: ident, $cfg: ident, $intr: ident) => {
+        let mut ast = TytleParser.parse($code).unwrap();
+        let generator = SymbolTableGenerator::new();
+
+        let mut $env = generator.generate(&mut ast).unwrap();
+        let mut checker = AstTypeCheck::new(&mut $env);
+
+        let res = checker.check(&mut ast);
+        assert!(res.is_ok());
+
+        let builder = CfgBuilder::new(&mut $env);
+        let $cfg = builder.build(&ast);
+
+        let mut $intr = Interpreter::new_async(&$cfg, &$env);
+    };
+}
+
+#[test]
+pub fn async_interpreter_awaits_host_calls() {
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            RETURN N * 2
+        END
+
+        FORWARD DOUBLE(5)
+        RIGHT 3
+        PENUP
+        PRINT DOUBLE(7)
+    "#;
+
+    setup_async_interpreter!(code, env, cfg, intr);
+
+    let mut host = YieldingHost::new();
+    let res = block_on(intr.exec_async(&mut host));
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(4, host.yields);
+    assert_eq!(vec!["PENUP", "14"], host.inner.get_log());
+    assert_eq!((3, 10), host.inner.xycors());
+}
+
+#[test]
+pub fn async_interpreter_store_and_retrieve() {
+    let code = r#"
+        STORE "SCORE 7
+        PRINT PARSE(RETRIEVE "SCORE) * 2
+    "#;
+
+    setup_async_interpreter!(code, env, cfg, intr);
+
+    let mut host = YieldingHost::new();
+    let res = block_on(intr.exec_async(&mut host));
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(2, host.yields);
+    assert_eq!(vec!["14"], host.inner.get_log());
+}
+
+#[test]
+pub fn async_interpreter_write_stream() {
+    let code = r#"
+        OPENWRITE "LOG
+        SETWRITE "LOG
+        PRINT 5
+        CLOSE "LOG
+    "#;
+
+    setup_async_interpreter!(code, env, cfg, intr);
+
+    let mut host = YieldingHost::new();
+    let res = block_on(intr.exec_async(&mut host));
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(3, host.yields);
+    assert_eq!(Some(&["5".to_string()][..]), host.inner.stream("LOG"));
+    assert_eq!(vec!["CLOSE LOG"], host.inner.get_log());
+}
+
+#[test]
+pub fn async_interpreter_executed_synchronously_has_no_host() {
+    setup_async_interpreter!("FORWARD 10", env, cfg, intr);
+    assert_eq!(Err(InterpreterException::NoHost), intr.exec_code());
+
+    setup_async_interpreter!("PRINT RETRIEVE \"SCORE", env, cfg, intr);
+    assert_eq!(Err(InterpreterException::NoHost), intr.exec_code());
+}
+
+#[test]
+pub fn async_interpreter_stack_overflow() {
+    let code = r#"
+        TO LOOP()
+            LOOP()
+        END
+
+        LOOP()
+    "#;
+
+    setup_async_interpreter!(code, env, cfg, intr);
+
+    let mut host = YieldingHost::new();
+    let res = block_on(intr.exec_async(&mut host));
+
+    assert_eq!(Err(InterpreterException::StackOverflow), res);
+}
+
+#[test]
+pub fn async_interpreter_yields_each_frame_when_throttled() {
+    let code = r#"
+        MAKEGLOBAL X = 0
+
+        REPEAT 5 [
+            MAKE X = X + 1
+        ]
+    "#;
+
+    setup_async_interpreter!(code, env, cfg, intr);
+
+    // an unthrottled frame runs the whole program
+    let steps = intr.exec_frame().unwrap().steps;
+
+    setup_async_interpreter!(code, env, cfg, intr);
+    intr.set_speed(3);
+
+    let mut host = YieldingHost::new();
+    let res = block_on(intr.exec_async(&mut host));
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(steps.div_ceil(3) - 1, host.frames);
+    assert_eq!(Ok(MemoryValue::Int(5)), intr.eval_expr("X"));
+}
+
+// a host implementing only the callbacks without a default
+struct CountingHost {
+    directs: usize,
+    prints: Vec<isize>,
+}
+
+impl AsyncHost for CountingHost {
+    fn exec_cmd<'a>(&'a mut self, _cmd: &'a Command) -> HostFuture<'a> {
+        Box::pin(async {})
+    }
+
+    fn exec_direct<'a>(&'a mut self, _direct: &'a Direction, _count: isize) -> HostFuture<'a> {
+        self.directs += 1;
+        Box::pin(async {})
+    }
+
+    fn exec_trap(&mut self, _node: usize, _ip: usize) -> HostFuture<'_> {
+        Box::pin(async {})
+    }
+
+    fn exec_print(&mut self, value: isize) -> HostFuture<'_> {
+        self.prints.push(value);
+        Box::pin(async {})
+    }
+}
+
+#[test]
+pub fn async_interpreter_with_a_host_implementing_only_the_required_callbacks() {
+    let code = r#"
+        SETPENCOLOR 2
+        FORWARD 10
+        STAMP
+        PRINT FORMAT("~A", 1)
+        PRINT 5
+    "#;
+
+    setup_async_interpreter!(code, env, cfg, intr);
+
+    let mut host = CountingHost {
+        directs: 0,
+        prints: Vec::new(),
+    };
+    let res = block_on(intr.exec_async(&mut host));
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(1, host.directs);
+    assert_eq!(vec![5], host.prints);
+}
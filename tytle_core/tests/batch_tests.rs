@@ -0,0 +1,94 @@
+extern crate tytle;
+
+use tytle::batch::{run_all, BatchOptions};
+use tytle::prelude::*;
+
+fn walk(steps: usize) -> String {
+    format!(
+        r#"
+        FORWARD {}
+        RIGHT {}
+    "#,
+        steps, steps
+    )
+}
+
+#[test]
+fn batch_run_all_keeps_programs_order() {
+    let programs = (1..=20).map(walk).collect::<Vec<_>>();
+
+    let options = BatchOptions {
+        threads: Some(4),
+        ..Default::default()
+    };
+
+    let outcomes = run_all(&programs, &options);
+    assert_eq!(20, outcomes.len());
+
+    for (i, outcome) in outcomes.iter().enumerate() {
+        let summary = outcome.result.as_ref().unwrap();
+
+        assert_eq!(2 * (i as u64 + 1), summary.path_length);
+
+        // the screen size query, followed by the two direction statements
+        assert_eq!(3, outcome.host_calls.len());
+    }
+}
+
+#[test]
+fn batch_run_all_isolates_programs() {
+    let programs = vec![
+        "FORWARD 10",
+        "REPEAT 1000000 [ FORWARD 1 ]",
+        "FORWARD",
+        "MAKEGLOBAL X = 5\nFORWARD X",
+    ];
+
+    let mut options = BatchOptions::default();
+    options.run.max_steps = Some(1000);
+
+    let outcomes = run_all(&programs, &options);
+
+    assert_eq!(10, outcomes[0].result.as_ref().unwrap().path_length);
+    assert_eq!(
+        Some(&TytleError::StepLimitExceeded(1000)),
+        outcomes[1].result.as_ref().err()
+    );
+    assert!(!outcomes[1].host_calls.is_empty());
+    assert!(matches!(outcomes[2].result, Err(TytleError::Parse { .. })));
+    assert!(outcomes[2].host_calls.is_empty());
+
+    // the globals of one program don't leak into the others
+    assert_eq!(5, outcomes[3].result.as_ref().unwrap().path_length);
+}
+
+#[test]
+fn batch_run_all_is_deterministic() {
+    let programs = (1..=8).map(walk).collect::<Vec<_>>();
+
+    let single = BatchOptions {
+        threads: Some(1),
+        ..Default::default()
+    };
+
+    let parallel = BatchOptions {
+        threads: Some(8),
+        ..Default::default()
+    };
+
+    let calls = |options: &BatchOptions| {
+        run_all(&programs, options)
+            .into_iter()
+            .map(|outcome| outcome.host_calls)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(calls(&single), calls(&parallel));
+}
+
+#[test]
+fn batch_run_all_no_programs() {
+    let programs: Vec<&str> = Vec::new();
+
+    assert!(run_all(&programs, &BatchOptions::default()).is_empty());
+}
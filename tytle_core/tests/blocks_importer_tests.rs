@@ -0,0 +1,150 @@
+extern crate tytle;
+
+use tytle::ast::semantic::*;
+use tytle::blocks::{BlocksImporter, ImportError};
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::vm::*;
+
+macro_rules! assert_imports_as {
+    ($json: expr, $code: expr) => {{
+        let expected = TytleParser.parse($code).unwrap();
+        let actual = BlocksImporter::import_json($json).unwrap();
+
+        assert_eq!(expected, actual);
+    }};
+}
+
+#[test]
+pub fn blocks_import_empty_program() {
+    assert_imports_as!(r#"{ "blocks": [] }"#, "");
+}
+
+#[test]
+pub fn blocks_import_motion_and_loops() {
+    let json = r#"
+    {
+        "blocks": [
+            { "type": "command", "name": "PENUP" },
+            { "type": "repeat", "times": 4, "body": [
+                { "type": "forward", "value": 100 },
+                { "type": "right", "value": 90 }
+            ] }
+        ]
+    }"#;
+
+    let code = r#"
+        PENUP
+        REPEAT 4 [
+            FORWARD 100
+            RIGHT 90
+        ]
+    "#;
+
+    assert_imports_as!(json, code);
+}
+
+#[test]
+pub fn blocks_import_variables_and_conditionals() {
+    let json = r#"
+    {
+        "blocks": [
+            { "type": "make_global", "name": "A", "value": 2 },
+            { "type": "make", "name": "A", "value": {
+                "type": "op", "op": "*",
+                "left": { "type": "parens", "value": { "type": "op", "op": "+", "left": { "type": "var", "name": "A" }, "right": 1 } },
+                "right": 3
+            } },
+            { "type": "if", "condition": { "type": "not", "value": false },
+              "then": [ { "type": "print", "value": { "type": "var", "name": "A" } } ],
+              "else": [ { "type": "sety", "value": 1 } ] }
+        ]
+    }"#;
+
+    let code = r#"
+        MAKEGLOBAL A = 2
+        MAKE A = (A + 1) * 3
+        IF NOT FALSE [PRINT A] [SETY 1]
+    "#;
+
+    assert_imports_as!(json, code);
+}
+
+#[test]
+pub fn blocks_import_procedures() {
+    let json = r#"
+    {
+        "blocks": [
+            { "type": "procedure", "name": "DOUBLE", "params": [{ "name": "N", "type": "INT" }], "returns": "INT", "body": [
+                { "type": "make_local", "name": "R", "value": { "type": "op", "op": "*", "left": { "type": "var", "name": "N" }, "right": 2 } },
+                { "type": "return", "value": { "type": "var", "name": "R" } }
+            ] },
+            { "type": "procedure", "name": "BOX", "params": [{ "name": "SIZE", "type": "INT" }], "body": [
+                { "type": "forward", "value": { "type": "var", "name": "SIZE" } }
+            ] },
+            { "type": "call", "name": "BOX", "args": [ { "type": "call", "name": "DOUBLE", "args": [5] } ] }
+        ]
+    }"#;
+
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            MAKELOCAL R = N * 2
+            RETURN R
+        END
+
+        TO BOX(SIZE: INT)
+            FORWARD SIZE
+        END
+
+        BOX(DOUBLE(5))
+    "#;
+
+    assert_imports_as!(json, code);
+}
+
+#[test]
+pub fn blocks_import_and_run() {
+    let json = r#"
+    {
+        "blocks": [
+            { "type": "repeat", "times": 3, "body": [
+                { "type": "forward", "value": 10 },
+                { "type": "right", "value": 5 }
+            ] }
+        ]
+    }"#;
+
+    let mut ast = BlocksImporter::import_json(json).unwrap();
+
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    assert!(AstTypeCheck::new(&mut env).check(&mut ast).is_ok());
+
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+
+    assert_eq!(Ok(()), intr.exec_code());
+    assert_eq!((15, 30), host.xycors());
+}
+
+#[test]
+pub fn blocks_import_errors() {
+    let res =
+        BlocksImporter::import_json(r#"{ "blocks": [ { "type": "command", "name": "JUMP" } ] }"#);
+    assert_eq!(Err(ImportError::UnknownCommand("JUMP".to_string())), res);
+
+    let json = r#"{ "blocks": [ { "type": "forward", "value": { "type": "op", "op": "-", "left": 1, "right": 2 } } ] }"#;
+    let res = BlocksImporter::import_json(json);
+    assert_eq!(Err(ImportError::UnknownOperator("-".to_string())), res);
+
+    let json = r#"{ "blocks": [ { "type": "procedure", "name": "P", "params": [{ "name": "N", "type": "FLOAT" }], "body": [] } ] }"#;
+    let res = BlocksImporter::import_json(json);
+    assert_eq!(Err(ImportError::UnknownType("FLOAT".to_string())), res);
+
+    let res = BlocksImporter::import_json(r#"{ "blocks": [ { "type": "jump" } ] }"#);
+    assert!(matches!(res, Err(ImportError::InvalidJson(..))));
+
+    let res = BlocksImporter::import_json("not json");
+    assert!(matches!(res, Err(ImportError::InvalidJson(..))));
+}
@@ -0,0 +1,458 @@
+#[macro_use]
+extern crate tytle;
+
+#[macro_use]
+extern crate maplit;
+
+use tytle::ast::semantic::*;
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+
+#[test]
+fn cfg_dominators_diamond() {
+    let graph = cfg_graph! {
+        node!(1),
+        node!(2),
+        node!(3),
+        node!(4),
+        node!(5),
+        edge_true_jmp!(1, 2),
+        edge_fallback_jmp!(1, 3),
+        edge_always_jmp!(2, 4),
+        edge_always_jmp!(3, 4)
+    };
+
+    let dom_tree = DominatorTree::new(&graph, 1);
+
+    assert_eq!(1, dom_tree.entry());
+    assert_eq!(None, dom_tree.idom(1));
+    assert_eq!(Some(1), dom_tree.idom(2));
+    assert_eq!(Some(1), dom_tree.idom(3));
+    assert_eq!(Some(1), dom_tree.idom(4));
+    assert_eq!(vec![2, 3, 4], dom_tree.children(1));
+
+    assert!(dom_tree.dominates(1, 4));
+    assert!(dom_tree.dominates(4, 4));
+    assert!(!dom_tree.dominates(2, 4));
+    assert_eq!(vec![4, 1], dom_tree.dominators(4));
+
+    // node `5` is unreachable
+    assert!(!dom_tree.is_reachable(5));
+    assert_eq!(None, dom_tree.idom(5));
+    assert!(dom_tree.dominators(5).is_empty());
+
+    let loop_info = LoopInfo::new(&graph, &dom_tree);
+    assert!(loop_info.back_edges().is_empty());
+    assert!(loop_info.loops().is_empty());
+}
+
+#[test]
+fn cfg_loops_nested() {
+    let graph = cfg_graph! {
+        node!(1),
+        node!(2),
+        node!(3),
+        node!(4),
+        node!(5),
+        edge_always_jmp!(1, 2),
+        edge_always_jmp!(2, 3),
+        edge_true_jmp!(3, 3),
+        edge_fallback_jmp!(3, 4),
+        edge_true_jmp!(4, 2),
+        edge_fallback_jmp!(4, 5)
+    };
+
+    let dom_tree = DominatorTree::new(&graph, 1);
+    assert_eq!(vec![1, 2, 3, 4, 5], dom_tree.nodes());
+    assert_eq!(Some(3), dom_tree.idom(4));
+    assert_eq!(Some(4), dom_tree.idom(5));
+
+    let loop_info = LoopInfo::new(&graph, &dom_tree);
+
+    assert_eq!(&[(3, 3), (4, 2)], loop_info.back_edges());
+    assert_eq!(vec![2, 3], loop_info.loop_headers());
+
+    let outer = &loop_info.loops()[0];
+    assert_eq!(2, outer.header);
+    assert_eq!(vec![4], outer.latches);
+    assert_eq!(hashset_of(&[2, 3, 4]), outer.body);
+
+    let inner = &loop_info.loops()[1];
+    assert_eq!(3, inner.header);
+    assert_eq!(vec![3], inner.latches);
+    assert_eq!(hashset_of(&[3]), inner.body);
+
+    assert!(loop_info.is_loop_header(2));
+    assert!(!loop_info.is_loop_header(4));
+
+    assert_eq!(0, loop_info.loop_depth(1));
+    assert_eq!(1, loop_info.loop_depth(4));
+    assert_eq!(2, loop_info.loop_depth(3));
+    assert_eq!(0, loop_info.loop_depth(5));
+}
+
+#[test]
+fn cfg_dominance_frontiers() {
+    let graph = cfg_graph! {
+        node!(1),
+        node!(2),
+        node!(3),
+        node!(4),
+        node!(5),
+        node!(6),
+        edge_always_jmp!(1, 2),
+        edge_true_jmp!(2, 3),
+        edge_fallback_jmp!(2, 4),
+        edge_always_jmp!(3, 5),
+        edge_always_jmp!(4, 5),
+        edge_true_jmp!(5, 2),
+        edge_fallback_jmp!(5, 6)
+    };
+
+    let dom_tree = DominatorTree::new(&graph, 1);
+    let frontiers = dom_tree.frontiers(&graph);
+
+    let frontier = |node_id| frontiers.get(&node_id).cloned().unwrap_or_default();
+
+    assert_eq!(btreeset![5], frontier(3));
+    assert_eq!(btreeset![5], frontier(4));
+
+    // the loop header `2` is the frontier of its body (itself included)
+    assert_eq!(btreeset![2], frontier(2));
+    assert_eq!(btreeset![2], frontier(5));
+
+    assert!(frontier(1).is_empty());
+    assert!(frontier(6).is_empty());
+}
+
+#[test]
+fn cfg_loops_compiled_repeat() {
+    let code = r#"
+        REPEAT 3 [
+            REPEAT 2 [FORWARD 1]
+            RIGHT 90
+        ]
+    "#;
+
+    let cfg = compile_cfg_obj!(code);
+    let entry_id = cfg.graph.get_entry_node_id();
+
+    let dom_tree = DominatorTree::new(&cfg.graph, entry_id);
+    let loop_info = LoopInfo::new(&cfg.graph, &dom_tree);
+
+    assert_eq!(2, loop_info.loops().len());
+
+    let outer = &loop_info.loops()[0];
+    let inner = &loop_info.loops()[1];
+
+    // the inner loop is nested in the outer one
+    assert!(inner.body.is_subset(&outer.body));
+    assert!(outer.body.len() > inner.body.len());
+
+    // each loop header dominates its whole body
+    for lp in loop_info.loops() {
+        assert!(lp.body.iter().all(|node_id| dom_tree.dominates(lp.header, *node_id)));
+    }
+}
+
+fn hashset_of(nodes: &[CfgNodeId]) -> std::collections::HashSet<CfgNodeId> {
+    nodes.iter().copied().collect()
+}
+
+fn hashset_of_symbols(ids: &[usize]) -> std::collections::HashSet<SymbolId> {
+    ids.iter().map(|id| SymbolId(*id)).collect()
+}
+
+#[test]
+fn cfg_definite_assignment_diamond() {
+    let graph = cfg_graph! {
+        node!(1),
+        node!(2, int_ins!(10), store_ins!(100)),
+        node!(3),
+        node!(4, load_ins!(100)),
+        edge_true_jmp!(1, 2),
+        edge_fallback_jmp!(1, 3),
+        edge_always_jmp!(2, 4),
+        edge_always_jmp!(3, 4)
+    };
+
+    let tracked = hashset_of_symbols(&[100]);
+    let analysis = DefiniteAssignment::new(&graph, 1, SymbolId(1), &tracked);
+
+    assert!(!analysis.is_sound());
+    assert_eq!(
+        &[UnassignedUse {
+            proc_id: SymbolId(1),
+            var_id: SymbolId(100),
+            use_node: 4,
+            decl_node: Some(2),
+        }],
+        analysis.unassigned_uses()
+    );
+}
+
+#[test]
+fn cfg_definite_assignment_both_branches() {
+    let graph = cfg_graph! {
+        node!(1),
+        node!(2, int_ins!(10), store_ins!(100)),
+        node!(3, int_ins!(20), store_ins!(100)),
+        node!(4, load_ins!(100), load_ins!(200)),
+        edge_true_jmp!(1, 2),
+        edge_fallback_jmp!(1, 3),
+        edge_always_jmp!(2, 4),
+        edge_always_jmp!(3, 4)
+    };
+
+    // variable `200` isn't tracked (a global for example)
+    let tracked = hashset_of_symbols(&[100]);
+    let analysis = DefiniteAssignment::new(&graph, 1, SymbolId(1), &tracked);
+
+    assert!(analysis.is_sound());
+}
+
+#[test]
+fn cfg_definite_assignment_loop() {
+    let graph = cfg_graph! {
+        node!(1, int_ins!(1), store_ins!(100)),
+        node!(2),
+        node!(3, load_ins!(100), load_ins!(200), int_ins!(2), store_ins!(200)),
+        node!(4),
+        edge_always_jmp!(1, 2),
+        edge_true_jmp!(2, 3),
+        edge_fallback_jmp!(2, 4),
+        edge_always_jmp!(3, 2)
+    };
+
+    // `200` is assigned only at the end of the loop body, so its first iteration loads it unassigned
+    let tracked = hashset_of_symbols(&[100, 200]);
+    let analysis = DefiniteAssignment::new(&graph, 1, SymbolId(1), &tracked);
+
+    assert_eq!(
+        &[UnassignedUse {
+            proc_id: SymbolId(1),
+            var_id: SymbolId(200),
+            use_node: 3,
+            decl_node: Some(3),
+        }],
+        analysis.unassigned_uses()
+    );
+}
+
+#[test]
+fn cfg_definite_assignment_compiled_program() {
+    let code = r#"
+        TO SUM(N: INT): INT
+            MAKELOCAL S = 0
+            MAKELOCAL I = 0
+            REPEAT N [
+                MAKE I = I + 1
+                MAKE S = S + I
+            ]
+            IF S > 10 [
+                MAKELOCAL BIG = 1
+                PRINT BIG
+            ]
+            RETURN S
+        END
+
+        PRINT SUM(5)
+    "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    assert!(AstTypeCheck::new(&mut env).check(&mut ast).is_ok());
+
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    assert!(DefiniteAssignment::analyze_program(&cfg, &env).is_empty());
+}
+
+fn unbalanced_procs(code: &str) -> Vec<String> {
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    assert!(AstTypeCheck::new(&mut env).check(&mut ast).is_ok());
+
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    let mut names = StateBalance::analyze_program(&cfg)
+        .into_iter()
+        .map(|proc_id| env.symbol_table.get_proc_by_id(proc_id).name.clone())
+        .collect::<Vec<_>>();
+
+    names.sort();
+    names
+}
+
+#[test]
+fn cfg_state_balance_balanced() {
+    let code = r#"
+        TO TREE(N: INT)
+            IF N > 0 [
+                PUSHSTATE
+                FORWARD N
+                TREE(N / 2)
+                POPSTATE
+                RIGHT N
+            ]
+        END
+
+        TO BRANCHES(N: INT): INT
+            REPEAT N [
+                PUSHSTATE
+                TREE(N)
+                POPSTATE
+            ]
+
+            PUSHSTATE
+            IF N > 2 [
+                POPSTATE
+                RETURN 1
+            ]
+            POPSTATE
+            RETURN 0
+        END
+
+        PRINT BRANCHES(3)
+    "#;
+
+    assert!(unbalanced_procs(code).is_empty());
+}
+
+#[test]
+fn cfg_state_balance_unbalanced() {
+    let code = r#"
+        TO LEAK(N: INT)
+            IF N > 0 [
+                PUSHSTATE
+            ]
+            POPSTATE
+        END
+
+        TO EARLY(N: INT): INT
+            PUSHSTATE
+            IF N > 0 [
+                RETURN 1
+            ]
+            POPSTATE
+            RETURN 0
+        END
+
+        TO GROWS(N: INT)
+            REPEAT N [
+                PUSHSTATE
+            ]
+        END
+
+        TO BALANCED()
+            PUSHSTATE
+            POPSTATE
+        END
+
+        LEAK(1)
+        PRINT EARLY(1)
+        GROWS(1)
+        BALANCED()
+        POPSTATE
+    "#;
+
+    let expected = vec!["EARLY", "GROWS", "LEAK", "__main__"];
+    assert_eq!(expected, unbalanced_procs(code));
+}
+
+// records the visited nodes, stores and edges
+struct EventsVisitor {
+    events: Vec<String>,
+}
+
+impl CfgVisitor for EventsVisitor {
+    fn on_node_start(&mut self, node_id: CfgNodeId) {
+        self.events.push(format!("node {}", node_id));
+    }
+
+    fn on_store(&mut self, _node_id: CfgNodeId, var_id: SymbolId) {
+        self.events.push(format!("store {}", var_id.0));
+    }
+
+    fn on_edge(&mut self, src_id: CfgNodeId, edge: &CfgEdge) {
+        self.events.push(format!("edge {} -> {}", src_id, edge.node_id));
+    }
+}
+
+#[test]
+fn cfg_visitor_reverse_postorder() {
+    let graph = cfg_graph! {
+        node!(1, int_ins!(10), store_ins!(100)),
+        node!(2),
+        node!(3, bool_ins!(true), store_ins!(200)),
+        node!(4),
+        node!(5),
+        edge_fallback_jmp!(1, 3),
+        edge_true_jmp!(1, 2),
+        edge_always_jmp!(2, 4),
+        edge_always_jmp!(3, 4),
+        edge_true_jmp!(4, 1)
+    };
+
+    let mut visitor = EventsVisitor { events: Vec::new() };
+    visitor.visit_proc(&graph, 1);
+
+    // node `5` is unreachable
+    let expected = vec![
+        "node 1",
+        "store 100",
+        "edge 1 -> 2",
+        "edge 1 -> 3",
+        "node 3",
+        "store 200",
+        "edge 3 -> 4",
+        "node 2",
+        "edge 2 -> 4",
+        "node 4",
+        "edge 4 -> 1",
+    ];
+
+    assert_eq!(expected, visitor.events);
+}
+
+// counts the calls of each procedure
+struct CallsVisitor {
+    procs: Vec<SymbolId>,
+    calls: usize,
+}
+
+impl CfgVisitor for CallsVisitor {
+    fn on_proc_start(&mut self, proc_id: SymbolId, _entry: CfgNodeId) {
+        self.procs.push(proc_id);
+    }
+
+    fn on_call(&mut self, _node_id: CfgNodeId, _entry_id: CfgNodeId) {
+        self.calls += 1;
+    }
+}
+
+#[test]
+fn cfg_visitor_compiled_program() {
+    let code = r#"
+        TO SQUARE(N: INT): INT
+            RETURN N * N
+        END
+
+        PRINT SQUARE(2) + SQUARE(3)
+    "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    assert!(AstTypeCheck::new(&mut env).check(&mut ast).is_ok());
+
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    let mut visitor = CallsVisitor {
+        procs: Vec::new(),
+        calls: 0,
+    };
+    visitor.visit_program(&cfg);
+
+    assert_eq!(2, visitor.procs.len());
+    assert_eq!(2, visitor.calls);
+}
@@ -4,6 +4,7 @@ extern crate tytle;
 #[macro_use]
 extern crate maplit;
 
+use tytle::ast::expression::ExpressionType;
 use tytle::ast::semantic::*;
 use tytle::ast::statement::*;
 use tytle::ir::*;
@@ -192,6 +193,30 @@ fn compile_cfg_graph_make_global_assign_int_expr() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn compile_cfg_graph_const_loads_are_inlined() {
+    let code = r#"
+        CONST A = 2 * 5
+        FORWARD A
+    "#;
+
+    let actual = compile_cfg_graph!(code);
+
+    let expected = cfg_graph! {
+        node!(1,
+            int_ins!(2),
+            int_ins!(5),
+            mul_ins!(),
+            store_ins!(1),
+            int_ins!(10),
+            direct_ins!(FORWARD),
+            eoc_ins!()
+        )
+    };
+
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn compile_cfg_graph_if_stmt_without_else_block() {
     let code = r#"
@@ -374,6 +399,79 @@ fn compile_cfg_graph_print() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn compile_cfg_graph_and_expr_short_circuits() {
+    let code = r#"
+        MAKEGLOBAL A = 10
+        MAKEGLOBAL B = A > 5 AND A < 20
+    "#;
+
+    let actual = compile_cfg_graph!(code);
+
+    let expected = cfg_graph! {
+        node!(1,
+            int_ins!(10),
+            store_ins!(1),
+            load_ins!(1),
+            int_ins!(5),
+            gt_ins!()
+        ),
+        node!(2,
+            load_ins!(1),
+            int_ins!(20),
+            lt_ins!()
+        ),
+        node!(3,
+            store_ins!(2),
+            eoc_ins!()
+        ),
+        edge_true_jmp!(1, 2),
+        edge_always_jmp!(2, 3),
+        edge_fallback_jmp!(1, 3)
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn compile_cfg_graph_or_expr_short_circuits() {
+    let code = r#"
+        MAKEGLOBAL A = 10
+        MAKEGLOBAL B = A > 5 OR A < 20
+    "#;
+
+    let actual = compile_cfg_graph!(code);
+
+    let expected = cfg_graph! {
+        node!(1,
+            int_ins!(10),
+            store_ins!(1),
+            load_ins!(1),
+            int_ins!(5),
+            gt_ins!()
+        ),
+        node!(2,
+            bool_ins!(true)
+        ),
+        node!(3,
+            pop_ins!(),    // the `A > 5` fallen-through `FALSE`
+            load_ins!(1),
+            int_ins!(20),
+            lt_ins!()
+        ),
+        node!(4,
+            store_ins!(2),
+            eoc_ins!()
+        ),
+        edge_true_jmp!(1, 2),
+        edge_fallback_jmp!(1, 3),
+        edge_always_jmp!(2, 4),
+        edge_always_jmp!(3, 4)
+    };
+
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn compile_cfg_graph_repeat_stmt() {
     let code = r#"
@@ -458,7 +556,7 @@ fn compile_cfg_graph_proc_with_no_external_calls() {
 
     // node `1` represents Procedure with `id = 0` (main)
     // node `2` represents Procedure with `id = 1`
-    let expected_jmp_table = hashmap! { 1 => SymbolId(0), 2 => SymbolId(1) };
+    let expected_jmp_table = btreemap! { 1 => SymbolId(0), 2 => SymbolId(1) };
 
     assert_eq!(expected_graph, actual.graph);
     assert_eq!(expected_jmp_table, actual.jmp_table);
@@ -494,7 +592,7 @@ fn compile_cfg_graph_proc_with_external_calls() {
         )
     };
 
-    let expected_jmp_table = hashmap! { 1 => SymbolId(0), 2 => SymbolId(1) };
+    let expected_jmp_table = btreemap! { 1 => SymbolId(0), 2 => SymbolId(1) };
 
     assert_eq!(expected_graph, actual.graph);
     assert_eq!(expected_jmp_table, actual.jmp_table);
@@ -591,3 +689,97 @@ fn compile_cfg_graph_mutually_exclusive_procedures() {
 
     assert_eq!(expected, actual.graph);
 }
+
+#[test]
+fn compile_cfg_obj_procs_frames_layouts() {
+    let code = r#"
+        TO SUM(A: INT, B: INT): INT
+            MAKELOCAL S = A + B
+            MAKELOCAL BIG = S > 10
+            RETURN S
+        END
+
+        REPEAT 2 [
+            PRINT SUM(1, 2)
+        ]
+    "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    assert!(AstTypeCheck::new(&mut env).check(&mut ast).is_ok());
+
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    let sum_id = env.symbol_table.get_proc_by_name("SUM").id;
+    let sum = cfg.get_proc(sum_id);
+
+    assert_eq!(sum_id, cfg.jmp_table[&sum.entry]);
+    assert_eq!(2, sum.args_count);
+    assert_eq!(vec![ExpressionType::Int, ExpressionType::Bool], sum.locals);
+    assert_eq!(4, sum.slots_count());
+    assert!(sum.returns_value());
+
+    // `__main__` has no params, but the `REPEAT` allocates it two temporaries
+    let main_id = env.symbol_table.get_proc_by_name("__main__").id;
+    let main = cfg.get_proc(main_id);
+
+    assert_eq!(1, main.entry);
+    assert_eq!(0, main.args_count);
+    assert_eq!(2, main.locals_count());
+    assert!(!main.returns_value());
+
+    assert_eq!(2, cfg.procs.len());
+}
+
+#[test]
+fn compile_cfg_edges_conds() {
+    let code = r#"
+        MAKEGLOBAL X = 20
+
+        IF X > 10 AND X < 30 [
+            PRINT X
+        ]
+
+        REPEAT 3 [
+            FORWARD X
+        ]
+    "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    assert!(AstTypeCheck::new(&mut env).check(&mut ast).is_ok());
+
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    let mut conds = Vec::new();
+
+    for node in cfg.graph.nodes.values() {
+        for edge in node.outgoing.iter() {
+            let cond = cfg.graph.edge_cond(node.id, edge);
+
+            if edge.jmp_type != CfgJumpType::WhenTrue {
+                assert_eq!(None, cond);
+                continue;
+            }
+
+            let cond = cond.unwrap();
+            conds.push(cond.text.clone());
+
+            // a condition of the source is a boolean
+            if let Some(expr_id) = cond.expr_id {
+                assert_eq!(Some(&ExpressionType::Bool), env.expr_types.get(expr_id));
+            }
+        }
+    }
+
+    let expected = vec!["X > 10", "X > 10 AND X < 30", "REPEAT 3", "REPEAT 3"];
+
+    assert_eq!(expected, conds);
+
+    // the `IF` condition is pushed by the `AND` branches
+    let if_node = cfg.graph.nodes.values().find(|node| node.cond.is_some() && node.is_empty());
+    assert_eq!(None, if_node.unwrap().cond_inst());
+
+    let first_node = cfg.graph.get_node(1);
+    assert_eq!(Some(&CfgInstruction::GreaterThan), first_node.cond_inst());
+}
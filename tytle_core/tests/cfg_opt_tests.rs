@@ -0,0 +1,904 @@
+#[macro_use]
+extern crate tytle;
+
+use tytle::ast::semantic::*;
+use tytle::ast::statement::Direction;
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::vm::*;
+
+use std::collections::BTreeMap;
+
+macro_rules! compile {
+    ($code: expr) => {{
+        let mut ast = TytleParser.parse($code).unwrap();
+        let generator = SymbolTableGenerator::new();
+
+        let mut env = generator.generate(&mut ast).unwrap();
+        let mut checker = AstTypeCheck::new(&mut env);
+
+        let res = checker.check(&mut ast);
+        assert!(res.is_ok());
+
+        let builder = CfgBuilder::new(&mut env);
+        let cfg = builder.build(&ast);
+
+        (env, cfg)
+    }};
+}
+
+macro_rules! compile_opt {
+    ($code: expr, $opt_level: expr) => {{
+        let mut ast = TytleParser.parse($code).unwrap();
+        let generator = SymbolTableGenerator::new();
+
+        let mut env = generator.generate(&mut ast).unwrap();
+        let mut checker = AstTypeCheck::new(&mut env);
+
+        let res = checker.check(&mut ast);
+        assert!(res.is_ok());
+
+        let builder = CfgBuilder::new(&mut env).opt_level($opt_level);
+        let cfg = builder.build(&ast);
+
+        (env, cfg)
+    }};
+}
+
+fn cfg_object(graph: CfgGraph) -> CfgObject {
+    CfgObject {
+        graph,
+        jmp_table: BTreeMap::new(),
+        procs: BTreeMap::new(),
+    }
+}
+
+fn run(env: &Environment, cfg: &CfgObject) -> (Vec<String>, (isize, isize)) {
+    let mut host = DummyHost::new();
+
+    let mut intr = Interpreter::new(cfg, env, &mut host);
+    let _ = intr.exec_code();
+
+    (host.get_log(), host.xycors())
+}
+
+fn entry_insts(cfg: &CfgObject) -> Vec<CfgInstruction> {
+    cfg.graph.get_node(1).insts.clone()
+}
+
+fn var_id(env: &Environment, name: &str) -> SymbolId {
+    let symbol = env.symbol_table.lookup(0, name, &SymbolKind::Var).unwrap();
+    symbol.as_var().id
+}
+
+#[test]
+fn cse_eliminates_repeated_binary_expr() {
+    let code = r#"
+        MAKEGLOBAL A = 2
+        MAKEGLOBAL B = 3
+        FORWARD (A * B) + (A * B)
+    "#;
+
+    let (mut env, mut cfg) = compile!(code);
+
+    let eliminated = CommonSubexprElim::new(&mut env).optimize(&mut cfg);
+    assert_eq!(1, eliminated);
+
+    let a = var_id(&env, "A");
+    let b = var_id(&env, "B");
+    let insts = entry_insts(&cfg);
+
+    let tmp = match insts[7] {
+        CfgInstruction::Store(tmp) => tmp,
+        ref inst => panic!("expected a `Store` of a temporary, got {:?}", inst),
+    };
+
+    assert_eq!(
+        vec![
+            CfgInstruction::Int(2),
+            CfgInstruction::Store(a),
+            CfgInstruction::Int(3),
+            CfgInstruction::Store(b),
+            CfgInstruction::Load(a),
+            CfgInstruction::Load(b),
+            CfgInstruction::Mul,
+            CfgInstruction::Store(tmp),
+            CfgInstruction::Load(tmp),
+            CfgInstruction::Load(tmp),
+            CfgInstruction::Add,
+            CfgInstruction::Direction(Direction::Forward),
+            CfgInstruction::EOC,
+        ],
+        insts
+    );
+}
+
+#[test]
+fn cse_eliminates_longest_expr_first() {
+    let code = r#"
+        MAKEGLOBAL A = 2
+        MAKEGLOBAL B = 3
+        FORWARD (A * B + 1) * (A * B + 1)
+    "#;
+
+    let (mut env, mut cfg) = compile!(code);
+
+    let eliminated = CommonSubexprElim::new(&mut env).optimize(&mut cfg);
+    assert_eq!(1, eliminated);
+
+    let insts = entry_insts(&cfg);
+    let muls = insts
+        .iter()
+        .filter(|inst| **inst == CfgInstruction::Mul)
+        .count();
+
+    // `A * B` is computed once (as part of `A * B + 1`) and the outer `*` once
+    assert_eq!(2, muls);
+}
+
+#[test]
+fn cse_store_invalidates_expr() {
+    let code = r#"
+        MAKEGLOBAL A = 2
+        MAKEGLOBAL B = 3
+        FORWARD A * B
+        MAKE A = 10
+        FORWARD A * B
+    "#;
+
+    let (mut env, mut cfg) = compile!(code);
+    let before = entry_insts(&cfg);
+
+    let eliminated = CommonSubexprElim::new(&mut env).optimize(&mut cfg);
+
+    assert_eq!(0, eliminated);
+    assert_eq!(before, entry_insts(&cfg));
+}
+
+#[test]
+fn cse_store_to_unrelated_var_keeps_expr() {
+    let code = r#"
+        MAKEGLOBAL A = 2
+        MAKEGLOBAL B = 3
+        MAKEGLOBAL C = 0
+        FORWARD A * B
+        MAKE C = 10
+        FORWARD A * B
+    "#;
+
+    let (mut env, mut cfg) = compile!(code);
+
+    let eliminated = CommonSubexprElim::new(&mut env).optimize(&mut cfg);
+    assert_eq!(1, eliminated);
+}
+
+#[test]
+fn cse_proc_call_invalidates_expr() {
+    let code = r#"
+        MAKEGLOBAL A = 2
+        MAKEGLOBAL B = 3
+
+        TO INC_A()
+            MAKE A = A + 1
+        END
+
+        FORWARD A * B
+        INC_A()
+        FORWARD A * B
+    "#;
+
+    let (mut env, mut cfg) = compile!(code);
+    let (before_log, before_xy) = run(&env, &cfg);
+
+    let eliminated = CommonSubexprElim::new(&mut env).optimize(&mut cfg);
+    assert_eq!(0, eliminated);
+
+    let (after_log, after_xy) = run(&env, &cfg);
+    assert_eq!(before_log, after_log);
+    assert_eq!(before_xy, after_xy);
+    assert_eq!((0, 15), after_xy);
+}
+
+#[test]
+fn cse_inside_procedure_uses_local_tmp() {
+    let code = r#"
+        TO SQUARES(N: INT): INT
+            RETURN (N * N) + (N * N)
+        END
+
+        FORWARD SQUARES(3)
+        RIGHT SQUARES(2)
+    "#;
+
+    let (mut env, mut cfg) = compile!(code);
+
+    let eliminated = CommonSubexprElim::new(&mut env).optimize(&mut cfg);
+    assert_eq!(1, eliminated);
+
+    let (_, xy) = run(&env, &cfg);
+    assert_eq!((8, 18), xy);
+}
+
+#[test]
+fn cse_preserves_program_semantics() {
+    let programs = [
+        r#"
+            MAKEGLOBAL X = 3
+            MAKEGLOBAL Y = 4
+            REPEAT 5 [
+                FORWARD (X * X + Y * Y) / (X * X + Y * Y + 1) + (X * X + Y * Y)
+                MAKE X = X + 1
+                RIGHT X * X + X * X
+            ]
+        "#,
+        r#"
+            MAKEGLOBAL A = 1
+            MAKEGLOBAL B = 2
+            IF A + B < (A + B) * 2 [
+                FORWARD A + B
+            ] [
+                FORWARD (A + B) * (A + B)
+            ]
+            PRINT A + B
+        "#,
+        r#"
+            MAKEGLOBAL N = 1
+
+            TO NEXT(): INT
+                MAKE N = N + 1
+                RETURN N * N
+            END
+
+            REPEAT 3 [
+                FORWARD NEXT() * NEXT()
+                RIGHT (N * 2) + (N * 2)
+            ]
+        "#,
+    ];
+
+    for code in programs.iter() {
+        let (mut env, mut cfg) = compile!(code);
+        let expected = run(&env, &cfg);
+
+        CommonSubexprElim::new(&mut env).optimize(&mut cfg);
+
+        assert_eq!(expected, run(&env, &cfg));
+    }
+}
+
+#[test]
+fn copy_prop_forwards_stored_literal() {
+    let code = r#"
+        MAKEGLOBAL A = 5
+        FORWARD A
+    "#;
+
+    let (env, mut cfg) = compile!(code);
+
+    let rewrites = CopyPropagation::new().optimize(&mut cfg);
+    assert_eq!(1, rewrites);
+
+    let a = var_id(&env, "A");
+
+    assert_eq!(
+        vec![
+            CfgInstruction::Int(5),
+            CfgInstruction::Store(a),
+            CfgInstruction::Int(5),
+            CfgInstruction::Direction(Direction::Forward),
+            CfgInstruction::EOC,
+        ],
+        entry_insts(&cfg)
+    );
+}
+
+#[test]
+fn copy_prop_forwards_store_load() {
+    let code = r#"
+        MAKEGLOBAL A = 2
+        MAKEGLOBAL B = A * 3
+        FORWARD B
+    "#;
+
+    let (env, mut cfg) = compile!(code);
+
+    let rewrites = CopyPropagation::new().optimize(&mut cfg);
+    assert_eq!(2, rewrites);
+
+    let a = var_id(&env, "A");
+    let b = var_id(&env, "B");
+
+    assert_eq!(
+        vec![
+            CfgInstruction::Int(2),
+            CfgInstruction::Store(a),
+            CfgInstruction::Int(2),
+            CfgInstruction::Int(3),
+            CfgInstruction::Mul,
+            CfgInstruction::Dup,
+            CfgInstruction::Store(b),
+            CfgInstruction::Direction(Direction::Forward),
+            CfgInstruction::EOC,
+        ],
+        entry_insts(&cfg)
+    );
+
+    let (_, xy) = run(&env, &cfg);
+    assert_eq!((0, 6), xy);
+}
+
+#[test]
+fn copy_prop_removes_overwritten_store() {
+    let code = r#"
+        MAKEGLOBAL A = 1
+        MAKE A = 2
+        FORWARD A
+    "#;
+
+    let (env, mut cfg) = compile!(code);
+
+    let rewrites = CopyPropagation::new().optimize(&mut cfg);
+    assert_eq!(2, rewrites);
+
+    let a = var_id(&env, "A");
+
+    assert_eq!(
+        vec![
+            CfgInstruction::Int(2),
+            CfgInstruction::Store(a),
+            CfgInstruction::Int(2),
+            CfgInstruction::Direction(Direction::Forward),
+            CfgInstruction::EOC,
+        ],
+        entry_insts(&cfg)
+    );
+}
+
+#[test]
+fn copy_prop_keeps_store_read_by_proc_call() {
+    let code = r#"
+        MAKEGLOBAL A = 1
+
+        TO WALK()
+            FORWARD A
+        END
+
+        WALK()
+        MAKE A = 2
+        WALK()
+    "#;
+
+    let (env, mut cfg) = compile!(code);
+    let before = entry_insts(&cfg);
+
+    let rewrites = CopyPropagation::new().optimize(&mut cfg);
+
+    assert_eq!(0, rewrites);
+    assert_eq!(before, entry_insts(&cfg));
+
+    let (_, xy) = run(&env, &cfg);
+    assert_eq!((0, 3), xy);
+}
+
+#[test]
+fn copy_prop_keeps_store_followed_by_an_inst_which_may_throw() {
+    let code = r#"
+        MAKEGLOBAL A = 1
+
+        CATCH "ERROR [
+            MAKE A = 2
+            MAKE A = PARSE("x")
+        ]
+
+        FORWARD A
+    "#;
+
+    let (env, mut cfg) = compile!(code);
+
+    CopyPropagation::new().optimize(&mut cfg);
+
+    let (_, xy) = run(&env, &cfg);
+    assert_eq!((0, 2), xy);
+}
+
+#[test]
+fn copy_prop_invalidates_copy_of_modified_var() {
+    let code = r#"
+        MAKEGLOBAL A = 1
+        MAKEGLOBAL B = A
+        MAKE A = 10
+        FORWARD B
+    "#;
+
+    let (env, mut cfg) = compile!(code);
+    let expected = run(&env, &cfg);
+
+    CopyPropagation::new().optimize(&mut cfg);
+
+    assert_eq!(expected, run(&env, &cfg));
+    assert_eq!((0, 1), expected.1);
+}
+
+#[test]
+fn copy_prop_preserves_program_semantics() {
+    let programs = [
+        r#"
+            MAKEGLOBAL X = 3
+            MAKEGLOBAL Y = X
+            REPEAT 5 [
+                MAKE X = X + Y
+                MAKE Y = X * 2
+                MAKE Y = Y + 1
+                FORWARD Y
+                RIGHT X
+            ]
+            PRINT X
+        "#,
+        r#"
+            TO SUM(A: INT, B: INT): INT
+                MAKELOCAL S = A
+                MAKE S = S + B
+                MAKE S = S + S
+                RETURN S
+            END
+
+            MAKEGLOBAL N = SUM(1, 2)
+            FORWARD N
+            MAKE N = SUM(N, N)
+            RIGHT N
+        "#,
+        r#"
+            MAKEGLOBAL X = 3
+            MAKEGLOBAL Y = 4
+            MAKEGLOBAL NORM = 0
+            REPEAT 3 [
+                MAKE NORM = (X * X + Y * Y) / (X * X + Y * Y + 1) + (X * X + Y * Y) * 2
+                MAKE X = X + 1
+                FORWARD NORM
+            ]
+        "#,
+    ];
+
+    for code in programs.iter() {
+        let (mut env, mut cfg) = compile!(code);
+        let expected = run(&env, &cfg);
+
+        CommonSubexprElim::new(&mut env).optimize(&mut cfg);
+        CopyPropagation::new().optimize(&mut cfg);
+
+        assert_eq!(expected, run(&env, &cfg));
+    }
+}
+
+#[test]
+fn peephole_builtin_rules() {
+    let graph = cfg_graph! {
+        node!(
+            1,
+            load_ins!(1),
+            store_ins!(1),
+            load_ins!(2),
+            int_ins!(1),
+            mul_ins!(),
+            int_ins!(0),
+            load_ins!(3),
+            add_ins!(),
+            bool_ins!(true),
+            not_ins!(),
+            not_ins!(),
+            eoc_ins!()
+        )
+    };
+
+    let mut cfg = cfg_object(graph);
+
+    let peephole = Peephole::new();
+    assert_eq!(
+        vec!["push-pop", "mul-by-one", "add-zero", "double-negation"],
+        peephole.rules_names()
+    );
+
+    let rewrites = peephole.optimize(&mut cfg);
+    assert_eq!(4, rewrites);
+
+    assert_eq!(
+        vec![load_ins!(2), load_ins!(3), bool_ins!(true), eoc_ins!()],
+        entry_insts(&cfg)
+    );
+}
+
+#[test]
+fn peephole_rewrites_until_fixpoint() {
+    let graph = cfg_graph! {
+        node!(1, int_ins!(0), int_ins!(1), load_ins!(2), mul_ins!(), add_ins!(), eoc_ins!())
+    };
+
+    let mut cfg = cfg_object(graph);
+
+    let rewrites = Peephole::new().optimize(&mut cfg);
+
+    assert_eq!(2, rewrites);
+    assert_eq!(vec![load_ins!(2), eoc_ins!()], entry_insts(&cfg));
+}
+
+struct FoldAdd;
+
+impl PeepholeRule for FoldAdd {
+    fn name(&self) -> &str {
+        "fold-add"
+    }
+
+    fn rewrite(&self, insts: &[CfgInstruction]) -> Option<(usize, Vec<CfgInstruction>)> {
+        match insts {
+            [CfgInstruction::Int(a), CfgInstruction::Int(b), CfgInstruction::Add, ..] => {
+                Some((3, vec![CfgInstruction::Int(a + b)]))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn peephole_custom_rule() {
+    let graph = cfg_graph! {
+        node!(1, int_ins!(1), int_ins!(2), add_ins!(), int_ins!(3), add_ins!(), eoc_ins!())
+    };
+
+    let mut cfg = cfg_object(graph);
+
+    let mut peephole = Peephole::empty();
+    peephole.add_rule(FoldAdd);
+    assert_eq!(vec!["fold-add"], peephole.rules_names());
+
+    let rewrites = peephole.optimize(&mut cfg);
+
+    assert_eq!(2, rewrites);
+    assert_eq!(vec![int_ins!(6), eoc_ins!()], entry_insts(&cfg));
+}
+
+#[test]
+fn peephole_threads_jumps() {
+    let graph = cfg_graph! {
+        node!(1, bool_ins!(true)),
+        node!(2),
+        node!(3),
+        node!(4, eoc_ins!()),
+        node!(5, eoc_ins!()),
+        edge_true_jmp!(1, 2),
+        edge_fallback_jmp!(1, 5),
+        edge_always_jmp!(2, 3),
+        edge_always_jmp!(3, 4)
+    };
+
+    let mut cfg = cfg_object(graph);
+
+    let rewrites = Peephole::empty().optimize(&mut cfg);
+    assert_eq!(2, rewrites);
+
+    let node = cfg.graph.get_node(1);
+    assert!(node.outgoing.contains(&CfgEdge {
+        node_id: 4,
+        jmp_type: CfgJumpType::WhenTrue
+    }));
+    assert!(node.outgoing.contains(&CfgEdge {
+        node_id: 5,
+        jmp_type: CfgJumpType::Fallback
+    }));
+    assert_eq!(2, node.outgoing.len());
+
+    assert!(cfg.graph.get_node(4).incoming.contains(&CfgEdge {
+        node_id: 1,
+        jmp_type: CfgJumpType::WhenTrue
+    }));
+    assert!(cfg.graph.get_node(2).incoming.is_empty());
+}
+
+#[test]
+fn peephole_skips_empty_nodes_loop() {
+    let graph = cfg_graph! {
+        node!(1),
+        node!(2),
+        edge_always_jmp!(1, 2),
+        edge_always_jmp!(2, 1)
+    };
+
+    let mut cfg = cfg_object(graph);
+
+    assert_eq!(0, Peephole::empty().optimize(&mut cfg));
+}
+
+#[test]
+fn block_merge_merges_straight_line_nodes() {
+    let graph = cfg_graph! {
+        node!(1, int_ins!(10)),
+        node!(2, int_ins!(20)),
+        node!(3),
+        node!(4, add_ins!(), eoc_ins!()),
+        edge_always_jmp!(1, 2),
+        edge_always_jmp!(2, 3),
+        edge_always_jmp!(3, 4)
+    };
+
+    let mut cfg = cfg_object(graph);
+
+    assert_eq!(3, BlockMerge::new().optimize(&mut cfg));
+    assert_eq!(1, cfg.graph.nodes.len());
+
+    let expected = vec![int_ins!(10), int_ins!(20), add_ins!(), eoc_ins!()];
+    assert_eq!(expected, entry_insts(&cfg));
+    assert!(cfg.graph.get_node(1).outgoing.is_empty());
+}
+
+#[test]
+fn block_merge_keeps_proc_entries_and_join_nodes() {
+    let graph = cfg_graph! {
+        node!(1, bool_ins!(true)),
+        node!(2, int_ins!(10)),
+        node!(3, int_ins!(20)),
+        node!(4, eoc_ins!()),
+        node!(5, int_ins!(30)),
+        node!(6, ret_ins!()),
+        edge_true_jmp!(1, 2),
+        edge_fallback_jmp!(1, 3),
+        edge_always_jmp!(2, 4),
+        edge_always_jmp!(3, 4),
+        edge_always_jmp!(5, 6),
+        edge_always_jmp!(6, 5)
+    };
+
+    let mut cfg = cfg_object(graph);
+    cfg.jmp_table.insert(1, SymbolId(0));
+    cfg.jmp_table.insert(5, SymbolId(1));
+
+    // node `4` is jumped to from both branches, and node `5` is a procedure entry
+    assert_eq!(1, BlockMerge::new().optimize(&mut cfg));
+    assert_eq!(vec![1, 2, 3, 4, 5], cfg.graph.nodes.keys().copied().collect::<Vec<_>>());
+
+    assert_eq!(vec![int_ins!(30), ret_ins!()], cfg.graph.get_node(5).insts);
+    assert_eq!(vec![5], cfg.graph.successors(5));
+}
+
+#[test]
+fn block_merge_rejoins_split_nodes() {
+    let code = r#"
+        MAKEGLOBAL X = 3
+        REPEAT 4 [
+            IF X > 1 AND X < 6 [
+                RIGHT 90
+                IF X < 5 [
+                    FORWARD X
+                ]
+            ] [
+                LEFT 90
+            ]
+            MAKE X = X + 1
+        ]
+        PRINT X
+    "#;
+
+    let (env, mut cfg) = compile!(code);
+    let expected = run(&env, &cfg);
+    let nodes_count = cfg.graph.nodes.len();
+
+    let node_ids = cfg.graph.nodes.keys().copied().collect::<Vec<_>>();
+    let mut splits = 0;
+
+    for node_id in node_ids {
+        let insts_count = cfg.graph.get_node(node_id).insts.len();
+
+        if insts_count > 1 {
+            cfg.graph.split_node(node_id, insts_count / 2);
+            splits += 1;
+        }
+    }
+
+    assert_eq!(nodes_count + splits, cfg.graph.nodes.len());
+    assert_eq!(expected, run(&env, &cfg));
+
+    assert_eq!(splits, BlockMerge::new().optimize(&mut cfg));
+    assert_eq!(nodes_count, cfg.graph.nodes.len());
+    assert_eq!(expected, run(&env, &cfg));
+}
+
+#[test]
+fn opt_level_preserves_program_semantics() {
+    let programs = [
+        r#"
+            MAKEGLOBAL X = 3
+            MAKEGLOBAL Y = 4
+            REPEAT 5 [
+                MAKE X = X * 1 + 0
+                IF NOT NOT (X < 6) [
+                    FORWARD (X * X + Y * Y) / (X * X + Y * Y + 1) + (X * X + Y * Y)
+                ] [
+                    RIGHT X * 1
+                ]
+                MAKE X = X + 1
+                MAKE Y = Y
+            ]
+            PRINT X
+        "#,
+        r#"
+            TO SUM(A: INT, B: INT): INT
+                MAKELOCAL S = A
+                MAKE S = S + B * 1
+                MAKE S = S + S
+                RETURN S
+            END
+
+            MAKEGLOBAL N = SUM(1, 2)
+            FORWARD N
+            IF N > 5 [
+                MAKE N = SUM(N, 0 + N)
+            ]
+            RIGHT N
+        "#,
+    ];
+
+    for code in programs.iter() {
+        let (env, cfg) = compile_opt!(code, OptLevel::None);
+        let expected = run(&env, &cfg);
+
+        for opt_level in [OptLevel::Basic, OptLevel::Full].iter() {
+            let (env, cfg) = compile_opt!(code, *opt_level);
+
+            assert_eq!(expected, run(&env, &cfg));
+        }
+    }
+}
+
+#[test]
+fn inline_replaces_call_with_body() {
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            RETURN N + N
+        END
+
+        FORWARD DOUBLE(3)
+    "#;
+
+    let (mut env, mut cfg) = compile!(code);
+
+    let inlined = ProcInliner::new(&mut env).optimize(&mut cfg);
+    assert_eq!(1, inlined);
+
+    // the param copy is a new local of `__main__`
+    let main_id = env.symbol_table.get_proc_by_name("__main__").id;
+    let main_locals = &env.locals_symbols[&main_id];
+    assert_eq!(1, main_locals.len());
+
+    let n_copy = env.symbol_table.get_var_by_id(main_locals[0]);
+    assert!(n_copy.is_tmp());
+    assert!(!n_copy.global);
+    assert!(n_copy.name.starts_with("$DOUBLE.N."));
+
+    // the `__main__` frame layout accounts for the copy
+    assert_eq!(1, cfg.get_proc(main_id).locals_count());
+
+    assert_eq!(
+        vec![
+            CfgInstruction::Int(3),
+            CfgInstruction::Store(n_copy.id),
+            CfgInstruction::Load(n_copy.id),
+            CfgInstruction::Load(n_copy.id),
+            CfgInstruction::Add,
+            CfgInstruction::Direction(Direction::Forward),
+            CfgInstruction::EOC,
+        ],
+        entry_insts(&cfg)
+    );
+
+    let (_, xy) = run(&env, &cfg);
+    assert_eq!((0, 6), xy);
+}
+
+#[test]
+fn inline_respects_threshold() {
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            RETURN N + N
+        END
+
+        FORWARD DOUBLE(3)
+    "#;
+
+    let (mut env, mut cfg) = compile!(code);
+
+    // the body of `DOUBLE` consists of 3 instructions (excluding its `RETURN`)
+    assert_eq!(0, ProcInliner::new(&mut env).threshold(2).optimize(&mut cfg));
+    assert_eq!(1, ProcInliner::new(&mut env).threshold(3).optimize(&mut cfg));
+}
+
+#[test]
+fn inline_skips_branching_procs() {
+    let code = r#"
+        TO ABS_STEP(N: INT)
+            IF N > 10 [
+                FORWARD 10
+            ] [
+                FORWARD N
+            ]
+        END
+
+        ABS_STEP(5)
+    "#;
+
+    let (mut env, mut cfg) = compile!(code);
+
+    assert_eq!(0, ProcInliner::new(&mut env).optimize(&mut cfg));
+}
+
+#[test]
+fn inline_skips_traced_and_asserting_procs() {
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            RETURN N + N
+        END
+
+        TO CHECKED(N: INT)
+            ASSERT N > 0
+        END
+
+        TRACE "DOUBLE
+        FORWARD DOUBLE(3)
+        CHECKED(1)
+    "#;
+
+    let (mut env, mut cfg) = compile!(code);
+
+    // the calls of `DOUBLE` are traced, and the `ASSERT` snapshot is of the `CHECKED` frame
+    assert_eq!(0, ProcInliner::new(&mut env).optimize(&mut cfg));
+}
+
+#[test]
+fn inline_recursive_proc_expanded_once() {
+    let code = r#"
+        TO LOOP(N: INT)
+            FORWARD N
+            LOOP(N)
+        END
+
+        LOOP(1)
+    "#;
+
+    let (mut env, mut cfg) = compile!(code);
+
+    assert_eq!(2, ProcInliner::new(&mut env).optimize(&mut cfg));
+
+    // the inlined body still calls `LOOP`
+    let loop_entry = cfg
+        .jmp_table
+        .iter()
+        .find(|(_, proc_id)| env.symbol_table.get_proc_by_id(**proc_id).name == "LOOP")
+        .map(|(entry_id, _)| *entry_id)
+        .unwrap();
+
+    assert!(entry_insts(&cfg).contains(&CfgInstruction::Call(loop_entry)));
+}
+
+#[test]
+fn inline_renames_locals_per_caller() {
+    let code = r#"
+        TO STEP(N: INT): INT
+            MAKELOCAL S = N * 2
+            RETURN S + 1
+        END
+
+        TO WALK(S: INT)
+            MAKELOCAL N = STEP(S)
+            FORWARD N + STEP(N) + S
+        END
+
+        WALK(2)
+        RIGHT STEP(STEP(1))
+    "#;
+
+    let (env, cfg) = compile!(code);
+    let expected = run(&env, &cfg);
+
+    // `STEP` is inlined twice into `WALK` and twice into `__main__`, and so is `WALK` itself
+    let (mut env, mut cfg) = compile!(code);
+    assert_eq!(5, ProcInliner::new(&mut env).optimize(&mut cfg));
+
+    assert_eq!(expected, run(&env, &cfg));
+}
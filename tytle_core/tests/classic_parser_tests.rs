@@ -0,0 +1,285 @@
+extern crate tytle;
+
+use tytle::compiler::{CompileOptions, Compiler};
+use tytle::diagnostics::{Diagnostic, DiagnosticRenderer, SourceMap};
+use tytle::lexer::Location;
+use tytle::parser::{ClassicParser, ParseError, Parser, ParserConfig, TytleParser};
+use tytle::vm::DummyHost;
+use tytle::TytleError;
+
+fn classic_config() -> ParserConfig {
+    ParserConfig::new().classic(true)
+}
+
+fn assert_same_ast(classic: &str, tytle: &str) {
+    let actual = ClassicParser.parse(classic).unwrap();
+    let expected = TytleParser.parse(tytle).unwrap();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn classic_parser_procedures_and_calls() {
+    let classic = r#"
+        to square :size
+          repeat 4 [fd :size rt :size]
+        end
+
+        make "len 10
+        square :len
+    "#;
+
+    let tytle = r#"
+        TO SQUARE(SIZE: INT)
+            REPEAT 4 [
+                FORWARD SIZE
+                RIGHT SIZE
+            ]
+        END
+
+        MAKEGLOBAL LEN = 10
+        SQUARE(LEN)
+    "#;
+
+    assert_same_ast(classic, tytle);
+}
+
+#[test]
+fn classic_parser_outputs_and_nested_calls() {
+    let classic = r#"
+        pr double 5 + 1
+        pr sum3 1 double 2 3
+
+        to double :n
+          op :n * 2
+        end
+
+        to sum3 :a :b :c
+          output :a + :b * :c
+        end
+    "#;
+
+    let tytle = r#"
+        PRINT DOUBLE(5 + 1)
+        PRINT SUM3(1, DOUBLE(2), 3)
+
+        TO DOUBLE(N: INT): INT
+            RETURN N * 2
+        END
+
+        TO SUM3(A: INT, B: INT, C: INT): INT
+            RETURN A + B * C
+        END
+    "#;
+
+    assert_same_ast(classic, tytle);
+}
+
+#[test]
+fn classic_parser_abbreviations() {
+    let classic = r#"
+        cs
+        pu bk 10 pd
+        setpc 4 setbg [255 255 0]
+        setpc [255 0 :x]
+        ht st pe
+        setx 5 sety 6 lt 2
+        wait 60
+    "#;
+
+    let tytle = r#"
+        CLEARSCREEN
+        PENUP
+        BACKWARD 10
+        PENDOWN
+        SETPENCOLOR 4
+        SETBACKGROUND
+        SETPENCOLOR RGB 255 0 X
+        HIDETURTLE
+        SHOWTURTLE
+        PENERASE
+        SETX 5
+        SETY 6
+        LEFT 2
+        WAIT
+    "#;
+
+    assert_same_ast(classic, tytle);
+}
+
+#[test]
+fn classic_parser_conditions_and_variables() {
+    let classic = r#"
+        make "x 1
+
+        to check :n
+          local "half
+          make "half :n / 2
+          localmake "big :half > 10
+          make "x :n
+          ifelse :big [pr 1] [pr 0]
+          if and :n > 1 not :n = 5 [stop]
+          if :n <> 3 [pr "three] [pr "other]
+        end
+    "#;
+
+    let tytle = r#"
+        MAKEGLOBAL X = 1
+
+        TO CHECK(N: INT)
+            MAKELOCAL HALF = N / 2
+            MAKELOCAL BIG = HALF > 10
+            MAKE X = N
+            IF BIG [
+                PRINT 1
+            ] [
+                PRINT 0
+            ]
+            IF N > 1 AND NOT N = 5 [
+                HALT
+            ]
+            IF N <> 3 [
+                PRINT "three
+            ] [
+                PRINT "other
+            ]
+        END
+    "#;
+
+    assert_same_ast(classic, tytle);
+}
+
+#[test]
+fn classic_parser_labels() {
+    let classic = r#"
+        setlabelheight 20
+        label "hello
+    "#;
+
+    let tytle = r#"
+        SETLABELSIZE 20
+        LABEL "hello
+    "#;
+
+    assert_same_ast(classic, tytle);
+}
+
+#[test]
+fn classic_parser_unsupported_construct_location() {
+    let code = "fd 10\n  fd 10 seth 90";
+
+    let res = ClassicParser.parse_located(code, &classic_config());
+
+    assert_eq!(
+        Err((ParseError::Unsupported("SETH".to_string()), Location(2, 9))),
+        res
+    );
+
+    let res = ClassicParser.parse_located("make \"a 10 - 5", &classic_config());
+
+    assert_eq!(
+        Err((ParseError::Unsupported("-".to_string()), Location(1, 12))),
+        res
+    );
+}
+
+#[test]
+fn classic_parser_run_block() {
+    let classic = r#"
+        make "step [fd 10 rt 90]
+        run :step
+        run [pu]
+    "#;
+
+    let tytle = r#"
+        MAKEGLOBAL STEP = [
+            FORWARD 10
+            RIGHT 90
+        ]
+        RUN STEP
+        RUN [
+            PENUP
+        ]
+    "#;
+
+    assert_same_ast(classic, tytle);
+}
+
+#[test]
+fn classic_parser_if_else_expr() {
+    let classic = r#"
+        to sign :n
+          op ifelse :n > 0 [1] [0]
+        end
+
+        ifelse (sign 5) = 1 [pr 1] [pr 0]
+    "#;
+
+    let tytle = r#"
+        TO SIGN(N: INT): INT
+            RETURN IFELSE N > 0 [1] [0]
+        END
+
+        IF (SIGN(5)) = 1 [
+            PRINT 1
+        ] [
+            PRINT 0
+        ]
+    "#;
+
+    assert_same_ast(classic, tytle);
+}
+
+#[test]
+fn classic_parser_unknown_command() {
+    let res = ClassicParser.parse_located("to square :n\nend\nsqare 5", &classic_config());
+
+    let expected = ParseError::UnknownCommand {
+        name: "SQARE".to_string(),
+        suggestion: Some("SQUARE".to_string()),
+    };
+
+    assert_eq!(Err((expected, Location(3, 1))), res);
+}
+
+#[test]
+fn classic_parser_compiles_and_runs() {
+    let code = r#"
+        to stairs :count :size
+          repeat :count [fd :size rt :size]
+        end
+
+        stairs 3 10
+    "#;
+
+    let program = Compiler::compile_with_config(code, CompileOptions::default(), &classic_config())
+        .unwrap();
+
+    let mut host = DummyHost::new();
+    program.interpreter(&mut host).exec_code().unwrap();
+
+    assert_eq!((30, 30), host.xycors());
+}
+
+#[test]
+fn classic_parser_error_diagnostic() {
+    let code = "cs\nfd 10 home";
+
+    let res = Compiler::compile_with_config(code, CompileOptions::default(), &classic_config());
+
+    let err = match res {
+        Err(err) => err,
+        Ok(_) => panic!("expected a compilation error"),
+    };
+
+    let diag = Diagnostic::from(&TytleError::from(err));
+
+    let expected = r#"error: Unsupported construct: `HOME`
+ --> 2:7
+  |
+2 | fd 10 home
+  |       ^^^^
+"#;
+
+    assert_eq!(expected, DiagnosticRenderer::new().render(&diag, &SourceMap::new(code)));
+}
@@ -0,0 +1,340 @@
+extern crate tytle;
+
+use tytle::ast::semantic::AstWalkError;
+use tytle::compiler::*;
+use tytle::ir::*;
+use tytle::parser::{KeywordTable, ParserConfig};
+use tytle::vm::*;
+
+fn run(program: &CompiledProgram) -> (Vec<String>, (isize, isize)) {
+    let mut host = DummyHost::new();
+
+    let mut intr = program.interpreter(&mut host);
+    let _ = intr.exec_code();
+
+    (host.get_log(), host.xycors())
+}
+
+#[test]
+fn compiler_compile_and_run() {
+    let code = r#"
+        TO SQUARE(N: INT): INT
+            RETURN N * N
+        END
+
+        FORWARD SQUARE(3)
+        PRINT SQUARE(2)
+    "#;
+
+    let program = Compiler::compile(code, CompileOptions::default()).unwrap();
+    assert!(program.ast.is_none());
+
+    let (log, xy) = run(&program);
+
+    assert_eq!(vec!["4".to_string()], log);
+    assert_eq!((0, 9), xy);
+}
+
+#[test]
+fn compiler_parse_error() {
+    let code = "FORWARD 10\nMAKEGLOBAL A 10";
+    let res = Compiler::compile(code, CompileOptions::default());
+
+    match res {
+        Err(CompileError::Parse { location, .. }) => assert_eq!(2, location.line()),
+        _ => panic!("expected a parse error"),
+    }
+}
+
+#[test]
+fn compiler_semantic_error() {
+    let code = r#"
+        TO MYPROC()
+        END
+
+        TO MYPROC()
+        END
+    "#;
+
+    let res = Compiler::compile(code, CompileOptions::default());
+
+    assert_eq!(
+        Some(CompileError::Semantic(AstWalkError::DuplicateProc(
+            "MYPROC".to_string()
+        ))),
+        res.err()
+    );
+}
+
+#[test]
+fn compiler_missing_return() {
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            IF N > 0 [
+                RETURN N * 2
+            ]
+        END
+
+        PRINT DOUBLE(0)
+    "#;
+
+    let res = Compiler::compile(code, CompileOptions::default());
+
+    assert_eq!(
+        Some(CompileError::MissingReturn {
+            proc_name: "DOUBLE".to_string(),
+        }),
+        res.err()
+    );
+
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            IF N > 0 [
+                RETURN N * 2
+            ] [
+                THROW "NEGATIVE
+            ]
+        END
+
+        PRINT DOUBLE(2)
+    "#;
+
+    let program = Compiler::compile(code, CompileOptions::default()).unwrap();
+
+    assert_eq!(vec!["4".to_string()], run(&program).0);
+}
+
+#[test]
+fn compiler_max_proc_params() {
+    let code = r#"
+        TO SUM(A: INT, B: INT, C: INT): INT
+            RETURN A + B + C
+        END
+    "#;
+
+    let options = CompileOptions {
+        max_proc_params: Some(2),
+        ..Default::default()
+    };
+
+    let res = Compiler::compile(code, options);
+
+    assert_eq!(
+        Some(CompileError::TooManyProcParams {
+            proc_name: "SUM".to_string(),
+            max: 2,
+            actual: 3,
+        }),
+        res.err()
+    );
+
+    let options = CompileOptions {
+        max_proc_params: Some(3),
+        ..Default::default()
+    };
+
+    assert!(Compiler::compile(code, options).is_ok());
+}
+
+#[test]
+fn compiler_capabilities() {
+    let options = |capabilities| CompileOptions {
+        capabilities,
+        ..Default::default()
+    };
+
+    let no_sound = options(Capabilities::ALL.without(Capability::Sound));
+    let res = Compiler::compile("FORWARD 10 TONE 440 100", no_sound);
+
+    assert_eq!(
+        Some(CompileError::CapabilityDisabled {
+            capability: Capability::Sound,
+            feature: "TONE".to_string(),
+        }),
+        res.err()
+    );
+
+    let no_input = options(Capabilities::ALL.without(Capability::Input));
+    let res = Compiler::compile(r#"ONKEY "LEFT [ LEFT 90 ]"#, no_input);
+
+    assert_eq!(
+        Some(CompileError::CapabilityDisabled {
+            capability: Capability::Input,
+            feature: "ONKEY".to_string(),
+        }),
+        res.err()
+    );
+
+    let no_load = options(Capabilities::NONE);
+    let res = Compiler::compile(r#"LOAD "lib"#, no_load);
+
+    assert_eq!(
+        Some(CompileError::CapabilityDisabled {
+            capability: Capability::Load,
+            feature: "LOAD".to_string(),
+        }),
+        res.err()
+    );
+
+    let only_sound = options(Capabilities::NONE.with(Capability::Sound));
+    assert!(Compiler::compile("TONE 440 100 BEEP", only_sound).is_ok());
+}
+
+#[test]
+fn compiler_debug_info_keeps_ast() {
+    let options = CompileOptions {
+        debug_info: true,
+        ..Default::default()
+    };
+
+    let program = Compiler::compile("FORWARD 10", options).unwrap();
+
+    assert!(program.ast.is_some());
+}
+
+#[test]
+fn compiler_stdlib() {
+    let code = r#"
+        TO SQUARE(SIZE: INT)
+            FORWARD SIZE
+        END
+    "#;
+
+    assert!(Compiler::compile(code, CompileOptions::default()).is_ok());
+
+    let options = CompileOptions {
+        stdlib: true,
+        ..Default::default()
+    };
+
+    let res = Compiler::compile(code, options);
+
+    assert_eq!(
+        Some(CompileError::Semantic(
+            AstWalkError::BuiltinProcRedefinition("SQUARE".to_string())
+        )),
+        res.err()
+    );
+}
+
+#[test]
+fn compiler_stdlib_overload_by_arity() {
+    let code = r#"
+        TO SQUARE(SIZE: INT, TIMES: INT)
+            REPEAT TIMES [ SQUARE(SIZE) ]
+        END
+
+        SQUARE(10, 2)
+    "#;
+
+    let options = CompileOptions {
+        stdlib: true,
+        ..Default::default()
+    };
+
+    assert!(Compiler::compile(code, options).is_ok());
+}
+
+#[test]
+fn compiler_opt_levels() {
+    let code = r#"
+        MAKEGLOBAL X = 2
+        REPEAT 3 [
+            MAKE X = X * 1 + 0
+            FORWARD (X + 1) * (X + 1)
+            MAKE X = X + 1
+        ]
+    "#;
+
+    let program = Compiler::compile(code, CompileOptions::default()).unwrap();
+    let expected = run(&program);
+
+    for opt_level in [OptLevel::Basic, OptLevel::Full].iter() {
+        let options = CompileOptions {
+            opt_level: *opt_level,
+            ..Default::default()
+        };
+
+        let program = Compiler::compile(code, options).unwrap();
+
+        assert_eq!(expected, run(&program));
+    }
+}
+
+#[test]
+fn compiler_translated_keywords() {
+    let spanish = r#"
+        PARA CUADRADO(N: INT): INT
+            DEVUELVE N * N
+        FIN
+
+        HAZGLOBAL A = 0
+        REPITE 3 [
+            HAZ A = A + 1
+        ]
+
+        SI A > 2 [
+            AVANZA CUADRADO(A)
+        ]
+        IMPRIME A
+    "#;
+
+    let english = r#"
+        TO CUADRADO(N: INT): INT
+            RETURN N * N
+        END
+
+        MAKEGLOBAL A = 0
+        REPEAT 3 [
+            MAKE A = A + 1
+        ]
+
+        IF A > 2 [
+            FORWARD CUADRADO(A)
+        ]
+        PRINT A
+    "#;
+
+    let config = ParserConfig::new().keywords(KeywordTable::spanish());
+
+    let translated =
+        Compiler::compile_with_config(spanish, CompileOptions::default(), &config).unwrap();
+    let canonical = Compiler::compile(english, CompileOptions::default()).unwrap();
+
+    assert_eq!(run(&canonical), run(&translated));
+    assert_eq!((vec!["3".to_string()], (0, 9)), run(&translated));
+}
+
+#[test]
+fn compiler_translated_keywords_not_enabled_by_default() {
+    let res = Compiler::compile("AVANZA 10", CompileOptions::default());
+
+    assert!(res.is_err());
+}
+
+#[test]
+fn compiler_hebrew_program() {
+    let code = r#"
+        לכל ריבוע(צלע: INT)
+            חזור 4 [
+                קדימה צלע
+            ]
+        סוף
+
+        ריבוע(5)
+        הדפס 7
+    "#;
+
+    let mut keywords = KeywordTable::new();
+    keywords.add("לכל", "TO").unwrap();
+    keywords.add("סוף", "END").unwrap();
+    keywords.add("חזור", "REPEAT").unwrap();
+    keywords.add("קדימה", "FORWARD").unwrap();
+    keywords.add("הדפס", "PRINT").unwrap();
+
+    let config = ParserConfig::new().keywords(keywords);
+
+    let program = Compiler::compile_with_config(code, CompileOptions::default(), &config).unwrap();
+
+    assert_eq!((vec!["7".to_string()], (0, 20)), run(&program));
+}
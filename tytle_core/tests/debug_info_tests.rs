@@ -0,0 +1,170 @@
+extern crate tytle;
+
+use tytle::compiler::*;
+use tytle::lexer::Location;
+use tytle::vm::*;
+
+// the code starts at line 1 (so the lines below are the ones reported)
+const CODE: &str = "TO SQUARE(N: INT): INT
+    MAKELOCAL M = N * N
+    RETURN M
+END
+MAKEGLOBAL A = SQUARE(3)
+REPEAT 3 [
+    FORWARD A
+]
+PRINT A";
+
+fn compile(code: &str) -> CompiledProgram {
+    let options = CompileOptions {
+        debug_info: true,
+        ..Default::default()
+    };
+
+    Compiler::compile(code, options).unwrap()
+}
+
+#[test]
+fn debug_info_emitted_only_when_requested() {
+    let program = Compiler::compile(CODE, CompileOptions::default()).unwrap();
+    assert!(program.debug_info.is_none());
+
+    let mut program = compile(CODE);
+    assert!(program.debug_info.is_some());
+
+    program.strip_debug_info();
+
+    assert!(program.ast.is_none());
+    assert!(program.debug_info.is_none());
+
+    let mut host = DummyHost::new();
+    let mut intr = program.interpreter(&mut host);
+
+    assert_eq!(Ok(()), intr.exec_code());
+    assert_eq!(None, intr.current_location());
+    assert_eq!(vec!["9".to_string()], host.get_log());
+}
+
+#[test]
+fn debug_info_statements_locations() {
+    let program = compile(CODE);
+    let debug_info = program.debug_info.as_ref().unwrap();
+
+    for line in 1..=9 {
+        let addrs = debug_info.line_addrs(line);
+
+        // `TO`, `END` and `]` generate no code
+        let has_code = ![1, 4, 8].contains(&line);
+
+        assert_eq!(has_code, !addrs.is_empty(), "line {}", line);
+
+        for (node_id, ip) in addrs {
+            let location = debug_info.location(node_id, ip).unwrap();
+
+            assert_eq!(line, location.line());
+        }
+    }
+
+    let (node_id, ip) = debug_info.line_addrs(7)[0];
+    assert_eq!(Some(Location(7, 5)), debug_info.location(node_id, ip));
+}
+
+#[test]
+fn debug_info_variables_names() {
+    let program = compile(CODE);
+    let debug_info = program.debug_info.as_ref().unwrap();
+
+    let square = debug_info.procs().find(|p| p.name == "SQUARE").unwrap();
+
+    assert_eq!(Some("N"), debug_info.slot_name(square.proc_id, 0));
+    assert_eq!(Some("M"), debug_info.slot_name(square.proc_id, 1));
+    assert_eq!(None, debug_info.slot_name(square.proc_id, 2));
+    assert_eq!(Some("A"), debug_info.global_name(0));
+}
+
+#[test]
+fn debug_info_procs_boundaries() {
+    let program = compile(CODE);
+    let debug_info = program.debug_info.as_ref().unwrap();
+
+    let square = debug_info.procs().find(|p| p.name == "SQUARE").unwrap();
+    let main = debug_info.proc_at(program.cfg.graph.get_entry_node_id()).unwrap();
+
+    assert_eq!(Some(Location(1, 1)), square.location);
+    assert_eq!(None, main.location);
+    assert_eq!(Some(square), debug_info.proc_at(square.entry));
+    assert!(square.nodes.is_disjoint(&main.nodes));
+
+    for (node_id, ip) in debug_info.line_addrs(3) {
+        assert_eq!(Some(square), debug_info.proc_at(node_id));
+        assert!(ip < program.cfg.graph.get_node(node_id).insts.len());
+    }
+}
+
+#[test]
+fn debug_info_breakpoint_pauses_and_resumes() {
+    let program = compile(CODE);
+
+    let mut host = DummyHost::new();
+    let mut intr = program.interpreter(&mut host);
+
+    assert!(intr.set_breakpoint(3));
+    assert!(!intr.set_breakpoint(4));
+
+    assert_eq!(
+        Err(InterpreterException::Breakpoint(Location(3, 5))),
+        intr.exec_code()
+    );
+
+    assert_eq!(Some(Location(3, 5)), intr.current_location());
+
+    let trace = intr.stack_trace();
+    let frames = trace.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(vec!["at __main__ (5:1)", "at SQUARE (3:5)"], frames);
+
+    intr.clear_breakpoints();
+
+    assert_eq!(Ok(()), intr.exec_code());
+    assert_eq!(vec!["9".to_string()], host.get_log());
+}
+
+#[test]
+fn debug_info_breakpoint_in_loop() {
+    let program = compile(CODE);
+
+    let mut host = DummyHost::new();
+    let mut intr = program.interpreter(&mut host);
+
+    intr.set_breakpoint(7);
+
+    let mut hits = 0;
+
+    while let Err(InterpreterException::Breakpoint(location)) = intr.exec_code() {
+        assert_eq!(7, location.line());
+        hits += 1;
+    }
+
+    intr.clear_breakpoint(7);
+    drop(intr);
+
+    assert_eq!(3, hits);
+    assert_eq!((0, 27), host.xycors());
+}
+
+#[test]
+fn debug_info_profiled_lines() {
+    let program = compile(CODE);
+
+    let mut host = DummyHost::new();
+    let mut intr = program.interpreter(&mut host);
+
+    intr.enable_profiling();
+    intr.exec_code().unwrap();
+
+    let report = intr.profile_report().unwrap();
+
+    assert!(report.line_insts[&7] >= 3 * 2);
+    assert!(!report.line_insts.contains_key(&4));
+    assert_eq!(report.total_insts, report.line_insts.values().sum::<usize>());
+}
@@ -0,0 +1,414 @@
+extern crate tytle;
+
+use tytle::diagnostics::*;
+use tytle::lexer::Location;
+use tytle::prelude::{DummyHost, RunOptions};
+use tytle::vm::InterpreterException;
+use tytle::TytleError;
+
+fn run_error(code: &str) -> Diagnostic {
+    let mut host = DummyHost::new();
+    let err = tytle::run(code, &mut host, RunOptions::default())
+        .err()
+        .unwrap();
+
+    Diagnostic::from(&err)
+}
+
+#[test]
+fn diagnostics_render_parse_error() {
+    let code = "FORWARD 10\nMAKEGLOBAL A 10\n";
+
+    let diag = run_error(code);
+    let rendered = DiagnosticRenderer::new().render(&diag, &SourceMap::new(code));
+
+    let expected = r#"error: Unexpected token: `10` (expected `=`)
+ --> 2:14
+  |
+2 | MAKEGLOBAL A 10
+  |              ^^
+"#;
+
+    assert_eq!(expected, rendered);
+}
+
+#[test]
+fn diagnostics_render_lex_error() {
+    let code = "FORWARD 1\u{7}0";
+
+    let diag = run_error(code);
+    assert_eq!(Some(Location(1, 10)), diag.location);
+
+    let rendered = DiagnosticRenderer::new().render(&diag, &SourceMap::new(code));
+    let lines = rendered.lines().collect::<Vec<_>>();
+
+    assert_eq!("error: Invalid character: `\\u{7}`", lines[0]);
+    assert_eq!("  |          ^", lines[4]);
+}
+
+#[test]
+fn diagnostics_render_without_location() {
+    let code = "TO FOO()\nEND\nTO FOO()\nEND";
+
+    let diag = run_error(code);
+    let rendered = DiagnosticRenderer::new().render(&diag, &SourceMap::new(code));
+
+    assert_eq!("error: Duplicate procedure: `FOO`\n", rendered);
+}
+
+#[test]
+fn diagnostics_render_help_and_word_span() {
+    let code = "REPEAT 2 [\n    FORWRD 10\n]";
+
+    let diag = Diagnostic::error("Unknown command")
+        .at(Location(2, 5))
+        .with_help("did you mean FORWARD?");
+
+    let rendered = DiagnosticRenderer::new().render(&diag, &SourceMap::new(code));
+
+    let expected = r#"error: Unknown command
+ --> 2:5
+  |
+2 |     FORWRD 10
+  |     ^^^^^^
+  = help: did you mean FORWARD?
+"#;
+
+    assert_eq!(expected, rendered);
+}
+
+#[test]
+fn diagnostics_render_colored() {
+    let code = "FORWARD 10";
+
+    let diag = Diagnostic::warning("Suspicious").at(Location(1, 1));
+    let rendered = DiagnosticRenderer::colored().render(&diag, &SourceMap::new(code));
+
+    assert!(rendered.starts_with("\u{1b}[1;33mwarning\u{1b}[0m: \u{1b}[1mSuspicious\u{1b}[0m\n"));
+    assert!(rendered.contains("\u{1b}[1;33m^^^^^^^\u{1b}[0m"));
+}
+
+#[test]
+fn diagnostics_render_all() {
+    let code = "FORWARD 10";
+
+    let diags = vec![Diagnostic::warning("first"), Diagnostic::error("second")];
+    let rendered = DiagnosticRenderer::new().render_all(&diags, &SourceMap::new(code));
+
+    assert_eq!("warning: first\n\nerror: second\n", rendered);
+}
+
+#[test]
+fn diagnostics_suggest_misspelled_command() {
+    let code = "REPEAT 2 [\n    FORWRD 10\n]";
+
+    let diag = run_error(code);
+    let rendered = DiagnosticRenderer::new().render(&diag, &SourceMap::new(code));
+
+    let expected = r#"error: Unknown command: `FORWRD`
+ --> 2:5
+  |
+2 |     FORWRD 10
+  |     ^^^^^^
+  = help: did you mean `FORWARD`?
+"#;
+
+    assert_eq!(expected, rendered);
+}
+
+#[test]
+fn diagnostics_unknown_command_without_suggestion() {
+    let diag = run_error("JUMP 10");
+
+    assert_eq!("Unknown command: `JUMP`", diag.message);
+    assert_eq!(None, diag.help);
+}
+
+#[test]
+fn diagnostics_suggest_misspelled_variable() {
+    let code = "MAKEGLOBAL LENGTH = 10\nFORWARD LENGHT";
+
+    let diag = run_error(code);
+
+    assert_eq!("Missing variable declaration for `LENGHT`", diag.message);
+    assert_eq!(Some("did you mean `LENGTH`?".to_string()), diag.help);
+}
+
+#[test]
+fn diagnostics_suggest_misspelled_local_variable() {
+    let code = r#"
+        TO SQUARE(SIDE: INT)
+            REPEAT 4 [
+                FORWARD SIDES
+                RIGHT 90
+            ]
+        END
+    "#;
+
+    let diag = run_error(code);
+
+    assert_eq!(Some("did you mean `SIDE`?".to_string()), diag.help);
+}
+
+#[test]
+fn diagnostics_suggest_misspelled_procedure() {
+    let code = "TO SQUARE()\nEND\nSQAURE()";
+
+    let diag = run_error(code);
+
+    assert_eq!("Missing procedure declaration for `SQAURE`", diag.message);
+    assert_eq!(Some("did you mean `SQUARE`?".to_string()), diag.help);
+}
+
+fn run_diagnostics(code: &str) -> Diagnostics {
+    Diagnostics::new(code, vec![run_error(code)])
+}
+
+#[test]
+fn diagnostics_render_english_by_default_locale() {
+    let code = "MAKEGLOBAL LENGTH = 10\nFORWARD LENGHT";
+
+    let diags = run_diagnostics(code);
+    let diag = diags.iter().next().unwrap();
+
+    assert_eq!(
+        DiagnosticRenderer::new().render(diag, &SourceMap::new(code)),
+        diags.render(DEFAULT_LOCALE)
+    );
+}
+
+#[test]
+fn diagnostics_english_catalog_matches_messages() {
+    let codes = vec![
+        "FORWARD 10\nMAKEGLOBAL A 10\n",
+        "FORWARD 1\u{7}0",
+        "TO FOO()\nEND\nTO FOO()\nEND",
+        "REPEAT 2 [\n    FORWRD 10\n]",
+        "TO SQUARE()\nEND\nSQAURE()",
+        "TO MYPROC(A: INT, A: INT)\nEND",
+        "MAKEGLOBAL A = 1 + TRUE",
+        "TO PRINT()\nEND",
+    ];
+
+    for code in codes {
+        let diag = run_error(code);
+        let localized = Diagnostics::new(code, vec![]).localize(&diag, "en");
+
+        assert!(diag.localizable.is_some());
+        assert_eq!(diag, localized);
+    }
+}
+
+#[test]
+fn diagnostics_render_spanish() {
+    let code = "REPEAT 2 [\n    FORWRD 10\n]";
+
+    let expected = r#"error: Comando desconocido: `FORWRD`
+ --> 2:5
+  |
+2 |     FORWRD 10
+  |     ^^^^^^
+  = ayuda: ¿quisiste decir `FORWARD`?
+"#;
+
+    assert_eq!(expected, run_diagnostics(code).render("es"));
+}
+
+#[test]
+fn diagnostics_render_locale_by_language() {
+    let code = "TO FOO()\nEND\nTO FOO()\nEND";
+
+    assert_eq!(
+        "error: Procedimiento duplicado: `FOO`\n",
+        run_diagnostics(code).render("es-AR")
+    );
+}
+
+#[test]
+fn diagnostics_render_unknown_locale_falls_back_to_english() {
+    let code = "TO FOO()\nEND\nTO FOO()\nEND";
+
+    assert_eq!(
+        "error: Duplicate procedure: `FOO`\n",
+        run_diagnostics(code).render("he")
+    );
+}
+
+#[test]
+fn diagnostics_render_custom_catalog() {
+    let code = "TO FOO()\nEND\nTO FOO()\nEND";
+
+    let catalog = Catalog::parse(
+        "fr",
+        "label.error = erreur\nsemantic.duplicate-proc = Procédure dupliquée : `{proc}`",
+    )
+    .unwrap();
+
+    let mut diags = run_diagnostics(code);
+    diags.add_catalog(catalog);
+
+    assert_eq!(vec!["en", "es", "fr"], diags.locales());
+    assert_eq!("erreur: Procédure dupliquée : `FOO`\n", diags.render("fr"));
+}
+
+#[test]
+fn diagnostics_render_partial_catalog_falls_back_to_english() {
+    let code = "TO FOO()\nEND\nTO FOO()\nEND";
+
+    let mut diags = run_diagnostics(code);
+    diags.add_catalog(Catalog::parse("fr", "label.error = erreur").unwrap());
+
+    assert_eq!("erreur: Duplicate procedure: `FOO`\n", diags.render("fr"));
+}
+
+#[test]
+fn diagnostics_render_not_localizable() {
+    let code = "FORWARD 10";
+
+    let diags = Diagnostics::new(code, vec![Diagnostic::warning("Custom").at(Location(1, 1))]);
+
+    assert!(diags.render("es").starts_with("advertencia: Custom\n"));
+}
+
+#[test]
+fn diagnostics_runtime_errors() {
+    let err = TytleError::Runtime(InterpreterException::StackOverflow);
+    let diags = Diagnostics::new("", vec![Diagnostic::from(&err)]);
+
+    assert_eq!(
+        "error: Stack overflow (too many nested procedure calls)\n",
+        diags.render("en")
+    );
+    assert_eq!(
+        "error: Desbordamiento de pila (demasiadas llamadas anidadas a procedimientos)\n",
+        diags.render("es")
+    );
+}
+
+#[test]
+fn diagnostics_assertion_failure() {
+    let code = "MAKEGLOBAL N = 2\nASSERT N > 2 \"N must be large\"";
+
+    let diag = run_error(code);
+
+    assert_eq!("Assertion failed: N must be large", diag.message);
+    assert_eq!(Some(Location(2, 1)), diag.location);
+    assert_eq!(Some("variables: N = 2".to_string()), diag.help);
+
+    let diags = Diagnostics::new(code, vec![diag]);
+    assert!(diags
+        .render("es")
+        .starts_with("error: La aserción falló: N must be large\n"));
+}
+
+#[test]
+fn diagnostics_render_after_unicode_text() {
+    let code = "MAKEGLOBAL אורך = 10\nFORWARD אורך + ארך";
+
+    let diag = run_error(code);
+    let rendered = DiagnosticRenderer::new().render(&diag, &SourceMap::new(code));
+
+    assert_eq!(Some("did you mean `אורך`?".to_string()), diag.help);
+    assert!(rendered.starts_with("error: Missing variable declaration for `ארך`\n"));
+}
+
+#[test]
+fn diagnostics_render_caret_after_emoji() {
+    let code = "PRINT \"🐢\" 🐢\u{7}";
+
+    let diag = run_error(code);
+    let rendered = DiagnosticRenderer::new().render(&diag, &SourceMap::new(code));
+
+    assert_eq!(Some(Location(1, 12)), diag.location);
+    assert_eq!("  |            ^", rendered.lines().nth(4).unwrap());
+}
+
+#[test]
+fn diagnostics_render_with_tabs_and_crlf() {
+    let code = "FORWARD 10\r\n\tMAKEGLOBAL A 10\r\n";
+
+    let diag = run_error(code);
+    let rendered = DiagnosticRenderer::new().render(&diag, &SourceMap::new(code));
+
+    let expected = r#"error: Unexpected token: `10` (expected `=`)
+ --> 2:18
+  |
+2 |     MAKEGLOBAL A 10
+  |                  ^^
+"#;
+
+    assert_eq!(expected, rendered);
+}
+
+#[test]
+fn diagnostics_byte_range_for_editors() {
+    let code = "FORWARD 10\r\n\tMAKEGLOBAL A 10\r\n";
+
+    let diag = run_error(code);
+    let source_map = SourceMap::new(code);
+
+    let range = source_map
+        .main_file()
+        .unwrap()
+        .byte_range(diag.location.unwrap(), diag.len.unwrap())
+        .unwrap();
+
+    assert_eq!(26..28, range);
+    assert_eq!("10", &code[range]);
+}
+
+#[test]
+fn diagnostics_render_within_loaded_file() {
+    let mut source_map = SourceMap::new("LOAD \"SHAPES\nSQUARE(10)");
+    let shapes = source_map.add_file("SHAPES", "TO SQUARE(N: INT)\n\tFORWARD M\nEND");
+
+    let diag = Diagnostic::error("Missing variable declaration `M`")
+        .at(Location(2, 13))
+        .in_file(shapes);
+
+    let rendered = DiagnosticRenderer::new().render(&diag, &source_map);
+
+    let expected = r#"error: Missing variable declaration `M`
+ --> SHAPES:2:13
+  |
+2 |     FORWARD M
+  |             ^
+"#;
+
+    assert_eq!(expected, rendered);
+}
+
+#[test]
+fn diagnostics_localized_within_loaded_file() {
+    let mut source_map = SourceMap::new("LOAD \"SHAPES");
+    let shapes = source_map.add_file("SHAPES", "FORWARD\n");
+
+    let diag = Diagnostic::error("Execution cancelled")
+        .localized(Message::new("runtime.cancelled"))
+        .at(Location(1, 1))
+        .in_file(shapes);
+
+    let diags = Diagnostics::with_source_map(source_map, vec![diag]);
+
+    assert!(diags.render("en").contains(" --> SHAPES:1:1\n"));
+}
+
+#[test]
+fn diagnostics_variable_declaration_rules() {
+    let diag = run_error("MAKE A = 20\nMAKEGLOBAL A = 10");
+    assert_eq!(
+        "Variable `A` is assigned before its declaration",
+        diag.message
+    );
+
+    let diag = run_error("REPEAT 2 [\nMAKEGLOBAL A = 10\n]");
+    assert_eq!(
+        "Globals must be declared at the top level, outside of any block (variable: `A`)",
+        diag.message
+    );
+
+    let diags = Diagnostics::new("", vec![diag]);
+    assert!(diags
+        .render("es")
+        .contains("Las variables globales deben declararse en el nivel superior"));
+}
@@ -0,0 +1,97 @@
+extern crate tytle;
+
+use tytle::compiler::*;
+
+const CODE: &str = "TO SQUARE(N: INT): INT
+    RETURN N * N
+END
+MAKEGLOBAL A = SQUARE(3)
+IF A > 5 [
+    PRINT A
+]";
+
+fn compile(code: &str) -> CompiledProgram {
+    let options = CompileOptions {
+        debug_info: true,
+        ..Default::default()
+    };
+
+    Compiler::compile(code, options).unwrap()
+}
+
+#[test]
+fn disassemble_with_debug_info() {
+    let program = compile(CODE);
+    let debug_info = program.debug_info.as_ref().unwrap();
+
+    let expected = r#"proc __main__ (entry @1)
+  @1:
+       0  int 3                     ; 4:1
+       1  call @2 (SQUARE)
+       2  store A
+       3  load A                    ; 5:1
+       4  int 5
+       5  greater_than
+    -> @3 when_true (A > 5)
+    -> @4 fallback
+  @3:
+       0  load A                    ; 6:5
+       1  print
+    -> @4 always
+  @4:
+       0  eoc
+
+proc SQUARE (entry @2, 1:1)
+  @2:
+       0  load N                    ; 2:5
+       1  load N
+       2  mul
+       3  return
+"#;
+
+    assert_eq!(expected, program.cfg.disassemble_with_debug_info(debug_info));
+}
+
+#[test]
+fn disassemble_without_debug_info() {
+    let program = compile(CODE);
+
+    let listing = program.cfg.disassemble();
+
+    assert!(listing.starts_with("proc #0 (entry @1)\n"));
+    assert!(listing.contains("       1  call @2 (#1)\n"));
+    assert!(listing.contains("\nproc #1 (entry @2)\n"));
+    assert!(!listing.contains(';'));
+}
+
+#[test]
+fn disassemble_unreachable_nodes() {
+    let mut program = compile("PRINT 1");
+
+    let node_id = program.cfg.graph.new_node();
+
+    let listing = program.cfg.disassemble();
+    let unreachable = format!("\nunreachable\n  @{}:\n", node_id);
+
+    assert!(listing.ends_with(&unreachable));
+}
+
+#[test]
+fn disassemble_operands() {
+    let code = r#"
+        CATCH "ERROR [
+            THROW "OOPS
+        ]
+        PRINT "Hello
+    "#;
+
+    let program = compile(code);
+    let debug_info = program.debug_info.as_ref().unwrap();
+
+    let listing = program.cfg.disassemble_with_debug_info(debug_info);
+
+    assert!(listing.contains("  catch \"ERROR\" @"));
+    assert!(listing.contains("  throw \"OOPS\""));
+    assert!(listing.contains("  str \"Hello\""));
+    assert!(listing.contains("  end_catch"));
+}
@@ -0,0 +1,416 @@
+extern crate tytle;
+
+use tytle::ast::expression::ExpressionType;
+use tytle::ast::statement::{Command, Direction};
+use tytle::diagnostics::Diagnostics;
+use tytle::grading::{
+    grade, Axis, Divergence, Drawing, Exercise, ProcSignature, Segment, GRADE_MAX_STEPS,
+};
+use tytle::prelude::{DummyHost, HostCall, RecordingHost, RunOptions};
+use tytle::TytleError;
+
+fn drawing(code: &str) -> Drawing {
+    let mut host = RecordingHost::new(DummyHost::new());
+    tytle::run(code, &mut host, RunOptions::default()).unwrap();
+
+    let (_, calls) = host.into_parts();
+    Drawing::from_host_calls(&calls)
+}
+
+#[test]
+fn drawing_collects_pen_down_segments() {
+    let code = r#"
+        FORWARD 10
+        PENUP
+        RIGHT 5
+        PENDOWN
+        RIGHT 20
+    "#;
+
+    let drawing = drawing(code);
+
+    let expected = vec![Segment::new((0, 0), (0, 10)), Segment::new((5, 10), (25, 10))];
+
+    assert_eq!(expected, drawing.segments());
+    assert_eq!(30.0, drawing.path_length());
+
+    let bounds = drawing.bounds().unwrap();
+    assert_eq!((0, 0, 25, 10), (bounds.min_x, bounds.min_y, bounds.max_x, bounds.max_y));
+}
+
+#[test]
+fn drawing_segments_angles() {
+    let code = r#"
+        SETX 50
+        SETY 50
+        FORWARD 10
+        RIGHT 10
+        BACKWARD 10
+        LEFT 10
+    "#;
+
+    assert_eq!(vec![0.0, 90.0, 90.0, 0.0, 270.0, 180.0], drawing(code).angles());
+}
+
+#[test]
+fn drawing_counts_closed_polygons() {
+    let code = r#"
+        TO SQUARE(SIZE: INT)
+            FORWARD SIZE
+            RIGHT SIZE
+            BACKWARD SIZE
+            LEFT SIZE
+        END
+
+        SQUARE(10)
+
+        PENUP
+        RIGHT 100
+        PENDOWN
+        SQUARE(20)
+
+        FORWARD 30
+        RIGHT 30
+    "#;
+
+    assert_eq!(2, drawing(code).closed_polygons());
+}
+
+#[test]
+fn drawing_open_path_is_not_a_polygon() {
+    let code = r#"
+        FORWARD 10
+        RIGHT 10
+        BACKWARD 10
+        PENUP
+        LEFT 10
+    "#;
+
+    assert_eq!(0, drawing(code).closed_polygons());
+}
+
+#[test]
+fn drawing_symmetry() {
+    // an `L` shape
+    let code = r#"
+        FORWARD 20
+        BACKWARD 20
+        RIGHT 10
+    "#;
+
+    let shape = drawing(code);
+    assert!(!shape.is_symmetric(Axis::Vertical));
+    assert!(!shape.is_symmetric(Axis::Horizontal));
+
+    // a `T` shape
+    let code = r#"
+        PENUP
+        RIGHT 10
+        PENDOWN
+        FORWARD 20
+        LEFT 10
+        PENUP
+        RIGHT 10
+        PENDOWN
+        RIGHT 10
+    "#;
+
+    let shape = drawing(code);
+    assert!(shape.is_symmetric(Axis::Vertical));
+    assert!(!shape.is_symmetric(Axis::Horizontal));
+}
+
+#[test]
+fn drawing_congruence_to_reference() {
+    let reference = Drawing::new(vec![
+        Segment::new((0, 0), (0, 20)),
+        Segment::new((0, 0), (10, 0)),
+    ]);
+
+    // the same `L` shape, mirrored and drawn elsewhere
+    let code = r#"
+        PENUP
+        SETX 100
+        SETY 100
+        PENDOWN
+        RIGHT 10
+        FORWARD 20
+    "#;
+
+    let shape = drawing(code);
+    assert!(shape.is_congruent(&reference, 0.0));
+
+    // a slightly longer arm
+    let code = r#"
+        FORWARD 22
+        PENUP
+        BACKWARD 22
+        PENDOWN
+        RIGHT 10
+    "#;
+
+    let shape = drawing(code);
+    assert!(!shape.is_congruent(&reference, 0.0));
+    assert!(shape.is_congruent(&reference, 2.0));
+
+    // a different shape
+    let code = r#"
+        FORWARD 20
+        RIGHT 20
+    "#;
+
+    let shape = drawing(code);
+    assert!(!shape.is_congruent(&reference, 0.0));
+}
+
+#[test]
+fn grade_same_drawing_in_another_order() {
+    let reference = r#"
+        FORWARD 10
+        RIGHT 10
+        BACKWARD 10
+        LEFT 10
+    "#;
+
+    // the same square, drawn counterclockwise
+    let student = r#"
+        TO SIDE(DIST: INT)
+            RIGHT DIST
+        END
+
+        SIDE(10)
+        FORWARD 10
+        LEFT 10
+        BACKWARD 10
+    "#;
+
+    let report = grade(student, reference, 0.0).unwrap();
+
+    assert_eq!(1.0, report.score);
+    assert_eq!(1, report.student.closed_polygons());
+
+    let divergence = Divergence {
+        index: 0,
+        student: Some(HostCall::Direct(Direction::Right, 10)),
+        reference: Some(HostCall::Direct(Direction::Forward, 10)),
+    };
+
+    assert_eq!(Some(divergence), report.divergence);
+}
+
+#[test]
+fn grade_partial_drawing() {
+    let reference = r#"
+        FORWARD 10
+        RIGHT 10
+        BACKWARD 10
+        LEFT 10
+    "#;
+
+    let student = r#"
+        FORWARD 11
+        RIGHT 10
+        PENUP
+        BACKWARD 10
+    "#;
+
+    let report = grade(student, reference, 1.0).unwrap();
+    assert_eq!(0.5, report.score);
+
+    let divergence = Divergence {
+        index: 0,
+        student: Some(HostCall::Direct(Direction::Forward, 11)),
+        reference: Some(HostCall::Direct(Direction::Forward, 10)),
+    };
+
+    assert_eq!(Some(divergence), report.divergence);
+
+    let report = grade(student, reference, 0.0).unwrap();
+    assert_eq!(0.0, report.score);
+}
+
+#[test]
+fn grade_identical_programs() {
+    let code = r#"
+        REPEAT 2 [
+            FORWARD 10
+            RIGHT 10
+        ]
+    "#;
+
+    let report = grade(code, code, 0.0).unwrap();
+
+    assert_eq!(1.0, report.score);
+    assert_eq!(None, report.divergence);
+}
+
+#[test]
+fn grade_missing_commands() {
+    let reference = r#"
+        FORWARD 10
+        PENUP
+    "#;
+
+    let report = grade("FORWARD 10", reference, 0.0).unwrap();
+
+    assert_eq!(1.0, report.score);
+
+    let divergence = Divergence {
+        index: 1,
+        student: None,
+        reference: Some(HostCall::Cmd(Command::PenUp)),
+    };
+
+    assert_eq!(Some(divergence), report.divergence);
+}
+
+#[test]
+fn grade_aborts_endless_program() {
+    let student = r#"
+        REPEAT 1000000000 [
+            FORWARD 1
+        ]
+    "#;
+
+    let err = grade(student, "FORWARD 10", 0.0).unwrap_err();
+    assert_eq!(TytleError::StepLimitExceeded(GRADE_MAX_STEPS), err);
+}
+
+const SQUARE_SOLUTION: &str = r#"
+    TO SQUARE(SIZE: INT)
+        FORWARD SIZE
+        RIGHT SIZE
+        BACKWARD SIZE
+        LEFT SIZE
+    END
+"#;
+
+fn square_exercise() -> Exercise {
+    let signature = ProcSignature::new("SQUARE", vec![ExpressionType::Int], ExpressionType::Unit);
+
+    Exercise::new()
+        .require_proc(signature)
+        .case("SQUARE(50)")
+        .case("SQUARE(120)")
+        .reference(SQUARE_SOLUTION, 0.0)
+}
+
+#[test]
+fn exercise_passes_all_cases() {
+    // the same square, drawn counterclockwise
+    let student = r#"
+        TO SQUARE(LEN: INT)
+            RIGHT LEN
+            FORWARD LEN
+            LEFT LEN
+            BACKWARD LEN
+        END
+    "#;
+
+    let report = square_exercise().check(student);
+
+    assert!(report.passed());
+    assert_eq!(2, report.passed_cases());
+    assert_eq!("SQUARE(120)", report.cases[1].invocation);
+}
+
+#[test]
+fn exercise_fails_mismatching_case() {
+    // ignores the size
+    let student = r#"
+        TO SQUARE(SIZE: INT)
+            FORWARD 50
+            RIGHT 50
+            BACKWARD 50
+            LEFT 50
+        END
+    "#;
+
+    let report = square_exercise().check(student);
+
+    assert!(!report.passed());
+    assert_eq!(1, report.passed_cases());
+
+    let case = &report.cases[1];
+    assert!(!case.passed);
+    assert_eq!(
+        "The drawing of `SQUARE(120)` differs from the expected one (0% similar)",
+        case.diagnostics[0].message
+    );
+}
+
+#[test]
+fn exercise_reports_signature_errors() {
+    let student = r#"
+        TO SQUARE(SIZE: INT, COLOR: INT)
+            FORWARD SIZE
+        END
+    "#;
+
+    let exercise = square_exercise().require_proc(ProcSignature::new(
+        "TRIANGLE",
+        vec![ExpressionType::Int],
+        ExpressionType::Unit,
+    ));
+
+    let report = exercise.check(student);
+
+    let messages = report
+        .diagnostics
+        .iter()
+        .map(|diag| diag.message.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            "Procedure `SQUARE` should be `SQUARE(INT)` (got `SQUARE(INT, INT)`)",
+            "Missing procedure `TRIANGLE(INT)`",
+        ],
+        messages
+    );
+
+    assert!(!report.passed());
+    assert!(report.cases.is_empty());
+
+    // the English catalog renders the same messages
+    for diag in report.diagnostics {
+        assert_eq!(diag, Diagnostics::new(student, vec![]).localize(&diag, "en"));
+    }
+}
+
+#[test]
+fn exercise_reports_compilation_error() {
+    let report = square_exercise().check("TO SQUARE(SIZE: INT)\n    FORWRD SIZE\nEND");
+
+    assert_eq!(1, report.diagnostics.len());
+    assert!(report.diagnostics[0].message.contains("FORWRD"));
+    assert!(!report.passed());
+}
+
+#[test]
+fn exercise_without_reference_checks_cases_run() {
+    let student = r#"
+        TO HALF(N: INT): INT
+            ASSERT N > 0 "positive"
+            RETURN N / 2
+        END
+    "#;
+
+    let signature = ProcSignature::new("HALF", vec![ExpressionType::Int], ExpressionType::Int);
+
+    let exercise = Exercise::new()
+        .require_proc(signature)
+        .case("PRINT HALF(10)")
+        .case("PRINT HALF(0)");
+
+    let report = exercise.check(student);
+
+    assert!(report.diagnostics.is_empty());
+    assert!(report.cases[0].passed);
+    assert!(!report.cases[1].passed);
+    assert_eq!(
+        "Assertion failed: positive",
+        report.cases[1].diagnostics[0].message
+    );
+    assert_eq!(1, report.passed_cases());
+}
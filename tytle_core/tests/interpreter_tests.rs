@@ -1,7 +1,17 @@
 extern crate tytle;
 
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use tytle::ast::expression::{BinaryOp, ExpressionType};
 use tytle::ast::semantic::*;
+use tytle::ast::statement::Direction;
 use tytle::ir::*;
+use tytle::lexer::Location;
 use tytle::parser::{Parser, TytleParser};
 use tytle::vm::*;
 
@@ -492,14 +502,101 @@ pub fn interpreter_clear_screen() {
 }
 
 #[test]
-#[ignore]
 pub fn interpreter_set_pen_color() {
     let code = r#"
-         SETPENCOLOR [255 255 255]
+        TO IDENTITY(K: COLOR): COLOR
+            RETURN K
+        END
+
+        MAKEGLOBAL C = RGB 300 128 PARSE("-5)
+        ASSERT TYPEOF C = "COLOR
+
+        SETPENCOLOR C
+        SETPENCOLOR 4
+        SETPENCOLOR "yellow
+        ASSERT PENCOLOR() = NAMEDCOLOR("YELLOW)
+
+        SETPENCOLOR IDENTITY(PALETTE(1))
     "#;
 
     setup_interpreter!(code, env, cfg, host, intr);
-    let _ = intr.exec_code();
+    assert_eq!(Ok(()), intr.exec_code());
+    assert_eq!(Color::new(0, 0, 255), intr.memory.pen.get_color());
+
+    let expected = vec![
+        "SETPENCOLOR RGB 255 128 0",
+        "SETPENCOLOR RGB 255 0 0",
+        "SETPENCOLOR RGB 255 255 0",
+        "SETPENCOLOR RGB 0 0 255",
+    ];
+
+    assert_eq!(expected, host.get_log());
+    assert_eq!(Color::new(0, 0, 255), host.get_pen().get_color());
+}
+
+#[test]
+pub fn interpreter_push_and_pop_state() {
+    let code = r#"
+        TO TREE(N: INT)
+            IF N > 0 [
+                PUSHSTATE
+                PENUP
+                FORWARD N
+                TREE(N / 2)
+                POPSTATE
+                RIGHT N
+            ]
+        END
+
+        TREE(4)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    // each branch returns to where it has started (with its pen down) before moving right
+    assert_eq!((4, 0), (intr.memory.turtle.xcor(), intr.memory.turtle.ycor()));
+    assert_eq!(&PenState::Down, intr.memory.pen.get_state());
+    assert!(intr.memory.states.is_empty());
+
+    let expected = vec![
+        "PENUP",
+        "PENUP",
+        "PENUP",
+        "POPSTATE at (0, 6) heading 0",
+        "POPSTATE at (0, 4) heading 0",
+        "POPSTATE at (0, 0) heading 0",
+    ];
+
+    assert_eq!(expected, host.get_log());
+    assert_eq!((4, 0), (host.get_turtle().xcor(), host.get_turtle().ycor()));
+    assert_eq!(&PenState::Down, host.get_pen().get_state());
+}
+
+#[test]
+pub fn interpreter_push_and_pop_state_errors() {
+    setup_interpreter!("FORWARD 10\nPOPSTATE", env, cfg, host, intr);
+    assert_eq!(Err(InterpreterException::StateStackEmpty), intr.exec_code());
+
+    let code = format!("REPEAT {} [ PUSHSTATE ]", MAX_STATE_DEPTH + 1);
+
+    setup_interpreter!(&code, env, cfg, host, intr);
+    assert_eq!(Err(InterpreterException::StateStackOverflow), intr.exec_code());
+    assert_eq!(MAX_STATE_DEPTH, intr.memory.states.len());
+}
+
+#[test]
+pub fn interpreter_set_pen_color_unknown() {
+    setup_interpreter!("SETPENCOLOR 16", env, cfg, host, intr);
+
+    let expected = InterpreterException::UnknownColor("16".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+
+    setup_interpreter!(r#"MAKEGLOBAL C = NAMEDCOLOR("PINK)"#, env, cfg, host, intr);
+
+    let expected = InterpreterException::UnknownColor("PINK".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+    assert_eq!(Color::default(), intr.memory.pen.get_color());
 }
 
 #[test]
@@ -532,6 +629,444 @@ pub fn interpreter_hide_turtle() {
     assert_eq!(false, host.get_turtle().is_visible());
 }
 
+#[test]
+pub fn interpreter_proc_stmts_after_if_stmt() {
+    let code = r#"
+        TO MYPROC(N: INT): INT
+            MAKELOCAL A = 0
+            IF N > 1 [MAKE A = 10]
+            MAKE A = A + 1
+            RETURN A
+        END
+
+        PRINT MYPROC(5)
+        PRINT MYPROC(0)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let _ = intr.exec_code();
+
+    assert_eq!(vec!["11", "1"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_exec_with_cancel_not_cancelled() {
+    let code = r#"
+        FORWARD 10
+        RIGHT 20
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let cancel = Arc::new(AtomicBool::new(false));
+    let res = intr.exec_with_cancel(cancel);
+
+    assert_eq!(Ok(()), res);
+    assert_eq!((20, 10), host.xycors());
+}
+
+#[test]
+pub fn interpreter_exec_with_cancel_already_cancelled() {
+    let code = "FORWARD 10";
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let cancel = Arc::new(AtomicBool::new(true));
+    let res = intr.exec_with_cancel(cancel);
+
+    assert_eq!(Err(InterpreterException::Cancelled), res);
+    assert!(host.get_log().is_empty());
+}
+
+#[test]
+pub fn interpreter_exec_with_cancel_aborts_runaway_program() {
+    let code = r#"
+        MAKEGLOBAL A = 0
+        REPEAT 1000000000 [MAKE A = A + 1]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    let stopper = {
+        let cancel = Arc::clone(&cancel);
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            cancel.store(true, Ordering::Relaxed);
+        })
+    };
+
+    let res = intr.exec_with_cancel(cancel);
+    stopper.join().unwrap();
+
+    assert_eq!(Err(InterpreterException::Cancelled), res);
+}
+
+#[test]
+pub fn interpreter_memory_tracks_turtle_and_pen() {
+    let code = r#"
+        FORWARD 10
+        RIGHT 20
+        PENUP
+        HIDETURTLE
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let _ = intr.exec_code();
+
+    assert_eq!(20, intr.memory.turtle.xcor());
+    assert_eq!(10, intr.memory.turtle.ycor());
+    assert!(!intr.memory.turtle.is_visible());
+    assert_eq!(&PenState::Up, intr.memory.pen.get_state());
+}
+
+#[test]
+pub fn interpreter_turtle_config_center_origin_with_turns() {
+    let code = r#"
+        FORWARD 10
+        RIGHT 90
+        FORWARD 5
+        LEFT 270
+        FORWARD 20
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    intr.set_turtle_config(TurtleConfig {
+        origin: Origin::Center,
+        y_axis: YAxis::Down,
+        angle_unit: AngleUnit::Degrees,
+        heading: 90.0,
+        steering: Steering::Turn,
+    });
+
+    let _ = intr.exec_code();
+
+    // heading right, then down (the y axis goes down) and then left, past the origin
+    assert_eq!((-10, 5), (intr.memory.turtle.xcor(), intr.memory.turtle.ycor()));
+    assert_eq!(270.0, intr.memory.turtle.heading());
+}
+
+#[test]
+pub fn interpreter_turtle_config_strafes_along_the_heading() {
+    let code = r#"
+        FORWARD 10
+        LEFT 4
+        XCOR
+        YCOR
+        RIGHT 10
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    intr.set_turtle_config(TurtleConfig {
+        angle_unit: AngleUnit::Radians,
+        heading: std::f64::consts::FRAC_PI_2,
+        ..TurtleConfig::default()
+    });
+
+    let _ = intr.exec_code();
+
+    // the corner origin clamps the last move at the bottom edge
+    assert_eq!((10, 0), (intr.memory.turtle.xcor(), intr.memory.turtle.ycor()));
+    assert_eq!(std::f64::consts::FRAC_PI_2, intr.memory.turtle.heading());
+}
+
+#[test]
+pub fn interpreter_turtle_config_defaults_to_the_tytle_convention() {
+    let config = TurtleConfig::default();
+
+    assert_eq!(Origin::Corner, config.origin);
+    assert_eq!(YAxis::Up, config.y_axis);
+    assert_eq!(Steering::Strafe, config.steering);
+    assert_eq!(&config, Turtle::new().config());
+}
+
+#[test]
+pub fn interpreter_movement_granularity() {
+    let code = r#"
+        FORWARD 10
+        RIGHT 5
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_movement_granularity(2);
+
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let expected = vec![
+        "MOVE Forward 10",
+        "FRAME 1 at (0.0, 5.0) heading 0",
+        "FRAME 2 at (0.0, 10.0) heading 0",
+        "MOVE Right 5",
+        "FRAME 1 at (2.5, 10.0) heading 0",
+        "FRAME 2 at (5.0, 10.0) heading 0",
+    ];
+
+    assert_eq!(expected, host.get_log());
+    assert_eq!((5, 10), (host.get_turtle().xcor(), host.get_turtle().ycor()));
+}
+
+#[test]
+pub fn interpreter_movement_granularity_tweens_the_whole_turn() {
+    setup_interpreter!("RIGHT 450", env, cfg, host, intr);
+
+    intr.set_turtle_config(TurtleConfig {
+        steering: Steering::Turn,
+        ..TurtleConfig::default()
+    });
+    intr.set_movement_granularity(2);
+
+    assert_eq!(Ok(()), intr.exec_code());
+    assert_eq!(90.0, intr.memory.turtle.heading());
+
+    let expected = vec![
+        "MOVE Right 450",
+        "FRAME 1 at (0.0, 0.0) heading 225",
+        "FRAME 2 at (0.0, 0.0) heading 450",
+    ];
+
+    assert_eq!(expected, host.get_log());
+}
+
+#[test]
+pub fn interpreter_snapshot_and_restore() {
+    let code = r#"
+        MAKEGLOBAL A = 0
+
+        REPEAT 5 [
+            MAKE A = A + 1
+            FORWARD 1
+        ]
+
+        PRINT A
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    for _ in 0..15 {
+        let _ = intr.exec_next();
+    }
+
+    let snapshot = intr.snapshot();
+    let _ = intr.exec_code();
+
+    assert_eq!(5, intr.memory.turtle.ycor());
+    assert_ne!(snapshot, intr.snapshot());
+
+    intr.restore(snapshot.clone());
+    assert_eq!(snapshot, intr.snapshot());
+
+    let _ = intr.exec_code();
+    assert_eq!(5, intr.memory.turtle.ycor());
+    assert_eq!(vec!["5", "5"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_snapshot_is_serializable() {
+    let code = r#"
+        TO MYPROC(N: INT)
+            MAKELOCAL B = TRUE
+            FORWARD N
+            TRAP
+        END
+
+        MAKEGLOBAL A = 3
+        MYPROC(A)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    while intr.memory.turtle.ycor() == 0 {
+        let _ = intr.exec_next();
+    }
+
+    let snapshot = intr.snapshot();
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let restored: VmSnapshot = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(snapshot, restored);
+    assert_eq!(2, restored.call_stack.frames.len());
+    assert_eq!(3, restored.memory.turtle.ycor());
+}
+
+#[test]
+pub fn interpreter_frames_slots() {
+    let code = r#"
+        TO MYPROC(N: INT, M: INT)
+            MAKELOCAL B = N > M
+            FORWARD N
+            TRAP
+        END
+
+        MAKEGLOBAL A = 3
+        MYPROC(A, 1)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    while intr.memory.turtle.ycor() == 0 {
+        let _ = intr.exec_next();
+    }
+
+    let frame = intr.call_stack.current_frame();
+
+    // `N`, `M` and `B`
+    assert_eq!(3, frame.slots_count());
+    assert_eq!(&CallStackItem::Int(3), frame.load(0));
+    assert_eq!(&CallStackItem::Int(1), frame.load(1));
+    assert_eq!(&CallStackItem::Bool(true), frame.load(2));
+
+    assert_eq!(Some(&MemoryValue::Int(3)), intr.memory.get_global(Address(0)));
+}
+
+#[test]
+pub fn interpreter_on_event() {
+    let code = r#"
+        TO MYPROC(N: INT)
+            MAKELOCAL B = N > 1
+        END
+
+        MAKEGLOBAL A = 1
+
+        REPEAT 2 [
+            FORWARD A
+        ]
+
+        MYPROC(A)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let observer_events = Rc::clone(&events);
+
+    intr.on_event(move |e| observer_events.borrow_mut().push(e.clone()));
+    let _ = intr.exec_code();
+
+    let a = env.globals_symbols[&0];
+    let myproc = env.symbol_table.get_proc_by_name("MYPROC");
+    let b = env.locals_symbols[&myproc.id]
+        .iter()
+        .find(|var_id| env.symbol_table.get_var_by_id(**var_id).name == "B")
+        .unwrap();
+
+    let events = events.borrow();
+    let loop_iterations = events
+        .iter()
+        .filter(|e| matches!(e, VmEvent::LoopIteration(_)))
+        .count();
+
+    assert_eq!(2, loop_iterations);
+
+    let events: Vec<&VmEvent> = events
+        .iter()
+        .filter(|e| !matches!(e, VmEvent::LoopIteration(_)))
+        .collect();
+
+    assert_eq!(
+        vec![
+            &VmEvent::VarWrite {
+                var_id: a,
+                global: true,
+                value: MemoryValue::Int(1)
+            },
+            &VmEvent::TurtleMove {
+                direct: Direction::Forward,
+                count: 1,
+                position: (0, 1)
+            },
+            &VmEvent::TurtleMove {
+                direct: Direction::Forward,
+                count: 1,
+                position: (0, 2)
+            },
+            &VmEvent::ProcEnter(myproc.id),
+            &VmEvent::VarWrite {
+                var_id: *b,
+                global: false,
+                value: MemoryValue::Bool(false)
+            },
+            &VmEvent::ProcExit(myproc.id),
+        ],
+        events
+    );
+}
+
+#[test]
+pub fn interpreter_watch_global() {
+    let code = r#"
+        MAKEGLOBAL A = 1
+        MAKEGLOBAL B = 2
+        MAKE A = A + B
+        PRINT A
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let var_id = intr.watch_global("A").unwrap();
+    let main_id = env.symbol_table.get_proc_by_name("__main__").id;
+
+    let hit = match intr.exec_code() {
+        Err(InterpreterException::Watchpoint(hit)) => hit,
+        res => panic!("expected a watchpoint, got `{:?}`", res),
+    };
+
+    assert_eq!(var_id, hit.var_id);
+    assert_eq!(MemoryValue::Int(0), hit.old_value);
+    assert_eq!(MemoryValue::Int(1), hit.new_value);
+    assert_eq!(vec![main_id], hit.call_stack);
+
+    let hit = match intr.exec_code() {
+        Err(InterpreterException::Watchpoint(hit)) => hit,
+        res => panic!("expected a watchpoint, got `{:?}`", res),
+    };
+
+    assert_eq!(MemoryValue::Int(1), hit.old_value);
+    assert_eq!(MemoryValue::Int(3), hit.new_value);
+
+    intr.unwatch(var_id);
+
+    assert_eq!(Ok(()), intr.exec_code());
+    assert_eq!(vec!["3"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_watch_local() {
+    let code = r#"
+        TO MYPROC(N: INT)
+            MAKELOCAL B = FALSE
+            MAKE B = N > 1
+        END
+
+        MYPROC(2)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    assert_eq!(None, intr.watch_local("MYPROC", "C"));
+    assert_eq!(None, intr.watch_local("NOPROC", "B"));
+    assert_eq!(None, intr.watch_global("B"));
+
+    intr.watch_local("MYPROC", "B").unwrap();
+
+    let main_id = env.symbol_table.get_proc_by_name("__main__").id;
+    let proc_id = env.symbol_table.get_proc_by_name("MYPROC").id;
+
+    let mut hits = Vec::new();
+
+    loop {
+        match intr.exec_code() {
+            Ok(()) => break,
+            Err(InterpreterException::Watchpoint(hit)) => hits.push(hit),
+            Err(e) => panic!("unexpected exception `{:?}`", e),
+        }
+    }
+
+    assert_eq!(2, hits.len());
+    assert_eq!(MemoryValue::Bool(false), hits[1].old_value);
+    assert_eq!(MemoryValue::Bool(true), hits[1].new_value);
+    assert_eq!(vec![main_id, proc_id], hits[1].call_stack);
+}
+
 #[test]
 #[ignore]
 pub fn interpreter_wait_const_expr() {}
@@ -547,3 +1082,1745 @@ pub fn interpreter_stop_within_main_proc() {}
 #[test]
 #[ignore]
 pub fn interpreter_stop_within_sub_proc() {}
+
+#[test]
+pub fn interpreter_eval_expr_globals() {
+    let code = r#"
+        MAKEGLOBAL A = 3
+        MAKEGLOBAL B = TRUE
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(10)), intr.eval_expr("A * 2 + 4"));
+    assert_eq!(Ok(MemoryValue::Bool(true)), intr.eval_expr("(A > 2) AND B"));
+    assert_eq!(Ok(MemoryValue::Bool(false)), intr.eval_expr("NOT B"));
+    assert_eq!(Ok(MemoryValue::Bool(true)), intr.eval_expr("A = 3"));
+    assert_eq!(Ok(MemoryValue::Bool(false)), intr.eval_expr("B <> TRUE"));
+    assert_eq!(Ok(MemoryValue::Bool(true)), intr.eval_expr("\"AB\" < \"B\""));
+}
+
+#[test]
+pub fn interpreter_eval_expr_paused_frame_locals() {
+    let code = r#"
+        MAKEGLOBAL G = 10
+
+        TO MYPROC(N: INT)
+            MAKELOCAL B = 0
+            MAKE B = N * 2
+        END
+
+        MYPROC(3)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    intr.watch_local("MYPROC", "B").unwrap();
+
+    // first hit: the `MAKELOCAL B = 0`
+    let res = intr.exec_code();
+    assert!(matches!(res, Err(InterpreterException::Watchpoint(..))));
+
+    // second hit: the `MAKE B = N * 2`
+    let res = intr.exec_code();
+    assert!(matches!(res, Err(InterpreterException::Watchpoint(..))));
+
+    assert_eq!(Ok(MemoryValue::Int(6)), intr.eval_expr("B"));
+    assert_eq!(Ok(MemoryValue::Int(19)), intr.eval_expr("G + N + B"));
+
+    assert_eq!(Ok(()), intr.exec_code());
+
+    // the procedure frame has been unwound
+    let err = AstWalkError::MissingVarDeclaration("N".to_string(), None);
+    assert_eq!(Err(EvalError::Semantic(err)), intr.eval_expr("N"));
+}
+
+#[test]
+pub fn interpreter_eval_expr_errors() {
+    let code = r#"
+        MAKEGLOBAL A = 3
+
+        TO MYPROC(): INT
+            RETURN 1
+        END
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let err = AstWalkError::MissingVarDeclaration("C".to_string(), None);
+    assert_eq!(Err(EvalError::Semantic(err)), intr.eval_expr("C + 1"));
+
+    let err =
+        AstWalkError::InvalidBinaryOp(BinaryOp::Add, ExpressionType::Int, ExpressionType::Bool);
+    assert_eq!(Err(EvalError::Semantic(err)), intr.eval_expr("A + TRUE"));
+
+    let err =
+        AstWalkError::InvalidBinaryOp(BinaryOp::Equal, ExpressionType::Int, ExpressionType::Bool);
+    assert_eq!(Err(EvalError::Semantic(err)), intr.eval_expr("A = TRUE"));
+
+    let err = AstWalkError::InvalidBinaryOp(
+        BinaryOp::LessThan,
+        ExpressionType::Bool,
+        ExpressionType::Bool,
+    );
+    assert_eq!(Err(EvalError::Semantic(err)), intr.eval_expr("TRUE < FALSE"));
+
+    let err = EvalError::ProcCallNotAllowed("MYPROC".to_string());
+    assert_eq!(Err(err), intr.eval_expr("A + MYPROC()"));
+
+    assert_eq!(
+        Err(EvalError::DivisionByZero),
+        intr.eval_expr("A / (A * 0)")
+    );
+
+    assert!(matches!(intr.eval_expr("A A"), Err(EvalError::Parse(..))));
+    assert!(matches!(intr.eval_expr(""), Err(EvalError::Parse(..))));
+}
+
+#[test]
+pub fn interpreter_profiling_disabled_by_default() {
+    setup_interpreter!("FORWARD 10", env, cfg, host, intr);
+
+    assert_eq!(Ok(()), intr.exec_code());
+    assert_eq!(None, intr.profile_report());
+}
+
+#[test]
+pub fn interpreter_profile_report() {
+    let code = r#"
+        TO COUNTDOWN(N: INT)
+            IF N > 0 [
+                FORWARD 1
+                COUNTDOWN(N / 2)
+            ]
+        END
+
+        REPEAT 3 [RIGHT 5]
+        COUNTDOWN(4)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.enable_profiling();
+
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let main_id = env.symbol_table.get_proc_by_name("__main__").id;
+    let proc_id = env.symbol_table.get_proc_by_name("COUNTDOWN").id;
+
+    let report = intr.profile_report().unwrap();
+
+    // `main` -> COUNTDOWN(4) -> COUNTDOWN(2) -> COUNTDOWN(1) -> COUNTDOWN(0)
+    assert_eq!(5, report.peak_stack_depth);
+
+    // 3 x `RIGHT` and 3 x `FORWARD`
+    assert_eq!(6, report.host_calls);
+
+    let main_insts = report.proc_insts[&main_id];
+    let proc_insts = report.proc_insts[&proc_id];
+    assert_eq!(report.total_insts, main_insts + proc_insts);
+
+    let nodes_insts: usize = report.node_insts.values().sum();
+    assert_eq!(report.total_insts, nodes_insts);
+
+    let hottest = report.hottest_nodes();
+    assert_eq!(report.node_insts.len(), hottest.len());
+    assert!(hottest.windows(2).all(|w| w[0].1 >= w[1].1));
+}
+
+#[test]
+pub fn interpreter_const() {
+    let code = r#"
+        CONST SIDE = 10 * 2
+        CONST CLOSED = NOT (SIDE < 10)
+
+        TO SQUARE()
+            REPEAT 4 [
+                FORWARD SIDE
+                RIGHT 90
+            ]
+        END
+
+        IF CLOSED [PRINT 1]
+        SQUARE()
+        PRINT SIDE
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let _ = intr.exec_code();
+
+    assert_eq!(vec!["1", "20"], host.get_log());
+    assert_eq!((360, 80), host.xycors());
+}
+
+#[test]
+pub fn interpreter_equality_and_ordering() {
+    let code = r#"
+        TO SAME(A: INT, B: INT): BOOL
+            RETURN A = B
+        END
+
+        MAKEGLOBAL FLAG = TRUE
+
+        IF SAME(2, 1 + 1) [PRINT 1]
+        IF SAME(2, 3) [PRINT 2]
+        IF FLAG <> FALSE [PRINT 3]
+        IF FLAG = FALSE [PRINT 4]
+        IF 3 <> 3 [PRINT 5]
+        IF 2 < 3 [PRINT 6]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let _ = intr.exec_code();
+
+    assert_eq!(vec!["1", "3", "6"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_list_equality() {
+    let code = r#"
+        MAKEGLOBAL L = [1, 2]
+
+        IF [1, 2] = [1, 2] [PRINT 1]
+        IF L = [2, 1] [PRINT 2]
+        IF L <> [1, 2, 3] [PRINT 3]
+        IF [[1], L] = [[1], [1, 2]] [PRINT 4]
+        IF ["A", "B"] <> ["A", "B"] [PRINT 5]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(vec!["1", "3", "4"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_type_queries() {
+    let code = r#"
+        TO DESCRIBE(KIND: STR)
+            IF KIND = "INT" [PRINT 1]
+            IF KIND = "BOOL" [PRINT 2]
+            IF KIND <> "BOOL" [
+                IF KIND <> "INT" [PRINT 3]
+            ]
+        END
+
+        MAKEGLOBAL N = 10
+        MAKEGLOBAL T = TYPEOF N
+
+        DESCRIBE(T)
+        DESCRIBE(TYPEOF (N > 1))
+        DESCRIBE(TYPEOF "N")
+
+        IF NUMBERP N [
+            IF NOT WORDP N [PRINT 4]
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Str("INT".to_string())), intr.eval_expr("T"));
+    assert_eq!(Ok(MemoryValue::Bool(false)), intr.eval_expr("EMPTYP T"));
+
+    assert_eq!(vec!["1", "2", "3", "4"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_string_primitives() {
+    let code = r#"
+        TO SHOUT(X: STR): STR
+            RETURN UPPERCASE(WORD(X, "!"))
+        END
+
+        MAKEGLOBAL W = WORD("Hello", "World")
+        MAKEGLOBAL N = COUNT(W)
+        MAKEGLOBAL C = ITEM(6, W)
+        MAKEGLOBAL S = SHOUT("hey")
+        MAKEGLOBAL L = LOWERCASE(W)
+        MAKEGLOBAL P = PARSE("42") + PARSE("-2")
+
+        IF COUNT("") = 0 [PRINT N]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let str_value = |s: &str| Ok(MemoryValue::Str(s.to_string()));
+
+    assert_eq!(str_value("HelloWorld"), intr.eval_expr("W"));
+    assert_eq!(Ok(MemoryValue::Int(10)), intr.eval_expr("N"));
+    assert_eq!(str_value("W"), intr.eval_expr("C"));
+    assert_eq!(str_value("HEY!"), intr.eval_expr("S"));
+    assert_eq!(str_value("helloworld"), intr.eval_expr("L"));
+    assert_eq!(Ok(MemoryValue::Int(40)), intr.eval_expr("P"));
+
+    assert_eq!(vec!["10"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_item_out_of_range() {
+    let code = r#"
+        MAKEGLOBAL W = "abc"
+        MAKEGLOBAL C = ITEM(4, W)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::ItemOutOfRange { index: 4, count: 3 };
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_parse_invalid_number() {
+    let code = r#"MAKEGLOBAL N = PARSE("12a")"#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::InvalidNumber("12a".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_format_and_printf() {
+    let code = r#"
+        MAKEGLOBAL X = 10
+        MAKEGLOBAL S = FORMAT("x:~a;y:~a", X, X + 10)
+        MAKEGLOBAL Q = FORMAT("~s|~a|~~|~a|~s", "abc", "abc", X > 5, [1, 2])
+        MAKEGLOBAL N = FORMAT("none")
+
+        PRINT S
+        PRINTF("~a-cats", X)
+        PRINT TRUE
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let str_value = |s: &str| Ok(MemoryValue::Str(s.to_string()));
+
+    assert_eq!(str_value("x:10;y:20"), intr.eval_expr("S"));
+    assert_eq!(str_value("\"abc|abc|~|TRUE|[1 2]"), intr.eval_expr("Q"));
+    assert_eq!(str_value("none"), intr.eval_expr("N"));
+
+    assert_eq!(vec!["x:10;y:20", "10-cats", "TRUE"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_store_and_retrieve() {
+    let code = r#"
+        STORE "SCORE 40 + 2
+        STORE "LEVELS [1, 2]
+        APPLY(PROC("STORE"), "DONE, TRUE)
+
+        MAKEGLOBAL S = RETRIEVE "SCORE
+        MAKEGLOBAL N = PARSE(RETRIEVE("SCORE)) + 1
+        MAKEGLOBAL L = RETRIEVE "LEVELS
+        MAKEGLOBAL M = RETRIEVE "MISSING
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let str_value = |s: &str| Ok(MemoryValue::Str(s.to_string()));
+
+    assert_eq!(str_value("42"), intr.eval_expr("S"));
+    assert_eq!(Ok(MemoryValue::Int(43)), intr.eval_expr("N"));
+    assert_eq!(str_value("[1 2]"), intr.eval_expr("L"));
+    assert_eq!(str_value(""), intr.eval_expr("M"));
+
+    assert_eq!(Some("TRUE".to_string()), host.kv_get("DONE"));
+}
+
+#[test]
+pub fn interpreter_write_streams() {
+    let code = r#"
+        OPENWRITE "SQUARES
+        OPENWRITE "LOG
+
+        SETWRITE "SQUARES
+        FOREACH [1, 2, 3] [
+            PRINT ? * ?
+        ]
+
+        SETWRITE "LOG
+        PRINTF("~a-squares", 3)
+
+        SETWRITE "
+        PRINT 1
+
+        SETWRITE "SQUARES
+        CLOSE "SQUARES
+        PRINT 2
+        CLOSE "LOG
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let lines = |lines: &[&str]| lines.iter().map(|line| line.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(Some(&lines(&["1", "4", "9"])[..]), host.stream("SQUARES"));
+    assert_eq!(Some(&lines(&["3-squares"])[..]), host.stream("LOG"));
+    assert_eq!(None, host.stream("OTHER"));
+    assert_eq!(vec!["1", "CLOSE SQUARES", "2", "CLOSE LOG"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_write_stream_not_open() {
+    let code = r#"SETWRITE "LOG"#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::StreamNotOpen("LOG".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+
+    let code = r#"
+        OPENWRITE "LOG
+        CLOSE "LOG
+        CLOSE "LOG
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::StreamNotOpen("LOG".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_format_errors() {
+    let code = r#"MAKEGLOBAL S = FORMAT("~a~a", 1)"#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::FormatArgsCount {
+        placeholders: 2,
+        args: 1,
+    };
+    assert_eq!(Err(expected), intr.exec_code());
+
+    let code = r#"MAKEGLOBAL S = FORMAT("100~", 1)"#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::InvalidFormatDirective("~".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_foreach_list_lit() {
+    let code = r#"
+        MAKEGLOBAL SUM = 0
+
+        FOREACH [1, 2, 3] [
+            MAKE SUM = SUM + ?
+            PRINT ?
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(6)), intr.eval_expr("SUM"));
+    assert_eq!(vec!["1", "2", "3"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_nested_foreach() {
+    let code = r#"
+        MAKEGLOBAL L = [1, 2]
+        MAKEGLOBAL X = 0
+
+        FOREACH L [
+            MAKE X = ?
+
+            FOREACH [10, 20] [
+                PRINT X * ?
+            ]
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(vec!["10", "20", "20", "40"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_foreach_within_proc() {
+    let code = r#"
+        TO SUM(NUMS: [INT]): INT
+            MAKELOCAL S = 0
+            FOREACH NUMS [ MAKE S = S + ? ]
+            RETURN S
+        END
+
+        MAKEGLOBAL TOTAL = SUM([4, 5, 6]) + SUM([10])
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(25)), intr.eval_expr("TOTAL"));
+}
+
+#[test]
+pub fn interpreter_proc_rest_param() {
+    let code = r#"
+        TO SUMALL(FIRST: INT, NUMS...: INT): INT
+            MAKELOCAL S = FIRST
+            FOREACH NUMS [ MAKE S = S + ? ]
+            RETURN S
+        END
+
+        TO DOUBLE(X: INT): INT
+            RETURN X * 2
+        END
+
+        TO JOIN(WORDS...: STR): STR
+            MAKELOCAL S = ""
+            FOREACH WORDS [ MAKE S = WORD(S, ?) ]
+            RETURN S
+        END
+
+        TO DOUBLES(NUMS...: INT): [INT]
+            RETURN MAP("DOUBLE", NUMS)
+        END
+
+        MAKEGLOBAL ONE = SUMALL(1)
+        MAKEGLOBAL TOTAL = SUMALL(1, 2, 3, 4)
+        MAKEGLOBAL JOINED = JOIN("a", "b", "c")
+        MAKEGLOBAL NONE = DOUBLES()
+        MAKEGLOBAL SOME = DOUBLES(5, 6)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(1)), intr.eval_expr("ONE"));
+    assert_eq!(Ok(MemoryValue::Int(10)), intr.eval_expr("TOTAL"));
+    assert_eq!(Ok(MemoryValue::Str("abc".to_string())), intr.eval_expr("JOINED"));
+    assert_eq!(Ok(MemoryValue::List(vec![])), intr.eval_expr("NONE"));
+
+    let some = vec![MemoryValue::Int(10), MemoryValue::Int(12)];
+    assert_eq!(Ok(MemoryValue::List(some)), intr.eval_expr("SOME"));
+}
+
+#[test]
+pub fn interpreter_proc_overloads() {
+    let code = r#"
+        TO AREA(W: INT): INT
+            MAKELOCAL R = W * W
+            RETURN R
+        END
+
+        TO AREA(W: INT, H: INT): INT
+            MAKELOCAL R = W * H
+            RETURN R
+        END
+
+        TO AREA(NAME: STR, W: INT, H: INT): STR
+            MAKELOCAL R = NAME
+            RETURN R
+        END
+
+        MAKEGLOBAL SQ = AREA(5)
+        MAKEGLOBAL RECT = AREA(2, 3)
+        MAKEGLOBAL NAMED = AREA("room", 2, 3)
+        MAKEGLOBAL BY_NAME = AREA(H=4, W=2)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(25)), intr.eval_expr("SQ"));
+    assert_eq!(Ok(MemoryValue::Int(6)), intr.eval_expr("RECT"));
+    assert_eq!(Ok(MemoryValue::Str("room".to_string())), intr.eval_expr("NAMED"));
+    assert_eq!(Ok(MemoryValue::Int(8)), intr.eval_expr("BY_NAME"));
+}
+
+#[test]
+pub fn interpreter_proc_call_with_named_args() {
+    let code = r#"
+        TO SHOW(A: INT, B: INT, C: INT)
+            PRINT A
+            PRINT B
+            PRINT C
+        END
+
+        TO SUMALL(FIRST: INT, NUMS...: INT): INT
+            FOREACH NUMS [ MAKE FIRST = FIRST + ? ]
+            RETURN FIRST
+        END
+
+        SHOW(C=3, A=1, B=2)
+        SHOW(10, C=30, B=20)
+        MAKEGLOBAL TOTAL = SUMALL(FIRST=5)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(5)), intr.eval_expr("TOTAL"));
+
+    let log = host.get_log();
+    assert_eq!(vec!["1", "2", "3", "10", "20", "30"], log[..6].to_vec());
+}
+
+#[test]
+pub fn interpreter_map_user_proc_and_primitive() {
+    let code = r#"
+        TO DOUBLE(X: INT): INT
+            RETURN X * 2
+        END
+
+        MAKEGLOBAL DOUBLES = MAP("DOUBLE", [1, 2, 3])
+        MAKEGLOBAL QUADS = MAP("DOUBLE", DOUBLES)
+        MAKEGLOBAL SHOUTS = MAP("UPPERCASE", ["a", "bc"])
+
+        FOREACH QUADS [ PRINT ? ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let int_list = |v: &[isize]| {
+        let items = v.iter().map(|n| MemoryValue::Int(*n)).collect();
+        Ok(MemoryValue::List(items))
+    };
+
+    assert_eq!(int_list(&[2, 4, 6]), intr.eval_expr("DOUBLES"));
+    assert_eq!(int_list(&[4, 8, 12]), intr.eval_expr("QUADS"));
+
+    let shouts = vec![
+        MemoryValue::Str("A".to_string()),
+        MemoryValue::Str("BC".to_string()),
+    ];
+    assert_eq!(Ok(MemoryValue::List(shouts)), intr.eval_expr("SHOUTS"));
+
+    assert_eq!(vec!["4", "8", "12"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_apply_proc_ref() {
+    let code = r#"
+        TO SHOW(X: INT)
+            PRINT X
+        END
+
+        TO TWICE(CALLBACK: PROC, X: INT)
+            APPLY(CALLBACK, X)
+            APPLY(CALLBACK, X * 2)
+        END
+
+        MAKEGLOBAL HANDLER = PROC("SHOW")
+        TWICE(HANDLER, 10)
+        APPLY(HANDLER, 7)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(vec!["10", "20", "7"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_apply_discards_the_return_value() {
+    let code = r#"
+        MAKEGLOBAL N = 0
+
+        TO INCR(X: INT): INT
+            MAKE N = N + X
+            RETURN N
+        END
+
+        MAKEGLOBAL F = PROC("INCR")
+        APPLY(F, 2)
+        APPLY(F, 3)
+        APPLY(PROC("UPPERCASE"), "abc")
+
+        MAKEGLOBAL SAME = F = PROC("INCR")
+        MAKEGLOBAL OTHER = F = PROC("UPPERCASE")
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(5)), intr.eval_expr("N"));
+    assert_eq!(Ok(MemoryValue::Bool(true)), intr.eval_expr("SAME"));
+    assert_eq!(Ok(MemoryValue::Bool(false)), intr.eval_expr("OTHER"));
+}
+
+#[test]
+pub fn interpreter_apply_args_count_mismatch() {
+    let code = r#"
+        TO SHOW(X: INT)
+            PRINT X
+        END
+
+        APPLY(PROC("SHOW"), 1, 2)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::ApplyArgsCount {
+        proc_name: "SHOW".to_string(),
+        expected: 1,
+        actual: 2,
+    };
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_apply_proc_with_rest_param() {
+    let code = r#"
+        TO SHOWALL(FIRST: INT, NUMS...: INT)
+            PRINT FIRST
+            FOREACH NUMS [ PRINT ? ]
+        END
+
+        MAKEGLOBAL F = PROC("SHOWALL")
+        APPLY(F, 0)
+        APPLY(F, 1, 2, 3)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(vec!["0", "1", "2", "3"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_apply_proc_with_rest_param_errors() {
+    let code = r#"
+        TO SHOWALL(FIRST: INT, NUMS...: INT)
+            PRINT FIRST
+        END
+
+        APPLY(PROC("SHOWALL"))
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::ApplyTooFewArgs {
+        proc_name: "SHOWALL".to_string(),
+        at_least: 1,
+        actual: 0,
+    };
+    assert_eq!(Err(expected), intr.exec_code());
+
+    let code = r#"
+        TO SHOWALL(FIRST: INT, NUMS...: INT)
+            PRINT FIRST
+        END
+
+        APPLY(PROC("SHOWALL"), 0, 1, "2")
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::ApplyArgType {
+        proc_name: "SHOWALL".to_string(),
+        index: 3,
+        expected: ExpressionType::Int,
+        actual: ExpressionType::Str,
+    };
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_apply_arg_type_mismatch() {
+    let code = r#"
+        TO SHOW(X: INT, Y: INT)
+            PRINT X + Y
+        END
+
+        APPLY(PROC("SHOW"), 1, "2")
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::ApplyArgType {
+        proc_name: "SHOW".to_string(),
+        index: 2,
+        expected: ExpressionType::Int,
+        actual: ExpressionType::Str,
+    };
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_run_block() {
+    let code = r#"
+        MAKEGLOBAL N = 0
+        MAKEGLOBAL B = [
+            MAKE N = N + 1
+            FORWARD 10
+        ]
+
+        TO TWICE(ACTION: BLOCK)
+            RUN ACTION
+            RUN ACTION
+        END
+
+        TWICE(B)
+        REPEAT 3 [ RUN B ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(5)), intr.eval_expr("N"));
+}
+
+#[test]
+pub fn interpreter_run_block_uses_its_procedure_locals() {
+    let code = r#"
+        TO TWICE(ACTION: BLOCK)
+            RUN ACTION
+            RUN ACTION
+        END
+
+        TO COUNT()
+            MAKELOCAL I = 1
+            TWICE([
+                PRINT I
+                MAKE I = I * 10
+            ])
+            PRINT I
+        END
+
+        COUNT()
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(vec!["1", "10", "100"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_run_block_of_returned_procedure() {
+    let code = r#"
+        MAKEGLOBAL B = [ PRINT 1 ]
+
+        TO KEEP()
+            MAKELOCAL I = 1
+            MAKE B = [ PRINT I ]
+        END
+
+        KEEP()
+        RUN B
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::StaleBlockLocal("I".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_if_else_expr() {
+    let code = r#"
+        TO SIGN(N: INT): STR
+            RETURN IFELSE N > 0 ["POSITIVE] [IFELSE N = 0 ["ZERO] ["NEGATIVE]]
+        END
+
+        TO LOUD(N: INT): INT
+            PRINT N
+            RETURN N
+        END
+
+        MAKEGLOBAL A = SIGN(5)
+        MAKEGLOBAL B = SIGN(0)
+        MAKEGLOBAL C = 1 + IFELSE A = "POSITIVE" [LOUD(10)] [LOUD(20)] * 2
+        MAKEGLOBAL D = [IFELSE FALSE [1] [2], 3]
+
+        MAKEGLOBAL N = 0
+        REPEAT IFELSE C > 5 [3] [1] [ MAKE N = N + 1 ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Str("POSITIVE".to_string())), intr.eval_expr("A"));
+    assert_eq!(Ok(MemoryValue::Str("ZERO".to_string())), intr.eval_expr("B"));
+    assert_eq!(Ok(MemoryValue::Int(21)), intr.eval_expr("C"));
+    assert_eq!(Ok(MemoryValue::Int(3)), intr.eval_expr("N"));
+
+    let items = vec![MemoryValue::Int(2), MemoryValue::Int(3)];
+    assert_eq!(Ok(MemoryValue::List(items)), intr.eval_expr("D"));
+
+    assert_eq!(
+        Ok(MemoryValue::Int(7)),
+        intr.eval_expr("IFELSE N < 2 [0] [N + 4]")
+    );
+
+    // only the chosen branch is evaluated
+    assert_eq!(vec!["10"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_dispatch_key_and_tick() {
+    let code = r#"
+        MAKEGLOBAL X = 1
+        MAKEGLOBAL TICKS = 0
+
+        TO STEP(N: INT): INT
+            RETURN N * 10
+        END
+
+        ONKEY "LEFT [
+            MAKE X = X + STEP(1)
+        ]
+
+        ONKEY "RIGHT [
+            MAKELOCAL D = STEP(2)
+            MAKE X = X * D
+        ]
+
+        ONTICK 100 [
+            MAKE TICKS = TICKS + 1
+            FORWARD 5
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(true), intr.dispatch_key("RIGHT"));
+    assert_eq!(Ok(true), intr.dispatch_key("LEFT"));
+    assert_eq!(Ok(true), intr.dispatch_key("RIGHT"));
+    assert_eq!(Ok(false), intr.dispatch_key("UP"));
+    assert_eq!(Ok(MemoryValue::Int(600)), intr.eval_expr("X"));
+
+    assert_eq!(Some(100), intr.tick_interval());
+    assert_eq!(Ok(true), intr.dispatch_tick());
+    assert_eq!(Ok(true), intr.dispatch_tick());
+    assert_eq!(Ok(MemoryValue::Int(2)), intr.eval_expr("TICKS"));
+
+    assert_eq!((0, 10), host.xycors());
+}
+
+#[test]
+pub fn interpreter_dispatch_with_no_handlers() {
+    setup_interpreter!("FORWARD 10", env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(None, intr.tick_interval());
+    assert_eq!(Ok(false), intr.dispatch_tick());
+    assert_eq!(Ok(false), intr.dispatch_key("LEFT"));
+}
+
+#[test]
+pub fn interpreter_dispatch_failed_handler_is_unwound() {
+    let code = r#"
+        MAKEGLOBAL N = 0
+
+        ONKEY "A [
+            MAKE N = N + PARSE("x")
+        ]
+
+        ONKEY "B [
+            MAKE N = N + 1
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let expected = InterpreterException::InvalidNumber("x".to_string());
+    assert_eq!(Err(expected), intr.dispatch_key("A"));
+
+    assert_eq!(Ok(true), intr.dispatch_key("B"));
+    assert_eq!(Ok(MemoryValue::Int(1)), intr.eval_expr("N"));
+}
+
+#[test]
+pub fn interpreter_throw_unwinds_to_the_matching_catch() {
+    let code = r#"
+        MAKEGLOBAL N = 0
+
+        TO SEARCH(LIMIT: INT)
+            REPEAT LIMIT [
+                MAKE N = N + 1
+
+                IF N = 3 [
+                    THROW "FOUND
+                ]
+            ]
+        END
+
+        CATCH "FOUND [
+            CATCH "OTHER [
+                SEARCH(10)
+            ]
+
+            MAKE N = 100
+        ]
+
+        MAKEGLOBAL AFTER = N + 1
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(3)), intr.eval_expr("N"));
+    assert_eq!(Ok(MemoryValue::Int(4)), intr.eval_expr("AFTER"));
+}
+
+#[test]
+pub fn interpreter_throw_within_an_expr_discards_its_operands() {
+    let code = r#"
+        MAKEGLOBAL N = 0
+
+        TO FAIL(): INT
+            THROW "FAILED
+            RETURN 1
+        END
+
+        REPEAT 3 [
+            CATCH "FAILED [
+                MAKE N = N + 10 * FAIL()
+            ]
+
+            MAKE N = N + 1
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(3)), intr.eval_expr("N"));
+}
+
+#[test]
+pub fn interpreter_if_returning_within_a_single_branch() {
+    let code = r#"
+        TO CLASSIFY(N: INT): INT
+            IF N > 10 [
+                RETURN 2
+            ]
+
+            IF N > 5 [
+                RETURN 1
+            ] [
+                MAKE N = 0
+            ]
+
+            RETURN N
+        END
+
+        PRINT CLASSIFY(20)
+        PRINT CLASSIFY(7)
+        PRINT CLASSIFY(3)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(vec!["2", "1", "0"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_catch_error_catches_runtime_errors() {
+    let code = r#"
+        MAKEGLOBAL N = 1
+
+        CATCH "ERROR [
+            MAKE N = PARSE("x")
+        ]
+
+        CATCH "ERROR [
+            THROW "UNKNOWN
+        ]
+
+        CATCH "ERROR [
+            THROW "ERROR
+        ]
+
+        MAKE N = N + 1
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(2)), intr.eval_expr("N"));
+}
+
+#[test]
+pub fn interpreter_resource_limits_value_sizes() {
+    let code = r#"
+        MAKEGLOBAL S = "ab"
+        CATCH "ERROR [
+            REPEAT 10 [ MAKE S = WORD(S, S) ]
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_resource_limits(ResourceLimits {
+        max_str_len: Some(100),
+        ..ResourceLimits::default()
+    });
+
+    // a `CATCH "ERROR` doesn't get around the limits
+    let expected = InterpreterException::ResourceLimit {
+        resource: Resource::StringLength,
+        limit: 100,
+        actual: 128,
+    };
+    assert_eq!(Err(expected), intr.exec_code());
+
+    setup_interpreter!("MAKEGLOBAL L = [1, 2, 3, 4]", env, cfg, host, intr);
+    intr.set_resource_limits(ResourceLimits {
+        max_list_len: Some(3),
+        ..ResourceLimits::default()
+    });
+
+    let expected = InterpreterException::ResourceLimit {
+        resource: Resource::ListLength,
+        limit: 3,
+        actual: 4,
+    };
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_resource_limits_heap() {
+    let code = r#"
+        MAKEGLOBAL S = "ab"
+        REPEAT 5 [ MAKE S = WORD(S, S) ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_resource_limits(ResourceLimits {
+        max_heap: Some(10_000),
+        ..ResourceLimits::default()
+    });
+
+    assert_eq!(Ok(()), intr.exec_code());
+    assert!(intr.heap_size() > 64);
+
+    let code = r#"
+        MAKEGLOBAL S = "ab"
+        REPEAT 20 [ MAKE S = WORD(S, S) ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_resource_limits(ResourceLimits {
+        max_heap: Some(10_000),
+        ..ResourceLimits::default()
+    });
+
+    let res = intr.exec_code();
+    assert!(matches!(
+        res,
+        Err(InterpreterException::ResourceLimit {
+            resource: Resource::Heap,
+            limit: 10_000,
+            ..
+        })
+    ));
+}
+
+#[test]
+pub fn interpreter_capabilities() {
+    let code = r#"
+        CATCH "ERROR [ WAIT ]
+        PRINT MOUSEX()
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_capabilities(Capabilities::ALL.without(Capability::Clock));
+
+    assert_eq!(
+        Err(InterpreterException::CapabilityDisabled {
+            capability: Capability::Clock,
+            feature: "WAIT".to_string(),
+        }),
+        intr.exec_code()
+    );
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_capabilities(Capabilities::ALL.without(Capability::Input));
+
+    assert_eq!(
+        Err(InterpreterException::CapabilityDisabled {
+            capability: Capability::Input,
+            feature: "MOUSEX".to_string(),
+        }),
+        intr.exec_code()
+    );
+
+    setup_interpreter!(r#"ONKEY "UP [ FORWARD 10 ]"#, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    intr.set_capabilities(Capabilities::NONE);
+
+    assert_eq!(
+        Err(InterpreterException::CapabilityDisabled {
+            capability: Capability::Input,
+            feature: "ONKEY".to_string(),
+        }),
+        intr.dispatch_key("UP")
+    );
+}
+
+#[test]
+pub fn interpreter_uncaught_throw() {
+    setup_interpreter!(r#"THROW "OOPS"#, env, cfg, host, intr);
+
+    let expected = InterpreterException::UncaughtThrow("OOPS".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+
+    let code = r#"
+        CATCH "OOPS [
+            THROW "ERROR
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::UncaughtThrow("ERROR".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_assert_failure() {
+    let code = r#"
+        MAKEGLOBAL N = 2
+        MAKEGLOBAL NAME = "TURTLE"
+
+        TO CHECK(X: INT)
+            MAKELOCAL Y = X * 2
+            ASSERT Y < 5 "Y is too large"
+        END
+
+        ASSERT N = 2
+        CHECK(1)
+        CHECK(N + 1)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::AssertionFailed {
+        message: "Y is too large".to_string(),
+        location: Location(7, 13),
+        vars: vec![
+            ("N".to_string(), MemoryValue::Int(2)),
+            ("NAME".to_string(), MemoryValue::Str("TURTLE".to_string())),
+            ("X".to_string(), MemoryValue::Int(3)),
+            ("Y".to_string(), MemoryValue::Int(6)),
+        ],
+    };
+
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_assert_failure_without_message() {
+    let code = r#"
+        MAKEGLOBAL N = 2
+        ASSERT N > 2
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::AssertionFailed {
+        message: "N > 2".to_string(),
+        location: Location(3, 9),
+        vars: vec![("N".to_string(), MemoryValue::Int(2))],
+    };
+
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn interpreter_catch_error_catches_assertion_failures() {
+    let code = r#"
+        MAKEGLOBAL N = 1
+
+        CATCH "ERROR [
+            ASSERT N > 1
+            MAKE N = 10
+        ]
+
+        MAKE N = N + 1
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(2)), intr.eval_expr("N"));
+}
+
+#[test]
+pub fn interpreter_trace_procs() {
+    let code = r#"
+        TO FACTORIAL(I: INT, N: INT): INT
+            IF I > N [
+                RETURN 1
+            ][
+                RETURN I * FACTORIAL(I + 1, N)
+            ]
+        END
+
+        TO SHOW(X: INT)
+            PRINT X
+        END
+
+        TRACE "FACTORIAL
+        TRACE "SHOW
+        SHOW(FACTORIAL(1, 2))
+
+        UNTRACE "FACTORIAL
+        SHOW(FACTORIAL(1, 1))
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let expected = vec![
+        "FACTORIAL(1, 2)",
+        "  FACTORIAL(2, 2)",
+        "    FACTORIAL(3, 2)",
+        "    FACTORIAL outputs 1",
+        "  FACTORIAL outputs 2",
+        "FACTORIAL outputs 2",
+        "SHOW(2)",
+        "2",
+        "SHOW stops",
+        "SHOW(1)",
+        "1",
+        "SHOW stops",
+    ];
+
+    assert_eq!(expected, host.get_log());
+}
+
+#[test]
+pub fn interpreter_trace_applied_proc() {
+    let code = r#"
+        TO GREET(NAME: STR)
+        END
+
+        TRACE "GREET
+        APPLY(PROC("GREET), "TURTLE)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(vec!["GREET(\"TURTLE)", "GREET stops"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_exec_frame_throttled() {
+    let code = r#"
+        REPEAT 10 [
+            FORWARD 1
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let total = intr.exec_frame().unwrap();
+
+    assert_eq!(YieldPoint::Completed, total.yield_point);
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_speed(4);
+
+    let mut frames = Vec::new();
+
+    loop {
+        let frame = intr.exec_frame().unwrap();
+        frames.push(frame);
+
+        if frame.yield_point == YieldPoint::Completed {
+            break;
+        }
+    }
+
+    let (last, rest) = frames.split_last().unwrap();
+
+    assert!(rest
+        .iter()
+        .all(|frame| *frame == FrameReport { steps: 4, yield_point: YieldPoint::Budget }));
+    assert!(last.steps <= 4);
+    assert_eq!(total.steps, frames.iter().map(|frame| frame.steps).sum::<usize>());
+    assert_eq!((0, 10), host.xycors());
+}
+
+#[test]
+pub fn interpreter_exec_frame_yields_on_wait() {
+    let code = r#"
+        FORWARD 1
+        WAIT
+        FORWARD 2
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let frame = intr.exec_frame().unwrap();
+    assert_eq!(YieldPoint::Wait, frame.yield_point);
+
+    let frame = intr.exec_frame().unwrap();
+    assert_eq!(YieldPoint::Completed, frame.yield_point);
+
+    assert_eq!((0, 3), host.xycors());
+}
+
+#[test]
+pub fn interpreter_stamp_and_labels() {
+    let code = r#"
+        FORWARD 10
+        STAMP
+        LABEL "HELLO
+        SETLABELFONT "MONO
+        SETLABELSIZE 10 * 2
+        RIGHT 5
+        LABEL WORD("HI, "!)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let expected = vec![
+        "STAMP at (0, 10) heading 0",
+        "LABEL \"HELLO\" at (0, 10) heading 0 (sans-serif 12)",
+        "LABEL \"HI!\" at (5, 10) heading 0 (MONO 20)",
+    ];
+
+    assert_eq!(expected, host.get_log());
+}
+
+#[test]
+pub fn interpreter_set_shape() {
+    let code = r#"
+        SETSHAPE "triangle
+        FORWARD 5
+        SETSHAPE "CIRCLE
+        STAMP
+        SETSHAPE "image:ROCKET
+        SETSHAPE "arrow
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.register_shape("ROCKET");
+
+    assert_eq!(Ok(()), intr.exec_code());
+    assert_eq!(&TurtleShape::Arrow, intr.memory.turtle.shape());
+
+    let expected = vec![
+        "SHAPE triangle",
+        "SHAPE circle",
+        "STAMP at (0, 5) heading 0",
+        "SHAPE image:ROCKET",
+        "SHAPE arrow",
+    ];
+
+    assert_eq!(expected, host.get_log());
+}
+
+#[test]
+pub fn interpreter_set_shape_unknown() {
+    setup_interpreter!(r#"SETSHAPE "square"#, env, cfg, host, intr);
+
+    let expected = InterpreterException::UnknownShape("square".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+
+    // an image which hasn't been registered
+    setup_interpreter!(r#"SETSHAPE "image:ROCKET"#, env, cfg, host, intr);
+    intr.register_shape("ANIMAL");
+
+    let expected = InterpreterException::UnknownShape("image:ROCKET".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+    assert_eq!(&TurtleShape::Arrow, intr.memory.turtle.shape());
+}
+
+#[test]
+pub fn interpreter_screen_size() {
+    let code = r#"
+        FORWARD SCREENHEIGHT() / 2
+        RIGHT SCREENWIDTH() / 4
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(DEFAULT_SCREEN_SIZE, (800, 600));
+    assert_eq!((200, 300), host.xycors());
+}
+
+#[test]
+pub fn interpreter_notify_resize_mid_run() {
+    let code = r#"
+        PRINT SCREENWIDTH()
+        WAIT
+        PRINT SCREENWIDTH()
+        PRINT SCREENHEIGHT()
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let frame = intr.exec_frame().unwrap();
+    assert_eq!(YieldPoint::Wait, frame.yield_point);
+
+    intr.notify_resize(1024, 768);
+
+    let frame = intr.exec_frame().unwrap();
+    assert_eq!(YieldPoint::Completed, frame.yield_point);
+
+    assert_eq!(vec!["800", "1024", "768"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_notify_mouse() {
+    let code = r#"
+        SETX MOUSEX()
+        SETY MOUSEY()
+        WAIT
+        IF BUTTONP() [
+            SETX MOUSEX()
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.notify_mouse(30, 40, false);
+
+    let frame = intr.exec_frame().unwrap();
+    assert_eq!(YieldPoint::Wait, frame.yield_point);
+
+    let turtle = &intr.memory.turtle;
+    assert_eq!((30, 40), (turtle.xcor(), turtle.ycor()));
+
+    intr.notify_mouse(70, 10, true);
+
+    let frame = intr.exec_frame().unwrap();
+    assert_eq!(YieldPoint::Completed, frame.yield_point);
+
+    assert_eq!(
+        MouseState {
+            position: (70, 10),
+            pressed: true
+        },
+        intr.memory.mouse
+    );
+    assert_eq!((70, 40), host.xycors());
+}
+
+#[test]
+pub fn interpreter_tone_and_beep() {
+    let code = r#"
+        TO NOTE(FREQ: INT)
+            TONE FREQ 250
+        END
+
+        TONE 220 * 2 100 + 400
+        NOTE(330)
+        BEEP
+        TONE PARSE("-5) 10
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let expected = [(440, 500), (330, 250), (BEEP_FREQ, BEEP_DURATION), (0, 10)];
+    assert_eq!(&expected[..], host.tones());
+
+    assert_eq!("TONE 440 500", host.get_log()[0]);
+}
+
+#[test]
+pub fn interpreter_and_or_short_circuit() {
+    let code = r#"
+        TO CHECK(N: INT): BOOL
+            PRINT N
+            RETURN N > 3
+        END
+
+        IF FALSE AND CHECK(1) [
+            PRINT 100
+        ]
+        IF TRUE OR CHECK(2) [
+            PRINT 200
+        ]
+        MAKEGLOBAL A = CHECK(3) AND CHECK(4)
+        MAKEGLOBAL B = CHECK(5) OR CHECK(6)
+        MAKEGLOBAL C = CHECK(1) OR CHECK(7)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Bool(false)), intr.eval_expr("A"));
+    assert_eq!(Ok(MemoryValue::Bool(true)), intr.eval_expr("B"));
+    assert_eq!(Ok(MemoryValue::Bool(true)), intr.eval_expr("C"));
+
+    // `CHECK(1)`, `CHECK(2)`, `CHECK(4)` and `CHECK(6)` are skipped
+    assert_eq!(vec!["200", "3", "5", "1", "7"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_and_or_chains_short_circuit() {
+    let code = r#"
+        TO CHECK(N: INT): BOOL
+            PRINT N
+            RETURN N > 3
+        END
+
+        MAKEGLOBAL A = CHECK(4) AND CHECK(2) AND CHECK(5)
+        MAKEGLOBAL B = CHECK(1) OR CHECK(6) OR CHECK(7)
+        MAKEGLOBAL C = CHECK(8) AND CHECK(9) OR CHECK(10)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Bool(false)), intr.eval_expr("A"));
+    assert_eq!(Ok(MemoryValue::Bool(true)), intr.eval_expr("B"));
+    assert_eq!(Ok(MemoryValue::Bool(true)), intr.eval_expr("C"));
+
+    assert_eq!(vec!["4", "2", "1", "6", "8", "9"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_integer_overflow_checked_by_default() {
+    let code = r#"
+        MAKEGLOBAL A = 9223372036854775807
+        MAKEGLOBAL B = A + 1
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let expected = InterpreterException::IntegerOverflow {
+        op: "+".to_string(),
+        lhs: isize::MAX,
+        rhs: 1,
+    };
+    assert_eq!(Err(expected), intr.exec_code());
+
+    let expected = EvalError::IntegerOverflow("9223372036854775807 * 2".to_string());
+    assert_eq!(Err(expected), intr.eval_expr("A * 2"));
+}
+
+#[test]
+pub fn interpreter_division_by_zero() {
+    let code = r#"
+        MAKEGLOBAL A = 0
+        MAKEGLOBAL B = 1
+
+        CATCH "ERROR [
+            MAKE B = 10 / A
+        ]
+
+        MAKEGLOBAL C = B / A
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    assert_eq!(Err(InterpreterException::DivisionByZero), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(1)), intr.eval_expr("B"));
+}
+
+#[test]
+pub fn interpreter_integer_overflow_wrapping() {
+    let code = r#"
+        MAKEGLOBAL A = 9223372036854775807
+        MAKEGLOBAL B = A + 1
+        MAKEGLOBAL C = A * 2
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_overflow_policy(OverflowPolicy::Wrapping);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(isize::MIN)), intr.eval_expr("B"));
+    assert_eq!(Ok(MemoryValue::Int(-2)), intr.eval_expr("C"));
+    assert_eq!(Ok(MemoryValue::Int(isize::MIN + 1)), intr.eval_expr("A + 2"));
+}
+
+#[test]
+pub fn interpreter_integer_overflow_saturating() {
+    let code = r#"
+        MAKEGLOBAL A = 9223372036854775807
+        MAKEGLOBAL B = A + 1
+        MAKEGLOBAL C = (A + 1) / 2
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_overflow_policy(OverflowPolicy::Saturating);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert_eq!(Ok(MemoryValue::Int(isize::MAX)), intr.eval_expr("B"));
+    assert_eq!(Ok(MemoryValue::Int(isize::MAX / 2)), intr.eval_expr("C"));
+    assert_eq!(Ok(MemoryValue::Int(isize::MAX)), intr.eval_expr("A * A"));
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+pub fn interpreter_integer_overflow_promote() {
+    let code = r#"
+        TO FACTORIAL(N: INT): INT
+            IF N < 2 [
+                RETURN 1
+            ] [
+                RETURN N * FACTORIAL(N + PARSE("-1))
+            ]
+        END
+
+        MAKEGLOBAL A = FACTORIAL(25)
+        MAKEGLOBAL B = FACTORIAL(24)
+        MAKEGLOBAL C = A / B
+        PRINT A
+        PRINT C
+        IF A > B AND A = FACTORIAL(25) AND NOT A < 9223372036854775807 [
+            PRINT 1
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_overflow_policy(OverflowPolicy::Promote);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let a = BigInt::parse_bytes(b"15511210043330985984000000", 10).unwrap();
+    assert_eq!(Ok(MemoryValue::BigInt(a.clone())), intr.eval_expr("A"));
+    assert_eq!(Ok(MemoryValue::BigInt(&a * 2)), intr.eval_expr("A + A"));
+
+    // a result fitting a machine integer is an `Int` again
+    assert_eq!(Ok(MemoryValue::Int(25)), intr.eval_expr("C"));
+    assert_eq!(Ok(MemoryValue::Bool(true)), intr.eval_expr("B * 25 = A"));
+
+    assert_eq!(vec!["15511210043330985984000000", "25", "1"], host.get_log());
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+pub fn interpreter_big_int_values_are_ints() {
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            RETURN N * 2
+        END
+
+        MAKEGLOBAL A = 9223372036854775807 * 4
+        MAKEGLOBAL F = PROC("DOUBLE")
+        MAKEGLOBAL L = [1, A]
+        APPLY(F, A)
+        MAKEGLOBAL D = DOUBLE(A)
+        FORWARD A
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_overflow_policy(OverflowPolicy::Promote);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let a = BigInt::from(isize::MAX) * BigInt::from(4);
+    assert_eq!(Ok(MemoryValue::BigInt(&a * 2)), intr.eval_expr("D"));
+
+    let list = MemoryValue::List(vec![MemoryValue::Int(1), MemoryValue::BigInt(a)]);
+    assert_eq!(Ok(list), intr.eval_expr("L"));
+
+    // a direction saturates its (big) count
+    assert_eq!((0, isize::MAX), host.xycors());
+}
@@ -0,0 +1,449 @@
+extern crate tytle;
+
+use tytle::ast::expression::ExpressionType;
+use tytle::ast::semantic::*;
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::vm::*;
+
+fn compile(code: &str) -> (CfgObject, Environment) {
+    let mut ast = TytleParser.parse(code).unwrap();
+
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    assert!(AstTypeCheck::new(&mut env).check(&mut ast).is_ok());
+
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    (cfg, env)
+}
+
+fn run(cfg: &CfgObject, env: &Environment) -> (Vec<String>, (isize, isize)) {
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(cfg, env, &mut host);
+
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let log = host.get_log();
+    (log, host.xycors())
+}
+
+#[test]
+pub fn json_ir_deterministic() {
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            RETURN N * 2
+        END
+
+        TO SQUARE(N: INT): INT
+            RETURN N * N
+        END
+
+        MAKEGLOBAL L = MAP("DOUBLE", [1, 2, 3])
+        FOREACH L [
+            IF ? > 2 [PRINT SQUARE(?)]
+        ]
+        REPEAT 2 [FORWARD DOUBLE(5)]
+    "#;
+
+    let (expected_cfg, env) = compile(code);
+    let json = expected_cfg.to_json(&env).unwrap();
+
+    // the same source always yields the same CFG, byte by byte
+    for _ in 0..5 {
+        let (cfg, env) = compile(code);
+
+        assert_eq!(json, cfg.to_json(&env).unwrap());
+        assert_eq!(expected_cfg.graph, cfg.graph);
+    }
+}
+
+#[test]
+pub fn json_ir_golden() {
+    let (cfg, env) = compile("FORWARD 10");
+
+    let expected = r#"{
+  "version": 1,
+  "entry": 1,
+  "nodes": [
+    {
+      "id": 1,
+      "insts": [
+        {
+          "op": "int",
+          "arg": 10
+        },
+        {
+          "op": "direction",
+          "arg": "Forward"
+        },
+        {
+          "op": "eoc"
+        }
+      ],
+      "edges": []
+    }
+  ],
+  "procs": [
+    {
+      "id": 0,
+      "name": "__main__",
+      "node": 1,
+      "params": [],
+      "return_type": "UNIT",
+      "locals": []
+    }
+  ],
+  "vars": []
+}"#;
+
+    assert_eq!(expected, cfg.to_json(&env).unwrap());
+}
+
+#[test]
+pub fn json_ir_symbols_metadata() {
+    let code = r#"
+        MAKEGLOBAL A = 5
+
+        TO DOUBLE(N: INT): INT
+            RETURN N * 2
+        END
+
+        FORWARD DOUBLE(A)
+    "#;
+
+    let (cfg, env) = compile(code);
+    let program = IrProgram::new(&cfg, &env).unwrap();
+
+    let double = program.procs.iter().find(|p| p.name == "DOUBLE").unwrap();
+    assert_eq!(vec![ExpressionType::Int], double.params);
+    assert_eq!(ExpressionType::Int, double.return_type);
+    assert_eq!(1, double.locals.len());
+    assert!(double.node.is_some());
+
+    let a = program.vars.iter().find(|v| v.name == "A").unwrap();
+    assert!(a.global);
+    assert_eq!(Some(ExpressionType::Int), a.var_type);
+    assert_eq!(0, a.index);
+
+    let n = program.vars.iter().find(|v| v.name == "N").unwrap();
+    assert!(!n.global);
+    assert!(n.param);
+    assert_eq!(double.locals[0], n.id);
+}
+
+#[test]
+pub fn json_ir_roundtrip_executes_the_same() {
+    let code = r#"
+        MAKEGLOBAL STEP = 2
+
+        TO SPIRAL(N: INT, LEN: INT)
+            IF N > 0 [
+                FORWARD LEN
+                RIGHT 90
+                PRINT LEN
+                SPIRAL(N / 2, LEN * STEP)
+            ]
+        END
+
+        REPEAT 2 [SPIRAL(8, 3)]
+    "#;
+
+    let (cfg, env) = compile(code);
+    let json = cfg.to_json(&env).unwrap();
+    assert!(json.contains(r#""cond": "N > 0""#));
+
+    let (restored_cfg, restored_env) = CfgObject::from_json(&json).unwrap();
+
+    assert_eq!(cfg.graph, restored_cfg.graph);
+    assert_eq!(cfg.jmp_table, restored_cfg.jmp_table);
+    assert_eq!(json, restored_cfg.to_json(&restored_env).unwrap());
+
+    assert_eq!(run(&cfg, &env), run(&restored_cfg, &restored_env));
+}
+
+#[test]
+pub fn json_ir_errors() {
+    let res = CfgObject::from_json("{ \"version\": 1 }");
+    assert!(matches!(res, Err(IrError::InvalidJson(..))));
+
+    let (cfg, env) = compile("FORWARD 10");
+    let json = cfg.to_json(&env).unwrap().replace("\"version\": 1", "\"version\": 2");
+
+    let res = CfgObject::from_json(&json);
+    assert!(matches!(res, Err(IrError::UnsupportedVersion(2))));
+
+    let mut env = Environment::new();
+    env.register_native("ZERO", &[], ExpressionType::Int, |_args, _host| {
+        Some(MemoryValue::Int(0))
+    });
+
+    let mut ast = TytleParser.parse("FORWARD ZERO()").unwrap();
+    let mut env = SymbolTableGenerator::with_env(env).generate(&mut ast).unwrap();
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    let res = cfg.to_json(&env);
+    assert_eq!(Err(IrError::NativeProc("ZERO".to_string())), res);
+}
+
+#[test]
+pub fn json_ir_unassigned_local() {
+    let (cfg, env) = compile("TO P()\nMAKELOCAL A = 1\nPRINT A\nEND\nP()");
+
+    let mut program = IrProgram::new(&cfg, &env).unwrap();
+
+    // dropping the `MAKELOCAL A` store, so that `A` is loaded before being assigned
+    for node in program.nodes.iter_mut() {
+        node.insts
+            .retain(|inst| !matches!(inst, CfgInstruction::Store(..)));
+    }
+
+    let res = program.into_parts();
+    assert_eq!(
+        Err(IrError::UnassignedLocal {
+            proc: "P".to_string(),
+            var: "A".to_string(),
+        }),
+        res.map(|_| ())
+    );
+}
+
+fn assert_invalid_json(program: IrProgram) {
+    let res = program.into_parts();
+    assert!(matches!(res, Err(IrError::InvalidJson(..))));
+}
+
+#[test]
+pub fn json_ir_dangling_edge() {
+    let (cfg, env) = compile("REPEAT 2 [ FORWARD 10 ]");
+
+    let mut program = IrProgram::new(&cfg, &env).unwrap();
+    let edge = program.nodes.iter_mut().find_map(|node| node.edges.first_mut()).unwrap();
+    edge.dst = 1000;
+
+    assert_invalid_json(program);
+}
+
+#[test]
+pub fn json_ir_unknown_proc_local() {
+    let (cfg, env) = compile("TO P()\nMAKELOCAL A = 1\nEND\nP()");
+
+    let mut program = IrProgram::new(&cfg, &env).unwrap();
+    let proc = program.procs.iter_mut().find(|proc| proc.name == "P").unwrap();
+    proc.locals.push(SymbolId(1000));
+
+    assert_invalid_json(program);
+}
+
+#[test]
+pub fn json_ir_invalid_entry() {
+    let (cfg, env) = compile("TO P()\nFORWARD 10\nEND\nP()");
+
+    let mut program = IrProgram::new(&cfg, &env).unwrap();
+    let p_node = program.procs.iter().find(|proc| proc.name == "P").unwrap().node;
+
+    // the entry must be the `__main__` node, even when the other node exists
+    program.entry = p_node.unwrap();
+    assert_invalid_json(program.clone());
+
+    program.entry = 1000;
+    assert_invalid_json(program);
+}
+
+#[test]
+pub fn json_ir_unknown_load_store_var() {
+    let (cfg, env) = compile("MAKEGLOBAL X = 10\nFORWARD X");
+
+    let program = IrProgram::new(&cfg, &env).unwrap();
+
+    let mut bad_load = program.clone();
+    let mut bad_store = program;
+
+    for node in bad_load.nodes.iter_mut() {
+        for inst in node.insts.iter_mut() {
+            if let CfgInstruction::Load(var_id) = inst {
+                *var_id = SymbolId(1000);
+            }
+        }
+    }
+
+    for node in bad_store.nodes.iter_mut() {
+        for inst in node.insts.iter_mut() {
+            if let CfgInstruction::Store(var_id) = inst {
+                *var_id = SymbolId(1000);
+            }
+        }
+    }
+
+    assert_invalid_json(bad_load);
+    assert_invalid_json(bad_store);
+}
+
+#[test]
+pub fn json_ir_foreign_local() {
+    let code = r#"
+        TO P()
+            MAKELOCAL A = 1
+            PRINT A
+        END
+
+        TO Q()
+            PRINT 2
+        END
+
+        P()
+        Q()
+    "#;
+
+    let (cfg, env) = compile(code);
+
+    let mut program = IrProgram::new(&cfg, &env).unwrap();
+
+    // loading the `P` local `A` from within `Q` (whose frame has no locals)
+    let a_id = program.vars.iter().find(|var| var.name == "A").unwrap().id;
+    let q_node = program.procs.iter().find(|proc| proc.name == "Q").unwrap().node;
+
+    let node = program.nodes.iter_mut().find(|node| Some(node.id) == q_node).unwrap();
+    node.insts.insert(0, CfgInstruction::Load(a_id));
+    node.insts.insert(1, CfgInstruction::Pop);
+
+    assert_invalid_json(program);
+}
+
+#[test]
+pub fn json_ir_invalid_call_target() {
+    let (cfg, env) = compile("TO P()\nFORWARD 10\nEND\nP()");
+
+    let mut program = IrProgram::new(&cfg, &env).unwrap();
+
+    for node in program.nodes.iter_mut() {
+        for inst in node.insts.iter_mut() {
+            if let CfgInstruction::Call(entry_id) = inst {
+                *entry_id = 1000;
+            }
+        }
+    }
+
+    assert_invalid_json(program);
+}
+
+#[test]
+pub fn json_ir_roundtrip_keeps_procs_frames_layouts() {
+    let (cfg, env) = compile("TO P(A: INT)\nMAKELOCAL B = A > 1\nPRINT A\nEND\nP(2)");
+
+    let json = cfg.to_json(&env).unwrap();
+    let (restored_cfg, _) = CfgObject::from_json(&json).unwrap();
+
+    assert_eq!(cfg.procs, restored_cfg.procs);
+
+    // the frames layouts can't be computed without the locals types
+    let mut program = IrProgram::new(&cfg, &env).unwrap();
+
+    for var in program.vars.iter_mut() {
+        var.var_type = None;
+    }
+
+    let res = program.into_parts().map(|_| ());
+    let expected = IrError::InvalidJson("missing type for local variable `A`".to_string());
+    assert_eq!(Err(expected), res);
+}
+
+#[test]
+pub fn json_ir_incomparable_values() {
+    let (cfg, env) = compile("MAKEGLOBAL A = 1 = 2");
+
+    let mut program = IrProgram::new(&cfg, &env).unwrap();
+
+    // an imported program isn't type-checked, so `1 = TRUE` is only detected at runtime
+    for node in program.nodes.iter_mut() {
+        for inst in node.insts.iter_mut() {
+            if *inst == CfgInstruction::Int(2) {
+                *inst = CfgInstruction::Bool(true);
+            }
+        }
+    }
+
+    let (cfg, env) = program.into_parts().unwrap();
+
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+
+    let expected = IncomparableValues(ExpressionType::Int, ExpressionType::Bool);
+    assert_eq!(Err(InterpreterException::Incomparable(expected)), intr.exec_code());
+}
+
+#[test]
+pub fn json_ir_roundtrip_string_primitives() {
+    let code = r#"
+        MAKEGLOBAL W = UPPERCASE(WORD("a", "b"))
+        PRINT COUNT(W)
+    "#;
+
+    let (cfg, env) = compile(code);
+
+    let json = cfg.to_json(&env).unwrap();
+    assert!(json.contains(r#""op": "primitive""#));
+    assert!(json.contains(r#""arg": "uppercase""#));
+
+    let program = IrProgram::new(&cfg, &env).unwrap();
+    assert!(program.procs.iter().all(|proc| proc.name != "WORD"));
+
+    let (cfg2, env2) = CfgObject::from_json(&json).unwrap();
+    assert_eq!(run(&cfg, &env), run(&cfg2, &env2));
+}
+
+#[test]
+pub fn json_ir_roundtrip_lists() {
+    let code = r#"
+        TO DOUBLE(X: INT): INT
+            RETURN X * 2
+        END
+
+        MAKEGLOBAL L = MAP("DOUBLE", [1, 2, 3])
+
+        FOREACH L [
+            PRINT ?
+        ]
+    "#;
+
+    let (cfg, env) = compile(code);
+
+    let json = cfg.to_json(&env).unwrap();
+    assert!(json.contains(r#""op": "make_list""#));
+    assert!(json.contains(r#""LIST": "INT""#));
+
+    let program = IrProgram::new(&cfg, &env).unwrap();
+    assert!(program.procs.iter().any(|proc| proc.name == "$MAP.DOUBLE"));
+
+    let (cfg2, env2) = CfgObject::from_json(&json).unwrap();
+    assert_eq!(run(&cfg, &env), run(&cfg2, &env2));
+}
+
+#[test]
+pub fn json_ir_roundtrip_event_handlers() {
+    let code = r#"
+        ONKEY "UP [ FORWARD 10 ]
+        ONKEY "DOWN [ BACKWARD 10 ]
+        ONTICK 20 [ RIGHT 90 ]
+    "#;
+
+    let (cfg, env) = compile(code);
+
+    let json = cfg.to_json(&env).unwrap();
+    assert!(json.contains(r#""event": "tick""#));
+    assert!(json.contains(r#""interval": 20"#));
+
+    let (cfg2, env2) = CfgObject::from_json(&json).unwrap();
+    assert_eq!(env.key_handlers, env2.key_handlers);
+    assert_eq!(env.tick_handler, env2.tick_handler);
+
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg2, &env2, &mut host);
+
+    assert_eq!(Ok(()), intr.exec_code());
+    assert_eq!(Ok(true), intr.dispatch_key("UP"));
+    assert_eq!(Ok(true), intr.dispatch_key("UP"));
+    assert_eq!(Ok(true), intr.dispatch_key("DOWN"));
+    assert_eq!(Ok(true), intr.dispatch_tick());
+
+    assert_eq!((90, 10), host.xycors());
+}
@@ -0,0 +1,204 @@
+extern crate tytle;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tytle::ast::expression::ExpressionType;
+use tytle::ast::semantic::*;
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::vm::*;
+
+fn distance_env() -> Environment {
+    let mut env = Environment::new();
+
+    env.register_native(
+        "DISTANCE",
+        &[ExpressionType::Int, ExpressionType::Int],
+        ExpressionType::Int,
+        |args, _host| {
+            let (a, b) = match args {
+                [MemoryValue::Int(a), MemoryValue::Int(b)] => (*a, *b),
+                _ => panic!("invalid args"),
+            };
+
+            Some(MemoryValue::Int((a - b).abs()))
+        },
+    );
+
+    env
+}
+
+macro_rules! setup_native_interpreter {
+    ($code: expr, $env: expr, $cfg: ident, $host: ident, $intr: ident) => {
+        let mut ast = TytleParser.parse($code).unwrap();
+        let generator = SymbolTableGenerator::with_env($env);
+
+        let mut env = generator.generate(&mut ast).unwrap();
+        let mut checker = AstTypeCheck::new(&mut env);
+
+        let res = checker.check(&mut ast);
+        assert!(res.is_ok());
+
+        let builder = CfgBuilder::new(&mut env);
+        let $cfg = builder.build(&ast);
+
+        let mut $host = DummyHost::new();
+        let mut $intr = Interpreter::new(&$cfg, &env, &mut $host);
+    };
+}
+
+#[test]
+pub fn native_proc_registered_as_proc_symbol() {
+    let env = distance_env();
+    let proc = env.symbol_table.get_proc_by_name("DISTANCE");
+
+    assert!(proc.native);
+    assert!(!proc.builtin);
+    assert_eq!(vec![ExpressionType::Int, ExpressionType::Int], proc.params_types);
+    assert_eq!(ExpressionType::Int, proc.return_type);
+}
+
+#[test]
+pub fn native_proc_call_emits_native_call_inst() {
+    let mut ast = TytleParser.parse("FORWARD DISTANCE(3, 10)").unwrap();
+    let generator = SymbolTableGenerator::with_env(distance_env());
+
+    let mut env = generator.generate(&mut ast).unwrap();
+    AstTypeCheck::new(&mut env).check(&mut ast).unwrap();
+
+    let proc_id = env.symbol_table.get_proc_by_name("DISTANCE").id;
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    let node = cfg.graph.get_node(cfg.graph.get_entry_node_id());
+    assert!(node.insts.contains(&CfgInstruction::NativeCall(proc_id)));
+}
+
+#[test]
+pub fn native_proc_call_returns_value() {
+    setup_native_interpreter!("FORWARD DISTANCE(3, 10)", distance_env(), cfg, host, intr);
+
+    let res = intr.exec_code();
+    assert_eq!(Ok(()), res);
+
+    assert_eq!((0, 7), host.xycors());
+}
+
+#[test]
+pub fn native_proc_args_are_passed_in_order() {
+    let code = r#"
+        TO DIFF(A: INT, B: INT): INT
+            RETURN DISTANCE(A * 2, B)
+        END
+
+        FORWARD DIFF(1, 10)
+    "#;
+
+    setup_native_interpreter!(code, distance_env(), cfg, host, intr);
+
+    let res = intr.exec_code();
+    assert_eq!(Ok(()), res);
+
+    assert_eq!((0, 8), host.xycors());
+}
+
+#[test]
+pub fn native_proc_unit_return_type() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let calls_clone = Arc::clone(&calls);
+
+    let mut env = Environment::new();
+    env.register_native(
+        "BUZZ",
+        &[ExpressionType::Int],
+        ExpressionType::Unit,
+        move |args, host| {
+            calls_clone.fetch_add(1, Ordering::SeqCst);
+            if let MemoryValue::Int(v) = args[0] {
+                host.exec_print(v);
+            }
+
+            None
+        },
+    );
+
+    setup_native_interpreter!("REPEAT 3 [BUZZ(5)]", env, cfg, host, intr);
+
+    let res = intr.exec_code();
+    assert_eq!(Ok(()), res);
+
+    assert_eq!(3, calls.load(Ordering::SeqCst));
+    assert_eq!(vec!["5", "5", "5"], host.get_log());
+}
+
+#[test]
+pub fn native_proc_call_invalid_arg_type() {
+    let mut ast = TytleParser.parse("FORWARD DISTANCE(3, TRUE)").unwrap();
+    let generator = SymbolTableGenerator::with_env(distance_env());
+
+    let mut env = generator.generate(&mut ast).unwrap();
+    let res = AstTypeCheck::new(&mut env).check(&mut ast);
+
+    let err = AstWalkError::InvalidProcCallArgType(2, ExpressionType::Int, ExpressionType::Bool);
+    assert_eq!(Err(err), res);
+}
+
+#[test]
+pub fn native_proc_call_invalid_args_count() {
+    let mut ast = TytleParser.parse("FORWARD DISTANCE(3)").unwrap();
+    let generator = SymbolTableGenerator::with_env(distance_env());
+
+    let mut env = generator.generate(&mut ast).unwrap();
+    let res = AstTypeCheck::new(&mut env).check(&mut ast);
+
+    let err = AstWalkError::InvalidProcCallArgsCount("DISTANCE".to_string(), 2, 1);
+    assert_eq!(Err(err), res);
+}
+
+#[test]
+pub fn native_proc_cannot_be_redefined() {
+    let code = r#"
+        TO DISTANCE(A: INT, B: INT): INT
+            RETURN A
+        END
+    "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let generator = SymbolTableGenerator::with_env(distance_env());
+    let res = generator.generate(&mut ast);
+
+    let err = AstWalkError::BuiltinProcRedefinition("DISTANCE".to_string());
+    assert_eq!(Some(err), res.err());
+}
+
+#[test]
+pub fn native_proc_returning_wrong_type_is_an_error() {
+    let mut env = Environment::new();
+
+    env.register_native("ANSWER", &[], ExpressionType::Int, |_args, _host| {
+        Some(MemoryValue::Str("42".to_string()))
+    });
+
+    setup_native_interpreter!("FORWARD ANSWER()", env, cfg, host, intr);
+
+    let expected = InterpreterException::NativeReturnType {
+        proc_name: "ANSWER".to_string(),
+        expected: ExpressionType::Int,
+        actual: ExpressionType::Str,
+    };
+    assert_eq!(Err(expected), intr.exec_code());
+}
+
+#[test]
+pub fn native_proc_call_without_synchronous_host_is_an_error() {
+    let mut ast = TytleParser.parse("FORWARD DISTANCE(3, 10)").unwrap();
+    let generator = SymbolTableGenerator::with_env(distance_env());
+
+    let mut env = generator.generate(&mut ast).unwrap();
+    AstTypeCheck::new(&mut env).check(&mut ast).unwrap();
+
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+    let mut intr = Interpreter::new_async(&cfg, &env);
+
+    let expected = InterpreterException::NativeNoHost("DISTANCE".to_string());
+    assert_eq!(Err(expected), intr.exec_code());
+}
@@ -0,0 +1,193 @@
+extern crate tytle;
+
+use std::future::Future;
+use std::task::{Context, Poll, Waker};
+
+use tytle::compiler::{CompileOptions, CompiledProgram, Compiler};
+use tytle::vm::*;
+
+use WheelCommand::{Drive, PenDown, PenUp};
+
+fn compile(code: &str) -> CompiledProgram {
+    Compiler::compile(code, CompileOptions::default()).unwrap()
+}
+
+fn turning_config() -> TurtleConfig {
+    TurtleConfig {
+        steering: Steering::Turn,
+        ..TurtleConfig::default()
+    }
+}
+
+fn drain(queue: &MotionQueue) -> Vec<WheelCommand> {
+    std::iter::from_fn(|| queue.pop()).collect()
+}
+
+// a robot whose wheels travel 2 ticks per step, with its wheels 10 steps away from the pen
+fn calibration() -> Calibration {
+    Calibration::new(2.0, 10.0)
+}
+
+fn run(code: &str, config: TurtleConfig) -> (PhysicalHost<DummyHost>, Vec<WheelCommand>) {
+    let program = compile(code);
+    let queue = MotionQueue::new(16);
+    let mut host = PhysicalHost::with_config(DummyHost::new(), calibration(), queue, config);
+
+    let mut intr = program.interpreter(&mut host);
+    intr.set_turtle_config(config);
+    intr.exec_code().unwrap();
+
+    let commands = drain(host.queue());
+    (host, commands)
+}
+
+#[test]
+fn physical_host_calibration() {
+    let calibration = calibration();
+
+    assert_eq!(20, calibration.drive_ticks(10.0));
+    assert_eq!(-5, calibration.drive_ticks(-2.5));
+
+    // a quarter of the circle of radius `10` is `15.7` steps
+    assert_eq!(31, calibration.turn_ticks(90.0));
+    assert_eq!(-63, calibration.turn_ticks(-180.0));
+}
+
+#[test]
+fn physical_host_drives_and_turns() {
+    let code = r#"
+        FORWARD 10
+        RIGHT 90
+        PENUP
+        FORWARD 5
+        PENDOWN
+        BACKWARD 5
+        LEFT 45
+    "#;
+
+    let (host, commands) = run(code, turning_config());
+
+    assert_eq!(
+        vec![
+            Drive { left: 20, right: 20 },
+            Drive { left: 31, right: -31 },
+            PenUp,
+            Drive { left: 10, right: 10 },
+            PenDown,
+            Drive { left: -10, right: -10 },
+            Drive { left: -16, right: 16 },
+        ],
+        commands
+    );
+
+    // the inner host gets the host-calls as usual
+    assert_eq!(vec!["PENUP", "PENDOWN"], host.inner().get_log());
+}
+
+#[test]
+fn physical_host_strafes() {
+    // the default steering moves sideways, so the robot turns, drives and turns back
+    let (_, commands) = run("RIGHT 10", TurtleConfig::default());
+
+    assert_eq!(
+        vec![
+            Drive { left: 31, right: -31 },
+            Drive { left: 20, right: 20 },
+            Drive { left: -31, right: 31 },
+        ],
+        commands
+    );
+}
+
+#[test]
+fn physical_host_backs_up_to_absolute_positions() {
+    let code = r#"
+        FORWARD 10
+        SETY 4
+        SETX 3
+    "#;
+
+    let (_, commands) = run(code, TurtleConfig::default());
+
+    assert_eq!(
+        vec![
+            Drive { left: 20, right: 20 },
+            Drive { left: -12, right: -12 },
+            Drive { left: 31, right: -31 },
+            Drive { left: 6, right: 6 },
+            Drive { left: -31, right: 31 },
+        ],
+        commands
+    );
+}
+
+#[test]
+fn physical_host_returns_to_restored_state_with_pen_up() {
+    let code = r#"
+        PUSHSTATE
+        FORWARD 10
+        PENUP
+        POPSTATE
+        FORWARD 1
+    "#;
+
+    let (_, commands) = run(code, turning_config());
+
+    assert_eq!(
+        vec![
+            Drive { left: 20, right: 20 },
+            PenUp,
+            Drive { left: -20, right: -20 },
+            PenDown,
+            Drive { left: 2, right: 2 },
+        ],
+        commands
+    );
+}
+
+#[test]
+fn physical_host_async_waits_for_the_hardware() {
+    let code = r#"
+        REPEAT 4 [
+            FORWARD 10
+            RIGHT 90
+        ]
+        PRINT 1
+    "#;
+
+    let program = compile(code);
+    let queue = MotionQueue::new(2);
+    let calibration = calibration();
+    let mut host =
+        PhysicalHost::with_config(DummyHost::new(), calibration, queue.clone(), turning_config());
+
+    let mut intr = Interpreter::new_async(&program.cfg, &program.env);
+    intr.set_turtle_config(turning_config());
+
+    let mut fut = Box::pin(intr.exec_async(&mut host));
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut commands = Vec::new();
+    let mut waits = 0;
+
+    // the motors driver: a command is done whenever the VM waits for room in the queue
+    let res = loop {
+        if let Poll::Ready(res) = fut.as_mut().poll(&mut cx) {
+            break res;
+        }
+
+        assert!(queue.is_full());
+        commands.push(queue.pop().unwrap());
+        waits += 1;
+    };
+
+    drop(fut);
+    commands.extend(drain(&queue));
+
+    assert_eq!(Ok(()), res);
+    assert!(waits > 0);
+    assert_eq!(8, commands.len());
+    assert!(commands
+        .chunks(2)
+        .all(|pair| pair == [Drive { left: 20, right: 20 }, Drive { left: 31, right: -31 }]));
+    assert_eq!(vec!["1"], host.inner().get_log());
+}
@@ -1,6 +1,7 @@
 extern crate tytle;
 
 use tytle::ast::expression::*;
+use tytle::ast::statement::*;
 
 #[test]
 fn pp_lit_true() {
@@ -40,7 +41,7 @@ fn pp_lit_str() {
 
 #[test]
 fn pp_lit_var() {
-    let ast = ExpressionAst::Literal(LiteralExpr::Var("MYVAR".to_string(), None));
+    let ast = ExpressionAst::Literal(LiteralExpr::Var("MYVAR".to_string()));
     let expr = Expression::new(ast);
     let buffer = PrettyPrintAst::pprint_expr(&expr);
 
@@ -62,7 +63,7 @@ fn pp_binary_expr_not() {
 #[test]
 fn pp_binary_expr_add() {
     let ast1 = ExpressionAst::Literal(LiteralExpr::Int(1));
-    let ast2 = ExpressionAst::Literal(LiteralExpr::Var("X".to_string(), None));
+    let ast2 = ExpressionAst::Literal(LiteralExpr::Var("X".to_string()));
 
     let ast = ExpressionAst::Binary(
         BinaryOp::Add,
@@ -99,10 +100,126 @@ fn pp_proc_call_expr() {
     let ast2 = ExpressionAst::Literal(LiteralExpr::Int(2));
 
     let proc_args = vec![Expression::new(ast1), Expression::new(ast2)];
-    let ast = ExpressionAst::ProcCall("MYPROC".to_string(), proc_args, None);
+    let ast = ExpressionAst::ProcCall("MYPROC".to_string(), proc_args);
 
     let expr = Expression::new(ast);
     let buffer = PrettyPrintAst::pprint_expr(&expr);
 
     assert_eq!(buffer, "MYPROC(1, 2)".to_string());
 }
+
+#[test]
+fn pp_proc_call_expr_without_args() {
+    let ast = ExpressionAst::ProcCall("MYPROC".to_string(), Vec::new());
+
+    let expr = Expression::new(ast);
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "MYPROC()".to_string());
+}
+
+#[test]
+fn pp_proc_call_expr_with_named_args() {
+    let size = Expression::new(ExpressionAst::Literal(LiteralExpr::Int(100)));
+    let n = Expression::new(ExpressionAst::Literal(LiteralExpr::Int(6)));
+
+    let proc_args = vec![
+        Expression::new(ExpressionAst::NamedArg("SIZE".to_string(), Box::new(size))),
+        Expression::new(ExpressionAst::NamedArg("N".to_string(), Box::new(n))),
+    ];
+    let ast = ExpressionAst::ProcCall("POLYGON".to_string(), proc_args);
+
+    let expr = Expression::new(ast);
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "POLYGON(SIZE=100, N=6)".to_string());
+}
+
+#[test]
+fn pp_type_query_expr() {
+    let ast1 = ExpressionAst::Literal(LiteralExpr::Var("X".to_string()));
+
+    let ast = ExpressionAst::TypeQuery(TypeQuery::TypeOf, Box::new(Expression::new(ast1)));
+
+    let expr = Expression::new(ast);
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "TYPEOF X".to_string());
+}
+
+#[test]
+fn pp_map_expr_over_list_expr() {
+    let ast1 = ExpressionAst::Literal(LiteralExpr::Int(1));
+    let ast2 = ExpressionAst::Literal(LiteralExpr::Var("X".to_string()));
+
+    let items = vec![Expression::new(ast1), Expression::new(ast2)];
+    let list_expr = Expression::new(ExpressionAst::List(items));
+
+    let ast = ExpressionAst::Map("DOUBLE".to_string(), Box::new(list_expr));
+
+    let expr = Expression::new(ast);
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "MAP(\"DOUBLE\", [1, X])".to_string());
+}
+
+#[test]
+fn pp_rgb_expr() {
+    let channels = vec![
+        Expression::new(ExpressionAst::Literal(LiteralExpr::Int(255))),
+        Expression::new(ExpressionAst::Literal(LiteralExpr::Int(0))),
+        Expression::new(ExpressionAst::Literal(LiteralExpr::Var("B".to_string()))),
+    ];
+
+    let ast = ExpressionAst::ProcCall("RGB".to_string(), channels);
+
+    let expr = Expression::new(ast);
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "RGB 255 0 B".to_string());
+}
+
+#[test]
+fn pp_block_expr() {
+    let forward = Statement::Direction(DirectionStmt {
+        direction: Direction::Forward,
+        expr: Expression::new(ExpressionAst::Literal(LiteralExpr::Int(10))),
+    });
+
+    let mut block = BlockStatement::new();
+    block.add_statement(forward);
+    block.add_statement(Statement::Command(Command::PenUp));
+
+    let expr = Expression::new(ExpressionAst::Block(block));
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "[FORWARD 10 PENUP]".to_string());
+}
+
+#[test]
+fn pp_if_else_expr() {
+    let cond = Expression::new(ExpressionAst::Literal(LiteralExpr::Var("C".to_string())));
+    let one = Expression::new(ExpressionAst::Literal(LiteralExpr::Int(1)));
+    let two = Expression::new(ExpressionAst::Literal(LiteralExpr::Int(2)));
+
+    let ast = ExpressionAst::IfElse(Box::new(cond), Box::new(one), Box::new(two));
+
+    let expr = Expression::new(ast);
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "IFELSE C [1] [2]".to_string());
+}
+
+#[test]
+fn pp_block_expr_with_print_and_return() {
+    let var = Expression::new(ExpressionAst::Literal(LiteralExpr::Var("A".to_string())));
+
+    let mut block = BlockStatement::new();
+    block.add_statement(Statement::Print(var));
+    block.add_statement(Statement::Return(ReturnStmt::new(None)));
+
+    let expr = Expression::new(ExpressionAst::Block(block));
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "[PRINT A RETURN]".to_string());
+}
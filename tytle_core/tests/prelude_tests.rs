@@ -0,0 +1,42 @@
+extern crate tytle;
+
+use tytle::prelude::*;
+
+#[test]
+fn prelude_compiles_and_runs_a_program() {
+    let code = r#"
+        TO SQUARE(N: INT): INT
+            RETURN N * N
+        END
+
+        PRINT SQUARE(4)
+    "#;
+
+    let program = match Compiler::compile(code, CompileOptions::default()) {
+        Ok(program) => program,
+        Err(err) => panic!("{}", err),
+    };
+
+    let mut host = DummyHost::new();
+    let mut intr: Interpreter = program.interpreter(&mut host);
+    intr.exec_code().unwrap();
+
+    assert_eq!(vec!["16"], host.get_log());
+}
+
+#[test]
+fn prelude_runs_a_program_with_options() {
+    let mut host = DummyHost::new();
+    let summary: RunSummary = run("FORWARD 10", &mut host, RunOptions::default()).unwrap();
+
+    assert!(summary.diagnostics.is_empty());
+}
+
+#[test]
+fn prelude_reports_errors_as_diagnostics() {
+    let mut host = DummyHost::new();
+    let err: TytleError = run("FORWARD [", &mut host, RunOptions::default()).unwrap_err();
+    let diagnostic = Diagnostic::from(&err);
+
+    assert_eq!(Severity::Error, diagnostic.severity);
+}
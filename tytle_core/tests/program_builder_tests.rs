@@ -0,0 +1,144 @@
+extern crate tytle;
+
+use tytle::ast::expression::BinaryOp;
+use tytle::ast::program_builder::{binary, call, not, parens, var};
+use tytle::ast::semantic::*;
+use tytle::ast::statement::Command;
+use tytle::ast::ProgramBuilder;
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::vm::*;
+
+macro_rules! assert_builds_as {
+    ($builder: expr, $code: expr) => {{
+        let expected = TytleParser.parse($code).unwrap();
+        let actual = $builder.build();
+
+        assert_eq!(expected, actual);
+    }};
+}
+
+#[test]
+pub fn program_builder_empty() {
+    assert_builds_as!(ProgramBuilder::new(), "");
+}
+
+#[test]
+pub fn program_builder_repeat() {
+    let builder = ProgramBuilder::new().repeat(4, |b| b.forward(100).right(90));
+
+    let code = r#"
+        REPEAT 4 [
+            FORWARD 100
+            RIGHT 90
+        ]
+    "#;
+
+    assert_builds_as!(builder, code);
+}
+
+#[test]
+pub fn program_builder_commands_and_variables() {
+    let builder = ProgramBuilder::new()
+        .make_global("A", 10)
+        .make_global("B", binary(BinaryOp::GreaterThan, var("A"), 5))
+        .pen_up()
+        .command(Command::ShowTurtle)
+        .make(
+            "A",
+            binary(BinaryOp::Mul, parens(binary(BinaryOp::Add, var("A"), 1)), 2),
+        )
+        .print(var("A"));
+
+    let code = r#"
+        MAKEGLOBAL A = 10
+        MAKEGLOBAL B = A > 5
+        PENUP
+        SHOWTURTLE
+        MAKE A = (A + 1) * 2
+        PRINT A
+    "#;
+
+    assert_builds_as!(builder, code);
+}
+
+#[test]
+pub fn program_builder_const() {
+    let builder = ProgramBuilder::new()
+        .make_const("SIZE", binary(BinaryOp::Mul, 10, 2))
+        .forward(var("SIZE"));
+
+    let code = r#"
+        CONST SIZE = 10 * 2
+        FORWARD SIZE
+    "#;
+
+    assert_builds_as!(builder, code);
+}
+
+#[test]
+pub fn program_builder_if_else() {
+    let builder = ProgramBuilder::new()
+        .if_then(not(false), |b| b.backward(10))
+        .if_else(true, |b| b.setx(1), |b| b.sety(2));
+
+    let code = r#"
+        IF NOT FALSE [BACKWARD 10]
+        IF TRUE [SETX 1] [SETY 2]
+    "#;
+
+    assert_builds_as!(builder, code);
+}
+
+#[test]
+pub fn program_builder_procedure() {
+    let builder = ProgramBuilder::new()
+        .procedure("DOUBLE", &[("N", "INT")], "INT", |b| {
+            b.make_local("R", binary(BinaryOp::Mul, var("N"), 2))
+                .ret(var("R"))
+        })
+        .procedure("SQUARE", &[("SIZE", "INT")], "UNIT", |b| {
+            b.repeat(4, |b| b.forward(var("SIZE")).left(90))
+        })
+        .call_proc("SQUARE", vec![call("DOUBLE", vec![5.into()])]);
+
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            MAKELOCAL R = N * 2
+            RETURN R
+        END
+
+        TO SQUARE(SIZE: INT)
+            REPEAT 4 [
+                FORWARD SIZE
+                LEFT 90
+            ]
+        END
+
+        SQUARE(DOUBLE(5))
+    "#;
+
+    assert_builds_as!(builder, code);
+}
+
+#[test]
+pub fn program_builder_build_and_run() {
+    let mut ast = ProgramBuilder::new()
+        .make_global("STEP", 10)
+        .repeat(3, |b| b.forward(var("STEP")).right(var("STEP")))
+        .build();
+
+    let generator = SymbolTableGenerator::new();
+    let mut env = generator.generate(&mut ast).unwrap();
+
+    let mut checker = AstTypeCheck::new(&mut env);
+    assert!(checker.check(&mut ast).is_ok());
+
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+
+    assert_eq!(Ok(()), intr.exec_code());
+    assert_eq!((30, 30), host.xycors());
+}
@@ -0,0 +1,124 @@
+extern crate tytle;
+
+use tytle::compiler::CompileOptions;
+use tytle::ir::OptLevel;
+use tytle::parser::{SourceLoader, MAIN_FILE};
+use tytle::project::{Project, ProjectError};
+use tytle::vm::{MemoryValue, OverflowPolicy, ResourceLimits};
+use tytle::RunOptions;
+
+#[test]
+fn project_save_and_load() {
+    let mut project = Project::new("LOAD \"SHAPES\nSQUARE(50)");
+    project.add_file("SHAPES", "TO SQUARE(SIZE: INT)\n    FORWARD SIZE\nEND");
+
+    project.options = RunOptions {
+        compile: CompileOptions {
+            opt_level: OptLevel::Basic,
+            stdlib: true,
+            ..CompileOptions::default()
+        },
+        max_steps: Some(1000),
+        timeout_ms: Some(500),
+        profile: false,
+        overflow: OverflowPolicy::Saturating,
+        globals: vec![("LEVEL".to_string(), MemoryValue::Int(3))],
+        limits: ResourceLimits {
+            max_list_len: Some(100),
+            ..ResourceLimits::default()
+        },
+    };
+
+    project.seed = Some(7);
+    project.preview = Some("<svg></svg>".to_string());
+
+    let json = project.save().unwrap();
+    let loaded = Project::load(&json).unwrap();
+
+    assert_eq!(project, loaded);
+    assert_eq!("LOAD \"SHAPES\nSQUARE(50)", loaded.entry_source());
+}
+
+#[test]
+fn project_load_with_defaults() {
+    let json = r#"
+    {
+        "version": 1,
+        "entry": "MAIN",
+        "files": [{ "path": "MAIN", "source": "FORWARD 10" }],
+        "options": { "compile": { "opt_level": "full" } }
+    }
+    "#;
+
+    let project = Project::load(json).unwrap();
+
+    assert_eq!(OptLevel::Full, project.options.compile.opt_level);
+    assert!(!project.options.compile.stdlib);
+    assert_eq!(None, project.options.max_steps);
+    assert_eq!(OverflowPolicy::Checked, project.options.overflow);
+    assert!(project.options.globals.is_empty());
+    assert_eq!(None, project.seed);
+    assert_eq!(None, project.preview);
+    assert_eq!("FORWARD 10", project.entry_source());
+}
+
+#[test]
+fn project_save_skips_missing_settings() {
+    let json = Project::new("FORWARD 10").save().unwrap();
+
+    assert!(!json.contains("seed"));
+    assert!(!json.contains("preview"));
+    assert_eq!(MAIN_FILE, Project::load(&json).unwrap().entry);
+}
+
+#[test]
+fn project_add_file_replaces_source() {
+    let mut project = Project::new("FORWARD 10");
+    project.add_file(MAIN_FILE, "FORWARD 20");
+
+    assert_eq!(1, project.files.len());
+    assert_eq!(Some("FORWARD 20"), project.file(MAIN_FILE));
+}
+
+#[test]
+fn project_resolves_loaded_files() {
+    let mut project = Project::new("LOAD \"SHAPES\nSQUARE(50)");
+    project.add_file("SHAPES", "TO SQUARE(SIZE: INT)\n    FORWARD SIZE\nEND");
+
+    let ast = SourceLoader::new(&project).load(&project.entry).unwrap();
+
+    assert_eq!(2, ast.statements.len());
+}
+
+#[test]
+fn project_load_errors() {
+    let unsupported = r#"{ "version": 2, "entry": "MAIN", "files": [] }"#;
+    assert_eq!(
+        Err(ProjectError::UnsupportedVersion(2)),
+        Project::load(unsupported)
+    );
+
+    let missing_entry = r#"{ "version": 1, "entry": "MAIN", "files": [] }"#;
+    assert_eq!(
+        Err(ProjectError::MissingEntry("MAIN".to_string())),
+        Project::load(missing_entry)
+    );
+
+    let duplicate = r#"
+    {
+        "version": 1,
+        "entry": "MAIN",
+        "files": [{ "path": "MAIN", "source": "" }, { "path": "MAIN", "source": "" }]
+    }
+    "#;
+    assert_eq!(
+        Err(ProjectError::DuplicateFile("MAIN".to_string())),
+        Project::load(duplicate)
+    );
+
+    match Project::load("{") {
+        Err(ProjectError::InvalidJson(_)) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
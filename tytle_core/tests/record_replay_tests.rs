@@ -0,0 +1,133 @@
+extern crate tytle;
+
+use tytle::ast::semantic::*;
+use tytle::ast::statement::{Command, Direction};
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::vm::*;
+
+macro_rules! compile {
+    ($code: expr, $env: ident, $cfg: ident) => {
+        let mut ast = TytleParser.parse($code).unwrap();
+        let generator = SymbolTableGenerator::new();
+
+        let mut $env = generator.generate(&mut ast).unwrap();
+        let mut checker = AstTypeCheck::new(&mut $env);
+
+        let res = checker.check(&mut ast);
+        assert!(res.is_ok());
+
+        let builder = CfgBuilder::new(&mut $env);
+        let $cfg = builder.build(&ast);
+    };
+}
+
+fn record(code: &str) -> Vec<HostCall> {
+    compile!(code, env, cfg);
+
+    let mut host = RecordingHost::new(DummyHost::new());
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    let _ = intr.exec_code();
+
+    let (_, log) = host.into_parts();
+    log
+}
+
+#[test]
+pub fn recording_host_logs_host_calls() {
+    let code = r#"
+        FORWARD 10
+        PENUP
+        PRINT 5 * 2
+    "#;
+
+    let log = record(code);
+
+    assert_eq!(
+        vec![
+            HostCall::ScreenSize(800, 600),
+            HostCall::Direct(Direction::Forward, 10),
+            HostCall::Cmd(Command::PenUp),
+            HostCall::Print(10),
+        ],
+        log
+    );
+}
+
+#[test]
+pub fn replay_host_reproduces_recording() {
+    let code = r#"
+        MAKEGLOBAL A = 1
+
+        REPEAT 3 [
+            FORWARD A
+            RIGHT A * 2
+            MAKE A = A + 1
+        ]
+
+        PRINT A
+    "#;
+
+    let log = record(code);
+    let json = serde_json::to_string(&log).unwrap();
+    let log: Vec<HostCall> = serde_json::from_str(&json).unwrap();
+
+    compile!(code, env, cfg);
+
+    let mut host = ReplayHost::new(DummyHost::new(), log);
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    let _ = intr.exec_code();
+
+    assert!(host.is_finished());
+    assert_eq!((12, 6), host.inner().xycors());
+}
+
+#[test]
+#[should_panic(expected = "replay diverged at host-call #2")]
+pub fn replay_host_panics_on_divergence() {
+    let log = record("FORWARD 10 \n RIGHT 20");
+
+    compile!("FORWARD 10 \n RIGHT 30", env, cfg);
+
+    let mut host = ReplayHost::new(DummyHost::new(), log);
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    let _ = intr.exec_code();
+}
+
+#[test]
+pub fn replay_host_answers_queries_from_recording() {
+    let code = r#"
+        IF COUNT(RETRIEVE "RUNS) > 0 [
+            FORWARD PARSE(RETRIEVE "RUNS)
+        ]
+
+        SETX SCREENWIDTH() / 2
+    "#;
+
+    compile!(code, env, cfg);
+
+    let mut recorded = DummyHost::new();
+    recorded.kv_set("RUNS", "7");
+    recorded.set_screen_size(300, 100);
+
+    let mut host = RecordingHost::new(recorded);
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let (_, log) = host.into_parts();
+
+    let get_runs = HostCall::KvGet {
+        key: "RUNS".to_string(),
+        value: Some("7".to_string()),
+    };
+    assert_eq!(HostCall::ScreenSize(300, 100), log[0]);
+    assert_eq!(2, log.iter().filter(|call| **call == get_runs).count());
+
+    // the replaying host has neither the stored value nor the same screen
+    let mut host = ReplayHost::new(DummyHost::new(), log);
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    assert!(host.is_finished());
+    assert_eq!((150, 7), host.inner().xycors());
+}
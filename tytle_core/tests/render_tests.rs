@@ -0,0 +1,140 @@
+extern crate tytle;
+
+use tytle::diagnostics::Diagnostic;
+use tytle::prelude::*;
+
+fn lines(svg: &str) -> Vec<&str> {
+    svg.lines().filter(|line| line.starts_with("<line")).collect()
+}
+
+#[test]
+fn render_to_svg_draws_lines() {
+    let code = r#"
+        FORWARD 10
+        PENUP
+        FORWARD 5
+        PENDOWN
+        SETPENCOLOR "RED
+        SETX 20
+    "#;
+
+    let options = RenderOptions {
+        width: 100,
+        height: 50,
+        ..Default::default()
+    };
+
+    let svg = render_to_svg(code, options).unwrap();
+
+    assert!(svg.starts_with(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="50" viewBox="0 0 100 50">"#
+    ));
+    assert!(svg.ends_with("</svg>\n"));
+
+    // the y-axis is flipped, so the turtle home is the bottom-left corner of the canvas
+    assert_eq!(
+        vec![
+            r##"<line x1="0" y1="50" x2="0" y2="40" stroke="#000000"/>"##,
+            r##"<line x1="0" y1="35" x2="20" y2="35" stroke="#ff0000"/>"##,
+        ],
+        lines(&svg)
+    );
+}
+
+#[test]
+fn render_to_svg_clean() {
+    let code = r#"
+        FORWARD 10
+        CLEAN
+        RIGHT 10
+    "#;
+
+    let svg = render_to_svg(code, RenderOptions::default()).unwrap();
+
+    assert_eq!(
+        vec![r##"<line x1="0" y1="590" x2="10" y2="590" stroke="#000000"/>"##],
+        lines(&svg)
+    );
+}
+
+#[test]
+fn render_to_svg_escapes_labels() {
+    let svg = render_to_svg(r#"LABEL "R&D"#, RenderOptions::default()).unwrap();
+
+    assert!(svg.contains(">R&amp;D</text>"));
+}
+
+#[test]
+fn render_to_svg_screen_size() {
+    let code = r#"
+        FORWARD SCREENWIDTH() / 2
+    "#;
+
+    let options = RenderOptions {
+        width: 40,
+        height: 30,
+        ..Default::default()
+    };
+
+    let svg = render_to_svg(code, options).unwrap();
+
+    assert_eq!(
+        vec![r##"<line x1="0" y1="30" x2="0" y2="10" stroke="#000000"/>"##],
+        lines(&svg)
+    );
+}
+
+#[test]
+fn render_to_svg_limits() {
+    let code = r#"
+        REPEAT 1000000 [
+            FORWARD 1
+        ]
+    "#;
+
+    let mut options = RenderOptions::default();
+    options.run.max_steps = Some(100);
+
+    let err = render_to_svg(code, options).unwrap_err();
+    assert_eq!(TytleError::StepLimitExceeded(100), err);
+
+    let mut options = RenderOptions::default();
+    options.run.max_steps = None;
+    options.run.timeout_ms = Some(0);
+
+    let err = render_to_svg(code, options).unwrap_err();
+    assert_eq!(TytleError::TimeLimitExceeded(0), err);
+    assert_eq!(
+        "Execution exceeded the time limit of 0 ms",
+        Diagnostic::from(&err).message
+    );
+}
+
+#[test]
+fn render_to_svg_default_memory_limits() {
+    // doubles the string till it's a few hundred MBs long
+    let code = r#"
+        MAKEGLOBAL S = "A
+        REPEAT 28 [
+            MAKE S = WORD(S, S)
+        ]
+    "#;
+
+    let err = render_to_svg(code, RenderOptions::default()).unwrap_err();
+
+    let expected = InterpreterException::ResourceLimit {
+        resource: Resource::StringLength,
+        limit: 1 << 20,
+        actual: 1 << 21,
+    };
+
+    assert_eq!(TytleError::Runtime(expected), err);
+}
+
+#[test]
+fn render_to_svg_compilation_error() {
+    let err = render_to_svg("FORWARD", RenderOptions::default()).unwrap_err();
+    let diagnostic = Diagnostic::from(&err);
+
+    assert_eq!(Severity::Error, diagnostic.severity);
+}
@@ -0,0 +1,123 @@
+extern crate tytle;
+
+use tytle::animation::AnimationHost;
+use tytle::ast::semantic::AstWalkError;
+use tytle::prelude::{render_to_svg, DummyHost, RenderOptions, ReplayHost, RunOptions, TytleError};
+
+// inputs which used to panic (found by mutating the programs of the test-suite)
+const PANICKING_INPUTS: &[&str] = &[
+    // a global assigned within a procedure, ahead of its declaration
+    "TO RESET()\nMAKE A = 0\nEND\nMAKEGLOBAL A = 10",
+    // an assignment ahead of the global's declaration
+    "A = 10\nMAKEGLOBAL A = 20",
+    // a variable used by its own declaration
+    "MAKEGLOBAL X = X\nPRINT X * 10",
+    // a procedure declared within another one
+    "TO MYPROC()\nTO MYPROC_2(Y: STR)\nEND\nMAKELOCAL A = MYPROC()\nEND",
+    // a duplicated procedure, declared within the first one
+    "TO AREA(W: INT): INT\nRETURN W * W\nTO AREA(SIDE: INT): INT\nEND\nRETURN SIDE\nEND",
+    // a param used after its procedure is over
+    "TO SQUARE(SIZE: INT)\nEND\nFORWARD SIZE",
+    // a procedure which doesn't return a value, printed
+    "TO MYPROC(X: INT)\nX + 1\nEND\nPRINT MYPROC(100)",
+    // `HALT` under the root scope
+    "HALT",
+    // a named arg of a primitive (which has no params names)
+    "FORMAT(S = 1)",
+    // a `PRINT` within a block, pretty-printed by the error message
+    "MAKEGLOBAL A = 2\nIF NOT [PRINT A] [SETY 1]",
+    // moves overflowing the turtle coordinates
+    "FORWARD 9223372036854775807\nFORWARD 9223372036854775807",
+    "BACKWARD 9223372036854775807\nBACKWARD 9223372036854775807\nFORWARD 9223372036854775807",
+    "RIGHT 9223372036854775807\nRIGHT 9223372036854775807\nLEFT 9223372036854775807",
+];
+
+fn run_options() -> RunOptions {
+    RunOptions {
+        max_steps: Some(10_000),
+        ..RunOptions::default()
+    }
+}
+
+#[test]
+fn previously_panicking_inputs_return() {
+    for input in PANICKING_INPUTS {
+        let mut host = DummyHost::new();
+        let res = tytle::run(input, &mut host, run_options());
+
+        // the panics are fixed, rather than caught by the safety net
+        if let Err(TytleError::Internal { .. }) = res {
+            panic!("internal error for {:?}: {:?}", input, res);
+        }
+    }
+}
+
+#[test]
+fn previously_panicking_inputs_render() {
+    for input in PANICKING_INPUTS {
+        let res = render_to_svg(input, RenderOptions::default());
+
+        if let Err(TytleError::Internal { .. }) = res {
+            panic!("internal error for {:?}: {:?}", input, res);
+        }
+    }
+}
+
+#[test]
+fn previously_panicking_inputs_animate() {
+    for input in PANICKING_INPUTS {
+        let mut host = AnimationHost::new(DummyHost::new(), 50, 50);
+        let res = tytle::run(input, &mut host, run_options());
+
+        if let Err(TytleError::Internal { .. }) = res {
+            panic!("internal error for {:?}: {:?}", input, res);
+        }
+    }
+}
+
+#[test]
+fn halt_under_the_root_scope_stops_the_program() {
+    let code = r#"
+        FORWARD 10
+        HALT
+        FORWARD 10
+    "#;
+
+    let mut host = DummyHost::new();
+    let summary = tytle::run(code, &mut host, run_options()).unwrap();
+
+    assert_eq!(10, summary.turtle.ycor());
+}
+
+#[test]
+fn turtle_coordinates_saturate() {
+    let mut host = DummyHost::new();
+    let summary = tytle::run(PANICKING_INPUTS[10], &mut host, run_options()).unwrap();
+
+    assert_eq!(isize::MAX, summary.turtle.ycor());
+    assert_eq!(isize::MAX as u64, summary.path_length);
+}
+
+#[test]
+fn proc_declared_within_a_proc_is_an_error() {
+    let mut host = DummyHost::new();
+    let res = tytle::run(PANICKING_INPUTS[3], &mut host, run_options());
+
+    let expected = AstWalkError::ProcNotAtTopLevel("MYPROC_2".to_string());
+    assert_eq!(Some(TytleError::Semantic(expected)), res.err());
+}
+
+#[test]
+fn run_returns_a_panic_as_an_internal_error() {
+    // a replay diverging from its (empty) log panics
+    let mut host = ReplayHost::new(DummyHost::new(), Vec::new());
+    let res = tytle::run("PRINT 1", &mut host, run_options());
+
+    match res {
+        Err(TytleError::Internal { context, message }) => {
+            assert_eq!("run", context);
+            assert!(message.starts_with("replay diverged at host-call #0"));
+        }
+        _ => panic!("expected an internal error, got: {:?}", res),
+    }
+}
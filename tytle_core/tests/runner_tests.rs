@@ -0,0 +1,527 @@
+extern crate tytle;
+
+use tytle::ast::semantic::AstWalkError;
+use tytle::ast::statement::{Command, Direction};
+use tytle::diagnostics::Message;
+use tytle::prelude::{
+    Capabilities, Capability, CompileError, CompileOptions, Diagnostic, DummyHost, Host,
+    InterpreterException, MemoryValue, OptLevel, OverflowPolicy, Resource, ResourceLimits,
+    RunOptions, TytleError,
+};
+use tytle::vm::{Bounds, PenState};
+
+#[test]
+fn run_returns_final_turtle_state() {
+    let code = r#"
+        PENUP
+        REPEAT 3 [
+            FORWARD 10
+            RIGHT 5
+        ]
+        HIDETURTLE
+        PRINT 7
+    "#;
+
+    let mut host = DummyHost::new();
+    let summary = tytle::run(code, &mut host, RunOptions::default()).unwrap();
+
+    assert_eq!(30, summary.turtle.ycor());
+    assert_eq!(15, summary.turtle.xcor());
+    assert!(!summary.turtle.is_visible());
+    assert_eq!(&PenState::Up, summary.pen.get_state());
+
+    // the host receives all the host-calls too
+    assert_eq!(vec!["PENUP", "HIDETURTLE", "7"], host.get_log());
+    assert_eq!((15, 30), host.xycors());
+
+    assert_eq!(9, summary.stats.host_calls);
+    assert!(summary.stats.steps > 0);
+    assert!(summary.stats.profile.is_none());
+    assert!(summary.diagnostics.is_empty());
+}
+
+#[test]
+fn run_returns_drawing_geometry() {
+    let code = r#"
+        FORWARD 100
+        RIGHT 50
+        PENUP
+        FORWARD 30
+        PENDOWN
+        SETX 10
+    "#;
+
+    let mut host = DummyHost::new();
+    let summary = tytle::run(code, &mut host, RunOptions::default()).unwrap();
+
+    let expected = Bounds {
+        min_x: 0,
+        min_y: 0,
+        max_x: 50,
+        max_y: 130,
+    };
+
+    assert_eq!(Some(expected), summary.bounds);
+    assert_eq!(190, summary.path_length);
+
+    // nothing is drawn while the pen is up
+    let summary = tytle::run("PENUP\nFORWARD 10", &mut host, RunOptions::default()).unwrap();
+
+    assert_eq!(None, summary.bounds);
+    assert_eq!(0, summary.path_length);
+}
+
+#[test]
+fn run_diagnostics_uncalled_procs() {
+    let code = r#"
+        TO UNUSED()
+            FORWARD 1
+        END
+
+        TO USED()
+            FORWARD 2
+        END
+
+        USED()
+    "#;
+
+    let mut host = DummyHost::new();
+    let summary = tytle::run(code, &mut host, RunOptions::default()).unwrap();
+
+    assert_eq!(
+        vec![Diagnostic::warning("Procedure `UNUSED` is never called")
+            .localized(Message::new("warning.unused-proc").arg("proc", "UNUSED"))],
+        summary.diagnostics
+    );
+}
+
+#[test]
+fn run_diagnostics_referenced_procs_are_considered_called() {
+    let code = r#"
+        TO HANDLER(X: INT)
+            FORWARD X
+        END
+
+        MAKEGLOBAL ON_CLICK = PROC("HANDLER")
+    "#;
+
+    let mut host = DummyHost::new();
+    let summary = tytle::run(code, &mut host, RunOptions::default()).unwrap();
+
+    assert!(summary.diagnostics.is_empty());
+}
+
+#[test]
+fn run_diagnostics_inlined_procs_are_considered_called() {
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            RETURN N + N
+        END
+
+        FORWARD DOUBLE(3)
+    "#;
+
+    let options = RunOptions {
+        compile: CompileOptions {
+            opt_level: OptLevel::Full,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut host = DummyHost::new();
+    let summary = tytle::run(code, &mut host, options).unwrap();
+
+    assert_eq!(6, summary.turtle.ycor());
+    assert!(summary.diagnostics.is_empty());
+}
+
+#[test]
+fn run_diagnostics_unknown_throw_tags() {
+    let code = r#"
+        TO STEP(N: INT)
+            IF N > 2 [
+                THROW "DONE
+            ]
+
+            IF N > 5 [
+                THROW "TOO_FAR
+            ]
+
+            IF N > 10 [
+                THROW "ERROR
+            ]
+        END
+
+        CATCH "DONE [
+            STEP(3)
+        ]
+    "#;
+
+    let mut host = DummyHost::new();
+    let summary = tytle::run(code, &mut host, RunOptions::default()).unwrap();
+
+    assert_eq!(
+        vec![
+            Diagnostic::warning("`THROW` of tag `TOO_FAR` which is never caught")
+                .localized(Message::new("warning.unknown-tag").arg("tag", "TOO_FAR"))
+        ],
+        summary.diagnostics
+    );
+}
+
+#[test]
+fn run_diagnostics_unbalanced_states() {
+    let code = r#"
+        TO BRANCH(N: INT)
+            PUSHSTATE
+            FORWARD N
+            IF N > 5 [
+                POPSTATE
+            ]
+        END
+
+        BRANCH(10)
+        PUSHSTATE
+    "#;
+
+    let mut host = DummyHost::new();
+    let summary = tytle::run(code, &mut host, RunOptions::default()).unwrap();
+
+    let branch = "Procedure `BRANCH` doesn't restore each state it saves \
+                  (unbalanced `PUSHSTATE` / `POPSTATE`)";
+
+    let main = "The program doesn't restore each state it saves \
+                (unbalanced `PUSHSTATE` / `POPSTATE`)";
+
+    assert_eq!(
+        vec![
+            Diagnostic::warning(main).localized(Message::new("warning.unbalanced-main-state")),
+            Diagnostic::warning(branch)
+                .localized(Message::new("warning.unbalanced-state").arg("proc", "BRANCH")),
+        ],
+        summary.diagnostics
+    );
+}
+
+#[test]
+fn run_with_profiling_and_compile_options() {
+    let options = RunOptions {
+        compile: CompileOptions {
+            opt_level: OptLevel::Full,
+            ..Default::default()
+        },
+        profile: true,
+        ..Default::default()
+    };
+
+    let mut host = DummyHost::new();
+    let summary = tytle::run("FORWARD (2 * 1) + (2 * 1)", &mut host, options).unwrap();
+
+    assert_eq!(4, summary.turtle.ycor());
+
+    let profile = summary.stats.profile.unwrap();
+    assert_eq!(1, profile.host_calls);
+}
+
+#[test]
+fn run_compile_error() {
+    let mut host = DummyHost::new();
+
+    let options = RunOptions {
+        compile: CompileOptions {
+            max_proc_params: Some(0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let res = tytle::run("TO P(A: INT)\nEND", &mut host, options);
+
+    assert_eq!(
+        Some(TytleError::Compile(CompileError::TooManyProcParams {
+            proc_name: "P".to_string(),
+            max: 0,
+            actual: 1,
+        })),
+        res.err()
+    );
+}
+
+#[test]
+fn run_step_limit() {
+    let code = r#"
+        REPEAT 1000 [
+            FORWARD 1
+        ]
+    "#;
+
+    let options = RunOptions {
+        max_steps: Some(50),
+        ..Default::default()
+    };
+
+    let mut host = DummyHost::new();
+    let res = tytle::run(code, &mut host, options);
+
+    assert_eq!(Some(TytleError::StepLimitExceeded(50)), res.err());
+}
+
+#[test]
+fn run_time_limit() {
+    let code = r#"
+        REPEAT 1000 [
+            FORWARD 1
+        ]
+    "#;
+
+    let options = RunOptions {
+        timeout_ms: Some(0),
+        ..Default::default()
+    };
+
+    let mut host = DummyHost::new();
+    let res = tytle::run(code, &mut host, options);
+
+    assert_eq!(Some(TytleError::TimeLimitExceeded(0)), res.err());
+}
+
+#[test]
+fn run_overflow_policy() {
+    let code = r#"
+        MAKEGLOBAL A = 9223372036854775807
+        SETX A * 2 + 10
+    "#;
+
+    let mut host = DummyHost::new();
+    let res = tytle::run(code, &mut host, RunOptions::default());
+
+    let expected = InterpreterException::IntegerOverflow {
+        op: "*".to_string(),
+        lhs: isize::MAX,
+        rhs: 2,
+    };
+    assert_eq!(Some(TytleError::Runtime(expected)), res.err());
+
+    let options = RunOptions {
+        overflow: OverflowPolicy::Wrapping,
+        ..Default::default()
+    };
+
+    let mut host = DummyHost::new();
+    let summary = tytle::run(code, &mut host, options).unwrap();
+
+    assert_eq!(8, summary.turtle.xcor());
+}
+
+#[test]
+fn run_examples() {
+    let examples = [
+        include_str!("../../examples/factorial.tytle"),
+        include_str!("../../examples/buildings.tytle"),
+    ];
+
+    for code in examples.iter() {
+        let mut host = DummyHost::new();
+        let res = tytle::run(code, &mut host, RunOptions::default());
+
+        assert!(res.is_ok(), "{:?}", res.err());
+    }
+}
+
+#[test]
+fn run_reports_the_host_screen_size() {
+    let code = r#"
+        SETX SCREENWIDTH() / 2
+        SETY SCREENHEIGHT() / 2
+    "#;
+
+    let mut host = DummyHost::new();
+    host.set_screen_size(300, 100);
+
+    let summary = tytle::run(code, &mut host, RunOptions::default()).unwrap();
+
+    assert_eq!((150, 50), (summary.turtle.xcor(), summary.turtle.ycor()));
+}
+
+#[test]
+fn run_with_injected_globals() {
+    let code = r#"
+        TO STEP(): INT
+            RETURN LEVEL * 10
+        END
+
+        FORWARD STEP()
+        MAKE LEVEL = LEVEL + 1
+        PRINTF("~a:~a", NAME, LEVEL)
+    "#;
+
+    let options = RunOptions {
+        globals: vec![
+            ("LEVEL".to_string(), MemoryValue::Int(3)),
+            ("NAME".to_string(), MemoryValue::Str("ada".to_string())),
+        ],
+        ..Default::default()
+    };
+
+    let mut host = DummyHost::new();
+    let summary = tytle::run(code, &mut host, options).unwrap();
+
+    assert_eq!(30, summary.turtle.ycor());
+    assert_eq!(vec!["ada:4"], host.get_log());
+}
+
+#[test]
+fn run_injected_global_declared_again() {
+    let options = RunOptions {
+        globals: vec![("LEVEL".to_string(), MemoryValue::Int(3))],
+        ..Default::default()
+    };
+
+    let mut host = DummyHost::new();
+    let res = tytle::run("MAKEGLOBAL LEVEL = 1", &mut host, options);
+
+    let expected = AstWalkError::DuplicateGlobalVar("LEVEL".to_string());
+    assert_eq!(Some(TytleError::Semantic(expected)), res.err());
+}
+
+#[test]
+fn run_injected_global_of_an_empty_list() {
+    // the type of the list elements can't be told
+    let options = RunOptions {
+        globals: vec![("SCORES".to_string(), MemoryValue::List(Vec::new()))],
+        ..Default::default()
+    };
+
+    let mut host = DummyHost::new();
+    let res = tytle::run("PRINT COUNT(\"A)", &mut host, options);
+
+    let expected = TytleError::Semantic(AstWalkError::EmptyList);
+    assert_eq!(Some(expected), res.err());
+}
+
+#[test]
+fn run_with_resource_limits() {
+    let options = RunOptions {
+        limits: ResourceLimits {
+            max_list_len: Some(2),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut host = DummyHost::new();
+    let res = tytle::run("MAKEGLOBAL L = [1, 2, 3]", &mut host, options);
+
+    let expected = InterpreterException::ResourceLimit {
+        resource: Resource::ListLength,
+        limit: 2,
+        actual: 3,
+    };
+    assert_eq!(Some(TytleError::Runtime(expected)), res.err());
+}
+
+#[test]
+fn run_with_disabled_capability() {
+    let options = RunOptions {
+        compile: CompileOptions {
+            capabilities: Capabilities::NONE,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut host = DummyHost::new();
+    let res = tytle::run("FORWARD 10 BEEP", &mut host, options);
+
+    let expected = CompileError::CapabilityDisabled {
+        capability: Capability::Sound,
+        feature: "BEEP".to_string(),
+    };
+    assert_eq!(Some(TytleError::Compile(expected)), res.err());
+}
+
+// a host implementing only the callbacks without a default (and the printing of text)
+struct PrintingHost {
+    log: Vec<String>,
+}
+
+impl Host for PrintingHost {
+    fn exec_cmd(&mut self, _cmd: &Command) {}
+    fn exec_direct(&mut self, _direct: &Direction, _count: isize) {}
+    fn exec_trap(&mut self, _node: usize, _ip: usize) {}
+
+    fn exec_print(&mut self, value: isize) {
+        self.log.push(value.to_string());
+    }
+
+    fn exec_print_text(&mut self, text: &str) {
+        self.log.push(text.to_string());
+    }
+
+    fn compilation_error(&mut self, _error: &str) {}
+}
+
+#[test]
+fn run_with_a_host_implementing_only_the_required_callbacks() {
+    let code = r#"
+        PUSHSTATE
+        FORWARD 10
+        STAMP
+        SETPENCOLOR 4
+        BEEP
+        POPSTATE
+
+        STORE "RUNS 1
+        PRINT COUNT(RETRIEVE "RUNS)
+        PRINT SCREENWIDTH()
+    "#;
+
+    let mut host = PrintingHost { log: Vec::new() };
+    let summary = tytle::run(code, &mut host, RunOptions::default()).unwrap();
+
+    assert_eq!(0, summary.turtle.ycor());
+    assert_eq!(vec!["0", "800"], host.log);
+}
+
+#[test]
+fn run_prints_the_trace_lines_by_default() {
+    let code = r#"
+        TO SHOW(X: INT)
+            PRINT X
+        END
+
+        TRACE "SHOW
+        SHOW(3)
+    "#;
+
+    let mut host = PrintingHost { log: Vec::new() };
+    let res = tytle::run(code, &mut host, RunOptions::default());
+
+    assert!(res.is_ok());
+    assert_eq!(vec!["SHOW(3)", "3", "SHOW stops"], host.log);
+}
+
+#[test]
+fn run_keeps_stored_values_across_runs() {
+    let code = r#"
+        MAKEGLOBAL RUNS = 1
+
+        IF COUNT(RETRIEVE "RUNS) > 0 [
+            MAKE RUNS = PARSE(RETRIEVE "RUNS) + 1
+        ]
+
+        STORE "RUNS RUNS
+        FORWARD RUNS
+    "#;
+
+    let mut host = DummyHost::new();
+
+    let summary = tytle::run(code, &mut host, RunOptions::default()).unwrap();
+    assert_eq!(1, summary.turtle.ycor());
+
+    let summary = tytle::run(code, &mut host, RunOptions::default()).unwrap();
+    assert_eq!(2, summary.turtle.ycor());
+
+    assert_eq!(Some("2".to_string()), host.kv_get("RUNS"));
+}
@@ -0,0 +1,236 @@
+extern crate tytle;
+
+use tytle::ast::expression::ExpressionType;
+use tytle::ast::semantic::AstWalkError;
+use tytle::session::*;
+use tytle::vm::*;
+
+#[test]
+pub fn session_exec_program() {
+    let code = r#"
+        TO SQUARE(N: INT): INT
+            RETURN N * N
+        END
+
+        MAKEGLOBAL A = SQUARE(3)
+        PRINT A
+    "#;
+
+    let mut session = Session::new();
+    let mut host = DummyHost::new();
+
+    assert_eq!(Ok(()), session.exec(code, &mut host));
+    assert_eq!(vec!["9"], host.get_log());
+    assert_eq!(Some(&MemoryValue::Int(9)), session.get_global("A"));
+}
+
+#[test]
+pub fn session_globals_and_procs_are_preserved_across_execs() {
+    let mut session = Session::new();
+    let mut host = DummyHost::new();
+
+    let _ = session.exec("MAKEGLOBAL COUNT = 10", &mut host);
+    let _ = session.exec("MAKEGLOBAL DONE = FALSE", &mut host);
+
+    let code = r#"
+        TO BUMP(N: INT)
+            MAKE COUNT = COUNT + N
+        END
+    "#;
+    let _ = session.exec(code, &mut host);
+
+    let _ = session.exec("BUMP(5)", &mut host);
+    let _ = session.exec("BUMP(2) \n PRINT COUNT", &mut host);
+
+    assert_eq!(vec!["17"], host.get_log());
+    assert_eq!(Some(&MemoryValue::Int(17)), session.get_global("COUNT"));
+    assert_eq!(Some(&MemoryValue::Bool(false)), session.get_global("DONE"));
+}
+
+#[test]
+pub fn session_redefine_proc_keeps_globals() {
+    let mut session = Session::new();
+    let mut host = DummyHost::new();
+
+    let code = r#"
+        TO STEP(): INT
+            RETURN 1
+        END
+
+        MAKEGLOBAL POS = STEP()
+    "#;
+    let _ = session.exec(code, &mut host);
+
+    let res = session.redefine_proc(
+        r#"
+        TO STEP(): INT
+            RETURN 10
+        END
+    "#,
+    );
+    assert_eq!(Ok(()), res);
+
+    let _ = session.exec("MAKE POS = POS + STEP() \n FORWARD POS", &mut host);
+
+    assert_eq!(Some(&MemoryValue::Int(11)), session.get_global("POS"));
+    assert_eq!((0, 11), host.xycors());
+}
+
+#[test]
+pub fn session_define_proc_overloads() {
+    let mut session = Session::new();
+    let mut host = DummyHost::new();
+
+    let res = session.exec("TO SQ(S: INT)\nFORWARD S\nEND", &mut host);
+    assert_eq!(Ok(()), res);
+
+    let res = session.exec("TO SQ(S: INT, N: INT)\nFORWARD S * N\nEND", &mut host);
+    assert_eq!(Ok(()), res);
+
+    let res = session.exec("SQ(5)\nSQ(5, 2)", &mut host);
+    assert_eq!(Ok(()), res);
+    assert_eq!((0, 15), host.xycors());
+
+    // redefining an overload leaves the other one in place
+    let res = session.redefine_proc("TO SQ(S: INT)\nFORWARD S + 1\nEND");
+    assert_eq!(Ok(()), res);
+
+    let res = session.exec("SQ(5)\nSQ(5, 2)", &mut host);
+    assert_eq!(Ok(()), res);
+    assert_eq!((0, 31), host.xycors());
+}
+
+#[test]
+pub fn session_redefine_proc_errors_leave_session_untouched() {
+    let mut session = Session::new();
+    let mut host = DummyHost::new();
+
+    let code = r#"
+        TO STEP(): INT
+            RETURN 1
+        END
+    "#;
+    let _ = session.exec(code, &mut host);
+
+    let res = session.redefine_proc("FORWARD 10");
+    assert_eq!(Err(SessionError::NotAProcedure), res);
+
+    let res = session.redefine_proc("TO STEP(): INT \n RETURN TRUE \n END");
+    assert_eq!(
+        Err(SessionError::Semantic(AstWalkError::InvalidReturnType(
+            ExpressionType::Int,
+            ExpressionType::Bool
+        ))),
+        res
+    );
+
+    let res = session.redefine_proc("TO STEP(: INT \n END");
+    assert!(matches!(res, Err(SessionError::Parse(_))));
+
+    let _ = session.exec("PRINT STEP()", &mut host);
+    assert_eq!(vec!["1"], host.get_log());
+}
+
+#[test]
+pub fn session_consts_remain_consts_across_execs() {
+    let mut session = Session::new();
+    let mut host = DummyHost::new();
+
+    let _ = session.exec("CONST SIZE = 5", &mut host);
+    let _ = session.exec("PRINT SIZE * 2", &mut host);
+
+    assert_eq!(vec!["10"], host.get_log());
+    assert_eq!(Some(&MemoryValue::Int(5)), session.get_global("SIZE"));
+
+    let res = session.exec("MAKE SIZE = 6", &mut host);
+
+    let expected = SessionError::Semantic(AstWalkError::AssignToConst("SIZE".to_string()));
+    assert_eq!(Err(expected), res);
+}
+
+#[test]
+pub fn session_proc_refs_are_preserved_across_execs() {
+    let mut session = Session::new();
+    let mut host = DummyHost::new();
+
+    let code = r#"
+        TO SHOW(N: INT)
+            PRINT N
+        END
+
+        MAKEGLOBAL HANDLERS = [PROC("SHOW")]
+        MAKEGLOBAL HANDLER = PROC("SHOW")
+    "#;
+    let _ = session.exec(code, &mut host);
+
+    // the procedures symbols are re-generated by the session
+    let code = r#"
+        TO NOP()
+        END
+
+        APPLY(HANDLER, 3)
+        FOREACH HANDLERS [ APPLY(?, 4) ]
+    "#;
+    assert_eq!(Ok(()), session.exec(code, &mut host));
+
+    assert_eq!(vec!["3", "4"], host.get_log());
+}
+
+#[test]
+pub fn session_negative_globals_are_preserved_across_execs() {
+    let mut session = Session::new();
+    let mut host = DummyHost::new();
+
+    let _ = session.exec("MAKEGLOBAL X = PARSE(\"-5\")", &mut host);
+    let _ = session.exec("MAKEGLOBAL Y = PARSE(\"-2\") * 3", &mut host);
+
+    assert_eq!(Ok(()), session.exec("PRINT X * Y", &mut host));
+    assert_eq!(vec!["30"], host.get_log());
+    assert_eq!(Some(&MemoryValue::Int(-5)), session.get_global("X"));
+}
+
+#[test]
+pub fn session_unassigned_proc_and_block_globals_remain_unassigned() {
+    let mut session = Session::new();
+    let mut host = DummyHost::new();
+
+    let code = r#"
+        TO SHOW(N: INT)
+            PRINT N
+        END
+
+        MAKEGLOBAL HANDLER = PROC("SHOW")
+        MAKEGLOBAL ACTION = [FORWARD 10]
+    "#;
+
+    // the execution fails before the globals are assigned
+    let res = session.exec(&format!("THROW \"ERROR\n{}", code), &mut host);
+    assert!(matches!(res, Err(SessionError::Runtime(_))));
+
+    assert_eq!(Ok(()), session.exec("PRINT 1", &mut host));
+
+    let res = session.exec("APPLY(HANDLER, 3)", &mut host);
+    assert_eq!(Err(SessionError::Runtime(InterpreterException::ApplyUnassigned)), res);
+
+    let res = session.exec("RUN ACTION", &mut host);
+    assert_eq!(Err(SessionError::Runtime(InterpreterException::RunUnassigned)), res);
+
+    // the globals can still be assigned
+    assert_eq!(Ok(()), session.exec("MAKE HANDLER = PROC(\"SHOW\")\nAPPLY(HANDLER, 3)", &mut host));
+    assert_eq!(vec!["1", "3"], host.get_log());
+}
+
+#[test]
+pub fn session_empty_list_globals_are_preserved_across_execs() {
+    let mut session = Session::new();
+    let mut host = DummyHost::new();
+
+    // the execution fails before the list is assigned, so it's left empty
+    let res = session.exec("THROW \"ERROR\nMAKEGLOBAL SCORES = [1, 2]", &mut host);
+    assert!(matches!(res, Err(SessionError::Runtime(_))));
+
+    assert_eq!(Ok(()), session.exec("PRINT SCORES", &mut host));
+    assert_eq!(Ok(()), session.exec("MAKE SCORES = [3, 4]", &mut host));
+    assert_eq!(Ok(()), session.exec("PRINT SCORES", &mut host));
+    assert_eq!(vec!["[]", "[3 4]"], host.get_log());
+}
@@ -0,0 +1,182 @@
+extern crate tytle;
+
+use tytle::ast::semantic::*;
+use tytle::ast::statement::Statement;
+use tytle::ir::*;
+use tytle::parser::*;
+use tytle::vm::*;
+
+fn proc_names(ast: &tytle::ast::Ast) -> Vec<String> {
+    ast.statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::Procedure(proc_stmt) => Some(proc_stmt.name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+pub fn source_loader_expands_load_stmts() {
+    let mut resolver = MapResolver::new();
+    resolver.add_file(
+        "SHAPES",
+        r#"
+        TO SQUARE(SIZE: INT)
+            REPEAT 4 [
+                FORWARD SIZE
+                RIGHT SIZE
+            ]
+        END
+    "#,
+    );
+
+    let code = r#"
+        LOAD "SHAPES
+        SQUARE(5)
+    "#;
+
+    let mut ast = SourceLoader::new(&resolver).load_code(code).unwrap();
+    assert_eq!(vec!["SQUARE"], proc_names(&ast));
+
+    let generator = SymbolTableGenerator::new();
+    let mut env = generator.generate(&mut ast).unwrap();
+    let mut checker = AstTypeCheck::new(&mut env);
+    assert!(checker.check(&mut ast).is_ok());
+
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    let _ = intr.exec_code();
+
+    assert_eq!((20, 20), host.xycors());
+}
+
+#[test]
+pub fn source_loader_loads_each_file_once() {
+    let mut resolver = MapResolver::new();
+    resolver.add_file("BASE", "TO ONE(): INT \n RETURN 1 \n END");
+    resolver.add_file(
+        "A",
+        "LOAD \"BASE \n TO TWO(): INT \n RETURN ONE() + ONE() \n END",
+    );
+    resolver.add_file(
+        "B",
+        "LOAD \"BASE \n TO THREE(): INT \n RETURN ONE() + TWO() \n END",
+    );
+    resolver.add_file("MAIN", "LOAD \"A \n LOAD \"B \n PRINT THREE()");
+
+    let ast = SourceLoader::new(&resolver).load("MAIN").unwrap();
+
+    assert_eq!(vec!["ONE", "TWO", "THREE"], proc_names(&ast));
+}
+
+#[test]
+pub fn source_loader_file_not_found() {
+    let resolver = MapResolver::new();
+
+    let res = SourceLoader::new(&resolver).load_code("LOAD \"MISSING");
+
+    assert_eq!(Err(LoadError::FileNotFound("MISSING".to_string())), res);
+}
+
+#[test]
+pub fn source_loader_circular_load() {
+    let mut resolver = MapResolver::new();
+    resolver.add_file("A", "LOAD \"B");
+    resolver.add_file("B", "LOAD \"C");
+    resolver.add_file("C", "LOAD \"A");
+
+    let res = SourceLoader::new(&resolver).load("A");
+
+    let chain = vec!["A", "B", "C", "A"]
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect();
+    assert_eq!(Err(LoadError::CircularLoad(chain)), res);
+}
+
+#[test]
+pub fn source_loader_self_load() {
+    let mut resolver = MapResolver::new();
+    resolver.add_file("A", "LOAD \"A");
+
+    let res = SourceLoader::new(&resolver).load("A");
+
+    let chain = vec!["A".to_string(), "A".to_string()];
+    assert_eq!(Err(LoadError::CircularLoad(chain)), res);
+}
+
+#[test]
+pub fn source_loader_duplicate_proc() {
+    let mut resolver = MapResolver::new();
+    resolver.add_file("LIB", "TO ONE(): INT \n RETURN 1 \n END");
+
+    let code = r#"
+        LOAD "LIB
+
+        TO ONE(): INT
+            RETURN 2
+        END
+    "#;
+
+    let res = SourceLoader::new(&resolver).load_code(code);
+
+    let expected = LoadError::DuplicateProc {
+        proc_name: "ONE".to_string(),
+        first_file: "LIB".to_string(),
+        second_file: MAIN_FILE.to_string(),
+    };
+    assert_eq!(Err(expected), res);
+}
+
+#[test]
+pub fn source_loader_parse_error_reports_file() {
+    let mut resolver = MapResolver::new();
+    resolver.add_file("LIB", "TO ONE(A INT) \n END");
+
+    let res = SourceLoader::new(&resolver).load_code("LOAD \"LIB");
+
+    let expected = LoadError::Parse {
+        file: "LIB".to_string(),
+        error: ParseError::MissingColon,
+    };
+    assert_eq!(Err(expected), res);
+}
+
+#[test]
+pub fn unresolved_load_stmt_is_a_semantic_error() {
+    let mut ast = TytleParser.parse("LOAD \"LIB").unwrap();
+
+    let generator = SymbolTableGenerator::new();
+    let res = generator.generate(&mut ast);
+
+    assert_eq!(
+        Some(AstWalkError::UnresolvedLoad("LIB".to_string())),
+        res.err()
+    );
+}
+
+#[test]
+pub fn source_loader_registers_loaded_files() {
+    let mut resolver = MapResolver::new();
+    resolver.add_file("BASE", "TO ONE(): INT \n RETURN 1 \n END");
+    resolver.add_file(
+        "A",
+        "LOAD \"BASE \n TO TWO(): INT \n RETURN ONE() + ONE() \n END",
+    );
+
+    let mut loader = SourceLoader::new(&resolver);
+    loader.load_code("LOAD \"A \n PRINT TWO()").unwrap();
+
+    let source_map = loader.into_source_map();
+
+    let names = source_map
+        .files()
+        .map(|(_, file)| file.name())
+        .collect::<Vec<_>>();
+    assert_eq!(vec![MAIN_FILE, "A", "BASE"], names);
+
+    let base = source_map.file_id("BASE").unwrap();
+    assert_eq!(Some(" RETURN 1 "), source_map.file(base).unwrap().line(2));
+}
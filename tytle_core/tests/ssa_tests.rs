@@ -0,0 +1,260 @@
+#[macro_use]
+extern crate tytle;
+
+#[macro_use]
+extern crate maplit;
+
+use tytle::ast::semantic::*;
+use tytle::ir::ssa::*;
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::vm::*;
+
+macro_rules! compile {
+    ($code: expr) => {{
+        let mut ast = TytleParser.parse($code).unwrap();
+        let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+        assert!(AstTypeCheck::new(&mut env).check(&mut ast).is_ok());
+
+        let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+        (env, cfg)
+    }};
+}
+
+fn run(env: &Environment, cfg: &CfgObject) -> (Vec<String>, (isize, isize)) {
+    let mut host = DummyHost::new();
+
+    let mut intr = Interpreter::new(cfg, env, &mut host);
+    let _ = intr.exec_code();
+
+    (host.get_log(), host.xycors())
+}
+
+fn ssa_var(var_id: usize, version: usize) -> SsaVar {
+    SsaVar::new(SymbolId(var_id), version)
+}
+
+fn proc_id(env: &Environment, name: &str) -> SymbolId {
+    env.symbol_table.get_proc_by_name(name).id
+}
+
+fn var_id(env: &Environment, proc_name: &str, name: &str) -> SymbolId {
+    let proc_id = proc_id(env, proc_name);
+
+    env.locals_symbols[&proc_id]
+        .iter()
+        .find(|var_id| env.symbol_table.get_var_by_id(**var_id).name == name)
+        .copied()
+        .unwrap()
+}
+
+#[test]
+fn ssa_straight_line_versions() {
+    let graph = cfg_graph! {
+        node!(
+            1,
+            int_ins!(1),
+            store_ins!(10),
+            load_ins!(10),
+            int_ins!(2),
+            store_ins!(10),
+            load_ins!(10),
+            load_ins!(20),
+            eoc_ins!()
+        )
+    };
+
+    let ssa_proc = SsaProc::new(&graph, 1, SymbolId(0), &btreeset! { SymbolId(10) });
+
+    let expected = vec![
+        SsaInstruction::Inst(int_ins!(1)),
+        SsaInstruction::Store(ssa_var(10, 1)),
+        SsaInstruction::Load(ssa_var(10, 1)),
+        SsaInstruction::Inst(int_ins!(2)),
+        SsaInstruction::Store(ssa_var(10, 2)),
+        SsaInstruction::Load(ssa_var(10, 2)),
+        SsaInstruction::Inst(load_ins!(20)),
+        SsaInstruction::Inst(eoc_ins!()),
+    ];
+
+    assert_eq!(expected, ssa_proc.get_node(1).insts);
+    assert_eq!(3, ssa_proc.versions_count(SymbolId(10)));
+    assert_eq!(0, ssa_proc.versions_count(SymbolId(20)));
+    assert_eq!(0, ssa_proc.phis_count());
+
+    assert_eq!(Some(1), ssa_proc.def_node(ssa_var(10, 2)));
+    assert_eq!(None, ssa_proc.def_node(ssa_var(10, 0)));
+}
+
+#[test]
+fn ssa_diamond_phi() {
+    let graph = cfg_graph! {
+        node!(1, bool_ins!(true)),
+        node!(2, int_ins!(1), store_ins!(10)),
+        node!(3, int_ins!(2), store_ins!(10)),
+        node!(4, load_ins!(10), load_ins!(20), eoc_ins!()),
+        edge_true_jmp!(1, 2),
+        edge_fallback_jmp!(1, 3),
+        edge_always_jmp!(2, 4),
+        edge_always_jmp!(3, 4)
+    };
+
+    let vars = btreeset! { SymbolId(10), SymbolId(20) };
+    let ssa_proc = SsaProc::new(&graph, 1, SymbolId(0), &vars);
+
+    // `20` isn't stored along any branch, so it has no phi
+    let node = ssa_proc.get_node(4);
+    assert_eq!(1, node.phis.len());
+    assert_eq!(None, node.phi(SymbolId(20)));
+
+    let phi = node.phi(SymbolId(10)).unwrap();
+    assert_eq!(ssa_var(10, 3), phi.dst);
+    assert_eq!(btreemap! { 2 => ssa_var(10, 1), 3 => ssa_var(10, 2) }, phi.args);
+
+    let expected = vec![
+        SsaInstruction::Load(ssa_var(10, 3)),
+        SsaInstruction::Load(ssa_var(20, 0)),
+        SsaInstruction::Inst(eoc_ins!()),
+    ];
+    assert_eq!(expected, node.insts);
+
+    assert_eq!(Some(4), ssa_proc.def_node(ssa_var(10, 3)));
+}
+
+#[test]
+fn ssa_loop_phi() {
+    let graph = cfg_graph! {
+        node!(1, int_ins!(0), store_ins!(10)),
+        node!(2, load_ins!(10), int_ins!(5), lt_ins!()),
+        node!(3, load_ins!(10), int_ins!(1), add_ins!(), store_ins!(10)),
+        node!(4, load_ins!(10), eoc_ins!()),
+        edge_always_jmp!(1, 2),
+        edge_true_jmp!(2, 3),
+        edge_fallback_jmp!(2, 4),
+        edge_always_jmp!(3, 2)
+    };
+
+    let ssa_proc = SsaProc::new(&graph, 1, SymbolId(0), &btreeset! { SymbolId(10) });
+
+    // the loop header merges the version stored before the loop with the one of the loop body
+    let phi = ssa_proc.get_node(2).phi(SymbolId(10)).unwrap();
+    assert_eq!(ssa_var(10, 2), phi.dst);
+    assert_eq!(btreemap! { 1 => ssa_var(10, 1), 3 => ssa_var(10, 3) }, phi.args);
+
+    assert_eq!(SsaInstruction::Load(ssa_var(10, 2)), ssa_proc.get_node(3).insts[0]);
+    assert_eq!(SsaInstruction::Load(ssa_var(10, 2)), ssa_proc.get_node(4).insts[0]);
+    assert_eq!(1, ssa_proc.phis_count());
+}
+
+#[test]
+fn ssa_skips_unreachable_nodes() {
+    let graph = cfg_graph! {
+        node!(1, int_ins!(1), store_ins!(10), eoc_ins!()),
+        node!(2, load_ins!(10))
+    };
+
+    let ssa_proc = SsaProc::new(&graph, 1, SymbolId(0), &btreeset! { SymbolId(10) });
+
+    assert_eq!(vec![1], ssa_proc.nodes.keys().copied().collect::<Vec<_>>());
+}
+
+#[test]
+fn ssa_compiled_proc() {
+    let code = r#"
+        TO SUM(N: INT): INT
+            MAKELOCAL S = 0
+            REPEAT N [
+                MAKE S = S + N
+            ]
+            IF S > 10 [
+                MAKE S = 10
+            ]
+            RETURN S
+        END
+
+        PRINT SUM(5)
+    "#;
+
+    let (env, cfg) = compile!(code);
+    let ssa = SsaForm::new(cfg, &env);
+
+    let sum_id = proc_id(&env, "SUM");
+    let n_id = var_id(&env, "SUM", "N");
+    let s_id = var_id(&env, "SUM", "S");
+
+    let ssa_proc = ssa.get_proc(sum_id);
+    assert!(ssa_proc.vars.contains(&n_id));
+    assert!(ssa_proc.vars.contains(&s_id));
+
+    // the param is only read, so it keeps its initial version (the argument)
+    assert_eq!(1, ssa_proc.versions_count(n_id));
+
+    // `S` is merged entering the loop body (from before the loop and from its previous iteration),
+    // following the loop (whether iterated or not) and following the `IF`
+    let s_phis = ssa_proc
+        .nodes
+        .values()
+        .filter(|node| node.phi(s_id).is_some())
+        .count();
+    assert_eq!(3, s_phis);
+
+    // the stored versions are numbered uniquely
+    let mut stores = ssa_proc
+        .nodes
+        .values()
+        .flat_map(|node| node.insts.iter())
+        .filter_map(|inst| match inst {
+            SsaInstruction::Store(var) if var.var_id == s_id => Some(var.version),
+            _ => None,
+        })
+        .collect::<Vec<usize>>();
+
+    let stores_count = stores.len();
+    stores.dedup();
+
+    assert_eq!(stores_count, stores.len());
+    assert!(stores.iter().all(|version| *version > 0));
+}
+
+#[test]
+fn ssa_roundtrip_preserves_program() {
+    let code = r#"
+        MAKEGLOBAL X = 0
+
+        TO FIB(N: INT): INT
+            MAKELOCAL A = 0
+            MAKELOCAL B = 1
+            REPEAT N [
+                MAKELOCAL T = A + B
+                MAKE A = B
+                MAKE B = T
+            ]
+            IF A > 20 AND B > 20 [
+                MAKE A = A / 2
+            ] [
+                MAKE X = X + 1
+            ]
+            RETURN A
+        END
+
+        REPEAT 3 [
+            FORWARD FIB(X + 8)
+            RIGHT 90
+        ]
+        PRINT X
+    "#;
+
+    let (env, cfg) = compile!(code);
+
+    let expected_graph = cfg.graph.clone();
+    let expected = run(&env, &cfg);
+
+    let ssa = SsaForm::new(cfg, &env);
+    assert!(ssa.phis_count() > 0);
+
+    let cfg = ssa.into_cfg();
+
+    assert_eq!(expected_graph, cfg.graph);
+    assert_eq!(expected, run(&env, &cfg));
+}
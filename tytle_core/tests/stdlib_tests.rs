@@ -0,0 +1,154 @@
+extern crate tytle;
+
+use tytle::ast::semantic::*;
+use tytle::grading::{Drawing, Segment};
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::stdlib;
+use tytle::vm::*;
+use tytle::RunOptions;
+
+macro_rules! setup_stdlib_interpreter {
+    ($code: expr, $env: ident, $cfg: ident, $host: ident, $intr: ident) => {
+        let mut ast = TytleParser.parse($code).unwrap();
+        let generator = SymbolTableGenerator::with_env(Environment::with_stdlib());
+
+        let mut $env = generator.generate(&mut ast).unwrap();
+        let mut checker = AstTypeCheck::new(&mut $env);
+
+        let res = checker.check(&mut ast);
+        assert!(res.is_ok());
+
+        let builder = CfgBuilder::new(&mut $env);
+        let $cfg = builder.build(&ast);
+
+        let mut $host = DummyHost::new();
+        let mut $intr = Interpreter::new(&$cfg, &$env, &mut $host);
+    };
+}
+
+#[test]
+pub fn stdlib_prelude_is_valid() {
+    let mut ast = TytleParser.parse(stdlib::PRELUDE).unwrap();
+    let generator = SymbolTableGenerator::new();
+
+    let mut env = generator.generate(&mut ast).unwrap();
+    let mut checker = AstTypeCheck::new(&mut env);
+
+    assert!(checker.check(&mut ast).is_ok());
+}
+
+#[test]
+pub fn stdlib_procs_are_builtins() {
+    let mut ast = TytleParser.parse("SQUARE(10)").unwrap();
+    let generator = SymbolTableGenerator::with_env(Environment::with_stdlib());
+    let env = generator.generate(&mut ast).unwrap();
+
+    for name in &["SQUARE", "RECTANGLE", "TRIANGLE", "POLYGON", "STAR", "SPIRAL"] {
+        assert!(stdlib::is_builtin(name));
+        assert!(env.symbol_table.get_proc_by_name(name).builtin);
+    }
+
+    assert!(!stdlib::is_builtin("__main__"));
+    assert!(!env.symbol_table.get_proc_by_name("__main__").builtin);
+}
+
+// the segments drawn by `code`, which may call the stdlib procedures
+fn stdlib_drawing(code: &str) -> Drawing {
+    let mut options = RunOptions::default();
+    options.compile.stdlib = true;
+
+    let mut host = RecordingHost::new(DummyHost::new());
+    tytle::run(code, &mut host, options).unwrap();
+
+    let (_, calls) = host.into_parts();
+    Drawing::from_host_calls(&calls)
+}
+
+#[test]
+pub fn stdlib_call_square() {
+    setup_stdlib_interpreter!("SQUARE(10)", env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    assert_eq!(Ok(()), res);
+
+    // the square closes back at the start (the prelude turns, whatever the steering)
+    assert_eq!((0, 0), host.xycors());
+    assert_eq!(0.0, host.get_turtle().heading());
+}
+
+#[test]
+pub fn stdlib_square_drawing_is_closed() {
+    let drawing = stdlib_drawing("SQUARE(10)");
+
+    let expected = vec![
+        Segment::new((0, 0), (0, 10)),
+        Segment::new((0, 10), (10, 10)),
+        Segment::new((10, 10), (10, 0)),
+        Segment::new((10, 0), (0, 0)),
+    ];
+
+    assert_eq!(expected, drawing.segments());
+    assert_eq!(1, drawing.closed_polygons());
+}
+
+#[test]
+pub fn stdlib_procs_turn_only_within_the_prelude() {
+    // the `RIGHT` of the program still moves the turtle sideways
+    setup_stdlib_interpreter!("SQUARE(10)\nRIGHT 5", env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    assert_eq!(Ok(()), res);
+    assert_eq!((5, 0), host.xycors());
+}
+
+#[test]
+pub fn stdlib_user_procs_may_call_builtins() {
+    let code = r#"
+        TO HEXAGON(SIZE: INT)
+            POLYGON(6, SIZE)
+        END
+
+        PENUP
+        SETX 100
+        SETY 100
+        PENDOWN
+
+        HEXAGON(10)
+    "#;
+
+    setup_stdlib_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    assert_eq!(Ok(()), res);
+    assert_eq!((100, 100), host.xycors());
+    assert_eq!(0.0, host.get_turtle().heading());
+
+    let drawing = stdlib_drawing(code);
+    assert_eq!(6, drawing.segments().len());
+    assert_eq!(1, drawing.closed_polygons());
+}
+
+#[test]
+pub fn stdlib_builtin_redefinition_error() {
+    let code = r#"
+        TO SQUARE(SIZE: INT)
+            FORWARD SIZE
+        END
+    "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let generator = SymbolTableGenerator::with_env(Environment::with_stdlib());
+    let res = generator.generate(&mut ast);
+
+    let err = res.err().unwrap();
+
+    assert_eq!(
+        AstWalkError::BuiltinProcRedefinition("SQUARE".to_string()),
+        err
+    );
+    assert_eq!(
+        "Can't redefine built-in procedure: `SQUARE`",
+        err.to_string()
+    );
+}
@@ -71,23 +71,25 @@ fn sym_generate_ast_records_var_global_index() {
     assert_eq!(var.name, "B".to_string());
     assert_eq!(var.id, SymbolId(2));
 
-    let lit_expr = LiteralExpr::Var("A".to_string(), Some(SymbolId(1)));
+    let lit_expr = LiteralExpr::Var("A".to_string());
     let expr_ast = ExpressionAst::Literal(lit_expr);
 
     let make_stmt = MakeStmt {
         kind: MakeStmtKind::Global,
         var_name: "B".to_string(),
         var_id: Some(SymbolId(2)),
-        expr: Expression {
-            expr_ast,
-            expr_type: None,
-        },
+        expr: Expression::new(expr_ast),
     };
 
     let expected = Statement::Make(make_stmt);
 
     assert_eq!(expected, actual_ast.statements[1]);
 
+    // the variable is resolved into the expression symbols side-table
+    if let Statement::Make(ref make_stmt) = actual_ast.statements[1] {
+        assert_eq!(SymbolId(1), env.expr_symbol(&make_stmt.expr));
+    }
+
     let a = var.id.0;
 
     assert_eq!(
@@ -285,9 +287,7 @@ fn sym_generate_proc_call_inject_proc_id() {
     let mut called = false;
 
     if let Statement::Expression(proc_call_expr) = proc_call_stmt {
-        let (_, _, proc_id) = proc_call_expr.as_proc_call_expr();
-
-        assert_eq!(proc.id, *proc_id.unwrap());
+        assert_eq!(proc.id, env.expr_symbol(proc_call_expr));
 
         called = true;
     }
@@ -301,7 +301,7 @@ fn sym_generate_error_global_use_before_declare() {
             MAKE A = 20
         "#;
 
-    let expected = AstWalkError::MissingVarDeclaration("A".to_string());
+    let expected = AstWalkError::MissingVarDeclaration("A".to_string(), None);
 
     assert_symbol_err!(expected, code);
 }
@@ -314,7 +314,7 @@ fn sym_generate_error_local_use_before_declare() {
             END
         "#;
 
-    let expected = AstWalkError::MissingVarDeclaration("A".to_string());
+    let expected = AstWalkError::MissingVarDeclaration("A".to_string(), None);
 
     assert_symbol_err!(expected, code);
 }
@@ -368,7 +368,7 @@ fn sym_generate_error_proc_cannot_declare_global_variables() {
             END
         "#;
 
-    let expected = AstWalkError::ProcNotAllowedToDeclareGlobals("A".to_string());
+    let expected = AstWalkError::ProcNotAllowedToDeclareGlobals("MYPROC".to_string());
 
     assert_symbol_err!(expected, code);
 }
@@ -426,3 +426,389 @@ fn sym_generate_error_locals_not_allowed_under_root_scope() {
 
     assert_symbol_err!(expected, code);
 }
+
+#[test]
+fn sym_generate_proc_doc() {
+    let code = r#"
+            ;; Draws a circle.
+            ;; @param RADIUS the circle's radius
+            TO CIRCLE(RADIUS: INT)
+            END
+
+            TO UNDOCUMENTED()
+            END
+        "#;
+
+    gen_symbols!(code, env);
+
+    let symbol = env.symbol_table.lookup(0, "CIRCLE", &SymbolKind::Proc);
+    let doc = symbol.unwrap().as_proc().doc.as_ref().unwrap();
+
+    assert_eq!("Draws a circle.", doc.summary);
+    assert_eq!(Some("the circle's radius"), doc.param("RADIUS"));
+
+    let symbol = env
+        .symbol_table
+        .lookup(0, "UNDOCUMENTED", &SymbolKind::Proc);
+    assert_eq!(None, symbol.unwrap().as_proc().doc);
+}
+
+#[test]
+fn sym_generate_error_global_declared_within_block() {
+    let code = r#"
+            REPEAT 2 [
+                MAKEGLOBAL A = 10
+            ]
+        "#;
+
+    let expected = AstWalkError::GlobalsNotAllowedUnderInnerScope("A".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_proc_cannot_declare_global_variables_within_block() {
+    let code = r#"
+            TO MYPROC()
+                IF TRUE [
+                    MAKEGLOBAL A = 10
+                ]
+            END
+        "#;
+
+    let expected = AstWalkError::ProcNotAllowedToDeclareGlobals("MYPROC".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_local_declared_within_root_block() {
+    let code = r#"
+            IF TRUE [
+                MAKELOCAL A = 10
+            ]
+        "#;
+
+    let expected = AstWalkError::LocalsNotAllowedUnderRootScope("A".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_global_assigned_before_declaration() {
+    let code = r#"
+            MAKE A = 20
+            MAKEGLOBAL A = 10
+        "#;
+
+    let expected = AstWalkError::AssignBeforeDeclaration("A".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_global_assigned_within_block_before_declaration() {
+    let code = r#"
+            REPEAT 2 [
+                MAKE A = 20
+            ]
+            MAKEGLOBAL A = 10
+        "#;
+
+    let expected = AstWalkError::AssignBeforeDeclaration("A".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_proc_assigns_global_declared_later() {
+    let code = r#"
+            TO RESET()
+                MAKE A = 0
+            END
+
+            MAKEGLOBAL A = 10
+            MAKE A = 20
+            RESET()
+        "#;
+
+    gen_symbols!(code, env);
+
+    let symbol = env.symbol_table.lookup(0, "A", &SymbolKind::Var);
+    assert!(symbol.unwrap().as_var().global);
+}
+
+#[test]
+fn sym_generate_const() {
+    let code = r#"
+            CONST SIZE = 10
+            MAKEGLOBAL A = SIZE
+        "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+
+    let var = env.symbol_table.lookup(0, "SIZE", &SymbolKind::Var).unwrap().as_var();
+    assert!(var.global);
+    assert!(var.constant);
+
+    let var = env.symbol_table.lookup(0, "A", &SymbolKind::Var).unwrap().as_var();
+    assert!(!var.constant);
+}
+
+#[test]
+fn sym_generate_error_assign_to_const() {
+    let code = r#"
+            CONST SIZE = 10
+            MAKE SIZE = 20
+        "#;
+
+    let expected = AstWalkError::AssignToConst("SIZE".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_proc_assigns_const() {
+    let code = r#"
+            CONST SIZE = 10
+
+            TO GROW()
+                MAKE SIZE = SIZE + 1
+            END
+        "#;
+
+    let expected = AstWalkError::AssignToConst("SIZE".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_const_under_inner_scope() {
+    let code = r#"
+            IF TRUE [
+                CONST SIZE = 10
+            ]
+        "#;
+
+    let expected = AstWalkError::GlobalsNotAllowedUnderInnerScope("SIZE".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_on_event_handlers() {
+    let code = r#"
+            ONKEY "LEFT [ FORWARD 1 ]
+            ONKEY "RIGHT [ BACKWARD 1 ]
+            ONTICK 50 [ RIGHT 1 ]
+        "#;
+
+    gen_symbols!(code, env);
+
+    let left = env.symbol_table.get_proc_by_name("$ONKEY.LEFT");
+    let tick = env.symbol_table.get_proc_by_name("$ONTICK");
+
+    assert_eq!(Some(&left.id), env.key_handlers.get("LEFT"));
+    assert_eq!(Some((50, tick.id)), env.tick_handler);
+    assert_eq!(2, env.key_handlers.len());
+    assert!(left.builtin);
+}
+
+#[test]
+fn sym_generate_error_duplicate_event_handler() {
+    let code = r#"
+            ONKEY "LEFT [ FORWARD 1 ]
+            ONKEY "LEFT [ FORWARD 2 ]
+        "#;
+
+    let expected = AstWalkError::DuplicateEventHandler("ONKEY \"LEFT".to_string());
+
+    assert_symbol_err!(expected, code);
+
+    let code = r#"
+            ONTICK 50 [ FORWARD 1 ]
+            ONTICK 100 [ FORWARD 2 ]
+        "#;
+
+    let expected = AstWalkError::DuplicateEventHandler("ONTICK 100".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_event_handler_not_at_top_level() {
+    let code = r#"
+            TO SETUP()
+                ONKEY "LEFT [ FORWARD 1 ]
+            END
+        "#;
+
+    let expected = AstWalkError::EventHandlerNotAtTopLevel("ONKEY \"LEFT".to_string());
+
+    assert_symbol_err!(expected, code);
+
+    let code = r#"
+            IF TRUE [
+                ONTICK 50 [ FORWARD 1 ]
+            ]
+        "#;
+
+    let expected = AstWalkError::EventHandlerNotAtTopLevel("ONTICK 50".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_proc_not_at_top_level() {
+    let code = r#"
+            TO OUTER()
+                TO INNER()
+                END
+            END
+        "#;
+
+    let expected = AstWalkError::ProcNotAtTopLevel("INNER".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_proc_param_used_after_the_proc() {
+    let code = r#"
+            TO SQUARE(SIZE: INT)
+            END
+
+            FORWARD SIZE
+        "#;
+
+    let expected = AstWalkError::MissingVarDeclaration("SIZE".to_string(), None);
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_trace_of_missing_proc() {
+    let code = r#"
+            TO SQUARE()
+            END
+
+            TRACE "SQAURE
+        "#;
+
+    let expected =
+        AstWalkError::MissingProcDeclaration("SQAURE".to_string(), Some("SQUARE".to_string()));
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_proc_overloads() {
+    let code = r#"
+            TO AREA(W: INT): INT
+                RETURN W * W
+            END
+
+            TO AREA(W: INT, H: INT): INT
+                RETURN W * H
+            END
+        "#;
+
+    gen_symbols!(code, env);
+
+    let overloads = env.symbol_table.get_proc_overloads("AREA");
+    assert_eq!(2, overloads.len());
+
+    assert_eq!(1, overloads[0].params_types.len());
+    assert_eq!(2, overloads[1].params_types.len());
+    assert_ne!(overloads[0].id, overloads[1].id);
+}
+
+#[test]
+fn sym_generate_error_overload_with_same_arity() {
+    let code = r#"
+            TO AREA(W: INT): INT
+                RETURN W * W
+            END
+
+            TO AREA(SIDE: INT): INT
+                RETURN SIDE
+            END
+        "#;
+
+    let expected = AstWalkError::DuplicateProc("AREA".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_no_proc_overload() {
+    let code = r#"
+            TO AREA(W: INT): INT
+                RETURN W * W
+            END
+
+            TO AREA(W: INT, H: INT): INT
+                RETURN W * H
+            END
+
+            MAKEGLOBAL A = AREA(1, 2, 3)
+        "#;
+
+    let expected = AstWalkError::NoProcOverload("AREA".to_string(), 3);
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_ambiguous_proc_call() {
+    let code = r#"
+            TO TOTAL(A: INT): INT
+                RETURN A
+            END
+
+            TO TOTAL(A: INT, REST...: INT): INT
+                RETURN A
+            END
+
+            MAKEGLOBAL T = TOTAL(1)
+        "#;
+
+    let expected = AstWalkError::AmbiguousProcCall("TOTAL".to_string(), 1);
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_ambiguous_proc_ref() {
+    let code = r#"
+            TO AREA(W: INT): INT
+                RETURN W * W
+            END
+
+            TO AREA(W: INT, H: INT): INT
+                RETURN W * H
+            END
+
+            MAKEGLOBAL F = PROC("AREA")
+        "#;
+
+    let expected = AstWalkError::AmbiguousProcRef("AREA".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_return_within_block() {
+    let code = r#"
+            TO WALK(): INT
+                MAKELOCAL B = [
+                    FORWARD 10
+                    RETURN 1
+                ]
+                RETURN 0
+            END
+        "#;
+
+    assert_symbol_err!(AstWalkError::ReturnWithinBlock, code);
+}
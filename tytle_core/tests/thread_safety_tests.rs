@@ -0,0 +1,82 @@
+extern crate tytle;
+
+use std::sync::Arc;
+use std::thread;
+
+use tytle::ast::semantic::*;
+use tytle::ast::Ast;
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::vm::*;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+fn compile(code: &str) -> (Environment, CfgObject) {
+    let mut ast = TytleParser.parse(code).unwrap();
+    let generator = SymbolTableGenerator::new();
+
+    let mut env = generator.generate(&mut ast).unwrap();
+    let mut checker = AstTypeCheck::new(&mut env);
+
+    let res = checker.check(&mut ast);
+    assert!(res.is_ok());
+
+    let builder = CfgBuilder::new(&mut env);
+    let cfg = builder.build(&ast);
+
+    (env, cfg)
+}
+
+#[test]
+pub fn compiled_artifacts_are_send_and_sync() {
+    assert_send_sync::<Ast>();
+    assert_send_sync::<SymbolTable>();
+    assert_send_sync::<Environment>();
+    assert_send_sync::<CfgGraph>();
+    assert_send_sync::<CfgObject>();
+}
+
+#[test]
+pub fn interpreters_share_compiled_program_across_threads() {
+    let code = r#"
+        TO SQUARE(SIZE: INT)
+            REPEAT 4 [
+                FORWARD SIZE
+                RIGHT SIZE
+            ]
+        END
+
+        MAKEGLOBAL STEP = 10
+        SQUARE(STEP)
+        PRINT STEP * 2
+    "#;
+
+    let program = Arc::new(compile(code));
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let program = Arc::clone(&program);
+
+            thread::spawn(move || {
+                let (env, cfg) = &*program;
+
+                let mut host = DummyHost::new();
+                let mut intr = Interpreter::new(cfg, env, &mut host);
+                let res = intr.exec_code();
+
+                assert!(res.is_ok());
+
+                let log = host.get_log();
+
+                (host.xycors(), log)
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let (xycors, log) = handle.join().unwrap();
+
+        assert_eq!((40, 40), xycors);
+        assert_eq!(Some(&"20".to_string()), log.last());
+    }
+}
@@ -0,0 +1,110 @@
+extern crate tytle;
+
+use tytle::lexer::Location;
+use tytle::tooling::*;
+
+const CODE: &str = r#";; Draws a square spiral.
+;; @param N the number of lines
+TO MYSPIRAL(N: INT)
+    SPIRAL(N, 2)
+END
+
+MYSPIRAL(10)
+"#;
+
+#[test]
+fn hover_user_proc() {
+    let hover = hover(CODE, Location(7, 3)).unwrap();
+
+    assert_eq!(Location(7, 1), hover.location);
+    assert_eq!(8, hover.len);
+    assert_eq!("MYSPIRAL", hover.proc_info.name);
+    assert!(!hover.proc_info.builtin);
+    assert_eq!(
+        "MYSPIRAL(N: INT)\n\nDraws a square spiral.\n\nN: INT - the number of lines",
+        hover.contents()
+    );
+}
+
+#[test]
+fn hover_proc_definition() {
+    let hover = hover(CODE, Location(3, 4)).unwrap();
+
+    assert_eq!(Location(3, 4), hover.location);
+    assert_eq!("MYSPIRAL", hover.proc_info.name);
+}
+
+#[test]
+fn hover_builtin_proc() {
+    let hover = hover(CODE, Location(4, 10)).unwrap();
+
+    assert_eq!("SPIRAL", hover.proc_info.name);
+    assert!(hover.proc_info.builtin);
+    assert_eq!(
+        Some("the number of lines"),
+        hover.proc_info.doc.unwrap().param("N")
+    );
+}
+
+#[test]
+fn hover_non_proc() {
+    assert_eq!(None, hover(CODE, Location(4, 12)));
+    assert_eq!(None, hover(CODE, Location(5, 4)));
+    assert_eq!(None, hover(CODE, Location(20, 1)));
+}
+
+#[test]
+fn hover_unparsable_code() {
+    assert_eq!(None, hover("TO SQUARE(\nSQUARE()", Location(2, 1)));
+}
+
+#[test]
+fn completions_by_prefix() {
+    let names = completions(CODE, "s")
+        .into_iter()
+        .map(|proc_info| proc_info.name)
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec!["SPIRAL", "SQUARE", "STAR"], names);
+}
+
+#[test]
+fn completions_include_docs() {
+    let completions = completions(CODE, "MY");
+
+    assert_eq!(1, completions.len());
+    assert_eq!("MYSPIRAL(N: INT)", completions[0].signature());
+    assert_eq!(
+        "Draws a square spiral.",
+        completions[0].doc.as_ref().unwrap().summary
+    );
+}
+
+#[test]
+fn completions_every_builtin_is_documented() {
+    for proc_info in completions("", "") {
+        assert!(proc_info.builtin);
+        assert!(
+            proc_info.doc.is_some(),
+            "`{}` is undocumented",
+            proc_info.name
+        );
+    }
+}
+
+#[test]
+fn hover_proc_with_rest_param() {
+    let code = r#";; Sums numbers.
+;; @param NUMS the numbers
+TO SUMALL(FIRST: INT, NUMS...: INT): INT
+    RETURN FIRST
+END
+"#;
+
+    let hover = hover(code, Location(3, 4)).unwrap();
+
+    assert_eq!(
+        "SUMALL(FIRST: INT, NUMS...: INT): INT\n\nSums numbers.\n\nNUMS...: INT - the numbers",
+        hover.contents()
+    );
+}
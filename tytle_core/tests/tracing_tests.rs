@@ -0,0 +1,159 @@
+#![cfg(feature = "tracing")]
+
+extern crate tytle;
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+use tytle::compiler::*;
+use tytle::ir::OptLevel;
+use tytle::vm::*;
+
+// records the spans (as `name fields < parent`) and the events (as their fields)
+#[derive(Default)]
+struct Recorder {
+    spans: Vec<String>,
+    events: Vec<String>,
+    names: HashMap<u64, String>,
+    entered: Vec<u64>,
+}
+
+#[derive(Clone, Default)]
+struct RecordingSubscriber(Arc<Mutex<Recorder>>);
+
+struct FieldsText(String);
+
+impl Visit for FieldsText {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        write!(self.0, " {}={:?}", field.name(), value).unwrap();
+    }
+}
+
+impl Subscriber for RecordingSubscriber {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes) -> Id {
+        let mut rec = self.0.lock().unwrap();
+
+        let mut fields = FieldsText(span.metadata().name().to_string());
+        span.record(&mut fields);
+
+        let parent = match span.parent() {
+            Some(parent) => Some(parent.into_u64()),
+            None if span.is_contextual() => rec.entered.last().copied(),
+            None => None,
+        };
+
+        if let Some(parent) = parent {
+            write!(fields.0, " < {}", rec.names[&parent]).unwrap();
+        }
+
+        let id = rec.names.len() as u64 + 1;
+        let name = span.metadata().name().to_string();
+
+        rec.names.insert(id, name);
+        rec.spans.push(fields.0);
+
+        Id::from_u64(id)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event) {
+        let mut fields = FieldsText(String::new());
+        event.record(&mut fields);
+
+        self.0.lock().unwrap().events.push(fields.0.trim().to_string());
+    }
+
+    fn enter(&self, span: &Id) {
+        self.0.lock().unwrap().entered.push(span.into_u64());
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.0.lock().unwrap().entered.pop();
+    }
+}
+
+const CODE: &str = r#"
+    TO SQUARE(N: INT): INT
+        RETURN N * N
+    END
+
+    TO DRAW(N: INT)
+        FORWARD SQUARE(N)
+    END
+
+    DRAW(2)
+    DRAW(3)
+"#;
+
+fn record<F: FnOnce()>(f: F) -> Recorder {
+    let subscriber = RecordingSubscriber::default();
+
+    tracing::subscriber::with_default(subscriber.clone(), f);
+
+    let taken = subscriber.0.lock().map(|mut rec| std::mem::take(&mut *rec));
+    taken.unwrap()
+}
+
+#[test]
+fn tracing_compiler_phases() {
+    let options = CompileOptions {
+        opt_level: OptLevel::Full,
+        ..Default::default()
+    };
+
+    let rec = record(|| {
+        Compiler::compile(CODE, options).unwrap();
+    });
+
+    let phases = rec
+        .spans
+        .iter()
+        .map(|span| span.split(' ').next().unwrap())
+        .filter(|name| *name != "proc")
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec!["parse", "analyze", "codegen", "optimize"], phases);
+
+    assert!(rec.spans.contains(&"proc name=\"SQUARE\" < codegen".to_string()));
+    assert!(rec.spans.contains(&"optimize opt_level=Full".to_string()));
+
+    let passes = rec
+        .events
+        .iter()
+        .filter(|event| event.starts_with("pass="))
+        .count();
+
+    assert_eq!(5, passes);
+}
+
+#[test]
+fn tracing_procs_calls() {
+    let program = Compiler::compile(CODE, CompileOptions::default()).unwrap();
+
+    let mut host = DummyHost::new();
+    let mut intr = program.interpreter(&mut host);
+
+    let rec = record(|| intr.exec_code().unwrap());
+
+    let expected = vec![
+        "exec",
+        "proc name=\"DRAW\" < exec",
+        "proc name=\"SQUARE\" < proc",
+        "proc name=\"DRAW\" < exec",
+        "proc name=\"SQUARE\" < proc",
+    ];
+
+    assert_eq!(expected, rec.spans);
+}
@@ -0,0 +1,756 @@
+extern crate tytle;
+
+use tytle::compiler::{CompileOptions, Compiler};
+use tytle::parser::{Parser, TytleParser};
+use tytle::transpiler::{
+    JsCanvasTranspiler, PythonTranspiler, Transpiled, Untranslated, JS_CANVAS_ID,
+};
+
+fn transpile(code: &str) -> Transpiled {
+    let ast = TytleParser.parse(code).unwrap();
+
+    PythonTranspiler::transpile(&ast)
+}
+
+fn transpile_js(code: &str) -> Transpiled {
+    let program = match Compiler::compile(code, CompileOptions::default()) {
+        Ok(program) => program,
+        Err(err) => panic!("compilation failed: {}", err),
+    };
+
+    JsCanvasTranspiler::transpile(&program)
+}
+
+#[test]
+fn python_transpile_procedures_and_repeat() {
+    let code = r#"
+        SQUARE(10)
+
+        ;; Draws a square.
+        TO SQUARE(SIDE: INT)
+            REPEAT 4 [
+                FORWARD SIDE
+                RIGHT SIDE
+            ]
+        END
+    "#;
+
+    let transpiled = transpile(code);
+
+    let expected = r#"# transpiled from a tytle program
+import turtle
+
+
+def move(dx, dy):
+    turtle.goto(max(turtle.xcor() + dx, 0), max(turtle.ycor() + dy, 0))
+
+
+def SQUARE(SIDE):
+    """Draws a square."""
+    for _ in range(4):
+        move(0, SIDE)
+        move(SIDE, 0)
+
+
+def main():
+    SQUARE(10)
+
+
+main()
+turtle.done()
+"#;
+
+    assert_eq!(expected, transpiled.source);
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn python_transpile_declares_assigned_globals() {
+    let code = r#"
+        MAKEGLOBAL TOTAL = 0
+
+        TO ADD(N: INT): INT
+            MAKELOCAL M = N * 2
+            MAKE TOTAL = TOTAL + M
+            MAKE N = M
+            RETURN N
+        END
+
+        PRINT ADD(3)
+    "#;
+
+    let source = transpile(code).source;
+
+    let expected = r#"def ADD(N):
+    global TOTAL
+    M = N * 2
+    TOTAL = TOTAL + M
+    N = M
+    return N
+
+
+def main():
+    global TOTAL
+    TOTAL = 0
+    print(ADD(3))
+"#;
+
+    assert!(source.contains(expected));
+}
+
+#[test]
+fn python_transpile_control_flow() {
+    let code = r#"
+        MAKEGLOBAL A = 3
+        IF (A > 2) AND NOT (A = 5) [
+            BACKWARD A + 1
+        ] [
+            SETX 0
+        ]
+        FOREACH [1, 2] [
+            FOREACH [3] [ PRINT ? ]
+            PRINT ?
+        ]
+    "#;
+
+    let source = transpile(code).source;
+
+    let expected = r#"def main():
+    global A
+    A = 3
+    if (A > 2) and (not (A == 5)):
+        move(0, -(A + 1))
+    else:
+        turtle.setx(max(0, 0))
+    for item0 in [1, 2]:
+        for item1 in [3]:
+            print(item1)
+        print(item0)
+"#;
+
+    assert!(source.contains(expected));
+}
+
+#[test]
+fn python_transpile_primitives_and_strings() {
+    let code = r#"
+        MAKEGLOBAL W = WORD("A, UPPERCASE("b"))
+        PRINT COUNT(W)
+        MAKEGLOBAL C = ITEM(1 + 1, W)
+        MAKEGLOBAL N = PARSE("12)
+    "#;
+
+    let source = transpile(code).source;
+
+    assert!(source.contains("    W = (\"A\" + \"b\".upper())\n"));
+    assert!(source.contains("    print(len(W))\n"));
+    assert!(source.contains("    C = W[(1 + 1) - 1]\n"));
+    assert!(source.contains("    N = int(\"12\")\n"));
+
+    let source = transpile("FORWARD SCREENHEIGHT() / 2 + SCREENWIDTH()").source;
+    assert!(source.contains("move(0, ((turtle.window_height() // 2) + turtle.window_width()))"));
+
+    let transpiled = transpile("IF BUTTONP() [ SETX MOUSEX() ]");
+    assert!(transpiled.source.contains("    if False:\n        turtle.setx(max(0, 0))\n"));
+
+    let untranslated = &transpiled.report.untranslated;
+    assert_eq!(2, untranslated.len());
+}
+
+#[test]
+fn python_transpile_format() {
+    let transpiled = transpile(r#"PRINTF("~a:~s", "X, 10)"#);
+    let source = transpiled.source;
+
+    assert!(source.contains("def format_text(f, *args):\n"));
+    assert!(source.contains("    print(format_text(\"~a:~s\", \"X\", 10))\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn python_transpile_store_and_retrieve() {
+    let transpiled = transpile(r#"STORE "HIGH RETRIEVE "SCORE"#);
+    let source = transpiled.source;
+
+    assert!(source.contains("def store(key, value):\n"));
+    assert!(source.contains("    store(\"HIGH\", retrieve(\"SCORE\"))\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn python_transpile_write_streams() {
+    let code = r#"
+        OPENWRITE "LOG
+        SETWRITE "LOG
+        PRINT 1
+        CLOSE "LOG
+    "#;
+
+    let transpiled = transpile(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("def print(*args):\n"));
+    assert!(source.contains("    open_write(\"LOG\")\n    set_write(\"LOG\")\n"));
+    assert!(source.contains("    print(1)\n    close_stream(\"LOG\")\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn python_transpile_stamp_and_labels() {
+    let code = r#"
+        STAMP
+        SETLABELFONT "MONO
+        SETLABELSIZE 20
+        LABEL "HI
+    "#;
+
+    let transpiled = transpile(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("\nlabel_font = [\"sans-serif\", 12]\n"));
+    assert!(source.contains("    turtle.stamp()\n"));
+    assert!(source.contains("    label_font[0] = \"MONO\"\n"));
+    assert!(source.contains("    label_font[1] = 20\n"));
+    assert!(source.contains(
+        "    turtle.write(\"HI\", font=(label_font[0], label_font[1], \"normal\"))\n"
+    ));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn python_transpile_push_and_pop_state() {
+    let transpiled = transpile("PUSHSTATE\nFORWARD 10\nPOPSTATE");
+    let source = transpiled.source;
+
+    assert!(source.contains("\nstates = []\n"));
+    assert!(source.contains("\ndef pop_state():\n"));
+    assert!(source.contains("    push_state()\n    move(0, 10)\n    pop_state()\n"));
+    assert!(transpiled.report.is_complete());
+
+    assert!(!transpile("FORWARD 10").source.contains("states = []"));
+}
+
+#[test]
+fn python_transpile_proc_rest_param() {
+    let code = r#"
+        TO SUMALL(FIRST: INT, NUMS...: INT)
+            FOREACH NUMS [ FORWARD ? ]
+        END
+
+        SUMALL(1, 2, 3)
+    "#;
+
+    let transpiled = transpile(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("def SUMALL(FIRST, *NUMS):\n    NUMS = list(NUMS)\n"));
+    assert!(source.contains("    SUMALL(1, 2, 3)\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn python_transpile_named_args() {
+    let code = r#"
+        TO POLY(N: INT, SIZE: INT)
+            REPEAT N [ FORWARD SIZE ]
+        END
+
+        POLY(6, SIZE=100)
+    "#;
+
+    let transpiled = transpile(code);
+
+    assert!(transpiled.source.contains("    POLY(6, SIZE=100)\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn python_transpile_proc_overloads() {
+    let code = r#"
+        TO STEP(SIZE: INT)
+            FORWARD SIZE
+        END
+
+        TO STEP(SIZE: INT, ANGLE: INT)
+            RIGHT ANGLE
+            FORWARD SIZE
+        END
+
+        STEP(10)
+        STEP(10, 90)
+    "#;
+
+    let transpiled = transpile(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("def STEP_1(SIZE):\n"));
+    assert!(source.contains("def STEP_2(SIZE, ANGLE):\n"));
+    assert!(source.contains("    STEP_1(10)\n"));
+    assert!(source.contains("    STEP_2(10, 90)\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn python_transpile_blocks_are_untranslated() {
+    let transpiled = transpile("MAKEGLOBAL B = [ FORWARD 10 ]\nRUN B");
+    let source = transpiled.source;
+
+    assert!(source.contains("B = None"));
+    assert!(source.contains("    pass  # untranslated: RUN B\n"));
+    assert_eq!(2, transpiled.report.untranslated.len());
+}
+
+#[test]
+fn python_transpile_if_else_expr() {
+    let transpiled = transpile("MAKEGLOBAL X = 1 + IFELSE TRUE [2] [3 * 4]");
+
+    assert!(transpiled.source.contains("X = 1 + (2 if True else (3 * 4))\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn python_transpile_shapes() {
+    let code = r#"
+        SETSHAPE "TRIANGLE
+        SETSHAPE "image:rocket.gif
+        SETSHAPE "arrow
+        SETSHAPE WORD("CIR, "CLE)
+    "#;
+
+    let transpiled = transpile(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("    turtle.shape(\"triangle\")\n"));
+    assert!(source.contains("    turtle.shape(\"rocket.gif\")\n"));
+    assert!(source.contains("    turtle.shape(\"classic\")\n"));
+    assert!(source.contains("    pass  # untranslated: SETSHAPE "));
+    assert_eq!(1, transpiled.report.untranslated.len());
+}
+
+#[test]
+fn python_transpile_pen_colors() {
+    let code = r#"
+        MAKEGLOBAL C = RGB 255 128 0
+        SETPENCOLOR C
+        PENERASE
+        PENDOWN
+        SETPENCOLOR "red
+        SETPENCOLOR PENCOLOR()
+    "#;
+
+    let transpiled = transpile(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("turtle.colormode(255)\npalette = [\n    (\"BLACK\", (0, 0, 0)),\n"));
+    assert!(source.contains("    C = color((255, 128, 0))\n"));
+    assert!(source.contains("    pen_color[0] = color(C)\n    turtle.pencolor(pen_color[0])\n"));
+    assert!(source.contains("    turtle.pencolor(pen_color[0])\n    turtle.pendown()\n"));
+    assert!(source.contains("    pen_color[0] = color(\"red\")\n"));
+    assert!(source.contains("    pen_color[0] = color(pen_color[0])\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn python_transpile_catch_and_throw() {
+    let code = r#"
+        CATCH "OOPS [
+            THROW "OOPS
+        ]
+        CATCH "ERROR [
+            FORWARD 10
+        ]
+    "#;
+
+    let source = transpile(code).source;
+
+    assert!(source.contains("class Throw(Exception):\n"));
+
+    let expected = r#"    try:
+        raise Throw("OOPS")
+    except Throw as error:
+        if error.tag != "OOPS":
+            raise
+    try:
+        move(0, 10)
+    except Exception as error:
+        if isinstance(error, Throw) and error.tag != "ERROR":
+            raise
+"#;
+
+    assert!(source.contains(expected));
+}
+
+#[test]
+fn python_transpile_event_handlers() {
+    let code = r#"
+        ONKEY "LEFT [ LEFT 10 ]
+        ONTICK 100 [ FORWARD 1 ]
+    "#;
+
+    let source = transpile(code).source;
+
+    let expected = r#"def on_key_left():
+    move(-10, 0)
+
+
+def on_tick():
+    turtle.ontimer(on_tick, 100)
+    move(0, 1)
+
+
+def main():
+    turtle.onkey(on_key_left, "Left")
+    turtle.ontimer(on_tick, 100)
+
+
+main()
+turtle.listen()
+turtle.done()
+"#;
+
+    assert!(source.ends_with(expected));
+}
+
+#[test]
+fn python_transpile_reports_untranslated_constructs() {
+    let code = r#"
+        TO SQUARE()
+            SETBACKGROUND
+        END
+
+        TRACE "SQUARE
+        SQUARE()
+        MAKEGLOBAL T = TYPEOF 1
+        PRINT POW(2, 3)
+    "#;
+
+    let transpiled = transpile(code);
+
+    assert!(transpiled
+        .source
+        .contains("def SQUARE():\n    pass  # untranslated: SETBACKGROUND\n"));
+    assert!(transpiled
+        .source
+        .contains("    pass  # untranslated: TRACE \"SQUARE\n"));
+    assert!(transpiled.source.contains("    T = None\n"));
+    assert!(transpiled.source.contains("    print(POW(2, 3))\n"));
+
+    let constructs = transpiled
+        .report
+        .untranslated
+        .iter()
+        .map(|untranslated| untranslated.construct.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec!["SETBACKGROUND", "TRACE \"SQUARE", "TYPEOF 1", "POW"],
+        constructs
+    );
+    assert!(!transpiled.report.is_complete());
+
+    let untranslated = Untranslated {
+        construct: "SETBACKGROUND".to_string(),
+        reason: "the background is picked by the host".to_string(),
+    };
+
+    assert_eq!(untranslated, transpiled.report.untranslated[0]);
+    assert_eq!(
+        "`SETBACKGROUND`: the background is picked by the host",
+        untranslated.to_string()
+    );
+}
+
+#[test]
+fn js_transpile_lowers_nodes_into_straight_line_cases() {
+    let code = r#"
+        TO DOUBLE(N: INT): INT
+            RETURN N * 2
+        END
+
+        MAKEGLOBAL A = 3
+        FORWARD DOUBLE(A)
+    "#;
+
+    let transpiled = transpile_js(code);
+    let source = transpiled.source;
+
+    assert!(source.starts_with("// transpiled from a tytle program"));
+    assert!(source.ends_with(&format!(
+        "}})(document.getElementById(\"{}\"));\n",
+        JS_CANVAS_ID
+    )));
+    assert!(source.contains("  const g = [0];\n"));
+
+    let main = r#"
+  function p0() {
+    const v = [];
+    const s = [];
+    let n = 1;
+
+    for (;;) {
+      switch (n) {
+        case 1:
+          s.push(3);
+          g[0] = s.pop();
+          s.push(g[0]);
+          s.push(p1(...rt.args(s, 1)));
+          rt.forward(s.pop());
+          return;
+      }
+    }
+  }
+"#;
+
+    let double = r#"
+  function p1(a0) {
+    const v = [a0];
+    const s = [];
+    let n = 2;
+
+    for (;;) {
+      switch (n) {
+        case 2:
+          s.push(v[0]);
+          s.push(2);
+          rt.mul(s);
+          return s.pop();
+      }
+    }
+  }
+"#;
+
+    assert!(source.contains(main));
+    assert!(source.contains(double));
+    assert!(source.contains("  rt.run(p0);\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn js_transpile_stamp_and_labels() {
+    let code = r#"
+        STAMP
+        SETLABELSIZE 20
+        LABEL "HI
+    "#;
+
+    let transpiled = transpile_js(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("          rt.stamp();\n"));
+    assert!(source.contains("          rt.labelSize(s.pop());\n"));
+    assert!(source.contains("          rt.label(s.pop());\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn js_transpile_push_and_pop_state() {
+    let transpiled = transpile_js("PUSHSTATE\nPOPSTATE");
+    let source = transpiled.source;
+
+    assert!(source.contains("          rt.pushState();\n          rt.popState();\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn js_transpile_proc_rest_param() {
+    let code = r#"
+        TO SUMALL(FIRST: INT, NUMS...: INT)
+            FOREACH NUMS [ FORWARD ? ]
+        END
+
+        SUMALL(1, 2, 3)
+        APPLY(PROC("SUMALL"), 1)
+    "#;
+
+    let transpiled = transpile_js(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("rt.makeList(s, 2);\n"));
+    assert!(source.contains("s.push(rt.rest("));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn js_transpile_format() {
+    let code = r#"
+        PRINTF("~a-~a", 1, 2)
+        APPLY(PROC("FORMAT"), "~s", "A")
+    "#;
+
+    let transpiled = transpile_js(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("s.push(rt.FORMAT(...rt.args(s, 2)));\n"));
+    assert!(source.contains("s.push(rt.rest(rt.FORMAT));\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn js_transpile_store_and_retrieve() {
+    let transpiled = transpile_js(r#"STORE "HIGH RETRIEVE "SCORE"#);
+    let source = transpiled.source;
+
+    assert!(source.contains("s.push(rt.RETRIEVE(...rt.args(s, 1)));\n"));
+    assert!(source.contains("rt.STORE(...rt.args(s, 2));\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn js_transpile_write_streams() {
+    let code = r#"
+        OPENWRITE "LOG
+        SETWRITE "LOG
+        PRINT 1
+        CLOSE "LOG
+    "#;
+
+    let transpiled = transpile_js(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("rt.openWrite(s.pop());\n"));
+    assert!(source.contains("rt.setWrite(s.pop());\n"));
+    assert!(source.contains("rt.print(s.pop());\n"));
+    assert!(source.contains("rt.close(s.pop());\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn js_transpile_blocks() {
+    let code = r#"
+        TO WALK()
+            MAKELOCAL SIZE = 10
+            MAKELOCAL STEP = [ FORWARD SIZE ]
+            RUN STEP
+        END
+
+        WALK()
+    "#;
+
+    let transpiled = transpile_js(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("(cv) {"));
+    assert!(source.contains("s.push(cv[0]);\n"));
+    assert!(source.contains(", v));\n"));
+    assert!(source.contains("          rt.runBlock(s);\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn js_transpile_if_else_expr() {
+    let transpiled = transpile_js("MAKEGLOBAL X = IFELSE TRUE [2] [3]\nFORWARD X");
+    let source = transpiled.source;
+
+    // the false branch pops the condition left by the fallback jump
+    assert!(source.contains("          s.pop();\n          s.push(3);\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn js_transpile_pen_colors() {
+    let code = r#"
+        SETPENCOLOR RGB 255 0 0
+        SETPENCOLOR NAMEDCOLOR("BLUE)
+        SETPENCOLOR 2
+    "#;
+
+    let transpiled = transpile_js(code);
+    let source = transpiled.source;
+
+    assert!(source.contains("s.push(rt.RGB(...rt.args(s, 3)));\n"));
+    assert!(source.contains("          rt.setPenColor(s.pop());\n"));
+    assert!(transpiled.report.is_complete());
+}
+
+#[test]
+fn js_transpile_jumps_between_nodes() {
+    let code = r#"
+        MAKEGLOBAL A = 3
+        IF A > 2 [ FORWARD 7 ] [ RIGHT 7 ]
+    "#;
+
+    let source = transpile_js(code).source;
+
+    let branch = r#"
+          rt.gt(s);
+          if (s[s.length - 1]) {
+            s.pop();
+            n = 2;
+          } else {
+            n = 3;
+          }
+          break;
+        case 2:
+          s.push(7);
+          rt.forward(s.pop());
+          n = 4;
+          break;
+        case 3:
+          s.push(7);
+          rt.right(s.pop());
+          n = 4;
+          break;
+"#;
+
+    assert!(source.contains(branch));
+}
+
+#[test]
+fn js_transpile_catch_and_throw() {
+    let code = r#"
+        CATCH "OOPS [
+            THROW "OOPS
+        ]
+        PRINT COUNT("ABC)
+    "#;
+
+    let source = transpile_js(code).source;
+
+    assert!(source.contains("    const c = [];\n"));
+    assert!(source.contains("            rt.catch(c, s, \"OOPS\", 2);\n"));
+    assert!(source.contains("            throw new Throw(\"OOPS\");\n"));
+    assert!(source.contains("            rt.endCatch(c);\n"));
+    assert!(source.contains("            s.push(rt.COUNT(...rt.args(s, 1)));\n"));
+    assert!(source.contains("        n = rt.unwind(err, c, s);\n"));
+    assert!(source.contains("            rt.leave(c);\n            return;\n"));
+}
+
+#[test]
+fn js_transpile_event_handlers() {
+    let code = r#"
+        ONKEY "LEFT [ LEFT 10 ]
+        ONTICK 100 [ FORWARD 1 ]
+    "#;
+
+    let source = transpile_js(code).source;
+
+    assert!(source.contains("  const keyHandlers = { \"LEFT\": p1 };\n"));
+    assert!(source.contains("  setInterval(() => rt.run(p2), 100);\n"));
+}
+
+#[test]
+fn js_transpile_reports_untranslated_constructs() {
+    let code = r#"
+        TO SQUARE()
+            SETBACKGROUND
+            WAIT
+        END
+
+        TRACE "SQUARE
+        SQUARE()
+    "#;
+
+    let transpiled = transpile_js(code);
+
+    assert!(transpiled.source.contains("// untranslated: SETBACKGROUND\n"));
+    assert!(transpiled.source.contains("// untranslated: TRACE \"SQUARE\n"));
+
+    let constructs = transpiled
+        .report
+        .untranslated
+        .iter()
+        .map(|untranslated| untranslated.construct.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec!["TRACE \"SQUARE", "SETBACKGROUND"], constructs);
+}
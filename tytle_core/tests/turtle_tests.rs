@@ -0,0 +1,106 @@
+extern crate tytle;
+
+use std::f64::consts::PI;
+use tytle::ast::statement::Direction;
+use tytle::vm::{AngleUnit, Steering, Turtle, TurtleConfig};
+
+fn turning_turtle(angle_unit: AngleUnit) -> Turtle {
+    Turtle::with_config(TurtleConfig {
+        angle_unit,
+        steering: Steering::Turn,
+        ..TurtleConfig::default()
+    })
+}
+
+#[test]
+fn turtle_heading_is_normalized() {
+    let mut turtle = turning_turtle(AngleUnit::Degrees);
+
+    turtle.exec_direct(&Direction::Left, 90);
+    assert_eq!(270.0, turtle.heading());
+
+    turtle.exec_direct(&Direction::Right, 815);
+    assert_eq!(5.0, turtle.heading());
+
+    turtle.exec_direct(&Direction::Left, 365);
+    assert_eq!(0.0, turtle.heading());
+}
+
+#[test]
+fn turtle_n_turns_of_a_full_turn_fraction_return_to_the_start() {
+    for n in 1..=1000 {
+        let mut turtle = turning_turtle(AngleUnit::Degrees);
+
+        for _ in 0..n {
+            turtle.turn(360.0 / n as f64);
+        }
+
+        assert_eq!(0.0, turtle.heading(), "{} turns of 360/{}", n, n);
+    }
+}
+
+#[test]
+fn turtle_n_turns_in_radians_return_to_the_start() {
+    for n in 1..=1000 {
+        let mut turtle = turning_turtle(AngleUnit::Radians);
+
+        for _ in 0..n {
+            turtle.turn(2.0 * PI / n as f64);
+        }
+
+        assert_eq!(0.0, turtle.heading(), "{} turns of 2PI/{}", n, n);
+    }
+}
+
+#[test]
+fn turtle_small_turns_do_not_drift() {
+    let mut turtle = turning_turtle(AngleUnit::Degrees);
+
+    for _ in 0..10_000 {
+        turtle.turn(0.01);
+    }
+
+    assert_eq!(100.0, turtle.heading());
+
+    for _ in 0..100_000 {
+        turtle.turn(-0.5);
+    }
+
+    assert_eq!(140.0, turtle.heading());
+}
+
+#[test]
+fn turtle_turns_undone_in_reverse_return_to_the_start() {
+    let mut seed: u32 = 11;
+    let mut angles = Vec::new();
+
+    for _ in 0..5000 {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        angles.push(f64::from(seed >> 8) / 1000.0 - 8000.0);
+    }
+
+    let mut turtle = turning_turtle(AngleUnit::Degrees);
+
+    for angle in angles.iter() {
+        turtle.turn(*angle);
+    }
+
+    for angle in angles.iter().rev() {
+        turtle.turn(-angle);
+    }
+
+    assert_eq!(0.0, turtle.heading());
+}
+
+#[test]
+fn turtle_moves_along_a_fractional_heading() {
+    let mut turtle = turning_turtle(AngleUnit::Degrees);
+
+    turtle.turn(22.5);
+    turtle.turn(22.5);
+    turtle.exec_direct(&Direction::Forward, 100);
+
+    // 100 * sin(45) = 70.71
+    assert_eq!((71, 71), (turtle.xcor(), turtle.ycor()));
+    assert_eq!(45.0, turtle.heading());
+}
@@ -0,0 +1,81 @@
+extern crate tytle;
+
+use tytle::ast::semantic::AstWalkError;
+use tytle::lexer::{LexError, Location};
+use tytle::parser::ParseError;
+use tytle::prelude::{DummyHost, RunOptions, RunSummary};
+use tytle::TytleError;
+
+fn run(code: &str) -> Result<RunSummary, TytleError> {
+    let mut host = DummyHost::new();
+
+    tytle::run(code, &mut host, RunOptions::default())
+}
+
+#[test]
+fn tytle_error_lex() {
+    let err = run("FORWARD 10\nRIGHT 1\u{0}").err().unwrap();
+
+    assert_eq!(
+        TytleError::Lex(LexError::InvalidCharacter {
+            ch: '\u{0}',
+            location: Location(2, 8),
+        }),
+        err
+    );
+    assert_eq!(
+        "Lexical error at 2:8: Invalid character: `\\u{0}`",
+        err.to_string()
+    );
+}
+
+#[test]
+fn tytle_error_parse_location() {
+    let code = "FORWARD 10\nMAKEGLOBAL A 10";
+    let err = run(code).err().unwrap();
+
+    match err {
+        TytleError::Parse {
+            error: ParseError::UnexpectedToken { .. },
+            location,
+        } => assert_eq!(Location(2, 14), location),
+        _ => panic!("expected a parse error, got: {:?}", err),
+    }
+
+    assert_eq!(
+        "Parse error at 2:14: Unexpected token: `10` (expected `=`)",
+        err.to_string()
+    );
+}
+
+#[test]
+fn tytle_error_semantic() {
+    let code = r#"
+        TO FOO()
+        END
+
+        TO FOO()
+        END
+    "#;
+
+    let err = run(code).err().unwrap();
+
+    assert_eq!(
+        TytleError::Semantic(AstWalkError::DuplicateProc("FOO".to_string())),
+        err
+    );
+    assert_eq!(None, err.location());
+}
+
+#[test]
+fn tytle_error_chaining() {
+    fn run_twice(code: &str) -> Result<isize, TytleError> {
+        let first = run(code)?;
+        let second = run(code)?;
+
+        Ok(first.turtle.ycor() + second.turtle.ycor())
+    }
+
+    assert_eq!(Ok(20), run_twice("FORWARD 10"));
+    assert!(run_twice("MAKEGLOBAL A 10").is_err());
+}
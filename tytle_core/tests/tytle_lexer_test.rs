@@ -1,6 +1,7 @@
 extern crate tytle;
 
-use tytle::lexer::{Lexer, Location, Token, TytleLexer};
+use tytle::lexer::{LexError, Lexer, Location, Token, TytleLexer};
+use tytle::parser::ParserConfig;
 
 macro_rules! assert_current_token {
     ($lexer:ident, $expected_tok:expr, $expected_loc:expr) => {{
@@ -89,7 +90,7 @@ fn lexer_one_line_1_token_with_spaces() {
 fn lexer_one_line_2_tokens() {
     let mut lexer = TytleLexer::new("111    222");
 
-    let (tok1, loc1) = lexer.pop_current_token().unwrap();;
+    let (tok1, loc1) = lexer.pop_current_token().unwrap();
     let (tok2, loc2) = lexer.pop_current_token().unwrap();
     let (tok3, loc3) = lexer.pop_current_token().unwrap();
 
@@ -508,3 +509,216 @@ fn lexer_not() {
     assert_eq!(loc1, Location(1, 1));
     assert_eq!(tok1, Token::NOT);
 }
+
+#[test]
+fn lexer_hebrew_identifiers() {
+    let mut lexer = TytleLexer::new("MAKEGLOBAL אורך = 10");
+
+    let (tok1, loc1) = lexer.pop_current_token().unwrap();
+    let (tok2, loc2) = lexer.pop_current_token().unwrap();
+    let (tok3, loc3) = lexer.pop_current_token().unwrap();
+    let (tok4, loc4) = lexer.pop_current_token().unwrap();
+
+    assert_eq!(
+        (tok1, loc1),
+        (Token::VALUE("MAKEGLOBAL".to_string()), Location(1, 1))
+    );
+    assert_eq!(
+        (tok2, loc2),
+        (Token::VALUE("אורך".to_string()), Location(1, 12))
+    );
+    assert_eq!((tok3, loc3), (Token::ASSIGN, Location(1, 17)));
+    assert_eq!(
+        (tok4, loc4),
+        (Token::VALUE("10".to_string()), Location(1, 19))
+    );
+}
+
+#[test]
+fn lexer_emoji_string_columns() {
+    // each emoji is a single char spanning 4 bytes
+    let mut lexer = TytleLexer::new("\"🐢🐢\" + \"שלום\"");
+
+    let (tok1, loc1) = lexer.pop_current_token().unwrap();
+    let (tok2, loc2) = lexer.pop_current_token().unwrap();
+    let (tok3, loc3) = lexer.pop_current_token().unwrap();
+
+    assert_eq!(
+        (tok1, loc1),
+        (Token::VALUE("\"🐢🐢\"".to_string()), Location(1, 1))
+    );
+    assert_eq!((tok2, loc2), (Token::ADD, Location(1, 6)));
+    assert_eq!(
+        (tok3, loc3),
+        (Token::VALUE("\"שלום\"".to_string()), Location(1, 8))
+    );
+}
+
+#[test]
+fn lexer_multibyte_text_next_line_columns() {
+    let mut lexer = TytleLexer::new("\"🐢\"\nFORWARD 10");
+
+    lexer.pop_current_token().unwrap();
+    lexer.pop_current_token().unwrap();
+
+    let (tok, loc) = lexer.pop_current_token().unwrap();
+    assert_eq!(
+        (tok, loc),
+        (Token::VALUE("FORWARD".to_string()), Location(2, 1))
+    );
+}
+
+#[test]
+fn lexer_check_accepts_unicode() {
+    assert_eq!(Ok(()), TytleLexer::check("PRINT \"שלום עולם 🐢\"\n"));
+}
+
+#[test]
+fn lexer_check_rejects_control_characters() {
+    let res = TytleLexer::check("PRINT \"🐢\"\u{0}");
+
+    assert_eq!(
+        Err(LexError::InvalidCharacter {
+            ch: '\u{0}',
+            location: Location(1, 10)
+        }),
+        res
+    );
+}
+
+#[test]
+fn lexer_crlf_line_endings() {
+    let mut lexer = TytleLexer::new("FORWARD 10\r\nRIGHT 20\r\n");
+
+    let tokens = (0..7)
+        .map(|_| lexer.pop_current_token().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            (Token::VALUE("FORWARD".to_string()), Location(1, 1)),
+            (Token::VALUE("10".to_string()), Location(1, 9)),
+            (Token::NEWLINE, Location(1, 11)),
+            (Token::VALUE("RIGHT".to_string()), Location(2, 1)),
+            (Token::VALUE("20".to_string()), Location(2, 7)),
+            (Token::NEWLINE, Location(2, 9)),
+            (Token::EOF, Location(4, 1)),
+        ],
+        tokens
+    );
+}
+
+#[test]
+fn lexer_tabs() {
+    let mut lexer = TytleLexer::new("\tFORWARD\t10\n  \tRIGHT 20");
+
+    let tokens = (0..5)
+        .map(|_| lexer.pop_current_token().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            (Token::VALUE("FORWARD".to_string()), Location(1, 5)),
+            (Token::VALUE("10".to_string()), Location(1, 13)),
+            (Token::NEWLINE, Location(1, 15)),
+            (Token::VALUE("RIGHT".to_string()), Location(2, 5)),
+            (Token::VALUE("20".to_string()), Location(2, 11)),
+        ],
+        tokens
+    );
+}
+
+#[test]
+fn lexer_configured_tab_width() {
+    let config = ParserConfig::new().tab_width(8);
+    let mut lexer = TytleLexer::with_config("\tFORWARD\t10", &config);
+
+    let (_, loc1) = lexer.pop_current_token().unwrap();
+    let (_, loc2) = lexer.pop_current_token().unwrap();
+
+    assert_eq!(Location(1, 9), loc1);
+    assert_eq!(Location(1, 17), loc2);
+}
+
+#[test]
+fn lexer_check_locations_with_tabs_and_crlf() {
+    let res = TytleLexer::check("FORWARD 10\r\n\tRIGHT\u{7}");
+
+    assert_eq!(
+        Err(LexError::InvalidCharacter {
+            ch: '\u{7}',
+            location: Location(2, 10)
+        }),
+        res
+    );
+}
+
+#[test]
+fn lexer_comments() {
+    let mut lexer = TytleLexer::new("FORWARD 10 ; moving forward\n; a comment line\nPRINT \"A;B");
+
+    let tokens = (0..7)
+        .map(|_| lexer.pop_current_token().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            (Token::VALUE("FORWARD".to_string()), Location(1, 1)),
+            (Token::VALUE("10".to_string()), Location(1, 9)),
+            (Token::NEWLINE, Location(1, 28)),
+            (Token::NEWLINE, Location(2, 17)),
+            (Token::VALUE("PRINT".to_string()), Location(3, 1)),
+            (Token::VALUE("\"A;B".to_string()), Location(3, 7)),
+            (Token::EOF, Location(4, 1)),
+        ],
+        tokens
+    );
+}
+
+#[test]
+fn lexer_doc_comments() {
+    let mut lexer =
+        TytleLexer::new("\t;;  Draws a square \r\nTO SQUARE() ;; not a doc comment\n;;");
+
+    let tokens = (0..8)
+        .map(|_| lexer.pop_current_token().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            (Token::DOC(" Draws a square".to_string()), Location(1, 5)),
+            (Token::NEWLINE, Location(1, 24)),
+            (Token::VALUE("TO".to_string()), Location(2, 1)),
+            (Token::VALUE("SQUARE".to_string()), Location(2, 4)),
+            (Token::LPAREN, Location(2, 10)),
+            (Token::RPAREN, Location(2, 11)),
+            (Token::NEWLINE, Location(2, 33)),
+            (Token::DOC("".to_string()), Location(3, 1)),
+        ],
+        tokens
+    );
+}
+
+#[test]
+fn lexer_colon_within_string() {
+    let mut lexer = TytleLexer::new("SETSHAPE \"image:ROCKET\nTO F(N: INT)");
+
+    let tokens = (0..9)
+        .map(|_| lexer.pop_current_token().unwrap().0)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            Token::VALUE("SETSHAPE".to_string()),
+            Token::VALUE("\"image:ROCKET".to_string()),
+            Token::NEWLINE,
+            Token::VALUE("TO".to_string()),
+            Token::VALUE("F".to_string()),
+            Token::LPAREN,
+            Token::VALUE("N".to_string()),
+            Token::COLON,
+            Token::VALUE("INT".to_string()),
+        ],
+        tokens
+    );
+}
@@ -2,7 +2,8 @@
 extern crate tytle;
 
 use tytle::ast::{expression::*, statement::*};
-use tytle::parser::{ParseError, Parser, TytleParser};
+use tytle::lexer::{Location, Token};
+use tytle::parser::{KeywordTable, ParseError, Parser, ParserConfig, TytleParser};
 
 macro_rules! assert_parse_err {
     ($expected:expr, $code:expr) => {{
@@ -293,6 +294,58 @@ fn parse_expr_stmt_consisting_of_proc_call() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn parse_expr_proc_call_with_named_args() {
+    let actual = TytleParser.parse("FOO(1, SIZE=X + 1, N = 6)").unwrap();
+
+    let named_arg = |name: &str, expr: Expression| {
+        Expression::new(ExpressionAst::NamedArg(name.to_string(), Box::new(expr)))
+    };
+
+    let call_expr = proc_call_expr! {
+        name: "FOO",
+        params: [
+            int_lit_expr!(1),
+            named_arg(
+                "SIZE",
+                binary_expr!("+", boxed_var_lit_expr!("X"), boxed_int_lit_expr!(1))
+            ),
+            named_arg("N", int_lit_expr!(6))
+        ]
+    };
+
+    let expected = ast! { expr_stmt!(call_expr) };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_expr_proc_call_positional_arg_after_named_arg() {
+    let expected = ParseError::Syntax {
+        message: "Positional args must precede the named args".to_string(),
+    };
+
+    assert_parse_err!(expected, "FOO(N=6, 100)");
+}
+
+#[test]
+fn parse_expr_proc_call_parenthesized_equality_arg() {
+    let actual = TytleParser.parse("FOO((X = 1))").unwrap();
+
+    let call_expr = proc_call_expr! {
+        name: "FOO",
+        params: [Expression::new(ExpressionAst::Parentheses(Box::new(binary_expr!(
+            "=",
+            boxed_var_lit_expr!("X"),
+            boxed_int_lit_expr!(1)
+        ))))]
+    };
+
+    let expected = ast! { expr_stmt!(call_expr) };
+
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn parse_expr_proc_call_as_part_of_expr() {
     let actual = TytleParser
@@ -353,6 +406,65 @@ fn parse_print_var_expr() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn parse_printf_stmt() {
+    let actual = TytleParser.parse(r#"PRINTF("x:~a", X)"#).unwrap();
+
+    let call_expr = proc_call_expr! {
+        name: "FORMAT",
+        params: [str_lit_expr!("x:~a"), var_lit_expr!("X")]
+    };
+
+    let expected = ast! { print_stmt!(call_expr) };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_store_stmt_and_retrieve_expr() {
+    let actual = TytleParser.parse(r#"STORE "HIGH RETRIEVE "SCORE"#).unwrap();
+
+    let retrieve_expr = proc_call_expr! {
+        name: "RETRIEVE",
+        params: [str_lit_expr!("SCORE")]
+    };
+
+    let store_expr = proc_call_expr! {
+        name: "STORE",
+        params: [str_lit_expr!("HIGH"), retrieve_expr]
+    };
+
+    let expected = ast! { Statement::Expression(store_expr) };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_stream_stmts() {
+    let code = r#"
+        OPENWRITE "LOG
+        SETWRITE "LOG
+        CLOSE "LOG
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let stream_stmt = |kind| {
+        Statement::Stream(StreamStmt {
+            kind,
+            expr: str_lit_expr!("LOG"),
+        })
+    };
+
+    let expected = ast! {
+        stream_stmt(StreamKind::Open),
+        stream_stmt(StreamKind::Set),
+        stream_stmt(StreamKind::Close)
+    };
+
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn parse_make_variable_assign_an_integer() {
     let actual = TytleParser.parse("MAKE MYVAR = 2").unwrap();
@@ -492,6 +604,63 @@ fn parse_if_stmt_without_else() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn parse_if_stmt_eq_and_ne_clauses() {
+    let code = r#"
+        IF 1 = 2 OR 3 <> 4 [
+            MAKE A = B = 10
+        ]
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let lexpr = binary_expr!("=", boxed_int_lit_expr!(1), boxed_int_lit_expr!(2));
+    let rexpr = binary_expr!("<>", boxed_int_lit_expr!(3), boxed_int_lit_expr!(4));
+    let cond_expr = binary_expr!("OR", boxed_expr!(lexpr), boxed_expr!(rexpr));
+
+    let assign_expr = binary_expr!("=", boxed_var_lit_expr!("B"), boxed_int_lit_expr!(10));
+
+    let if_stmt = if_stmt! {
+        cond: cond_expr,
+        when_true: block_stmt! {
+            make_stmt!("A", assign_expr)
+        }
+    };
+
+    let expected = ast! { if_stmt };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_expr_separated_lt_and_gt_is_not_ne() {
+    let res = TytleParser.parse("MAKEGLOBAL A = 1 < > 2");
+
+    assert!(res.is_err());
+}
+
+#[test]
+fn parse_expr_type_queries() {
+    let code = r#"
+        MAKEGLOBAL A = TYPEOF B = "INT"
+        MAKEGLOBAL C = NOT EMPTYP (D)
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let typeof_expr = type_query_expr!("TYPEOF", var_lit_expr!("B"));
+    let a_expr = binary_expr!("=", boxed_expr!(typeof_expr), boxed_expr!(str_lit_expr!("INT")));
+
+    let c_expr = not_expr!(type_query_expr!("EMPTYP", var_lit_expr!("D", parens: true)));
+
+    let expected = ast! {
+        make_global_stmt!("A", a_expr),
+        make_global_stmt!("C", c_expr)
+    };
+
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn parse_if_stmt_and_clauses() {
     let code = r#"
@@ -582,6 +751,38 @@ fn parse_if_stmt_or_clauses() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn parse_if_stmt_chained_and_or_clauses() {
+    let code = r#"
+        IF 1 < 2 AND 3 < 4 AND 5 < 6 OR 7 < 8 [
+            MAKE A = 10
+        ]
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let expr12 = binary_expr!("<", boxed_int_lit_expr!(1), boxed_int_lit_expr!(2));
+    let expr34 = binary_expr!("<", boxed_int_lit_expr!(3), boxed_int_lit_expr!(4));
+    let expr56 = binary_expr!("<", boxed_int_lit_expr!(5), boxed_int_lit_expr!(6));
+    let expr78 = binary_expr!("<", boxed_int_lit_expr!(7), boxed_int_lit_expr!(8));
+
+    // ((1 < 2 AND 3 < 4) AND 5 < 6) OR 7 < 8
+    let and_clause = binary_expr!("AND", boxed_expr!(expr12), boxed_expr!(expr34));
+    let and_clause = binary_expr!("AND", boxed_expr!(and_clause), boxed_expr!(expr56));
+    let cond_expr = binary_expr!("OR", boxed_expr!(and_clause), boxed_expr!(expr78));
+
+    let if_stmt = if_stmt! {
+        cond: cond_expr,
+        when_true: block_stmt! {
+            make_stmt!("A", int_lit_expr!(10))
+        }
+    };
+
+    let expected = ast! { if_stmt };
+
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn parse_if_stmt_with_else() {
     let code = r#"
@@ -851,9 +1052,32 @@ fn parse_command_clear_screen() {
 }
 
 #[test]
-fn parse_command_set_pen_color() {
-    let actual = TytleParser.parse("SETPENCOLOR").unwrap();
-    let expected = ast! { command_stmt!(SETPENCOLOR) };
+fn parse_command_push_and_pop_state() {
+    let actual = TytleParser.parse("PUSHSTATE\nPOPSTATE").unwrap();
+    let expected = ast! { command_stmt!(PUSHSTATE), command_stmt!(POPSTATE) };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_set_pen_color() {
+    let actual = TytleParser.parse("SETPENCOLOR 4").unwrap();
+    let expected = ast! { Statement::SetPenColor(int_lit_expr!(4)) };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_set_pen_color_rgb() {
+    let actual = TytleParser.parse("SETPENCOLOR RGB (1 + 2) 0 X").unwrap();
+
+    let red = binary_expr!("+", boxed_int_lit_expr!(1), boxed_int_lit_expr!(2), parens: true);
+
+    let rgb = proc_call_expr!(
+        name: "RGB",
+        params: [red, int_lit_expr!(0), var_lit_expr!("X")]
+    );
+    let expected = ast! { Statement::SetPenColor(rgb) };
 
     assert_eq!(expected, actual);
 }
@@ -1004,14 +1228,41 @@ fn parse_error_proc_param_cannot_be_unit() {
     assert_parse_err!(expected, code);
 }
 
+#[test]
+fn parse_error_unknown_command() {
+    let code = "PENUPP";
+
+    let expected = ParseError::UnknownCommand {
+        name: "PENUPP".to_string(),
+        suggestion: Some("PENUP".to_string()),
+    };
+
+    assert_parse_err!(expected, code);
+}
+
+#[test]
+fn parse_error_unknown_command_located() {
+    let code = "FORWARD 10\nFORWRD 10";
+
+    let expected = ParseError::UnknownCommand {
+        name: "FORWRD".to_string(),
+        suggestion: Some("FORWARD".to_string()),
+    };
+
+    assert_eq!(
+        Err((expected, Location(2, 1))),
+        TytleParser.parse_located(code)
+    );
+}
+
 #[test]
 fn parse_error_unexpected_lit() {
     let code = r#"
-            1 + [
+            1 + ]
         "#;
 
     let expected = ParseError::Syntax {
-        message: "Invalid syntax: `[`".to_string(),
+        message: "Invalid syntax: `]`".to_string(),
     };
 
     assert_parse_err!(expected, code);
@@ -1166,3 +1417,586 @@ fn parse_error_or_is_a_reserved_keyword() {
 fn parse_error_not_is_a_reserved_keyword() {
     assert_invalid_identifier!("NOT");
 }
+
+#[test]
+fn parse_load_stmt() {
+    let expected = ast! { Statement::Load("LIB".to_string()) };
+
+    assert_eq!(expected, TytleParser.parse("LOAD \"LIB").unwrap());
+    assert_eq!(expected, TytleParser.parse("LOAD \"LIB\"").unwrap());
+}
+
+#[test]
+fn parse_load_stmt_expects_file_name() {
+    let expected = ParseError::Syntax {
+        message: "`LOAD` expects a file name (got `LIB`)".to_string(),
+    };
+
+    assert_parse_err!(expected, "LOAD LIB");
+}
+
+#[test]
+fn parse_error_load_is_a_reserved_keyword() {
+    assert_reserved_word!("LOAD");
+}
+
+#[test]
+fn parse_with_translated_keywords() {
+    let mut keywords = KeywordTable::new();
+    keywords.add("VORWAERTS", "FORWARD").unwrap();
+    keywords.add("WIEDERHOLE", "REPEAT").unwrap();
+
+    let config = ParserConfig::new().keywords(keywords);
+
+    let translated = TytleParser
+        .parse_with_config("WIEDERHOLE 2 [\n    VORWAERTS 10\n]", &config)
+        .unwrap();
+    let canonical = TytleParser.parse("REPEAT 2 [\n    FORWARD 10\n]").unwrap();
+
+    assert_eq!(canonical, translated);
+}
+
+#[test]
+fn parse_with_translated_keywords_keeps_canonical_ones() {
+    let config = ParserConfig::new().keywords(KeywordTable::spanish());
+
+    let translated = TytleParser
+        .parse_with_config("AVANZA 10\nFORWARD 10", &config)
+        .unwrap();
+    let canonical = TytleParser.parse("FORWARD 10\nFORWARD 10").unwrap();
+
+    assert_eq!(canonical, translated);
+}
+
+#[test]
+fn parse_with_translated_keywords_error_location() {
+    let config = ParserConfig::new().keywords(KeywordTable::spanish());
+
+    let expected = ParseError::UnexpectedToken {
+        expected: Token::ASSIGN,
+        actual: Token::VALUE("10".to_string()),
+    };
+
+    assert_eq!(
+        Err((expected, Location(2, 13))),
+        TytleParser.parse_located_with_config("AVANZA 10\nHAZGLOBAL A 10", &config)
+    );
+}
+
+#[test]
+fn parse_with_translated_operators() {
+    let config = ParserConfig::new().keywords(KeywordTable::spanish());
+
+    let translated = TytleParser
+        .parse_with_config("HAZGLOBAL A = VERDADERO Y NO FALSO O B", &config)
+        .unwrap();
+    let canonical = TytleParser
+        .parse("MAKEGLOBAL A = TRUE AND NOT FALSE OR B")
+        .unwrap();
+
+    assert_eq!(canonical, translated);
+}
+
+#[test]
+fn parse_unicode_strings() {
+    let expected = ast! { make_global_stmt!("A", str_lit_expr!("שלום🐢")) };
+
+    assert_eq!(
+        expected,
+        TytleParser.parse("MAKEGLOBAL A = \"שלום🐢\"").unwrap()
+    );
+}
+
+#[test]
+fn parse_string_ending_with_multibyte_char() {
+    let expected = ast! { make_global_stmt!("A", str_lit_expr!("🐢")) };
+
+    assert_eq!(expected, TytleParser.parse("MAKEGLOBAL A = \"🐢").unwrap());
+}
+
+#[test]
+fn parse_hebrew_identifiers() {
+    let code = "TO ריבוע(צלע: INT)\nEND\nMAKEGLOBAL אורך = 10";
+
+    assert!(TytleParser.parse(code).is_ok());
+}
+
+#[test]
+fn parse_error_lowercase_unicode_identifier() {
+    let code = "MAKEGLOBAL längd = 10";
+
+    let expected = ParseError::InvalidIdentifierDeclaration(
+        "All characters must be capital, digit or `_` (got `längd`)".to_string(),
+    );
+
+    assert_parse_err!(expected, code);
+}
+
+#[test]
+fn parse_error_location_after_unicode_text() {
+    let code = "MAKEGLOBAL אורך = 10\nMAKEGLOBAL ש 10";
+
+    let expected = ParseError::UnexpectedToken {
+        expected: Token::ASSIGN,
+        actual: Token::VALUE("10".to_string()),
+    };
+
+    assert_eq!(
+        Err((expected, Location(2, 14))),
+        TytleParser.parse_located(code)
+    );
+}
+
+#[test]
+fn parse_crlf_and_tabs() {
+    let code = "TO SQUARE(N: INT)\r\n\tREPEAT 4 [\r\n\t\tFORWARD N\r\n\t]\r\nEND\r\n";
+    let expected = "TO SQUARE(N: INT)\n    REPEAT 4 [\n        FORWARD N\n    ]\nEND\n";
+
+    assert_eq!(
+        TytleParser.parse(expected).unwrap(),
+        TytleParser.parse(code).unwrap()
+    );
+}
+
+#[test]
+fn parse_error_location_with_tabs_and_crlf() {
+    let code = "FORWARD 10\r\n\tMAKEGLOBAL A 10\r\n";
+
+    let expected = ParseError::UnexpectedToken {
+        expected: Token::ASSIGN,
+        actual: Token::VALUE("10".to_string()),
+    };
+
+    assert_eq!(
+        Err((expected, Location(2, 18))),
+        TytleParser.parse_located(code)
+    );
+}
+
+#[test]
+fn parse_comments() {
+    let code = r#"
+        ; a comment line
+        FORWARD 10 ; moving forward
+        REPEAT 2 [
+            ;; a doc comment not preceding a procedure
+            RIGHT 90
+        ]
+    "#;
+
+    let expected = TytleParser
+        .parse("FORWARD 10\nREPEAT 2 [\nRIGHT 90\n]")
+        .unwrap();
+
+    assert_eq!(expected, TytleParser.parse(code).unwrap());
+}
+
+#[test]
+fn parse_proc_doc_comment() {
+    let code = r#"
+        ;; Draws a regular polygon.
+        ;; @param N the number of sides
+        ;; @param SIZE the length of each side
+        TO POLYGON(N: INT, SIZE: INT)
+        END
+
+        ;; a comment separated from the procedure by a statement
+        FORWARD 10
+        TO UNDOCUMENTED()
+        END
+    "#;
+
+    let ast = TytleParser.parse(code).unwrap();
+
+    let docs = ast
+        .statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::Procedure(proc_stmt) => Some(proc_stmt.doc.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let expected = ProcDoc {
+        summary: "Draws a regular polygon.".to_string(),
+        params: vec![
+            ("N".to_string(), "the number of sides".to_string()),
+            ("SIZE".to_string(), "the length of each side".to_string()),
+        ],
+    };
+
+    assert_eq!(vec![Some(expected), None], docs);
+}
+
+#[test]
+fn parse_const_stmt() {
+    let actual = TytleParser.parse("CONST SIZE = 10 * 2").unwrap();
+
+    let expr = binary_expr!("*", boxed_int_lit_expr!(10), boxed_int_lit_expr!(2));
+
+    let expected = ast! {
+        make_const_stmt!("SIZE", expr)
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_foreach_stmt_over_list_lit() {
+    let code = r#"
+        FOREACH [1, A] [
+            PRINT ?
+        ]
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let list_expr = Expression::new(ExpressionAst::List(vec![
+        int_lit_expr!(1),
+        var_lit_expr!("A"),
+    ]));
+
+    let block = block_stmt! {
+        print_stmt!(var_lit_expr!("?"))
+    };
+
+    let expected = ast! {
+        foreach_stmt! { list_expr, block }
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_map_expr() {
+    let actual = TytleParser.parse(r#"MAKE L = MAP("DOUBLE", L)"#).unwrap();
+
+    let map_expr = Expression::new(ExpressionAst::Map(
+        "DOUBLE".to_string(),
+        Box::new(var_lit_expr!("L")),
+    ));
+
+    let expected = ast! {
+        make_stmt!("L", map_expr)
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_map_expr_error_proc_name_isnt_a_word() {
+    let actual = TytleParser.parse("MAKE L = MAP(DOUBLE, L)");
+
+    let expected = ParseError::Syntax {
+        message: "`MAP` expects a procedure name word (e.g `\"DOUBLE\"`)".to_string(),
+    };
+
+    assert_eq!(Err(expected), actual);
+}
+
+#[test]
+fn parse_proc_with_list_param() {
+    let code = r#"
+        TO SUM(NUMS: [INT], WORDS: [[STR]])
+        END
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let expected = ast! {
+        proc_stmt! {
+            name: "SUM",
+            params: [proc_param!("NUMS", "[INT]"), proc_param!("WORDS", "[[STR]]")],
+            returns: UNIT,
+            body: block_stmt! { }
+        }
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_proc_with_rest_param() {
+    let code = r#"
+        TO SUMALL(FIRST: INT, NUMS...: INT)
+        END
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let mut rest_param = proc_param!("NUMS", "[INT]");
+    rest_param.rest = true;
+
+    let expected = ast! {
+        proc_stmt! {
+            name: "SUMALL",
+            params: [proc_param!("FIRST", "INT"), rest_param],
+            returns: UNIT,
+            body: block_stmt! { }
+        }
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_proc_rest_param_must_be_last() {
+    let code = r#"
+        TO SUMALL(NUMS...: INT, LAST: INT)
+        END
+    "#;
+
+    let expected = ParseError::Syntax {
+        message: "A rest param must be the last param".to_string(),
+    };
+
+    assert_parse_err!(expected, code);
+}
+
+#[test]
+fn parse_apply_of_proc_ref_expr() {
+    let actual = TytleParser.parse(r#"APPLY(PROC("SHOW"), 1, X)"#).unwrap();
+
+    let proc_ref_expr = Expression::new(ExpressionAst::ProcRef("SHOW".to_string()));
+
+    let apply_expr = Expression::new(ExpressionAst::Apply(
+        Box::new(proc_ref_expr),
+        vec![int_lit_expr!(1), var_lit_expr!("X")],
+    ));
+
+    let expected = ast! { expr_stmt!(apply_expr) };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_on_event_stmts() {
+    let code = r#"
+        ONKEY "LEFT [
+            MAKE X = X + 1
+        ]
+        ONTICK 100 [
+            FORWARD 1
+        ]
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let key_block = block_stmt! {
+        make_stmt!("X", binary_expr!("+", boxed_var_lit_expr!("X"), boxed_int_lit_expr!(1)))
+    };
+
+    let mut key_proc = ProcedureStmt::new("$ONKEY.LEFT".to_string());
+    key_proc.return_type = "UNIT".to_string();
+    key_proc.block = key_block;
+
+    let tick_block = block_stmt! {
+        direct_stmt!(FORWARD, int_lit_expr!(1))
+    };
+
+    let mut tick_proc = ProcedureStmt::new("$ONTICK".to_string());
+    tick_proc.return_type = "UNIT".to_string();
+    tick_proc.block = tick_block;
+
+    let expected = ast! {
+        Statement::OnEvent(OnEventStmt {
+            event: Event::Key("LEFT".to_string()),
+            proc_stmt: key_proc,
+        }),
+        Statement::OnEvent(OnEventStmt {
+            event: Event::Tick(100),
+            proc_stmt: tick_proc,
+        })
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_on_event_stmts_errors() {
+    let expected = ParseError::Syntax {
+        message: "`ONKEY` expects a key name word (e.g `\"LEFT\"`)".to_string(),
+    };
+    assert_parse_err!(expected, "ONKEY 10 [ FORWARD 1 ]");
+
+    let expected = ParseError::Syntax {
+        message: "`ONTICK` expects a positive interval (in milliseconds)".to_string(),
+    };
+    assert_parse_err!(expected, "ONTICK 0 [ FORWARD 1 ]");
+    assert_parse_err!(expected, r#"ONTICK "FAST [ FORWARD 1 ]"#);
+}
+
+#[test]
+fn parse_assert_stmt() {
+    let code = r#"
+        ASSERT X > 0
+        ASSERT X < 10 "X must be < 10"
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let expected = ast! {
+        Statement::Assert(AssertStmt {
+            cond_expr: binary_expr!(">", boxed_var_lit_expr!("X"), boxed_int_lit_expr!(0)),
+            message: None,
+            location: Location(2, 9),
+        }),
+        Statement::Assert(AssertStmt {
+            cond_expr: binary_expr!("<", boxed_var_lit_expr!("X"), boxed_int_lit_expr!(10)),
+            message: Some("X must be < 10".to_string()),
+            location: Location(3, 9),
+        })
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_trace_and_untrace_stmts() {
+    let code = r#"
+        TRACE "SQUARE
+        UNTRACE "SQUARE
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let expected = ast! {
+        Statement::Trace(TraceStmt {
+            proc_name: "SQUARE".to_string(),
+            proc_id: None,
+            enabled: true,
+        }),
+        Statement::Trace(TraceStmt {
+            proc_name: "SQUARE".to_string(),
+            proc_id: None,
+            enabled: false,
+        })
+    };
+
+    assert_eq!(expected, actual);
+
+    let expected = ParseError::Syntax {
+        message: "`UNTRACE` expects a procedure name word (e.g `\"DOUBLE\"`)".to_string(),
+    };
+    assert_parse_err!(expected, "UNTRACE 10");
+}
+
+#[test]
+fn parse_catch_and_throw_stmts() {
+    let code = r#"
+        CATCH "DONE [
+            THROW "DONE
+        ]
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let block = block_stmt! {
+        Statement::Throw("DONE".to_string())
+    };
+
+    let expected = ast! {
+        Statement::Catch(CatchStmt {
+            tag: "DONE".to_string(),
+            block,
+        })
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_catch_and_throw_stmts_errors() {
+    let expected = ParseError::Syntax {
+        message: "`CATCH` expects a tag word (e.g `\"DONE\"`)".to_string(),
+    };
+    assert_parse_err!(expected, "CATCH DONE [ FORWARD 1 ]");
+
+    let expected = ParseError::Syntax {
+        message: "`THROW` expects a tag word (e.g `\"DONE\"`)".to_string(),
+    };
+    assert_parse_err!(expected, "THROW 10");
+}
+
+#[test]
+fn parse_block_expr_and_run_stmt() {
+    let code = r#"
+        MAKEGLOBAL B = [
+            FORWARD 10
+            RIGHT 90
+        ]
+        RUN B
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let block = block_stmt! {
+        direct_lit_expr!(FORWARD, 10),
+        direct_lit_expr!(RIGHT, 90)
+    };
+    let block_expr = Expression::adjust_parentheses(ExpressionAst::Block(block), false);
+
+    let expected = ast! {
+        make_global_stmt!("B", block_expr),
+        Statement::Run(var_lit_expr!("B"))
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_if_else_expr() {
+    let actual = TytleParser.parse("MAKEGLOBAL X = IFELSE A > 1 [2] [3] + 4").unwrap();
+
+    let cond_expr = binary_expr!(">", boxed_var_lit_expr!("A"), boxed_int_lit_expr!(1));
+    let ast = ExpressionAst::IfElse(
+        Box::new(cond_expr),
+        boxed_int_lit_expr!(2),
+        boxed_int_lit_expr!(3),
+    );
+    let if_else_expr = Expression::adjust_parentheses(ast, false);
+
+    let expected = ast! {
+        make_global_stmt!(
+            "X",
+            binary_expr!("+", Box::new(if_else_expr), boxed_int_lit_expr!(4))
+        )
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_if_else_expr_errors() {
+    let expected = ParseError::UnexpectedToken {
+        expected: Token::LBRACKET,
+        actual: Token::VALUE("2".to_string()),
+    };
+    assert_parse_err!(expected, "MAKEGLOBAL X = IFELSE TRUE 2 3");
+}
+
+#[test]
+fn parse_error_unterminated_block() {
+    let expected = ParseError::UnexpectedToken {
+        expected: Token::RBRACKET,
+        actual: Token::EOF,
+    };
+
+    assert_parse_err!(expected, "REPEAT 3 [\n    FORWARD 10\n");
+}
+
+#[test]
+fn parse_error_statement_starting_with_operator() {
+    let expected = ParseError::Syntax {
+        message: "a statement can't start with `+`".to_string(),
+    };
+
+    assert_parse_err!(expected, "+ 1");
+}
+
+#[test]
+fn parse_error_truncated_code() {
+    for code in &["MAKEGLOBAL A = 1 +", "MAKEGLOBAL A = (1", "TO F(A: INT", "PRINT \"HI OR"] {
+        assert!(TytleParser.parse(code).is_err(), "{}", code);
+    }
+}